@@ -0,0 +1,16 @@
+// Service worker showing Open DroneLog Web Push notifications ({ kind, title, body })
+self.addEventListener('push', (event) => {
+  const data = event.data ? event.data.json() : {};
+  event.waitUntil(
+    self.registration.showNotification(data.title || 'Open DroneLog', {
+      body: data.body || '',
+      tag: data.kind,
+      icon: '/favicon.png',
+    })
+  );
+});
+
+self.addEventListener('notificationclick', (event) => {
+  event.notification.close();
+  event.waitUntil(self.clients.openWindow('/'));
+});
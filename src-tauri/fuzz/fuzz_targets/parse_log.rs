@@ -0,0 +1,63 @@
+//! Fuzz target for the import parsers.
+//!
+//! The first input byte picks the file extension (formats are detected by
+//! extension and content), the rest is the file. Any panic that escapes
+//! `LogParser::parse_log`, and any abort or timeout, is a finding; fixed
+//! findings go into `tests/corpus/`.
+//!
+//! ```sh
+//! cargo install cargo-fuzz
+//! cd src-tauri
+//! cargo +nightly fuzz run parse_log -- -max_len=65536 -timeout=30
+//! ```
+//!
+//! Seed it with the regression corpus: `cp tests/corpus/* fuzz/corpus/parse_log/`
+//! (the seeds then start with their own first byte as the extension pick).
+
+#![no_main]
+
+use std::sync::OnceLock;
+
+use drone_logbook_lib::database::Database;
+use drone_logbook_lib::parser::LogParser;
+use libfuzzer_sys::fuzz_target;
+
+const EXTENSIONS: [&str; 9] = ["txt", "DAT", "csv", "json", "gpx", "bbl", "bfl", "pud", "log"];
+
+struct Env {
+    db: Database,
+    runtime: tokio::runtime::Runtime,
+    scratch: std::path::PathBuf,
+}
+
+fn env() -> &'static Env {
+    static ENV: OnceLock<Env> = OnceLock::new();
+    ENV.get_or_init(|| {
+        // libfuzzer-sys aborts on every panic, including the ones the parsers
+        // catch and turn into errors. Put the default hook back: a panic that
+        // escapes `parse_log` still aborts through the fuzz_target wrapper.
+        let _ = std::panic::take_hook();
+
+        let root = std::env::temp_dir().join(format!("dronelog-fuzz-{}", std::process::id()));
+        let scratch = root.join("inputs");
+        std::fs::create_dir_all(&scratch).unwrap();
+        Env {
+            db: Database::new(root.join("data"), "default").unwrap(),
+            runtime: tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap(),
+            scratch,
+        }
+    })
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&pick, content)) = data.split_first() else {
+        return;
+    };
+    let env = env();
+    let path = env.scratch.join(format!("input.{}", EXTENSIONS[pick as usize % EXTENSIONS.len()]));
+    std::fs::write(&path, content).unwrap();
+
+    let parser = LogParser::new(&env.db);
+    // Errors are expected; only a panic or abort is a finding
+    let _ = env.runtime.block_on(parser.parse_log(&path));
+});
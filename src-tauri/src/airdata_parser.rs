@@ -19,7 +19,8 @@ use chrono::{DateTime, NaiveDateTime, Utc};
 
 use crate::database::Database;
 use crate::models::{FlightMessage, FlightMetadata, FlightStats, TelemetryPoint};
-use crate::parser::{LogParser, ParseResult, ParserError};
+use crate::formats::{FlightLogFormat, ParseFuture};
+use crate::parser::{LogParser, ParseInfo, ParseResult, ParserError};
 
 // ---------------------------------------------------------------------------
 // Unit detection & conversion
@@ -217,6 +218,23 @@ fn parse_csv_line(line: &str) -> Vec<String> {
 // AirdataParser
 // ---------------------------------------------------------------------------
 
+/// Airdata CSV exports
+pub struct AirdataFormat;
+
+impl FlightLogFormat for AirdataFormat {
+    fn name(&self) -> &'static str {
+        "Airdata CSV"
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        AirdataParser::is_airdata_csv(path)
+    }
+
+    fn parse<'a>(&'a self, ctx: &'a LogParser<'_>, path: &'a Path, file_hash: &'a str) -> ParseFuture<'a> {
+        Box::pin(async move { AirdataParser::new(ctx.db()).parse(path, file_hash) })
+    }
+}
+
 /// Airdata CSV Parser
 pub struct AirdataParser<'a> {
     db: &'a Database,
@@ -394,6 +412,7 @@ impl<'a> AirdataParser<'a> {
             notes: None,
             color: None,
             messages,
+            info: ParseInfo::new("Airdata CSV"),
         })
     }
 
@@ -524,6 +543,8 @@ impl<'a> AirdataParser<'a> {
             is_photo: col_map.get_bool(row, "isphoto"),
             is_video: col_map.get_bool(row, "isvideo"),
 
+            is_flying: None,
+
             // Battery capacity (not available in Airdata CSV)
             battery_full_capacity: None,
             battery_remained_capacity: None,
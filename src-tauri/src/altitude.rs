@@ -0,0 +1,126 @@
+//! Altitude references.
+//!
+//! Logs report height in different references: DJI logs and most apps
+//! record the barometric height above the takeoff point (`height`), some
+//! formats add the GPS altitude above mean sea level (`altitude_abs`), and a
+//! few (Skydio, FlightHub 2) only have the latter. Each flight records which
+//! of them it has (`AltitudeSource`), and a profile setting
+//! (`AltitudeReference`) picks what the charts, the map track and the
+//! max-altitude stats show:
+//!
+//! - `takeoff` (default) — height above the takeoff point
+//! - `msl` — altitude above mean sea level. Series are the height shifted by
+//!   the takeoff elevation (GPS altitude minus height, averaged over the first
+//!   samples with both, so barometric smoothness is kept); max altitude is the
+//!   highest GPS altitude recorded.
+//!
+//! A flight without the data for the chosen reference shows the other one,
+//! and `FlightDataResponse.altitude_reference` says which was used.
+
+use crate::database::Database;
+use crate::models::{TelemetryData, TelemetryPoint};
+
+pub use drone_logbook_types::altitude::AltitudeReference;
+
+/// Which altitude data a log has, stored per flight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AltitudeSource {
+    /// Barometric height above takeoff only
+    Barometric,
+    /// GPS altitude above mean sea level only
+    GpsMsl,
+    Both,
+    None,
+}
+
+impl AltitudeSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Barometric => "barometric",
+            Self::GpsMsl => "gps_msl",
+            Self::Both => "barometric+gps_msl",
+            Self::None => "none",
+        }
+    }
+}
+
+/// Altitude data of a log, computed at import
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AltitudeProfile {
+    pub source: AltitudeSource,
+    /// Highest GPS altitude above mean sea level
+    pub max_altitude_msl: Option<f64>,
+}
+
+pub fn profile(points: &[TelemetryPoint]) -> AltitudeProfile {
+    let has_height = points.iter().any(|p| p.height.is_some());
+    let max_altitude_msl = points
+        .iter()
+        .filter_map(|p| p.altitude_abs)
+        .filter(|a| a.is_finite())
+        .fold(None, |max: Option<f64>, a| Some(max.map_or(a, |m| m.max(a))));
+    let source = match (has_height, max_altitude_msl.is_some()) {
+        (true, true) => AltitudeSource::Both,
+        (true, false) => AltitudeSource::Barometric,
+        (false, true) => AltitudeSource::GpsMsl,
+        (false, false) => AltitudeSource::None,
+    };
+    AltitudeProfile { source, max_altitude_msl }
+}
+
+/// Convert a height series to `reference`, given the flight's takeoff
+/// elevation. Returns the reference the series is in afterwards (unchanged
+/// when the elevation isn't known).
+pub fn convert_heights(heights: &mut [Option<f64>], reference: AltitudeReference, takeoff_elevation: Option<f64>) -> AltitudeReference {
+    match (reference, takeoff_elevation) {
+        (AltitudeReference::Msl, Some(elevation)) => {
+            for h in heights.iter_mut().flatten() {
+                *h += elevation;
+            }
+            AltitudeReference::Msl
+        }
+        _ => AltitudeReference::Takeoff,
+    }
+}
+
+/// Put a flight's height series in the profile's reference. Returns the
+/// reference used.
+pub fn apply_to_telemetry(db: &Database, flight_id: i64, telemetry: &mut TelemetryData) -> AltitudeReference {
+    let reference = db.get_altitude_reference();
+    let takeoff_elevation = match reference {
+        AltitudeReference::Takeoff => None,
+        AltitudeReference::Msl => db.get_takeoff_elevation(flight_id).unwrap_or_else(|e| {
+            log::warn!("Failed to get takeoff elevation of flight {}: {}", flight_id, e);
+            None
+        }),
+    };
+    convert_heights(&mut telemetry.height, reference, takeoff_elevation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_and_conversion() {
+        let points = vec![
+            TelemetryPoint { height: Some(0.0), altitude_abs: Some(412.0), ..Default::default() },
+            TelemetryPoint { height: Some(55.0), altitude_abs: Some(468.5), ..Default::default() },
+            TelemetryPoint { height: Some(60.0), ..Default::default() },
+        ];
+        let p = profile(&points);
+        assert_eq!(p.source, AltitudeSource::Both);
+        assert_eq!(p.max_altitude_msl, Some(468.5));
+        assert_eq!(profile(&points[2..]).source, AltitudeSource::Barometric);
+        assert_eq!(profile(&[]).source, AltitudeSource::None);
+
+        let mut heights = vec![Some(0.0), None, Some(60.0)];
+        assert_eq!(convert_heights(&mut heights, AltitudeReference::Msl, None), AltitudeReference::Takeoff);
+        assert_eq!(heights, vec![Some(0.0), None, Some(60.0)]);
+        assert_eq!(convert_heights(&mut heights, AltitudeReference::Msl, Some(412.0)), AltitudeReference::Msl);
+        assert_eq!(heights, vec![Some(412.0), None, Some(472.0)]);
+
+        assert_eq!(AltitudeReference::parse("msl"), Ok(AltitudeReference::Msl));
+        assert!(AltitudeReference::parse("agl").is_err());
+    }
+}
@@ -0,0 +1,328 @@
+//! Parser module for DJI Assistant 2 black-box CSV exports.
+//!
+//! DJI Assistant 2 pulls the aircraft's onboard flight records over USB. Raw
+//! `.DAT` records are handled by [`crate::dat_parser`]; this module reads the
+//! CSV form of the same stream (Assistant 2 / DatCon layout), where columns are
+//! named `<stream>(<index>):<field>` — e.g. `IMU_ATTI(0):Latitude`,
+//! `GPS(0):dateTimeStamp`, `Battery(0):cellVolts1` — next to an `offsetTime`
+//! column in seconds. The decoded stream is a [`DatLog`], so it can be imported
+//! as its own flight or fused into the phone log of the same flight exactly
+//! like a DAT file.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Utc};
+
+use crate::dat_parser::{DatLog, DatParser, MIN_POINT_INTERVAL_MS};
+use crate::formats::{has_extension, FlightLogFormat, ParseFuture};
+use crate::models::TelemetryPoint;
+use crate::parser::{LogParser, ParserError};
+
+/// DJI Assistant 2 flight record CSV exports
+pub struct DjiAssistantFormat;
+
+impl FlightLogFormat for DjiAssistantFormat {
+    fn name(&self) -> &'static str {
+        "DJI Assistant 2 CSV"
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        is_assistant_csv(path)
+    }
+
+    fn parse<'a>(&'a self, ctx: &'a LogParser<'_>, path: &'a Path, file_hash: &'a str) -> ParseFuture<'a> {
+        Box::pin(async move {
+            let parse_start = std::time::Instant::now();
+            log::info!("Parsing DJI Assistant 2 CSV file: {:?}", path);
+            let dat = decode_file(path)?;
+            Ok(DatParser::new(ctx.db()).flight_from_log(dat, path, file_hash, "DJI Assistant 2 CSV", parse_start))
+        })
+    }
+}
+
+/// Check the header line for the black-box stream columns
+pub fn is_assistant_csv(path: &Path) -> bool {
+    if !has_extension(path, "csv") {
+        return false;
+    }
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    match BufReader::new(file).lines().next() {
+        Some(Ok(first_line)) => {
+            let lower = first_line.to_lowercase();
+            lower.contains("offsettime") && lower.contains("imu_atti(") && lower.contains(":latitude")
+        }
+        _ => false,
+    }
+}
+
+/// Read and decode an Assistant 2 CSV export
+pub fn decode_file(path: &Path) -> Result<DatLog, ParserError> {
+    let content = std::fs::read_to_string(path)?;
+    decode_csv(&content)
+}
+
+/// Normalize a column name: lowercase, stream index groups removed
+/// (`IMU_ATTI(0):Latitude` → `imu_atti:latitude`)
+fn normalize_header(header: &str) -> String {
+    let mut out = String::with_capacity(header.len());
+    let mut chars = header.trim().trim_matches('"').chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '(' {
+            let rest: String = chars.clone().take_while(|c| *c != ')').collect();
+            if !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit()) {
+                for _ in 0..=rest.len() {
+                    chars.next();
+                }
+                continue;
+            }
+        }
+        out.push(c.to_ascii_lowercase());
+    }
+    out
+}
+
+struct Columns {
+    index: HashMap<String, usize>,
+}
+
+impl Columns {
+    fn new(headers: &[&str]) -> Self {
+        let mut index = HashMap::new();
+        for (i, h) in headers.iter().enumerate() {
+            // First occurrence wins (stream 0 before stream 1)
+            index.entry(normalize_header(h)).or_insert(i);
+        }
+        Self { index }
+    }
+
+    /// First matching column among the aliases
+    fn find(&self, aliases: &[&str]) -> Option<usize> {
+        aliases.iter().find_map(|a| self.index.get(*a).copied())
+    }
+
+    /// Cell voltage columns in cell order
+    fn cells(&self) -> Vec<usize> {
+        let mut cells: Vec<(u32, usize)> = self
+            .index
+            .iter()
+            .filter_map(|(name, &i)| {
+                let n = name
+                    .strip_prefix("battery:cellvolts")
+                    .or_else(|| name.strip_prefix("battery:cellvolt"))
+                    .or_else(|| name.strip_prefix("battery:cell"))?;
+                n.parse::<u32>().ok().map(|n| (n, i))
+            })
+            .collect();
+        cells.sort();
+        cells.into_iter().map(|(_, i)| i).collect()
+    }
+}
+
+/// Decode the CSV text into a telemetry stream
+pub fn decode_csv(content: &str) -> Result<DatLog, ParserError> {
+    let mut lines = content.lines();
+    let header_line = lines
+        .next()
+        .ok_or_else(|| ParserError::Parse("Empty CSV file".to_string()))?;
+    let headers: Vec<&str> = header_line.split(',').collect();
+    let cols = Columns::new(&headers);
+
+    let time_col = cols
+        .find(&["offsettime"])
+        .ok_or_else(|| ParserError::Parse("Assistant 2 CSV has no offsetTime column".to_string()))?;
+    let lat_col = cols.find(&["imu_atti:latitude", "gps:latitude"]);
+    let lon_col = cols.find(&["imu_atti:longitude", "gps:longitude"]);
+    let (Some(lat_col), Some(lon_col)) = (lat_col, lon_col) else {
+        return Err(ParserError::Parse("Assistant 2 CSV has no latitude/longitude columns".to_string()));
+    };
+    let tick_col = cols.find(&["tick#", "tick"]);
+    let alt_col = cols.find(&["imu_atti:alti:d", "imu_atti:alti", "imu_atti:altitude", "gps:heightmsl"]);
+    let height_col = cols.find(&["general:relativeheight", "osd:height", "imu_atti:relativeheight"]);
+    let vel_n = cols.find(&["imu_atti:veln"]);
+    let vel_e = cols.find(&["imu_atti:vele"]);
+    let vel_d = cols.find(&["imu_atti:veld"]);
+    let roll_col = cols.find(&["imu_atti:roll:d", "imu_atti:roll"]);
+    let pitch_col = cols.find(&["imu_atti:pitch:d", "imu_atti:pitch"]);
+    let yaw_col = cols.find(&["imu_atti:yaw:d", "imu_atti:yaw360", "imu_atti:yaw"]);
+    let gimbal_pitch = cols.find(&["gimbal:pitch"]);
+    let gimbal_roll = cols.find(&["gimbal:roll"]);
+    let gimbal_yaw = cols.find(&["gimbal:yaw"]);
+    let battery_pct = cols.find(&["battery:relativecapacity", "smart_batt:battery%", "battery:percent"]);
+    let battery_volts = cols.find(&["battery:totalvolts", "battery:voltage"]);
+    let battery_current = cols.find(&["battery:current"]);
+    let battery_temp = cols.find(&["battery:temp", "battery:temperature"]);
+    let battery_full = cols.find(&["battery:fcc", "battery:fullchargecapacity"]);
+    let battery_remain = cols.find(&["battery:remcap", "battery:remainingcapacity"]);
+    let cell_cols = cols.cells();
+    let sats_col = cols.find(&["gps:numsv", "imu_atti:numsats"]);
+    let mode_col = cols.find(&["controller:flightmode", "flightcontroller:flightmode", "general:flycstate", "flycstate"]);
+    let gps_dt_col = cols.find(&["gps:datetimestamp"]);
+    let gps_date_col = cols.find(&["gps:date"]);
+    let gps_time_col = cols.find(&["gps:time"]);
+
+    let mut points: Vec<TelemetryPoint> = Vec::new();
+    let mut start_time: Option<DateTime<Utc>> = None;
+    let mut first_offset: Option<f64> = None;
+    let mut base_alt: Option<f64> = None;
+    let mut last_ts: Option<i64> = None;
+    let mut ticks: Vec<(f64, f64)> = Vec::new();
+
+    for line in lines {
+        let fields: Vec<&str> = line.split(',').collect();
+        let num = |col: Option<usize>| -> Option<f64> {
+            col.and_then(|c| fields.get(c))
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|v| v.is_finite())
+        };
+        let text = |col: Option<usize>| col.and_then(|c| fields.get(c)).map(|v| v.trim()).filter(|v| !v.is_empty());
+
+        let Some(offset) = num(Some(time_col)) else {
+            continue;
+        };
+        if let Some(tick) = num(tick_col) {
+            ticks.push((tick, offset));
+        }
+
+        // Anchor the timeline on the first GPS time seen
+        if start_time.is_none() {
+            let gps_time = text(gps_dt_col).and_then(parse_gps_datetime).or_else(|| {
+                match (num(gps_date_col), num(gps_time_col)) {
+                    (Some(d), Some(t)) => parse_gps_date_time(d as u32, t as u32),
+                    _ => None,
+                }
+            });
+            start_time = gps_time.map(|t| t - chrono::Duration::milliseconds((offset * 1000.0).round() as i64));
+        }
+
+        let (Some(lat), Some(lon)) = (num(Some(lat_col)), num(Some(lon_col))) else {
+            continue;
+        };
+        if lat.abs() > 90.0 || lon.abs() > 180.0 || (lat.abs() < 1e-6 && lon.abs() < 1e-6) {
+            continue;
+        }
+
+        let t0 = *first_offset.get_or_insert(offset);
+        let ts = ((offset - t0) * 1000.0).round() as i64;
+        if last_ts.is_some_and(|prev| ts - prev < MIN_POINT_INTERVAL_MS) {
+            continue;
+        }
+        last_ts = Some(ts);
+
+        let altitude = num(alt_col);
+        if base_alt.is_none() {
+            base_alt = altitude;
+        }
+        let (vn, ve, vd) = (num(vel_n), num(vel_e), num(vel_d));
+        let cells: Vec<f64> = cell_cols
+            .iter()
+            .filter_map(|&c| num(Some(c)))
+            .map(|v| if v > 100.0 { v / 1000.0 } else { v })
+            .collect();
+
+        points.push(TelemetryPoint {
+            timestamp_ms: ts,
+            latitude: Some(lat),
+            longitude: Some(lon),
+            altitude,
+            height: num(height_col).or_else(|| altitude.zip(base_alt).map(|(a, b)| a - b)),
+            speed: vn.zip(ve).map(|(n, e)| (n * n + e * e).sqrt()),
+            velocity_x: vn,
+            velocity_y: ve,
+            velocity_z: vd,
+            pitch: num(pitch_col),
+            roll: num(roll_col),
+            yaw: num(yaw_col),
+            gimbal_pitch: num(gimbal_pitch),
+            gimbal_roll: num(gimbal_roll),
+            gimbal_yaw: num(gimbal_yaw),
+            battery_percent: num(battery_pct).map(|v| v.round() as i32),
+            battery_voltage: num(battery_volts).map(|v| if v > 100.0 { v / 1000.0 } else { v }),
+            battery_current: num(battery_current).map(f64::abs),
+            battery_temp: num(battery_temp),
+            battery_full_capacity: num(battery_full),
+            battery_remained_capacity: num(battery_remain),
+            cell_voltages: if cells.is_empty() { None } else { Some(cells) },
+            satellites: num(sats_col).map(|v| v as i32),
+            flight_mode: text(mode_col).map(str::to_string),
+            ..Default::default()
+        });
+    }
+
+    if points.is_empty() {
+        log::warn!("No positioned rows found in Assistant 2 CSV");
+        return Err(ParserError::NoTelemetryData);
+    }
+
+    // Shift the anchor from the first row to the first emitted point
+    let start_time = start_time.map(|t| t + chrono::Duration::milliseconds((first_offset.unwrap_or(0.0) * 1000.0).round() as i64));
+
+    let tick_rate_hz = match (ticks.first(), ticks.last()) {
+        (Some(&(k0, t0)), Some(&(k1, t1))) if t1 > t0 && k1 > k0 => (k1 - k0) / (t1 - t0),
+        _ => 0.0,
+    };
+
+    Ok(DatLog { points, start_time, tick_rate_hz })
+}
+
+/// `2019-05-10T14:32:07Z` (also accepts a space separator and no zone)
+fn parse_gps_datetime(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            let s = s.trim_end_matches('Z');
+            NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S")
+                .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S"))
+                .ok()
+                .map(|ndt| ndt.and_utc())
+        })
+        .filter(|dt| dt.year() >= 2010)
+}
+
+/// GPS date as yyyymmdd and time as hhmmss
+fn parse_gps_date_time(date: u32, time: u32) -> Option<DateTime<Utc>> {
+    let (year, month, day) = ((date / 10_000) as i32, (date / 100) % 100, date % 100);
+    if year < 2010 {
+        return None;
+    }
+    NaiveDate::from_ymd_opt(year, month, day)?
+        .and_hms_opt(time / 10_000, (time / 100) % 100, time % 100)
+        .map(|ndt| ndt.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_header() {
+        assert_eq!(normalize_header("IMU_ATTI(0):Latitude"), "imu_atti:latitude");
+        assert_eq!(normalize_header("Battery(1):cellVolts3"), "battery:cellvolts3");
+        assert_eq!(normalize_header("Tick#"), "tick#");
+        assert_eq!(normalize_header("Smart_Batt:battery(%)"), "smart_batt:battery(%)");
+    }
+
+    #[test]
+    fn test_decode_csv() {
+        let csv = "Tick#,offsetTime,IMU_ATTI(0):Latitude,IMU_ATTI(0):Longitude,IMU_ATTI(0):alti:D,IMU_ATTI(0):velN,IMU_ATTI(0):velE,GPS(0):dateTimeStamp,Battery(0):cellVolts1,Battery(0):cellVolts2\n\
+                   6000,10.0,,,,,,,,\n\
+                   6060,10.1,47.1,8.5,400.0,3.0,4.0,2023-06-01T12:00:10Z,3950,3948\n\
+                   6066,10.11,47.1,8.5,400.0,3.0,4.0,,,\n\
+                   6120,10.2,47.1001,8.5001,402.5,3.0,4.0,,3949,3947\n";
+        let log = decode_csv(csv).unwrap();
+        assert_eq!(log.points.len(), 2);
+        assert_eq!(log.points[0].timestamp_ms, 0);
+        assert_eq!(log.points[1].timestamp_ms, 100);
+        assert!((log.points[0].speed.unwrap() - 5.0).abs() < 1e-9);
+        assert!((log.points[1].height.unwrap() - 2.5).abs() < 1e-9);
+        assert!((log.points[0].cell_voltages.as_ref().unwrap()[0] - 3.95).abs() < 1e-9);
+        let expected = NaiveDate::from_ymd_opt(2023, 6, 1).unwrap().and_hms_opt(12, 0, 10).unwrap().and_utc();
+        assert_eq!(log.start_time, Some(expected));
+        assert!((log.tick_rate_hz - 600.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,235 @@
+//! Battery retirement recommendations.
+//!
+//! A battery is recommended for retirement when any configured threshold is
+//! crossed: cycle count, full-charge capacity left relative to the first
+//! capacity seen, growth of the take-off voltage sag (an ageing cell's
+//! internal resistance rises), or age since its first logged flight. The
+//! figures come from the fleet overview; thresholds live in config.json and a
+//! threshold of 0 switches that check off.
+//!
+//! Recommendations are listed with the overview stats. The first time a
+//! battery gets flagged, the post-import pipeline raises a `battery_health`
+//! notification and, when a webhook URL is set, posts a JSON notification.
+
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::database::{Database, DatabaseError};
+use crate::fleet::{FleetBattery, FleetOverview};
+
+pub use drone_logbook_types::battery_retirement::RetirementRecommendation;
+
+const CONFIG_KEY: &str = "battery_retirement";
+/// Settings key prefix marking batteries the webhook was already told about
+const NOTIFIED_PREFIX: &str = "battery_retirement.notified.";
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Retirement thresholds (0 disables a check)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetirementSettings {
+    pub max_cycles: i64,
+    /// Full-charge capacity left, percent of the first capacity seen
+    pub min_capacity_retention_pct: f64,
+    /// Growth of the take-off voltage sag over the first flights, percent
+    pub max_sag_increase_pct: f64,
+    /// Days since the battery's first logged flight
+    pub max_age_days: i64,
+    /// Receives a JSON POST the first time a battery is flagged
+    pub webhook_url: Option<String>,
+}
+
+impl Default for RetirementSettings {
+    fn default() -> Self {
+        Self {
+            max_cycles: 300,
+            min_capacity_retention_pct: 80.0,
+            max_sag_increase_pct: 30.0,
+            max_age_days: 1095,
+            webhook_url: None,
+        }
+    }
+}
+
+impl RetirementSettings {
+    pub fn from_config(config: &serde_json::Value) -> Self {
+        config
+            .get(CONFIG_KEY)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Read the settings from a `config.json` (defaults when missing)
+    pub fn load(config_path: &Path) -> Self {
+        let config: serde_json::Value = std::fs::read_to_string(config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+        Self::from_config(&config)
+    }
+}
+
+/// Store new thresholds in config.json
+pub fn save_settings(config_path: &Path, mut settings: RetirementSettings) -> Result<RetirementSettings, String> {
+    if settings.max_cycles < 0 || settings.max_age_days < 0 || settings.max_sag_increase_pct < 0.0 {
+        return Err("Thresholds cannot be negative".to_string());
+    }
+    if !(0.0..=100.0).contains(&settings.min_capacity_retention_pct) {
+        return Err("Capacity retention threshold must be between 0 and 100%".to_string());
+    }
+    settings.webhook_url = settings.webhook_url.map(|u| u.trim().to_string()).filter(|u| !u.is_empty());
+    if let Some(url) = &settings.webhook_url {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!("Webhook URL must start with http:// or https://: {}", url));
+        }
+    }
+
+    let mut config: serde_json::Value = std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    config[CONFIG_KEY] = serde_json::json!(settings);
+    std::fs::write(config_path, serde_json::to_string_pretty(&config).unwrap())
+        .map_err(|e| format!("Failed to write config: {}", e))?;
+    Ok(settings)
+}
+
+/// Check one battery against the thresholds
+pub fn assess(battery: &FleetBattery, settings: &RetirementSettings, now: DateTime<Utc>) -> Option<RetirementRecommendation> {
+    let sag_increase_pct = match (battery.baseline_sag_v, battery.recent_sag_v) {
+        (Some(base), Some(recent)) if base > 0.0 => Some((recent / base - 1.0) * 100.0),
+        _ => None,
+    };
+    let age_days = battery
+        .first_flight
+        .as_deref()
+        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+        .map(|t| (now - t.with_timezone(&Utc)).num_days());
+
+    let mut reasons = Vec::new();
+    if settings.max_cycles > 0 && battery.cycles >= settings.max_cycles {
+        reasons.push(format!("{} cycles (limit {})", battery.cycles, settings.max_cycles));
+    }
+    if let Some(retention) = battery.capacity_retention_pct.filter(|r| settings.min_capacity_retention_pct > 0.0 && *r < settings.min_capacity_retention_pct) {
+        reasons.push(format!(
+            "{:.0}% of its original capacity left (minimum {:.0}%)",
+            retention, settings.min_capacity_retention_pct
+        ));
+    }
+    if let Some(increase) = sag_increase_pct.filter(|i| settings.max_sag_increase_pct > 0.0 && *i > settings.max_sag_increase_pct) {
+        reasons.push(format!(
+            "take-off voltage sag up {:.0}% since its first flights (limit {:.0}%)",
+            increase, settings.max_sag_increase_pct
+        ));
+    }
+    if let Some(age) = age_days.filter(|a| settings.max_age_days > 0 && *a >= settings.max_age_days) {
+        reasons.push(format!("in service for {} days (limit {})", age, settings.max_age_days));
+    }
+    if reasons.is_empty() {
+        return None;
+    }
+
+    Some(RetirementRecommendation {
+        battery_serial: battery.serial.clone(),
+        display_name: battery.display_name.clone(),
+        cycles: battery.cycles,
+        capacity_retention_pct: battery.capacity_retention_pct,
+        sag_increase_pct,
+        age_days,
+        reasons,
+    })
+}
+
+/// Recommendations for every battery in the fleet, most cycles first
+pub fn recommendations(fleet: &FleetOverview, settings: &RetirementSettings, now: DateTime<Utc>) -> Vec<RetirementRecommendation> {
+    fleet.batteries.iter().filter_map(|b| assess(b, settings, now)).collect()
+}
+
+/// Load the fleet and list the batteries recommended for retirement
+pub fn load_recommendations(db: &Database, config_path: &Path) -> Result<Vec<RetirementRecommendation>, DatabaseError> {
+    let settings = RetirementSettings::load(config_path);
+    let fleet = crate::fleet::load_fleet_overview(db, 30)?;
+    Ok(recommendations(&fleet, &settings, Utc::now()))
+}
+
+/// True (and remembered) the first time a battery is reported
+pub fn mark_notified(db: &Database, serial: &str) -> bool {
+    let key = format!("{}{}", NOTIFIED_PREFIX, serial);
+    if db.get_setting(&key).ok().flatten().is_some() {
+        return false;
+    }
+    if let Err(e) = db.set_setting(&key, &Utc::now().to_rfc3339()) {
+        log::warn!("Failed to record battery retirement notification for {}: {}", serial, e);
+    }
+    true
+}
+
+/// POST a recommendation to the webhook
+pub async fn notify_webhook(url: &str, recommendation: &RetirementRecommendation, flight_id: i64) -> Result<(), String> {
+    let payload = serde_json::json!({
+        "event": "battery_retirement_recommended",
+        "flightId": flight_id,
+        "battery": recommendation,
+    });
+    let client = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .post(url)
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|e| format!("Webhook request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Webhook returned HTTP {}", response.status()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fleet::MaintenanceStatus;
+
+    fn battery(cycles: i64, retention: Option<f64>, sag: (Option<f64>, Option<f64>), first_flight: &str) -> FleetBattery {
+        FleetBattery {
+            serial: "BAT1".to_string(),
+            display_name: None,
+            flight_count: cycles,
+            cycles,
+            total_duration_secs: 0.0,
+            first_flight: Some(first_flight.to_string()),
+            last_flight: None,
+            recent_flights: 0,
+            first_full_capacity_mah: None,
+            latest_full_capacity_mah: None,
+            capacity_retention_pct: retention,
+            recent_drain_per_min: None,
+            baseline_sag_v: sag.0,
+            recent_sag_v: sag.1,
+            maintenance: MaintenanceStatus::default(),
+        }
+    }
+
+    #[test]
+    fn test_assess_thresholds() {
+        let now = "2026-10-16T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let settings = RetirementSettings::default();
+
+        let healthy = battery(120, Some(92.0), (Some(1.0), Some(1.1)), "2025-06-01T00:00:00Z");
+        assert!(assess(&healthy, &settings, now).is_none());
+
+        let worn = battery(310, Some(76.0), (Some(1.0), Some(1.5)), "2023-01-01T00:00:00Z");
+        let rec = assess(&worn, &settings, now).unwrap();
+        assert_eq!(rec.reasons.len(), 4);
+        assert_eq!(rec.sag_increase_pct.map(|p| p.round()), Some(50.0));
+
+        // A threshold of 0 switches the check off
+        let settings = RetirementSettings { max_cycles: 0, min_capacity_retention_pct: 0.0, max_sag_increase_pct: 0.0, max_age_days: 0, webhook_url: None };
+        assert!(assess(&worn, &settings, now).is_none());
+    }
+}
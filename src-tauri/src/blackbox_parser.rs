@@ -0,0 +1,896 @@
+//! Parser module for Betaflight / INAV blackbox logs (`.bbl`, `.bfl`).
+//!
+//! A blackbox file holds one or more logs (one per arming), each made of
+//! `H name:value` header lines followed by a binary frame stream:
+//!
+//! - `I` / `P` — main frames (intra / predicted from the two previous frames)
+//!   with loop time, battery, barometer, attitude and stick inputs
+//! - `G` / `H` — GPS fix and GPS home position
+//! - `S` — slow frames with flight mode flags
+//! - `E` — events (`LOG_END` closes a log)
+//!
+//! The headers describe every field's name, predictor and encoding, so frames
+//! are decoded generically following the reference `blackbox-tools` decoder.
+//! Corrupt frames are skipped by resynchronizing on the next frame marker.
+//!
+//! Main frames run at up to several kHz; points are emitted at 10 Hz. When a
+//! file contains several logs the longest one is imported. Logs without GPS
+//! (most FPV quads) still import with battery, altitude and attitude data but
+//! no track. Blackbox logs do not record state of charge, so the battery
+//! percentage is estimated from the voltage per cell.
+
+use std::io::Read;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+use crate::database::Database;
+use crate::formats::{FlightLogFormat, ParseFuture};
+use crate::models::{FlightMetadata, TelemetryPoint};
+use crate::parser::{LogParser, ParseInfo, ParseResult, ParserError};
+
+/// First header line of every log
+const LOG_START_MARKER: &[u8] = b"H Product:Blackbox flight data recorder";
+/// Emit at most one point every 100 ms (main frames are logged at up to 8 kHz)
+const POINT_INTERVAL_US: i64 = 100_000;
+/// Per-cell voltage range used to estimate the battery percentage
+const CELL_EMPTY_V: f64 = 3.3;
+const CELL_FULL_V: f64 = 4.2;
+/// Highest resting voltage of a charged cell (HV LiPo), used to count cells
+const CELL_MAX_V: f64 = 4.35;
+
+const ENC_SIGNED_VB: u8 = 0;
+const ENC_UNSIGNED_VB: u8 = 1;
+const ENC_NEG_14BIT: u8 = 3;
+const ENC_TAG8_8SVB: u8 = 6;
+const ENC_TAG2_3S32: u8 = 7;
+const ENC_TAG8_4S16: u8 = 8;
+const ENC_NULL: u8 = 9;
+const ENC_TAG2_3SVARIABLE: u8 = 10;
+
+const PRED_PREVIOUS: u8 = 1;
+const PRED_STRAIGHT_LINE: u8 = 2;
+const PRED_AVERAGE_2: u8 = 3;
+const PRED_MINTHROTTLE: u8 = 4;
+const PRED_MOTOR_0: u8 = 5;
+const PRED_INC: u8 = 6;
+const PRED_HOME_COORD: u8 = 7;
+const PRED_1500: u8 = 8;
+const PRED_VBATREF: u8 = 9;
+const PRED_LAST_MAIN_FRAME_TIME: u8 = 10;
+const PRED_MINMOTOR: u8 = 11;
+
+const EVENT_SYNC_BEEP: u8 = 0;
+const EVENT_INFLIGHT_ADJUSTMENT: u8 = 13;
+const EVENT_LOGGING_RESUME: u8 = 14;
+const EVENT_DISARM: u8 = 15;
+const EVENT_FLIGHT_MODE: u8 = 30;
+const EVENT_IMU_FAILURE: u8 = 40;
+const EVENT_LOG_END: u8 = 255;
+
+/// Betaflight / INAV blackbox logs (detected by content, whatever the extension)
+pub struct BlackboxFormat;
+
+impl FlightLogFormat for BlackboxFormat {
+    fn name(&self) -> &'static str {
+        "Blackbox"
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        let Ok(file) = std::fs::File::open(path) else {
+            return false;
+        };
+        let mut head = Vec::with_capacity(LOG_START_MARKER.len());
+        let _ = file.take(LOG_START_MARKER.len() as u64).read_to_end(&mut head);
+        head == LOG_START_MARKER
+    }
+
+    fn parse<'a>(&'a self, ctx: &'a LogParser<'_>, path: &'a Path, file_hash: &'a str) -> ParseFuture<'a> {
+        Box::pin(async move { BlackboxParser::new(ctx.db()).parse(path, file_hash) })
+    }
+}
+
+/// Field definitions of one frame type
+#[derive(Debug, Default, Clone)]
+struct FieldDefs {
+    names: Vec<String>,
+    predictor: Vec<u8>,
+    encoding: Vec<u8>,
+}
+
+impl FieldDefs {
+    fn index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+}
+
+#[derive(Debug, Default)]
+struct LogHeader {
+    main: FieldDefs,
+    /// P frames share the I frame field names
+    inter: FieldDefs,
+    gps: FieldDefs,
+    home: FieldDefs,
+    slow: FieldDefs,
+    firmware: String,
+    craft_name: Option<String>,
+    start_time: Option<DateTime<Utc>>,
+    minthrottle: i64,
+    motor_output_low: i64,
+    vbatref: i64,
+    vbatscale: i64,
+}
+
+impl LogHeader {
+    fn is_inav(&self) -> bool {
+        self.firmware.to_lowercase().contains("inav")
+    }
+
+    /// Betaflight 4+ and INAV log voltage/current in 0.01 units; older
+    /// firmware logs raw ADC readings
+    fn scaled_power(&self) -> bool {
+        if self.is_inav() {
+            return true;
+        }
+        self.firmware
+            .split_whitespace()
+            .find_map(|w| w.split('.').next().and_then(|m| m.parse::<u32>().ok()))
+            .is_some_and(|major| major >= 4)
+    }
+
+    /// Betaflight 4+ logs GPS altitude in decimetres, INAV and older firmware in metres
+    fn gps_altitude_scale(&self) -> f64 {
+        if !self.is_inav() && self.scaled_power() {
+            0.1
+        } else {
+            1.0
+        }
+    }
+}
+
+fn parse_list(value: &str) -> Vec<u8> {
+    value.split(',').map(|v| v.trim().parse().unwrap_or(0)).collect()
+}
+
+/// Parse the `H` header lines; returns the header and the offset of the first frame
+fn parse_header(data: &[u8]) -> (LogHeader, usize) {
+    let mut h = LogHeader::default();
+    let mut pos = 0;
+    while data.get(pos) == Some(&b'H') && data.get(pos + 1) == Some(&b' ') {
+        let end = data[pos..].iter().position(|&b| b == b'\n').map_or(data.len(), |e| pos + e);
+        let line = String::from_utf8_lossy(&data[pos + 2..end]);
+        pos = (end + 1).min(data.len());
+
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key {
+            "Field I name" => h.main.names = value.split(',').map(str::to_string).collect(),
+            "Field I predictor" => h.main.predictor = parse_list(value),
+            "Field I encoding" => h.main.encoding = parse_list(value),
+            "Field P predictor" => h.inter.predictor = parse_list(value),
+            "Field P encoding" => h.inter.encoding = parse_list(value),
+            "Field G name" => h.gps.names = value.split(',').map(str::to_string).collect(),
+            "Field G predictor" => h.gps.predictor = parse_list(value),
+            "Field G encoding" => h.gps.encoding = parse_list(value),
+            "Field H name" => h.home.names = value.split(',').map(str::to_string).collect(),
+            "Field H predictor" => h.home.predictor = parse_list(value),
+            "Field H encoding" => h.home.encoding = parse_list(value),
+            "Field S name" => h.slow.names = value.split(',').map(str::to_string).collect(),
+            "Field S predictor" => h.slow.predictor = parse_list(value),
+            "Field S encoding" => h.slow.encoding = parse_list(value),
+            "Firmware revision" => h.firmware = value.to_string(),
+            "Craft name" if !value.is_empty() => h.craft_name = Some(value.to_string()),
+            "Log start datetime" => {
+                // Boards without a real-time clock write year 0000
+                h.start_time = DateTime::parse_from_rfc3339(value)
+                    .ok()
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .filter(|dt| dt.timestamp() > 1_262_304_000);
+            }
+            "minthrottle" => h.minthrottle = value.parse().unwrap_or(0),
+            "motorOutput" => h.motor_output_low = value.split(',').next().and_then(|v| v.parse().ok()).unwrap_or(0),
+            "vbatref" => h.vbatref = value.parse().unwrap_or(0),
+            "vbatscale" | "vbat_scale" => h.vbatscale = value.parse().unwrap_or(110),
+            _ => {}
+        }
+    }
+    h.inter.names = h.main.names.clone();
+    (h, pos)
+}
+
+fn sign_extend(value: u32, bits: u32) -> i64 {
+    let shift = 32 - bits;
+    (((value << shift) as i32) >> shift) as i64
+}
+
+/// Byte reader over the frame stream
+struct Stream<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Stream<'_> {
+    fn byte(&mut self) -> Option<u8> {
+        let b = *self.data.get(self.pos)?;
+        self.pos += 1;
+        Some(b)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    fn unsigned_vb(&mut self) -> Option<u32> {
+        let mut result: u32 = 0;
+        for i in 0..5 {
+            let b = self.byte()?;
+            result |= ((b & 0x7F) as u32) << (7 * i);
+            if b & 0x80 == 0 {
+                return Some(result);
+            }
+        }
+        // More than 5 bytes: corrupt stream
+        None
+    }
+
+    fn signed_vb(&mut self) -> Option<i64> {
+        let u = self.unsigned_vb()?;
+        Some(((u >> 1) as i32 ^ -((u & 1) as i32)) as i64)
+    }
+
+    fn tag2_3s32(&mut self, out: &mut [i64; 3]) -> Option<()> {
+        let lead = self.byte()?;
+        match lead >> 6 {
+            0 => {
+                out[0] = sign_extend(((lead >> 4) & 0x03) as u32, 2);
+                out[1] = sign_extend(((lead >> 2) & 0x03) as u32, 2);
+                out[2] = sign_extend((lead & 0x03) as u32, 2);
+            }
+            1 => {
+                out[0] = sign_extend((lead & 0x0F) as u32, 4);
+                let b = self.byte()?;
+                out[1] = sign_extend((b >> 4) as u32, 4);
+                out[2] = sign_extend((b & 0x0F) as u32, 4);
+            }
+            2 => {
+                out[0] = sign_extend((lead & 0x3F) as u32, 6);
+                out[1] = sign_extend((self.byte()? & 0x3F) as u32, 6);
+                out[2] = sign_extend((self.byte()? & 0x3F) as u32, 6);
+            }
+            _ => self.tag2_wide(lead, out)?,
+        }
+        Some(())
+    }
+
+    /// Selector 3 of the TAG2 encodings: per-field 8/16/24/32-bit little-endian values
+    fn tag2_wide(&mut self, lead: u8, out: &mut [i64; 3]) -> Option<()> {
+        let mut selector = lead;
+        for v in out.iter_mut() {
+            *v = match selector & 0x03 {
+                0 => self.byte()? as i8 as i64,
+                1 => i16::from_le_bytes([self.byte()?, self.byte()?]) as i64,
+                2 => {
+                    let raw = u32::from_le_bytes([self.byte()?, self.byte()?, self.byte()?, 0]);
+                    sign_extend(raw, 24)
+                }
+                _ => i32::from_le_bytes([self.byte()?, self.byte()?, self.byte()?, self.byte()?]) as i64,
+            };
+            selector >>= 2;
+        }
+        Some(())
+    }
+
+    fn tag2_3svariable(&mut self, out: &mut [i64; 3]) -> Option<()> {
+        let lead = self.byte()?;
+        match lead >> 6 {
+            0 => {
+                out[0] = sign_extend(((lead >> 4) & 0x03) as u32, 2);
+                out[1] = sign_extend(((lead >> 2) & 0x03) as u32, 2);
+                out[2] = sign_extend((lead & 0x03) as u32, 2);
+            }
+            1 => {
+                out[0] = sign_extend(((lead & 0x3E) >> 1) as u32, 5);
+                let b = self.byte()?;
+                out[1] = sign_extend((((lead & 0x01) << 4) | (b >> 4)) as u32, 5);
+                out[2] = sign_extend((b & 0x0F) as u32, 4);
+            }
+            2 => {
+                let (b1, b2) = (self.byte()?, self.byte()?);
+                out[0] = sign_extend((((lead & 0x3F) as u32) << 2) | (b1 >> 6) as u32, 8);
+                out[1] = sign_extend((((b1 & 0x3F) as u32) << 1) | (b2 >> 7) as u32, 7);
+                out[2] = sign_extend((b2 & 0x7F) as u32, 7);
+            }
+            _ => self.tag2_wide(lead, out)?,
+        }
+        Some(())
+    }
+
+    fn tag8_4s16(&mut self, out: &mut [i64; 4]) -> Option<()> {
+        let mut selector = self.byte()?;
+        let mut buffer: u8 = 0;
+        let mut odd_nibble = false;
+        for v in out.iter_mut() {
+            *v = match selector & 0x03 {
+                0 => 0,
+                1 => {
+                    if odd_nibble {
+                        odd_nibble = false;
+                        sign_extend((buffer & 0x0F) as u32, 4)
+                    } else {
+                        buffer = self.byte()?;
+                        odd_nibble = true;
+                        sign_extend((buffer >> 4) as u32, 4)
+                    }
+                }
+                2 => {
+                    if odd_nibble {
+                        let b = self.byte()?;
+                        let value = ((buffer & 0x0F) << 4) | (b >> 4);
+                        buffer = b;
+                        value as i8 as i64
+                    } else {
+                        self.byte()? as i8 as i64
+                    }
+                }
+                _ => {
+                    if odd_nibble {
+                        let (b1, b2) = (self.byte()?, self.byte()?);
+                        let value = (((buffer & 0x0F) as u16) << 12) | ((b1 as u16) << 4) | ((b2 >> 4) as u16);
+                        buffer = b2;
+                        value as i16 as i64
+                    } else {
+                        let (b1, b2) = (self.byte()?, self.byte()?);
+                        i16::from_be_bytes([b1, b2]) as i64
+                    }
+                }
+            };
+            selector >>= 2;
+        }
+        Some(())
+    }
+
+    /// Skip an event frame body; returns true at `LOG_END`
+    fn event(&mut self) -> Option<bool> {
+        match self.byte()? {
+            EVENT_SYNC_BEEP | EVENT_DISARM | EVENT_IMU_FAILURE => {
+                self.unsigned_vb()?;
+            }
+            EVENT_INFLIGHT_ADJUSTMENT => {
+                let function = self.byte()?;
+                if function & 0x80 != 0 {
+                    self.pos += 4;
+                } else {
+                    self.signed_vb()?;
+                }
+            }
+            EVENT_LOGGING_RESUME | EVENT_FLIGHT_MODE => {
+                self.unsigned_vb()?;
+                self.unsigned_vb()?;
+            }
+            EVENT_LOG_END => return Some(true),
+            _ => return None,
+        }
+        Some(false)
+    }
+}
+
+/// Values the predictors refer to outside the frame being decoded
+struct PredictionContext<'h> {
+    header: &'h LogHeader,
+    motor0: Option<usize>,
+    home: [i64; 2],
+    last_main_time: i64,
+}
+
+impl PredictionContext<'_> {
+    fn predict(
+        &self,
+        defs: &FieldDefs,
+        i: usize,
+        raw: i64,
+        current: &[i64],
+        prev: Option<&[i64]>,
+        prev2: Option<&[i64]>,
+    ) -> i64 {
+        let p1 = prev.map_or(0, |p| p[i]);
+        let p2 = prev2.or(prev).map_or(0, |p| p[i]);
+        match defs.predictor.get(i).copied().unwrap_or(0) {
+            PRED_PREVIOUS => raw + p1,
+            PRED_STRAIGHT_LINE => raw + 2 * p1 - p2,
+            PRED_AVERAGE_2 => raw + (p1 + p2) / 2,
+            PRED_MINTHROTTLE => raw + self.header.minthrottle,
+            PRED_MOTOR_0 => raw + self.motor0.and_then(|m| current.get(m).copied()).unwrap_or(0),
+            PRED_HOME_COORD => raw + if defs.names[i].ends_with("[1]") { self.home[1] } else { self.home[0] },
+            PRED_1500 => raw + 1500,
+            PRED_VBATREF => raw + self.header.vbatref,
+            PRED_LAST_MAIN_FRAME_TIME => raw + self.last_main_time,
+            PRED_MINMOTOR => raw + self.header.motor_output_low,
+            _ => raw,
+        }
+    }
+
+    /// Decode one frame's fields, applying the predictors in field order
+    fn decode(
+        &self,
+        s: &mut Stream<'_>,
+        defs: &FieldDefs,
+        prev: Option<&[i64]>,
+        prev2: Option<&[i64]>,
+    ) -> Option<Vec<i64>> {
+        let n = defs.names.len();
+        let mut values = vec![0i64; n];
+        let mut i = 0;
+        while i < n {
+            if defs.predictor.get(i) == Some(&PRED_INC) {
+                values[i] = prev.map_or(0, |p| p[i]) + 1;
+                i += 1;
+                continue;
+            }
+            let encoding = defs.encoding.get(i).copied().unwrap_or(ENC_NULL);
+            let mut raw = [0i64; 8];
+            let count = match encoding {
+                ENC_SIGNED_VB => {
+                    raw[0] = s.signed_vb()?;
+                    1
+                }
+                ENC_UNSIGNED_VB => {
+                    raw[0] = s.unsigned_vb()? as i64;
+                    1
+                }
+                ENC_NEG_14BIT => {
+                    raw[0] = -sign_extend(s.unsigned_vb()?, 14);
+                    1
+                }
+                ENC_NULL => 1,
+                ENC_TAG8_8SVB => {
+                    let group = (i..n.min(i + 8)).take_while(|&j| defs.encoding.get(j) == Some(&ENC_TAG8_8SVB)).count();
+                    if group == 1 {
+                        raw[0] = s.signed_vb()?;
+                    } else {
+                        let header = s.byte()?;
+                        for (j, v) in raw.iter_mut().enumerate().take(group) {
+                            if header & (1 << j) != 0 {
+                                *v = s.signed_vb()?;
+                            }
+                        }
+                    }
+                    group
+                }
+                ENC_TAG2_3S32 | ENC_TAG2_3SVARIABLE => {
+                    let mut out = [0i64; 3];
+                    if encoding == ENC_TAG2_3S32 {
+                        s.tag2_3s32(&mut out)?;
+                    } else {
+                        s.tag2_3svariable(&mut out)?;
+                    }
+                    raw[..3].copy_from_slice(&out);
+                    3
+                }
+                ENC_TAG8_4S16 => {
+                    let mut out = [0i64; 4];
+                    s.tag8_4s16(&mut out)?;
+                    raw[..4].copy_from_slice(&out);
+                    4
+                }
+                _ => return None,
+            };
+            for (j, r) in raw.iter().enumerate().take(count) {
+                if i + j < n {
+                    let value = self.predict(defs, i + j, *r, &values, prev, prev2);
+                    values[i + j] = value;
+                }
+            }
+            i += count;
+        }
+        Some(values)
+    }
+}
+
+/// One decoded log, reduced to 10 Hz points
+struct DecodedLog {
+    header: LogHeader,
+    points: Vec<TelemetryPoint>,
+}
+
+/// Field positions looked up once per log
+struct FieldIndex {
+    time: Option<usize>,
+    vbat: Option<usize>,
+    amperage: Option<usize>,
+    baro: Option<usize>,
+    rssi: Option<usize>,
+    attitude: [Option<usize>; 3],
+    rc: [Option<usize>; 4],
+    gps_sats: Option<usize>,
+    gps_lat: Option<usize>,
+    gps_lon: Option<usize>,
+    gps_alt: Option<usize>,
+    gps_speed: Option<usize>,
+    gps_course: Option<usize>,
+    mode_flags: Option<usize>,
+    failsafe: Option<usize>,
+}
+
+impl FieldIndex {
+    fn new(h: &LogHeader) -> Self {
+        Self {
+            time: h.main.index("time"),
+            vbat: h.main.index("vbatLatest"),
+            amperage: h.main.index("amperageLatest"),
+            baro: h.main.index("BaroAlt"),
+            rssi: h.main.index("rssi"),
+            attitude: [h.main.index("attitude[0]"), h.main.index("attitude[1]"), h.main.index("attitude[2]")],
+            rc: [
+                h.main.index("rcCommand[0]"),
+                h.main.index("rcCommand[1]"),
+                h.main.index("rcCommand[2]"),
+                h.main.index("rcCommand[3]"),
+            ],
+            gps_sats: h.gps.index("GPS_numSat"),
+            gps_lat: h.gps.index("GPS_coord[0]"),
+            gps_lon: h.gps.index("GPS_coord[1]"),
+            gps_alt: h.gps.index("GPS_altitude"),
+            gps_speed: h.gps.index("GPS_speed"),
+            gps_course: h.gps.index("GPS_ground_course"),
+            mode_flags: h.slow.index("flightModeFlags"),
+            failsafe: h.slow.index("failsafePhase"),
+        }
+    }
+}
+
+/// Betaflight `flightModeFlags` bits (INAV uses the same low bits for angle/horizon)
+fn flight_mode_label(flags: i64, failsafe_phase: i64) -> &'static str {
+    if failsafe_phase != 0 {
+        "Failsafe"
+    } else if flags & 0x1 != 0 {
+        "Angle"
+    } else if flags & 0x2 != 0 {
+        "Horizon"
+    } else {
+        "Acro"
+    }
+}
+
+/// Decode the frame stream of one log
+fn decode_log(data: &[u8]) -> DecodedLog {
+    let (header, start) = parse_header(data);
+    let idx = FieldIndex::new(&header);
+    let mut ctx = PredictionContext {
+        header: &header,
+        motor0: header.main.index("motor[0]"),
+        home: [0, 0],
+        last_main_time: 0,
+    };
+
+    let mut s = Stream { data, pos: start };
+    let mut prev: Option<Vec<i64>> = None;
+    let mut prev2: Option<Vec<i64>> = None;
+    let mut last_gps: Option<Vec<i64>> = None;
+    let mut last_slow: Option<Vec<i64>> = None;
+    let mut main_frames: Vec<(Vec<i64>, Option<Vec<i64>>, Option<Vec<i64>>)> = Vec::new();
+    let mut corrupt = 0usize;
+
+    while let Some(marker) = s.byte() {
+        let frame_start = s.pos - 1;
+        let mut log_end = false;
+        let decoded = match marker {
+            b'I' => ctx.decode(&mut s, &header.main, None, None),
+            // P frames need an intact history; decode anyway to stay in sync
+            b'P' => ctx.decode(&mut s, &header.inter, prev.as_deref(), prev2.as_deref()),
+            b'G' => ctx.decode(&mut s, &header.gps, last_gps.as_deref(), None),
+            b'H' if !header.home.names.is_empty() => ctx.decode(&mut s, &header.home, None, None),
+            b'S' => ctx.decode(&mut s, &header.slow, None, None),
+            b'E' => s.event().map(|end| {
+                log_end = end;
+                Vec::new()
+            }),
+            _ => None,
+        };
+        if log_end {
+            break;
+        }
+
+        // A frame is only trusted when the next byte starts another frame
+        let next_ok = s.peek().map_or(true, |b| b"IPGHSE".contains(&b));
+        let Some(values) = decoded.filter(|_| next_ok) else {
+            corrupt += 1;
+            s.pos = frame_start + 1;
+            if marker == b'I' || marker == b'P' {
+                prev = None;
+                prev2 = None;
+            }
+            continue;
+        };
+
+        match marker {
+            b'I' | b'P' => {
+                if marker == b'P' && prev.is_none() {
+                    continue;
+                }
+                let time = idx.time.map_or(0, |t| values[t]);
+                if marker == b'P' && time < ctx.last_main_time {
+                    prev = None;
+                    prev2 = None;
+                    continue;
+                }
+                ctx.last_main_time = time;
+                main_frames.push((values.clone(), last_gps.clone(), last_slow.clone()));
+                prev2 = if marker == b'I' { Some(values.clone()) } else { prev.take() };
+                prev = Some(values);
+            }
+            b'G' => last_gps = Some(values),
+            b'H' => {
+                ctx.home = [values.first().copied().unwrap_or(0), values.get(1).copied().unwrap_or(0)];
+            }
+            b'S' => last_slow = Some(values),
+            _ => {}
+        }
+    }
+    if corrupt > 0 {
+        log::debug!("Blackbox decode: skipped {} corrupt frames", corrupt);
+    }
+
+    let points = build_points(&header, &idx, &main_frames);
+    DecodedLog { header, points }
+}
+
+/// Turn decoded main frames (with the GPS and slow frame in effect) into 10 Hz points
+fn build_points(
+    header: &LogHeader,
+    idx: &FieldIndex,
+    frames: &[(Vec<i64>, Option<Vec<i64>>, Option<Vec<i64>>)],
+) -> Vec<TelemetryPoint> {
+    let scaled = header.scaled_power();
+    let volts = |raw: i64| -> f64 {
+        if scaled {
+            raw as f64 / 100.0
+        } else {
+            // Legacy ADC reading: 3.3 V reference, 12-bit ADC, vbatscale in 1/10
+            (raw * 330 * header.vbatscale.max(1)) as f64 / 4095.0 / 1000.0
+        }
+    };
+    let get = |values: &[i64], i: Option<usize>| i.and_then(|i| values.get(i).copied());
+
+    let first_time = frames.first().and_then(|(v, _, _)| get(v, idx.time)).unwrap_or(0);
+    let base_baro = frames.iter().find_map(|(v, _, _)| get(v, idx.baro));
+    let cells = frames
+        .iter()
+        .find_map(|(v, _, _)| get(v, idx.vbat))
+        .map(|raw| (volts(raw) / CELL_MAX_V).ceil().max(1.0));
+    let mut base_gps_alt: Option<f64> = None;
+    let mut last_emitted: Option<i64> = None;
+    let mut points = Vec::new();
+
+    for (main, gps, slow) in frames {
+        let time = get(main, idx.time).unwrap_or(0);
+        if last_emitted.is_some_and(|prev| time - prev < POINT_INTERVAL_US) {
+            continue;
+        }
+        last_emitted = Some(time);
+
+        let gps = gps.as_deref().filter(|g| {
+            get(g, idx.gps_lat).zip(get(g, idx.gps_lon)).is_some_and(|(lat, lon)| lat != 0 || lon != 0)
+        });
+        let latitude = gps.and_then(|g| get(g, idx.gps_lat)).map(|v| v as f64 / 1e7);
+        let longitude = gps.and_then(|g| get(g, idx.gps_lon)).map(|v| v as f64 / 1e7);
+        let gps_alt = gps.and_then(|g| get(g, idx.gps_alt)).map(|v| v as f64 * header.gps_altitude_scale());
+        if base_gps_alt.is_none() {
+            base_gps_alt = gps_alt;
+        }
+
+        let height = get(main, idx.baro)
+            .zip(base_baro)
+            .map(|(b, base)| (b - base) as f64 / 100.0)
+            .or_else(|| gps_alt.zip(base_gps_alt).map(|(a, base)| a - base));
+        let voltage = get(main, idx.vbat).map(volts);
+        let battery_percent = voltage.zip(cells).map(|(v, c)| {
+            (((v / c - CELL_EMPTY_V) / (CELL_FULL_V - CELL_EMPTY_V)) * 100.0).clamp(0.0, 100.0).round() as i32
+        });
+        let attitude = |axis: usize| get(main, idx.attitude[axis]).map(|v| v as f64 / 10.0);
+        let stick = |axis: usize| get(main, idx.rc[axis]).map(|v| (v as f64 / 5.0).clamp(-100.0, 100.0));
+
+        points.push(TelemetryPoint {
+            timestamp_ms: (time - first_time) / 1000,
+            latitude,
+            longitude,
+            altitude: gps_alt.or(height),
+            height,
+            speed: gps.and_then(|g| get(g, idx.gps_speed)).map(|v| v as f64 / 100.0),
+            roll: attitude(0),
+            pitch: attitude(1),
+            yaw: attitude(2).or_else(|| gps.and_then(|g| get(g, idx.gps_course)).map(|v| v as f64 / 10.0)),
+            battery_voltage: voltage,
+            battery_current: if scaled { get(main, idx.amperage).map(|a| a as f64 / 100.0) } else { None },
+            battery_percent,
+            satellites: gps.and_then(|g| get(g, idx.gps_sats)).map(|v| v as i32),
+            flight_mode: slow.as_deref().and_then(|sl| {
+                get(sl, idx.mode_flags).map(|f| flight_mode_label(f, get(sl, idx.failsafe).unwrap_or(0)).to_string())
+            }),
+            rc_signal: get(main, idx.rssi).map(|r| (r * 100 / 1023) as i32),
+            rc_aileron: stick(0),
+            rc_elevator: stick(1),
+            rc_rudder: stick(2),
+            rc_throttle: get(main, idx.rc[3]).map(|v| ((v as f64 - 1500.0) / 5.0).clamp(-100.0, 100.0)),
+            ..Default::default()
+        });
+    }
+    points
+}
+
+/// Split a file into its logs (one per arming)
+fn split_logs(data: &[u8]) -> Vec<&[u8]> {
+    let starts: Vec<usize> = data
+        .windows(LOG_START_MARKER.len())
+        .enumerate()
+        .filter(|(_, w)| *w == LOG_START_MARKER)
+        .map(|(i, _)| i)
+        .collect();
+    starts
+        .iter()
+        .enumerate()
+        .map(|(k, &start)| &data[start..starts.get(k + 1).copied().unwrap_or(data.len())])
+        .collect()
+}
+
+/// Betaflight / INAV blackbox parser
+pub struct BlackboxParser<'a> {
+    db: &'a Database,
+}
+
+impl<'a> BlackboxParser<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Parse a blackbox file, importing its longest log
+    pub fn parse(&self, file_path: &Path, file_hash: &str) -> Result<ParseResult, ParserError> {
+        let parse_start = std::time::Instant::now();
+        log::info!("Parsing blackbox file: {:?}", file_path);
+
+        let data = std::fs::read(file_path)?;
+        let logs = split_logs(&data);
+        let log_count = logs.len();
+        let log = logs
+            .into_iter()
+            .map(decode_log)
+            .max_by_key(|l| l.points.len())
+            .filter(|l| !l.points.is_empty())
+            .ok_or(ParserError::NoTelemetryData)?;
+        if log_count > 1 {
+            log::info!("Blackbox file contains {} logs; importing the longest ({} points)", log_count, log.points.len());
+        }
+
+        let points = log.points;
+        let header = log.header;
+        let has_gps = points.iter().any(|p| p.latitude.is_some());
+        if !has_gps {
+            log::info!("Blackbox log has no GPS fix — importing without a flight track");
+        }
+
+        let stats = LogParser::new(self.db).calculate_stats(&points);
+
+        let file_name = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let display_name = header
+            .craft_name
+            .clone()
+            .or_else(|| file_path.file_stem().and_then(|s| s.to_str()).map(str::to_string))
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| file_name.clone());
+
+        let firmware_name = if header.is_inav() { "INAV" } else { "Betaflight" };
+        let end_time = header
+            .start_time
+            .map(|t| t + chrono::Duration::milliseconds((stats.duration_secs * 1000.0) as i64));
+
+        let metadata = FlightMetadata {
+            id: self.db.generate_flight_id(),
+            file_name,
+            display_name,
+            file_hash: Some(file_hash.to_string()),
+            drone_model: Some(format!("FPV ({})", firmware_name)),
+            drone_serial: None,
+            aircraft_name: header.craft_name.clone(),
+            battery_serial: None,
+            cycle_count: None,
+            start_time: header.start_time,
+            end_time,
+            duration_secs: Some(stats.duration_secs),
+            total_distance: Some(stats.total_distance_m),
+            max_altitude: Some(stats.max_altitude_m),
+            max_speed: Some(stats.max_speed_ms),
+            home_lat: stats.home_location.map(|h| h[1]),
+            home_lon: stats.home_location.map(|h| h[0]),
+            point_count: points.len() as i32,
+            photo_count: 0,
+            video_count: 0,
+            rc_serial: None,
+            battery_life: None,
+        };
+
+        log::info!(
+            "Blackbox parse complete in {:.1}s: firmware={:?}, duration={:.1}s, distance={:.0}m, max_alt={:.1}m, points={}, gps={}",
+            parse_start.elapsed().as_secs_f64(),
+            header.firmware,
+            stats.duration_secs,
+            stats.total_distance_m,
+            stats.max_altitude_m,
+            points.len(),
+            has_gps
+        );
+
+        let mut tags = LogParser::generate_smart_tags(&metadata, &stats);
+        tags.insert(0, firmware_name.to_string());
+        tags.insert(0, "Blackbox".to_string());
+
+        Ok(ParseResult { metadata, points, tags, manual_tags: Vec::new(), notes: None, color: None, messages: Vec::new(), info: ParseInfo::new("Blackbox") })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uvb(mut v: u32, out: &mut Vec<u8>) {
+        while v >= 0x80 {
+            out.push((v as u8) | 0x80);
+            v >>= 7;
+        }
+        out.push(v as u8);
+    }
+
+    fn svb(v: i32, out: &mut Vec<u8>) {
+        uvb(((v << 1) ^ (v >> 31)) as u32, out);
+    }
+
+    #[test]
+    fn test_tag_encodings() {
+        let mut s = Stream { data: &[0xE4, 0xE5, 0xA1, 0x23, 0x40], pos: 0 };
+        let mut four = [0i64; 4];
+        s.tag8_4s16(&mut four).unwrap();
+        assert_eq!(four, [0, -2, 0x5A, 0x1234]);
+
+        let mut bytes = vec![0xF4, 0xFF, 0x34, 0x12];
+        bytes.extend_from_slice(&(-100_000i32).to_le_bytes());
+        let mut s = Stream { data: &bytes, pos: 0 };
+        let mut three = [0i64; 3];
+        s.tag2_3s32(&mut three).unwrap();
+        assert_eq!(three, [-1, 0x1234, -100_000]);
+    }
+
+    #[test]
+    fn test_decode_minimal_log() {
+        let mut data = b"H Product:Blackbox flight data recorder by Nicholas Sherlock\n\
+H Firmware revision:Betaflight 4.4.2 (abc) STM32F7X2\n\
+H Craft name:Whoop\n\
+H Field I name:loopIteration,time,vbatLatest\n\
+H Field I predictor:0,0,0\n\
+H Field I encoding:1,1,1\n\
+H Field P predictor:6,2,1\n\
+H Field P encoding:9,0,0\n"
+            .to_vec();
+        data.push(b'I');
+        uvb(0, &mut data);
+        uvb(1_000, &mut data);
+        uvb(1_680, &mut data);
+        // 150 ms later, 0.1 V lower
+        data.push(b'P');
+        svb(150_000, &mut data);
+        svb(-10, &mut data);
+        data.extend_from_slice(b"E\xFFEnd of log\0");
+
+        let log = decode_log(&data);
+        assert_eq!(log.header.craft_name.as_deref(), Some("Whoop"));
+        assert_eq!(log.points.len(), 2);
+        assert_eq!(log.points[1].timestamp_ms, 150);
+        assert!((log.points[0].battery_voltage.unwrap() - 16.8).abs() < 1e-9);
+        assert!((log.points[1].battery_voltage.unwrap() - 16.7).abs() < 1e-9);
+        assert_eq!(log.points[0].battery_percent, Some(100));
+        assert!(log.points[0].latitude.is_none());
+    }
+}
@@ -0,0 +1,535 @@
+//! Per-flight export bundle.
+//!
+//! Packs everything known about one flight into a single zip for handing over
+//! to clients, insurers or regulators:
+//!
+//! - `raw/<file>` — the original log, when a copy was kept at import
+//! - `telemetry.csv` — full-resolution telemetry
+//! - `track.gpx`, `track.kml` — the flight path
+//! - `track.png` — rendered track image
+//! - `events.json` — flight metadata, tags, app messages/anomalies and RTH events
+//! - `weather.json` — historical weather at the home point (Open-Meteo), when reachable
+//! - `summary.pdf` — one-page summary with key figures (in the display
+//!   units, see `units`) and the track
+//! - `manifest.json` — bundle contents and generation time
+//!
+//! With home location anonymization enabled, positions are anonymized
+//! throughout and the raw log (which holds the true positions) is left out.
+
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use thiserror::Error;
+use zip::write::SimpleFileOptions;
+
+use crate::database::{Database, DatabaseError};
+use crate::models::{Flight, TelemetryRecord};
+use crate::parser::LogParser;
+use crate::privacy::PrivacySettings;
+use crate::render::{self, RenderOptions};
+use crate::stats_export::csv_field;
+use crate::units::UnitSettings;
+
+/// Timeout for the best-effort weather lookup
+const WEATHER_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Error, Debug)]
+pub enum BundleError {
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("Zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A finished bundle
+pub struct FlightBundle {
+    /// Suggested download file name
+    pub file_name: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Build the export bundle of a flight. `upload_folder` is where original
+/// logs are kept at import (the `keep_uploaded_files` setting).
+pub async fn export_flight_bundle(
+    db: &Database,
+    flight_id: i64,
+    upload_folder: Option<&Path>,
+    privacy: &PrivacySettings,
+    units: &UnitSettings,
+) -> Result<FlightBundle, BundleError> {
+    let mut flight = db.get_flight_by_id(flight_id)?;
+    let mut records = db.get_flight_telemetry(flight_id, None, flight.point_count.map(|c| c as i64))?;
+    let messages = db.get_flight_messages(flight_id)?;
+    let rth_events = db.get_rth_events(flight_id)?;
+    let mut track = render::flight_track(db, flight_id)?;
+    let anonymized = privacy.is_active();
+    if anonymized {
+        privacy.apply_to_flight(&mut flight);
+        privacy.apply_to_records(&mut records);
+        privacy.apply_to_track(&mut track);
+    }
+
+    let weather = match (flight.home_lat, flight.home_lon, flight.start_time_utc()) {
+        (Some(lat), Some(lon), Some(start)) => fetch_weather(lat, lon, start).await,
+        _ => None,
+    };
+    let raw_log = upload_folder
+        .filter(|_| !anonymized)
+        .and_then(|folder| find_raw_log(folder, &flight));
+
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut contents = Vec::new();
+    let mut add = |zip: &mut zip::ZipWriter<Cursor<Vec<u8>>>, name: &str, data: &[u8]| -> Result<(), BundleError> {
+        zip.start_file(name, options)?;
+        zip.write_all(data)?;
+        contents.push(name.to_string());
+        Ok(())
+    };
+
+    if let Some(path) = &raw_log {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("log");
+        add(&mut zip, &format!("raw/{}", name), &std::fs::read(path)?)?;
+    }
+    add(&mut zip, "telemetry.csv", telemetry_csv(&records).as_bytes())?;
+    add(&mut zip, "track.gpx", track_gpx(&flight, &records).as_bytes())?;
+    add(&mut zip, "track.kml", track_kml(&flight, &records).as_bytes())?;
+    if track.len() >= 2 {
+        add(&mut zip, "track.png", &render::render_png(&track, &RenderOptions::default()))?;
+    }
+    let events = json!({
+        "flight": flight,
+        "tags": flight.tags,
+        "messages": messages,
+        "rthEvents": rth_events,
+    });
+    add(&mut zip, "events.json", serde_json::to_string_pretty(&events).unwrap_or_default().as_bytes())?;
+    if let Some(weather) = &weather {
+        add(&mut zip, "weather.json", serde_json::to_string_pretty(weather).unwrap_or_default().as_bytes())?;
+    }
+    add(&mut zip, "summary.pdf", &summary_pdf(&flight, weather.as_ref(), &track, units))?;
+
+    let manifest = json!({
+        "flightId": flight.id,
+        "displayName": flight.display_name,
+        "generatedAt": Utc::now().to_rfc3339(),
+        "appVersion": env!("CARGO_PKG_VERSION"),
+        "rawLogIncluded": raw_log.is_some(),
+        "weatherIncluded": weather.is_some(),
+        "locationsAnonymized": anonymized,
+        "files": contents,
+    });
+    zip.start_file("manifest.json", options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest).unwrap_or_default().as_bytes())?;
+
+    let bytes = zip.finish()?.into_inner();
+    Ok(FlightBundle {
+        file_name: format!("{}_bundle.zip", sanitize_file_stem(&flight.display_name)),
+        bytes,
+    })
+}
+
+/// Locate the kept copy of the original log. Copies are stored under the
+/// original name, or `<stem>_<hash8>.<ext>` when a different file with the
+/// same name was already kept; the content hash must match the flight's.
+pub(crate) fn find_raw_log(folder: &Path, flight: &Flight) -> Option<PathBuf> {
+    let original = Path::new(&flight.file_name);
+    let mut candidates = vec![folder.join(&flight.file_name)];
+    if let Some(hash) = flight.file_hash.as_deref() {
+        let stem = original.file_stem().and_then(|s| s.to_str()).unwrap_or("file");
+        let suffix = &hash[..8.min(hash.len())];
+        candidates.push(match original.extension().and_then(|e| e.to_str()) {
+            Some(ext) => folder.join(format!("{}_{}.{}", stem, suffix, ext)),
+            None => folder.join(format!("{}_{}", stem, suffix)),
+        });
+    }
+    candidates.into_iter().find(|p| {
+        p.is_file()
+            && match flight.file_hash.as_deref() {
+                Some(hash) => LogParser::calculate_file_hash(p).is_ok_and(|h| h == hash),
+                None => true,
+            }
+    })
+}
+
+/// Historical hourly weather at the flight's start, or `None` if the service
+/// is unreachable (the bundle is still produced)
+async fn fetch_weather(lat: f64, lon: f64, start: DateTime<Utc>) -> Option<serde_json::Value> {
+    let date = start.format("%Y-%m-%d").to_string();
+    let url = format!(
+        "https://archive-api.open-meteo.com/v1/archive?latitude={:.4}&longitude={:.4}&start_date={}&end_date={}&hourly=temperature_2m,relative_humidity_2m,wind_speed_10m,wind_gusts_10m,wind_direction_10m,cloud_cover,precipitation,surface_pressure,weather_code&timezone=GMT",
+        lat, lon, date, date
+    );
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(WEATHER_TIMEOUT_SECS))
+        .build()
+        .ok()?;
+    let body: serde_json::Value = match client.get(&url).send().await {
+        Ok(resp) if resp.status().is_success() => resp.json().await.ok()?,
+        Ok(resp) => {
+            log::warn!("Weather lookup for bundle failed: HTTP {}", resp.status());
+            return None;
+        }
+        Err(e) => {
+            log::warn!("Weather lookup for bundle failed: {}", e);
+            return None;
+        }
+    };
+
+    let hourly = body.get("hourly")?;
+    let idx = start.format("%H").to_string().parse::<usize>().ok()?;
+    let value = |key: &str| hourly.get(key).and_then(|a| a.get(idx)).cloned().unwrap_or(serde_json::Value::Null);
+    Some(json!({
+        "source": "open-meteo archive",
+        "latitude": lat,
+        "longitude": lon,
+        "time": format!("{}T{:02}:00Z", date, idx),
+        "temperatureC": value("temperature_2m"),
+        "humidityPercent": value("relative_humidity_2m"),
+        "windSpeedKmh": value("wind_speed_10m"),
+        "windGustsKmh": value("wind_gusts_10m"),
+        "windDirectionDeg": value("wind_direction_10m"),
+        "cloudCoverPercent": value("cloud_cover"),
+        "precipitationMm": value("precipitation"),
+        "pressureHpa": value("surface_pressure"),
+        "weatherCode": value("weather_code"),
+    }))
+}
+
+const CSV_HEADER: &str = "timestamp_ms,latitude,longitude,altitude,height,vps_height,speed,velocity_x,velocity_y,velocity_z,\
+battery_percent,battery_voltage,battery_temp,battery_current,cell_voltages,pitch,roll,yaw,\
+gimbal_pitch,gimbal_roll,gimbal_yaw,satellites,flight_mode,rc_signal,rc_uplink,rc_downlink,is_photo,is_video";
+
+fn telemetry_csv(records: &[TelemetryRecord]) -> String {
+    fn f(v: Option<f64>) -> String {
+        v.map(|x| x.to_string()).unwrap_or_default()
+    }
+    fn i(v: Option<i32>) -> String {
+        v.map(|x| x.to_string()).unwrap_or_default()
+    }
+    fn b(v: Option<bool>) -> String {
+        v.map(|x| x.to_string()).unwrap_or_default()
+    }
+
+    let mut out = String::from(CSV_HEADER);
+    out.push('\n');
+    for r in records {
+        let cells = r
+            .cell_voltages
+            .as_ref()
+            .map(|c| c.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(";"))
+            .unwrap_or_default();
+        let row = [
+            r.timestamp_ms.to_string(),
+            f(r.latitude),
+            f(r.longitude),
+            f(r.altitude),
+            f(r.height),
+            f(r.vps_height),
+            f(r.speed),
+            f(r.velocity_x),
+            f(r.velocity_y),
+            f(r.velocity_z),
+            i(r.battery_percent),
+            f(r.battery_voltage),
+            f(r.battery_temp),
+            f(r.battery_current),
+            cells,
+            f(r.pitch),
+            f(r.roll),
+            f(r.yaw),
+            f(r.gimbal_pitch),
+            f(r.gimbal_roll),
+            f(r.gimbal_yaw),
+            i(r.satellites),
+            csv_field(r.flight_mode.as_deref().unwrap_or("")),
+            i(r.rc_signal),
+            i(r.rc_uplink),
+            i(r.rc_downlink),
+            b(r.is_photo),
+            b(r.is_video),
+        ];
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Records with a usable position, with their absolute time when known
+pub(crate) fn positioned<'a>(
+    flight: &Flight,
+    records: &'a [TelemetryRecord],
+) -> impl Iterator<Item = (&'a TelemetryRecord, f64, f64, Option<DateTime<Utc>>)> + 'a {
+    let start = flight.start_time_utc();
+    records.iter().filter_map(move |r| {
+        let (lat, lon) = (r.latitude?, r.longitude?);
+        if lat.abs() < 1e-6 && lon.abs() < 1e-6 {
+            return None;
+        }
+        Some((r, lat, lon, start.map(|s| s + chrono::Duration::milliseconds(r.timestamp_ms))))
+    })
+}
+
+pub(crate) fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub(crate) fn track_gpx(flight: &Flight, records: &[TelemetryRecord]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<gpx version=\"1.1\" creator=\"Open DroneLog\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n",
+    );
+    out.push_str(&format!("  <trk>\n    <name>{}</name>\n    <trkseg>\n", xml_escape(&flight.display_name)));
+    for (r, lat, lon, time) in positioned(flight, records) {
+        out.push_str(&format!("      <trkpt lat=\"{:.7}\" lon=\"{:.7}\">", lat, lon));
+        if let Some(ele) = r.altitude.or(r.height) {
+            out.push_str(&format!("<ele>{:.2}</ele>", ele));
+        }
+        if let Some(t) = time {
+            out.push_str(&format!("<time>{}</time>", t.format("%Y-%m-%dT%H:%M:%S%.3fZ")));
+        }
+        out.push_str("</trkpt>\n");
+    }
+    out.push_str("    </trkseg>\n  </trk>\n</gpx>\n");
+    out
+}
+
+fn track_kml(flight: &Flight, records: &[TelemetryRecord]) -> String {
+    let coords: Vec<String> = positioned(flight, records)
+        .map(|(r, lat, lon, _)| format!("{:.7},{:.7},{:.2}", lon, lat, r.altitude.or(r.height).unwrap_or(0.0)))
+        .collect();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n  <Document>\n    <name>{name}</name>\n    <Placemark>\n      <name>{name}</name>\n      <LineString>\n        <altitudeMode>absolute</altitudeMode>\n        <coordinates>{coords}</coordinates>\n      </LineString>\n    </Placemark>\n  </Document>\n</kml>\n",
+        name = xml_escape(&flight.display_name),
+        coords = coords.join(" ")
+    )
+}
+
+/// Keep a display name usable as a file name
+pub(crate) fn sanitize_file_stem(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    if cleaned.is_empty() {
+        "flight".to_string()
+    } else {
+        cleaned
+    }
+}
+
+// ============================================================================
+// SUMMARY PDF
+// ============================================================================
+
+/// A4 page size in points
+pub(crate) const PDF_PAGE_WIDTH: f64 = 595.0;
+pub(crate) const PDF_PAGE_HEIGHT: f64 = 842.0;
+pub(crate) const PDF_MARGIN: f64 = 50.0;
+
+/// Escape a string for a PDF literal; characters outside Latin-1 become '?'
+pub(crate) fn pdf_text(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{}", c),
+            c if (' '..='~').contains(&c) => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+fn summary_lines(flight: &Flight, weather: Option<&serde_json::Value>, units: &UnitSettings) -> Vec<(String, String)> {
+    let mut lines = vec![
+        ("Start".to_string(), flight.start_time.clone().unwrap_or_else(|| "-".into())),
+        ("Aircraft".to_string(), flight.aircraft_name.clone().or_else(|| flight.drone_model.clone()).unwrap_or_else(|| "-".into())),
+        ("Aircraft serial".to_string(), flight.drone_serial.clone().unwrap_or_else(|| "-".into())),
+        ("Battery serial".to_string(), flight.battery_serial.clone().unwrap_or_else(|| "-".into())),
+        (
+            "Duration".to_string(),
+            flight
+                .duration_secs
+                .map(|d| format!("{}m {:02}s", (d / 60.0).floor() as i64, (d % 60.0).round() as i64))
+                .unwrap_or_else(|| "-".into()),
+        ),
+        ("Distance".to_string(), flight.total_distance.map(|d| units.format_distance(d)).unwrap_or_else(|| "-".into())),
+        ("Max altitude".to_string(), flight.max_altitude.map(|a| units.format_altitude(a)).unwrap_or_else(|| "-".into())),
+        ("Max speed".to_string(), flight.max_speed.map(|s| units.format_speed(s)).unwrap_or_else(|| "-".into())),
+        (
+            "Home point".to_string(),
+            match (flight.home_lat, flight.home_lon) {
+                (Some(lat), Some(lon)) => format!("{:.5}, {:.5}", lat, lon),
+                _ => "-".into(),
+            },
+        ),
+        (
+            "Photos / videos".to_string(),
+            format!("{} / {}", flight.photo_count.unwrap_or(0), flight.video_count.unwrap_or(0)),
+        ),
+    ];
+    if !flight.tags.is_empty() {
+        lines.push(("Tags".to_string(), flight.tags.iter().map(|t| t.tag.as_str()).collect::<Vec<_>>().join(", ")));
+    }
+    if let Some(w) = weather {
+        let num = |k: &str| w.get(k).and_then(|v| v.as_f64());
+        if let (Some(t), Some(wind)) = (num("temperatureC"), num("windSpeedKmh")) {
+            lines.push((
+                "Weather".to_string(),
+                format!(
+                    "{}, wind {} (gusts {})",
+                    units.format_temperature(t),
+                    units.format_speed(wind / 3.6),
+                    units.format_speed(num("windGustsKmh").unwrap_or(wind) / 3.6)
+                ),
+            ));
+        }
+    }
+    if let Some(notes) = flight.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        lines.push(("Notes".to_string(), notes.replace('\n', " ")));
+    }
+    lines
+}
+
+/// One-page PDF: title, key figures and the track drawn as a vector path
+fn summary_pdf(flight: &Flight, weather: Option<&serde_json::Value>, track: &[[f64; 3]], units: &UnitSettings) -> Vec<u8> {
+    let mut content = String::new();
+    let mut y = PDF_PAGE_HEIGHT - PDF_MARGIN - 10.0;
+    content.push_str(&format!(
+        "BT /F2 18 Tf {:.1} {:.1} Td ({}) Tj ET\n",
+        PDF_MARGIN,
+        y,
+        pdf_text(&flight.display_name)
+    ));
+    y -= 30.0;
+    for (label, value) in summary_lines(flight, weather, units) {
+        content.push_str(&format!(
+            "BT /F2 10 Tf {:.1} {:.1} Td ({}) Tj ET\nBT /F1 10 Tf {:.1} {:.1} Td ({}) Tj ET\n",
+            PDF_MARGIN,
+            y,
+            pdf_text(&label),
+            PDF_MARGIN + 110.0,
+            y,
+            pdf_text(&value)
+        ));
+        y -= 16.0;
+    }
+
+    // Track box below the figures
+    let box_top = y - 10.0;
+    let box_bottom = PDF_MARGIN + 20.0;
+    let box_w = PDF_PAGE_WIDTH - 2.0 * PDF_MARGIN;
+    let box_h = box_top - box_bottom;
+    content.push_str(&format!(
+        "0.8 G 0.5 w {:.1} {:.1} {:.1} {:.1} re S\n",
+        PDF_MARGIN, box_bottom, box_w, box_h
+    ));
+    if track.len() >= 2 && box_h > 50.0 {
+        let (mut min_lon, mut max_lon, mut min_lat, mut max_lat) = (f64::MAX, f64::MIN, f64::MAX, f64::MIN);
+        for p in track {
+            min_lon = min_lon.min(p[0]);
+            max_lon = max_lon.max(p[0]);
+            min_lat = min_lat.min(p[1]);
+            max_lat = max_lat.max(p[1]);
+        }
+        // Equirectangular projection, longitude scaled by cos(lat)
+        let k = ((min_lat + max_lat) / 2.0).to_radians().cos().max(0.01);
+        let span_x = ((max_lon - min_lon) * k).max(1e-9);
+        let span_y = (max_lat - min_lat).max(1e-9);
+        let inner = 15.0;
+        let scale = ((box_w - 2.0 * inner) / span_x).min((box_h - 2.0 * inner) / span_y);
+        let off_x = PDF_MARGIN + (box_w - span_x * scale) / 2.0;
+        let off_y = box_bottom + (box_h - span_y * scale) / 2.0;
+        let to_page = |p: &[f64; 3]| (off_x + (p[0] - min_lon) * k * scale, off_y + (p[1] - min_lat) * scale);
+
+        content.push_str("0.13 0.55 0.9 RG 1.5 w 1 J 1 j\n");
+        for (i, p) in track.iter().enumerate() {
+            let (x, y) = to_page(p);
+            content.push_str(&format!("{:.2} {:.2} {}\n", x, y, if i == 0 { "m" } else { "l" }));
+        }
+        content.push_str("S\n");
+        for (p, color) in [(&track[0], "0.13 0.77 0.37"), (&track[track.len() - 1], "0.94 0.27 0.27")] {
+            let (x, y) = to_page(p);
+            content.push_str(&format!("{} rg {:.2} {:.2} 6 6 re f\n", color, x - 3.0, y - 3.0));
+        }
+    }
+    content.push_str(&format!(
+        "BT /F1 8 Tf {:.1} {:.1} Td (Generated by Open DroneLog on {}) Tj ET\n",
+        PDF_MARGIN,
+        PDF_MARGIN,
+        Utc::now().format("%Y-%m-%d %H:%M UTC")
+    ));
+
+    build_pdf(&content)
+}
+
+/// Assemble a single-page PDF around a content stream (Helvetica fonts)
+pub(crate) fn build_pdf(content: &str) -> Vec<u8> {
+    build_pdf_pages(&[content.to_string()])
+}
+
+/// Assemble a PDF with one page per content stream (Helvetica fonts)
+pub(crate) fn build_pdf_pages(pages: &[String]) -> Vec<u8> {
+    // 1 catalog, 2 page tree, 3-4 fonts, then a page and its content per page
+    let kids: Vec<String> = (0..pages.len()).map(|i| format!("{} 0 R", 5 + 2 * i)).collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_string(),
+    ];
+    for (i, content) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            PDF_PAGE_WIDTH,
+            PDF_PAGE_HEIGHT,
+            6 + 2 * i
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content));
+    }
+
+    let mut out = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, obj) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, obj).as_bytes());
+    }
+    let xref_pos = out.len();
+    out.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+    for off in offsets {
+        out.extend_from_slice(format!("{:010} 00000 n \n", off).as_bytes());
+    }
+    out.extend_from_slice(
+        format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref_pos).as_bytes(),
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pdf_xref_points_at_table() {
+        let pdf = build_pdf("BT /F1 10 Tf 50 50 Td (x) Tj ET\n");
+        let text = String::from_utf8(pdf).unwrap();
+        assert!(text.starts_with("%PDF-1.4"));
+        let start = text.rfind("startxref\n").unwrap() + "startxref\n".len();
+        let pos: usize = text[start..].lines().next().unwrap().parse().unwrap();
+        assert!(text[pos..].starts_with("xref"));
+        let obj6 = text.find("6 0 obj").unwrap();
+        assert!(text[pos..].contains(&format!("{:010} 00000 n", obj6)));
+    }
+
+    #[test]
+    fn test_pdf_text_escaping() {
+        assert_eq!(pdf_text("a(b)\\c"), "a\\(b\\)\\\\c");
+        assert_eq!(pdf_text("Zürich"), "Z?rich");
+    }
+}
@@ -0,0 +1,276 @@
+//! Time-synced export of two flights flown at the same time (e.g. a camera
+//! drone and an FPV chase drone).
+//!
+//! Both tracks are placed on one timeline using the absolute time of each
+//! telemetry record (flight start + offset), and written to one file:
+//!
+//! - KML: a `gx:Track` per flight, so Google Earth's time slider plays both
+//! - CZML: a packet per flight with a sampled position and a trailing path,
+//!   and a document clock spanning both flights, for Cesium
+//!
+//! The flights must overlap in time. With home location anonymization
+//! enabled, positions are anonymized as in the other exports.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use thiserror::Error;
+
+use crate::bundle::{positioned, sanitize_file_stem, xml_escape};
+use crate::database::{Database, DatabaseError};
+use crate::models::{Flight, TelemetryRecord};
+use crate::privacy::PrivacySettings;
+
+/// Track colors as RGBA: first flight orange, second flight blue
+const COLORS: [[u8; 4]; 2] = [[255, 140, 0, 255], [30, 144, 255, 255]];
+
+#[derive(Error, Debug)]
+pub enum ChaseError {
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("A chase export needs two different flights")]
+    SameFlight,
+
+    #[error("Flight {0} has no start time or no GPS positions")]
+    NoTimedTrack(i64),
+
+    #[error("The flights were not in the air at the same time")]
+    NoOverlap,
+}
+
+/// Output format of the chase export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChaseFormat {
+    Kml,
+    Czml,
+}
+
+/// A finished export
+pub struct ChaseExport {
+    /// Suggested download file name
+    pub file_name: String,
+    pub content_type: &'static str,
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TrackPoint {
+    time: DateTime<Utc>,
+    lat: f64,
+    lon: f64,
+    alt: f64,
+}
+
+#[derive(Debug, Clone)]
+struct ChaseTrack {
+    flight_id: i64,
+    name: String,
+    points: Vec<TrackPoint>,
+}
+
+impl ChaseTrack {
+    fn start(&self) -> DateTime<Utc> {
+        self.points[0].time
+    }
+
+    fn end(&self) -> DateTime<Utc> {
+        self.points[self.points.len() - 1].time
+    }
+}
+
+fn build_track(flight: &Flight, records: &[TelemetryRecord]) -> Result<ChaseTrack, ChaseError> {
+    let mut points: Vec<TrackPoint> = positioned(flight, records)
+        .filter_map(|(r, lat, lon, time)| {
+            Some(TrackPoint { time: time?, lat, lon, alt: r.altitude.or(r.height).unwrap_or(0.0) })
+        })
+        .collect();
+    // Keep the first sample of each time so viewers get strictly increasing times
+    points.dedup_by_key(|p| p.time);
+    if points.len() < 2 {
+        return Err(ChaseError::NoTimedTrack(flight.id));
+    }
+    let aircraft = flight.aircraft_name.as_deref().or(flight.drone_model.as_deref());
+    Ok(ChaseTrack {
+        flight_id: flight.id,
+        name: match aircraft {
+            Some(aircraft) => format!("{} ({})", flight.display_name, aircraft),
+            None => flight.display_name.clone(),
+        },
+        points,
+    })
+}
+
+/// Time both flights were in the air
+fn overlap(a: &ChaseTrack, b: &ChaseTrack) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let start = a.start().max(b.start());
+    let end = a.end().min(b.end());
+    (start < end).then_some((start, end))
+}
+
+fn iso(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// KML color (aabbggrr) of an RGBA color
+fn kml_color([r, g, b, a]: [u8; 4]) -> String {
+    format!("{:02x}{:02x}{:02x}{:02x}", a, b, g, r)
+}
+
+fn to_kml(tracks: &[ChaseTrack; 2], title: &str) -> String {
+    let mut out = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<kml xmlns=\"http://www.opengis.net/kml/2.2\" xmlns:gx=\"http://www.google.com/kml/ext/2.2\">\n  <Document>\n    <name>{}</name>\n",
+        xml_escape(title)
+    );
+    for (i, color) in COLORS.iter().enumerate() {
+        out.push_str(&format!(
+            "    <Style id=\"track{i}\">\n      <IconStyle><color>{c}</color></IconStyle>\n      <LineStyle><color>{c}</color><width>3</width></LineStyle>\n    </Style>\n",
+            i = i,
+            c = kml_color(*color)
+        ));
+    }
+    for (i, track) in tracks.iter().enumerate() {
+        out.push_str(&format!(
+            "    <Placemark>\n      <name>{}</name>\n      <styleUrl>#track{}</styleUrl>\n      <gx:Track>\n        <altitudeMode>absolute</altitudeMode>\n",
+            xml_escape(&track.name),
+            i
+        ));
+        for p in &track.points {
+            out.push_str(&format!("        <when>{}</when>\n", iso(p.time)));
+        }
+        for p in &track.points {
+            out.push_str(&format!("        <gx:coord>{:.7} {:.7} {:.2}</gx:coord>\n", p.lon, p.lat, p.alt));
+        }
+        out.push_str("      </gx:Track>\n    </Placemark>\n");
+    }
+    out.push_str("  </Document>\n</kml>\n");
+    out
+}
+
+fn to_czml(tracks: &[ChaseTrack; 2], title: &str) -> serde_json::Value {
+    let start = tracks[0].start().min(tracks[1].start());
+    let end = tracks[0].end().max(tracks[1].end());
+    let mut packets = vec![json!({
+        "id": "document",
+        "name": title,
+        "version": "1.0",
+        "clock": {
+            "interval": format!("{}/{}", iso(start), iso(end)),
+            "currentTime": iso(start),
+            "multiplier": 1,
+            "range": "LOOP_STOP",
+            "step": "SYSTEM_CLOCK_MULTIPLIER",
+        },
+    })];
+    for (track, color) in tracks.iter().zip(COLORS) {
+        let samples: Vec<f64> = track
+            .points
+            .iter()
+            .flat_map(|p| [(p.time - start).num_milliseconds() as f64 / 1000.0, p.lon, p.lat, p.alt])
+            .collect();
+        packets.push(json!({
+            "id": format!("flight-{}", track.flight_id),
+            "name": track.name,
+            "availability": format!("{}/{}", iso(track.start()), iso(track.end())),
+            "position": {
+                "epoch": iso(start),
+                "cartographicDegrees": samples,
+            },
+            "point": { "pixelSize": 10, "color": { "rgba": color } },
+            "path": {
+                "material": { "solidColor": { "color": { "rgba": color } } },
+                "width": 3,
+                "leadTime": 0,
+                "resolution": 1,
+            },
+        }));
+    }
+    serde_json::Value::Array(packets)
+}
+
+/// Build the chase export of two flights
+pub fn export_chase(
+    db: &Database,
+    flight_ids: [i64; 2],
+    format: ChaseFormat,
+    privacy: &PrivacySettings,
+) -> Result<ChaseExport, ChaseError> {
+    if flight_ids[0] == flight_ids[1] {
+        return Err(ChaseError::SameFlight);
+    }
+    let load = |flight_id: i64| -> Result<ChaseTrack, ChaseError> {
+        let mut flight = db.get_flight_by_id(flight_id)?;
+        let mut records = db.get_flight_telemetry(flight_id, None, flight.point_count.map(|c| c as i64))?;
+        if privacy.is_active() {
+            privacy.apply_to_flight(&mut flight);
+            privacy.apply_to_records(&mut records);
+        }
+        build_track(&flight, &records)
+    };
+    let tracks = [load(flight_ids[0])?, load(flight_ids[1])?];
+    if overlap(&tracks[0], &tracks[1]).is_none() {
+        return Err(ChaseError::NoOverlap);
+    }
+
+    let title = format!("{} + {}", tracks[0].name, tracks[1].name);
+    let stem = format!(
+        "{}_{}_chase",
+        sanitize_file_stem(&tracks[0].name),
+        sanitize_file_stem(&tracks[1].name)
+    );
+    Ok(match format {
+        ChaseFormat::Kml => ChaseExport {
+            file_name: format!("{}.kml", stem),
+            content_type: "application/vnd.google-earth.kml+xml",
+            bytes: to_kml(&tracks, &title).into_bytes(),
+        },
+        ChaseFormat::Czml => ChaseExport {
+            file_name: format!("{}.czml", stem),
+            content_type: "application/json",
+            bytes: serde_json::to_vec_pretty(&to_czml(&tracks, &title)).unwrap_or_default(),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(flight_id: i64, start: DateTime<Utc>, secs: i64) -> ChaseTrack {
+        ChaseTrack {
+            flight_id,
+            name: format!("Flight {}", flight_id),
+            points: (0..=secs)
+                .map(|s| TrackPoint {
+                    time: start + chrono::Duration::seconds(s),
+                    lat: 47.0 + s as f64 * 1e-5,
+                    lon: 8.0,
+                    alt: 500.0,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_chase_timeline() {
+        let t0 = DateTime::parse_from_rfc3339("2024-05-01T10:00:00Z").unwrap().with_timezone(&Utc);
+        let camera = track(1, t0, 600);
+        let chase = track(2, t0 + chrono::Duration::seconds(120), 300);
+        assert_eq!(
+            overlap(&camera, &chase),
+            Some((t0 + chrono::Duration::seconds(120), t0 + chrono::Duration::seconds(420)))
+        );
+        assert_eq!(overlap(&camera, &track(3, t0 + chrono::Duration::hours(1), 60)), None);
+
+        // Both tracks share the epoch of the earlier start
+        let czml = to_czml(&[camera.clone(), chase.clone()], "Chase");
+        assert_eq!(czml[0]["clock"]["interval"], "2024-05-01T10:00:00.000Z/2024-05-01T10:10:00.000Z");
+        assert_eq!(czml[2]["position"]["epoch"], "2024-05-01T10:00:00.000Z");
+        assert_eq!(czml[2]["position"]["cartographicDegrees"][0], 120.0);
+
+        let kml = to_kml(&[camera, chase], "Chase");
+        assert_eq!(kml.matches("<gx:Track>").count(), 2);
+        assert!(kml.contains("<when>2024-05-01T10:02:00.000Z</when>"));
+    }
+}
@@ -0,0 +1,267 @@
+//! Optional outbound push of newly imported flights to AirData UAV or
+//! DroneLogbook.com.
+//!
+//! Some pilots need those services' compliance features (maintenance records,
+//! operator reports) but keep this app as their source of truth. When enabled in
+//! the `cloud_push` key of `config.json`, every newly imported log file is
+//! uploaded to the configured service in the background; import never waits on
+//! or fails because of the upload. Each attempt is recorded in the audit log
+//! (action `cloud_push`), and a flight can be pushed again by hand as long as its
+//! original file was kept in the upload folder.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::database::{Database, DatabaseError};
+
+const CONFIG_KEY: &str = "cloud_push";
+const AIRDATA_UPLOAD_URL: &str = "https://api.airdata.com/flight/upload";
+const DRONELOGBOOK_UPLOAD_URL: &str = "https://api.dronelogbook.com/flight/upload";
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+/// Raw log types both services accept (this app's own CSV exports are also
+/// `.csv`; the services reject what they cannot read)
+const PUSHABLE_EXTENSIONS: [&str; 3] = ["txt", "csv", "dat"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum PushError {
+    #[error("Cloud push is not configured")]
+    NotConfigured,
+
+    #[error("File type '{0}' is not accepted by the cloud service")]
+    UnsupportedFile(String),
+
+    #[error("Upload failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("The original log of this flight was not kept in the upload folder")]
+    RawFileMissing,
+
+    #[error("Failed to read log file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("{service} rejected the upload ({status}): {body}")]
+    Rejected { service: &'static str, status: u16, body: String },
+}
+
+/// Target service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloudService {
+    Airdata,
+    Dronelogbook,
+}
+
+impl CloudService {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Airdata => "AirData",
+            Self::Dronelogbook => "DroneLogbook.com",
+        }
+    }
+
+    fn default_url(&self) -> &'static str {
+        match self {
+            Self::Airdata => AIRDATA_UPLOAD_URL,
+            Self::Dronelogbook => DRONELOGBOOK_UPLOAD_URL,
+        }
+    }
+}
+
+/// Push settings as shown to the frontend (the API key is never returned)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudPushSettings {
+    pub enabled: bool,
+    pub service: CloudService,
+    /// Write-only: empty keeps the stored key
+    #[serde(default, skip_serializing)]
+    pub api_key: String,
+    /// Read-only: whether a key is stored
+    #[serde(default)]
+    pub has_api_key: bool,
+    /// Override of the service's upload URL
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+impl Default for CloudPushSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            service: CloudService::Airdata,
+            api_key: String::new(),
+            has_api_key: false,
+            endpoint: None,
+        }
+    }
+}
+
+impl CloudPushSettings {
+    /// Read the settings from a parsed config.json
+    pub fn from_config(config: &serde_json::Value) -> Self {
+        let Some(section) = config.get(CONFIG_KEY) else {
+            return Self::default();
+        };
+        let api_key = section.get("api_key").and_then(|v| v.as_str()).unwrap_or("").to_string();
+        Self {
+            enabled: section.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false),
+            service: section
+                .get("service")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or(CloudService::Airdata),
+            has_api_key: !api_key.is_empty(),
+            api_key,
+            endpoint: section.get("endpoint").and_then(|v| v.as_str()).filter(|s| !s.is_empty()).map(str::to_string),
+        }
+    }
+
+    /// True when enabled and a key is stored
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.api_key.is_empty()
+    }
+}
+
+/// Store new settings in config.json; an empty `api_key` keeps the existing one
+pub fn save_settings(config_path: &Path, mut settings: CloudPushSettings) -> Result<CloudPushSettings, String> {
+    let mut config: serde_json::Value = std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    if settings.api_key.trim().is_empty() {
+        settings.api_key = CloudPushSettings::from_config(&config).api_key;
+    }
+    if let Some(endpoint) = settings.endpoint.as_deref().filter(|e| !e.trim().is_empty()) {
+        if !endpoint.starts_with("https://") && !endpoint.starts_with("http://") {
+            return Err("Endpoint must be an http(s) URL".to_string());
+        }
+    }
+    config[CONFIG_KEY] = serde_json::json!({
+        "enabled": settings.enabled,
+        "service": settings.service,
+        "api_key": settings.api_key.trim(),
+        "endpoint": settings.endpoint.as_deref().map(str::trim).unwrap_or(""),
+    });
+    std::fs::write(config_path, serde_json::to_string_pretty(&config).unwrap())
+        .map_err(|e| format!("Failed to write config: {}", e))?;
+    Ok(CloudPushSettings::from_config(&config))
+}
+
+/// Upload one log file, returning the service's response text
+pub async fn push_file(settings: &CloudPushSettings, file_name: &str, bytes: Vec<u8>) -> Result<String, PushError> {
+    if settings.api_key.is_empty() {
+        return Err(PushError::NotConfigured);
+    }
+    if !is_pushable(file_name) {
+        let ext = Path::new(file_name).extension().and_then(|e| e.to_str()).unwrap_or("");
+        return Err(PushError::UnsupportedFile(ext.to_string()));
+    }
+
+    let url = settings.endpoint.as_deref().unwrap_or(settings.service.default_url());
+    let part = reqwest::multipart::Part::bytes(bytes).file_name(file_name.to_string());
+    let form = reqwest::multipart::Form::new().part("file", part);
+    let client = reqwest::Client::builder().timeout(UPLOAD_TIMEOUT).build()?;
+    let request = match settings.service {
+        // AirData authenticates with the API key as the basic-auth user name
+        CloudService::Airdata => client.post(url).basic_auth(&settings.api_key, Some("")),
+        CloudService::Dronelogbook => client.post(url).bearer_auth(&settings.api_key),
+    };
+    let response = request.multipart(form).send().await?;
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(PushError::Rejected {
+            service: settings.service.label(),
+            status: status.as_u16(),
+            body: body.chars().take(300).collect(),
+        });
+    }
+    Ok(body)
+}
+
+/// Upload a log file and record the outcome in the audit log
+pub async fn push_and_record(
+    db: &Database,
+    settings: &CloudPushSettings,
+    flight_id: i64,
+    file_name: &str,
+    bytes: Vec<u8>,
+) -> Result<(), PushError> {
+    let result = push_file(settings, file_name, bytes).await;
+    let service = settings.service.label();
+    match &result {
+        Ok(_) => log::info!("Pushed flight {} ({}) to {}", flight_id, file_name, service),
+        Err(e) => log::warn!("Cloud push of flight {} to {} failed: {}", flight_id, service, e),
+    }
+    db.audit(
+        "cloud_push",
+        "cloud_push",
+        Some(&flight_id.to_string()),
+        serde_json::json!({
+            "service": service,
+            "file": file_name,
+            "success": result.is_ok(),
+            "error": result.as_ref().err().map(|e| e.to_string()),
+        }),
+    );
+    result.map(|_| ())
+}
+
+/// Push a stored flight by hand (e.g. to retry a failed upload). Works while
+/// automatic push is disabled, as long as an API key is stored.
+pub async fn push_flight(
+    db: &Database,
+    config: &serde_json::Value,
+    upload_folder: &Path,
+    flight_id: i64,
+) -> Result<(), PushError> {
+    let settings = CloudPushSettings::from_config(config);
+    if settings.api_key.is_empty() {
+        return Err(PushError::NotConfigured);
+    }
+    let flight = db.get_flight_by_id(flight_id)?;
+    let path = crate::bundle::find_raw_log(upload_folder, &flight).ok_or(PushError::RawFileMissing)?;
+    let bytes = std::fs::read(&path)?;
+    push_and_record(db, &settings, flight_id, &flight.file_name, bytes).await
+}
+
+fn is_pushable(file_name: &str) -> bool {
+    let ext = Path::new(file_name).extension().and_then(|e| e.to_str()).unwrap_or("");
+    PUSHABLE_EXTENSIONS.iter().any(|p| p.eq_ignore_ascii_case(ext))
+}
+
+/// After an import: push the file in the background when cloud push is
+/// enabled. Unsupported file types are skipped silently.
+pub fn push_in_background(db: Arc<Database>, config: &serde_json::Value, flight_id: i64, file_path: &Path) {
+    let settings = CloudPushSettings::from_config(config);
+    let file_name = file_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if !settings.is_active() || !is_pushable(&file_name) {
+        return;
+    }
+    match std::fs::read(file_path) {
+        Ok(bytes) => spawn_push(db, settings, flight_id, file_name, bytes),
+        Err(e) => log::warn!("Cloud push: failed to read {}: {}", file_name, e),
+    }
+}
+
+/// Same as [`push_in_background`] for an upload already held in memory
+pub fn push_bytes_in_background(db: Arc<Database>, config: &serde_json::Value, flight_id: i64, file_name: &str, bytes: &[u8]) {
+    let settings = CloudPushSettings::from_config(config);
+    if settings.is_active() && is_pushable(file_name) {
+        spawn_push(db, settings, flight_id, file_name.to_string(), bytes.to_vec());
+    }
+}
+
+fn spawn_push(db: Arc<Database>, settings: CloudPushSettings, flight_id: i64, file_name: String, bytes: Vec<u8>) {
+    tokio::spawn(async move {
+        let _ = push_and_record(&db, &settings, flight_id, &file_name, bytes).await;
+    });
+}
@@ -0,0 +1,284 @@
+//! Opt-in crash reporting for the desktop app.
+//!
+//! When enabled, a panic hook writes a JSON report (message, location,
+//! thread, backtrace, app version and platform) to `crash_reports/` in the
+//! data directory. This includes panics in dji-log-parser that the parser
+//! catches, which are the usual cause of "file won't import" bug reports.
+//!
+//! Native crashes (segfaults in DuckDB or the log parser) can't be handled
+//! in-process without a separate crash-handler process, so they are detected
+//! instead: a session marker is written at startup and removed on a clean
+//! exit. A marker left behind means the previous run died, and an
+//! `unclean_exit` report is written with the end of the log file.
+//!
+//! Reports stay on disk until the user deletes them; the frontend offers them
+//! for attachment to a GitHub issue through a prefilled issue URL.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+const CRASH_DIR: &str = "crash_reports";
+const ENABLED_FILE: &str = "crash_reporting.txt";
+const SESSION_MARKER: &str = "session.json";
+/// Reports kept; the oldest are removed beyond this
+const MAX_REPORTS: usize = 20;
+/// Log lines attached to an unclean exit report
+const LOG_TAIL_LINES: usize = 200;
+const ISSUE_URL: &str = "https://github.com/arpanghosh8453/open-dronelog/issues/new";
+/// Report text included in the issue URL (browsers cap URL length)
+const ISSUE_BODY_MAX_CHARS: usize = 6000;
+
+fn crash_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(CRASH_DIR)
+}
+
+/// Whether the user opted in
+pub fn is_enabled(data_dir: &Path) -> bool {
+    fs::read_to_string(data_dir.join(ENABLED_FILE))
+        .map(|s| s.trim() == "on")
+        .unwrap_or(false)
+}
+
+/// Opt in or out; takes effect at the next start
+pub fn set_enabled(data_dir: &Path, enabled: bool) -> Result<(), String> {
+    fs::write(data_dir.join(ENABLED_FILE), if enabled { "on" } else { "off" })
+        .map_err(|e| format!("Failed to save crash reporting setting: {}", e))?;
+    if !enabled {
+        mark_clean_exit(data_dir);
+    }
+    Ok(())
+}
+
+/// A stored report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    /// "panic" or "unclean_exit"
+    pub kind: String,
+    pub created_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub thread: Option<String>,
+    pub message: String,
+    /// `file:line:column` of the panic
+    pub location: Option<String>,
+    pub backtrace: Option<String>,
+    /// End of the log file, for unclean exits
+    pub log_tail: Vec<String>,
+}
+
+impl CrashReport {
+    fn new(kind: &str, message: String) -> Self {
+        Self {
+            kind: kind.to_string(),
+            created_at: Utc::now().to_rfc3339(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            thread: None,
+            message,
+            location: None,
+            backtrace: None,
+            log_tail: Vec::new(),
+        }
+    }
+}
+
+/// A report as listed to the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReportEntry {
+    pub file_name: String,
+    pub report: CrashReport,
+    /// Prefilled GitHub new-issue link
+    pub issue_url: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionMarker {
+    started_at: String,
+    app_version: String,
+}
+
+fn write_report(data_dir: &Path, report: &CrashReport) {
+    let dir = crash_dir(data_dir);
+    let file_name = format!("{}-{}.json", report.kind, Utc::now().format("%Y%m%d-%H%M%S%.3f"));
+    let result = fs::create_dir_all(&dir).and_then(|_| {
+        fs::write(dir.join(&file_name), serde_json::to_string_pretty(report).unwrap_or_default())
+    });
+    if let Err(e) = result {
+        eprintln!("Failed to write crash report {}: {}", file_name, e);
+        return;
+    }
+    prune(&dir);
+}
+
+/// Remove the oldest reports beyond `MAX_REPORTS`
+fn prune(dir: &Path) {
+    let mut names = report_file_names(dir);
+    if names.len() <= MAX_REPORTS {
+        return;
+    }
+    names.sort_by_key(|n| n.split_once('-').map(|(_, ts)| ts.to_string()).unwrap_or_default());
+    for name in &names[..names.len() - MAX_REPORTS] {
+        let _ = fs::remove_file(dir.join(name));
+    }
+}
+
+fn report_file_names(dir: &Path) -> Vec<String> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+                .filter(|n| n.ends_with(".json") && n != SESSION_MARKER)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Install the panic hook and check for an unclean previous exit. Does
+/// nothing unless the user opted in.
+pub fn install(data_dir: &Path, log_dir: Option<&Path>) {
+    if !is_enabled(data_dir) {
+        return;
+    }
+    start_session(data_dir, log_dir);
+
+    let data_dir = data_dir.to_path_buf();
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        let mut report = CrashReport::new("panic", message);
+        report.thread = std::thread::current().name().map(|n| n.to_string());
+        report.location = info.location().map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+        report.backtrace = Some(std::backtrace::Backtrace::force_capture().to_string());
+        write_report(&data_dir, &report);
+        default_hook(info);
+    }));
+    log::info!("Crash reporting enabled, reports go to {:?}", crash_dir(&data_dir));
+}
+
+/// Report a marker left by a previous session, then write this session's
+fn start_session(data_dir: &Path, log_dir: Option<&Path>) {
+    let dir = crash_dir(data_dir);
+    let marker = dir.join(SESSION_MARKER);
+
+    if let Some(previous) = fs::read_to_string(&marker).ok().and_then(|s| serde_json::from_str::<SessionMarker>(&s).ok()) {
+        log::warn!("Previous session (started {}) did not exit cleanly", previous.started_at);
+        let mut report = CrashReport::new(
+            "unclean_exit",
+            format!(
+                "The previous session (version {}, started {}) ended without a clean shutdown",
+                previous.app_version, previous.started_at
+            ),
+        );
+        if let Some(log_dir) = log_dir {
+            report.log_tail = crate::support::tail(log_dir, LOG_TAIL_LINES).map(|t| t.lines).unwrap_or_default();
+        }
+        write_report(data_dir, &report);
+    }
+
+    let session = SessionMarker {
+        started_at: Utc::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    if let Err(e) = fs::create_dir_all(&dir).and_then(|_| fs::write(&marker, serde_json::to_string(&session).unwrap_or_default())) {
+        log::warn!("Failed to write session marker: {}", e);
+    }
+}
+
+/// Remove the session marker on a clean shutdown
+pub fn mark_clean_exit(data_dir: &Path) {
+    let _ = fs::remove_file(crash_dir(data_dir).join(SESSION_MARKER));
+}
+
+/// Prefilled GitHub new-issue URL for a report
+pub fn issue_url(report: &CrashReport) -> String {
+    let title = match report.kind.as_str() {
+        "panic" => format!("Crash: {}", report.message.lines().next().unwrap_or_default()),
+        _ => format!("Unexpected exit in v{}", report.app_version),
+    };
+    let mut details = serde_json::to_string_pretty(report).unwrap_or_default();
+    if details.chars().count() > ISSUE_BODY_MAX_CHARS {
+        details = details.chars().take(ISSUE_BODY_MAX_CHARS).collect::<String>() + "\n… (truncated, please attach the full report file)";
+    }
+    let body = format!(
+        "**What were you doing when it happened?**\n\n\n**Crash report** (v{} on {}/{})\n\n```json\n{}\n```\n",
+        report.app_version, report.os, report.arch, details
+    );
+    reqwest::Url::parse_with_params(ISSUE_URL, &[("title", title.as_str()), ("body", body.as_str())])
+        .map(|url| url.to_string())
+        .unwrap_or_else(|_| ISSUE_URL.to_string())
+}
+
+/// Stored reports, newest first
+pub fn list_reports(data_dir: &Path) -> Vec<CrashReportEntry> {
+    let dir = crash_dir(data_dir);
+    let mut entries: Vec<CrashReportEntry> = report_file_names(&dir)
+        .into_iter()
+        .filter_map(|file_name| {
+            let report: CrashReport = serde_json::from_str(&fs::read_to_string(dir.join(&file_name)).ok()?).ok()?;
+            Some(CrashReportEntry { issue_url: issue_url(&report), file_name, report })
+        })
+        .collect();
+    entries.sort_by(|a, b| b.report.created_at.cmp(&a.report.created_at));
+    entries
+}
+
+/// Delete one report, or all of them when `file_name` is None
+pub fn delete_reports(data_dir: &Path, file_name: Option<&str>) -> Result<usize, String> {
+    let dir = crash_dir(data_dir);
+    let names = report_file_names(&dir);
+    let targets: Vec<&String> = match file_name {
+        Some(name) => names.iter().filter(|n| n.as_str() == name).collect(),
+        None => names.iter().collect(),
+    };
+    if file_name.is_some() && targets.is_empty() {
+        return Err(format!("Crash report not found: {}", file_name.unwrap_or_default()));
+    }
+    for name in &targets {
+        fs::remove_file(dir.join(name)).map_err(|e| format!("Failed to delete crash report {}: {}", name, e))?;
+    }
+    Ok(targets.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!is_enabled(dir.path()));
+        set_enabled(dir.path(), true).unwrap();
+        assert!(is_enabled(dir.path()));
+
+        // A marker left by a previous session becomes an unclean exit report
+        let marker = SessionMarker { started_at: "2026-10-15T10:00:00Z".to_string(), app_version: "1.0.0".to_string() };
+        fs::create_dir_all(crash_dir(dir.path())).unwrap();
+        fs::write(crash_dir(dir.path()).join(SESSION_MARKER), serde_json::to_string(&marker).unwrap()).unwrap();
+        start_session(dir.path(), None);
+
+        let reports = list_reports(dir.path());
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].report.kind, "unclean_exit");
+        assert!(reports[0].issue_url.starts_with(ISSUE_URL));
+        assert!(crash_dir(dir.path()).join(SESSION_MARKER).exists());
+
+        mark_clean_exit(dir.path());
+        assert!(!crash_dir(dir.path()).join(SESSION_MARKER).exists());
+        assert_eq!(delete_reports(dir.path(), None), Ok(1));
+        assert!(list_reports(dir.path()).is_empty());
+    }
+}
@@ -0,0 +1,626 @@
+//! Parser module for DJI aircraft onboard `.DAT` flight logs.
+//!
+//! The flight controller writes a high-rate record stream (10–50 Hz) to the
+//! aircraft SD card. This module decodes the unencrypted record layout used by
+//! Phantom 3/4, Mavic Pro, Spark and Inspire firmware (field offsets follow the
+//! reverse-engineered layout documented by the DatCon project), and can fuse the
+//! decoded stream into an already-imported mobile `.txt` flight so incident
+//! analysis gets full-rate position and attitude data.
+//!
+//! Newer aircraft encrypt their DAT files; those are detected and rejected with
+//! a descriptive error instead of producing garbage telemetry.
+
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::database::Database;
+use crate::models::{FlightMetadata, TelemetryPoint, TelemetryRecord};
+use crate::formats::{has_extension, FlightLogFormat, ParseFuture};
+use crate::parser::{LogParser, ParseInfo, ParseResult, ParserError};
+
+/// Marker written into the DAT file header by the flight controller
+const HEADER_MAGIC: &[u8] = b"BUILD";
+/// Byte offset of the header marker
+const HEADER_MAGIC_OFFSET: usize = 16;
+/// Records start right after the fixed-size header
+const RECORDS_START: usize = 128;
+/// Every record starts with this sync byte
+const RECORD_SYNC: u8 = 0x55;
+/// Record header (sync, length, padding, type, tick) plus trailing CRC
+const RECORD_OVERHEAD: usize = 12;
+
+/// IMU attitude record: fused position, barometric altitude, quaternion, velocity
+const REC_IMU_ATTI: u16 = 2048;
+/// GPS receiver record: UTC date/time used to anchor the tick clock
+const REC_GPS: u16 = 2096;
+
+/// Flight controller tick rate used when it cannot be derived from GPS time
+const DEFAULT_TICK_RATE_HZ: f64 = 600.0;
+/// Emit at most one point every 20 ms (50 Hz)
+pub(crate) const MIN_POINT_INTERVAL_MS: i64 = 20;
+/// Slack allowed between the DAT and mobile clocks when matching flights
+const FUSION_CLOCK_SLACK_SECS: i64 = 60;
+
+/// Decoded DAT stream before it is turned into a flight
+pub struct DatLog {
+    pub points: Vec<TelemetryPoint>,
+    /// Absolute UTC time of the first point (None if the GPS never reported a date)
+    pub start_time: Option<DateTime<Utc>>,
+    /// Tick rate used to convert controller ticks to milliseconds (for CSV
+    /// exports, estimated from the tick and time columns; 0 when unknown)
+    pub tick_rate_hz: f64,
+}
+
+/// DJI aircraft onboard `.DAT` logs (encrypted variants are rejected by the parser)
+pub struct DatFormat;
+
+impl FlightLogFormat for DatFormat {
+    fn name(&self) -> &'static str {
+        "DJI DAT"
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        has_extension(path, "dat")
+    }
+
+    fn parse<'a>(&'a self, ctx: &'a LogParser<'_>, path: &'a Path, file_hash: &'a str) -> ParseFuture<'a> {
+        Box::pin(async move { DatParser::new(ctx.db()).parse(path, file_hash) })
+    }
+}
+
+/// DJI aircraft DAT parser
+pub struct DatParser<'a> {
+    db: &'a Database,
+}
+
+impl<'a> DatParser<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Parse a DAT file as a standalone flight
+    pub fn parse(&self, file_path: &Path, file_hash: &str) -> Result<ParseResult, ParserError> {
+        let parse_start = std::time::Instant::now();
+        log::info!("Parsing DJI DAT file: {:?}", file_path);
+
+        let dat = Self::decode_file(file_path)?;
+        Ok(self.flight_from_log(dat, file_path, file_hash, "DJI DAT", parse_start))
+    }
+
+    /// Turn a decoded black-box stream (raw DAT or Assistant 2 CSV export)
+    /// into a standalone flight
+    pub fn flight_from_log(
+        &self,
+        dat: DatLog,
+        file_path: &Path,
+        file_hash: &str,
+        format: &str,
+        parse_start: std::time::Instant,
+    ) -> ParseResult {
+        let points = dat.points;
+
+        let stats = LogParser::new(self.db).calculate_stats(&points);
+
+        let file_name = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let display_name = file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(&file_name)
+            .to_string();
+
+        let end_time = dat
+            .start_time
+            .map(|t| t + chrono::Duration::milliseconds((stats.duration_secs * 1000.0) as i64));
+
+        let metadata = FlightMetadata {
+            id: self.db.generate_flight_id(),
+            file_name,
+            display_name,
+            file_hash: Some(file_hash.to_string()),
+            // The DAT stream does not carry product type or serial numbers
+            drone_model: None,
+            drone_serial: None,
+            aircraft_name: None,
+            battery_serial: None,
+            cycle_count: None,
+            start_time: dat.start_time,
+            end_time,
+            duration_secs: Some(stats.duration_secs),
+            total_distance: Some(stats.total_distance_m),
+            max_altitude: Some(stats.max_altitude_m),
+            max_speed: Some(stats.max_speed_ms),
+            home_lat: stats.home_location.map(|h| h[1]),
+            home_lon: stats.home_location.map(|h| h[0]),
+            point_count: points.len() as i32,
+            photo_count: 0,
+            video_count: 0,
+            rc_serial: None,
+            battery_life: None,
+        };
+
+        log::info!(
+            "{} parse complete in {:.1}s: duration={:.1}s, distance={:.0}m, max_alt={:.1}m, points={}, tick_rate={:.0}Hz",
+            format,
+            parse_start.elapsed().as_secs_f64(),
+            stats.duration_secs,
+            stats.total_distance_m,
+            stats.max_altitude_m,
+            points.len(),
+            dat.tick_rate_hz
+        );
+
+        let mut tags = LogParser::generate_smart_tags(&metadata, &stats);
+        tags.insert(0, "DAT".to_string());
+
+        ParseResult { metadata, points, tags, manual_tags: Vec::new(), notes: None, color: None, messages: Vec::new(), info: ParseInfo::new(format) }
+    }
+
+    /// Read and decode a DAT file into a high-rate telemetry stream
+    pub fn decode_file(file_path: &Path) -> Result<DatLog, ParserError> {
+        let data = fs::read(file_path)?;
+        if data.len() <= RECORDS_START
+            || &data[HEADER_MAGIC_OFFSET..HEADER_MAGIC_OFFSET + HEADER_MAGIC.len()] != HEADER_MAGIC
+        {
+            return Err(ParserError::Parse(
+                "Unrecognized DAT header — this aircraft likely writes encrypted DAT files, which are not supported".to_string(),
+            ));
+        }
+
+        let dat = decode_records(&data);
+        if dat.points.is_empty() {
+            log::warn!("No positioned IMU records found in DAT file");
+            return Err(ParserError::NoTelemetryData);
+        }
+        Ok(dat)
+    }
+
+    /// Decode a black-box log for fusion: a raw `.DAT` file or a DJI
+    /// Assistant 2 CSV export of one
+    pub fn decode_black_box(file_path: &Path) -> Result<DatLog, ParserError> {
+        if has_extension(file_path, "csv") {
+            crate::assistant_parser::decode_file(file_path)
+        } else {
+            Self::decode_file(file_path)
+        }
+    }
+
+    /// Fuse a DAT log (or its Assistant 2 CSV export) into an existing flight's telemetry.
+    ///
+    /// When `flight_id` is None the flight is matched by time: the DAT stream
+    /// carries no aircraft serial, so the mobile flight whose start falls
+    /// inside the DAT recording window is used. Returns the flight id and the
+    /// number of fused points stored.
+    pub fn fuse_into_flight(
+        db: &Database,
+        dat_path: &Path,
+        flight_id: Option<i64>,
+    ) -> Result<(i64, usize), ParserError> {
+        let dat = Self::decode_black_box(dat_path)?;
+        let dat_start = dat.start_time.ok_or_else(|| {
+            ParserError::Parse("DAT log has no GPS time — cannot align it with a mobile log".to_string())
+        })?;
+        let dat_end = dat_start
+            + chrono::Duration::milliseconds(dat.points.last().map(|p| p.timestamp_ms).unwrap_or(0));
+
+        let flight_id = match flight_id {
+            Some(id) => id,
+            None => db
+                .find_flight_in_time_window(dat_start - chrono::Duration::seconds(FUSION_CLOCK_SLACK_SECS), dat_end)
+                .map_err(|e| ParserError::Parse(e.to_string()))?
+                .ok_or_else(|| {
+                    ParserError::Parse("No imported flight overlaps the DAT recording window".to_string())
+                })?,
+        };
+
+        let mobile_start = db
+            .get_flight_start_time(flight_id)
+            .map_err(|e| ParserError::Parse(e.to_string()))?
+            .ok_or_else(|| ParserError::Parse(format!("Flight {} has no start time", flight_id)))?;
+
+        if mobile_start < dat_start - chrono::Duration::seconds(FUSION_CLOCK_SLACK_SECS) || mobile_start > dat_end {
+            return Err(ParserError::Parse(format!(
+                "DAT recording ({} – {}) does not overlap flight {}",
+                dat_start, dat_end, flight_id
+            )));
+        }
+
+        let mobile = db
+            .get_flight_telemetry(flight_id, None, None)
+            .map_err(|e| ParserError::Parse(e.to_string()))?;
+
+        let offset_ms = (dat_start - mobile_start).num_milliseconds();
+        let fused = fuse_with_mobile(&dat.points, offset_ms, &mobile);
+        if fused.is_empty() {
+            return Err(ParserError::Parse(format!(
+                "DAT log does not overlap the telemetry of flight {}",
+                flight_id
+            )));
+        }
+
+        let stored = db
+            .replace_flight_telemetry(flight_id, &fused)
+            .map_err(|e| ParserError::Parse(e.to_string()))?;
+
+        log::info!(
+            "Fused {} DAT points into flight {} (previously {} mobile points, offset {} ms)",
+            stored,
+            flight_id,
+            mobile.len(),
+            offset_ms
+        );
+        Ok((flight_id, stored))
+    }
+}
+
+/// Walk the record stream and build telemetry points from IMU records.
+fn decode_records(data: &[u8]) -> DatLog {
+    let mut imu: Vec<(u32, ImuSample)> = Vec::new();
+    // (tick, utc) at every GPS second transition — used to anchor the tick clock
+    let mut gps_marks: Vec<(u32, DateTime<Utc>)> = Vec::new();
+    let mut resyncs = 0usize;
+
+    let mut pos = RECORDS_START;
+    while pos + RECORD_OVERHEAD <= data.len() {
+        if data[pos] != RECORD_SYNC {
+            pos += 1;
+            resyncs += 1;
+            continue;
+        }
+        let len = data[pos + 1] as usize;
+        let next = pos + len;
+        // Reject implausible lengths and 0x55 bytes that happen to sit inside a payload
+        if len < RECORD_OVERHEAD || next > data.len() || (next < data.len() && data[next] != RECORD_SYNC) {
+            pos += 1;
+            resyncs += 1;
+            continue;
+        }
+
+        let rec_type = u16::from_le_bytes([data[pos + 4], data[pos + 5]]);
+        let tick = u32::from_le_bytes([data[pos + 6], data[pos + 7], data[pos + 8], data[pos + 9]]);
+
+        if rec_type == REC_IMU_ATTI || rec_type == REC_GPS {
+            // Payload bytes are XOR-scrambled with the low byte of the tick counter
+            let key = tick as u8;
+            let payload: Vec<u8> = data[pos + 10..next - 2].iter().map(|b| b ^ key).collect();
+
+            if rec_type == REC_IMU_ATTI {
+                if let Some(sample) = ImuSample::decode(&payload) {
+                    imu.push((tick, sample));
+                }
+            } else if let Some(utc) = decode_gps_time(&payload) {
+                if gps_marks.last().map_or(true, |(_, prev)| *prev != utc) {
+                    gps_marks.push((tick, utc));
+                }
+            }
+        }
+
+        pos = next;
+    }
+
+    if resyncs > 0 {
+        log::debug!("DAT decode: skipped {} bytes while resynchronizing", resyncs);
+    }
+
+    // The first GPS mark may be mid-second; use the first and last transitions
+    let tick_rate_hz = match (gps_marks.get(1), gps_marks.last()) {
+        (Some(&(t0, u0)), Some(&(t1, u1))) if (u1 - u0).num_seconds() >= 10 && t1 > t0 => {
+            let rate = (t1 - t0) as f64 / (u1 - u0).num_seconds() as f64;
+            if rate.is_finite() && rate > 10.0 { rate } else { DEFAULT_TICK_RATE_HZ }
+        }
+        _ => DEFAULT_TICK_RATE_HZ,
+    };
+
+    let first_tick = match imu.first() {
+        Some(&(t, _)) => t,
+        None => {
+            return DatLog { points: Vec::new(), start_time: None, tick_rate_hz };
+        }
+    };
+    // Signed: GPS marks can precede the first IMU record
+    let tick_to_ms = |tick: u32| ((tick as i64 - first_tick as i64) as f64 / tick_rate_hz * 1000.0).round() as i64;
+
+    let base_alt = imu.iter().find_map(|(_, s)| s.altitude);
+    let mut points: Vec<TelemetryPoint> = Vec::with_capacity(imu.len());
+    let mut last_ts: Option<i64> = None;
+
+    for (tick, s) in &imu {
+        let ts = tick_to_ms(*tick);
+        if let Some(prev) = last_ts {
+            if ts - prev < MIN_POINT_INTERVAL_MS {
+                continue;
+            }
+        }
+        last_ts = Some(ts);
+
+        let (roll, pitch, yaw) = s.euler_degrees();
+        points.push(TelemetryPoint {
+            timestamp_ms: ts,
+            latitude: Some(s.latitude),
+            longitude: Some(s.longitude),
+            altitude: s.altitude,
+            height: s.altitude.zip(base_alt).map(|(a, b)| a - b),
+            speed: Some((s.vel_n.powi(2) + s.vel_e.powi(2)).sqrt()),
+            velocity_x: Some(s.vel_n),
+            velocity_y: Some(s.vel_e),
+            velocity_z: Some(s.vel_d),
+            pitch: Some(pitch),
+            roll: Some(roll),
+            yaw: Some(yaw),
+            ..Default::default()
+        });
+    }
+
+    // Re-base the timeline on the first emitted point and derive its UTC time
+    let start_time = gps_marks.get(1).or(gps_marks.first()).map(|&(tick, utc)| {
+        utc - chrono::Duration::milliseconds(tick_to_ms(tick))
+    });
+
+    DatLog { points, start_time, tick_rate_hz }
+}
+
+/// One decoded IMU_ATTI record
+struct ImuSample {
+    latitude: f64,
+    longitude: f64,
+    altitude: Option<f64>,
+    quat: [f64; 4],
+    vel_n: f64,
+    vel_e: f64,
+    vel_d: f64,
+}
+
+impl ImuSample {
+    fn decode(p: &[u8]) -> Option<Self> {
+        let longitude = f64_at(p, 0)?.to_degrees();
+        let latitude = f64_at(p, 8)?.to_degrees();
+        if !latitude.is_finite()
+            || !longitude.is_finite()
+            || latitude.abs() > 90.0
+            || longitude.abs() > 180.0
+            || (latitude.abs() < 1e-6 && longitude.abs() < 1e-6)
+        {
+            return None;
+        }
+
+        let altitude = f32_at(p, 44)
+            .map(|v| v as f64)
+            .filter(|v| v.is_finite() && v.abs() < 10_000.0);
+        let quat = [f32_at(p, 48)?, f32_at(p, 52)?, f32_at(p, 56)?, f32_at(p, 60)?].map(|v| v as f64);
+        let vel_n = f32_at(p, 76)? as f64;
+        let vel_e = f32_at(p, 80)? as f64;
+        let vel_d = f32_at(p, 84)? as f64;
+
+        if quat.iter().chain([vel_n, vel_e, vel_d].iter()).any(|v| !v.is_finite())
+            || vel_n.abs() > 100.0
+            || vel_e.abs() > 100.0
+            || vel_d.abs() > 100.0
+        {
+            return None;
+        }
+
+        Some(Self { latitude, longitude, altitude, quat, vel_n, vel_e, vel_d })
+    }
+
+    /// Convert the attitude quaternion to (roll, pitch, yaw) in degrees
+    fn euler_degrees(&self) -> (f64, f64, f64) {
+        let [w, x, y, z] = self.quat;
+        let roll = (2.0 * (w * x + y * z)).atan2(1.0 - 2.0 * (x * x + y * y));
+        let pitch = (2.0 * (w * y - z * x)).clamp(-1.0, 1.0).asin();
+        let yaw = (2.0 * (w * z + x * y)).atan2(1.0 - 2.0 * (y * y + z * z));
+        (roll.to_degrees(), pitch.to_degrees(), yaw.to_degrees())
+    }
+}
+
+/// Decode the UTC timestamp of a GPS record (date as yyyymmdd, time as hhmmss)
+fn decode_gps_time(p: &[u8]) -> Option<DateTime<Utc>> {
+    let date = u32_at(p, 0)?;
+    let time = u32_at(p, 4)?;
+    let (year, month, day) = ((date / 10_000) as i32, (date / 100) % 100, date % 100);
+    if year < 2010 {
+        return None;
+    }
+    NaiveDate::from_ymd_opt(year, month, day)?
+        .and_hms_opt(time / 10_000, (time / 100) % 100, time % 100)
+        .map(|ndt| ndt.and_utc())
+}
+
+/// Merge high-rate DAT telemetry with a lower-rate mobile log.
+///
+/// `offset_ms` maps DAT timestamps onto the mobile timeline
+/// (`mobile_ts = dat_ts + offset_ms`). Position, attitude and velocity come
+/// from the DAT stream; battery, RC, gimbal, camera and status fields are
+/// carried forward from the latest mobile sample. Only the overlapping range
+/// is kept.
+pub fn fuse_with_mobile(
+    dat_points: &[TelemetryPoint],
+    offset_ms: i64,
+    mobile: &[TelemetryRecord],
+) -> Vec<TelemetryPoint> {
+    let (first_ts, last_ts) = match (mobile.first(), mobile.last()) {
+        (Some(f), Some(l)) => (f.timestamp_ms, l.timestamp_ms),
+        _ => return Vec::new(),
+    };
+
+    let mut fused = Vec::with_capacity(dat_points.len());
+    let mut j = 0usize;
+    let mut last_mobile_idx: Option<usize> = None;
+    // DAT height is relative to the first IMU fix; re-zero it on the mobile height
+    let mut height_bias: Option<f64> = None;
+
+    for p in dat_points {
+        let ts = p.timestamp_ms + offset_ms;
+        if ts < first_ts || ts > last_ts {
+            continue;
+        }
+        while j + 1 < mobile.len() && mobile[j + 1].timestamp_ms <= ts {
+            j += 1;
+        }
+        let m = &mobile[j];
+        // Camera events are rising edges — only emit them once per mobile sample
+        let new_sample = last_mobile_idx != Some(j);
+        last_mobile_idx = Some(j);
+
+        if height_bias.is_none() {
+            height_bias = Some(match (m.height, p.height) {
+                (Some(mh), Some(dh)) => mh - dh,
+                _ => 0.0,
+            });
+        }
+
+        fused.push(TelemetryPoint {
+            timestamp_ms: ts,
+            latitude: p.latitude.or(m.latitude),
+            longitude: p.longitude.or(m.longitude),
+            altitude: m.altitude.or(p.altitude),
+            height: p.height.map(|h| h + height_bias.unwrap_or(0.0)).or(m.height),
+            vps_height: m.vps_height,
+            altitude_abs: p.altitude,
+            speed: p.speed.or(m.speed),
+            velocity_x: p.velocity_x.or(m.velocity_x),
+            velocity_y: p.velocity_y.or(m.velocity_y),
+            velocity_z: p.velocity_z.or(m.velocity_z),
+            pitch: p.pitch.or(m.pitch),
+            roll: p.roll.or(m.roll),
+            yaw: p.yaw.or(m.yaw),
+            gimbal_pitch: m.gimbal_pitch,
+            gimbal_roll: m.gimbal_roll,
+            gimbal_yaw: m.gimbal_yaw,
+            battery_percent: m.battery_percent,
+            battery_voltage: m.battery_voltage,
+            battery_current: m.battery_current,
+            battery_temp: m.battery_temp,
+            battery_full_capacity: m.battery_full_capacity,
+            battery_remained_capacity: m.battery_remained_capacity,
+            cell_voltages: m.cell_voltages.clone(),
+            flight_mode: m.flight_mode.clone(),
+            gps_signal: m.gps_signal,
+            satellites: m.satellites,
+            rc_signal: m.rc_signal,
+            rc_uplink: m.rc_uplink,
+            rc_downlink: m.rc_downlink,
+            rc_aileron: m.rc_aileron,
+            rc_elevator: m.rc_elevator,
+            rc_throttle: m.rc_throttle,
+            rc_rudder: m.rc_rudder,
+            is_photo: Some(new_sample && m.is_photo.unwrap_or(false)),
+            is_video: Some(new_sample && m.is_video.unwrap_or(false)),
+            // Not stored with the mobile telemetry
+            is_flying: None,
+        });
+    }
+
+    fused
+}
+
+fn u32_at(p: &[u8], off: usize) -> Option<u32> {
+    p.get(off..off + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn f32_at(p: &[u8], off: usize) -> Option<f32> {
+    u32_at(p, off).map(f32::from_bits)
+}
+
+fn f64_at(p: &[u8], off: usize) -> Option<f64> {
+    p.get(off..off + 8).map(|b| {
+        f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encode one record: header, payload scrambled with the tick, CRC placeholder
+    fn record(rec_type: u16, tick: u32, payload: &[u8]) -> Vec<u8> {
+        let mut rec = vec![RECORD_SYNC, (payload.len() + RECORD_OVERHEAD) as u8, 0, 0];
+        rec.extend_from_slice(&rec_type.to_le_bytes());
+        rec.extend_from_slice(&tick.to_le_bytes());
+        rec.extend(payload.iter().map(|b| b ^ tick as u8));
+        rec.extend_from_slice(&[0, 0]);
+        rec
+    }
+
+    fn imu_payload(lat: f64, lon: f64, alt: f32) -> Vec<u8> {
+        let mut p = vec![0u8; 88];
+        p[0..8].copy_from_slice(&lon.to_radians().to_le_bytes());
+        p[8..16].copy_from_slice(&lat.to_radians().to_le_bytes());
+        p[44..48].copy_from_slice(&alt.to_le_bytes());
+        // Level attitude (identity quaternion), hovering
+        p[48..52].copy_from_slice(&1.0f32.to_le_bytes());
+        p
+    }
+
+    fn gps_payload(date: u32, time: u32) -> Vec<u8> {
+        [date.to_le_bytes(), time.to_le_bytes()].concat()
+    }
+
+    #[test]
+    fn test_decode_records() {
+        let mut data = vec![0u8; RECORDS_START];
+        data[HEADER_MAGIC_OFFSET..HEADER_MAGIC_OFFSET + HEADER_MAGIC.len()].copy_from_slice(HEADER_MAGIC);
+        // GPS time reported before the first IMU record
+        data.extend(record(REC_GPS, 400, &gps_payload(20240501, 120000)));
+        data.extend(record(REC_IMU_ATTI, 1000, &imu_payload(47.0, 8.0, 100.0)));
+        data.extend([0x55, 0x00, 0x13]);
+        data.extend(record(REC_IMU_ATTI, 1006, &imu_payload(47.0, 8.0, 100.0)));
+        data.extend(record(REC_IMU_ATTI, 1012, &imu_payload(47.0, 8.0, 100.0)));
+        data.extend(record(REC_IMU_ATTI, 1600, &imu_payload(47.0001, 8.0, 102.0)));
+
+        let dat = decode_records(&data);
+        assert_eq!(dat.tick_rate_hz, DEFAULT_TICK_RATE_HZ);
+        // The 10 ms record is dropped, the junk bytes skipped
+        let timestamps: Vec<i64> = dat.points.iter().map(|p| p.timestamp_ms).collect();
+        assert_eq!(timestamps, vec![0, 20, 1000]);
+        assert!((dat.points[0].latitude.unwrap() - 47.0).abs() < 1e-9);
+        assert_eq!(dat.points[2].height, Some(2.0));
+        assert_eq!(dat.points[0].pitch, Some(0.0));
+        // 12:00:00 was 1 s before the first point
+        assert_eq!(dat.start_time.unwrap().to_rfc3339(), "2024-05-01T12:00:01+00:00");
+    }
+
+    #[test]
+    fn test_fuse_with_mobile() {
+        let dat_points: Vec<TelemetryPoint> = [0, 20, 1000, 2000]
+            .iter()
+            .map(|&ts| TelemetryPoint {
+                timestamp_ms: ts,
+                latitude: Some(47.0),
+                longitude: Some(8.0),
+                height: Some(ts as f64 / 1000.0),
+                ..Default::default()
+            })
+            .collect();
+        let mobile: Vec<TelemetryRecord> = [(500, 4), (1500, 5)]
+            .iter()
+            .map(|&(ts, gps)| TelemetryRecord {
+                timestamp_ms: ts,
+                height: Some(10.0),
+                battery_percent: Some(80),
+                gps_signal: Some(gps),
+                is_photo: Some(true),
+                ..Default::default()
+            })
+            .collect();
+
+        // The DAT clock runs 500 ms behind the mobile one
+        let fused = fuse_with_mobile(&dat_points, 500, &mobile);
+        let timestamps: Vec<i64> = fused.iter().map(|p| p.timestamp_ms).collect();
+        assert_eq!(timestamps, vec![500, 520, 1500]);
+        assert_eq!(fused[0].height, Some(10.0));
+        assert_eq!(fused[2].height, Some(11.0));
+        assert_eq!(fused[1].gps_signal, Some(4));
+        assert_eq!(fused[2].gps_signal, Some(5));
+        assert_eq!(fused[2].battery_percent, Some(80));
+        // One photo per mobile sample, not per DAT point
+        let photos: Vec<bool> = fused.iter().map(|p| p.is_photo.unwrap()).collect();
+        assert_eq!(photos, vec![true, false, true]);
+    }
+}
@@ -0,0 +1,222 @@
+//! Relocation of the desktop app's data directory.
+//!
+//! The data directory (databases, keychains, configs, uploaded raw logs,
+//! attachments) normally lives in the OS app data folder. It can be moved to
+//! another folder, e.g. a synced drive or a bigger disk: every file is copied,
+//! then verified by size and SHA-256, and only then does a small
+//! `data_location.txt` in the OS app data folder point the app at the new
+//! location. The app restarts on the new folder; when requested, the old copy
+//! is removed on that next start, once no database file is open there.
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// Pointer file in the OS app data folder naming the relocated data directory
+pub const LOCATION_FILE: &str = "data_location.txt";
+/// Written into the new folder when the old copy should be removed on next start
+const CLEANUP_FILE: &str = "relocated_from.txt";
+
+#[derive(Debug, thiserror::Error)]
+pub enum RelocationError {
+    #[error("The data directory is already at {0}")]
+    SameLocation(PathBuf),
+
+    #[error("The new folder cannot be inside the current data directory (or contain it)")]
+    Nested,
+
+    #[error("The new folder is not empty: {0}")]
+    TargetNotEmpty(PathBuf),
+
+    #[error("Copy verification failed for {0}")]
+    VerificationFailed(PathBuf),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Outcome of a relocation
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelocationReport {
+    pub from: String,
+    pub to: String,
+    pub files: usize,
+    pub bytes: u64,
+    /// The old copy will be deleted on the next start
+    pub old_removal_pending: bool,
+}
+
+/// Data directory to use: the relocated folder named in [`LOCATION_FILE`] when it
+/// exists, otherwise the default OS app data folder
+pub fn resolve(default_dir: &Path) -> PathBuf {
+    let Ok(content) = fs::read_to_string(default_dir.join(LOCATION_FILE)) else {
+        return default_dir.to_path_buf();
+    };
+    let target = PathBuf::from(content.trim());
+    if target.as_os_str().is_empty() {
+        return default_dir.to_path_buf();
+    }
+    if target.is_dir() {
+        target
+    } else {
+        // A detached external drive must not silently start an empty logbook
+        // in its place; fall back and let the user see their old location
+        log::warn!(
+            "Relocated data directory {:?} is not available, using default {:?}",
+            target,
+            default_dir
+        );
+        default_dir.to_path_buf()
+    }
+}
+
+/// Relative paths of every file below `root`, skipping the pointer and cleanup files
+fn list_files(root: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
+    let mut files = Vec::new();
+    let mut stack = vec![PathBuf::new()];
+    while let Some(rel) = stack.pop() {
+        for entry in fs::read_dir(root.join(&rel))? {
+            let entry = entry?;
+            let name = entry.file_name();
+            if rel.as_os_str().is_empty() && (name == LOCATION_FILE || name == CLEANUP_FILE) {
+                continue;
+            }
+            let child = rel.join(&name);
+            if entry.file_type()?.is_dir() {
+                stack.push(child);
+            } else {
+                files.push(child);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn file_hash(path: &Path) -> Result<[u8; 32], std::io::Error> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1 << 16];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finalize().into())
+}
+
+/// Copy the data directory `from` to `to` and verify every file. On success
+/// `default_dir` points at `to` (or at nothing when `to` is the default).
+/// The caller must checkpoint the open database first and restart afterwards.
+pub fn relocate(default_dir: &Path, from: &Path, to: &Path, remove_old: bool) -> Result<RelocationReport, RelocationError> {
+    fs::create_dir_all(to)?;
+    let from = from.canonicalize()?;
+    let to = to.canonicalize()?;
+    if from == to {
+        return Err(RelocationError::SameLocation(to));
+    }
+    if to.starts_with(&from) || from.starts_with(&to) {
+        return Err(RelocationError::Nested);
+    }
+    if fs::read_dir(&to)?.next().is_some() {
+        return Err(RelocationError::TargetNotEmpty(to));
+    }
+
+    let files = list_files(&from)?;
+    let mut bytes = 0;
+    for rel in &files {
+        let dest = to.join(rel);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        bytes += fs::copy(from.join(rel), &dest)?;
+    }
+    for rel in &files {
+        let (src, dest) = (from.join(rel), to.join(rel));
+        if fs::metadata(&src)?.len() != fs::metadata(&dest)?.len() || file_hash(&src)? != file_hash(&dest)? {
+            return Err(RelocationError::VerificationFailed(rel.clone()));
+        }
+    }
+    log::info!("Copied and verified {} files ({} bytes) from {:?} to {:?}", files.len(), bytes, from, to);
+
+    if remove_old {
+        fs::write(to.join(CLEANUP_FILE), from.to_string_lossy().as_bytes())?;
+    }
+    let pointer = default_dir.join(LOCATION_FILE);
+    if default_dir.canonicalize().is_ok_and(|d| d == to) {
+        let _ = fs::remove_file(&pointer);
+    } else {
+        fs::create_dir_all(default_dir)?;
+        fs::write(&pointer, to.to_string_lossy().as_bytes())?;
+    }
+
+    Ok(RelocationReport {
+        from: from.to_string_lossy().to_string(),
+        to: to.to_string_lossy().to_string(),
+        files: files.len(),
+        bytes,
+        old_removal_pending: remove_old,
+    })
+}
+
+/// At startup, before any database is opened: delete the previous copy of the
+/// data directory when the last relocation asked for it. Only files that also
+/// exist in the new folder are removed; the pointer file is kept.
+pub fn finish_pending_cleanup(data_dir: &Path) {
+    let marker = data_dir.join(CLEANUP_FILE);
+    let Ok(old) = fs::read_to_string(&marker) else {
+        return;
+    };
+    let _ = fs::remove_file(&marker);
+    let old = PathBuf::from(old.trim());
+    let Ok(files) = list_files(&old) else {
+        return;
+    };
+    let mut removed = 0;
+    for rel in files.iter().filter(|rel| data_dir.join(rel).is_file()) {
+        if fs::remove_file(old.join(rel)).is_ok() {
+            removed += 1;
+        }
+    }
+    // Drop directories left empty (deepest first)
+    let mut dirs: Vec<PathBuf> = files.iter().filter_map(|rel| rel.parent().map(Path::to_path_buf)).collect();
+    dirs.sort_by_key(|d| std::cmp::Reverse(d.components().count()));
+    dirs.dedup();
+    for dir in dirs.iter().filter(|d| !d.as_os_str().is_empty()) {
+        let _ = fs::remove_dir(old.join(dir));
+    }
+    log::info!("Removed {} files of the previous data directory {:?}", removed, old);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relocate_copies_and_points_default_at_target() {
+        let default_dir = tempfile::tempdir().unwrap();
+        let target = tempfile::tempdir().unwrap();
+        fs::write(default_dir.path().join("flights.db"), b"db").unwrap();
+        fs::create_dir_all(default_dir.path().join("keychains")).unwrap();
+        fs::write(default_dir.path().join("keychains").join("k.json"), b"{}").unwrap();
+
+        let report = relocate(default_dir.path(), default_dir.path(), target.path(), true).unwrap();
+        assert_eq!(report.files, 2);
+        assert_eq!(resolve(default_dir.path()), target.path().canonicalize().unwrap());
+        assert_eq!(fs::read(target.path().join("keychains").join("k.json")).unwrap(), b"{}");
+
+        finish_pending_cleanup(&resolve(default_dir.path()));
+        assert!(!default_dir.path().join("flights.db").exists());
+        assert!(default_dir.path().join(LOCATION_FILE).exists());
+        assert!(matches!(
+            relocate(default_dir.path(), target.path(), target.path(), false),
+            Err(RelocationError::SameLocation(_))
+        ));
+    }
+}
@@ -6,15 +6,242 @@
 //! - Optimized bulk inserts using Appender
 //! - Downsampled query retrieval for large datasets
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
 use std::sync::Mutex;
 
-use duckdb::{params, Connection, OptionalExt, Result as DuckResult};
+use duckdb::{params, AccessMode, Config, Connection, OptionalExt, Result as DuckResult};
+use serde::Serialize;
 use thiserror::Error;
 
-use crate::models::{BatteryHealthPoint, BatteryUsage, DroneUsage, Flight, FlightDateCount, FlightMessage, FlightMetadata, FlightTag, OverviewStats, TelemetryPoint, TelemetryRecord, TopDistanceFlight, TopFlight};
+use crate::models::{ActivityDay, ActivityStats, Attachment, AuditEntry, BatteryHealthPoint, ChecklistRecord, ChecklistTemplate, BatteryUsage, BusiestWeek, DroneUsage, ExceedanceLimits, Flight, FlightChanges, FlightDateCount, FlightMessage, FlightMetadata, FlightExceedance, FlightMetadataUpdate, FlightPreview, FlightStats, FlightTag, FlyingStreak, Histogram, HistogramBin, HistogramMetric, ImportReport, LogbookSummary, MonthlyStats, OverviewStats, RankedFlight, TelemetryPoint, TelemetryRecord, TopDistanceFlight, TopFlight, TopFlightMetric};
+use crate::altitude::AltitudeReference;
+use crate::db_maintenance::MaintenanceStatus;
+use crate::dedupe::ExistingFlight;
+use crate::duration_rounding::DurationRounding;
+use crate::endurance::{EnduranceSample, FlightWeather};
+use crate::fleet::{AirframeSeed, BatteryFlightCondition, MaintenanceRecord};
+use crate::flight_phases::PhaseTimes;
+use crate::import_queue::{QueueStatus, QueuedImport};
+use crate::import_status::ImportActivity;
+use crate::integrity::{self, IntegrityEntry, IntegrityReport};
+use crate::jobs::Job;
+use crate::mission_plan::FlightPlan;
+use crate::notifications::{PushKeys, PushSubscription};
+use crate::overflight::AreaSample;
+use crate::requirements::{Requirement, RequirementMetric};
+use crate::recompute::FlightStatsValues;
+use crate::rth::RthEvent;
+
+/// Settings key holding the app version that last initialized the schema
+const SCHEMA_VERSION_KEY: &str = "schema_app_version";
+/// Pre-migration snapshots kept per database file
+const MIGRATION_SNAPSHOTS_KEPT: usize = 3;
+
+/// Bucket sizes (ms) of the precomputed downsampled telemetry tiers: 1 Hz and 0.2 Hz
+const TELEMETRY_TIERS_MS: [i64; 2] = [1000, 5000];
+
+/// Per-bucket aggregation of the telemetry columns, in [`TelemetryRecord`] order
+/// after the bucket timestamp (see [`map_telemetry_row`])
+const DOWNSAMPLE_AGGREGATES: &str = r#"
+                    AVG(latitude) AS latitude,
+                    AVG(longitude) AS longitude,
+                    AVG(altitude) AS altitude,
+                    AVG(height) AS height,
+                    AVG(vps_height) AS vps_height,
+                    AVG(speed) AS speed,
+                    AVG(velocity_x) AS velocity_x,
+                    AVG(velocity_y) AS velocity_y,
+                    AVG(velocity_z) AS velocity_z,
+                    AVG(battery_percent)::INTEGER AS battery_percent,
+                    AVG(battery_voltage) AS battery_voltage,
+                    AVG(battery_temp) AS battery_temp,
+                    FIRST(cell_voltages ORDER BY timestamp_ms) AS cell_voltages,
+                    AVG(pitch) AS pitch,
+                    AVG(roll) AS roll,
+                    AVG(yaw) AS yaw,
+                    AVG(gimbal_pitch) AS gimbal_pitch,
+                    AVG(gimbal_roll) AS gimbal_roll,
+                    AVG(gimbal_yaw) AS gimbal_yaw,
+                    ROUND(AVG(satellites))::INTEGER AS satellites,
+                    FIRST(flight_mode ORDER BY timestamp_ms) AS flight_mode,
+                    AVG(rc_signal)::INTEGER AS rc_signal,
+                    AVG(rc_uplink)::INTEGER AS rc_uplink,
+                    AVG(rc_downlink)::INTEGER AS rc_downlink,
+                    AVG(rc_aileron) AS rc_aileron,
+                    AVG(rc_elevator) AS rc_elevator,
+                    AVG(rc_throttle) AS rc_throttle,
+                    AVG(rc_rudder) AS rc_rudder,
+                    BOOL_OR(is_photo) AS is_photo,
+                    BOOL_OR(is_video) AS is_video,
+                    AVG(battery_full_capacity) AS battery_full_capacity,
+                    AVG(battery_remained_capacity) AS battery_remained_capacity,
+                    ROUND(AVG(gps_signal))::INTEGER AS gps_signal
+"#;
+
+/// Read the four phase columns (`hover_secs`, `transit_secs`, `ascend_secs`,
+/// `descend_secs`) starting at `first`; None when they were never computed
+fn map_phase_times(row: &duckdb::Row<'_>, first: usize) -> DuckResult<Option<PhaseTimes>> {
+    let hover_secs: Option<f64> = row.get(first)?;
+    Ok(match hover_secs {
+        Some(hover_secs) => Some(PhaseTimes {
+            hover_secs,
+            transit_secs: row.get::<_, Option<f64>>(first + 1)?.unwrap_or(0.0),
+            ascend_secs: row.get::<_, Option<f64>>(first + 2)?.unwrap_or(0.0),
+            descend_secs: row.get::<_, Option<f64>>(first + 3)?.unwrap_or(0.0),
+        }),
+        None => None,
+    })
+}
+
+/// Build a [`TelemetryRecord`] from a row of `timestamp_ms` followed by the
+/// columns of [`DOWNSAMPLE_AGGREGATES`]
+fn map_telemetry_row(row: &duckdb::Row<'_>) -> DuckResult<TelemetryRecord> {
+    // Parse cell_voltages from JSON string
+    let cell_voltages_json: Option<String> = row.get(13)?;
+    let cell_voltages = cell_voltages_json.and_then(|s| {
+        serde_json::from_str::<Vec<f64>>(&s).ok()
+    });
+
+    Ok(TelemetryRecord {
+        timestamp_ms: row.get(0)?,
+        latitude: row.get(1)?,
+        longitude: row.get(2)?,
+        altitude: row.get(3)?,
+        height: row.get(4)?,
+        vps_height: row.get(5)?,
+        speed: row.get(6)?,
+        velocity_x: row.get(7)?,
+        velocity_y: row.get(8)?,
+        velocity_z: row.get(9)?,
+        battery_percent: row.get(10)?,
+        battery_voltage: row.get(11)?,
+        battery_temp: row.get(12)?,
+        cell_voltages,
+        pitch: row.get(14)?,
+        roll: row.get(15)?,
+        yaw: row.get(16)?,
+        gimbal_pitch: row.get(17)?,
+        gimbal_roll: row.get(18)?,
+        gimbal_yaw: row.get(19)?,
+        satellites: row.get(20)?,
+        flight_mode: row.get(21)?,
+        rc_signal: row.get(22)?,
+        rc_uplink: row.get(23)?,
+        rc_downlink: row.get(24)?,
+        rc_aileron: row.get(25)?,
+        rc_elevator: row.get(26)?,
+        rc_throttle: row.get(27)?,
+        rc_rudder: row.get(28)?,
+        is_photo: row.get(29)?,
+        is_video: row.get(30)?,
+        battery_current: None,
+        battery_full_capacity: row.get(31)?,
+        battery_remained_capacity: row.get(32)?,
+        gps_signal: row.get(33)?,
+    })
+}
+
+/// Flag manual entries made before `is_manual` existed (also run after restoring
+/// an older backup); they are recognizable by their placeholder file name
+const MARK_LEGACY_MANUAL_FLIGHTS: &str =
+    "UPDATE flights SET is_manual = TRUE WHERE file_hash IS NULL AND file_name LIKE 'manual_entry_%';";
+
+/// COPY sources for a scrubbed (shareable) backup: serials hashed with a
+/// per-backup salt, coordinates shifted by one random per-backup offset
+struct ScrubbedSources {
+    salt: String,
+    dlat: f64,
+    dlon: f64,
+    /// Named sites of the location overrides, whose auto tags are dropped
+    site_names: Vec<String>,
+}
+
+impl ScrubbedSources {
+    fn new(site_names: Vec<String>) -> Self {
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+        let mut shift = || rng.gen_range(0.5..1.5) * if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+        Self {
+            salt: uuid::Uuid::new_v4().simple().to_string(),
+            dlat: shift(),
+            dlon: shift(),
+            site_names,
+        }
+    }
+
+    fn hashed(&self, column: &str) -> String {
+        format!(
+            "CASE WHEN {0} IS NULL OR {0} = '' THEN {0} ELSE 'anon-' || LEFT(md5('{1}' || {0}), 12) END AS {0}",
+            column, self.salt
+        )
+    }
+
+    /// Shift a latitude/longitude pair, leaving the (0, 0) "no fix" marker alone
+    fn shifted(&self, lat: &str, lon: &str) -> String {
+        let no_fix = format!("(ABS({0}) < 0.000001 AND ABS({1}) < 0.000001)", lat, lon);
+        format!(
+            "CASE WHEN {nf} THEN {lat} ELSE LEAST(89.9, GREATEST(-89.9, {lat} + {dlat})) END AS {lat}, \
+             CASE WHEN {nf} THEN {lon} ELSE (({lon} + {dlon} + 540) % 360) - 180 END AS {lon}",
+            nf = no_fix,
+            lat = lat,
+            lon = lon,
+            dlat = self.dlat,
+            dlon = self.dlon,
+        )
+    }
+
+    fn flights(&self) -> String {
+        format!(
+            "(SELECT * REPLACE ({}, {}, {}, {}, \
+             CAST(NULL AS VARCHAR) AS pilot, CAST(NULL AS VARCHAR) AS location_name, \
+             CAST(NULL AS VARCHAR) AS original_metadata, CAST(NULL AS VARCHAR) AS owner) FROM flights)",
+            self.hashed("drone_serial"),
+            self.hashed("battery_serial"),
+            self.hashed("rc_serial"),
+            self.shifted("home_lat", "home_lon")
+        )
+    }
+
+    fn telemetry(&self) -> String {
+        format!("(SELECT * REPLACE ({}) FROM telemetry)", self.shifted("latitude", "longitude"))
+    }
+
+    fn equipment_names(&self) -> String {
+        format!("(SELECT * REPLACE ({}) FROM equipment_names)", self.hashed("serial"))
+    }
+
+    /// Tags without the auto tags of named sites (they name private places)
+    fn flight_tags(&self) -> String {
+        if self.site_names.is_empty() {
+            return "flight_tags".to_string();
+        }
+        let names: Vec<String> = self.site_names.iter().map(|n| format!("'{}'", n.replace('\'', "''"))).collect();
+        format!(
+            "(SELECT * FROM flight_tags WHERE NOT (tag_type = 'auto' AND tag IN ({})))",
+            names.join(", ")
+        )
+    }
+
+    /// Settings without the location overrides (site names and coordinates)
+    fn settings(&self) -> String {
+        format!("(SELECT * FROM settings WHERE key <> '{}')", crate::locations::SETTING_KEY)
+    }
+}
+
+/// Settings keys for the personal exceedance limits (meters)
+const LIMIT_MAX_ALTITUDE_KEY: &str = "limit_max_altitude_m";
+const LIMIT_MAX_DISTANCE_KEY: &str = "limit_max_distance_m";
+/// Settings key of the altitude reference (see `altitude`)
+const ALTITUDE_REFERENCE_KEY: &str = "altitude_reference";
+/// Settings key of the flight time rounding policy (see `duration_rounding`)
+const DURATION_ROUNDING_KEY: &str = "duration_rounding";
+
+/// Battery cycles after which the dashboard summary flags a battery (80% of
+/// the 400-cycle life assumed by the overview's battery health)
+const BATTERY_WARNING_CYCLES: i64 = 320;
 
 #[derive(Error, Debug)]
 pub enum DatabaseError {
@@ -26,12 +253,73 @@ pub enum DatabaseError {
 
     #[error("Flight not found: {0}")]
     FlightNotFound(i64),
+
+    #[error("Database is locked by another process: {0}")]
+    Locked(String),
+}
+
+/// DuckDB reports a file lock held by another process as an IO error
+fn is_lock_error(err: &duckdb::Error) -> bool {
+    let message = err.to_string();
+    message.contains("Could not set lock") || message.contains("Conflicting lock")
+}
+
+/// Prefix of the read-only snapshot copies in the temp directory
+const READ_ONLY_SNAPSHOT_PREFIX: &str = "drone-logbook-readonly-";
+
+/// A read-only snapshot copy of the database file, deleted when dropped
+struct SnapshotFile(PathBuf);
+
+impl Drop for SnapshotFile {
+    fn drop(&mut self) {
+        match fs::remove_file(&self.0) {
+            Ok(()) => log::info!("Removed read-only snapshot {:?}", self.0),
+            Err(e) => log::warn!("Failed to remove read-only snapshot {:?}: {}", self.0, e),
+        }
+    }
+}
+
+/// The SQL console's own DuckDB instance (see `query_read_only`)
+struct ConsoleSnapshot {
+    conn: Connection,
+    /// Size and modification time of the database file when it was copied
+    source: (u64, Option<std::time::SystemTime>),
+    /// Declared after `conn`, so the file is removed once it is closed
+    _file: SnapshotFile,
 }
 
 /// Thread-safe database manager
 pub struct Database {
     conn: Mutex<Connection>,
     pub data_dir: PathBuf,
+    /// Folder holding this profile's attachment files
+    pub attachments_dir: PathBuf,
+    /// Set while a background import queue worker runs on this database
+    pub import_worker_active: AtomicBool,
+    /// Running and recently finished imports (see `import_status`)
+    pub import_activity: Mutex<ImportActivity>,
+    /// The database file
+    db_path: PathBuf,
+    /// Last command or request using the database (ms since the epoch), for idle maintenance
+    last_activity_ms: AtomicI64,
+    /// Outcome of the last checkpoint / compaction (see `db_maintenance`)
+    pub maintenance: Mutex<MaintenanceStatus>,
+    /// Why the database was opened read-only (another process holds its lock)
+    read_only_reason: Option<String>,
+    /// Locked-down copy the SQL console queries, opened on first use
+    console: Mutex<Option<ConsoleSnapshot>>,
+    /// The copy `conn` reads in read-only mode. Declared after `conn`, so the
+    /// file is removed once the connection is closed.
+    _snapshot: Option<SnapshotFile>,
+}
+
+/// Whether this process could lock the database file, as reported to the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbLockStatus {
+    pub read_only: bool,
+    /// The lock error reported by DuckDB (names the holding process when known)
+    pub message: Option<String>,
 }
 
 impl Drop for Database {
@@ -54,117 +342,396 @@ impl Database {
     /// {app_data_dir}/
     /// ├── flights.db              # DuckDB database file (default profile)
     /// ├── flights_{profile}.db    # DuckDB database file (named profile)
+    /// ├── attachments/            # Attached documents (named profiles in attachments/{profile}/)
     /// └── keychains/              # Cached decryption keys
     /// ```
     pub fn new(app_data_dir: PathBuf, profile: &str) -> Result<Self, DatabaseError> {
-        // Ensure directory structure exists
-        fs::create_dir_all(&app_data_dir)?;
-        fs::create_dir_all(app_data_dir.join("keychains"))?;
-
-        let db_path = if profile == "default" {
-            app_data_dir.join("flights.db")
-        } else {
-            app_data_dir.join(format!("flights_{}.db", profile))
-        };
-
-        log::info!("Initializing DuckDB at: {:?}", db_path);
-
-        // Open or create the database (with WAL recovery)
-        let conn = Self::open_with_recovery(&db_path)?;
-
-        // Configure DuckDB for optimal performance
-        Self::configure_connection(&conn)?;
+        let (db, db_path) = Self::open(app_data_dir, profile)?;
+        if db.is_read_only() {
+            return Ok(db);
+        }
 
         // Checkpoint WAL to main database file for faster subsequent startups
-        if let Err(e) = conn.execute_batch("CHECKPOINT;") {
+        if let Err(e) = db.conn.lock().unwrap().execute_batch("CHECKPOINT;") {
             log::warn!("WAL checkpoint failed (non-fatal): {}", e);
         }
 
-        let db = Self {
-            conn: Mutex::new(conn),
-            data_dir: app_data_dir,
-        };
+        // Copy the database before a new app version migrates its schema
+        let snapshot = db.snapshot_before_migration(&db_path);
 
         // Initialize schema
         db.init_schema()?;
 
+        if let Err(e) = db.set_setting(SCHEMA_VERSION_KEY, env!("CARGO_PKG_VERSION")) {
+            log::warn!("Failed to record schema version: {}", e);
+        }
+        if let Some((path, previous)) = snapshot {
+            db.audit(
+                "startup",
+                "migration_snapshot",
+                Some(&path.to_string_lossy()),
+                serde_json::json!({ "fromVersion": previous, "toVersion": env!("CARGO_PKG_VERSION") }),
+            );
+        }
+
+        // Remove flights left half-imported by a crash or kill mid-import
+        db.recover_interrupted_imports();
+        db.requeue_interrupted_imports();
+
         // Run one-time startup deduplication for existing data
         db.run_startup_deduplication();
 
         // Backfill flight_customizations for existing user-edited flights
         db.backfill_flight_customizations();
 
+        // Precompute chart tiers for flights that have none, so reads never build them
+        db.build_missing_telemetry_tiers();
+
         // Perform a checkpoint right after startup, as migrations (especially those touching thousands of rows)
         // create large WAL files. This ensures the 100+ MB WAL isn't held in memory until the user
         // closes the app window, which prevents process locking issues.
         log::info!("Starting post-startup WAL checkpoint to clear large migration logs...");
         let checkpoint_start = std::time::Instant::now();
-        if let Err(e) = db.conn.lock().unwrap().execute_batch("CHECKPOINT; VACUUM;") {
+        if let Err(e) = db.vacuum() {
             log::warn!("Post-startup WAL checkpoint & vacuum failed (non-fatal): {} (took {:.1}s)", e, checkpoint_start.elapsed().as_secs_f64());
         } else {
             log::info!("Post-startup WAL checkpoint & vacuum completed successfully in {:.1}s", checkpoint_start.elapsed().as_secs_f64());
+            let mut status = db.maintenance.lock().unwrap();
+            status.last_checkpoint = Some(chrono::Utc::now());
+            status.last_vacuum = status.last_checkpoint;
+            status.last_duration_ms = Some(checkpoint_start.elapsed().as_millis() as u64);
         }
 
         Ok(db)
     }
 
+    /// Open the database file (with WAL recovery, or a read-only snapshot
+    /// when another process holds the lock) without touching the schema
+    fn open(app_data_dir: PathBuf, profile: &str) -> Result<(Self, PathBuf), DatabaseError> {
+        // Ensure directory structure exists
+        fs::create_dir_all(&app_data_dir)?;
+        fs::create_dir_all(app_data_dir.join("keychains"))?;
+        let attachments_dir = attachments_folder(&app_data_dir, profile);
+        fs::create_dir_all(&attachments_dir)?;
+
+        let db_path = if profile == "default" {
+            app_data_dir.join("flights.db")
+        } else {
+            app_data_dir.join(format!("flights_{}.db", profile))
+        };
+
+        log::info!("Initializing DuckDB at: {:?}", db_path);
+
+        // Put back a recovery copy the user chose to restore
+        if let Err(e) = crate::recovery::apply_pending_restore(&db_path) {
+            log::error!("Failed to restore {:?} from its recovery copy: {}", db_path, e);
+        }
+
+        Self::remove_stale_snapshots();
+
+        // Open or create the database (with WAL recovery). When another
+        // process holds the lock, fall back to a read-only snapshot instead.
+        let (conn, read_only_reason, snapshot) = match Self::open_with_recovery(&db_path) {
+            Ok(conn) => (conn, None, None),
+            Err(DatabaseError::Locked(reason)) => {
+                let (conn, snapshot) = Self::open_read_only_snapshot(&db_path, &reason)?;
+                (conn, Some(reason), Some(snapshot))
+            }
+            Err(e) => return Err(e),
+        };
+
+        // Configure DuckDB for optimal performance
+        Self::configure_connection(&conn)?;
+
+        // No extension downloads in offline mode (air-gapped machines)
+        let offline = crate::duckdb_extensions::offline_mode(&config_path_for_profile(&app_data_dir, profile));
+        if let Err(e) = crate::duckdb_extensions::configure(&conn, &app_data_dir, offline.enabled) {
+            log::warn!("Failed to apply DuckDB extension settings: {}", e);
+        }
+
+        let db = Self {
+            conn: Mutex::new(conn),
+            data_dir: app_data_dir,
+            attachments_dir,
+            import_worker_active: AtomicBool::new(false),
+            import_activity: Mutex::new(ImportActivity::default()),
+            db_path: db_path.clone(),
+            last_activity_ms: AtomicI64::new(chrono::Utc::now().timestamp_millis()),
+            maintenance: Mutex::new(MaintenanceStatus::default()),
+            read_only_reason,
+            console: Mutex::new(None),
+            _snapshot: snapshot,
+        };
+        Ok((db, db_path))
+    }
+
+    /// Open the database for safe mode: no migrations, deduplication,
+    /// backfills or vacuum, so a database that crashes the normal startup can
+    /// still be backed up and repaired
+    pub fn open_safe_mode(app_data_dir: PathBuf, profile: &str) -> Result<Self, DatabaseError> {
+        log::warn!("Opening profile '{}' in safe mode (startup maintenance skipped)", profile);
+        Self::open(app_data_dir, profile).map(|(db, _)| db)
+    }
+
+    /// Open the database. If DuckDB can't open it, the file and its WAL are
+    /// copied aside first (see `recovery`), then it is opened without the
+    /// WAL, and only as a last resort recreated empty.
     fn open_with_recovery(db_path: &PathBuf) -> Result<Connection, DatabaseError> {
+        use crate::recovery::{self, RecoveryOutcome};
+
         match Connection::open(db_path) {
             Ok(conn) => Ok(conn),
+            // Another process has the database open: the file is healthy, so the
+            // WAL removal / rebuild below would destroy its data
+            Err(err) if is_lock_error(&err) => {
+                log::warn!("Database {:?} is locked by another process: {}", db_path, err);
+                Err(DatabaseError::Locked(err.to_string()))
+            }
             Err(err) => {
                 log::warn!("DuckDB open failed: {}. Attempting WAL recovery...", err);
 
+                // Nothing is removed unless the copy succeeded
+                let mut report = recovery::preserve(db_path, RecoveryOutcome::WalSetAside, vec![err.to_string()])
+                    .map_err(|e| DatabaseError::Io(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())))?;
+
                 let wal_path = db_path.with_extension("db.wal");
                 if wal_path.exists() {
-                    if let Err(wal_err) = fs::remove_file(&wal_path) {
-                        log::warn!("Failed to remove WAL file {:?}: {}", wal_path, wal_err);
-                    } else {
-                        log::info!("Removed WAL file {:?}", wal_path);
-                    }
+                    fs::remove_file(&wal_path)?;
+                    log::info!("Set WAL file {:?} aside (copy in {})", wal_path, report.backup_dir);
                 }
 
-                match Connection::open(db_path) {
-                    Ok(conn) => Ok(conn),
+                let conn = match Connection::open(db_path) {
+                    Ok(conn) => conn,
                     Err(second_err) => {
-                        log::warn!("WAL recovery failed: {}. Backing up DB and recreating...", second_err);
-
-                        let backup_path = Self::backup_db(db_path)?;
-                        log::warn!("Database backed up to {:?}", backup_path);
-
-                        Connection::open(db_path).map_err(DatabaseError::from)
+                        log::warn!("WAL recovery failed: {}. Recreating the database (copy in {})", second_err, report.backup_dir);
+                        report.outcome = RecoveryOutcome::Recreated;
+                        report.errors.push(second_err.to_string());
+                        fs::remove_file(db_path)?;
+                        Connection::open(db_path)?
                     }
+                };
+                if let Err(e) = recovery::update(db_path, &report) {
+                    log::warn!("Failed to update recovery report {}: {}", report.id, e);
                 }
+                Ok(conn)
+            }
+        }
+    }
+
+    /// Open a copy of the database file read-only. DuckDB cannot open a file
+    /// that another process holds for writing, not even read-only, so the copy
+    /// shows the data as of that process's last checkpoint. A copy taken
+    /// mid-checkpoint may be torn; then this fails with the lock error.
+    fn open_read_only_snapshot(db_path: &PathBuf, lock_reason: &str) -> Result<(Connection, SnapshotFile), DatabaseError> {
+        let file_name = db_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let snapshot = SnapshotFile(std::env::temp_dir().join(format!(
+            "{}{}-{}",
+            READ_ONLY_SNAPSHOT_PREFIX,
+            std::process::id(),
+            file_name
+        )));
+        let size = fs::copy(db_path, &snapshot.0)?;
+        log::warn!("Opening read-only snapshot {:?} of {:?} ({} bytes)", snapshot.0, db_path, size);
+
+        let opened = Config::default()
+            .access_mode(AccessMode::ReadOnly)
+            .and_then(|config| Connection::open_with_flags(&snapshot.0, config))
+            .and_then(|conn| {
+                conn.query_row("SELECT COUNT(*) FROM duckdb_tables()", [], |row| row.get::<_, i64>(0))?;
+                Ok(conn)
+            });
+        match opened {
+            Ok(conn) => Ok((conn, snapshot)),
+            Err(e) => {
+                log::warn!("Read-only snapshot {:?} is unusable: {}", snapshot.0, e);
+                Err(DatabaseError::Locked(lock_reason.to_string()))
+            }
+        }
+    }
+
+    /// Delete read-only snapshots left in the temp directory by processes
+    /// that did not shut down cleanly. Snapshots still open elsewhere are
+    /// kept where the OS refuses to delete open files, and stay readable
+    /// through the open handle otherwise.
+    fn remove_stale_snapshots() {
+        let own_prefix = format!("{}{}-", READ_ONLY_SNAPSHOT_PREFIX, std::process::id());
+        let Ok(entries) = fs::read_dir(std::env::temp_dir()) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with(READ_ONLY_SNAPSHOT_PREFIX) || name.starts_with(&own_prefix) {
+                continue;
+            }
+            match fs::remove_file(entry.path()) {
+                Ok(()) => log::info!("Removed stale read-only snapshot {:?}", entry.path()),
+                Err(e) => log::debug!("Keeping read-only snapshot {:?}: {}", entry.path(), e),
+            }
+        }
+    }
+
+    /// True when another process held the lock and this is a read-only snapshot
+    pub fn is_read_only(&self) -> bool {
+        self.read_only_reason.is_some()
+    }
+
+    /// Lock status for `get_db_lock_status`
+    pub fn lock_status(&self) -> DbLockStatus {
+        DbLockStatus {
+            read_only: self.is_read_only(),
+            message: self.read_only_reason.clone(),
+        }
+    }
+
+    /// Database details for support bundles: engine version, size, row counts
+    pub fn support_info(&self) -> Result<serde_json::Value, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let duckdb_version: String = conn.query_row("SELECT version()", [], |row| row.get(0))?;
+        let database_size: Option<String> = conn
+            .query_row("SELECT database_size FROM pragma_database_size()", [], |row| row.get(0))
+            .ok();
+        let wal_size: Option<String> = conn
+            .query_row("SELECT wal_size FROM pragma_database_size()", [], |row| row.get(0))
+            .ok();
+        let schema_version: Option<String> = conn
+            .query_row("SELECT value FROM settings WHERE key = ?", params![SCHEMA_VERSION_KEY], |row| row.get(0))
+            .optional()?;
+        let mut row_counts = serde_json::Map::new();
+        for table in ["flights", "telemetry", "flight_tags", "flight_messages", "keychains", "attachments"] {
+            if let Ok(count) = conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get::<_, i64>(0)) {
+                row_counts.insert(table.to_string(), serde_json::json!(count));
             }
         }
+        Ok(serde_json::json!({
+            "duckdbVersion": duckdb_version,
+            "databaseSize": database_size,
+            "walSize": wal_size,
+            "schemaAppVersion": schema_version,
+            "readOnly": self.is_read_only(),
+            "readOnlyReason": self.read_only_reason,
+            "rowCounts": row_counts,
+        }))
     }
 
-    /// Backup the database before WAL recovery or rebuilds
-    fn backup_db(db_path: &PathBuf) -> Result<PathBuf, DatabaseError> {
-        if !db_path.exists() {
-            return Ok(db_path.clone());
+    /// When an existing database was last opened by another app version, copy
+    /// the checkpointed file to `snapshots/{file stem}/` in the data directory before
+    /// `init_schema` migrates it (column additions, telemetry table rebuilds).
+    /// Returns the snapshot path and the previous version (None when it predates
+    /// version tracking). The oldest snapshots beyond the last few are removed.
+    fn snapshot_before_migration(&self, db_path: &std::path::Path) -> Option<(PathBuf, Option<String>)> {
+        let previous = {
+            let conn = self.conn.lock().unwrap();
+            let has_flights: i64 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM information_schema.tables WHERE table_name = 'flights'",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap_or(0);
+            if has_flights == 0 {
+                return None; // new database, nothing to protect
+            }
+            conn.query_row("SELECT value FROM settings WHERE key = ?", params![SCHEMA_VERSION_KEY], |row| {
+                row.get::<_, String>(0)
+            })
+            .ok()
+        };
+        if previous.as_deref() == Some(env!("CARGO_PKG_VERSION")) {
+            return None;
         }
 
-        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-        let backup_path = db_path.with_extension(format!("db.bak.{}", timestamp));
-        fs::rename(db_path, &backup_path)?;
+        let stem = db_path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let dir = self.data_dir.join("snapshots").join(&stem);
+        let snapshot = dir.join(format!(
+            "v{}_{}.db",
+            previous.as_deref().unwrap_or("unknown"),
+            chrono::Utc::now().format("%Y%m%d_%H%M%S")
+        ));
+        let result = fs::create_dir_all(&dir).and_then(|_| fs::copy(db_path, &snapshot));
+        if let Err(e) = result {
+            log::warn!("Failed to write pre-migration snapshot {:?}: {}", snapshot, e);
+            return None;
+        }
+        log::info!(
+            "Database last opened by version {}; snapshot written to {:?} before schema migration",
+            previous.as_deref().unwrap_or("unknown"),
+            snapshot
+        );
 
-        let wal_path = db_path.with_extension("db.wal");
-        if wal_path.exists() {
-            let wal_backup = wal_path.with_extension(format!("db.wal.bak.{}", timestamp));
-            let _ = fs::rename(&wal_path, wal_backup);
+        // Names sort by version first, so order by modification time
+        let mut existing: Vec<(std::time::SystemTime, PathBuf)> = fs::read_dir(&dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+                    .collect()
+            })
+            .unwrap_or_default();
+        existing.sort();
+        let excess = existing.len().saturating_sub(MIGRATION_SNAPSHOTS_KEPT);
+        for (_, old) in existing.into_iter().take(excess) {
+            let _ = fs::remove_file(old);
         }
 
-        Ok(backup_path)
+        Some((snapshot, previous))
     }
 
     /// Explicitly forces a WAL checkpoint. Useful for flushing the WAL before shutdown.
-    #[cfg(feature = "tauri-app")]
+    #[tracing::instrument(skip_all)]
     pub fn checkpoint(&self) -> Result<(), DatabaseError> {
         let conn = self.conn.lock().unwrap();
         conn.execute_batch("CHECKPOINT;")?;
         Ok(())
     }
 
+    /// Checkpoint the WAL and compact the database file
+    #[tracing::instrument(skip_all)]
+    pub fn vacuum(&self) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch("CHECKPOINT; VACUUM;")?;
+        Ok(())
+    }
+
+    /// The database file
+    pub fn path(&self) -> &Path {
+        &self.db_path
+    }
+
+    /// The write-ahead log next to the database file
+    pub fn wal_path(&self) -> PathBuf {
+        self.db_path.with_extension("db.wal")
+    }
+
+    /// Switch DuckDB's offline mode (no extension downloads) on or off
+    pub fn set_extensions_offline(&self, offline: bool) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        crate::duckdb_extensions::configure(&conn, &self.data_dir, offline)?;
+        Ok(())
+    }
+
+    /// Extensions DuckDB has loaded or installed
+    pub fn extensions(&self) -> Result<Vec<crate::duckdb_extensions::ExtensionInfo>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        Ok(crate::duckdb_extensions::list(&conn)?)
+    }
+
+    /// Number of flights in the logbook
+    pub fn flight_count(&self) -> Result<i64, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        Ok(conn.query_row("SELECT COUNT(*) FROM flights", [], |row| row.get(0))?)
+    }
+
+    /// Record use of the database, postponing idle maintenance
+    pub fn touch(&self) {
+        self.last_activity_ms.store(chrono::Utc::now().timestamp_millis(), Ordering::Relaxed);
+    }
+
+    /// Time since the database was last used
+    pub fn idle_for(&self) -> std::time::Duration {
+        let idle_ms = chrono::Utc::now().timestamp_millis() - self.last_activity_ms.load(Ordering::Relaxed);
+        std::time::Duration::from_millis(idle_ms.max(0) as u64)
+    }
+
     /// Configure DuckDB connection for optimal analytical performance
     fn configure_connection(conn: &Connection) -> DuckResult<()> {
         // Memory settings for better performance with large datasets
@@ -329,6 +896,18 @@ impl Database {
                 PRIMARY KEY (serial, equipment_type)
             );
 
+            -- ============================================================
+            -- AIRFRAME_SEEDS TABLE: Flights and time an aircraft flew
+            -- before this logbook, added to its airframe hours
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS airframe_seeds (
+                serial              VARCHAR PRIMARY KEY, -- aircraft serial number (upper case)
+                prior_duration_secs DOUBLE NOT NULL,
+                prior_flights       BIGINT NOT NULL DEFAULT 0,
+                notes               VARCHAR,
+                updated_at          TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
             -- ============================================================
             -- FLIGHT_MESSAGES TABLE: App messages (tips/warnings/cautions) per flight
             -- ============================================================
@@ -363,51 +942,376 @@ impl Database {
                 file_hash       VARCHAR PRIMARY KEY,
                 created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
             );
-            "#,
-        )?;
 
-        // Run selective migrations only for missing columns
-        Self::migrate_flights_table(&conn)?;
-        Self::migrate_telemetry_table(&conn)?;
-        Self::migrate_flight_tags_table(&conn)?;
-        Self::migrate_flight_messages_table(&conn)?;
+            -- ============================================================
+            -- PUSH_SUBSCRIPTIONS TABLE: Browsers receiving Web Push notifications
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS push_subscriptions (
+                endpoint        VARCHAR PRIMARY KEY,
+                p256dh          VARCHAR NOT NULL,
+                auth            VARCHAR NOT NULL,
+                owner           VARCHAR,                 -- user who subscribed (web login)
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
 
-        // Run type optimization migration (DOUBLE -> FLOAT for non-critical metrics)
-        // Must run before column order check since it recreates the table
-        Self::migrate_telemetry_types(&conn)?;
+            -- ============================================================
+            -- FLIGHT_IMPORT_REPORTS TABLE: Validation summary recorded at import
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_import_reports (
+                flight_id       BIGINT PRIMARY KEY,
+                format          VARCHAR NOT NULL,        -- parser that produced the flight
+                record_version  INTEGER,                 -- DJI FlightRecord version
+                decryption      VARCHAR,                 -- 'none', 'keychain', 'keychain (DJIFly fallback)'
+                quirk           VARCHAR,                 -- model quirk id, if one was applied
+                speed_derived   BOOLEAN DEFAULT FALSE,   -- logged speed replaced by speed from positions
+                gps_outliers    INTEGER DEFAULT 0,       -- GPS fixes removed as glitches
+                point_count     BIGINT NOT NULL,
+                field_coverage  VARCHAR NOT NULL,        -- JSON array of per-field coverage
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
 
-        Self::ensure_telemetry_column_order(&conn)?;
+            -- ============================================================
+            -- FLIGHT_WEATHER TABLE: Historical weather at home point and start hour
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_weather (
+                flight_id       BIGINT PRIMARY KEY,
+                temperature_c   DOUBLE,
+                wind_speed_kmh  DOUBLE,                  -- 10 m wind
+                wind_gusts_kmh  DOUBLE,
+                fetched_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
 
-        log::info!("Database schema initialized successfully");
-        Ok(())
-    }
+            -- ============================================================
+            -- IMPORT_PROFILES TABLE: Per-stage import timings (diagnostics only)
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS import_profiles (
+                flight_id       BIGINT PRIMARY KEY,
+                file_name       VARCHAR NOT NULL,
+                format          VARCHAR NOT NULL,
+                file_size_bytes BIGINT NOT NULL,
+                point_count     BIGINT NOT NULL,
+                read_ms         DOUBLE NOT NULL,
+                decrypt_ms      DOUBLE,                  -- NULL for unencrypted logs
+                parse_ms        DOUBLE NOT NULL,
+                insert_ms       DOUBLE NOT NULL,
+                post_process_ms DOUBLE NOT NULL,
+                total_ms        DOUBLE NOT NULL,
+                recorded_at     TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
 
-    /// Get existing column names for a table (single query)
-    fn get_table_columns(conn: &Connection, table_name: &str) -> Result<HashSet<String>, DatabaseError> {
-        let mut stmt = conn.prepare(&format!("PRAGMA table_info('{}')", table_name))?;
-        let columns: HashSet<String> = stmt
-            .query_map([], |row| row.get::<_, String>(1))?
-            .collect::<Result<HashSet<_>, _>>()?;
-        Ok(columns)
-    }
+            -- ============================================================
+            -- FLIGHT_EXCEEDANCES TABLE: Flights over the personal altitude/range limits
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_exceedances (
+                flight_id       BIGINT NOT NULL,
+                kind            VARCHAR NOT NULL,        -- 'altitude' or 'distance'
+                limit_m         DOUBLE NOT NULL,
+                actual_m        DOUBLE NOT NULL,
+                PRIMARY KEY (flight_id, kind)
+            );
 
-    /// Migrate flights table - only add missing columns
-    fn migrate_flights_table(conn: &Connection) -> Result<(), DatabaseError> {
-        let columns = Self::get_table_columns(conn, "flights")?;
-        
-        let migrations: &[(&str, &str)] = &[
-            ("display_name", "ALTER TABLE flights ADD COLUMN display_name VARCHAR"),
-            ("aircraft_name", "ALTER TABLE flights ADD COLUMN aircraft_name VARCHAR"),
-            ("battery_serial", "ALTER TABLE flights ADD COLUMN battery_serial VARCHAR"),
-            ("photo_count", "ALTER TABLE flights ADD COLUMN photo_count INTEGER"),
-            ("video_count", "ALTER TABLE flights ADD COLUMN video_count INTEGER"),
-            ("color", "ALTER TABLE flights ADD COLUMN color VARCHAR DEFAULT '#7dd3fc'"),
-            ("cycle_count", "ALTER TABLE flights ADD COLUMN cycle_count INTEGER"),
-            ("rc_serial", "ALTER TABLE flights ADD COLUMN rc_serial VARCHAR"),
+            -- ============================================================
+            -- FLIGHT_RTH_EVENTS TABLE: Return-to-home activations and outcomes
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_rth_events (
+                flight_id                       BIGINT NOT NULL,
+                trigger_ms                      BIGINT NOT NULL,
+                trigger_mode                    VARCHAR NOT NULL,
+                trigger_distance_m              DOUBLE,
+                trigger_height_m                DOUBLE,
+                trigger_battery_percent         INTEGER,
+                time_to_land_secs               DOUBLE,
+                landing_battery_percent         INTEGER,
+                landing_distance_from_home_m    DOUBLE,
+                landed_at_home                  BOOLEAN NOT NULL,
+                cancelled                       BOOLEAN NOT NULL,
+                PRIMARY KEY (flight_id, trigger_ms)
+            );
+
+            -- ============================================================
+            -- ATTACHMENTS TABLE: Documents stored under the attachments folder
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS attachments (
+                id              VARCHAR PRIMARY KEY,
+                flight_id       BIGINT,                  -- NULL for logbook-wide documents
+                file_name       VARCHAR NOT NULL,        -- original file name
+                stored_name     VARCHAR NOT NULL,        -- file name inside the attachments folder
+                content_type    VARCHAR NOT NULL,
+                size_bytes      BIGINT NOT NULL,
+                description     VARCHAR,
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- ============================================================
+            -- CHECKLIST TABLES: Pre/post-flight templates and completed runs
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS checklist_templates (
+                id              VARCHAR PRIMARY KEY,
+                name            VARCHAR NOT NULL,
+                phase           VARCHAR NOT NULL,        -- 'preflight' or 'postflight'
+                items           VARCHAR NOT NULL,        -- JSON array of item labels
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE TABLE IF NOT EXISTS checklist_records (
+                id              VARCHAR PRIMARY KEY,
+                template_id     VARCHAR,
+                template_name   VARCHAR NOT NULL,
+                phase           VARCHAR NOT NULL,
+                flight_id       BIGINT,                  -- NULL when recorded against a session only
+                session         VARCHAR,                 -- free-form session label (e.g. field day)
+                items           VARCHAR NOT NULL,        -- JSON array of { item, checked, checkedAt }
+                started_at      VARCHAR,                 -- RFC 3339
+                completed_at    VARCHAR,                 -- RFC 3339
+                notes           VARCHAR
+            );
+
+            -- ============================================================
+            -- FLIGHT_PREVIEWS TABLE: Cached list previews (rebuilt on reprocess)
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_previews (
+                flight_id           BIGINT PRIMARY KEY,
+                track               VARCHAR NOT NULL,    -- JSON array of [lon, lat]
+                altitude_sparkline  VARCHAR NOT NULL,    -- JSON array of numbers
+                battery_sparkline   VARCHAR NOT NULL,    -- JSON array of numbers
+                thumbnail_svg       VARCHAR,
+                updated_at          TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- ============================================================
+            -- TELEMETRY_TIERS TABLE: Precomputed 1 Hz / 0.2 Hz downsampled
+            -- telemetry (derived from telemetry; rebuilt when missing)
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS telemetry_tiers (
+                tier_ms         BIGINT NOT NULL,         -- Bucket size: 1000 or 5000
+                flight_id       BIGINT NOT NULL,
+                timestamp_ms    BIGINT NOT NULL,         -- Bucket start
+                latitude        DOUBLE,
+                longitude       DOUBLE,
+                altitude        DOUBLE,
+                height          DOUBLE,
+                vps_height      DOUBLE,
+                speed           DOUBLE,
+                velocity_x      DOUBLE,
+                velocity_y      DOUBLE,
+                velocity_z      DOUBLE,
+                battery_percent INTEGER,
+                battery_voltage DOUBLE,
+                battery_temp    DOUBLE,
+                cell_voltages   VARCHAR,
+                pitch           DOUBLE,
+                roll            DOUBLE,
+                yaw             DOUBLE,
+                gimbal_pitch    DOUBLE,
+                gimbal_roll     DOUBLE,
+                gimbal_yaw      DOUBLE,
+                satellites      INTEGER,
+                flight_mode     VARCHAR,
+                rc_signal       INTEGER,
+                rc_uplink       INTEGER,
+                rc_downlink     INTEGER,
+                rc_aileron      DOUBLE,
+                rc_elevator     DOUBLE,
+                rc_throttle     DOUBLE,
+                rc_rudder       DOUBLE,
+                is_photo        BOOLEAN,
+                is_video        BOOLEAN,
+                battery_full_capacity     DOUBLE,
+                battery_remained_capacity DOUBLE,
+                gps_signal      INTEGER,
+                PRIMARY KEY (flight_id, tier_ms, timestamp_ms)
+            );
+
+            -- ============================================================
+            -- AUDIT_LOG TABLE: Data-modifying actions (imports, deletes, restores...)
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS audit_log (
+                id              VARCHAR PRIMARY KEY,
+                timestamp       TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+                actor           VARCHAR NOT NULL,        -- 'desktop', 'sync' or 'web (<client address>)'
+                action          VARCHAR NOT NULL,        -- e.g. 'import', 'delete_flight', 'settings_change'
+                target          VARCHAR,                 -- flight ID, file name, setting key...
+                details         VARCHAR                  -- JSON object
+            );
+
+            -- ============================================================
+            -- REQUIREMENTS TABLE: User-defined currency / recency rules
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS requirements (
+                id              VARCHAR PRIMARY KEY,
+                name            VARCHAR NOT NULL,
+                metric          VARCHAR NOT NULL,        -- 'takeoffs', 'night_flights', 'flight_hours'
+                min_value       DOUBLE NOT NULL,
+                window_days     INTEGER NOT NULL,
+                drone_model     VARCHAR,
+                created_at      TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- ============================================================
+            -- FLIGHT_PLANS TABLE: Planned missions attached to flights
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_plans (
+                flight_id       BIGINT PRIMARY KEY,
+                name            VARCHAR NOT NULL,        -- mission file name
+                source_format   VARCHAR NOT NULL,        -- 'litchi_csv', 'dji_wpml' or 'kml'
+                waypoints       VARCHAR NOT NULL,        -- JSON array of { lat, lon, altitudeM, speedMs }
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- ============================================================
+            -- JOB TABLES: Jobs / contracts and the flights assigned to them
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS jobs (
+                id              VARCHAR PRIMARY KEY,
+                name            VARCHAR NOT NULL,
+                client          VARCHAR,
+                site            VARCHAR,
+                start_date      VARCHAR,                 -- YYYY-MM-DD
+                end_date        VARCHAR,                 -- YYYY-MM-DD
+                notes           VARCHAR,
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE TABLE IF NOT EXISTS job_flights (
+                flight_id       BIGINT PRIMARY KEY,      -- a flight belongs to at most one job
+                job_id          VARCHAR NOT NULL
+            );
+
+            -- ============================================================
+            -- MAINTENANCE_LOG TABLE: Maintenance performed on aircraft and batteries
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS maintenance_log (
+                id              VARCHAR PRIMARY KEY,
+                equipment_type  VARCHAR NOT NULL,        -- 'aircraft' or 'battery'
+                serial          VARCHAR NOT NULL,
+                performed_at    VARCHAR NOT NULL,        -- YYYY-MM-DD or RFC 3339
+                notes           VARCHAR,
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- ============================================================
+            -- IMPORT_JOURNAL TABLE: Imports in progress (a row left behind
+            -- at startup means the app was killed mid-import)
+            -- ============================================================
+            -- ============================================================
+            -- IMPORT_QUEUE TABLE: Files waiting for the background importer
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS import_queue (
+                id              VARCHAR PRIMARY KEY,
+                file_name       VARCHAR NOT NULL,
+                file_path       VARCHAR NOT NULL,
+                status          VARCHAR NOT NULL,        -- 'queued', 'running', 'done' or 'failed'
+                message         VARCHAR,
+                flight_id       BIGINT,
+                created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+                started_at      TIMESTAMP WITH TIME ZONE,
+                finished_at     TIMESTAMP WITH TIME ZONE
+            );
+
+            CREATE TABLE IF NOT EXISTS import_journal (
+                flight_id       BIGINT PRIMARY KEY,
+                file_name       VARCHAR NOT NULL,
+                file_hash       VARCHAR,
+                started_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- ============================================================
+            -- INTEGRITY_CHAIN TABLE: Hash chain over imports, edits and deletions
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS integrity_chain (
+                seq             BIGINT PRIMARY KEY,
+                recorded_at     VARCHAR NOT NULL,        -- RFC 3339, hashed as stored
+                flight_id       BIGINT NOT NULL,
+                event           VARCHAR NOT NULL,        -- 'import', 'update', 'delete', 'restore' or 'baseline'
+                file_hash       VARCHAR,                 -- SHA256 of the raw log
+                record_hash     VARCHAR,                 -- SHA256 of the flight record (NULL for 'delete')
+                prev_hash       VARCHAR NOT NULL,
+                entry_hash      VARCHAR NOT NULL
+            );
+
+            -- ============================================================
+            -- FLIGHT_TOMBSTONES TABLE: Deleted flights, for delta sync
+            -- ============================================================
+            CREATE TABLE IF NOT EXISTS flight_tombstones (
+                flight_id       BIGINT PRIMARY KEY,
+                deleted_at      TIMESTAMP WITH TIME ZONE NOT NULL
+            );
+            "#,
+        )?;
+
+        // Run selective migrations only for missing columns
+        Self::migrate_flights_table(&conn)?;
+        Self::migrate_telemetry_table(&conn)?;
+        Self::migrate_flight_tags_table(&conn)?;
+        Self::migrate_flight_messages_table(&conn)?;
+        Self::migrate_import_queue_table(&conn)?;
+        Self::migrate_import_reports_table(&conn)?;
+        Self::migrate_telemetry_tiers_table(&conn)?;
+
+        // Run type optimization migration (DOUBLE -> FLOAT for non-critical metrics)
+        // Must run before column order check since it recreates the table
+        Self::migrate_telemetry_types(&conn)?;
+
+        Self::ensure_telemetry_column_order(&conn)?;
+
+        log::info!("Database schema initialized successfully");
+        Ok(())
+    }
+
+    /// Get existing column names for a table (single query)
+    fn get_table_columns(conn: &Connection, table_name: &str) -> Result<HashSet<String>, DatabaseError> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info('{}')", table_name))?;
+        let columns: HashSet<String> = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<HashSet<_>, _>>()?;
+        Ok(columns)
+    }
+
+    /// Migrate flights table - only add missing columns
+    fn migrate_flights_table(conn: &Connection) -> Result<(), DatabaseError> {
+        let columns = Self::get_table_columns(conn, "flights")?;
+        
+        let migrations: &[(&str, &str)] = &[
+            ("display_name", "ALTER TABLE flights ADD COLUMN display_name VARCHAR"),
+            ("aircraft_name", "ALTER TABLE flights ADD COLUMN aircraft_name VARCHAR"),
+            ("battery_serial", "ALTER TABLE flights ADD COLUMN battery_serial VARCHAR"),
+            ("photo_count", "ALTER TABLE flights ADD COLUMN photo_count INTEGER"),
+            ("video_count", "ALTER TABLE flights ADD COLUMN video_count INTEGER"),
+            ("color", "ALTER TABLE flights ADD COLUMN color VARCHAR DEFAULT '#7dd3fc'"),
+            ("cycle_count", "ALTER TABLE flights ADD COLUMN cycle_count INTEGER"),
+            ("rc_serial", "ALTER TABLE flights ADD COLUMN rc_serial VARCHAR"),
             ("battery_life", "ALTER TABLE flights ADD COLUMN battery_life INTEGER"),
+            ("pilot", "ALTER TABLE flights ADD COLUMN pilot VARCHAR"),
+            // Takeoff location name
+            ("location_name", "ALTER TABLE flights ADD COLUMN location_name VARCHAR"),
+            // Correction applied to the parsed start time by metadata edits
+            ("time_offset_secs", "ALTER TABLE flights ADD COLUMN time_offset_secs DOUBLE"),
+            // JSON of the parsed values replaced by metadata edits
+            ("original_metadata", "ALTER TABLE flights ADD COLUMN original_metadata VARCHAR"),
+            ("is_manual", "ALTER TABLE flights ADD COLUMN is_manual BOOLEAN DEFAULT FALSE"),
+            // Web account that imported the flight (multi-user mode)
+            ("owner", "ALTER TABLE flights ADD COLUMN owner VARCHAR"),
+            // Takeoffs/landings detected in the telemetry (NULL = not counted)
+            ("takeoff_count", "ALTER TABLE flights ADD COLUMN takeoff_count INTEGER"),
+            ("landing_count", "ALTER TABLE flights ADD COLUMN landing_count INTEGER"),
+            // Airborne seconds per phase (see `flight_phases`; NULL = not computed)
+            ("hover_secs", "ALTER TABLE flights ADD COLUMN hover_secs DOUBLE"),
+            ("transit_secs", "ALTER TABLE flights ADD COLUMN transit_secs DOUBLE"),
+            ("ascend_secs", "ALTER TABLE flights ADD COLUMN ascend_secs DOUBLE"),
+            ("descend_secs", "ALTER TABLE flights ADD COLUMN descend_secs DOUBLE"),
+            // 0-100, see `smoothness` (NULL = not scored)
+            ("smoothness_score", "ALTER TABLE flights ADD COLUMN smoothness_score DOUBLE"),
+            // Altitude data in the log and the highest GPS altitude (see `altitude`)
+            ("altitude_source", "ALTER TABLE flights ADD COLUMN altitude_source VARCHAR"),
+            ("max_altitude_msl", "ALTER TABLE flights ADD COLUMN max_altitude_msl DOUBLE"),
+            // Last import or change of the flight, its tags or exceedances (delta sync)
+            ("updated_at", "ALTER TABLE flights ADD COLUMN updated_at TIMESTAMP WITH TIME ZONE"),
         ];
 
         let need_backfill = !columns.contains("photo_count");
+        let need_manual_backfill = !columns.contains("is_manual");
+        let need_altitude_backfill = !columns.contains("altitude_source");
+        let need_updated_backfill = !columns.contains("updated_at");
 
         for (col_name, sql) in migrations {
             if !columns.contains(*col_name) {
@@ -416,6 +1320,14 @@ impl Database {
             }
         }
 
+        if need_manual_backfill {
+            conn.execute_batch(MARK_LEGACY_MANUAL_FLIGHTS)?;
+        }
+
+        if need_updated_backfill {
+            conn.execute_batch("UPDATE flights SET updated_at = COALESCE(imported_at, CURRENT_TIMESTAMP)")?;
+        }
+
         // Backfill photo/video counts from telemetry for existing flights
         if need_backfill {
             log::info!("Backfilling photo_count and video_count from telemetry data...");
@@ -441,6 +1353,30 @@ impl Database {
             }
         }
 
+        if need_altitude_backfill {
+            log::info!("Backfilling altitude sources from telemetry data...");
+            let backfill_sql = r#"
+                UPDATE flights SET
+                    altitude_source = CASE
+                        WHEN t.heights > 0 AND t.msl_count > 0 THEN 'barometric+gps_msl'
+                        WHEN t.heights > 0 THEN 'barometric'
+                        WHEN t.msl_count > 0 THEN 'gps_msl'
+                        ELSE 'none'
+                    END,
+                    max_altitude_msl = t.max_msl
+                FROM (
+                    SELECT flight_id, COUNT(height) AS heights, COUNT(altitude_abs) AS msl_count,
+                           MAX(altitude_abs)::DOUBLE AS max_msl
+                    FROM telemetry GROUP BY flight_id
+                ) t
+                WHERE flights.id = t.flight_id AND NOT COALESCE(flights.is_manual, FALSE)
+            "#;
+            match conn.execute_batch(backfill_sql) {
+                Ok(()) => log::info!("Backfilled altitude sources successfully"),
+                Err(e) => log::warn!("Failed to backfill altitude sources: {}", e),
+            }
+        }
+
         Ok(())
     }
 
@@ -496,6 +1432,46 @@ impl Database {
         Ok(())
     }
 
+    /// Migrate import_queue table - add the submitting account
+    fn migrate_import_queue_table(conn: &Connection) -> Result<(), DatabaseError> {
+        let columns = Self::get_table_columns(conn, "import_queue")?;
+        if !columns.contains("owner") {
+            log::info!("Migrating import_queue table: adding owner column");
+            conn.execute_batch("ALTER TABLE import_queue ADD COLUMN owner VARCHAR;")?;
+        }
+        Ok(())
+    }
+
+    /// Migrate flight_import_reports table - add the telemetry cleanup results
+    fn migrate_import_reports_table(conn: &Connection) -> Result<(), DatabaseError> {
+        let columns = Self::get_table_columns(conn, "flight_import_reports")?;
+        let migrations: &[(&str, &str)] = &[
+            ("speed_derived", "ALTER TABLE flight_import_reports ADD COLUMN speed_derived BOOLEAN DEFAULT FALSE"),
+            ("gps_outliers", "ALTER TABLE flight_import_reports ADD COLUMN gps_outliers INTEGER DEFAULT 0"),
+        ];
+        for (col_name, sql) in migrations {
+            if !columns.contains(*col_name) {
+                log::info!("Migrating flight_import_reports table: adding {} column", col_name);
+                conn.execute_batch(sql)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Migrate telemetry_tiers table - add gps_signal. The tiers stored
+    /// without it are dropped; `build_missing_telemetry_tiers` rebuilds them.
+    fn migrate_telemetry_tiers_table(conn: &Connection) -> Result<(), DatabaseError> {
+        let columns = Self::get_table_columns(conn, "telemetry_tiers")?;
+        if !columns.contains("gps_signal") {
+            log::info!("Migrating telemetry_tiers table: adding gps_signal column");
+            conn.execute_batch(
+                "DELETE FROM telemetry_tiers;
+                 ALTER TABLE telemetry_tiers ADD COLUMN gps_signal INTEGER;",
+            )?;
+        }
+        Ok(())
+    }
+
     /// Migrate flight_messages table — expand PK to include message text.
     /// Old PK was (flight_id, timestamp_ms, message_type) which silently dropped
     /// multiple messages at the same timestamp+type. State-change tracking can
@@ -891,19 +1867,30 @@ impl Database {
         timestamp % 1_000_000_000_000
     }
 
-    /// Insert flight metadata and return the flight ID
+    /// Insert flight metadata and return the flight ID.
+    ///
+    /// The flight is recorded in the import journal until [`Self::finish_import`]
+    /// is called, so a flight whose telemetry, tags or reports were only partly
+    /// written when the app died is removed at the next start.
+    #[tracing::instrument(skip_all, fields(flight_id = flight.id))]
     pub fn insert_flight(&self, flight: &FlightMetadata) -> Result<i64, DatabaseError> {
         let conn = self.conn.lock().unwrap();
 
         conn.execute(
+            "INSERT INTO import_journal (flight_id, file_name, file_hash) VALUES (?, ?, ?)
+             ON CONFLICT (flight_id) DO NOTHING",
+            params![flight.id, flight.file_name, flight.file_hash],
+        )?;
+
+        if let Err(e) = conn.execute(
             r#"
             INSERT INTO flights (
                 id, file_name, display_name, file_hash, drone_model, drone_serial,
                 aircraft_name, battery_serial, cycle_count,
                 start_time, end_time, duration_secs, total_distance,
                 max_altitude, max_speed, home_lat, home_lon, point_count,
-                photo_count, video_count, rc_serial, battery_life
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                photo_count, video_count, rc_serial, battery_life, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
             "#,
             params![
                 flight.id,
@@ -929,22 +1916,97 @@ impl Database {
                 flight.rc_serial,
                 flight.battery_life,
             ],
-        )?;
+        ) {
+            let _ = conn.execute("DELETE FROM import_journal WHERE flight_id = ?", params![flight.id]);
+            return Err(e.into());
+        }
 
         log::info!("Inserted flight with ID: {}", flight.id);
         Ok(flight.id)
     }
 
+    /// Insert a flight entered by hand (no log file), flagged `is_manual`
+    pub fn insert_manual_flight(&self, flight: &FlightMetadata) -> Result<i64, DatabaseError> {
+        let id = self.insert_flight(flight)?;
+        let conn = self.conn.lock().unwrap();
+        conn.execute("UPDATE flights SET is_manual = TRUE WHERE id = ?", params![id])?;
+        Ok(id)
+    }
+
+    /// Mark an import as complete (clears its import journal entry and
+    /// records the flight in the integrity chain)
+    pub fn finish_import(&self, flight_id: i64) {
+        let conn = self.conn.lock().unwrap();
+        if let Err(e) = conn.execute("DELETE FROM import_journal WHERE flight_id = ?", params![flight_id]) {
+            log::warn!("Failed to clear import journal for flight {}: {}", flight_id, e);
+        }
+        Self::record_integrity(&conn, flight_id, "import");
+    }
+
+    /// Delete flights whose import never finished (the app was closed or killed
+    /// mid-import). Each one is recorded in the audit log (action
+    /// `recover_import`); sync folders re-import the file on their next run.
+    /// Returns the number of flights removed.
+    pub(crate) fn recover_interrupted_imports(&self) -> usize {
+        let entries: Vec<(i64, String)> = {
+            let conn = self.conn.lock().unwrap();
+            let result = conn
+                .prepare("SELECT flight_id, file_name FROM import_journal ORDER BY started_at")
+                .and_then(|mut stmt| {
+                    stmt.query_map(params![], |row| Ok((row.get(0)?, row.get(1)?)))?
+                        .collect::<Result<Vec<_>, _>>()
+                });
+            match result {
+                Ok(entries) => entries,
+                Err(e) => {
+                    log::warn!("Failed to read import journal: {}", e);
+                    return 0;
+                }
+            }
+        };
+
+        let mut removed = 0;
+        for (flight_id, file_name) in entries {
+            log::warn!("Import of '{}' (flight {}) was interrupted; removing partial data", file_name, flight_id);
+            match self.delete_flight(flight_id) {
+                Ok(()) => {
+                    // Not `finish_import`: that would log an import in the integrity chain
+                    {
+                        let conn = self.conn.lock().unwrap();
+                        if let Err(e) = conn.execute("DELETE FROM import_journal WHERE flight_id = ?", params![flight_id]) {
+                            log::warn!("Failed to clear import journal for flight {}: {}", flight_id, e);
+                        }
+                    }
+                    self.audit(
+                        "startup",
+                        "recover_import",
+                        Some(&flight_id.to_string()),
+                        serde_json::json!({ "file": file_name }),
+                    );
+                    removed += 1;
+                }
+                Err(e) => log::error!("Failed to remove partially imported flight {}: {}", flight_id, e),
+            }
+        }
+        removed
+    }
+
     /// Bulk insert telemetry data using DuckDB's Appender for maximum performance
     ///
     /// This is significantly faster than individual INSERT statements for large datasets.
+    #[tracing::instrument(skip_all, fields(flight_id = flight_id, points = points.len()))]
     pub fn bulk_insert_telemetry(
         &self,
         flight_id: i64,
         points: &[TelemetryPoint],
     ) -> Result<usize, DatabaseError> {
         let conn = self.conn.lock().unwrap();
+        Self::insert_telemetry(&conn, flight_id, points)
+    }
 
+    /// Append telemetry and refresh the flight's telemetry-derived columns
+    /// (see [`Self::bulk_insert_telemetry`])
+    fn insert_telemetry(conn: &Connection, flight_id: i64, points: &[TelemetryPoint]) -> Result<usize, DatabaseError> {
         // Use DuckDB Appender for high-performance bulk inserts
         let mut appender = conn.appender("telemetry")?;
 
@@ -1011,6 +2073,7 @@ impl Database {
         }
 
         appender.flush()?;
+        drop(appender);
 
         log::info!(
             "Bulk inserted {} telemetry points for flight {} ({} skipped)",
@@ -1018,78 +2081,363 @@ impl Database {
             flight_id,
             skipped
         );
-        Ok(inserted)
-    }
-
-    /// Get all flights metadata (for the flight list sidebar)
-    pub fn get_all_flights(&self) -> Result<Vec<Flight>, DatabaseError> {
-        let start = std::time::Instant::now();
-        let conn = self.conn.lock().unwrap();
 
-        let mut stmt = conn.prepare(
-            r#"
-            SELECT 
-                id, file_name, COALESCE(display_name, file_name) AS display_name,
-                file_hash,
-                drone_model, drone_serial, aircraft_name, battery_serial,
-                CAST(start_time AS VARCHAR) AS start_time,
-                duration_secs, total_distance,
-                max_altitude, max_speed, home_lat, home_lon, point_count,
-                photo_count, video_count, notes, COALESCE(color, '#7dd3fc') AS color,
-                cycle_count, rc_serial, battery_life
-            FROM flights
-            ORDER BY start_time DESC
-            "#,
+        // Manual entries keep the implicit single cycle: their GPX tracks
+        // carry elevation above sea level, not height above takeoff
+        if let Some((takeoffs, landings)) = crate::parser::count_takeoffs_landings(points) {
+            conn.execute(
+                "UPDATE flights SET takeoff_count = ?, landing_count = ?
+                 WHERE id = ? AND NOT COALESCE(is_manual, FALSE)",
+                params![takeoffs, landings, flight_id],
+            )?;
+        }
+        if let Some(phases) = crate::flight_phases::segment(points) {
+            conn.execute(
+                "UPDATE flights SET hover_secs = ?, transit_secs = ?, ascend_secs = ?, descend_secs = ?
+                 WHERE id = ? AND NOT COALESCE(is_manual, FALSE)",
+                params![phases.hover_secs, phases.transit_secs, phases.ascend_secs, phases.descend_secs, flight_id],
+            )?;
+        }
+        if let Some(smoothness) = crate::smoothness::score(points) {
+            conn.execute(
+                "UPDATE flights SET smoothness_score = ? WHERE id = ? AND NOT COALESCE(is_manual, FALSE)",
+                params![smoothness.score, flight_id],
+            )?;
+        }
+        let altitude = crate::altitude::profile(points);
+        conn.execute(
+            "UPDATE flights SET altitude_source = ?, max_altitude_msl = ? WHERE id = ? AND NOT COALESCE(is_manual, FALSE)",
+            params![altitude.source.as_str(), altitude.max_altitude_msl, flight_id],
         )?;
 
-        let mut flights: Vec<Flight> = stmt
-            .query_map([], |row| {
-                Ok(Flight {
-                    id: row.get(0)?,
-                    file_name: row.get(1)?,
-                    display_name: row.get(2)?,
-                    file_hash: row.get(3)?,
-                    drone_model: row.get(4)?,
-                    drone_serial: row.get(5)?,
-                    aircraft_name: row.get(6)?,
-                    battery_serial: row.get(7)?,
-                    cycle_count: row.get(20)?,
-                    start_time: row.get(8)?,
-                    duration_secs: row.get(9)?,
-                    total_distance: row.get(10)?,
-                    max_altitude: row.get(11)?,
-                    max_speed: row.get(12)?,
-                    home_lat: row.get(13)?,
-                    home_lon: row.get(14)?,
-                    point_count: row.get(15)?,
-                    photo_count: row.get(16)?,
-                    video_count: row.get(17)?,
-                    tags: Vec::new(),
-                    notes: row.get(18)?,
-                    color: row.get(19)?,
-                    rc_serial: row.get(21)?,
-                    battery_life: row.get(22)?,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
-
-        // Load all tags and attach to flights
-        // Use a separate query to avoid breaking if flight_tags table doesn't exist yet
-        let tag_map = self.get_all_flight_tags_with_conn(&conn);
-        if let Ok(tags) = tag_map {
-            for flight in &mut flights {
-                if let Some(flight_tags) = tags.get(&flight.id) {
-                    flight.tags = flight_tags.clone();
-                }
-            }
+        // Charts read these instead of aggregating on every open; a failure
+        // only costs the fallback aggregation later
+        if let Err(e) = Self::build_telemetry_tiers(conn, flight_id) {
+            log::warn!("Failed to precompute telemetry tiers for flight {}: {}", flight_id, e);
         }
-
-        log::debug!("get_all_flights: {} rows in {:.1}ms", flights.len(), start.elapsed().as_secs_f64() * 1000.0);
-        Ok(flights)
+        // Invalidates cached flight data (see `flight_data_cache`)
+        Self::touch_flights(conn, Some(flight_id));
+        Ok(inserted)
     }
 
-    /// Helper: get all flight tags using an existing connection lock
-    fn get_all_flight_tags_with_conn(&self, conn: &Connection) -> Result<std::collections::HashMap<i64, Vec<FlightTag>>, DatabaseError> {
+    /// (Re)compute the downsampled telemetry tiers of a flight
+    fn build_telemetry_tiers(conn: &Connection, flight_id: i64) -> Result<(), DatabaseError> {
+        conn.execute("DELETE FROM telemetry_tiers WHERE flight_id = ?", params![flight_id])?;
+        for tier_ms in TELEMETRY_TIERS_MS {
+            conn.execute(
+                &format!(
+                    r#"
+                    INSERT INTO telemetry_tiers
+                    SELECT
+                        ? AS tier_ms,
+                        flight_id,
+                        (timestamp_ms // ?) * ? AS bucket_ts,{}
+                    FROM telemetry
+                    WHERE flight_id = ?
+                    GROUP BY flight_id, bucket_ts
+                    "#,
+                    DOWNSAMPLE_AGGREGATES
+                ),
+                params![tier_ms, tier_ms, tier_ms, flight_id],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Build the tiers of flights that have telemetry but none yet (imported
+    /// before tiers existed, or dropped by `migrate_telemetry_tiers_table`)
+    fn build_missing_telemetry_tiers(&self) {
+        let conn = self.conn.lock().unwrap();
+        let flight_ids = conn
+            .prepare(
+                "SELECT id FROM flights f
+                 WHERE EXISTS (SELECT 1 FROM telemetry t WHERE t.flight_id = f.id)
+                   AND NOT EXISTS (SELECT 1 FROM telemetry_tiers tt WHERE tt.flight_id = f.id)",
+            )
+            .and_then(|mut stmt| {
+                let ids = stmt.query_map([], |row| row.get::<_, i64>(0))?.collect::<Result<Vec<_>, _>>()?;
+                Ok(ids)
+            });
+        let flight_ids = match flight_ids {
+            Ok(ids) => ids,
+            Err(e) => {
+                log::warn!("Failed to look up flights without telemetry tiers: {}", e);
+                return;
+            }
+        };
+        if flight_ids.is_empty() {
+            return;
+        }
+        let start = std::time::Instant::now();
+        for &flight_id in &flight_ids {
+            if let Err(e) = Self::build_telemetry_tiers(&conn, flight_id) {
+                log::warn!("Failed to precompute telemetry tiers for flight {}: {}", flight_id, e);
+            }
+        }
+        log::info!(
+            "Built telemetry tiers for {} flights in {:.1}s",
+            flight_ids.len(),
+            start.elapsed().as_secs_f64()
+        );
+    }
+
+    /// Serve downsampled telemetry from the finest precomputed tier holding at
+    /// most `max_points` buckets. None when even the coarsest is too dense, or
+    /// the flight has no tiers (the caller then aggregates the raw telemetry).
+    fn query_tier_telemetry(
+        &self,
+        conn: &Connection,
+        flight_id: i64,
+        max_points: usize,
+    ) -> Result<Option<Vec<TelemetryRecord>>, DatabaseError> {
+        // A missing or not yet migrated table (read-only snapshot, safe mode)
+        // counts as no tier
+        let count_tier = |tier_ms: i64| -> i64 {
+            conn.query_row(
+                "SELECT COUNT(*) FROM telemetry_tiers WHERE flight_id = ? AND tier_ms = ?",
+                params![flight_id, tier_ms],
+                |row| row.get(0),
+            )
+            .unwrap_or(0)
+        };
+
+        for tier_ms in TELEMETRY_TIERS_MS {
+            let buckets = count_tier(tier_ms);
+            if buckets == 0 || buckets as usize > max_points {
+                continue;
+            }
+            log::debug!("Serving {} Hz telemetry tier for flight {}", 1000.0 / tier_ms as f64, flight_id);
+            let records = (|| -> Result<Vec<TelemetryRecord>, DatabaseError> {
+                let mut stmt = conn.prepare(
+                    r#"
+                    SELECT
+                        timestamp_ms, latitude, longitude, altitude, height, vps_height,
+                        speed, velocity_x, velocity_y, velocity_z,
+                        battery_percent, battery_voltage, battery_temp, cell_voltages,
+                        pitch, roll, yaw, gimbal_pitch, gimbal_roll, gimbal_yaw,
+                        satellites, flight_mode, rc_signal, rc_uplink, rc_downlink,
+                        rc_aileron, rc_elevator, rc_throttle, rc_rudder,
+                        is_photo, is_video, battery_full_capacity, battery_remained_capacity,
+                        gps_signal
+                    FROM telemetry_tiers
+                    WHERE flight_id = ? AND tier_ms = ?
+                    ORDER BY timestamp_ms ASC
+                    "#,
+                )?;
+                let records = stmt
+                    .query_map(params![flight_id, tier_ms], map_telemetry_row)?
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(records)
+            })();
+            return Ok(match records {
+                Ok(records) => Some(records),
+                Err(e) => {
+                    log::warn!("Failed to read telemetry tiers of flight {}: {}", flight_id, e);
+                    None
+                }
+            });
+        }
+        Ok(None)
+    }
+
+    /// Replace all telemetry of a flight (e.g. after fusing a higher-rate log)
+    /// and update the stored point count. Returns the number of points inserted.
+    pub fn replace_flight_telemetry(
+        &self,
+        flight_id: i64,
+        points: &[TelemetryPoint],
+    ) -> Result<usize, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        // One transaction, so a failed insert leaves the old telemetry in place
+        conn.execute_batch("BEGIN TRANSACTION")?;
+        let result = (|| -> Result<usize, DatabaseError> {
+            conn.execute("DELETE FROM telemetry WHERE flight_id = ?", params![flight_id])?;
+            let inserted = Self::insert_telemetry(&conn, flight_id, points)?;
+            conn.execute(
+                "UPDATE flights SET point_count = ? WHERE id = ?",
+                params![inserted as i64, flight_id],
+            )?;
+            conn.execute_batch("COMMIT")?;
+            Ok(inserted)
+        })();
+        if result.is_err() {
+            if let Err(e) = conn.execute_batch("ROLLBACK") {
+                log::warn!("Failed to roll back telemetry replacement of flight {}: {}", flight_id, e);
+            }
+        }
+        result
+    }
+
+    /// Overwrite the telemetry-derived summary columns of a flight
+    pub fn update_flight_stats(&self, flight_id: i64, stats: &FlightStats) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let updated = conn.execute(
+            "UPDATE flights SET total_distance = ?, max_altitude = ?, max_speed = ? WHERE id = ?",
+            params![stats.total_distance_m, stats.max_altitude_m, stats.max_speed_ms, flight_id],
+        )?;
+        if updated == 0 {
+            return Err(DatabaseError::FlightNotFound(flight_id));
+        }
+        Self::record_integrity(&conn, flight_id, "update");
+        Ok(())
+    }
+
+    /// Overwrite the summary columns recomputed from telemetry, duration
+    /// included. Returns the values they had before.
+    pub fn replace_flight_stats(&self, flight_id: i64, stats: &FlightStats) -> Result<FlightStatsValues, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let before = conn
+            .query_row(
+                "SELECT total_distance, max_altitude, max_speed, duration_secs FROM flights WHERE id = ?",
+                params![flight_id],
+                |row| {
+                    Ok(FlightStatsValues {
+                        total_distance: row.get(0)?,
+                        max_altitude: row.get(1)?,
+                        max_speed: row.get(2)?,
+                        duration_secs: row.get(3)?,
+                    })
+                },
+            )
+            .optional()?
+            .ok_or(DatabaseError::FlightNotFound(flight_id))?;
+        conn.execute(
+            "UPDATE flights SET total_distance = ?, max_altitude = ?, max_speed = ?, duration_secs = ? WHERE id = ?",
+            params![stats.total_distance_m, stats.max_altitude_m, stats.max_speed_ms, stats.duration_secs, flight_id],
+        )?;
+        Self::record_integrity(&conn, flight_id, "update");
+        Ok(before)
+    }
+
+    /// Get all flights metadata (for the flight list sidebar)
+    #[tracing::instrument(skip_all)]
+    pub fn get_all_flights(&self) -> Result<Vec<Flight>, DatabaseError> {
+        let start = std::time::Instant::now();
+        let max_altitude = self.get_altitude_reference().max_altitude_sql();
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT 
+                id, file_name, COALESCE(display_name, file_name) AS display_name,
+                file_hash,
+                drone_model, drone_serial, aircraft_name, battery_serial,
+                CAST(start_time AS VARCHAR) AS start_time,
+                duration_secs, total_distance,
+                {} AS max_altitude, max_speed, home_lat, home_lon, point_count,
+                photo_count, video_count, notes, COALESCE(color, '#7dd3fc') AS color,
+                cycle_count, rc_serial, battery_life,
+                pilot, location_name, time_offset_secs, original_metadata,
+                COALESCE(is_manual, FALSE) AS is_manual, owner,
+                takeoff_count, landing_count,
+                hover_secs, transit_secs, ascend_secs, descend_secs,
+                smoothness_score, altitude_source
+            FROM flights
+            ORDER BY start_time DESC
+            "#,
+            max_altitude
+        ))?;
+
+        let mut flights: Vec<Flight> = stmt
+            .query_map([], |row| {
+                Ok(Flight {
+                    id: row.get(0)?,
+                    file_name: row.get(1)?,
+                    display_name: row.get(2)?,
+                    file_hash: row.get(3)?,
+                    drone_model: row.get(4)?,
+                    drone_serial: row.get(5)?,
+                    aircraft_name: row.get(6)?,
+                    battery_serial: row.get(7)?,
+                    cycle_count: row.get(20)?,
+                    start_time: row.get(8)?,
+                    duration_secs: row.get(9)?,
+                    total_distance: row.get(10)?,
+                    max_altitude: row.get(11)?,
+                    max_speed: row.get(12)?,
+                    home_lat: row.get(13)?,
+                    home_lon: row.get(14)?,
+                    point_count: row.get(15)?,
+                    photo_count: row.get(16)?,
+                    video_count: row.get(17)?,
+                    tags: Vec::new(),
+                    notes: row.get(18)?,
+                    color: row.get(19)?,
+                    rc_serial: row.get(21)?,
+                    battery_life: row.get(22)?,
+                    pilot: row.get(23)?,
+                    location_name: row.get(24)?,
+                    time_offset_secs: row.get(25)?,
+                    original_metadata: row
+                        .get::<_, Option<String>>(26)?
+                        .and_then(|s| serde_json::from_str(&s).ok()),
+                    is_manual: row.get(27)?,
+                    owner: row.get(28)?,
+                    takeoff_count: row.get(29)?,
+                    landing_count: row.get(30)?,
+                    phase_times: map_phase_times(row, 31)?,
+                    smoothness_score: row.get(35)?,
+                    altitude_source: row.get(36)?,
+                    exceedances: Vec::new(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Load all tags and attach to flights
+        // Use a separate query to avoid breaking if flight_tags table doesn't exist yet
+        let tag_map = self.get_all_flight_tags_with_conn(&conn);
+        if let Ok(tags) = tag_map {
+            for flight in &mut flights {
+                if let Some(flight_tags) = tags.get(&flight.id) {
+                    flight.tags = flight_tags.clone();
+                }
+            }
+        }
+
+        // Attach limit exceedances (table may be empty if no limits are set)
+        if let Ok(mut exceedances) = self.get_all_flight_exceedances_with_conn(&conn, None) {
+            for flight in &mut flights {
+                if let Some(list) = exceedances.remove(&flight.id) {
+                    flight.exceedances = list;
+                }
+            }
+        }
+
+        log::debug!("get_all_flights: {} rows in {:.1}ms", flights.len(), start.elapsed().as_secs_f64() * 1000.0);
+        Ok(flights)
+    }
+
+    /// Flights imported, changed or deleted after `since` (delta sync)
+    pub fn get_flight_changes(&self, since: chrono::DateTime<chrono::Utc>) -> Result<FlightChanges, DatabaseError> {
+        // Taken first, so changes made while this runs are sent again next time
+        let synced_at = chrono::Utc::now();
+        let since = since.to_rfc3339();
+        let (changed, deleted_ids) = {
+            let conn = self.conn.lock().unwrap();
+            let changed: HashSet<i64> = conn
+                .prepare("SELECT id FROM flights WHERE updated_at > ?::TIMESTAMPTZ")?
+                .query_map(params![since], |row| row.get::<_, i64>(0))?
+                .collect::<Result<HashSet<_>, _>>()?;
+            let deleted_ids = conn
+                .prepare("SELECT flight_id FROM flight_tombstones WHERE deleted_at > ?::TIMESTAMPTZ ORDER BY deleted_at")?
+                .query_map(params![since], |row| row.get::<_, i64>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            (changed, deleted_ids)
+        };
+        let flights = if changed.is_empty() {
+            Vec::new()
+        } else {
+            self.get_all_flights()?.into_iter().filter(|f| changed.contains(&f.id)).collect()
+        };
+        Ok(FlightChanges {
+            flights,
+            deleted_ids,
+            synced_at: synced_at.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        })
+    }
+
+    /// Helper: get all flight tags using an existing connection lock
+    fn get_all_flight_tags_with_conn(&self, conn: &Connection) -> Result<std::collections::HashMap<i64, Vec<FlightTag>>, DatabaseError> {
         let mut stmt = conn.prepare(
             "SELECT flight_id, tag, tag_type FROM flight_tags ORDER BY flight_id, tag",
         )?;
@@ -1104,23 +2452,77 @@ impl Database {
         Ok(map)
     }
 
+    /// Helper: get limit exceedances (all flights, or one) using an existing connection lock
+    fn get_all_flight_exceedances_with_conn(
+        &self,
+        conn: &Connection,
+        flight_id: Option<i64>,
+    ) -> Result<std::collections::HashMap<i64, Vec<FlightExceedance>>, DatabaseError> {
+        let filter = flight_id.map(|id| format!("WHERE flight_id = {}", id)).unwrap_or_default();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT flight_id, kind, limit_m, actual_m FROM flight_exceedances {} ORDER BY flight_id, kind",
+            filter
+        ))?;
+        let mut map: std::collections::HashMap<i64, Vec<FlightExceedance>> = std::collections::HashMap::new();
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                FlightExceedance {
+                    kind: row.get(1)?,
+                    limit_m: row.get(2)?,
+                    actual_m: row.get(3)?,
+                },
+            ))
+        })?;
+        for row in rows {
+            let (id, exceedance) = row?;
+            map.entry(id).or_default().push(exceedance);
+        }
+        Ok(map)
+    }
+
+    /// When a flight or its telemetry last changed, in ms since the epoch
+    /// (keys the flight data cache, web feature only)
+    #[allow(dead_code)]
+    pub fn get_flight_updated_ms(&self, flight_id: i64) -> Result<Option<i64>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let updated = conn
+            .query_row(
+                "SELECT epoch_ms(updated_at) FROM flights WHERE id = ?",
+                params![flight_id],
+                |row| row.get::<_, Option<i64>>(0),
+            )
+            .optional()?
+            .ok_or(DatabaseError::FlightNotFound(flight_id))?;
+        Ok(updated)
+    }
+
     /// Get a single flight by ID (avoids loading all flights)
     pub fn get_flight_by_id(&self, flight_id: i64) -> Result<Flight, DatabaseError> {
+        let max_altitude = self.get_altitude_reference().max_altitude_sql();
         let conn = self.conn.lock().unwrap();
 
         let mut flight = conn.query_row(
-            r#"
+            &format!(
+                r#"
             SELECT 
                 id, file_name, COALESCE(display_name, file_name) AS display_name,
                 file_hash, drone_model, drone_serial, aircraft_name, battery_serial,
                 CAST(start_time AS VARCHAR) AS start_time,
                 duration_secs, total_distance,
-                max_altitude, max_speed, home_lat, home_lon, point_count,
+                {} AS max_altitude, max_speed, home_lat, home_lon, point_count,
                 photo_count, video_count, notes, COALESCE(color, '#7dd3fc') AS color,
-                cycle_count, rc_serial, battery_life
+                cycle_count, rc_serial, battery_life,
+                pilot, location_name, time_offset_secs, original_metadata,
+                COALESCE(is_manual, FALSE) AS is_manual, owner,
+                takeoff_count, landing_count,
+                hover_secs, transit_secs, ascend_secs, descend_secs,
+                smoothness_score, altitude_source
             FROM flights
             WHERE id = ?
             "#,
+                max_altitude
+            ),
             params![flight_id],
             |row| {
                 Ok(Flight {
@@ -1148,6 +2550,20 @@ impl Database {
                     color: row.get(19)?,
                     rc_serial: row.get(21)?,
                     battery_life: row.get(22)?,
+                    pilot: row.get(23)?,
+                    location_name: row.get(24)?,
+                    time_offset_secs: row.get(25)?,
+                    original_metadata: row
+                        .get::<_, Option<String>>(26)?
+                        .and_then(|s| serde_json::from_str(&s).ok()),
+                    is_manual: row.get(27)?,
+                    owner: row.get(28)?,
+                    takeoff_count: row.get(29)?,
+                    landing_count: row.get(30)?,
+                    phase_times: map_phase_times(row, 31)?,
+                    smoothness_score: row.get(35)?,
+                    altitude_source: row.get(36)?,
+                    exceedances: Vec::new(),
                 })
             },
         )
@@ -1171,19 +2587,62 @@ impl Database {
             }
         }
 
+        if let Ok(mut exceedances) = self.get_all_flight_exceedances_with_conn(&conn, Some(flight_id)) {
+            flight.exceedances = exceedances.remove(&flight_id).unwrap_or_default();
+        }
+
         Ok(flight)
     }
 
+    /// Telemetry columns that hold no value at all for a flight (e.g. `vps_height`
+    /// or `rc_uplink` on aircraft that never report them)
+    pub fn null_telemetry_columns(&self, flight_id: i64) -> Result<Vec<String>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare("PRAGMA table_info('telemetry')")?;
+        let columns: Vec<String> = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|c| c != "flight_id" && c != "timestamp_ms")
+            .collect();
+        if columns.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let counts = columns.iter().map(|c| format!("COUNT(\"{}\")", c)).collect::<Vec<_>>().join(", ");
+        let (rows, non_null): (i64, Vec<i64>) = conn.query_row(
+            &format!("SELECT COUNT(*), {} FROM telemetry WHERE flight_id = ?", counts),
+            params![flight_id],
+            |row| {
+                let non_null = (1..=columns.len()).map(|i| row.get::<_, i64>(i)).collect::<Result<Vec<_>, _>>()?;
+                Ok((row.get(0)?, non_null))
+            },
+        )?;
+        if rows == 0 {
+            return Ok(Vec::new());
+        }
+
+        Ok(columns
+            .into_iter()
+            .zip(non_null)
+            .filter(|(_, n)| *n == 0)
+            .map(|(c, _)| c)
+            .collect())
+    }
+
     /// Get flight telemetry with automatic downsampling for large datasets.
     ///
     /// Strategy:
     /// - If max_points is None: return all raw data (for export)
     /// - If points <= max_points: return raw data
-    /// - If points > max_points: group by time-bucket intervals, averaging values
+    /// - If points > max_points: read the finest precomputed 1 Hz / 0.2 Hz tier
+    ///   that fits, else group by time-bucket intervals, averaging values
     /// - This keeps the frontend responsive while preserving data trends
     ///
     /// `known_point_count` avoids an extra COUNT query when the flight metadata
     /// already provides the point count.
+    #[tracing::instrument(skip_all, fields(flight_id = flight_id, max_points = ?max_points))]
     pub fn get_flight_telemetry(
         &self,
         flight_id: i64,
@@ -1261,8 +2720,10 @@ impl Database {
                 flight_id
             );
             self.query_raw_telemetry(&conn, flight_id)?
+        } else if let Some(records) = self.query_tier_telemetry(&conn, flight_id, max_points)? {
+            records
         } else {
-            // Downsample using 1-second interval averaging
+            // Too long for the coarsest tier: aggregate on the fly
             log::debug!(
                 "Downsampling {} points to ~{} for flight {}",
                 point_count,
@@ -1316,7 +2777,8 @@ impl Database {
                 is_photo,
                 is_video,
                 battery_full_capacity,
-                battery_remained_capacity
+                battery_remained_capacity,
+                gps_signal
             FROM telemetry
             WHERE flight_id = ?
             ORDER BY timestamp_ms ASC
@@ -1324,50 +2786,7 @@ impl Database {
         )?;
 
         let records = stmt
-            .query_map(params![flight_id], |row| {
-                // Parse cell_voltages from JSON string
-                let cell_voltages_json: Option<String> = row.get(13)?;
-                let cell_voltages = cell_voltages_json.and_then(|s| {
-                    serde_json::from_str::<Vec<f64>>(&s).ok()
-                });
-                
-                Ok(TelemetryRecord {
-                    timestamp_ms: row.get(0)?,
-                    latitude: row.get(1)?,
-                    longitude: row.get(2)?,
-                    altitude: row.get(3)?,
-                    height: row.get(4)?,
-                    vps_height: row.get(5)?,
-                    speed: row.get(6)?,
-                    velocity_x: row.get(7)?,
-                    velocity_y: row.get(8)?,
-                    velocity_z: row.get(9)?,
-                    battery_percent: row.get(10)?,
-                    battery_voltage: row.get(11)?,
-                    battery_temp: row.get(12)?,
-                    cell_voltages,
-                    pitch: row.get(14)?,
-                    roll: row.get(15)?,
-                    yaw: row.get(16)?,
-                    gimbal_pitch: row.get(17)?,
-                    gimbal_roll: row.get(18)?,
-                    gimbal_yaw: row.get(19)?,
-                    satellites: row.get(20)?,
-                    flight_mode: row.get(21)?,
-                    rc_signal: row.get(22)?,
-                    rc_uplink: row.get(23)?,
-                    rc_downlink: row.get(24)?,
-                    rc_aileron: row.get(25)?,
-                    rc_elevator: row.get(26)?,
-                    rc_throttle: row.get(27)?,
-                    rc_rudder: row.get(28)?,
-                    is_photo: row.get(29)?,
-                    is_video: row.get(30)?,
-                    battery_current: None,
-                    battery_full_capacity: row.get(31)?,
-                    battery_remained_capacity: row.get(32)?,
-                })
-            })?
+            .query_map(params![flight_id], map_telemetry_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(records)
@@ -1403,111 +2822,42 @@ impl Database {
         let duration_ms = max_ts - min_ts;
         let bucket_size_ms = (duration_ms / target_points as i64).max(1000); // At least 1 second
 
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             r#"
             WITH bucketed AS (
                 SELECT 
-                    (timestamp_ms / ?) * ? AS bucket_ts,
-                    AVG(latitude) AS latitude,
-                    AVG(longitude) AS longitude,
-                    AVG(altitude) AS altitude,
-                    AVG(height) AS height,
-                    AVG(vps_height) AS vps_height,
-                    AVG(speed) AS speed,
-                    AVG(velocity_x) AS velocity_x,
-                    AVG(velocity_y) AS velocity_y,
-                    AVG(velocity_z) AS velocity_z,
-                    AVG(battery_percent)::INTEGER AS battery_percent,
-                    AVG(battery_voltage) AS battery_voltage,
-                    AVG(battery_temp) AS battery_temp,
-                    FIRST(cell_voltages ORDER BY timestamp_ms) AS cell_voltages,
-                    AVG(pitch) AS pitch,
-                    AVG(roll) AS roll,
-                    AVG(yaw) AS yaw,
-                    AVG(gimbal_pitch) AS gimbal_pitch,
-                    AVG(gimbal_roll) AS gimbal_roll,
-                    AVG(gimbal_yaw) AS gimbal_yaw,
-                    ROUND(AVG(satellites))::INTEGER AS satellites,
-                    FIRST(flight_mode ORDER BY timestamp_ms) AS flight_mode,
-                    AVG(rc_signal)::INTEGER AS rc_signal,
-                    AVG(rc_uplink)::INTEGER AS rc_uplink,
-                    AVG(rc_downlink)::INTEGER AS rc_downlink,
-                    AVG(rc_aileron) AS rc_aileron,
-                    AVG(rc_elevator) AS rc_elevator,
-                    AVG(rc_throttle) AS rc_throttle,
-                    AVG(rc_rudder) AS rc_rudder,
-                    BOOL_OR(is_photo) AS is_photo,
-                    BOOL_OR(is_video) AS is_video,
-                    AVG(battery_full_capacity) AS battery_full_capacity,
-                    AVG(battery_remained_capacity) AS battery_remained_capacity
-                FROM telemetry
+                    (timestamp_ms / ?) * ? AS bucket_ts,{}                FROM telemetry
                 WHERE flight_id = ?
                 GROUP BY bucket_ts
                 ORDER BY bucket_ts ASC
             )
             SELECT * FROM bucketed
             "#,
-        )?;
+            DOWNSAMPLE_AGGREGATES
+        ))?;
 
         let records = stmt
-            .query_map(params![bucket_size_ms, bucket_size_ms, flight_id], |row| {
-                // Parse cell_voltages from JSON string
-                let cell_voltages_json: Option<String> = row.get(13)?;
-                let cell_voltages = cell_voltages_json.and_then(|s| {
-                    serde_json::from_str::<Vec<f64>>(&s).ok()
-                });
-                
-                Ok(TelemetryRecord {
-                    timestamp_ms: row.get(0)?,
-                    latitude: row.get(1)?,
-                    longitude: row.get(2)?,
-                    altitude: row.get(3)?,
-                    height: row.get(4)?,
-                    vps_height: row.get(5)?,
-                    speed: row.get(6)?,
-                    velocity_x: row.get(7)?,
-                    velocity_y: row.get(8)?,
-                    velocity_z: row.get(9)?,
-                    battery_percent: row.get(10)?,
-                    battery_voltage: row.get(11)?,
-                    battery_temp: row.get(12)?,
-                    cell_voltages,
-                    pitch: row.get(14)?,
-                    roll: row.get(15)?,
-                    yaw: row.get(16)?,
-                    gimbal_pitch: row.get(17)?,
-                    gimbal_roll: row.get(18)?,
-                    gimbal_yaw: row.get(19)?,
-                    satellites: row.get(20)?,
-                    flight_mode: row.get(21)?,
-                    rc_signal: row.get(22)?,
-                    rc_uplink: row.get(23)?,
-                    rc_downlink: row.get(24)?,
-                    rc_aileron: row.get(25)?,
-                    rc_elevator: row.get(26)?,
-                    rc_throttle: row.get(27)?,
-                    rc_rudder: row.get(28)?,
-                    is_photo: row.get(29)?,
-                    is_video: row.get(30)?,
-                    battery_current: None,
-                    battery_full_capacity: row.get(31)?,
-                    battery_remained_capacity: row.get(32)?,
-                })
-            })?
+            .query_map(params![bucket_size_ms, bucket_size_ms, flight_id], map_telemetry_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(records)
     }
 
     /// Delete a flight and all associated telemetry data
+    #[tracing::instrument(skip_all, fields(flight_id = flight_id))]
     pub fn delete_flight(&self, flight_id: i64) -> Result<(), DatabaseError> {
         let start = std::time::Instant::now();
         let conn = self.conn.lock().unwrap();
+        Self::record_integrity(&conn, flight_id, "delete");
 
         conn.execute(
             "DELETE FROM telemetry WHERE flight_id = ?",
             params![flight_id],
         )?;
+        let _ = conn.execute(
+            "DELETE FROM telemetry_tiers WHERE flight_id = ?",
+            params![flight_id],
+        );
         // Clean up tags (ignore errors if table doesn't exist in old DBs)
         let _ = conn.execute(
             "DELETE FROM flight_tags WHERE flight_id = ?",
@@ -1518,24 +2868,121 @@ impl Database {
             "DELETE FROM flight_messages WHERE flight_id = ?",
             params![flight_id],
         );
-        conn.execute("DELETE FROM flights WHERE id = ?", params![flight_id])?;
-
-        log::info!("Deleted flight {} in {:.1}ms", flight_id, start.elapsed().as_secs_f64() * 1000.0);
-        Ok(())
-    }
-
-    /// Delete all flights and associated telemetry
-    pub fn delete_all_flights(&self) -> Result<(), DatabaseError> {
-        let start = std::time::Instant::now();
-        let conn = self.conn.lock().unwrap();
-
-        conn.execute("DELETE FROM telemetry", params![])?;
-        let _ = conn.execute("DELETE FROM flight_tags", params![]);
-        let _ = conn.execute("DELETE FROM flight_messages", params![]);
-        conn.execute("DELETE FROM flights", params![])?;
-
-        log::info!("Deleted all flights and telemetry in {:.1}ms", start.elapsed().as_secs_f64() * 1000.0);
-        Ok(())
+        let _ = conn.execute(
+            "DELETE FROM flight_import_reports WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_weather WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_rth_events WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_exceedances WHERE flight_id = ?",
+            params![flight_id],
+        );
+        // Keep attached documents, but as logbook-wide ones
+        let _ = conn.execute(
+            "UPDATE attachments SET flight_id = NULL WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM checklist_records WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_previews WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM flight_plans WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM job_flights WHERE flight_id = ?",
+            params![flight_id],
+        );
+        let _ = conn.execute(
+            "DELETE FROM import_journal WHERE flight_id = ?",
+            params![flight_id],
+        );
+        conn.execute("DELETE FROM flights WHERE id = ?", params![flight_id])?;
+
+        log::info!("Deleted flight {} in {:.1}ms", flight_id, start.elapsed().as_secs_f64() * 1000.0);
+        Ok(())
+    }
+
+    /// Delete all flights and associated telemetry
+    pub fn delete_all_flights(&self) -> Result<(), DatabaseError> {
+        let start = std::time::Instant::now();
+        let conn = self.conn.lock().unwrap();
+
+        let flight_ids: Vec<i64> = conn
+            .prepare("SELECT id FROM flights ORDER BY id")?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        for flight_id in flight_ids {
+            Self::record_integrity(&conn, flight_id, "delete");
+        }
+
+        conn.execute("DELETE FROM telemetry", params![])?;
+        let _ = conn.execute("DELETE FROM telemetry_tiers", params![]);
+        let _ = conn.execute("DELETE FROM flight_tags", params![]);
+        let _ = conn.execute("DELETE FROM flight_messages", params![]);
+        let _ = conn.execute("DELETE FROM flight_import_reports", params![]);
+        let _ = conn.execute("DELETE FROM flight_weather", params![]);
+        let _ = conn.execute("DELETE FROM flight_rth_events", params![]);
+        let _ = conn.execute("DELETE FROM flight_exceedances", params![]);
+        let _ = conn.execute("UPDATE attachments SET flight_id = NULL WHERE flight_id IS NOT NULL", params![]);
+        let _ = conn.execute("DELETE FROM checklist_records WHERE flight_id IS NOT NULL", params![]);
+        let _ = conn.execute("DELETE FROM flight_previews", params![]);
+        let _ = conn.execute("DELETE FROM flight_plans", params![]);
+        let _ = conn.execute("DELETE FROM job_flights", params![]);
+        let _ = conn.execute("DELETE FROM import_journal", params![]);
+        conn.execute("DELETE FROM flights", params![])?;
+
+        log::info!("Deleted all flights and telemetry in {:.1}ms", start.elapsed().as_secs_f64() * 1000.0);
+        Ok(())
+    }
+
+    /// Per-month flight aggregates, oldest month first
+    pub fn get_monthly_stats(&self) -> Result<Vec<MonthlyStats>, DatabaseError> {
+        let max_altitude = self.get_altitude_reference().max_altitude_sql();
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT
+                STRFTIME(DATE_TRUNC('month', start_time), '%Y-%m') AS month,
+                COUNT(*)::BIGINT,
+                COALESCE(SUM(duration_secs), 0)::DOUBLE,
+                COALESCE(SUM(total_distance), 0)::DOUBLE,
+                COALESCE(MAX({}), 0)::DOUBLE,
+                COALESCE(SUM(photo_count), 0)::BIGINT,
+                COALESCE(SUM(video_count), 0)::BIGINT
+            FROM flights
+            WHERE start_time IS NOT NULL
+            GROUP BY DATE_TRUNC('month', start_time)
+            ORDER BY month ASC
+            "#,
+            max_altitude
+        ))?;
+        let months = stmt
+            .query_map([], |row| {
+                Ok(MonthlyStats {
+                    month: row.get(0)?,
+                    flight_count: row.get(1)?,
+                    total_duration_secs: row.get(2)?,
+                    total_distance_m: row.get(3)?,
+                    max_altitude_m: row.get(4)?,
+                    total_photos: row.get(5)?,
+                    total_videos: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(months)
     }
 
     /// Get battery full capacity history across flights for a given battery serial
@@ -1565,15 +3012,461 @@ impl Database {
         Ok(rows)
     }
 
+    /// Top `limit` flights by `metric`, best first. Flights without a value for
+    /// the metric are left out.
+    pub fn get_top_flights(&self, metric: TopFlightMetric, limit: usize) -> Result<Vec<RankedFlight>, DatabaseError> {
+        let max_altitude = self.get_altitude_reference().max_altitude_sql();
+        let conn = self.conn.lock().unwrap();
+
+        let flight_column = |column: &str| {
+            format!(
+                "SELECT id, COALESCE(display_name, file_name), {0}::DOUBLE, CAST(start_time AS VARCHAR)
+                 FROM flights WHERE {0} IS NOT NULL ORDER BY {0} DESC, id LIMIT ?",
+                column
+            )
+        };
+        let sql = match metric {
+            TopFlightMetric::Duration => flight_column("duration_secs"),
+            TopFlightMetric::Distance => flight_column("total_distance"),
+            TopFlightMetric::MaxSpeed => flight_column("max_speed"),
+            TopFlightMetric::MaxAltitude => flight_column(max_altitude),
+            TopFlightMetric::Smoothness => flight_column("smoothness_score"),
+            TopFlightMetric::DistanceFromHome => r#"
+                SELECT
+                    f.id,
+                    COALESCE(f.display_name, f.file_name),
+                    MAX(
+                        6371000 * 2 * ASIN(SQRT(
+                            POWER(SIN(RADIANS(t.latitude - f.home_lat) / 2), 2) +
+                            COS(RADIANS(f.home_lat)) * COS(RADIANS(t.latitude)) *
+                            POWER(SIN(RADIANS(t.longitude - f.home_lon) / 2), 2)
+                        ))
+                    )::DOUBLE AS value,
+                    CAST(f.start_time AS VARCHAR)
+                FROM flights f
+                JOIN telemetry t ON f.id = t.flight_id
+                WHERE f.home_lat IS NOT NULL AND f.home_lon IS NOT NULL
+                  AND NOT (ABS(f.home_lat) < 0.000001 AND ABS(f.home_lon) < 0.000001)
+                  AND t.latitude IS NOT NULL AND t.longitude IS NOT NULL
+                  AND NOT (ABS(t.latitude) < 0.000001 AND ABS(t.longitude) < 0.000001)
+                GROUP BY f.id, f.display_name, f.file_name, f.start_time
+                ORDER BY value DESC, f.id
+                LIMIT ?
+            "#
+            .to_string(),
+            TopFlightMetric::ColdestTemperature => r#"
+                SELECT
+                    f.id,
+                    COALESCE(f.display_name, f.file_name),
+                    MIN(t.battery_temp)::DOUBLE AS value,
+                    CAST(f.start_time AS VARCHAR)
+                FROM flights f
+                JOIN telemetry t ON f.id = t.flight_id
+                WHERE t.battery_temp IS NOT NULL
+                GROUP BY f.id, f.display_name, f.file_name, f.start_time
+                ORDER BY value ASC, f.id
+                LIMIT ?
+            "#
+            .to_string(),
+            TopFlightMetric::MostEvents => r#"
+                SELECT
+                    f.id,
+                    COALESCE(f.display_name, f.file_name),
+                    COUNT(*)::DOUBLE AS value,
+                    CAST(f.start_time AS VARCHAR)
+                FROM flights f
+                JOIN flight_messages m ON f.id = m.flight_id
+                GROUP BY f.id, f.display_name, f.file_name, f.start_time
+                ORDER BY value DESC, f.id
+                LIMIT ?
+            "#
+            .to_string(),
+        };
+
+        let mut stmt = conn.prepare(&sql)?;
+        let flights = stmt
+            .query_map(params![limit as i64], |row| {
+                Ok(RankedFlight {
+                    id: row.get(0)?,
+                    display_name: row.get(1)?,
+                    value: row.get(2)?,
+                    start_time: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(flights)
+    }
+
+    /// `(timestamp_ms, value)` of a derived-metric expression over the raw
+    /// telemetry of a flight (see `derived_metrics`, which validates it)
+    pub fn evaluate_telemetry_expression(&self, flight_id: i64, expression: &str) -> Result<Vec<(i64, Option<f64>)>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let sql = format!(
+            "SELECT timestamp_ms, ({})::DOUBLE FROM telemetry WHERE flight_id = ? ORDER BY timestamp_ms",
+            expression
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let samples = stmt
+            .query_map(params![flight_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(samples)
+    }
+
+    /// `(timestamp_ms, latitude, longitude)` of every raw telemetry sample of
+    /// a flight, for recording gap detection (see `gaps`)
+    pub fn get_telemetry_sample_times(&self, flight_id: i64) -> Result<Vec<(i64, Option<f64>, Option<f64>)>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT timestamp_ms, latitude, longitude FROM telemetry WHERE flight_id = ? ORDER BY timestamp_ms",
+        )?;
+        let samples = stmt
+            .query_map(params![flight_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(samples)
+    }
+
+    /// Bind a derived-metric expression without evaluating it, to report
+    /// unknown functions and type errors when it is saved
+    pub fn check_telemetry_expression(&self, expression: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let sql = format!("SELECT ({})::DOUBLE FROM telemetry LIMIT 0", expression);
+        let mut stmt = conn.prepare(&sql)?;
+        stmt.query([])?.next()?;
+        Ok(())
+    }
+
+    /// DuckDB's JSON syntax tree of `sql` (an error object for anything but a
+    /// SELECT), for `sql_console` to check before running it
+    pub fn serialize_sql(&self, sql: &str) -> Result<String, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let serialized = conn.query_row("SELECT json_serialize_sql(?::VARCHAR)::VARCHAR", params![sql], |row| row.get(0))?;
+        Ok(serialized)
+    }
+
+    /// A connection to the SQL console's snapshot of the database, taken
+    /// again once the file has changed. External access and configuration
+    /// changes are global to a DuckDB instance, so the console cannot lock
+    /// them down on the main one (backups and mirrors need file access);
+    /// its copy runs as a separate instance with both disabled.
+    fn console_connection(&self) -> Result<Connection, DatabaseError> {
+        if !self.is_read_only() {
+            if let Err(e) = self.conn.lock().unwrap().execute_batch("CHECKPOINT") {
+                log::debug!("Checkpoint before console snapshot failed: {}", e);
+            }
+        }
+        let metadata = fs::metadata(&self.db_path)?;
+        let source = (metadata.len(), metadata.modified().ok());
+
+        let mut console = self.console.lock().unwrap();
+        let current = match console.as_ref() {
+            Some(snapshot) => snapshot.source == source,
+            None => false,
+        };
+        if !current {
+            *console = None;
+            let file_name = self.db_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let file = SnapshotFile(std::env::temp_dir().join(format!(
+                "{}{}-console-{}",
+                READ_ONLY_SNAPSHOT_PREFIX,
+                std::process::id(),
+                file_name
+            )));
+            fs::copy(&self.db_path, &file.0)?;
+            let config = Config::default()
+                .access_mode(AccessMode::ReadOnly)?
+                .enable_external_access(false)?;
+            let conn = Connection::open_with_flags(&file.0, config)?;
+            conn.execute_batch("SET lock_configuration = true")?;
+            log::debug!("Opened SQL console snapshot {:?}", file.0);
+            *console = Some(ConsoleSnapshot { conn, source, _file: file });
+        }
+        let snapshot = console.as_ref().expect("console snapshot was just opened");
+        Ok(snapshot.conn.try_clone()?)
+    }
+
+    /// Run a console query on the console's locked-down snapshot (see
+    /// `console_connection`), inside a transaction that is always rolled
+    /// back, interrupting it after `timeout`. Returns the column names and up
+    /// to `limit` rows as JSON objects.
+    #[tracing::instrument(skip_all, fields(limit = limit))]
+    pub fn query_read_only(
+        &self,
+        sql: &str,
+        limit: usize,
+        timeout: std::time::Duration,
+    ) -> Result<(Vec<String>, Vec<String>), DatabaseError> {
+        let conn = self.console_connection()?;
+        let interrupt = conn.interrupt_handle();
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+        let watchdog = std::thread::spawn(move || {
+            if done_rx.recv_timeout(timeout) == Err(std::sync::mpsc::RecvTimeoutError::Timeout) {
+                interrupt.interrupt();
+            }
+        });
+
+        conn.execute_batch("BEGIN TRANSACTION")?;
+        let result = (|| -> Result<(Vec<String>, Vec<String>), DatabaseError> {
+            let mut stmt = conn.prepare(&format!("SELECT * FROM ({}) AS console LIMIT 0", sql))?;
+            stmt.query([])?.next()?;
+            let columns = stmt.column_names();
+            let mut stmt = conn.prepare(&format!(
+                "SELECT to_json(console)::VARCHAR FROM ({}) AS console LIMIT {}",
+                sql, limit
+            ))?;
+            let rows = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<Result<Vec<String>, _>>()?;
+            Ok((columns, rows))
+        })();
+        if let Err(e) = conn.execute_batch("ROLLBACK") {
+            log::warn!("Failed to roll back console query: {}", e);
+        }
+        drop(done_tx);
+        let _ = watchdog.join();
+        result
+    }
+
+    /// Time spent per `bin_width` band of `metric`, for one flight or all
+    /// flights (manual entries left out). Each sample counts for the time
+    /// until the next one; gaps over 5 s are not counted.
+    pub fn get_histogram(
+        &self,
+        metric: HistogramMetric,
+        flight_id: Option<i64>,
+        bin_width: f64,
+    ) -> Result<Histogram, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let column = match metric {
+            HistogramMetric::Height => "t.height",
+            HistogramMetric::Speed => "t.speed",
+        };
+        let sql = format!(
+            r#"
+            WITH samples AS (
+                SELECT
+                    {}::DOUBLE AS value,
+                    LEAD(t.timestamp_ms) OVER (PARTITION BY t.flight_id ORDER BY t.timestamp_ms) - t.timestamp_ms AS dt_ms
+                FROM telemetry t
+                JOIN flights f ON f.id = t.flight_id
+                WHERE (?1 IS NULL AND NOT COALESCE(f.is_manual, FALSE)) OR t.flight_id = ?1
+            )
+            SELECT FLOOR(value / ?2)::BIGINT AS bin, (SUM(dt_ms) / 1000.0)::DOUBLE
+            FROM samples
+            WHERE value IS NOT NULL AND dt_ms > 0 AND dt_ms <= 5000
+            GROUP BY bin
+            ORDER BY bin
+            "#,
+            column
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let counted: Vec<(i64, f64)> = stmt
+            .query_map(params![flight_id, bin_width], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let bins = match (counted.first(), counted.last()) {
+            (Some(&(first, _)), Some(&(last, _))) => (first..=last)
+                .map(|bin| HistogramBin {
+                    min: bin as f64 * bin_width,
+                    max: (bin + 1) as f64 * bin_width,
+                    seconds: counted.iter().find(|(b, _)| *b == bin).map_or(0.0, |(_, secs)| *secs),
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        Ok(Histogram {
+            metric,
+            flight_id,
+            bin_width,
+            total_secs: counted.iter().map(|(_, secs)| secs).sum(),
+            bins,
+        })
+    }
+
+    /// Flights, duration and distance per day over the whole logbook, or the
+    /// inclusive `YYYY-MM-DD` range given
+    pub fn get_activity_calendar(&self, from: Option<&str>, to: Option<&str>) -> Result<Vec<ActivityDay>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                CAST(start_time AS DATE)::VARCHAR AS flight_date,
+                COUNT(*)::BIGINT,
+                COALESCE(SUM(duration_secs), 0)::DOUBLE,
+                COALESCE(SUM(total_distance), 0)::DOUBLE
+            FROM flights
+            WHERE start_time IS NOT NULL
+              AND (?1 IS NULL OR CAST(start_time AS DATE) >= CAST(?1 AS DATE))
+              AND (?2 IS NULL OR CAST(start_time AS DATE) <= CAST(?2 AS DATE))
+            GROUP BY CAST(start_time AS DATE)
+            ORDER BY flight_date ASC
+            "#,
+        )?;
+        let days = stmt
+            .query_map(params![from, to], |row| {
+                Ok(ActivityDay {
+                    date: row.get(0)?,
+                    count: row.get(1)?,
+                    duration_secs: row.get(2)?,
+                    distance_m: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(days)
+    }
+
+    /// Telemetry positions within a lat/lon box, for flights taken off within
+    /// the inclusive `YYYY-MM-DD` range, ordered by flight and time
+    pub fn get_positions_in_box(
+        &self,
+        (min_lat, max_lat): (f64, f64),
+        (min_lon, max_lon): (f64, f64),
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<Vec<AreaSample>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT t.flight_id, t.timestamp_ms, t.latitude, t.longitude,
+                   CAST(COALESCE(t.height, t.altitude) AS DOUBLE)
+            FROM telemetry t
+            JOIN flights f ON f.id = t.flight_id
+            WHERE t.latitude BETWEEN ?1 AND ?2
+              AND t.longitude BETWEEN ?3 AND ?4
+              AND (?5 IS NULL OR CAST(f.start_time AS DATE) >= CAST(?5 AS DATE))
+              AND (?6 IS NULL OR CAST(f.start_time AS DATE) <= CAST(?6 AS DATE))
+            ORDER BY t.flight_id, t.timestamp_ms
+            "#,
+        )?;
+        let samples = stmt
+            .query_map(params![min_lat, max_lat, min_lon, max_lon, from, to], |row| {
+                Ok(AreaSample {
+                    flight_id: row.get(0)?,
+                    timestamp_ms: row.get(1)?,
+                    lat: row.get(2)?,
+                    lon: row.get(3)?,
+                    height: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(samples)
+    }
+
+    /// Flying streaks, days since the last flight and the busiest day and week
+    pub fn get_activity_stats(&self) -> Result<ActivityStats, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        let (last_flight_date, days_since_last_flight, flying_days): (Option<String>, Option<i64>, i64) = conn.query_row(
+            r#"
+            SELECT
+                MAX(CAST(start_time AS DATE))::VARCHAR,
+                (CURRENT_DATE - MAX(CAST(start_time AS DATE)))::BIGINT,
+                COUNT(DISTINCT CAST(start_time AS DATE))::BIGINT
+            FROM flights
+            WHERE start_time IS NOT NULL
+            "#,
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        // Gaps and islands: consecutive dates share `date - row_number`
+        let mut stmt = conn.prepare(
+            r#"
+            WITH days AS (
+                SELECT DISTINCT CAST(start_time AS DATE) AS d
+                FROM flights
+                WHERE start_time IS NOT NULL
+            ),
+            islands AS (
+                SELECT d, d - CAST(ROW_NUMBER() OVER (ORDER BY d) AS INTEGER) AS grp
+                FROM days
+            )
+            SELECT
+                MIN(d)::VARCHAR,
+                MAX(d)::VARCHAR,
+                COUNT(*)::BIGINT AS days,
+                MAX(d) >= CURRENT_DATE - 1 AS alive
+            FROM islands
+            GROUP BY grp
+            ORDER BY days DESC, MIN(d) DESC
+            "#,
+        )?;
+        let streaks = stmt
+            .query_map([], |row| {
+                Ok((
+                    FlyingStreak {
+                        start_date: row.get(0)?,
+                        end_date: row.get(1)?,
+                        days: row.get(2)?,
+                    },
+                    row.get::<_, bool>(3)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        let longest_streak = streaks.first().map(|(s, _)| s.clone());
+        let current_streak = streaks.into_iter().find(|(_, alive)| *alive).map(|(s, _)| s);
+
+        let busiest_day = conn
+            .query_row(
+                r#"
+                SELECT CAST(start_time AS DATE)::VARCHAR AS flight_date, COUNT(*)::BIGINT AS count
+                FROM flights
+                WHERE start_time IS NOT NULL
+                GROUP BY CAST(start_time AS DATE)
+                ORDER BY count DESC, flight_date DESC
+                LIMIT 1
+                "#,
+                [],
+                |row| Ok(FlightDateCount { date: row.get(0)?, count: row.get(1)? }),
+            )
+            .optional()?;
+
+        let busiest_week = conn
+            .query_row(
+                r#"
+                SELECT
+                    CAST(DATE_TRUNC('week', start_time) AS DATE)::VARCHAR AS week_start,
+                    COUNT(*)::BIGINT AS count,
+                    COALESCE(SUM(duration_secs), 0)::DOUBLE
+                FROM flights
+                WHERE start_time IS NOT NULL
+                GROUP BY DATE_TRUNC('week', start_time)
+                ORDER BY count DESC, week_start DESC
+                LIMIT 1
+                "#,
+                [],
+                |row| {
+                    Ok(BusiestWeek {
+                        week_start: row.get(0)?,
+                        count: row.get(1)?,
+                        duration_secs: row.get(2)?,
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(ActivityStats {
+            flying_days,
+            last_flight_date,
+            days_since_last_flight,
+            current_streak,
+            longest_streak,
+            busiest_day,
+            busiest_week,
+        })
+    }
+
     /// Get overview stats across all flights
+    #[tracing::instrument(skip_all)]
     pub fn get_overview_stats(&self) -> Result<OverviewStats, DatabaseError> {
         let start = std::time::Instant::now();
+        let max_altitude_sql = self.get_altitude_reference().max_altitude_sql();
         let conn = self.conn.lock().unwrap();
 
         // Basic aggregate stats
         let (total_flights, total_distance, total_duration, total_points, total_photos, total_videos, max_altitude): (i64, f64, f64, i64, i64, i64, f64) =
             conn.query_row(
-                r#"
+                &format!(
+                    r#"
                 SELECT
                     COUNT(*)::BIGINT,
                     COALESCE(SUM(total_distance), 0)::DOUBLE,
@@ -1581,9 +3474,11 @@ impl Database {
                     COALESCE(SUM(point_count), 0)::BIGINT,
                     COALESCE(SUM(photo_count), 0)::BIGINT,
                     COALESCE(SUM(video_count), 0)::BIGINT,
-                    COALESCE(MAX(max_altitude), 0)::DOUBLE
+                    COALESCE(MAX({}), 0)::DOUBLE
                 FROM flights
                 "#,
+                    max_altitude_sql
+                ),
                 [],
                 |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?)),
             )?;
@@ -1776,6 +3671,42 @@ impl Database {
             .map(|f| f.max_distance_from_home_m)
             .unwrap_or(0.0);
 
+        // Flights over the personal altitude / range limits
+        let (altitude_exceedance_flights, distance_exceedance_flights): (i64, i64) = conn
+            .query_row(
+                r#"
+                SELECT
+                    COUNT(*) FILTER (WHERE kind = 'altitude')::BIGINT,
+                    COUNT(*) FILTER (WHERE kind = 'distance')::BIGINT
+                FROM flight_exceedances
+                "#,
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap_or((0, 0));
+
+        let phase_times = conn
+            .query_row(
+                r#"
+                SELECT
+                    COALESCE(SUM(hover_secs), 0)::DOUBLE,
+                    COALESCE(SUM(transit_secs), 0)::DOUBLE,
+                    COALESCE(SUM(ascend_secs), 0)::DOUBLE,
+                    COALESCE(SUM(descend_secs), 0)::DOUBLE
+                FROM flights
+                "#,
+                [],
+                |row| {
+                    Ok(PhaseTimes {
+                        hover_secs: row.get(0)?,
+                        transit_secs: row.get(1)?,
+                        ascend_secs: row.get(2)?,
+                        descend_secs: row.get(3)?,
+                    })
+                },
+            )
+            .unwrap_or_default();
+
         log::debug!(
             "get_overview_stats: {} flights, {} batteries, {} drones in {:.1}ms",
             total_flights, batteries_used.len(), drones_used.len(),
@@ -1797,23 +3728,86 @@ impl Database {
             top_flights,
             top_distance_flights,
             battery_health_points,
+            altitude_exceedance_flights,
+            distance_exceedance_flights,
+            phase_times,
+            battery_retirement: Vec::new(),
         })
     }
 
-    /// Update the display name for a flight and persist to customizations overlay
-    pub fn update_flight_name(&self, flight_id: i64, display_name: &str) -> Result<(), DatabaseError> {
+    /// Headline numbers for dashboard widgets. A battery is flagged once its
+    /// cycle count (or flight count when the log has none) reaches
+    /// `BATTERY_WARNING_CYCLES`.
+    pub fn get_logbook_summary(&self) -> Result<LogbookSummary, DatabaseError> {
         let conn = self.conn.lock().unwrap();
 
-        conn.execute(
-            "UPDATE flights SET display_name = ? WHERE id = ?",
-            params![display_name, flight_id],
+        let (total_flights, total_distance, total_duration): (i64, f64, f64) = conn.query_row(
+            r#"
+            SELECT
+                COUNT(*)::BIGINT,
+                COALESCE(SUM(total_distance), 0)::DOUBLE,
+                COALESCE(SUM(duration_secs), 0)::DOUBLE
+            FROM flights
+            "#,
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
         )?;
-        // Write-through to customizations overlay (keyed by file_hash)
-        Self::save_customization_field(&conn, flight_id, "display_name", Some(display_name))?;
 
-        log::debug!("Updated flight {} display name to '{}'", flight_id, display_name);
-        Ok(())
-    }
+        let last_flight: Option<(Option<String>, Option<String>)> = conn
+            .query_row(
+                r#"
+                SELECT CAST(start_time AS VARCHAR), COALESCE(display_name, file_name)
+                FROM flights
+                WHERE start_time IS NOT NULL
+                ORDER BY start_time DESC
+                LIMIT 1
+                "#,
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let (last_flight_date, last_flight_name) = last_flight.unwrap_or((None, None));
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT battery_serial
+            FROM flights
+            WHERE battery_serial IS NOT NULL AND battery_serial <> ''
+            GROUP BY battery_serial
+            HAVING COALESCE(MAX(cycle_count), COUNT(*)) >= ?
+            ORDER BY battery_serial
+            "#,
+        )?;
+        let battery_warning_serials = stmt
+            .query_map(params![BATTERY_WARNING_CYCLES], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(LogbookSummary {
+            total_flights,
+            total_hours: (total_duration / 360.0).round() / 10.0,
+            total_distance_km: (total_distance / 100.0).round() / 10.0,
+            last_flight_date,
+            last_flight_name,
+            battery_warnings: battery_warning_serials.len() as i64,
+            battery_warning_serials,
+        })
+    }
+
+    /// Update the display name for a flight and persist to customizations overlay
+    pub fn update_flight_name(&self, flight_id: i64, display_name: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "UPDATE flights SET display_name = ? WHERE id = ?",
+            params![display_name, flight_id],
+        )?;
+        // Write-through to customizations overlay (keyed by file_hash)
+        Self::save_customization_field(&conn, flight_id, "display_name", Some(display_name))?;
+        Self::record_integrity(&conn, flight_id, "update");
+
+        log::debug!("Updated flight {} display name to '{}'", flight_id, display_name);
+        Ok(())
+    }
 
     /// Update the notes for a flight and persist to customizations overlay
     pub fn update_flight_notes(&self, flight_id: i64, notes: Option<&str>) -> Result<(), DatabaseError> {
@@ -1825,6 +3819,7 @@ impl Database {
         )?;
         // Write-through to customizations overlay (keyed by file_hash)
         Self::save_customization_field(&conn, flight_id, "notes", notes)?;
+        Self::record_integrity(&conn, flight_id, "update");
 
         log::debug!("Updated flight {} notes", flight_id);
         Ok(())
@@ -1840,11 +3835,138 @@ impl Database {
         )?;
         // Write-through to customizations overlay (keyed by file_hash)
         Self::save_customization_field(&conn, flight_id, "color", Some(color))?;
+        Self::touch_flights(&conn, Some(flight_id));
 
         log::debug!("Updated flight {} color to '{}'", flight_id, color);
         Ok(())
     }
 
+    /// Web account that imported a flight (None when imported without one)
+    pub fn get_flight_owner(&self, flight_id: i64) -> Result<Option<String>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT owner FROM flights WHERE id = ?",
+            params![flight_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or(DatabaseError::FlightNotFound(flight_id))
+    }
+
+    /// Record the web account that imported a flight
+    pub fn set_flight_owner(&self, flight_id: i64, owner: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE flights SET owner = ? WHERE id = ?",
+            params![owner, flight_id],
+        )?;
+        Ok(())
+    }
+
+    /// Correct parsed flight metadata. Fields left as None are unchanged and
+    /// empty strings clear a value. The start time is set to the parsed start
+    /// time plus `time_offset_secs`, so an offset of 0 restores it. The value
+    /// each field had before its first edit is kept in `original_metadata`.
+    pub fn update_flight_metadata(&self, flight_id: i64, update: &FlightMetadataUpdate) -> Result<Flight, DatabaseError> {
+        {
+            let conn = self.conn.lock().unwrap();
+            let (drone_model, battery_serial, pilot, location_name, start_time, end_time, original): (
+                Option<String>,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+            ) = conn
+                .query_row(
+                    "SELECT drone_model, battery_serial, pilot, location_name,
+                            CAST(start_time AS VARCHAR), CAST(end_time AS VARCHAR), original_metadata
+                     FROM flights WHERE id = ?",
+                    params![flight_id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?)),
+                )
+                .optional()?
+                .ok_or(DatabaseError::FlightNotFound(flight_id))?;
+
+            let mut original: serde_json::Map<String, serde_json::Value> =
+                original.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default();
+            let mut keep_original = |key: &str, value: &Option<String>| {
+                original.entry(key).or_insert_with(|| serde_json::json!(value));
+            };
+
+            let clean = |v: &str| Some(v.trim().to_string()).filter(|v| !v.is_empty());
+            let mut assignments: Vec<(&str, Option<String>)> = Vec::new();
+            if let Some(v) = &update.drone_model {
+                keep_original("drone_model", &drone_model);
+                assignments.push(("drone_model", clean(v)));
+            }
+            if let Some(v) = &update.battery_serial {
+                keep_original("battery_serial", &battery_serial);
+                assignments.push(("battery_serial", clean(v).map(|s| s.to_uppercase())));
+            }
+            if let Some(v) = &update.pilot {
+                keep_original("pilot", &pilot);
+                assignments.push(("pilot", clean(v)));
+            }
+            if let Some(v) = &update.location_name {
+                keep_original("location_name", &location_name);
+                assignments.push(("location_name", clean(v)));
+            }
+            if update.time_offset_secs.is_some() {
+                keep_original("start_time", &start_time);
+                keep_original("end_time", &end_time);
+            }
+
+            for (column, value) in &assignments {
+                conn.execute(&format!("UPDATE flights SET {} = ? WHERE id = ?", column), params![value, flight_id])?;
+            }
+            if let Some(offset) = update.time_offset_secs {
+                let micros = (offset * 1_000_000.0).round() as i64;
+                let parsed = |key: &str| original.get(key).and_then(|v| v.as_str()).map(str::to_string);
+                conn.execute(
+                    "UPDATE flights SET
+                        start_time = CAST(? AS TIMESTAMP WITH TIME ZONE) + to_microseconds(?),
+                        end_time = CAST(? AS TIMESTAMP WITH TIME ZONE) + to_microseconds(?),
+                        time_offset_secs = ?
+                     WHERE id = ?",
+                    params![parsed("start_time"), micros, parsed("end_time"), micros, offset, flight_id],
+                )?;
+            }
+            conn.execute(
+                "UPDATE flights SET original_metadata = ? WHERE id = ?",
+                params![serde_json::Value::Object(original).to_string(), flight_id],
+            )?;
+            Self::record_integrity(&conn, flight_id, "update");
+        }
+
+        log::debug!("Updated metadata of flight {}", flight_id);
+        self.get_flight_by_id(flight_id)
+    }
+
+    /// Set the location name derived from named sites. A name edited by hand
+    /// (recorded in `original_metadata`) is kept; returns whether it changed.
+    pub fn set_detected_location_name(&self, flight_id: i64, name: Option<&str>) -> Result<bool, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let (current, original): (Option<String>, Option<String>) = conn
+            .query_row(
+                "SELECT location_name, original_metadata FROM flights WHERE id = ?",
+                params![flight_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .ok_or(DatabaseError::FlightNotFound(flight_id))?;
+        let edited = original
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+            .is_some_and(|o| o.get("location_name").is_some());
+        if edited || current.as_deref() == name {
+            return Ok(false);
+        }
+        conn.execute("UPDATE flights SET location_name = ? WHERE id = ?", params![name, flight_id])?;
+        Self::record_integrity(&conn, flight_id, "update");
+        Ok(true)
+    }
+
     // ================================================================
     // TAG MANAGEMENT
     // ================================================================
@@ -1900,6 +4022,7 @@ impl Database {
         )?;
         // Sync manual tags to customizations overlay
         Self::sync_manual_tags_to_customizations(&conn, flight_id)?;
+        Self::touch_flights(&conn, Some(flight_id));
         log::debug!("Added manual tag '{}' to flight {}", trimmed, flight_id);
         Ok(())
     }
@@ -1913,6 +4036,7 @@ impl Database {
         )?;
         // Sync manual tags to customizations overlay
         Self::sync_manual_tags_to_customizations(&conn, flight_id)?;
+        Self::touch_flights(&conn, Some(flight_id));
         log::debug!("Removed tag '{}' from flight {}", tag, flight_id);
         Ok(())
     }
@@ -1948,6 +4072,7 @@ impl Database {
                 params![flight_id, trimmed],
             )?;
         }
+        Self::touch_flights(&conn, Some(flight_id));
         Ok(())
     }
 
@@ -1959,12 +4084,19 @@ impl Database {
             "DELETE FROM flight_tags WHERE tag_type = 'auto'",
             [],
         )?;
+        if removed > 0 {
+            Self::touch_flights(&conn, None);
+        }
         Ok(removed)
     }
 
     /// Get all flight IDs (for bulk operations like tag regeneration)
     pub fn get_all_flight_ids(&self) -> Result<Vec<i64>, DatabaseError> {
         let conn = self.conn.lock().unwrap();
+        Self::flight_ids(&conn)
+    }
+
+    fn flight_ids(conn: &Connection) -> Result<Vec<i64>, DatabaseError> {
         let mut stmt = conn.prepare("SELECT id FROM flights ORDER BY id")?;
         let ids = stmt
             .query_map([], |row| row.get::<_, i64>(0))?
@@ -1972,54 +4104,1486 @@ impl Database {
         Ok(ids)
     }
 
-    // ================================================================
-    // MESSAGE MANAGEMENT
-    // ================================================================
-
-    /// Insert flight messages (tips and warnings) for a flight
-    pub fn insert_flight_messages(&self, flight_id: i64, messages: &[FlightMessage]) -> Result<(), DatabaseError> {
-        if messages.is_empty() {
-            return Ok(());
+    // ================================================================
+    // MESSAGE MANAGEMENT
+    // ================================================================
+
+    /// Insert flight messages (tips and warnings) for a flight
+    pub fn insert_flight_messages(&self, flight_id: i64, messages: &[FlightMessage]) -> Result<(), DatabaseError> {
+        if messages.is_empty() {
+            return Ok(());
+        }
+        let conn = self.conn.lock().unwrap();
+        for msg in messages {
+            // Use INSERT OR IGNORE to avoid duplicate key errors
+            conn.execute(
+                "INSERT OR IGNORE INTO flight_messages (flight_id, timestamp_ms, message_type, message) VALUES (?, ?, ?, ?)",
+                params![flight_id, msg.timestamp_ms, msg.message_type, msg.message],
+            )?;
+        }
+        Self::touch_flights(&conn, Some(flight_id));
+        log::debug!("Inserted {} messages for flight {}", messages.len(), flight_id);
+        Ok(())
+    }
+
+    /// Get all messages for a flight
+    pub fn get_flight_messages(&self, flight_id: i64) -> Result<Vec<FlightMessage>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT timestamp_ms, message_type, message FROM flight_messages WHERE flight_id = ? ORDER BY timestamp_ms",
+        )?;
+        let messages = stmt
+            .query_map(params![flight_id], |row| {
+                Ok(FlightMessage {
+                    timestamp_ms: row.get(0)?,
+                    message_type: row.get(1)?,
+                    message: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(messages)
+    }
+
+    /// Delete all messages for a flight
+    #[allow(dead_code)]
+    pub fn delete_flight_messages(&self, flight_id: i64) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM flight_messages WHERE flight_id = ?",
+            params![flight_id],
+        )?;
+        Ok(())
+    }
+
+    /// Store (or replace) the import validation report for a flight
+    pub fn save_import_report(&self, report: &ImportReport) -> Result<(), DatabaseError> {
+        let coverage_json = serde_json::to_string(&report.field_coverage).unwrap_or_else(|_| "[]".to_string());
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO flight_import_reports
+                (flight_id, format, record_version, decryption, quirk, speed_derived, gps_outliers, point_count, field_coverage, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)",
+            params![
+                report.flight_id,
+                report.format,
+                report.record_version,
+                report.decryption,
+                report.quirk,
+                report.speed_derived,
+                report.gps_outliers as i64,
+                report.point_count as i64,
+                coverage_json,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get the import validation report for a flight (None for flights imported
+    /// before reports were recorded, or created manually)
+    pub fn get_import_report(&self, flight_id: i64) -> Result<Option<ImportReport>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let report = conn
+            .query_row(
+                "SELECT flight_id, format, record_version, decryption, quirk, point_count,
+                        field_coverage, CAST(created_at AS VARCHAR), COALESCE(speed_derived, FALSE),
+                        COALESCE(gps_outliers, 0)
+                 FROM flight_import_reports WHERE flight_id = ?",
+                params![flight_id],
+                |row| {
+                    let coverage_json: String = row.get(6)?;
+                    Ok(ImportReport {
+                        flight_id: row.get(0)?,
+                        format: row.get(1)?,
+                        record_version: row.get(2)?,
+                        decryption: row.get(3)?,
+                        quirk: row.get(4)?,
+                        speed_derived: row.get(8)?,
+                        gps_outliers: row.get::<_, i64>(9)? as usize,
+                        point_count: row.get::<_, i64>(5)? as usize,
+                        field_coverage: serde_json::from_str(&coverage_json).unwrap_or_default(),
+                        created_at: row.get(7)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(report)
+    }
+
+    /// Store the import profile of a flight, keeping only the most recent ones
+    pub fn save_import_profile(&self, profile: &crate::import_profile::ImportProfile) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO import_profiles
+                (flight_id, file_name, format, file_size_bytes, point_count, read_ms, decrypt_ms,
+                 parse_ms, insert_ms, post_process_ms, total_ms, recorded_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)",
+            params![
+                profile.flight_id,
+                profile.file_name,
+                profile.format,
+                profile.file_size_bytes,
+                profile.point_count,
+                profile.read_ms,
+                profile.decrypt_ms,
+                profile.parse_ms,
+                profile.insert_ms,
+                profile.post_process_ms,
+                profile.total_ms,
+            ],
+        )?;
+        conn.execute(
+            "DELETE FROM import_profiles WHERE flight_id NOT IN (
+                SELECT flight_id FROM import_profiles ORDER BY recorded_at DESC LIMIT ?
+             )",
+            params![crate::import_profile::MAX_PROFILES],
+        )?;
+        Ok(())
+    }
+
+    /// All stored import profiles, newest first
+    pub fn get_import_profiles(&self) -> Result<Vec<crate::import_profile::ImportProfile>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT flight_id, file_name, format, file_size_bytes, point_count, read_ms, decrypt_ms,
+                    parse_ms, insert_ms, post_process_ms, total_ms, CAST(recorded_at AS VARCHAR)
+             FROM import_profiles
+             ORDER BY recorded_at DESC, flight_id DESC",
+        )?;
+        let profiles = stmt
+            .query_map([], |row| {
+                Ok(crate::import_profile::ImportProfile {
+                    flight_id: row.get(0)?,
+                    file_name: row.get(1)?,
+                    format: row.get(2)?,
+                    file_size_bytes: row.get(3)?,
+                    point_count: row.get(4)?,
+                    read_ms: row.get(5)?,
+                    decrypt_ms: row.get(6)?,
+                    parse_ms: row.get(7)?,
+                    insert_ms: row.get(8)?,
+                    post_process_ms: row.get(9)?,
+                    total_ms: row.get(10)?,
+                    recorded_at: row.get(11)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(profiles)
+    }
+
+    /// Replace the stored RTH events of a flight
+    pub fn replace_rth_events(&self, flight_id: i64, events: &[RthEvent]) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM flight_rth_events WHERE flight_id = ?", params![flight_id])?;
+        for e in events {
+            conn.execute(
+                "INSERT OR REPLACE INTO flight_rth_events
+                    (flight_id, trigger_ms, trigger_mode, trigger_distance_m, trigger_height_m,
+                     trigger_battery_percent, time_to_land_secs, landing_battery_percent,
+                     landing_distance_from_home_m, landed_at_home, cancelled)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    flight_id,
+                    e.trigger_ms,
+                    e.trigger_mode,
+                    e.trigger_distance_m,
+                    e.trigger_height_m,
+                    e.trigger_battery_percent,
+                    e.time_to_land_secs,
+                    e.landing_battery_percent,
+                    e.landing_distance_from_home_m,
+                    e.landed_at_home,
+                    e.cancelled,
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Get the RTH events of a flight in trigger order
+    pub fn get_rth_events(&self, flight_id: i64) -> Result<Vec<RthEvent>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT trigger_ms, trigger_mode, trigger_distance_m, trigger_height_m,
+                    trigger_battery_percent, time_to_land_secs, landing_battery_percent,
+                    landing_distance_from_home_m, landed_at_home, cancelled
+             FROM flight_rth_events WHERE flight_id = ? ORDER BY trigger_ms",
+        )?;
+        let events = stmt
+            .query_map(params![flight_id], |row| {
+                Ok(RthEvent {
+                    trigger_ms: row.get(0)?,
+                    trigger_mode: row.get(1)?,
+                    trigger_distance_m: row.get(2)?,
+                    trigger_height_m: row.get(3)?,
+                    trigger_battery_percent: row.get(4)?,
+                    time_to_land_secs: row.get(5)?,
+                    landing_battery_percent: row.get(6)?,
+                    landing_distance_from_home_m: row.get(7)?,
+                    landed_at_home: row.get(8)?,
+                    cancelled: row.get(9)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(events)
+    }
+
+    /// Reference for charts, tracks and max-altitude stats (takeoff when unset)
+    pub fn get_altitude_reference(&self) -> AltitudeReference {
+        self.get_setting(ALTITUDE_REFERENCE_KEY)
+            .ok()
+            .flatten()
+            .and_then(|s| AltitudeReference::parse(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn set_altitude_reference(&self, reference: AltitudeReference) -> Result<(), DatabaseError> {
+        self.set_setting(ALTITUDE_REFERENCE_KEY, reference.as_str())
+    }
+
+    pub fn get_duration_rounding(&self) -> DurationRounding {
+        self.get_setting(DURATION_ROUNDING_KEY)
+            .ok()
+            .flatten()
+            .and_then(|s| DurationRounding::parse(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn set_duration_rounding(&self, rounding: DurationRounding) -> Result<(), DatabaseError> {
+        self.set_setting(DURATION_ROUNDING_KEY, rounding.as_str())
+    }
+
+    /// Elevation above mean sea level of a flight's takeoff point: GPS
+    /// altitude minus height over the first samples that have both (None
+    /// for logs without GPS altitude)
+    pub fn get_takeoff_elevation(&self, flight_id: i64) -> Result<Option<f64>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let elevation = conn.query_row(
+            "SELECT AVG(altitude_abs - height)::DOUBLE FROM (
+                 SELECT altitude_abs, height FROM telemetry
+                 WHERE flight_id = ? AND altitude_abs IS NOT NULL AND height IS NOT NULL
+                 ORDER BY timestamp_ms LIMIT 20
+             )",
+            params![flight_id],
+            |row| row.get(0),
+        )?;
+        Ok(elevation)
+    }
+
+    /// Personal altitude/range limits (stored in the settings table)
+    pub fn get_exceedance_limits(&self) -> Result<ExceedanceLimits, DatabaseError> {
+        let parse = |v: Option<String>| v.and_then(|s| s.parse::<f64>().ok()).filter(|v| *v > 0.0);
+        Ok(ExceedanceLimits {
+            max_altitude_m: parse(self.get_setting(LIMIT_MAX_ALTITUDE_KEY)?),
+            max_distance_m: parse(self.get_setting(LIMIT_MAX_DISTANCE_KEY)?),
+        })
+    }
+
+    /// Save the limits and re-evaluate every flight against them.
+    /// Returns the number of exceedances recorded.
+    pub fn set_exceedance_limits(&self, limits: &ExceedanceLimits) -> Result<usize, DatabaseError> {
+        let to_setting = |v: Option<f64>| v.filter(|v| *v > 0.0).map(|v| v.to_string()).unwrap_or_default();
+        self.set_setting(LIMIT_MAX_ALTITUDE_KEY, &to_setting(limits.max_altitude_m))?;
+        self.set_setting(LIMIT_MAX_DISTANCE_KEY, &to_setting(limits.max_distance_m))?;
+        self.refresh_exceedances(None)
+    }
+
+    /// Recompute the exceedance flags of one flight (or all flights when None)
+    /// from the stored max altitude and the telemetry distance from home.
+    pub fn refresh_exceedances(&self, flight_id: Option<i64>) -> Result<usize, DatabaseError> {
+        let limits = self.get_exceedance_limits()?;
+        let conn = self.conn.lock().unwrap();
+
+        let filter = flight_id.map(|id| format!("AND f.id = {}", id)).unwrap_or_default();
+        match flight_id {
+            Some(id) => conn.execute("DELETE FROM flight_exceedances WHERE flight_id = ?", params![id])?,
+            None => conn.execute("DELETE FROM flight_exceedances", params![])?,
+        };
+
+        let mut recorded = 0;
+        if let Some(limit) = limits.max_altitude_m {
+            recorded += conn.execute(
+                &format!(
+                    "INSERT INTO flight_exceedances (flight_id, kind, limit_m, actual_m)
+                     SELECT f.id, 'altitude', ?, f.max_altitude FROM flights f
+                     WHERE f.max_altitude > ? {}",
+                    filter
+                ),
+                params![limit, limit],
+            )?;
+        }
+        if let Some(limit) = limits.max_distance_m {
+            recorded += conn.execute(
+                &format!(
+                    r#"
+                    INSERT INTO flight_exceedances (flight_id, kind, limit_m, actual_m)
+                    SELECT flight_id, 'distance', ?, max_dist FROM (
+                        SELECT
+                            f.id AS flight_id,
+                            MAX(6371000 * 2 * ASIN(SQRT(
+                                POWER(SIN(RADIANS(t.latitude - f.home_lat) / 2), 2) +
+                                COS(RADIANS(f.home_lat)) * COS(RADIANS(t.latitude)) *
+                                POWER(SIN(RADIANS(t.longitude - f.home_lon) / 2), 2)
+                            )))::DOUBLE AS max_dist
+                        FROM flights f
+                        JOIN telemetry t ON f.id = t.flight_id
+                        WHERE f.home_lat IS NOT NULL AND f.home_lon IS NOT NULL
+                          AND NOT (ABS(f.home_lat) < 0.000001 AND ABS(f.home_lon) < 0.000001)
+                          AND t.latitude IS NOT NULL AND t.longitude IS NOT NULL
+                          AND NOT (ABS(t.latitude) < 0.000001 AND ABS(t.longitude) < 0.000001)
+                          {}
+                        GROUP BY f.id
+                    )
+                    WHERE max_dist > ?
+                    "#,
+                    filter
+                ),
+                params![limit, limit],
+            )?;
+        }
+        // The exceedance flag is part of the flight list
+        Self::touch_flights(&conn, flight_id);
+        Ok(recorded)
+    }
+
+    /// Get all currency/recency requirement rules
+    pub fn get_requirements(&self) -> Result<Vec<Requirement>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, metric, min_value, window_days, drone_model
+             FROM requirements ORDER BY created_at, name",
+        )?;
+        let rows = stmt
+            .query_map(params![], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, f64>(3)?,
+                    row.get::<_, i64>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(id, name, metric, min_value, window_days, drone_model)| {
+                let metric = match RequirementMetric::parse(&metric) {
+                    Some(m) => m,
+                    None => {
+                        log::warn!("Skipping requirement {} with unknown metric '{}'", id, metric);
+                        return None;
+                    }
+                };
+                Some(Requirement { id, name, metric, min_value, window_days, drone_model })
+            })
+            .collect())
+    }
+
+    /// Insert or update a requirement rule. A new id is generated when empty.
+    pub fn save_requirement(&self, requirement: &Requirement) -> Result<Requirement, DatabaseError> {
+        let mut saved = requirement.clone();
+        if saved.id.trim().is_empty() {
+            saved.id = uuid::Uuid::new_v4().to_string();
+        }
+        saved.drone_model = saved
+            .drone_model
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty());
+
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO requirements (id, name, metric, min_value, window_days, drone_model)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT (id) DO UPDATE SET
+                name = excluded.name,
+                metric = excluded.metric,
+                min_value = excluded.min_value,
+                window_days = excluded.window_days,
+                drone_model = excluded.drone_model",
+            params![
+                saved.id,
+                saved.name,
+                saved.metric.as_str(),
+                saved.min_value,
+                saved.window_days,
+                saved.drone_model,
+            ],
+        )?;
+        Ok(saved)
+    }
+
+    /// Delete a requirement rule
+    pub fn delete_requirement(&self, id: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM requirements WHERE id = ?", params![id])?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // FLIGHT PLANS
+    // One planned mission per flight; waypoints are stored as JSON.
+    // ========================================================================
+
+    /// Attach a planned mission to a flight, replacing any previous one
+    pub fn save_flight_plan(&self, plan: &FlightPlan) -> Result<(), DatabaseError> {
+        let waypoints = serde_json::to_string(&plan.waypoints).unwrap_or_else(|_| "[]".to_string());
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO flight_plans (flight_id, name, source_format, waypoints)
+             VALUES (?, ?, ?, ?)
+             ON CONFLICT (flight_id) DO UPDATE SET
+                name = excluded.name,
+                source_format = excluded.source_format,
+                waypoints = excluded.waypoints,
+                created_at = CURRENT_TIMESTAMP",
+            params![plan.flight_id, plan.name, plan.source_format, waypoints],
+        )?;
+        Ok(())
+    }
+
+    /// Get the planned mission attached to a flight
+    pub fn get_flight_plan(&self, flight_id: i64) -> Result<Option<FlightPlan>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let row = conn
+            .query_row(
+                "SELECT name, source_format, waypoints, CAST(created_at AS VARCHAR)
+                 FROM flight_plans WHERE flight_id = ?",
+                params![flight_id],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                    ))
+                },
+            )
+            .optional()?;
+        let Some((name, source_format, waypoints, created_at)) = row else {
+            return Ok(None);
+        };
+        Ok(Some(FlightPlan {
+            flight_id,
+            name,
+            source_format,
+            waypoints: serde_json::from_str(&waypoints).unwrap_or_default(),
+            created_at,
+        }))
+    }
+
+    /// Detach the planned mission from a flight
+    pub fn delete_flight_plan(&self, flight_id: i64) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM flight_plans WHERE flight_id = ?", params![flight_id])?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // IMPORT QUEUE
+    // ========================================================================
+
+    /// Add a file to the background import queue
+    pub fn enqueue_import(&self, file_name: &str, file_path: &str, owner: Option<&str>) -> Result<QueuedImport, DatabaseError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        {
+            let conn = self.conn.lock().unwrap();
+            conn.execute(
+                "INSERT INTO import_queue (id, file_name, file_path, status, owner) VALUES (?, ?, ?, 'queued', ?)",
+                params![id, file_name, file_path, owner],
+            )?;
+        }
+        Ok(self
+            .get_import_queue()?
+            .into_iter()
+            .find(|q| q.id == id)
+            .expect("queued import just inserted"))
+    }
+
+    /// All queue entries, oldest first
+    pub fn get_import_queue(&self) -> Result<Vec<QueuedImport>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, file_name, file_path, status, message, flight_id,
+                    CAST(created_at AS VARCHAR), CAST(started_at AS VARCHAR), CAST(finished_at AS VARCHAR), owner
+             FROM import_queue ORDER BY created_at, id",
+        )?;
+        let entries = stmt
+            .query_map(params![], |row| {
+                let status: String = row.get(3)?;
+                Ok(QueuedImport {
+                    id: row.get(0)?,
+                    file_name: row.get(1)?,
+                    file_path: row.get(2)?,
+                    status: QueueStatus::parse(&status),
+                    message: row.get(4)?,
+                    flight_id: row.get(5)?,
+                    created_at: row.get(6)?,
+                    started_at: row.get(7)?,
+                    finished_at: row.get(8)?,
+                    owner: row.get(9)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    /// Whether any entry is waiting
+    pub fn has_queued_imports(&self) -> Result<bool, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM import_queue WHERE status = 'queued'", params![], |row| row.get(0))?;
+        Ok(count > 0)
+    }
+
+    /// Mark the oldest queued entry as running and return it
+    pub fn claim_next_import(&self) -> Result<Option<QueuedImport>, DatabaseError> {
+        let id: Option<String> = {
+            let conn = self.conn.lock().unwrap();
+            let id = conn
+                .query_row(
+                    "SELECT id FROM import_queue WHERE status = 'queued' ORDER BY created_at, id LIMIT 1",
+                    params![],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if let Some(ref id) = id {
+                conn.execute(
+                    "UPDATE import_queue SET status = 'running', started_at = CURRENT_TIMESTAMP WHERE id = ?",
+                    params![id],
+                )?;
+            }
+            id
+        };
+        match id {
+            Some(id) => Ok(self.get_import_queue()?.into_iter().find(|q| q.id == id)),
+            None => Ok(None),
+        }
+    }
+
+    /// Record the outcome of a queued import
+    pub fn complete_queued_import(
+        &self,
+        id: &str,
+        status: QueueStatus,
+        message: &str,
+        flight_id: Option<i64>,
+    ) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE import_queue SET status = ?, message = ?, flight_id = ?, finished_at = CURRENT_TIMESTAMP WHERE id = ?",
+            params![status.as_str(), message, flight_id, id],
+        )?;
+        Ok(())
+    }
+
+    /// Remove finished (done or failed) entries; returns them so spooled files can be cleaned up
+    pub fn clear_finished_imports(&self) -> Result<Vec<QueuedImport>, DatabaseError> {
+        let finished: Vec<QueuedImport> = self
+            .get_import_queue()?
+            .into_iter()
+            .filter(|q| matches!(q.status, QueueStatus::Done | QueueStatus::Failed))
+            .collect();
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM import_queue WHERE status IN ('done', 'failed')", params![])?;
+        Ok(finished)
+    }
+
+    /// Mark queued and running imports failed so they aren't resumed (safe mode)
+    pub fn cancel_queued_imports(&self) -> Result<usize, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let cancelled = conn.execute(
+            "UPDATE import_queue SET status = 'failed', message = 'Cancelled in safe mode'
+             WHERE status IN ('queued', 'running')",
+            params![],
+        )?;
+        Ok(cancelled)
+    }
+
+    /// Entries left running by a crash or restart go back to the queue
+    fn requeue_interrupted_imports(&self) {
+        let conn = self.conn.lock().unwrap();
+        match conn.execute(
+            "UPDATE import_queue SET status = 'queued', started_at = NULL WHERE status = 'running'",
+            params![],
+        ) {
+            Ok(0) => {}
+            Ok(n) => log::info!("Re-queued {} interrupted queued imports", n),
+            Err(e) => log::warn!("Failed to re-queue interrupted imports: {}", e),
+        }
+    }
+
+    // ========================================================================
+    // JOBS
+    // Jobs / contracts; job_flights maps each flight to at most one job.
+    // ========================================================================
+
+    /// Get all jobs with their flight counts, most recent first
+    pub fn get_jobs(&self) -> Result<Vec<Job>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT j.id, j.name, j.client, j.site, j.start_date, j.end_date, j.notes, COUNT(jf.flight_id)
+             FROM jobs j LEFT JOIN job_flights jf ON jf.job_id = j.id
+             GROUP BY j.id, j.name, j.client, j.site, j.start_date, j.end_date, j.notes, j.created_at
+             ORDER BY COALESCE(j.start_date, '') DESC, j.created_at DESC",
+        )?;
+        let jobs = stmt
+            .query_map(params![], |row| {
+                Ok(Job {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    client: row.get(2)?,
+                    site: row.get(3)?,
+                    start_date: row.get(4)?,
+                    end_date: row.get(5)?,
+                    notes: row.get(6)?,
+                    flight_count: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(jobs)
+    }
+
+    /// Get one job by id
+    pub fn get_job(&self, id: &str) -> Result<Option<Job>, DatabaseError> {
+        Ok(self.get_jobs()?.into_iter().find(|j| j.id == id))
+    }
+
+    /// Insert or update a job. A new id is generated when empty.
+    pub fn save_job(&self, job: &Job) -> Result<Job, DatabaseError> {
+        let mut saved = job.clone();
+        if saved.id.trim().is_empty() {
+            saved.id = uuid::Uuid::new_v4().to_string();
+        }
+        let clean = |v: &Option<String>| v.as_ref().map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        saved.client = clean(&saved.client);
+        saved.site = clean(&saved.site);
+        saved.start_date = clean(&saved.start_date);
+        saved.end_date = clean(&saved.end_date);
+        saved.notes = clean(&saved.notes);
+
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO jobs (id, name, client, site, start_date, end_date, notes)
+             VALUES (?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT (id) DO UPDATE SET
+                name = excluded.name,
+                client = excluded.client,
+                site = excluded.site,
+                start_date = excluded.start_date,
+                end_date = excluded.end_date,
+                notes = excluded.notes",
+            params![saved.id, saved.name, saved.client, saved.site, saved.start_date, saved.end_date, saved.notes],
+        )?;
+        saved.flight_count = conn.query_row(
+            "SELECT COUNT(*) FROM job_flights WHERE job_id = ?",
+            params![saved.id],
+            |row| row.get(0),
+        )?;
+        Ok(saved)
+    }
+
+    /// Delete a job; its flights are kept and become unassigned
+    pub fn delete_job(&self, id: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM job_flights WHERE job_id = ?", params![id])?;
+        conn.execute("DELETE FROM jobs WHERE id = ?", params![id])?;
+        Ok(())
+    }
+
+    /// Assign flights to a job (moving them out of any other job), or
+    /// unassign them when `job_id` is None
+    pub fn assign_flights_to_job(&self, job_id: Option<&str>, flight_ids: &[i64]) -> Result<usize, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut changed = 0;
+        for flight_id in flight_ids {
+            changed += match job_id {
+                Some(job_id) => conn.execute(
+                    "INSERT INTO job_flights (flight_id, job_id) VALUES (?, ?)
+                     ON CONFLICT (flight_id) DO UPDATE SET job_id = excluded.job_id",
+                    params![flight_id, job_id],
+                )?,
+                None => conn.execute("DELETE FROM job_flights WHERE flight_id = ?", params![flight_id])?,
+            };
+        }
+        Ok(changed)
+    }
+
+    /// Job a flight is assigned to, if any
+    pub fn get_flight_job_id(&self, flight_id: i64) -> Result<Option<String>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        Ok(conn
+            .query_row("SELECT job_id FROM job_flights WHERE flight_id = ?", params![flight_id], |row| row.get(0))
+            .optional()?)
+    }
+
+    /// IDs of the flights assigned to a job
+    pub fn get_job_flight_ids(&self, job_id: &str) -> Result<Vec<i64>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT flight_id FROM job_flights WHERE job_id = ? ORDER BY flight_id")?;
+        let ids = stmt
+            .query_map(params![job_id], |row| row.get(0))?
+            .collect::<Result<Vec<i64>, _>>()?;
+        Ok(ids)
+    }
+
+    // ========================================================================
+    // FLEET
+    // Maintenance log and per-flight battery condition for the fleet overview.
+    // ========================================================================
+
+    /// Maintenance records, newest first, optionally for one serial
+    pub fn get_maintenance_records(&self, serial: Option<&str>) -> Result<Vec<MaintenanceRecord>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, equipment_type, serial, performed_at, notes
+             FROM maintenance_log
+             ORDER BY performed_at DESC, created_at DESC",
+        )?;
+        let records = stmt
+            .query_map(params![], |row| {
+                Ok(MaintenanceRecord {
+                    id: row.get(0)?,
+                    equipment_type: row.get(1)?,
+                    serial: row.get(2)?,
+                    performed_at: row.get(3)?,
+                    notes: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<MaintenanceRecord>, _>>()?;
+        Ok(match serial {
+            Some(serial) => records.into_iter().filter(|r| r.serial.eq_ignore_ascii_case(serial.trim())).collect(),
+            None => records,
+        })
+    }
+
+    /// Insert or update a maintenance record. A new id is generated when empty.
+    pub fn save_maintenance_record(&self, record: &MaintenanceRecord) -> Result<MaintenanceRecord, DatabaseError> {
+        let mut saved = record.clone();
+        if saved.id.trim().is_empty() {
+            saved.id = uuid::Uuid::new_v4().to_string();
+        }
+        saved.serial = saved.serial.trim().to_string();
+        saved.performed_at = saved.performed_at.trim().to_string();
+        saved.notes = saved.notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO maintenance_log (id, equipment_type, serial, performed_at, notes)
+             VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT (id) DO UPDATE SET
+                equipment_type = excluded.equipment_type,
+                serial = excluded.serial,
+                performed_at = excluded.performed_at,
+                notes = excluded.notes",
+            params![saved.id, saved.equipment_type, saved.serial, saved.performed_at, saved.notes],
+        )?;
+        Ok(saved)
+    }
+
+    /// Delete a maintenance record
+    pub fn delete_maintenance_record(&self, id: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM maintenance_log WHERE id = ?", params![id])?;
+        Ok(())
+    }
+
+    /// Discharge rate, full-charge capacity and take-off voltage sag of every
+    /// flight with a battery serial, keyed by upper-cased serial and ordered
+    /// by start time. Sag is the resting voltage before take-off minus the
+    /// lowest voltage in the first 30 s above 2 m.
+    pub fn get_battery_flight_conditions(&self) -> Result<HashMap<String, Vec<BatteryFlightCondition>>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            WITH takeoff AS (
+                SELECT flight_id, MIN(timestamp_ms) AS t0
+                FROM telemetry
+                WHERE height > 2
+                GROUP BY flight_id
+            )
+            SELECT
+                UPPER(TRIM(f.battery_serial)),
+                COALESCE(f.duration_secs, 0)::DOUBLE,
+                (MAX(t.battery_percent) - MIN(t.battery_percent))::DOUBLE,
+                MAX(CASE WHEN t.battery_full_capacity > 0 THEN t.battery_full_capacity END)::DOUBLE,
+                (MAX(t.battery_voltage) FILTER (WHERE t.timestamp_ms < k.t0 AND t.battery_voltage > 0)
+                    - MIN(t.battery_voltage) FILTER (WHERE t.timestamp_ms BETWEEN k.t0 AND k.t0 + 30000 AND t.battery_voltage > 0))::DOUBLE
+            FROM flights f
+            JOIN telemetry t ON t.flight_id = f.id
+            LEFT JOIN takeoff k ON k.flight_id = f.id
+            WHERE f.battery_serial IS NOT NULL AND TRIM(f.battery_serial) <> ''
+            GROUP BY f.battery_serial, f.id, f.duration_secs, f.start_time, k.t0
+            ORDER BY f.start_time ASC
+            "#,
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                let duration_secs: f64 = row.get(1)?;
+                let delta_percent: Option<f64> = row.get(2)?;
+                Ok((
+                    row.get::<_, String>(0)?,
+                    BatteryFlightCondition {
+                        drain_per_min: delta_percent.filter(|_| duration_secs >= 60.0).map(|d| d / (duration_secs / 60.0)),
+                        full_capacity_mah: row.get(3)?,
+                        sag_v: row.get::<_, Option<f64>>(4)?.filter(|v| *v >= 0.0),
+                    },
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut by_serial: HashMap<String, Vec<BatteryFlightCondition>> = HashMap::new();
+        for (serial, condition) in rows {
+            by_serial.entry(serial).or_default().push(condition);
+        }
+        Ok(by_serial)
+    }
+
+    /// Logged flights with a home point and no stored weather, newest first:
+    /// (flight id, home latitude, home longitude, start time)
+    pub fn get_flights_missing_weather(&self, limit: usize) -> Result<Vec<(i64, f64, f64, chrono::DateTime<chrono::Utc>)>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT f.id, f.home_lat, f.home_lon, f.start_time
+             FROM flights f
+             LEFT JOIN flight_weather w ON w.flight_id = f.id
+             WHERE w.flight_id IS NULL
+               AND NOT COALESCE(f.is_manual, FALSE)
+               AND f.start_time IS NOT NULL
+               AND f.home_lat IS NOT NULL AND f.home_lon IS NOT NULL
+               AND NOT (f.home_lat = 0 AND f.home_lon = 0)
+             ORDER BY f.start_time DESC
+             LIMIT ?",
+        )?;
+        let rows = stmt
+            .query_map(params![limit as i64], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Store the weather a flight was flown in
+    pub fn save_flight_weather(&self, weather: &FlightWeather) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO flight_weather (flight_id, temperature_c, wind_speed_kmh, wind_gusts_kmh)
+             VALUES (?, ?, ?, ?)",
+            params![weather.flight_id, weather.temperature_c, weather.wind_speed_kmh, weather.wind_gusts_kmh],
+        )?;
+        Ok(())
+    }
+
+    /// Battery drain and weather of every logged flight of at least two
+    /// minutes with a battery serial, for endurance benchmarks
+    pub fn get_endurance_samples(&self) -> Result<Vec<EnduranceSample>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                COALESCE(NULLIF(TRIM(f.drone_model), ''), 'Unknown'),
+                UPPER(TRIM(f.battery_serial)),
+                f.duration_secs::DOUBLE,
+                (MAX(t.battery_percent) - MIN(t.battery_percent))::DOUBLE,
+                w.wind_speed_kmh,
+                w.temperature_c
+            FROM flights f
+            JOIN telemetry t ON t.flight_id = f.id
+            LEFT JOIN flight_weather w ON w.flight_id = f.id
+            WHERE f.battery_serial IS NOT NULL AND TRIM(f.battery_serial) <> ''
+              AND NOT COALESCE(f.is_manual, FALSE)
+              AND f.duration_secs >= 120
+            GROUP BY f.id, f.drone_model, f.battery_serial, f.duration_secs, w.wind_speed_kmh, w.temperature_c
+            "#,
+        )?;
+        let samples = stmt
+            .query_map([], |row| {
+                Ok(EnduranceSample {
+                    aircraft: row.get(0)?,
+                    battery_serial: row.get(1)?,
+                    duration_secs: row.get(2)?,
+                    drain_percent: row.get::<_, Option<f64>>(3)?.unwrap_or(0.0),
+                    wind_speed_kmh: row.get(4)?,
+                    temperature_c: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(samples.into_iter().filter(|s| s.drain_percent > 0.0).collect())
+    }
+
+    // ========================================================================
+    // ATTACHMENTS
+    // Files are copied into the attachments folder under a generated name;
+    // the table keeps the original name and the optional flight link.
+    // ========================================================================
+
+    /// Store a document, optionally linked to a flight
+    pub fn add_attachment(
+        &self,
+        flight_id: Option<i64>,
+        file_name: &str,
+        data: &[u8],
+        description: Option<&str>,
+    ) -> Result<Attachment, DatabaseError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let file_name = std::path::Path::new(file_name)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| "attachment".to_string());
+        let stored_name = format!("{}_{}", id, sanitize_attachment_name(&file_name));
+        let content_type = attachment_content_type(&file_name).to_string();
+        let description = description.map(|d| d.trim().to_string()).filter(|d| !d.is_empty());
+
+        let conn = self.conn.lock().unwrap();
+        if let Some(fid) = flight_id {
+            let exists: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM flights WHERE id = ?",
+                params![fid],
+                |row| row.get(0),
+            )?;
+            if exists == 0 {
+                return Err(DatabaseError::FlightNotFound(fid));
+            }
+        }
+
+        fs::create_dir_all(&self.attachments_dir)?;
+        let path = self.attachments_dir.join(&stored_name);
+        fs::write(&path, data)?;
+
+        if let Err(e) = conn.execute(
+            "INSERT INTO attachments (id, flight_id, file_name, stored_name, content_type, size_bytes, description)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            params![id, flight_id, file_name, stored_name, content_type, data.len() as i64, description],
+        ) {
+            let _ = fs::remove_file(&path);
+            return Err(e.into());
+        }
+
+        log::info!("Stored attachment '{}' ({} bytes) as {}", file_name, data.len(), stored_name);
+        Ok(Attachment {
+            id,
+            flight_id,
+            file_name,
+            content_type,
+            size_bytes: data.len() as i64,
+            description,
+            created_at: None,
+        })
+    }
+
+    /// List attachments of one flight, or every attachment when `flight_id` is None
+    pub fn get_attachments(&self, flight_id: Option<i64>) -> Result<Vec<Attachment>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, flight_id, file_name, content_type, size_bytes, description, CAST(created_at AS VARCHAR)
+             FROM attachments WHERE ? IS NULL OR flight_id = ? ORDER BY created_at DESC",
+        )?;
+        let attachments = stmt
+            .query_map(params![flight_id, flight_id], |row| {
+                Ok(Attachment {
+                    id: row.get(0)?,
+                    flight_id: row.get(1)?,
+                    file_name: row.get(2)?,
+                    content_type: row.get(3)?,
+                    size_bytes: row.get(4)?,
+                    description: row.get(5)?,
+                    created_at: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(attachments)
+    }
+
+    /// Look up an attachment and the path of its stored file
+    pub fn get_attachment_file(&self, id: &str) -> Result<Option<(Attachment, PathBuf)>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let row = conn
+            .query_row(
+                "SELECT id, flight_id, file_name, content_type, size_bytes, description,
+                        CAST(created_at AS VARCHAR), stored_name
+                 FROM attachments WHERE id = ?",
+                params![id],
+                |row| {
+                    Ok((
+                        Attachment {
+                            id: row.get(0)?,
+                            flight_id: row.get(1)?,
+                            file_name: row.get(2)?,
+                            content_type: row.get(3)?,
+                            size_bytes: row.get(4)?,
+                            description: row.get(5)?,
+                            created_at: row.get(6)?,
+                        },
+                        row.get::<_, String>(7)?,
+                    ))
+                },
+            )
+            .optional()?;
+        match row {
+            Some((attachment, stored_name)) if is_plain_file_name(&stored_name) => {
+                Ok(Some((attachment, self.attachments_dir.join(stored_name))))
+            }
+            Some((_, stored_name)) => {
+                log::warn!("Refusing attachment {} with unsafe stored name {:?}", id, stored_name);
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Delete an attachment and its stored file
+    pub fn delete_attachment(&self, id: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let stored_name: Option<String> = conn
+            .query_row(
+                "SELECT stored_name FROM attachments WHERE id = ?",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        conn.execute("DELETE FROM attachments WHERE id = ?", params![id])?;
+        if let Some(name) = stored_name {
+            if !is_plain_file_name(&name) {
+                log::warn!("Not removing attachment file with unsafe stored name {:?}", name);
+                return Ok(());
+            }
+            let path = self.attachments_dir.join(name);
+            if let Err(e) = fs::remove_file(&path) {
+                log::warn!("Failed to remove attachment file {:?}: {}", path, e);
+            }
+        }
+        Ok(())
+    }
+
+    // ========================================================================
+    // CHECKLISTS
+    // ========================================================================
+
+    /// Get all checklist templates
+    pub fn get_checklist_templates(&self) -> Result<Vec<ChecklistTemplate>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, phase, items FROM checklist_templates ORDER BY phase DESC, name",
+        )?;
+        let templates = stmt
+            .query_map(params![], |row| {
+                let items: String = row.get(3)?;
+                Ok(ChecklistTemplate {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    phase: row.get(2)?,
+                    items: serde_json::from_str(&items).unwrap_or_default(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(templates)
+    }
+
+    /// Insert or update a checklist template. A new id is generated when empty.
+    pub fn save_checklist_template(&self, template: &ChecklistTemplate) -> Result<ChecklistTemplate, DatabaseError> {
+        let mut saved = template.clone();
+        if saved.id.trim().is_empty() {
+            saved.id = uuid::Uuid::new_v4().to_string();
+        }
+        saved.items = saved
+            .items
+            .iter()
+            .map(|i| i.trim().to_string())
+            .filter(|i| !i.is_empty())
+            .collect();
+        let items = serde_json::to_string(&saved.items).unwrap_or_else(|_| "[]".to_string());
+
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO checklist_templates (id, name, phase, items) VALUES (?, ?, ?, ?)
+             ON CONFLICT (id) DO UPDATE SET
+                name = excluded.name,
+                phase = excluded.phase,
+                items = excluded.items",
+            params![saved.id, saved.name, saved.phase, items],
+        )?;
+        Ok(saved)
+    }
+
+    /// Delete a checklist template. Completed records keep their copy of the name.
+    pub fn delete_checklist_template(&self, id: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM checklist_templates WHERE id = ?", params![id])?;
+        Ok(())
+    }
+
+    /// Store a completed checklist. `completed_at` defaults to now.
+    pub fn record_checklist(&self, record: &ChecklistRecord) -> Result<ChecklistRecord, DatabaseError> {
+        let mut saved = record.clone();
+        if saved.id.trim().is_empty() {
+            saved.id = uuid::Uuid::new_v4().to_string();
+        }
+        if saved.completed_at.is_none() {
+            saved.completed_at = Some(chrono::Utc::now().to_rfc3339());
+        }
+        let items = serde_json::to_string(&saved.items).unwrap_or_else(|_| "[]".to_string());
+
+        let conn = self.conn.lock().unwrap();
+        if let Some(fid) = saved.flight_id {
+            let exists: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM flights WHERE id = ?",
+                params![fid],
+                |row| row.get(0),
+            )?;
+            if exists == 0 {
+                return Err(DatabaseError::FlightNotFound(fid));
+            }
+        }
+        conn.execute(
+            "INSERT OR REPLACE INTO checklist_records
+                (id, template_id, template_name, phase, flight_id, session, items, started_at, completed_at, notes)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                saved.id,
+                saved.template_id,
+                saved.template_name,
+                saved.phase,
+                saved.flight_id,
+                saved.session,
+                items,
+                saved.started_at,
+                saved.completed_at,
+                saved.notes,
+            ],
+        )?;
+        Ok(saved)
+    }
+
+    /// Get completed checklists for a flight, or all of them when `flight_id` is None
+    pub fn get_checklist_records(&self, flight_id: Option<i64>) -> Result<Vec<ChecklistRecord>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let filter = flight_id.map(|id| format!("WHERE flight_id = {}", id)).unwrap_or_default();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, template_id, template_name, phase, flight_id, session, items, started_at, completed_at, notes
+             FROM checklist_records {} ORDER BY completed_at",
+            filter
+        ))?;
+        let records = stmt
+            .query_map(params![], |row| {
+                let items: String = row.get(6)?;
+                Ok(ChecklistRecord {
+                    id: row.get(0)?,
+                    template_id: row.get(1)?,
+                    template_name: row.get(2)?,
+                    phase: row.get(3)?,
+                    flight_id: row.get(4)?,
+                    session: row.get(5)?,
+                    items: serde_json::from_str(&items).unwrap_or_default(),
+                    started_at: row.get(7)?,
+                    completed_at: row.get(8)?,
+                    notes: row.get(9)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(records)
+    }
+
+    /// Delete a completed checklist
+    pub fn delete_checklist_record(&self, id: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM checklist_records WHERE id = ?", params![id])?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // FLIGHT PREVIEWS
+    // ========================================================================
+
+    /// Store (or replace) the cached preview of a flight
+    pub fn save_flight_preview(&self, preview: &FlightPreview) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO flight_previews
+                (flight_id, track, altitude_sparkline, battery_sparkline, thumbnail_svg, updated_at)
+             VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP)",
+            params![
+                preview.flight_id,
+                serde_json::to_string(&preview.track).unwrap_or_else(|_| "[]".to_string()),
+                serde_json::to_string(&preview.altitude_sparkline).unwrap_or_else(|_| "[]".to_string()),
+                serde_json::to_string(&preview.battery_sparkline).unwrap_or_else(|_| "[]".to_string()),
+                preview.thumbnail_svg,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get cached previews for the given flights, or for every flight when
+    /// `flight_ids` is None. Flights without a cached preview are omitted.
+    pub fn get_flight_previews(&self, flight_ids: Option<&[i64]>) -> Result<Vec<FlightPreview>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let filter = match flight_ids {
+            Some([]) => return Ok(Vec::new()),
+            Some(ids) => format!(
+                "WHERE flight_id IN ({})",
+                ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",")
+            ),
+            None => String::new(),
+        };
+        let mut stmt = conn.prepare(&format!(
+            "SELECT flight_id, track, altitude_sparkline, battery_sparkline, thumbnail_svg
+             FROM flight_previews {} ORDER BY flight_id",
+            filter
+        ))?;
+        let previews = stmt
+            .query_map(params![], |row| {
+                let track: String = row.get(1)?;
+                let altitude: String = row.get(2)?;
+                let battery: String = row.get(3)?;
+                Ok(FlightPreview {
+                    flight_id: row.get(0)?,
+                    track: serde_json::from_str(&track).unwrap_or_default(),
+                    altitude_sparkline: serde_json::from_str(&altitude).unwrap_or_default(),
+                    battery_sparkline: serde_json::from_str(&battery).unwrap_or_default(),
+                    thumbnail_svg: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(previews)
+    }
+
+    // ========================================================================
+    // AUDIT LOG
+    // ========================================================================
+
+    /// Record a data-modifying action. Failures are logged, never returned, so
+    /// auditing can't break the action itself.
+    pub fn audit(&self, actor: &str, action: &str, target: Option<&str>, details: serde_json::Value) {
+        let conn = self.conn.lock().unwrap();
+        let details = if details.is_null() { None } else { Some(details.to_string()) };
+        if let Err(e) = conn.execute(
+            "INSERT INTO audit_log (id, timestamp, actor, action, target, details)
+             VALUES (?, CURRENT_TIMESTAMP, ?, ?, ?, ?)",
+            params![uuid::Uuid::new_v4().to_string(), actor, action, target, details],
+        ) {
+            log::warn!("Failed to write audit log entry '{}': {}", action, e);
+        }
+    }
+
+    /// Get audit log entries, newest first, optionally filtered by action
+    pub fn get_audit_log(
+        &self,
+        limit: usize,
+        offset: usize,
+        action: Option<&str>,
+    ) -> Result<Vec<AuditEntry>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, CAST(timestamp AS VARCHAR), actor, action, target, details
+             FROM audit_log
+             WHERE ? IS NULL OR action = ?
+             ORDER BY timestamp DESC
+             LIMIT ? OFFSET ?",
+        )?;
+        let entries = stmt
+            .query_map(params![action, action, limit as i64, offset as i64], |row| {
+                let details: Option<String> = row.get(5)?;
+                Ok(AuditEntry {
+                    id: row.get(0)?,
+                    timestamp: row.get(1)?,
+                    actor: row.get(2)?,
+                    action: row.get(3)?,
+                    target: row.get(4)?,
+                    details: details
+                        .and_then(|d| serde_json::from_str(&d).ok())
+                        .unwrap_or(serde_json::Value::Null),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    // ========================================================================
+    // INTEGRITY CHAIN
+    // ========================================================================
+
+    /// Raw log hash and record hash of each flight (all flights when `flight_id` is None)
+    fn flight_integrity_hashes(
+        conn: &Connection,
+        flight_id: Option<i64>,
+    ) -> Result<HashMap<i64, (Option<String>, String)>, DatabaseError> {
+        let mut stmt = conn.prepare(
+            "SELECT id, file_hash, file_name, display_name, notes, drone_model, drone_serial, aircraft_name,
+                    battery_serial, pilot, location_name, epoch_ms(start_time), epoch_ms(end_time),
+                    duration_secs, total_distance, max_altitude, max_speed, home_lat, home_lon, point_count
+             FROM flights
+             WHERE ? IS NULL OR id = ?",
+        )?;
+        let hashes = stmt
+            .query_map(params![flight_id, flight_id], |row| {
+                let values = serde_json::json!({
+                    "file_name": row.get::<_, Option<String>>(2)?,
+                    "display_name": row.get::<_, Option<String>>(3)?,
+                    "notes": row.get::<_, Option<String>>(4)?,
+                    "drone_model": row.get::<_, Option<String>>(5)?,
+                    "drone_serial": row.get::<_, Option<String>>(6)?,
+                    "aircraft_name": row.get::<_, Option<String>>(7)?,
+                    "battery_serial": row.get::<_, Option<String>>(8)?,
+                    "pilot": row.get::<_, Option<String>>(9)?,
+                    "location_name": row.get::<_, Option<String>>(10)?,
+                    "start_time_ms": row.get::<_, Option<i64>>(11)?,
+                    "end_time_ms": row.get::<_, Option<i64>>(12)?,
+                    "duration_secs": row.get::<_, Option<f64>>(13)?,
+                    "total_distance": row.get::<_, Option<f64>>(14)?,
+                    "max_altitude": row.get::<_, Option<f64>>(15)?,
+                    "max_speed": row.get::<_, Option<f64>>(16)?,
+                    "home_lat": row.get::<_, Option<f64>>(17)?,
+                    "home_lon": row.get::<_, Option<f64>>(18)?,
+                    "point_count": row.get::<_, Option<i64>>(19)?,
+                });
+                let record_hash = integrity::record_hash(values.as_object().expect("json object"));
+                Ok((row.get::<_, i64>(0)?, (row.get::<_, Option<String>>(1)?, record_hash)))
+            })?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(hashes)
+    }
+
+    /// Append an entry for a flight to the integrity chain. Deletions are only
+    /// recorded for flights already in the chain, and nothing is recorded for
+    /// a flight that no longer exists.
+    fn append_integrity_entry(conn: &Connection, flight_id: i64, event: &str) -> Result<(), DatabaseError> {
+        let (file_hash, record_hash) = if event == "delete" {
+            let recorded: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM integrity_chain WHERE flight_id = ?",
+                params![flight_id],
+                |row| row.get(0),
+            )?;
+            let file_hash: Option<Option<String>> = conn
+                .query_row("SELECT file_hash FROM flights WHERE id = ?", params![flight_id], |row| row.get(0))
+                .optional()?;
+            match file_hash {
+                Some(file_hash) if recorded > 0 => (file_hash, None),
+                _ => return Ok(()),
+            }
+        } else {
+            match Self::flight_integrity_hashes(conn, Some(flight_id))?.remove(&flight_id) {
+                Some((file_hash, record_hash)) => (file_hash, Some(record_hash)),
+                None => return Ok(()),
+            }
+        };
+
+        let (last_seq, prev_hash): (i64, String) = conn
+            .query_row(
+                "SELECT seq, entry_hash FROM integrity_chain ORDER BY seq DESC LIMIT 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .unwrap_or((0, integrity::GENESIS_HASH.to_string()));
+        let seq = last_seq + 1;
+        let recorded_at = chrono::Utc::now().to_rfc3339();
+        let entry_hash = integrity::entry_hash(
+            &prev_hash,
+            seq,
+            &recorded_at,
+            flight_id,
+            event,
+            file_hash.as_deref(),
+            record_hash.as_deref(),
+        );
+        conn.execute(
+            "INSERT INTO integrity_chain (seq, recorded_at, flight_id, event, file_hash, record_hash, prev_hash, entry_hash)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            params![seq, recorded_at, flight_id, event, file_hash, record_hash, prev_hash, entry_hash],
+        )?;
+        Ok(())
+    }
+
+    /// Record a flight change in the integrity chain and for delta sync
+    /// (`updated_at`, or a tombstone for deletes). Failures are logged, so
+    /// the change itself still goes through (and shows up on verification).
+    fn record_integrity(conn: &Connection, flight_id: i64, event: &str) {
+        if let Err(e) = Self::append_integrity_entry(conn, flight_id, event) {
+            log::warn!("Failed to record '{}' of flight {} in the integrity chain: {}", event, flight_id, e);
+        }
+        if event == "delete" {
+            Self::record_tombstones(conn, &[flight_id]);
+        } else {
+            Self::touch_flights(conn, Some(flight_id));
+        }
+    }
+
+    /// Mark a flight (or all flights) as changed for delta sync. A flight id
+    /// that comes back (re-import, restore) loses its tombstone.
+    fn touch_flights(conn: &Connection, flight_id: Option<i64>) {
+        let result = conn
+            .execute(
+                "UPDATE flights SET updated_at = CURRENT_TIMESTAMP WHERE ? IS NULL OR id = ?",
+                params![flight_id, flight_id],
+            )
+            .and_then(|_| match flight_id {
+                Some(id) => conn.execute("DELETE FROM flight_tombstones WHERE flight_id = ?", params![id]),
+                None => Ok(0),
+            });
+        if let Err(e) = result {
+            log::warn!("Failed to mark flight {:?} as changed: {}", flight_id, e);
         }
-        let conn = self.conn.lock().unwrap();
-        for msg in messages {
-            // Use INSERT OR IGNORE to avoid duplicate key errors
-            conn.execute(
-                "INSERT OR IGNORE INTO flight_messages (flight_id, timestamp_ms, message_type, message) VALUES (?, ?, ?, ?)",
-                params![flight_id, msg.timestamp_ms, msg.message_type, msg.message],
-            )?;
+    }
+
+    /// Remember deleted flights for delta sync
+    fn record_tombstones(conn: &Connection, flight_ids: &[i64]) {
+        for flight_id in flight_ids {
+            if let Err(e) = conn.execute(
+                "INSERT OR REPLACE INTO flight_tombstones (flight_id, deleted_at) VALUES (?, CURRENT_TIMESTAMP)",
+                params![flight_id],
+            ) {
+                log::warn!("Failed to record deletion of flight {}: {}", flight_id, e);
+            }
         }
-        log::debug!("Inserted {} messages for flight {}", messages.len(), flight_id);
-        Ok(())
     }
 
-    /// Get all messages for a flight
-    pub fn get_flight_messages(&self, flight_id: i64) -> Result<Vec<FlightMessage>, DatabaseError> {
+    /// Integrity chain entries, oldest first, optionally for one flight
+    pub fn get_integrity_chain(&self, flight_id: Option<i64>) -> Result<Vec<IntegrityEntry>, DatabaseError> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT timestamp_ms, message_type, message FROM flight_messages WHERE flight_id = ? ORDER BY timestamp_ms",
+            "SELECT seq, recorded_at, flight_id, event, file_hash, record_hash, prev_hash, entry_hash
+             FROM integrity_chain
+             WHERE ? IS NULL OR flight_id = ?
+             ORDER BY seq",
         )?;
-        let messages = stmt
-            .query_map(params![flight_id], |row| {
-                Ok(FlightMessage {
-                    timestamp_ms: row.get(0)?,
-                    message_type: row.get(1)?,
-                    message: row.get(2)?,
+        let entries = stmt
+            .query_map(params![flight_id, flight_id], |row| {
+                Ok(IntegrityEntry {
+                    seq: row.get(0)?,
+                    recorded_at: row.get(1)?,
+                    flight_id: row.get(2)?,
+                    event: row.get(3)?,
+                    file_hash: row.get(4)?,
+                    record_hash: row.get(5)?,
+                    prev_hash: row.get(6)?,
+                    entry_hash: row.get(7)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
-        Ok(messages)
+        Ok(entries)
     }
 
-    /// Delete all messages for a flight
-    #[allow(dead_code)]
-    pub fn delete_flight_messages(&self, flight_id: i64) -> Result<(), DatabaseError> {
+    /// Check the whole chain and every flight against it
+    pub fn verify_integrity(&self) -> Result<IntegrityReport, DatabaseError> {
+        let entries = self.get_integrity_chain(None)?;
         let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "DELETE FROM flight_messages WHERE flight_id = ?",
-            params![flight_id],
-        )?;
-        Ok(())
+        let flights = Self::flight_integrity_hashes(&conn, None)?;
+        Ok(integrity::verify(&entries, &flights))
+    }
+
+    /// Add a 'baseline' entry for each flight not yet in the chain (flights
+    /// imported before it existed). Returns the flights added.
+    pub fn seal_unrecorded_flights(&self) -> Result<Vec<i64>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let flight_ids: Vec<i64> = conn
+            .prepare(
+                "SELECT id FROM flights
+                 WHERE id NOT IN (SELECT flight_id FROM integrity_chain)
+                 ORDER BY id",
+            )?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        for &flight_id in &flight_ids {
+            Self::append_integrity_entry(&conn, flight_id, "baseline")?;
+        }
+        Ok(flight_ids)
     }
 
     // ========================================================================
@@ -2233,6 +5797,50 @@ impl Database {
         Ok((battery_names, aircraft_names))
     }
 
+    /// Set the flights and time an aircraft flew before this logbook.
+    /// Zero flights and time removes the seed.
+    pub fn set_airframe_seed(&self, seed: &AirframeSeed) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let serial_upper = seed.serial.trim().to_uppercase();
+        if seed.prior_duration_secs <= 0.0 && seed.prior_flights <= 0 {
+            conn.execute("DELETE FROM airframe_seeds WHERE serial = ?", params![serial_upper])?;
+            log::info!("Removed airframe seed for serial {}", serial_upper);
+        } else {
+            let notes = seed.notes.as_deref().map(str::trim).filter(|n| !n.is_empty());
+            conn.execute(
+                "INSERT OR REPLACE INTO airframe_seeds (serial, prior_duration_secs, prior_flights, notes, updated_at)
+                 VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP)",
+                params![serial_upper, seed.prior_duration_secs, seed.prior_flights, notes],
+            )?;
+            log::info!(
+                "Set airframe seed for serial {}: {:.1}h in {} flights",
+                serial_upper,
+                seed.prior_duration_secs / 3600.0,
+                seed.prior_flights
+            );
+        }
+        Ok(())
+    }
+
+    /// Get the airframe seeds of all aircraft
+    pub fn get_airframe_seeds(&self) -> Result<Vec<AirframeSeed>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT serial, prior_duration_secs, prior_flights, notes FROM airframe_seeds ORDER BY serial",
+        )?;
+        let seeds = stmt
+            .query_map(params![], |row| {
+                Ok(AirframeSeed {
+                    serial: row.get(0)?,
+                    prior_duration_secs: row.get(1)?,
+                    prior_flights: row.get(2)?,
+                    notes: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(seeds)
+    }
+
     /// Check if a file has already been imported (by hash)
     /// Returns the display_name of the matching flight if found, None otherwise
     pub fn is_file_imported(&self, file_hash: &str) -> Result<Option<String>, DatabaseError> {
@@ -2304,6 +5912,42 @@ impl Database {
         Ok(hashes)
     }
 
+    /// Register a browser for Web Push (replaces an existing subscription with the same endpoint).
+    pub fn add_push_subscription(&self, subscription: &PushSubscription, owner: Option<&str>) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO push_subscriptions (endpoint, p256dh, auth, owner, created_at)
+             VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP)",
+            params![subscription.endpoint, subscription.keys.p256dh, subscription.keys.auth, owner],
+        )?;
+        Ok(())
+    }
+
+    /// Remove Web Push subscriptions by endpoint. Returns the number removed.
+    pub fn remove_push_subscriptions(&self, endpoints: &[String]) -> Result<usize, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut removed = 0;
+        for endpoint in endpoints {
+            removed += conn.execute("DELETE FROM push_subscriptions WHERE endpoint = ?", params![endpoint])?;
+        }
+        Ok(removed)
+    }
+
+    /// All Web Push subscriptions of this profile.
+    pub fn get_push_subscriptions(&self) -> Result<Vec<PushSubscription>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT endpoint, p256dh, auth FROM push_subscriptions ORDER BY created_at")?;
+        let subscriptions = stmt
+            .query_map([], |row| {
+                Ok(PushSubscription {
+                    endpoint: row.get(0)?,
+                    keys: PushKeys { p256dh: row.get(1)?, auth: row.get(2)? },
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(subscriptions)
+    }
+
     /// Check whether a file hash is blacklisted.
     #[allow(dead_code)]
     pub fn is_sync_blacklisted(&self, file_hash: &str) -> Result<bool, DatabaseError> {
@@ -2323,63 +5967,85 @@ impl Database {
         Ok(exists.is_some())
     }
 
-    /// Check if a duplicate flight exists based on exact signature match (drone_serial + battery_serial + start_time).
-    /// Returns the display_name of the matching flight if found, None otherwise.
-    /// If any of the signature fields are None, returns None (can't reliably deduplicate).
-    pub fn is_duplicate_flight(
+    /// Flights of an aircraft that started within `tolerance_ms` of `start`,
+    /// candidates for the duplicate check (see `dedupe`)
+    pub fn find_flights_near_start(
         &self,
-        drone_serial: Option<&str>,
-        battery_serial: Option<&str>,
-        start_time: Option<chrono::DateTime<chrono::Utc>>,
-    ) -> Result<Option<String>, DatabaseError> {
-        // We need at least drone_serial and start_time to check for duplicates
-        let drone = match drone_serial {
-            Some(d) if !d.is_empty() => d,
-            _ => return Ok(None),
-        };
-        let time = match start_time {
-            Some(t) => t,
-            None => return Ok(None),
-        };
-
+        drone_serial: &str,
+        start: chrono::DateTime<chrono::Utc>,
+        tolerance_ms: i64,
+    ) -> Result<Vec<ExistingFlight>, DatabaseError> {
         let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, COALESCE(display_name, file_name), battery_serial,
+                   CAST(epoch_ms(start_time) AS BIGINT), duration_secs
+            FROM flights
+            WHERE drone_serial = ?
+              AND start_time IS NOT NULL
+              AND start_time BETWEEN ?::TIMESTAMPTZ AND ?::TIMESTAMPTZ
+            ORDER BY abs(epoch_ms(start_time) - ?)
+            "#,
+        )?;
+        let margin = chrono::Duration::milliseconds(tolerance_ms);
+        let flights = stmt
+            .query_map(
+                params![
+                    drone_serial,
+                    (start - margin).to_rfc3339(),
+                    (start + margin).to_rfc3339(),
+                    start.timestamp_millis()
+                ],
+                |row| {
+                    Ok(ExistingFlight {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        battery_serial: row.get(2)?,
+                        start_ms: row.get(3)?,
+                        duration_secs: row.get(4)?,
+                    })
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(flights)
+    }
 
-        // Build query based on whether battery_serial is available
-        let result: Option<String> = match battery_serial {
-            Some(b) if !b.is_empty() => {
-                // Full check: drone + battery + start_time
-                conn.query_row(
-                    r#"
-                    SELECT COALESCE(display_name, file_name) FROM flights 
-                    WHERE drone_serial = ?
-                      AND battery_serial = ?
-                      AND start_time IS NOT NULL
-                      AND start_time = ?::TIMESTAMPTZ
-                    LIMIT 1
-                    "#,
-                    params![drone, b, time.to_rfc3339()],
-                    |row| row.get(0),
-                ).optional()?
-            }
-            _ => {
-                // Partial check: drone + start_time only (battery unknown)
-                // Also match flights that have NULL battery_serial
-                conn.query_row(
-                    r#"
-                    SELECT COALESCE(display_name, file_name) FROM flights 
-                    WHERE drone_serial = ?
-                      AND (battery_serial IS NULL OR battery_serial = '')
-                      AND start_time IS NOT NULL
-                      AND start_time = ?::TIMESTAMPTZ
-                    LIMIT 1
-                    "#,
-                    params![drone, time.to_rfc3339()],
-                    |row| row.get(0),
-                ).optional()?
-            }
-        };
+    /// Find the flight whose start time falls inside `[from, to]`, earliest first.
+    pub fn find_flight_in_time_window(
+        &self,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<i64>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let id = conn
+            .query_row(
+                r#"
+                SELECT id FROM flights
+                WHERE start_time IS NOT NULL
+                  AND start_time BETWEEN ?::TIMESTAMPTZ AND ?::TIMESTAMPTZ
+                ORDER BY start_time ASC
+                LIMIT 1
+                "#,
+                params![from.to_rfc3339(), to.to_rfc3339()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(id)
+    }
 
-        Ok(result)
+    /// Get a flight's start time as a UTC timestamp (None if the flight has no start time)
+    pub fn get_flight_start_time(
+        &self,
+        flight_id: i64,
+    ) -> Result<Option<chrono::DateTime<chrono::Utc>>, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let epoch_ms: Option<i64> = conn.query_row(
+            "SELECT CAST(epoch(start_time) * 1000 AS BIGINT) FROM flights WHERE id = ?",
+            params![flight_id],
+            |row| row.get(0),
+        ).optional()?
+        .ok_or(DatabaseError::FlightNotFound(flight_id))?;
+        Ok(epoch_ms.and_then(chrono::DateTime::from_timestamp_millis))
     }
 
     /// Remove duplicate flights from the database based on exact signature match (drone_serial + battery_serial + start_time).
@@ -2391,6 +6057,7 @@ impl Database {
         log::info!("Starting flight deduplication...");
 
         let mut total_removed = 0;
+        let ids_before = Self::flight_ids(&conn)?;
 
         // Method 1: Remove exact file_hash duplicates (keep the one with most telemetry points)
         let hash_duplicates = conn.execute(
@@ -2451,12 +6118,22 @@ impl Database {
         total_removed += signature_duplicates;
         log::info!("Removed {} signature-based duplicates", signature_duplicates);
 
+        if total_removed > 0 {
+            let ids_after: HashSet<i64> = Self::flight_ids(&conn)?.into_iter().collect();
+            let removed: Vec<i64> = ids_before.into_iter().filter(|id| !ids_after.contains(id)).collect();
+            Self::record_tombstones(&conn, &removed);
+        }
+
         // Clean up orphaned telemetry data
         let orphaned_telemetry = conn.execute(
             "DELETE FROM telemetry WHERE flight_id NOT IN (SELECT id FROM flights)",
             [],
         )?;
         log::info!("Cleaned up {} orphaned telemetry records", orphaned_telemetry);
+        let _ = conn.execute(
+            "DELETE FROM telemetry_tiers WHERE flight_id NOT IN (SELECT id FROM flights)",
+            [],
+        );
 
         // Clean up orphaned tags
         let orphaned_tags = conn.execute(
@@ -2662,19 +6339,73 @@ impl Database {
         }
     }
 
+    /// Copy every table except `exclude` into a new DuckDB file at `dest`,
+    /// in one transaction so the copy is a consistent snapshot (see
+    /// `db_mirror`). Returns the number of tables copied.
+    pub fn write_snapshot(&self, dest: &std::path::Path, exclude: &[&str]) -> Result<usize, DatabaseError> {
+        let conn = self.conn.lock().unwrap();
+        let tables: Vec<String> = conn
+            .prepare(
+                "SELECT table_name FROM duckdb_tables()
+                 WHERE database_name = current_database() AND schema_name = 'main' AND NOT temporary
+                 ORDER BY table_name",
+            )?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let tables: Vec<String> = tables.into_iter().filter(|t| !exclude.contains(&t.as_str())).collect();
+
+        conn.execute_batch(&format!(
+            "ATTACH '{}' AS snapshot;",
+            dest.to_string_lossy().replace('\'', "''")
+        ))?;
+        let mut sql = String::from("BEGIN TRANSACTION;\n");
+        for table in &tables {
+            sql.push_str(&format!("CREATE TABLE snapshot.main.\"{0}\" AS SELECT * FROM main.\"{0}\";\n", table));
+        }
+        sql.push_str("COMMIT;");
+        let copied = conn.execute_batch(&sql);
+        if copied.is_err() {
+            let _ = conn.execute_batch("ROLLBACK;");
+        }
+        let detached = conn.execute_batch("DETACH snapshot;");
+        copied?;
+        detached?;
+        Ok(tables.len())
+    }
+
     /// Export the entire database to a compressed backup file.
     ///
     /// Uses DuckDB's Parquet COPY for each table, then packs them into a single
     /// gzip-compressed tar archive.  The resulting `.db.backup` file is portable
     /// and can be restored with `import_backup`.
-    pub fn export_backup(&self, dest_path: &std::path::Path) -> Result<(), DatabaseError> {
+    ///
+    /// With `scrub`, the backup is safe to share for debugging: serial numbers
+    /// are replaced by salted hashes (consistent within the backup), all
+    /// coordinates are shifted by one random offset (so distances and shapes
+    /// stay intact), and keychains, the audit log, the integrity chain,
+    /// attachments, flight plans, jobs, the location overrides and the tags of
+    /// named sites are left out.
+    ///
+    /// Limit exceedances and telemetry tiers are derived data and not backed
+    /// up; `import_backup` recomputes them.
+    #[tracing::instrument(skip_all, fields(scrub = scrub))]
+    pub fn export_backup(&self, dest_path: &std::path::Path, scrub: bool) -> Result<(), DatabaseError> {
         let start = std::time::Instant::now();
-        log::info!("Starting database backup to {:?}", dest_path);
+        log::info!("Starting database backup to {:?} (scrubbed: {})", dest_path, scrub);
 
         // Create a temp directory for the Parquet exports
         let temp_dir = std::env::temp_dir().join(format!("dji-logbook-backup-{}", uuid::Uuid::new_v4()));
         fs::create_dir_all(&temp_dir)?;
 
+        // Read before taking the lock (loading locks the connection itself)
+        let site_names: Vec<String> = if scrub {
+            crate::locations::load(self)
+                .map(|overrides| overrides.sites.into_iter().map(|site| site.name).collect())
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
         let conn = self.conn.lock().unwrap();
 
         // Export each table to Parquet (fast, compressed, columnar)
@@ -2686,22 +6417,50 @@ impl Database {
         let equipment_names_path = temp_dir.join("equipment_names.parquet");
         let customizations_path = temp_dir.join("flight_customizations.parquet");
         let settings_path = temp_dir.join("settings.parquet");
+        let import_reports_path = temp_dir.join("flight_import_reports.parquet");
+        let rth_events_path = temp_dir.join("flight_rth_events.parquet");
+        let requirements_path = temp_dir.join("requirements.parquet");
+        let attachments_path = temp_dir.join("attachments.parquet");
+        let checklist_templates_path = temp_dir.join("checklist_templates.parquet");
+        let checklist_records_path = temp_dir.join("checklist_records.parquet");
+        let audit_log_path = temp_dir.join("audit_log.parquet");
+        let flight_plans_path = temp_dir.join("flight_plans.parquet");
+        let jobs_path = temp_dir.join("jobs.parquet");
+        let job_flights_path = temp_dir.join("job_flights.parquet");
+        let maintenance_log_path = temp_dir.join("maintenance_log.parquet");
+        let airframe_seeds_path = temp_dir.join("airframe_seeds.parquet");
+        let integrity_chain_path = temp_dir.join("integrity_chain.parquet");
+
+        let scrubbed = scrub.then(|| ScrubbedSources::new(site_names));
+        let (flights_src, telemetry_src, equipment_names_src) = match &scrubbed {
+            Some(s) => (s.flights(), s.telemetry(), s.equipment_names()),
+            None => ("flights".to_string(), "telemetry".to_string(), "equipment_names".to_string()),
+        };
+        let (tags_src, settings_src) = match &scrubbed {
+            Some(s) => (s.flight_tags(), s.settings()),
+            None => ("flight_tags".to_string(), "settings".to_string()),
+        };
 
         conn.execute_batch(&format!(
-            "COPY flights    TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            "COPY {} TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            flights_src,
             flights_path.to_string_lossy()
         ))?;
         conn.execute_batch(&format!(
-            "COPY telemetry  TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            "COPY {} TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            telemetry_src,
             telemetry_path.to_string_lossy()
         ))?;
-        conn.execute_batch(&format!(
-            "COPY keychains  TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
-            keychains_path.to_string_lossy()
-        ))?;
+        if !scrub {
+            conn.execute_batch(&format!(
+                "COPY keychains  TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+                keychains_path.to_string_lossy()
+            ))?;
+        }
         // Export tags table (ignore error if empty or doesn't exist)
         let _ = conn.execute_batch(&format!(
-            "COPY flight_tags TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            "COPY {} TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            tags_src,
             tags_path.to_string_lossy()
         ));
         // Export messages table (ignore error if empty or doesn't exist)
@@ -2711,7 +6470,8 @@ impl Database {
         ));
         // Export equipment_names table (ignore error if empty or doesn't exist)
         let _ = conn.execute_batch(&format!(
-            "COPY equipment_names TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            "COPY {} TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            equipment_names_src,
             equipment_names_path.to_string_lossy()
         ));
         // Export flight_customizations table (ignore error if empty or doesn't exist)
@@ -2721,18 +6481,98 @@ impl Database {
         ));
         // Export settings table (ignore error if empty or doesn't exist)
         let _ = conn.execute_batch(&format!(
-            "COPY settings TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            "COPY {} TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            settings_src,
             settings_path.to_string_lossy()
         ));
+        // Export flight_import_reports table (ignore error if empty or doesn't exist)
+        let _ = conn.execute_batch(&format!(
+            "COPY flight_import_reports TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            import_reports_path.to_string_lossy()
+        ));
+        // Export flight_rth_events table (ignore error if empty or doesn't exist)
+        let _ = conn.execute_batch(&format!(
+            "COPY flight_rth_events TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            rth_events_path.to_string_lossy()
+        ));
+        // Export requirements table (ignore error if empty or doesn't exist)
+        let _ = conn.execute_batch(&format!(
+            "COPY requirements TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            requirements_path.to_string_lossy()
+        ));
+        // Export attachments table (ignore error if empty or doesn't exist)
+        let _ = conn.execute_batch(&format!(
+            "COPY attachments TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            attachments_path.to_string_lossy()
+        ));
+        // Export checklist tables (ignore error if empty or doesn't exist)
+        let _ = conn.execute_batch(&format!(
+            "COPY checklist_templates TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            checklist_templates_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY checklist_records TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            checklist_records_path.to_string_lossy()
+        ));
+        // Export audit_log table (ignore error if empty or doesn't exist)
+        let _ = conn.execute_batch(&format!(
+            "COPY audit_log TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            audit_log_path.to_string_lossy()
+        ));
+        // Export flight_plans table (ignore error if empty or doesn't exist)
+        let _ = conn.execute_batch(&format!(
+            "COPY flight_plans TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            flight_plans_path.to_string_lossy()
+        ));
+        // Export job tables (ignore error if empty or doesn't exist)
+        let _ = conn.execute_batch(&format!(
+            "COPY jobs TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            jobs_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY job_flights TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            job_flights_path.to_string_lossy()
+        ));
+        // Export maintenance_log and airframe_seeds tables (ignore error if empty or doesn't exist)
+        let _ = conn.execute_batch(&format!(
+            "COPY maintenance_log TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            maintenance_log_path.to_string_lossy()
+        ));
+        let _ = conn.execute_batch(&format!(
+            "COPY airframe_seeds TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            airframe_seeds_path.to_string_lossy()
+        ));
+        // Export integrity_chain table (ignore error if empty or doesn't exist)
+        let _ = conn.execute_batch(&format!(
+            "COPY integrity_chain TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD);",
+            integrity_chain_path.to_string_lossy()
+        ));
 
         drop(conn); // release the lock while we tar
 
+        if scrub {
+            // Identifying tables that aren't needed to reproduce parser/stats issues
+            // The chain's record hashes would not match the scrubbed flights anyway
+            for path in [&attachments_path, &audit_log_path, &flight_plans_path, &jobs_path, &job_flights_path, &maintenance_log_path, &airframe_seeds_path, &integrity_chain_path] {
+                let _ = fs::remove_file(path);
+            }
+            fs::write(
+                temp_dir.join("scrubbed.json"),
+                serde_json::json!({
+                    "serialsHashed": true,
+                    "coordinatesShifted": true,
+                    "omitted": ["keychains", "attachments", "audit_log", "flight_plans", "jobs", "job_flights", "maintenance_log", "airframe_seeds", "integrity_chain"],
+                })
+                .to_string(),
+            )?;
+        }
+
         // Pack the Parquet files into a gzip-compressed tar archive
         let dest_file = fs::File::create(dest_path)?;
         let gz = flate2::write::GzEncoder::new(dest_file, flate2::Compression::fast());
         let mut tar = tar::Builder::new(gz);
 
-        for name in &["flights.parquet", "telemetry.parquet", "keychains.parquet", "flight_tags.parquet", "flight_messages.parquet", "equipment_names.parquet", "flight_customizations.parquet", "settings.parquet"] {
+        for name in &["flights.parquet", "telemetry.parquet", "keychains.parquet", "flight_tags.parquet", "flight_messages.parquet", "equipment_names.parquet", "flight_customizations.parquet", "settings.parquet", "flight_import_reports.parquet", "flight_rth_events.parquet", "requirements.parquet", "attachments.parquet", "checklist_templates.parquet", "checklist_records.parquet", "audit_log.parquet", "flight_plans.parquet", "jobs.parquet", "job_flights.parquet", "maintenance_log.parquet", "airframe_seeds.parquet", "integrity_chain.parquet", "scrubbed.json"] {
             let file_path = temp_dir.join(name);
             if file_path.exists() {
                 tar.append_path_with_name(&file_path, name)
@@ -2740,6 +6580,21 @@ impl Database {
             }
         }
 
+        // Attached documents travel with the backup (files only — the default
+        // profile's folder also holds the named profiles' subfolders), except
+        // in scrubbed backups
+        let attachment_entries = if scrub { None } else { fs::read_dir(&self.attachments_dir).ok() };
+        if let Some(entries) = attachment_entries {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_file() {
+                    let name = format!("attachments/{}", entry.file_name().to_string_lossy());
+                    tar.append_path_with_name(&path, name)
+                        .map_err(|e| DatabaseError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+                }
+            }
+        }
+
         tar.into_inner()
             .map_err(|e| DatabaseError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?
             .finish()
@@ -2760,6 +6615,7 @@ impl Database {
     ///
     /// Existing records are kept.  If a flight with the same ID already exists
     /// it is overwritten (its telemetry is replaced as well).
+    #[tracing::instrument(skip_all)]
     pub fn import_backup(&self, src_path: &std::path::Path) -> Result<String, DatabaseError> {
         let start = std::time::Instant::now();
         log::info!("Starting database restore from {:?}", src_path);
@@ -2796,12 +6652,14 @@ impl Database {
             DELETE FROM flights
             WHERE id IN (SELECT id FROM read_parquet('{}'))
                OR file_hash IN (SELECT file_hash FROM read_parquet('{}') WHERE file_hash IS NOT NULL);
-            INSERT INTO flights
+            INSERT INTO flights BY NAME
             SELECT * FROM read_parquet('{}');
+            {}
             "#,
             flights_path.to_string_lossy(),
             flights_path.to_string_lossy(),
-            flights_path.to_string_lossy()
+            flights_path.to_string_lossy(),
+            MARK_LEGACY_MANUAL_FLIGHTS
         ))?;
 
         let flights_restored: i64 = conn.query_row(
@@ -2909,8 +6767,132 @@ impl Database {
             ));
         }
 
+        // --- Restore import reports (backward compatible — may not exist in old backups) ---
+        let import_reports_path = temp_dir.join("flight_import_reports.parquet");
+        if import_reports_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO flight_import_reports BY NAME
+                SELECT * FROM read_parquet('{}');
+                "#,
+                import_reports_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore RTH events (backward compatible — may not exist in old backups) ---
+        let rth_events_path = temp_dir.join("flight_rth_events.parquet");
+        if rth_events_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                DELETE FROM flight_rth_events
+                WHERE flight_id IN (
+                    SELECT DISTINCT flight_id FROM read_parquet('{}')
+                );
+                INSERT INTO flight_rth_events
+                SELECT * FROM read_parquet('{}');
+                "#,
+                rth_events_path.to_string_lossy(),
+                rth_events_path.to_string_lossy()
+            ));
+        }
+
+        // Previews are not backed up; drop stale ones for the restored flights
+        let _ = conn.execute_batch(&format!(
+            "DELETE FROM flight_previews WHERE flight_id IN (SELECT id FROM read_parquet('{}'));",
+            flights_path.to_string_lossy()
+        ));
+
+        // --- Restore the integrity chain (backward compatible — may not exist in old backups) ---
+        // Chains can't be merged without breaking the hashes, so the backup's
+        // chain is only taken over by a database that has none yet
+        let integrity_chain_path = temp_dir.join("integrity_chain.parquet");
+        if integrity_chain_path.exists() {
+            let local_entries: i64 = conn
+                .query_row("SELECT COUNT(*) FROM integrity_chain", [], |row| row.get(0))
+                .unwrap_or(1);
+            if local_entries == 0 {
+                if let Err(e) = conn.execute_batch(&format!(
+                    "INSERT INTO integrity_chain SELECT * FROM read_parquet('{}');",
+                    integrity_chain_path.to_string_lossy()
+                )) {
+                    log::warn!("Failed to restore the integrity chain: {}", e);
+                }
+            } else {
+                log::info!("Keeping the existing integrity chain; the backup's chain is not restored");
+            }
+        }
+
+        // Record the restored flights as they are now, and rebuild their telemetry tiers
+        let restored_ids: Vec<i64> = conn
+            .prepare(&format!("SELECT id FROM read_parquet('{}') ORDER BY id", flights_path.to_string_lossy()))
+            .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect())
+            .unwrap_or_default();
+        for flight_id in restored_ids {
+            Self::record_integrity(&conn, flight_id, "restore");
+            if let Err(e) = Self::build_telemetry_tiers(&conn, flight_id) {
+                log::warn!("Failed to rebuild telemetry tiers for flight {}: {}", flight_id, e);
+            }
+        }
+
+        // --- Restore requirements (backward compatible — may not exist in old backups) ---
+        let requirements_path = temp_dir.join("requirements.parquet");
+        if requirements_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO requirements
+                SELECT * FROM read_parquet('{}');
+                "#,
+                requirements_path.to_string_lossy()
+            ));
+        }
+
+        // --- Restore attachments (backward compatible — may not exist in old backups) ---
+        // Rows whose stored name is not a plain file name (as `add_attachment`
+        // generates) could point outside the attachments folder, so they are skipped
+        let attachments_path = temp_dir.join("attachments.parquet");
+        if attachments_path.exists() {
+            let _ = conn.execute_batch(&format!(
+                r#"
+                INSERT OR REPLACE INTO attachments
+                SELECT * FROM read_parquet('{}')
+                WHERE regexp_full_match(stored_name, '[A-Za-z0-9._-]+')
+                  AND stored_name NOT IN ('.', '..');
+                "#,
+                attachments_path.to_string_lossy()
+            ));
+        }
+        // --- Restore checklists, audit log, flight plans, jobs, maintenance log and airframe seeds (backward compatible — may not exist in old backups) ---
+        for table in ["checklist_templates", "checklist_records", "audit_log", "flight_plans", "jobs", "job_flights", "maintenance_log", "airframe_seeds"] {
+            let path = temp_dir.join(format!("{}.parquet", table));
+            if path.exists() {
+                let _ = conn.execute_batch(&format!(
+                    "INSERT OR REPLACE INTO {} SELECT * FROM read_parquet('{}');",
+                    table,
+                    path.to_string_lossy()
+                ));
+            }
+        }
+
+        let attachment_files = temp_dir.join("attachments");
+        if let Ok(entries) = fs::read_dir(&attachment_files) {
+            let _ = fs::create_dir_all(&self.attachments_dir);
+            for entry in entries.flatten() {
+                let dest = self.attachments_dir.join(entry.file_name());
+                if entry.path().is_file() && !dest.exists() {
+                    if let Err(e) = fs::copy(entry.path(), &dest) {
+                        log::warn!("Failed to restore attachment file {:?}: {}", dest, e);
+                    }
+                }
+            }
+        }
+
         drop(conn);
 
+        // Exceedance flags are derived data — re-evaluate against the restored limits
+        if let Err(e) = self.refresh_exceedances(None) {
+            log::warn!("Failed to refresh limit exceedances after restore: {}", e);
+        }
+
         // Clean up temp dir
         let _ = fs::remove_dir_all(&temp_dir);
 
@@ -3009,6 +6991,59 @@ pub fn default_upload_folder(data_dir: &std::path::Path, profile: &str) -> std::
     }
 }
 
+/// Return the attachments folder for a given profile.
+/// "default" → `attachments`, anything else → `attachments/{profile}`.
+pub fn attachments_folder(data_dir: &std::path::Path, profile: &str) -> std::path::PathBuf {
+    if profile == "default" {
+        data_dir.join("attachments")
+    } else {
+        data_dir.join("attachments").join(profile)
+    }
+}
+
+/// Reduce an attachment file name to characters that are safe on every filesystem
+/// True when `name` is a single file name, with no directory part
+fn is_plain_file_name(name: &str) -> bool {
+    std::path::Path::new(name).file_name() == Some(std::ffi::OsStr::new(name))
+}
+
+fn sanitize_attachment_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+    let trimmed = cleaned.trim_matches('.');
+    if trimmed.is_empty() {
+        "attachment".to_string()
+    } else {
+        trimmed.chars().take(100).collect()
+    }
+}
+
+/// MIME type for an attachment, from its extension
+fn attachment_content_type(name: &str) -> &'static str {
+    let ext = std::path::Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    match ext.as_str() {
+        "pdf" => "application/pdf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "json" => "application/json",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "zip" => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
 /// Return the sync folder path for a given profile.
 /// When `SYNC_LOGS_PATH` provides a base path:
 ///   "default" → `{base}`, anything else → `{base}/{profile}`.
@@ -3080,6 +7115,12 @@ pub fn delete_profile(data_dir: &std::path::Path, profile: &str) -> Result<(), S
         let _ = fs::remove_dir_all(&upload_dir);
     }
 
+    // Clean up per-profile attachments
+    let attachments_dir = attachments_folder(data_dir, profile);
+    if attachments_dir.exists() {
+        let _ = fs::remove_dir_all(&attachments_dir);
+    }
+
     // If this was the active profile, switch back to default
     if get_active_profile(data_dir) == profile {
         let _ = set_active_profile(data_dir, "default");
@@ -3102,6 +7143,7 @@ mod tests {
 
         // Verify directories were created
         assert!(temp_dir.path().join("keychains").exists());
+        assert!(temp_dir.path().join("attachments").exists());
         assert!(temp_dir.path().join("flights.db").exists());
 
         // Verify we can get flights (empty)
@@ -0,0 +1,176 @@
+//! Idle-time database maintenance.
+//!
+//! A crash or kill while the write-ahead log holds a lot of uncheckpointed
+//! data is what sends the next start into the WAL recovery path of
+//! `Database::open_with_recovery`, which opens the database without the WAL
+//! (the changes since the last checkpoint then only exist in the recovery
+//! copy, see `recovery`). To keep that window small, a background task looks
+//! at the open databases every `CHECK_INTERVAL`. Once a database has been
+//! idle for `IDLE_AFTER` (no commands or requests, no import queue worker),
+//! its WAL is checkpointed when it is not empty, and the file is compacted
+//! (`CHECKPOINT; VACUUM`) when the last compaction is older than
+//! `VACUUM_INTERVAL`. The outcome is reported by `get_database_info`.
+
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::database::Database;
+
+/// How often the scheduler looks at the open databases
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Time without database access before maintenance runs
+pub const IDLE_AFTER: Duration = Duration::from_secs(120);
+/// Time between compactions
+const VACUUM_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+
+/// Outcome of the last maintenance runs of a database
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceStatus {
+    pub last_checkpoint: Option<DateTime<Utc>>,
+    /// Compaction at startup or by the scheduler
+    pub last_vacuum: Option<DateTime<Utc>>,
+    pub last_duration_ms: Option<u64>,
+    /// Error of the last run (None when it succeeded)
+    pub last_error: Option<String>,
+}
+
+/// Database file details returned by `get_database_info`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseInfo {
+    pub path: String,
+    pub size_bytes: u64,
+    pub wal_size_bytes: u64,
+    pub read_only: bool,
+    pub idle_secs: u64,
+    pub maintenance: MaintenanceStatus,
+    /// Earliest time of the next compaction (it waits for an idle period)
+    pub next_vacuum_due: Option<DateTime<Utc>>,
+}
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+fn vacuum_due_at(status: &MaintenanceStatus) -> Option<DateTime<Utc>> {
+    status
+        .last_vacuum
+        .map(|t| t + chrono::Duration::from_std(VACUUM_INTERVAL).unwrap_or_default())
+}
+
+/// Size, WAL size and maintenance status of a database
+pub fn database_info(db: &Database) -> DatabaseInfo {
+    let maintenance = db.maintenance.lock().unwrap().clone();
+    DatabaseInfo {
+        path: db.path().to_string_lossy().to_string(),
+        size_bytes: file_size(db.path()),
+        wal_size_bytes: file_size(&db.wal_path()),
+        read_only: db.is_read_only(),
+        idle_secs: db.idle_for().as_secs(),
+        next_vacuum_due: vacuum_due_at(&maintenance),
+        maintenance,
+    }
+}
+
+/// What an idle database needs, if anything
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Task {
+    Checkpoint,
+    Vacuum,
+}
+
+fn due_task(wal_size: u64, status: &MaintenanceStatus, now: DateTime<Utc>) -> Option<Task> {
+    if vacuum_due_at(status).map_or(true, |due| now >= due) {
+        Some(Task::Vacuum)
+    } else if wal_size > 0 {
+        Some(Task::Checkpoint)
+    } else {
+        None
+    }
+}
+
+/// Checkpoint or compact the database if it is idle and needs it
+pub fn run_if_idle(db: &Database) {
+    if db.is_read_only() || db.import_worker_active.load(Ordering::SeqCst) || db.idle_for() < IDLE_AFTER {
+        return;
+    }
+    let now = Utc::now();
+    let task = {
+        let status = db.maintenance.lock().unwrap();
+        due_task(file_size(&db.wal_path()), &status, now)
+    };
+    let Some(task) = task else {
+        return;
+    };
+
+    let start = Instant::now();
+    let result = match task {
+        Task::Checkpoint => db.checkpoint(),
+        Task::Vacuum => db.vacuum(),
+    };
+    let elapsed = start.elapsed();
+
+    let mut status = db.maintenance.lock().unwrap();
+    status.last_duration_ms = Some(elapsed.as_millis() as u64);
+    match result {
+        Ok(()) => {
+            log::info!("Idle maintenance ({:?}) of {:?} completed in {:.1}s", task, db.path(), elapsed.as_secs_f64());
+            status.last_checkpoint = Some(now);
+            if task == Task::Vacuum {
+                status.last_vacuum = Some(now);
+            }
+            status.last_error = None;
+        }
+        Err(e) => {
+            log::warn!("Idle maintenance ({:?}) of {:?} failed: {}", task, db.path(), e);
+            status.last_error = Some(e.to_string());
+        }
+    }
+}
+
+/// Run maintenance on the databases returned by `databases` forever
+pub async fn run_scheduler<F>(databases: F)
+where
+    F: Fn() -> Vec<Arc<Database>>,
+{
+    log::info!(
+        "Database maintenance scheduler started (checkpoint after {}s idle, compaction every {}h)",
+        IDLE_AFTER.as_secs(),
+        VACUUM_INTERVAL.as_secs() / 3600
+    );
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        for db in databases() {
+            if let Err(e) = tokio::task::spawn_blocking(move || run_if_idle(&db)).await {
+                log::warn!("Database maintenance task failed: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_due_task() {
+        let now = Utc::now();
+        // Never compacted (startup vacuum failed)
+        assert_eq!(due_task(0, &MaintenanceStatus::default(), now), Some(Task::Vacuum));
+
+        let recent = MaintenanceStatus { last_vacuum: Some(now - chrono::Duration::hours(1)), ..Default::default() };
+        assert_eq!(due_task(4096, &recent, now), Some(Task::Checkpoint));
+        assert_eq!(due_task(0, &recent, now), None);
+
+        let old = MaintenanceStatus { last_vacuum: Some(now - chrono::Duration::hours(25)), ..Default::default() };
+        assert_eq!(due_task(0, &old, now), Some(Task::Vacuum));
+    }
+}
@@ -0,0 +1,233 @@
+//! Read-only mirror of the database for other tools.
+//!
+//! Grafana's DuckDB datasource, notebooks or BI tools cannot open the live
+//! database while the app holds its lock, and reading the file underneath it
+//! could see a half-written state. Instead, a snapshot of all tables is
+//! published to a separate DuckDB file: it is written in one transaction to a
+//! temporary file next to the target and then renamed over it, so readers
+//! always find a complete copy (tools that keep the old file open continue to
+//! see the previous snapshot). Keychains and push subscriptions are left out.
+//!
+//! Settings are kept under the `db_mirror` key of the profile's `config.json`.
+//! The desktop app publishes to the chosen path; the web server publishes to
+//! `{DB_MIRROR_PATH}/{profile}.duckdb` (default `mirror/` in the data
+//! directory), so the path cannot be set through the API.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::database::Database;
+
+const CONFIG_KEY: &str = "db_mirror";
+/// Tables holding secrets
+const EXCLUDED_TABLES: &[&str] = &["keychains", "push_subscriptions"];
+const DEFAULT_INTERVAL_MINUTES: u32 = 60;
+const MIN_INTERVAL_MINUTES: u32 = 5;
+/// How often the scheduler checks whether a mirror is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+fn default_interval() -> u32 {
+    DEFAULT_INTERVAL_MINUTES
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Destination file (desktop only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default = "default_interval")]
+    pub interval_minutes: u32,
+}
+
+impl Default for MirrorSettings {
+    fn default() -> Self {
+        Self { enabled: false, path: None, interval_minutes: DEFAULT_INTERVAL_MINUTES }
+    }
+}
+
+impl MirrorSettings {
+    pub fn from_config(config: &serde_json::Value) -> Self {
+        config
+            .get(CONFIG_KEY)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Read the settings from a `config.json` (disabled when missing)
+    pub fn load(config_path: &Path) -> Self {
+        let config: serde_json::Value = std::fs::read_to_string(config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+        Self::from_config(&config)
+    }
+}
+
+/// A published snapshot
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorReport {
+    pub path: String,
+    pub tables: usize,
+    pub size_bytes: u64,
+    pub duration_ms: u64,
+    pub published_at: DateTime<Utc>,
+}
+
+/// Check and store the settings in config.json. `require_path` is set on
+/// desktop, where the user chooses the destination.
+pub fn save_settings(config_path: &Path, settings: MirrorSettings, require_path: bool) -> Result<MirrorSettings, String> {
+    let path = settings.path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    if settings.interval_minutes < MIN_INTERVAL_MINUTES {
+        return Err(format!("The mirror interval must be at least {} minutes", MIN_INTERVAL_MINUTES));
+    }
+    if require_path && settings.enabled {
+        match &path {
+            None => return Err("Choose a file for the mirror".to_string()),
+            Some(p) if !Path::new(p).is_absolute() => return Err("The mirror path must be absolute".to_string()),
+            _ => {}
+        }
+    }
+    let settings = MirrorSettings { path, ..settings };
+
+    let mut config: serde_json::Value = std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    config[CONFIG_KEY] = serde_json::to_value(&settings).map_err(|e| e.to_string())?;
+    std::fs::write(config_path, serde_json::to_string_pretty(&config).unwrap())
+        .map_err(|e| format!("Failed to write config: {}", e))?;
+    Ok(settings)
+}
+
+/// Where the web server publishes a profile's mirror
+pub fn web_mirror_path(data_dir: &Path, profile: &str) -> PathBuf {
+    let dir = std::env::var("DB_MIRROR_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| data_dir.join("mirror"));
+    dir.join(format!("{}.duckdb", profile))
+}
+
+/// Publish a snapshot of the database to `dest`
+pub fn publish(db: &Database, dest: &Path) -> Result<MirrorReport, String> {
+    let start = std::time::Instant::now();
+    if dest == db.path() {
+        return Err("The mirror cannot replace the database itself".to_string());
+    }
+    let file_name = dest
+        .file_name()
+        .ok_or_else(|| format!("Invalid mirror path: {}", dest.display()))?
+        .to_string_lossy()
+        .to_string();
+    if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    // Written next to the target, so the rename is atomic
+    let temp = dest.with_file_name(format!(".{}.tmp", file_name));
+    let temp_wal = dest.with_file_name(format!(".{}.tmp.wal", file_name));
+    let _ = std::fs::remove_file(&temp);
+    let _ = std::fs::remove_file(&temp_wal);
+    let tables = match db.write_snapshot(&temp, EXCLUDED_TABLES) {
+        Ok(tables) => tables,
+        Err(e) => {
+            let _ = std::fs::remove_file(&temp);
+            let _ = std::fs::remove_file(&temp_wal);
+            return Err(format!("Failed to write the mirror: {}", e));
+        }
+    };
+    let _ = std::fs::remove_file(&temp_wal);
+    std::fs::rename(&temp, dest).map_err(|e| {
+        let _ = std::fs::remove_file(&temp);
+        format!("Failed to replace {}: {}", dest.display(), e)
+    })?;
+
+    let report = MirrorReport {
+        path: dest.display().to_string(),
+        tables,
+        size_bytes: std::fs::metadata(dest).map(|m| m.len()).unwrap_or(0),
+        duration_ms: start.elapsed().as_millis() as u64,
+        published_at: Utc::now(),
+    };
+    log::info!(
+        "Published database mirror to {} ({} tables, {} bytes, {} ms)",
+        report.path,
+        report.tables,
+        report.size_bytes,
+        report.duration_ms
+    );
+    Ok(report)
+}
+
+/// Whether a mirror last written at `modified` is due again
+fn is_due(modified: Option<SystemTime>, interval_minutes: u32, now: SystemTime) -> bool {
+    let interval = Duration::from_secs(u64::from(interval_minutes.max(MIN_INTERVAL_MINUTES)) * 60);
+    match modified.and_then(|m| now.duration_since(m).ok()) {
+        Some(age) => age >= interval,
+        // Never written (or written in the future)
+        None => modified.is_none(),
+    }
+}
+
+/// Publish the mirrors that are due, checking every `CHECK_INTERVAL`.
+/// `mirrors` lists the databases with mirroring enabled and their destination.
+pub async fn run_scheduler<F>(mirrors: F)
+where
+    F: Fn() -> Vec<(Arc<Database>, PathBuf, u32)>,
+{
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        for (db, dest, interval_minutes) in mirrors() {
+            let modified = std::fs::metadata(&dest).and_then(|m| m.modified()).ok();
+            if !is_due(modified, interval_minutes, SystemTime::now()) {
+                continue;
+            }
+            let result = tokio::task::spawn_blocking(move || {
+                let result = publish(&db, &dest);
+                if let Err(e) = &result {
+                    log::warn!("Scheduled database mirror to {} failed: {}", dest.display(), e);
+                }
+                result
+            })
+            .await;
+            if let Err(e) = result {
+                log::warn!("Database mirror task failed: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_mirror() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(dir.path().to_path_buf(), "default").unwrap();
+        let dest = dir.path().join("mirror").join("default.duckdb");
+        let report = publish(&db, &dest).unwrap();
+        assert!(report.tables > 10);
+        // Published again over the previous copy
+        publish(&db, &dest).unwrap();
+
+        let mirror = duckdb::Connection::open(&dest).unwrap();
+        let flights: i64 = mirror.query_row("SELECT COUNT(*) FROM flights", [], |row| row.get(0)).unwrap();
+        assert_eq!(flights, 0);
+        assert!(mirror.query_row("SELECT COUNT(*) FROM keychains", [], |row| row.get::<_, i64>(0)).is_err());
+
+        let now = SystemTime::now();
+        assert!(is_due(None, 60, now));
+        assert!(!is_due(Some(now - Duration::from_secs(600)), 60, now));
+        assert!(is_due(Some(now - Duration::from_secs(3600)), 60, now));
+    }
+}
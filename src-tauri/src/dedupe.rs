@@ -0,0 +1,161 @@
+//! Duplicate detection for imports beyond the file hash.
+//!
+//! The same flight exported from two phones (or downloaded again from the
+//! cloud) can arrive as files with different bytes, which the SHA256 check
+//! at parse time does not catch. After parsing, the flight is compared with
+//! those of the same aircraft serial that started around the same time:
+//!
+//! - same battery serial and exact start time (the signature check), or
+//! - start within `START_TOLERANCE_MS` and duration within
+//!   `DURATION_TOLERANCE_SECS` / `DURATION_TOLERANCE_RATIO`
+//!
+//! A match is reported as an [`ImportConflict`] instead of importing a
+//! second copy. Importing the file again with a [`ConflictResolution`]
+//! settles it: `skip`, `replace` (the new flight is imported and the
+//! existing one deleted) or `keep_both`. Unattended imports (folder sync,
+//! import queue) always skip. The match reason and skip message are
+//! localized (see `messages`).
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::database::{Database, DatabaseError};
+use crate::messages;
+use crate::models::{FlightMetadata, ImportConflict};
+
+/// Largest start time difference of two copies of a flight
+pub const START_TOLERANCE_MS: i64 = 2_000;
+const DURATION_TOLERANCE_SECS: f64 = 2.0;
+const DURATION_TOLERANCE_RATIO: f64 = 0.01;
+
+/// What to do with a file that matches a flight in the logbook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    Skip,
+    /// Import the file and delete the existing flight
+    Replace,
+    /// Import the file as a separate flight
+    KeepBoth,
+}
+
+/// A flight of the same aircraft that started around the same time
+#[derive(Debug, Clone)]
+pub struct ExistingFlight {
+    pub id: i64,
+    pub name: String,
+    pub battery_serial: Option<String>,
+    pub start_ms: i64,
+    pub duration_secs: Option<f64>,
+}
+
+/// Outcome of the duplicate check
+#[derive(Debug, Clone)]
+pub enum DuplicateCheck {
+    /// Import; delete the flight in `replaces` once the import succeeded
+    Import { replaces: Option<i64> },
+    /// Don't import
+    Skip { message: String, conflict: ImportConflict },
+}
+
+fn durations_match(a: f64, b: f64) -> bool {
+    (a - b).abs() <= DURATION_TOLERANCE_SECS.max(a.max(b) * DURATION_TOLERANCE_RATIO)
+}
+
+/// Why `existing` is the same flight as one starting at `start_ms`, if it is
+/// (a `messages` code)
+pub fn match_reason(
+    existing: &ExistingFlight,
+    battery_serial: Option<&str>,
+    start_ms: i64,
+    duration_secs: Option<f64>,
+) -> Option<&'static str> {
+    let battery = battery_serial.map(str::trim).filter(|b| !b.is_empty());
+    let existing_battery = existing.battery_serial.as_deref().map(str::trim).filter(|b| !b.is_empty());
+    if existing.start_ms == start_ms && battery == existing_battery {
+        return Some("dedupe.same_battery_start");
+    }
+    let same_duration = matches!((existing.duration_secs, duration_secs), (Some(a), Some(b)) if durations_match(a, b));
+    if (existing.start_ms - start_ms).abs() <= START_TOLERANCE_MS && same_duration {
+        return Some("dedupe.same_start_duration");
+    }
+    None
+}
+
+/// Find the flight already in the logbook that `metadata` duplicates (needs
+/// the aircraft serial and start time), with the reason in `locale`
+pub fn find_duplicate(
+    db: &Database,
+    metadata: &FlightMetadata,
+    locale: &str,
+) -> Result<Option<ImportConflict>, DatabaseError> {
+    let (Some(drone_serial), Some(start)) = (metadata.drone_serial.as_deref().filter(|s| !s.is_empty()), metadata.start_time) else {
+        return Ok(None);
+    };
+    let start_ms = start.timestamp_millis();
+    for existing in db.find_flights_near_start(drone_serial, start, START_TOLERANCE_MS)? {
+        if let Some(reason) = match_reason(&existing, metadata.battery_serial.as_deref(), start_ms, metadata.duration_secs) {
+            return Ok(Some(ImportConflict {
+                existing_flight_id: existing.id,
+                existing_name: existing.name,
+                existing_start_time: DateTime::<Utc>::from_timestamp_millis(existing.start_ms).map(|t| t.to_rfc3339()),
+                existing_duration_secs: existing.duration_secs,
+                duration_secs: metadata.duration_secs,
+                reason: messages::text(locale, reason, &[]),
+            }));
+        }
+    }
+    Ok(None)
+}
+
+/// Check a parsed flight against the logbook and apply `resolution` to a match
+/// (None skips it, reporting the conflict in `locale`)
+pub fn check(
+    db: &Database,
+    metadata: &FlightMetadata,
+    resolution: Option<ConflictResolution>,
+    locale: &str,
+) -> Result<DuplicateCheck, DatabaseError> {
+    let Some(conflict) = find_duplicate(db, metadata, locale)? else {
+        return Ok(DuplicateCheck::Import { replaces: None });
+    };
+    Ok(match resolution {
+        Some(ConflictResolution::Replace) => DuplicateCheck::Import { replaces: Some(conflict.existing_flight_id) },
+        Some(ConflictResolution::KeepBoth) => DuplicateCheck::Import { replaces: None },
+        Some(ConflictResolution::Skip) | None => DuplicateCheck::Skip {
+            message: messages::text(
+                locale,
+                "import.duplicate",
+                &[("flight", &conflict.existing_name), ("reason", &conflict.reason)],
+            ),
+            conflict,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_reason() {
+        let existing = ExistingFlight {
+            id: 1,
+            name: "Beach".to_string(),
+            battery_serial: Some("BAT1".to_string()),
+            start_ms: 1_700_000_000_000,
+            duration_secs: Some(900.0),
+        };
+        // Signature: same battery and start, whatever the duration
+        assert_eq!(match_reason(&existing, Some("BAT1"), existing.start_ms, None), Some("dedupe.same_battery_start"));
+        // Another phone's export: start rounded differently, duration within 1 %
+        assert_eq!(
+            match_reason(&existing, None, existing.start_ms + 1_500, Some(906.0)),
+            Some("dedupe.same_start_duration")
+        );
+        // Back-to-back flights of the same length are different flights
+        assert_eq!(match_reason(&existing, Some("BAT2"), existing.start_ms + 5_000, Some(900.0)), None);
+        assert_eq!(match_reason(&existing, Some("BAT2"), existing.start_ms + 1_000, Some(600.0)), None);
+        assert_eq!(match_reason(&existing, Some("BAT2"), existing.start_ms, None), None);
+    }
+}
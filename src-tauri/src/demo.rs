@@ -0,0 +1,450 @@
+//! Demo logbook seeding.
+//!
+//! Generates a handful of synthetic but realistic flights (orbit, mapping
+//! survey, out-and-back, hover practice, cinematic figure-eight) with full
+//! 10 Hz telemetry, so new users and the public demo instance can explore the
+//! app without importing personal logs. Seeding is idempotent: every demo
+//! flight has a fixed `demo-...` file hash and is skipped when already present.
+//!
+//! Triggered by the `seed_demo_data` command / `POST /api/demo/seed`, and on
+//! startup of the web server when `DEMO_MODE=true` and the logbook is empty.
+
+use chrono::{DateTime, Duration, Timelike, Utc};
+
+use crate::database::Database;
+use crate::models::{FlightMetadata, TelemetryPoint};
+use crate::parser::LogParser;
+
+/// Tag added to every demo flight so they are easy to find and remove
+pub const DEMO_TAG: &str = "Demo";
+
+/// Telemetry sample interval (10 Hz, like DJI logs)
+const TICK_SECS: f64 = 0.1;
+/// Meters per degree of latitude
+const METERS_PER_DEG: f64 = 111_320.0;
+
+/// Aircraft used by the demo flights
+struct DemoAircraft {
+    model: &'static str,
+    serial: &'static str,
+    name: &'static str,
+    battery_serial: &'static str,
+    cells: usize,
+    capacity_mah: f64,
+    /// Hover endurance in minutes, drives the battery drain
+    endurance_min: f64,
+}
+
+const MAVIC: DemoAircraft = DemoAircraft {
+    model: "Mavic 3 Classic",
+    serial: "1581F5FHDEMO0001",
+    name: "Demo Mavic",
+    battery_serial: "DEMOBATTM3A01",
+    cells: 4,
+    capacity_mah: 5000.0,
+    endurance_min: 40.0,
+};
+
+const MINI: DemoAircraft = DemoAircraft {
+    model: "Mini 4 Pro",
+    serial: "1581F6Z9DEMO0002",
+    name: "Demo Mini",
+    battery_serial: "DEMOBATTM4P01",
+    cells: 2,
+    capacity_mah: 2590.0,
+    endurance_min: 30.0,
+};
+
+/// Camera activity during the mission legs
+#[derive(Clone, Copy, PartialEq)]
+enum Camera {
+    Idle,
+    /// One photo every N seconds
+    Interval(f64),
+    Video,
+}
+
+/// Move to `to` (local east/north/up meters from home) at `speed` m/s, then
+/// hold for `hold_secs`
+#[derive(Clone, Copy)]
+struct Leg {
+    to: [f64; 3],
+    speed: f64,
+    hold_secs: f64,
+    mode: &'static str,
+}
+
+fn leg(to: [f64; 3], speed: f64, mode: &'static str) -> Leg {
+    Leg { to, speed, hold_secs: 0.0, mode }
+}
+
+struct DemoFlight {
+    slug: &'static str,
+    title: &'static str,
+    aircraft: &'static DemoAircraft,
+    home: [f64; 2],
+    /// Ground elevation above sea level (m)
+    elevation: f64,
+    days_ago: i64,
+    /// Local-ish start hour (UTC)
+    hour: u32,
+    camera: Camera,
+    gimbal_pitch: f64,
+    /// Mission legs between take-off climb and return/landing
+    mission: Vec<Leg>,
+    cruise_alt: f64,
+}
+
+fn demo_flights() -> Vec<DemoFlight> {
+    let orbit: Vec<Leg> = (0..=72)
+        .map(|i| {
+            let a = (i as f64 * 10.0).to_radians();
+            leg([60.0 * a.cos(), 60.0 * a.sin(), 45.0], 5.0, "P-GPS")
+        })
+        .collect();
+
+    let mut survey = Vec::new();
+    for row in 0..8 {
+        let y = row as f64 * 25.0;
+        let (x0, x1) = if row % 2 == 0 { (-120.0, 120.0) } else { (120.0, -120.0) };
+        survey.push(leg([x0, y, 80.0], 8.0, "Waypoint"));
+        survey.push(leg([x1, y, 80.0], 8.0, "Waypoint"));
+    }
+
+    let out_and_back = vec![
+        leg([300.0, 400.0, 100.0], 12.0, "P-GPS"),
+        leg([700.0, 950.0, 110.0], 14.0, "P-GPS"),
+        Leg { to: [720.0, 980.0, 110.0], speed: 3.0, hold_secs: 45.0, mode: "P-GPS" },
+        leg([400.0, 500.0, 90.0], 14.0, "P-GPS"),
+    ];
+
+    let hover = vec![
+        Leg { to: [0.0, 8.0, 6.0], speed: 1.5, hold_secs: 30.0, mode: "P-GPS" },
+        Leg { to: [10.0, 8.0, 10.0], speed: 1.5, hold_secs: 30.0, mode: "P-GPS" },
+        Leg { to: [10.0, -6.0, 15.0], speed: 2.0, hold_secs: 40.0, mode: "P-GPS" },
+        Leg { to: [-8.0, -6.0, 8.0], speed: 2.0, hold_secs: 40.0, mode: "P-GPS" },
+        Leg { to: [0.0, 4.0, 5.0], speed: 1.5, hold_secs: 20.0, mode: "P-GPS" },
+    ];
+
+    let figure_eight: Vec<Leg> = (0..=96)
+        .map(|i| {
+            let t = (i as f64 * 7.5).to_radians();
+            let alt = 90.0 + 30.0 * (t * 0.5).sin();
+            leg([150.0 * t.sin(), 80.0 * (2.0 * t).sin() + 120.0, alt], 9.0, "Cine")
+        })
+        .collect();
+
+    vec![
+        DemoFlight {
+            slug: "lakeside-orbit",
+            title: "Lakeside orbit",
+            aircraft: &MAVIC,
+            home: [46.5069, 6.6266],
+            elevation: 372.0,
+            days_ago: 4,
+            hour: 8,
+            camera: Camera::Video,
+            gimbal_pitch: -25.0,
+            mission: orbit,
+            cruise_alt: 45.0,
+        },
+        DemoFlight {
+            slug: "field-survey",
+            title: "Field survey",
+            aircraft: &MAVIC,
+            home: [47.4012, 8.5485],
+            elevation: 430.0,
+            days_ago: 12,
+            hour: 9,
+            camera: Camera::Interval(2.0),
+            gimbal_pitch: -90.0,
+            mission: survey,
+            cruise_alt: 80.0,
+        },
+        DemoFlight {
+            slug: "coastal-out-and-back",
+            title: "Coastal out-and-back",
+            aircraft: &MAVIC,
+            home: [43.6955, 7.2728],
+            elevation: 8.0,
+            days_ago: 27,
+            hour: 15,
+            camera: Camera::Video,
+            gimbal_pitch: -15.0,
+            mission: out_and_back,
+            cruise_alt: 100.0,
+        },
+        DemoFlight {
+            slug: "hover-practice",
+            title: "Evening hover practice",
+            aircraft: &MINI,
+            home: [47.3667, 8.5500],
+            elevation: 408.0,
+            days_ago: 41,
+            hour: 19,
+            camera: Camera::Idle,
+            gimbal_pitch: 0.0,
+            mission: hover,
+            cruise_alt: 6.0,
+        },
+        DemoFlight {
+            slug: "ridge-cinematic",
+            title: "Ridge cinematic",
+            aircraft: &MINI,
+            home: [46.6863, 7.8632],
+            elevation: 1050.0,
+            days_ago: 63,
+            hour: 17,
+            camera: Camera::Video,
+            gimbal_pitch: -10.0,
+            mission: figure_eight,
+            cruise_alt: 90.0,
+        },
+    ]
+}
+
+/// Small deterministic PRNG so demo data is identical on every install
+struct Noise(u64);
+
+impl Noise {
+    fn next(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % 10_000) as f64 / 10_000.0
+    }
+
+    /// Uniform value in `[-amp, amp]`
+    fn jitter(&mut self, amp: f64) -> f64 {
+        (self.next() * 2.0 - 1.0) * amp
+    }
+}
+
+/// Simulate a flight: climb to the first mission altitude, fly the legs,
+/// return home at cruise altitude and land
+fn simulate(flight: &DemoFlight) -> Vec<TelemetryPoint> {
+    let a = flight.aircraft;
+    let mut legs = Vec::with_capacity(flight.mission.len() + 4);
+    let first_alt = flight.mission.first().map_or(flight.cruise_alt, |l| l.to[2]);
+    legs.push(leg([0.0, 0.0, first_alt], 4.0, "AutoTakeoff"));
+    legs.extend(flight.mission.iter().copied());
+    legs.push(leg([0.0, 0.0, flight.cruise_alt], 12.0, "GoHome"));
+    legs.push(leg([0.0, 0.0, 0.0], 3.0, "AutoLanding"));
+
+    let seed = flight.slug.bytes().fold(0x9e37_79b9_7f4a_7c15u64, |h, b| (h ^ b as u64).wrapping_mul(0x0100_0000_01b3));
+    let mut noise = Noise(seed | 1);
+    let cos_lat = flight.home[0].to_radians().cos();
+    let mut points = Vec::new();
+    let mut pos = [0.0f64, 0.0, 0.0];
+    let mut t = 0.0f64;
+    let mut heading = 0.0f64;
+    let mut last_photo = f64::NEG_INFINITY;
+    let start_battery = 98.0;
+    let start_temp = 24.0 + noise.jitter(2.0);
+
+    let mission_range = 1..legs.len() - 2;
+    for (i, l) in legs.iter().enumerate() {
+        let on_mission = mission_range.contains(&i);
+        let mut hold_left = l.hold_secs;
+        loop {
+            let d = [l.to[0] - pos[0], l.to[1] - pos[1], l.to[2] - pos[2]];
+            let dist = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+            let step = l.speed * TICK_SECS;
+            let vel = if dist > step {
+                [d[0] / dist * l.speed, d[1] / dist * l.speed, d[2] / dist * l.speed]
+            } else if dist > 1e-6 {
+                [d[0] / TICK_SECS, d[1] / TICK_SECS, d[2] / TICK_SECS]
+            } else if hold_left > 0.0 {
+                hold_left -= TICK_SECS;
+                [0.0, 0.0, 0.0]
+            } else {
+                break;
+            };
+            for k in 0..3 {
+                pos[k] += vel[k] * TICK_SECS;
+            }
+            pos[2] = pos[2].max(0.0);
+            t += TICK_SECS;
+
+            let h_speed = (vel[0] * vel[0] + vel[1] * vel[1]).sqrt();
+            if h_speed > 0.5 {
+                heading = vel[0].atan2(vel[1]).to_degrees();
+            }
+
+            // Battery drains faster when moving fast or climbing
+            let load = 1.0 + h_speed / 25.0 + vel[2].max(0.0) / 10.0;
+            let drained = t / 60.0 / a.endurance_min * 100.0 * load.min(1.6);
+            let pct = (start_battery - drained).max(5.0);
+            let cell_v = 3.55 + 0.6 * pct / 100.0 - 0.02 * load + noise.jitter(0.005);
+            let cells: Vec<f64> = (0..a.cells)
+                .map(|_| ((cell_v + noise.jitter(0.004)) * 1000.0).round() / 1000.0)
+                .collect();
+
+            let take_photo = match flight.camera {
+                Camera::Interval(every) => on_mission && t - last_photo >= every,
+                _ => false,
+            };
+            if take_photo {
+                last_photo = t;
+            }
+
+            let north = pos[1] + noise.jitter(0.15);
+            let east = pos[0] + noise.jitter(0.15);
+            let dist_home = (pos[0] * pos[0] + pos[1] * pos[1]).sqrt();
+            points.push(TelemetryPoint {
+                timestamp_ms: (t * 1000.0).round() as i64,
+                latitude: Some(flight.home[0] + north / METERS_PER_DEG),
+                longitude: Some(flight.home[1] + east / (METERS_PER_DEG * cos_lat)),
+                altitude: Some(flight.elevation + pos[2]),
+                height: Some(pos[2]),
+                vps_height: (pos[2] < 30.0).then_some(pos[2]),
+                altitude_abs: Some(flight.elevation + pos[2]),
+                speed: Some(h_speed),
+                velocity_x: Some(vel[1]),
+                velocity_y: Some(vel[0]),
+                velocity_z: Some(-vel[2]),
+                pitch: Some(-(h_speed * 1.6).min(30.0) + noise.jitter(0.8)),
+                roll: Some(noise.jitter(2.0)),
+                yaw: Some(heading),
+                gimbal_pitch: Some(if pos[2] > 2.0 { flight.gimbal_pitch } else { 0.0 }),
+                gimbal_roll: Some(0.0),
+                gimbal_yaw: Some(heading),
+                battery_percent: Some(pct.round() as i32),
+                battery_voltage: Some(cells.iter().sum::<f64>()),
+                battery_current: Some(6.0 * load + noise.jitter(0.6)),
+                battery_temp: Some(start_temp + (t / 60.0).min(14.0)),
+                battery_full_capacity: Some(a.capacity_mah),
+                battery_remained_capacity: Some((a.capacity_mah * pct / 100.0).round()),
+                cell_voltages: Some(cells),
+                flight_mode: Some(l.mode.to_string()),
+                gps_signal: Some(5),
+                satellites: Some(19 + (noise.next() * 4.0) as i32),
+                rc_signal: Some((100.0 - dist_home / 40.0).clamp(60.0, 100.0) as i32),
+                rc_uplink: Some((100.0 - dist_home / 45.0).clamp(55.0, 100.0) as i32),
+                rc_downlink: Some((100.0 - dist_home / 35.0).clamp(50.0, 100.0) as i32),
+                rc_aileron: Some(0.0),
+                rc_elevator: Some(if l.mode == "P-GPS" { (h_speed / 15.0 * 100.0).min(100.0) } else { 0.0 }),
+                rc_throttle: Some((vel[2] / 5.0 * 100.0).clamp(-100.0, 100.0)),
+                rc_rudder: Some(0.0),
+                is_photo: Some(take_photo),
+                is_video: Some(flight.camera == Camera::Video && on_mission),
+                is_flying: None,
+            });
+        }
+    }
+    points
+}
+
+/// Insert the demo flights that are not in the logbook yet. Returns the IDs
+/// of the flights created.
+pub fn seed_demo_data(db: &Database, config: &serde_json::Value) -> Result<Vec<i64>, String> {
+    let now = Utc::now();
+    let base_id = db.generate_flight_id();
+    let mut created = Vec::new();
+
+    for (i, flight) in demo_flights().iter().enumerate() {
+        let hash = format!("demo-{}", flight.slug);
+        if db.is_file_imported(&hash).map_err(|e| e.to_string())?.is_some() {
+            continue;
+        }
+
+        let points = simulate(flight);
+        let start: DateTime<Utc> = (now - Duration::days(flight.days_ago))
+            .with_hour(flight.hour)
+            .and_then(|d| d.with_minute(10 + i as u32 * 7))
+            .unwrap_or(now);
+        let duration = points.last().map_or(0.0, |p| p.timestamp_ms as f64 / 1000.0);
+        let distance: f64 = points
+            .windows(2)
+            .map(|w| {
+                crate::parser::haversine_distance(
+                    w[0].latitude.unwrap_or(0.0),
+                    w[0].longitude.unwrap_or(0.0),
+                    w[1].latitude.unwrap_or(0.0),
+                    w[1].longitude.unwrap_or(0.0),
+                )
+            })
+            .sum();
+        let photos = points
+            .windows(2)
+            .filter(|w| w[1].is_photo == Some(true) && w[0].is_photo != Some(true))
+            .count() as i32;
+
+        let id = base_id + i as i64;
+        let metadata = FlightMetadata {
+            id,
+            file_name: format!("DEMO_{}.txt", flight.slug.replace('-', "_").to_uppercase()),
+            display_name: flight.title.to_string(),
+            file_hash: Some(hash),
+            drone_model: Some(flight.aircraft.model.to_string()),
+            drone_serial: Some(flight.aircraft.serial.to_string()),
+            aircraft_name: Some(flight.aircraft.name.to_string()),
+            battery_serial: Some(flight.aircraft.battery_serial.to_string()),
+            cycle_count: Some(40 + i as i32 * 3),
+            start_time: Some(start),
+            end_time: Some(start + Duration::milliseconds((duration * 1000.0) as i64)),
+            duration_secs: Some(duration),
+            total_distance: Some(distance),
+            max_altitude: points.iter().filter_map(|p| p.height).reduce(f64::max),
+            max_speed: points.iter().filter_map(|p| p.speed).reduce(f64::max),
+            home_lat: Some(flight.home[0]),
+            home_lon: Some(flight.home[1]),
+            point_count: points.len() as i32,
+            photo_count: photos,
+            video_count: if flight.camera == Camera::Video { 1 } else { 0 },
+            rc_serial: Some("DEMORC000001".to_string()),
+            battery_life: None,
+        };
+
+        db.insert_flight(&metadata)
+            .map_err(|e| format!("Failed to insert demo flight '{}': {}", flight.title, e))?;
+        if let Err(e) = db.bulk_insert_telemetry(id, &points) {
+            let _ = db.delete_flight(id);
+            return Err(format!("Failed to insert demo telemetry for '{}': {}", flight.title, e));
+        }
+
+        let stats = LogParser::new(db).calculate_stats(&points);
+        let mut tags = LogParser::generate_smart_tags(&metadata, &stats);
+        tags.push(DEMO_TAG.to_string());
+        if let Err(e) = db.insert_flight_tags(id, &tags) {
+            log::warn!("Failed to tag demo flight {}: {}", id, e);
+        }
+        if let Err(e) = crate::hooks::run_pipeline(db, id, config, crate::hooks::HookTrigger::Import) {
+            log::warn!("Post-import hooks failed for demo flight {}: {}", id, e);
+        }
+        db.finish_import(id);
+        created.push(id);
+    }
+
+    log::info!("Seeded {} demo flights", created.len());
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulated_flights_take_off_and_land_at_home() {
+        for flight in demo_flights() {
+            let points = simulate(&flight);
+            assert!(points.len() > 1000, "{} too short", flight.slug);
+            let last = points.last().unwrap();
+            assert!(last.height.unwrap() < 0.01);
+            assert!(points.windows(2).all(|w| w[1].timestamp_ms > w[0].timestamp_ms));
+            let peak = points.iter().filter_map(|p| p.height).fold(0.0, f64::max);
+            assert!(peak >= flight.cruise_alt * 0.9);
+            let end_pct = last.battery_percent.unwrap();
+            assert!(end_pct > 5 && end_pct < 98);
+        }
+    }
+
+    #[test]
+    fn test_survey_takes_interval_photos() {
+        let survey = demo_flights().into_iter().find(|f| f.slug == "field-survey").unwrap();
+        let points = simulate(&survey);
+        let photos = points.iter().filter(|p| p.is_photo == Some(true)).count();
+        assert!(photos > 50);
+        assert!(points.iter().all(|p| p.is_video != Some(true)));
+    }
+}
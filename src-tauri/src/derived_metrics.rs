@@ -0,0 +1,229 @@
+//! User-defined telemetry columns.
+//!
+//! Advanced users can add series such as `power = battery_voltage *
+//! battery_current` as DuckDB SQL expressions over the telemetry columns. They
+//! are kept under the `derived_metrics` key of `config.json` and evaluated on
+//! demand for each flight data request, against the raw telemetry; when the
+//! response is downsampled, each point gets the mean of the raw values in its
+//! time bucket.
+//!
+//! Expressions are spliced into a query, so they are restricted to telemetry
+//! columns, numbers, arithmetic and comparison operators and a short list of
+//! functions and keywords (including `LAG`/`LEAD ... OVER (ORDER BY
+//! timestamp_ms)` for rates of change) — no strings, comments or statements.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::database::Database;
+use crate::models::TelemetryRecord;
+
+pub use drone_logbook_types::derived_metrics::DerivedSeries;
+
+const CONFIG_KEY: &str = "derived_metrics";
+const MAX_NAME_LEN: usize = 64;
+const MAX_EXPRESSION_LEN: usize = 500;
+
+/// Telemetry table columns usable in expressions
+const COLUMNS: &[&str] = &[
+    "timestamp_ms", "latitude", "longitude", "altitude", "height", "vps_height", "altitude_abs",
+    "speed", "velocity_x", "velocity_y", "velocity_z", "pitch", "roll", "yaw",
+    "gimbal_pitch", "gimbal_roll", "gimbal_yaw", "battery_percent", "battery_voltage",
+    "battery_current", "battery_temp", "gps_signal", "satellites", "rc_signal", "rc_uplink",
+    "rc_downlink", "rc_aileron", "rc_elevator", "rc_throttle", "rc_rudder",
+];
+
+const FUNCTIONS: &[&str] = &[
+    "abs", "sqrt", "pow", "power", "exp", "ln", "log10", "sin", "cos", "tan", "asin", "acos",
+    "atan", "atan2", "radians", "degrees", "greatest", "least", "round", "floor", "ceil",
+    "sign", "coalesce", "nullif", "lag", "lead",
+];
+
+const KEYWORDS: &[&str] = &[
+    "case", "when", "then", "else", "end", "and", "or", "not", "null", "is", "between",
+    "over", "order", "by",
+];
+
+/// A derived column definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DerivedMetric {
+    pub name: String,
+    /// DuckDB SQL expression over the telemetry columns
+    pub expression: String,
+    #[serde(default)]
+    pub unit: Option<String>,
+}
+
+fn from_config(config: &serde_json::Value) -> Vec<DerivedMetric> {
+    config
+        .get(CONFIG_KEY)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Read the definitions from a `config.json` (none when missing)
+pub fn load(config_path: &Path) -> Vec<DerivedMetric> {
+    let config: serde_json::Value = std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    from_config(&config)
+}
+
+/// Check an expression against the allowed vocabulary
+fn validate_expression(expression: &str) -> Result<(), String> {
+    if expression.trim().is_empty() {
+        return Err("Expression cannot be empty".to_string());
+    }
+    if expression.len() > MAX_EXPRESSION_LEN {
+        return Err(format!("Expression must be at most {} characters", MAX_EXPRESSION_LEN));
+    }
+    if expression.contains("--") || expression.contains("/*") {
+        return Err("Comments are not allowed in expressions".to_string());
+    }
+
+    let mut depth = 0i32;
+    let mut chars = expression.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_ascii_alphabetic() || c == '_' {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_ascii_alphanumeric() || **c == '_') {
+                word.push(c.to_ascii_lowercase());
+                chars.next();
+            }
+            let word = word.as_str();
+            if !COLUMNS.contains(&word) && !FUNCTIONS.contains(&word) && !KEYWORDS.contains(&word) {
+                return Err(format!("Unknown column or function '{}'", word));
+            }
+            continue;
+        }
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth < 0 {
+                    return Err("Unbalanced parentheses".to_string());
+                }
+            }
+            '0'..='9' | '.' | '+' | '-' | '*' | '/' | '%' | '<' | '>' | '=' | '!' | ',' => {}
+            c if c.is_whitespace() => {}
+            other => return Err(format!("Character '{}' is not allowed in expressions", other)),
+        }
+        chars.next();
+    }
+    if depth != 0 {
+        return Err("Unbalanced parentheses".to_string());
+    }
+    Ok(())
+}
+
+/// Validate the definitions (also against the database, so type errors show
+/// up now) and store them in config.json
+pub fn save_settings(config_path: &Path, db: &Database, mut metrics: Vec<DerivedMetric>) -> Result<Vec<DerivedMetric>, String> {
+    let mut names = HashSet::new();
+    for metric in &mut metrics {
+        metric.name = metric.name.trim().to_string();
+        metric.expression = metric.expression.trim().to_string();
+        metric.unit = metric.unit.take().map(|u| u.trim().to_string()).filter(|u| !u.is_empty());
+        if metric.name.is_empty() || metric.name.len() > MAX_NAME_LEN {
+            return Err(format!("Names must be 1 to {} characters", MAX_NAME_LEN));
+        }
+        if !names.insert(metric.name.to_lowercase()) {
+            return Err(format!("Duplicate name '{}'", metric.name));
+        }
+        validate_expression(&metric.expression).map_err(|e| format!("{}: {}", metric.name, e))?;
+        db.check_telemetry_expression(&metric.expression)
+            .map_err(|e| format!("{}: {}", metric.name, e))?;
+    }
+
+    let mut config: serde_json::Value = std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    config[CONFIG_KEY] = serde_json::json!(metrics);
+    std::fs::write(config_path, serde_json::to_string_pretty(&config).unwrap())
+        .map_err(|e| format!("Failed to write config: {}", e))?;
+    Ok(metrics)
+}
+
+/// Mean of the `(timestamp_ms, value)` samples falling in each bucket that
+/// starts at a record's timestamp and ends at the next one's
+fn bucket_means(records: &[TelemetryRecord], samples: &[(i64, Option<f64>)]) -> Vec<Option<f64>> {
+    let mut values = Vec::with_capacity(records.len());
+    let mut samples = samples.iter().peekable();
+    let ends = records.iter().skip(1).map(|r| Some(r.timestamp_ms)).chain(std::iter::once(None));
+    for end in ends {
+        let (mut sum, mut count) = (0.0, 0);
+        while let Some(&&(t, value)) = samples.peek() {
+            if end.is_some_and(|end| t >= end) {
+                break;
+            }
+            if let Some(v) = value.filter(|v| v.is_finite()) {
+                sum += v;
+                count += 1;
+            }
+            samples.next();
+        }
+        values.push((count > 0).then(|| sum / count as f64));
+    }
+    values
+}
+
+/// Evaluate the configured metrics for a flight, aligned with `records`.
+/// A metric that fails is logged and left out.
+pub fn series_for_flight(
+    db: &Database,
+    config_path: &Path,
+    flight_id: i64,
+    records: &[TelemetryRecord],
+) -> Vec<DerivedSeries> {
+    if records.is_empty() {
+        return Vec::new();
+    }
+    load(config_path)
+        .into_iter()
+        .filter_map(|metric| {
+            if let Err(e) = validate_expression(&metric.expression) {
+                log::warn!("Skipping derived metric '{}': {}", metric.name, e);
+                return None;
+            }
+            match db.evaluate_telemetry_expression(flight_id, &metric.expression) {
+                Ok(samples) => Some(DerivedSeries {
+                    values: bucket_means(records, &samples),
+                    name: metric.name,
+                    unit: metric.unit,
+                }),
+                Err(e) => {
+                    log::warn!("Failed to evaluate derived metric '{}' for flight {}: {}", metric.name, flight_id, e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expressions_and_buckets() {
+        assert!(validate_expression("battery_voltage * battery_current").is_ok());
+        assert!(validate_expression(
+            "(height - LAG(height) OVER (ORDER BY timestamp_ms)) * 1000 / (timestamp_ms - LAG(timestamp_ms) OVER (ORDER BY timestamp_ms))"
+        )
+        .is_ok());
+        assert!(validate_expression("CASE WHEN speed > 10 THEN 1 ELSE 0 END").is_ok());
+        assert!(validate_expression("1); DROP TABLE flights; --").is_err());
+        assert!(validate_expression("height) FROM telemetry UNION SELECT (1").is_err());
+        assert!(validate_expression("flight_mode = 'Sport'").is_err());
+
+        let record = |t| TelemetryRecord { timestamp_ms: t, ..Default::default() };
+        let records = [record(0), record(1000), record(2000)];
+        let samples = [(0, Some(1.0)), (500, Some(3.0)), (1000, None), (2000, Some(5.0)), (2500, Some(7.0))];
+        assert_eq!(bucket_means(&records, &samples), vec![Some(2.0), None, Some(6.0)]);
+    }
+}
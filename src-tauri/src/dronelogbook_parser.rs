@@ -13,13 +13,14 @@ use chrono::{DateTime, NaiveDateTime, Utc, TimeZone};
 
 use crate::database::Database;
 use crate::models::{FlightMetadata, FlightMessage, FlightStats, TelemetryPoint};
-use crate::parser::{ParseResult, ParserError, LogParser};
+use crate::formats::{FlightLogFormat, ParseFuture};
+use crate::parser::{ParseInfo, ParseResult, ParserError, LogParser};
 
 /// Parse a timestamp string flexibly, handling multiple formats:
 /// - RFC3339: "2026-02-01T14:35:52+00:00" or "2026-02-01T14:35:52Z"
 /// - DuckDB VARCHAR cast: "2026-02-01 14:35:52+00"
 /// - ISO without timezone: "2026-02-01T14:35:52" or "2026-02-01 14:35:52" (assumed UTC)
-fn parse_timestamp_flexible(s: &str) -> Option<DateTime<Utc>> {
+pub(crate) fn parse_timestamp_flexible(s: &str) -> Option<DateTime<Utc>> {
     let s = s.trim();
     
     // Try RFC3339 first (includes timezone)
@@ -140,6 +141,23 @@ impl ColumnMap {
     }
 }
 
+/// Open DroneLog CSV exports (also the output format of custom parser plugins)
+pub struct DroneLogbookFormat;
+
+impl FlightLogFormat for DroneLogbookFormat {
+    fn name(&self) -> &'static str {
+        "Open DroneLog CSV"
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        DroneLogbookParser::is_dronelogbook_csv(path)
+    }
+
+    fn parse<'a>(&'a self, ctx: &'a LogParser<'_>, path: &'a Path, file_hash: &'a str) -> ParseFuture<'a> {
+        Box::pin(async move { DroneLogbookParser::new(ctx.db()).parse(path, file_hash) })
+    }
+}
+
 /// Drone Logbook CSV Parser
 pub struct DroneLogbookParser<'a> {
     db: &'a Database,
@@ -568,6 +586,7 @@ impl<'a> DroneLogbookParser<'a> {
                 // Camera state
                 is_photo: col_map.get_bool(fields, "is_photo"),
                 is_video: col_map.get_bool(fields, "is_video"),
+                is_flying: None,
 
                 // Battery capacity
                 battery_full_capacity: col_map.get_f64(fields, "battery_full_capacity_mah"),
@@ -730,7 +749,7 @@ impl<'a> DroneLogbookParser<'a> {
         log::info!("Final auto tags: {:?}, manual tags: {:?}, notes: {:?}, messages: {}", 
             tags, imported_manual_tags, meta_notes.is_some(), imported_messages.len());
 
-        Ok(ParseResult { metadata, points, tags, manual_tags: imported_manual_tags, notes: meta_notes, color: meta_color, messages: imported_messages })
+        Ok(ParseResult { metadata, points, tags, manual_tags: imported_manual_tags, notes: meta_notes, color: meta_color, messages: imported_messages, info: ParseInfo::new("Open DroneLog CSV") })
     }
 }
 
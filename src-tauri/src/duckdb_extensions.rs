@@ -0,0 +1,182 @@
+//! DuckDB extension loading and offline mode.
+//!
+//! The extensions the app needs (`parquet` for backups and exports, `json`)
+//! are compiled into the bundled DuckDB through the crate's features, so they
+//! never have to be downloaded. DuckDB would still try to install other known
+//! extensions from the network when a query needs one. In offline mode
+//! (`duckdb_offline` in `config.json`, or `DUCKDB_OFFLINE=true` which
+//! overrides it) auto-installing is switched off, and extensions are only
+//! loaded from the bundled set and `duckdb_extensions/` in the data directory,
+//! where extension files can be copied by hand on air-gapped machines.
+
+use std::path::{Path, PathBuf};
+
+use duckdb::Connection;
+use serde::Serialize;
+
+use crate::database::{Database, DatabaseError};
+
+const CONFIG_KEY: &str = "duckdb_offline";
+const ENV_VAR: &str = "DUCKDB_OFFLINE";
+/// Extension files for offline mode, in the data directory
+const EXTENSION_DIR: &str = "duckdb_extensions";
+/// Compiled into the bundled DuckDB (see the `duckdb` features in Cargo.toml)
+pub const BUNDLED: &[&str] = &["parquet", "json"];
+
+/// Offline mode, as configured
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineMode {
+    pub enabled: bool,
+    /// Forced by `DUCKDB_OFFLINE` (the config setting is ignored)
+    pub from_env: bool,
+}
+
+/// An extension known to DuckDB
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionInfo {
+    pub name: String,
+    pub loaded: bool,
+    pub installed: bool,
+    pub bundled: bool,
+    pub install_path: Option<String>,
+}
+
+/// Returned by `get_duckdb_extensions`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionStatus {
+    pub offline: OfflineMode,
+    /// Where extensions are loaded from in offline mode
+    pub extension_directory: String,
+    /// Loaded or installed extensions
+    pub extensions: Vec<ExtensionInfo>,
+}
+
+fn env_flag() -> Option<bool> {
+    std::env::var(ENV_VAR)
+        .ok()
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+}
+
+/// Read the offline setting from a `config.json` (off when missing)
+pub fn offline_mode(config_path: &Path) -> OfflineMode {
+    if let Some(enabled) = env_flag() {
+        return OfflineMode { enabled, from_env: true };
+    }
+    let enabled = std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        .and_then(|config| config.get(CONFIG_KEY).and_then(|v| v.as_bool()))
+        .unwrap_or(false);
+    OfflineMode { enabled, from_env: false }
+}
+
+/// Store the offline setting in config.json
+pub fn save_offline_mode(config_path: &Path, enabled: bool) -> Result<(), String> {
+    let mut config: serde_json::Value = std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    config[CONFIG_KEY] = serde_json::json!(enabled);
+    std::fs::write(config_path, serde_json::to_string_pretty(&config).unwrap())
+        .map_err(|e| format!("Failed to write config: {}", e))
+}
+
+/// `duckdb_extensions/` in the data directory
+pub fn extension_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(EXTENSION_DIR)
+}
+
+fn sql_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Apply the extension settings to a connection (they are global to the
+/// database instance) and load the bundled extensions
+pub fn configure(conn: &Connection, data_dir: &Path, offline: bool) -> duckdb::Result<()> {
+    if offline {
+        let dir = extension_dir(data_dir);
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            log::warn!("Failed to create extension directory {:?}: {}", dir, e);
+        }
+        conn.execute_batch(&format!(
+            "SET GLOBAL autoinstall_known_extensions = false;
+             SET GLOBAL extension_directory = {};",
+            sql_string(&dir.to_string_lossy())
+        ))?;
+        log::info!("DuckDB offline mode: extensions are not downloaded, local ones are loaded from {:?}", dir);
+    } else {
+        conn.execute_batch("RESET GLOBAL autoinstall_known_extensions; RESET GLOBAL extension_directory;")?;
+    }
+    for name in BUNDLED {
+        if let Err(e) = conn.execute_batch(&format!("LOAD {};", name)) {
+            log::warn!("Bundled DuckDB extension '{}' failed to load: {}", name, e);
+        }
+    }
+    Ok(())
+}
+
+/// Extensions DuckDB has loaded or installed
+pub fn list(conn: &Connection) -> duckdb::Result<Vec<ExtensionInfo>> {
+    let mut stmt = conn.prepare(
+        "SELECT extension_name, loaded, installed, install_path
+         FROM duckdb_extensions()
+         WHERE loaded OR installed
+         ORDER BY extension_name",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let name: String = row.get(0)?;
+        Ok(ExtensionInfo {
+            bundled: BUNDLED.contains(&name.as_str()),
+            name,
+            loaded: row.get(1)?,
+            installed: row.get(2)?,
+            install_path: row.get::<_, Option<String>>(3)?.filter(|p| !p.is_empty()),
+        })
+    })?;
+    rows.collect()
+}
+
+/// Offline mode and extensions of a database
+pub fn status(db: &Database, config_path: &Path) -> Result<ExtensionStatus, DatabaseError> {
+    Ok(ExtensionStatus {
+        offline: offline_mode(config_path),
+        extension_directory: extension_dir(&db.data_dir).to_string_lossy().to_string(),
+        extensions: db.extensions()?,
+    })
+}
+
+/// Store the offline setting and apply it to the open database
+pub fn set_offline(db: &Database, config_path: &Path, enabled: bool) -> Result<ExtensionStatus, String> {
+    save_offline_mode(config_path, enabled)?;
+    let mode = offline_mode(config_path);
+    db.set_extensions_offline(mode.enabled).map_err(|e| e.to_string())?;
+    status(db, config_path).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offline_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.json");
+        assert!(!offline_mode(&config_path).enabled);
+        save_offline_mode(&config_path, true).unwrap();
+        assert!(offline_mode(&config_path).enabled);
+
+        let conn = Connection::open_in_memory().unwrap();
+        configure(&conn, dir.path(), true).unwrap();
+        let autoinstall: String = conn
+            .query_row("SELECT current_setting('autoinstall_known_extensions')::VARCHAR", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(autoinstall, "false");
+        assert!(extension_dir(dir.path()).is_dir());
+        // The bundled extensions are there without a download
+        let names: Vec<String> = list(&conn).unwrap().into_iter().filter(|e| e.loaded).map(|e| e.name).collect();
+        assert!(BUNDLED.iter().all(|b| names.iter().any(|n| n == b)));
+    }
+}
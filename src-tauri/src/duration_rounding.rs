@@ -0,0 +1,104 @@
+//! Flight time rounding for reports and exports.
+//!
+//! Authorities and employers want flight time logged in different ways:
+//!
+//! - `exact` (default) — to the second
+//! - `minute` — to the nearest whole minute
+//! - `tenths` — to the nearest tenth of an hour (6-minute blocks), the
+//!   convention of manned aviation logbooks and Hobbs meters
+//!
+//! The policy is a profile setting. It is applied to each flight's duration
+//! before totals are summed, as on the lines of a paper logbook, in the
+//! operator log, the invoice report, CSV export templates and the email
+//! report. Stored durations and the app's own views stay exact.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DurationRounding {
+    #[default]
+    Exact,
+    Minute,
+    Tenths,
+}
+
+impl DurationRounding {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim() {
+            "exact" => Ok(Self::Exact),
+            "minute" => Ok(Self::Minute),
+            "tenths" => Ok(Self::Tenths),
+            other => Err(format!("Unknown duration rounding '{}' (use exact, minute or tenths)", other)),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Exact => "exact",
+            Self::Minute => "minute",
+            Self::Tenths => "tenths",
+        }
+    }
+
+    /// Length of the step durations are rounded to (s)
+    fn step_secs(self) -> f64 {
+        match self {
+            Self::Exact => 1.0,
+            Self::Minute => 60.0,
+            Self::Tenths => 360.0,
+        }
+    }
+
+    /// Round one flight's duration (s)
+    pub fn round_secs(self, secs: f64) -> f64 {
+        let step = self.step_secs();
+        (secs.max(0.0) / step).round() * step
+    }
+
+    /// Sum of the rounded durations (s)
+    pub fn total_secs<I: IntoIterator<Item = f64>>(self, durations: I) -> f64 {
+        durations.into_iter().map(|d| self.round_secs(d)).sum()
+    }
+
+    /// Format a duration in this policy: `H:MM:SS`, `H:MM` or decimal hours (`1.3`)
+    pub fn format(self, secs: f64) -> String {
+        let secs = self.round_secs(secs);
+        match self {
+            Self::Exact => {
+                let s = secs as i64;
+                format!("{}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60)
+            }
+            Self::Minute => {
+                let minutes = (secs / 60.0) as i64;
+                format!("{}:{:02}", minutes / 60, minutes % 60)
+            }
+            Self::Tenths => format!("{:.1}", secs / 3600.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rounding_policies() {
+        // 1 h 5 min 31 s
+        let secs = 3931.4;
+        assert_eq!(DurationRounding::Exact.round_secs(secs), 3931.0);
+        assert_eq!(DurationRounding::Minute.round_secs(secs), 3960.0);
+        assert_eq!(DurationRounding::Tenths.round_secs(secs), 3960.0);
+        assert_eq!(DurationRounding::Tenths.round_secs(170.0), 0.0);
+        assert_eq!(DurationRounding::Tenths.round_secs(190.0), 360.0);
+
+        assert_eq!(DurationRounding::Exact.format(secs), "1:05:31");
+        assert_eq!(DurationRounding::Minute.format(secs), "1:06");
+        assert_eq!(DurationRounding::Tenths.format(secs), "1.1");
+
+        // Each flight is rounded before summing
+        assert_eq!(DurationRounding::Tenths.total_secs([200.0, 200.0, 200.0]), 1080.0);
+        assert_eq!(DurationRounding::parse("minute"), Ok(DurationRounding::Minute));
+        assert!(DurationRounding::parse("hobbs").is_err());
+    }
+}
@@ -0,0 +1,353 @@
+//! Scheduled summary emails for web deployments.
+//!
+//! The SMTP server comes from the environment (`SMTP_HOST`, `SMTP_FROM`, and
+//! optionally `SMTP_PORT`, `SMTP_USERNAME`, `SMTP_PASSWORD` and
+//! `SMTP_SECURITY` = `starttls` (default), `tls` or `none`). Each profile
+//! opts in under the `email_report` key of its `config.json` with the
+//! recipients and a weekly or monthly frequency. Every day at 07:00 UTC the
+//! scheduler sends weekly reports on Mondays (covering the previous Monday to
+//! Sunday) and monthly reports on the 1st (covering the previous month):
+//! new flights, hours and distance, batteries near the end of their cycle
+//! life and currency requirements that have lapsed or lapse within 30 days.
+
+use std::path::Path;
+
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::{Deserialize, Serialize};
+
+use crate::database::{Database, DatabaseError};
+use crate::duration_rounding::DurationRounding;
+use crate::models::{Flight, LogbookSummary};
+use crate::requirements::RequirementStatus;
+use crate::units::UnitSettings;
+
+const CONFIG_KEY: &str = "email_report";
+/// Requirements lapsing within this many days are listed
+const LAPSE_WARNING_DAYS: i64 = 30;
+
+#[derive(Debug, thiserror::Error)]
+pub enum EmailError {
+    #[error("SMTP is not configured (set SMTP_HOST and SMTP_FROM)")]
+    NotConfigured,
+
+    #[error("No recipients configured")]
+    NoRecipients,
+
+    #[error("Invalid email address '{0}'")]
+    InvalidAddress(String),
+
+    #[error("Failed to build the email: {0}")]
+    Build(#[from] lettre::error::Error),
+
+    #[error("SMTP error: {0}")]
+    Smtp(#[from] lettre::transport::smtp::Error),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Plain connection upgraded with STARTTLS (port 587)
+    StartTls,
+    /// TLS from the start (port 465)
+    Tls,
+    /// Unencrypted, for a relay on the local network
+    Plain,
+}
+
+/// SMTP server settings read from the environment
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+}
+
+impl SmtpConfig {
+    /// None unless `SMTP_HOST` and `SMTP_FROM` are set
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let host = var("SMTP_HOST")?;
+        let from = var("SMTP_FROM")?;
+        let security = match var("SMTP_SECURITY").as_deref() {
+            None | Some("starttls") => SmtpSecurity::StartTls,
+            Some("tls") => SmtpSecurity::Tls,
+            Some("none") => SmtpSecurity::Plain,
+            Some(other) => {
+                log::warn!("Ignoring invalid SMTP_SECURITY '{}' (expected starttls, tls or none)", other);
+                SmtpSecurity::StartTls
+            }
+        };
+        let default_port = match security {
+            SmtpSecurity::StartTls => 587,
+            SmtpSecurity::Tls => 465,
+            SmtpSecurity::Plain => 25,
+        };
+        Some(Self {
+            host,
+            port: var("SMTP_PORT").and_then(|p| p.parse().ok()).unwrap_or(default_port),
+            security,
+            username: var("SMTP_USERNAME"),
+            password: var("SMTP_PASSWORD"),
+            from,
+        })
+    }
+
+    /// Send a plain-text email
+    pub async fn send(&self, recipients: &[String], subject: &str, body: String) -> Result<(), EmailError> {
+        if recipients.is_empty() {
+            return Err(EmailError::NoRecipients);
+        }
+        let mailbox = |address: &str| {
+            address
+                .parse::<Mailbox>()
+                .map_err(|_| EmailError::InvalidAddress(address.to_string()))
+        };
+        let mut message = Message::builder().from(mailbox(&self.from)?).subject(subject);
+        for recipient in recipients {
+            message = message.to(mailbox(recipient)?);
+        }
+        let email = message.header(ContentType::TEXT_PLAIN).body(body)?;
+
+        let mut transport = match self.security {
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.host)?,
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&self.host)?,
+            SmtpSecurity::Plain => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&self.host),
+        }
+        .port(self.port);
+        if let (Some(username), Some(password)) = (&self.username, &self.password) {
+            transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        transport.build().send(email).await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFrequency {
+    #[default]
+    Weekly,
+    Monthly,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailReportSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub frequency: ReportFrequency,
+    #[serde(default)]
+    pub recipients: Vec<String>,
+}
+
+impl EmailReportSettings {
+    pub fn from_config(config: &serde_json::Value) -> Self {
+        config
+            .get(CONFIG_KEY)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Read the settings from a `config.json` (defaults when missing)
+    pub fn load(config_path: &Path) -> Self {
+        let config: serde_json::Value = std::fs::read_to_string(config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+        Self::from_config(&config)
+    }
+}
+
+/// Store new settings in config.json
+pub fn save_settings(config_path: &Path, mut settings: EmailReportSettings) -> Result<EmailReportSettings, String> {
+    settings.recipients = settings
+        .recipients
+        .iter()
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .collect();
+    for recipient in &settings.recipients {
+        if recipient.parse::<Mailbox>().is_err() {
+            return Err(format!("Invalid email address '{}'", recipient));
+        }
+    }
+    if settings.enabled && settings.recipients.is_empty() {
+        return Err("Add at least one recipient to enable the report".to_string());
+    }
+
+    let mut config: serde_json::Value = std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    config[CONFIG_KEY] = serde_json::json!(settings);
+    std::fs::write(config_path, serde_json::to_string_pretty(&config).unwrap())
+        .map_err(|e| format!("Failed to write config: {}", e))?;
+    Ok(settings)
+}
+
+/// The inclusive date range a report sent on `today` covers: the previous
+/// Monday to Sunday, or the previous calendar month
+pub fn report_period(frequency: ReportFrequency, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+    match frequency {
+        ReportFrequency::Weekly => {
+            let this_monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+            (this_monday - Duration::days(7), this_monday - Duration::days(1))
+        }
+        ReportFrequency::Monthly => {
+            let last_day = today.with_day(1).unwrap_or(today) - Duration::days(1);
+            (last_day.with_day(1).unwrap_or(last_day), last_day)
+        }
+    }
+}
+
+/// Whether a report of `frequency` is due on `today`
+pub fn is_due(frequency: ReportFrequency, today: NaiveDate) -> bool {
+    match frequency {
+        ReportFrequency::Weekly => today.weekday() == chrono::Weekday::Mon,
+        ReportFrequency::Monthly => today.day() == 1,
+    }
+}
+
+/// Subject and plain-text body of the report
+#[allow(clippy::too_many_arguments)]
+pub fn render_report(
+    profile: &str,
+    frequency: ReportFrequency,
+    (from, to): (NaiveDate, NaiveDate),
+    flights: &[Flight],
+    summary: &LogbookSummary,
+    requirements: &[RequirementStatus],
+    rounding: DurationRounding,
+    units: &UnitSettings,
+    today: NaiveDate,
+) -> (String, String) {
+    let mut period_flights: Vec<&Flight> = flights
+        .iter()
+        .filter(|f| f.start_time_utc().map(|t| t.date_naive()).is_some_and(|d| d >= from && d <= to))
+        .collect();
+    period_flights.sort_by_key(|f| f.start_time_utc());
+
+    let kind = match frequency {
+        ReportFrequency::Weekly => "Weekly",
+        ReportFrequency::Monthly => "Monthly",
+    };
+    let subject = if profile == "default" {
+        format!("Open DroneLog {} summary: {} to {}", kind.to_lowercase(), from, to)
+    } else {
+        format!("Open DroneLog {} summary ({}): {} to {}", kind.to_lowercase(), profile, from, to)
+    };
+
+    let secs = rounding.total_secs(period_flights.iter().filter_map(|f| f.duration_secs));
+    let meters: f64 = period_flights.iter().filter_map(|f| f.total_distance).sum();
+    let mut body = format!("{} summary for {} to {}\n\n", kind, from, to);
+    body.push_str(&format!(
+        "Flights: {} ({} h, {})\n",
+        period_flights.len(),
+        rounding.format(secs),
+        units.format_distance(meters)
+    ));
+    for f in &period_flights {
+        body.push_str(&format!(
+            "  {}  {}  {}\n",
+            f.start_time_utc().map(|t| t.format("%Y-%m-%d").to_string()).unwrap_or_default(),
+            rounding.format(f.duration_secs.unwrap_or(0.0)),
+            f.display_name
+        ));
+    }
+    body.push_str(&format!(
+        "\nLogbook total: {} flights, {:.1} h, {}\n",
+        summary.total_flights,
+        summary.total_hours,
+        units.format_distance(summary.total_distance_km * 1000.0)
+    ));
+
+    if !summary.battery_warning_serials.is_empty() {
+        body.push_str(&format!(
+            "\nBatteries near the end of their cycle life: {}\n",
+            summary.battery_warning_serials.join(", ")
+        ));
+    }
+
+    let horizon = today + Duration::days(LAPSE_WARNING_DAYS);
+    let due: Vec<String> = requirements
+        .iter()
+        .filter_map(|r| {
+            if !r.compliant {
+                return Some(format!("  {}: not current", r.requirement.name));
+            }
+            let expires = r.expires_at.as_deref()?;
+            let date = NaiveDate::parse_from_str(expires.get(..10)?, "%Y-%m-%d").ok()?;
+            (date <= horizon).then(|| format!("  {}: lapses {}", r.requirement.name, date))
+        })
+        .collect();
+    if !due.is_empty() {
+        body.push_str("\nCurrency:\n");
+        body.push_str(&due.join("\n"));
+        body.push('\n');
+    }
+    body.push_str("\n-- \nSent by Open DroneLog. Change or turn off this report in the settings.\n");
+    (subject, body)
+}
+
+/// Build the report of a profile for the period ending before `today`
+pub fn build_report(
+    db: &Database,
+    profile: &str,
+    frequency: ReportFrequency,
+    units: &UnitSettings,
+    today: NaiveDate,
+) -> Result<(String, String), EmailError> {
+    let flights = db.get_all_flights()?;
+    let summary = db.get_logbook_summary()?;
+    let now = Utc::now();
+    let requirements: Vec<RequirementStatus> = db
+        .get_requirements()?
+        .iter()
+        .map(|r| crate::requirements::evaluate(r, &flights, now))
+        .collect();
+    Ok(render_report(
+        profile,
+        frequency,
+        report_period(frequency, today),
+        &flights,
+        &summary,
+        &requirements,
+        db.get_duration_rounding(),
+        units,
+        today,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_period() {
+        let thursday = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        let monday = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        assert_eq!(
+            report_period(ReportFrequency::Weekly, monday),
+            (NaiveDate::from_ymd_opt(2026, 10, 5).unwrap(), NaiveDate::from_ymd_opt(2026, 10, 11).unwrap())
+        );
+        assert!(is_due(ReportFrequency::Weekly, monday));
+        assert!(!is_due(ReportFrequency::Weekly, thursday));
+
+        let first = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        assert_eq!(
+            report_period(ReportFrequency::Monthly, first),
+            (NaiveDate::from_ymd_opt(2026, 2, 1).unwrap(), NaiveDate::from_ymd_opt(2026, 2, 28).unwrap())
+        );
+        assert!(is_due(ReportFrequency::Monthly, first));
+    }
+}
@@ -0,0 +1,406 @@
+//! Weather-aware endurance benchmarking.
+//!
+//! Flight endurance depends on the conditions as much as on the aircraft:
+//! headwinds make the motors work harder, and a cold pack delivers less of
+//! its capacity. For every aircraft model + battery combination, the battery
+//! drain of past flights (charge used per minute) is correlated with the
+//! weather they were flown in:
+//!
+//! ```text
+//! drain per minute = base + wind_coef × wind (km/h) + cold_coef × max(0, 20 °C − temperature)
+//! ```
+//!
+//! fitted by least squares. The conditions of each flight come from the
+//! Open-Meteo historical weather at the home point and start hour, fetched
+//! once and kept in the `flight_weather` table. Flights without weather still
+//! count towards the base drain. The expected endurance under given
+//! conditions is the usable charge (100 % minus the landing reserve) divided
+//! by the predicted drain.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::database::{Database, DatabaseError};
+
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(10);
+/// Temperature below which cold is assumed to cost capacity (°C)
+pub const REFERENCE_TEMP_C: f64 = 20.0;
+/// Charge left at landing when none is given (%)
+pub const DEFAULT_RESERVE_PERCENT: f64 = 20.0;
+/// Flights needed before a combination is benchmarked
+const MIN_FLIGHTS: usize = 3;
+/// Flights with weather needed before the wind and cold terms are fitted
+const MIN_WEATHER_FLIGHTS: usize = 5;
+/// Flights whose weather is looked up per request
+pub const WEATHER_BACKFILL_LIMIT: usize = 25;
+
+/// Weather at a flight's home point and start hour
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightWeather {
+    pub flight_id: i64,
+    pub temperature_c: Option<f64>,
+    pub wind_speed_kmh: Option<f64>,
+    pub wind_gusts_kmh: Option<f64>,
+}
+
+/// One flight's drain and conditions
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnduranceSample {
+    pub aircraft: String,
+    pub battery_serial: String,
+    pub duration_secs: f64,
+    /// Battery percent used during the flight
+    pub drain_percent: f64,
+    pub wind_speed_kmh: Option<f64>,
+    pub temperature_c: Option<f64>,
+}
+
+impl EnduranceSample {
+    fn drain_per_min(&self) -> f64 {
+        self.drain_percent / (self.duration_secs / 60.0)
+    }
+}
+
+/// Conditions the endurance is estimated for
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Conditions {
+    pub wind_speed_kmh: f64,
+    pub temperature_c: f64,
+}
+
+impl Default for Conditions {
+    fn default() -> Self {
+        Self { wind_speed_kmh: 0.0, temperature_c: REFERENCE_TEMP_C }
+    }
+}
+
+/// Fitted drain model of one combination (% per minute)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DrainModel {
+    /// Drain in calm air at 20 °C or warmer
+    pub base: f64,
+    /// Extra drain per km/h of wind
+    pub wind_coef: f64,
+    /// Extra drain per °C below 20 °C
+    pub cold_coef: f64,
+}
+
+impl DrainModel {
+    pub fn drain_per_min(&self, conditions: Conditions) -> f64 {
+        self.base
+            + self.wind_coef * conditions.wind_speed_kmh.max(0.0)
+            + self.cold_coef * (REFERENCE_TEMP_C - conditions.temperature_c).max(0.0)
+    }
+
+    /// Minutes until the reserve is reached, None when the model predicts no drain
+    pub fn endurance_minutes(&self, conditions: Conditions, reserve_percent: f64) -> Option<f64> {
+        let drain = self.drain_per_min(conditions);
+        (drain > 0.0).then(|| (100.0 - reserve_percent).max(0.0) / drain)
+    }
+}
+
+/// Endurance benchmark of one aircraft model + battery combination
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnduranceBenchmark {
+    pub aircraft: String,
+    pub battery_serial: String,
+    pub battery_name: Option<String>,
+    pub flights: usize,
+    pub flights_with_weather: usize,
+    pub mean_flight_minutes: f64,
+    pub model: DrainModel,
+    /// False when too few flights have weather: only the base drain is fitted
+    pub weather_fitted: bool,
+    /// Endurance in calm air at 20 °C
+    pub baseline_minutes: Option<f64>,
+    /// Endurance under the requested conditions
+    pub expected_minutes: Option<f64>,
+}
+
+/// Benchmarks for every combination with enough flights
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnduranceReport {
+    pub conditions: Conditions,
+    /// "forecast" when looked up at a location, otherwise "given"
+    pub conditions_source: String,
+    pub reserve_percent: f64,
+    /// Flights whose weather was looked up for this report
+    pub weather_backfilled: usize,
+    pub benchmarks: Vec<EnduranceBenchmark>,
+}
+
+/// Solve a 3×3 linear system by Gaussian elimination with partial pivoting
+fn solve3(mut a: [[f64; 3]; 3], mut b: [f64; 3]) -> Option<[f64; 3]> {
+    for col in 0..3 {
+        let pivot = (col..3).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..3 {
+            let factor = a[row][col] / a[col][col];
+            for k in col..3 {
+                a[row][k] -= factor * a[col][k];
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = [0.0; 3];
+    for row in (0..3).rev() {
+        let sum: f64 = (row + 1..3).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+/// Fit the drain model. Flights without weather take the mean conditions of
+/// those with weather, so they only inform the base drain.
+pub fn fit(samples: &[EnduranceSample]) -> Option<(DrainModel, bool)> {
+    if samples.is_empty() {
+        return None;
+    }
+    let mean_drain = samples.iter().map(|s| s.drain_per_min()).sum::<f64>() / samples.len() as f64;
+    let with_weather: Vec<(f64, f64)> = samples
+        .iter()
+        .filter_map(|s| Some((s.wind_speed_kmh?, (REFERENCE_TEMP_C - s.temperature_c?).max(0.0))))
+        .collect();
+    if with_weather.len() < MIN_WEATHER_FLIGHTS {
+        return Some((DrainModel { base: mean_drain, wind_coef: 0.0, cold_coef: 0.0 }, false));
+    }
+    let n = with_weather.len() as f64;
+    let mean_wind = with_weather.iter().map(|w| w.0).sum::<f64>() / n;
+    let mean_cold = with_weather.iter().map(|w| w.1).sum::<f64>() / n;
+
+    // Normal equations of drain = base + wind_coef·wind + cold_coef·cold
+    let mut ata = [[0.0; 3]; 3];
+    let mut atb = [0.0; 3];
+    for s in samples {
+        let wind = s.wind_speed_kmh.unwrap_or(mean_wind);
+        let cold = s.temperature_c.map_or(mean_cold, |t| (REFERENCE_TEMP_C - t).max(0.0));
+        let row = [1.0, wind, cold];
+        for i in 0..3 {
+            for j in 0..3 {
+                ata[i][j] += row[i] * row[j];
+            }
+            atb[i] += row[i] * s.drain_per_min();
+        }
+    }
+    // Conditions that never varied (always warm, say) leave a term undetermined
+    for (i, spread) in [(1, with_weather.iter().any(|w| (w.0 - mean_wind).abs() > 1e-9)), (2, with_weather.iter().any(|w| (w.1 - mean_cold).abs() > 1e-9))] {
+        if !spread {
+            ata[i] = [0.0; 3];
+            ata[i][i] = 1.0;
+            atb[i] = 0.0;
+            for (j, row) in ata.iter_mut().enumerate() {
+                if j != i {
+                    row[i] = 0.0;
+                }
+            }
+        }
+    }
+    let [base, wind_coef, cold_coef] = solve3(ata, atb)?;
+    // Wind and cold cannot make a flight cheaper: refit without a negative term
+    if wind_coef < 0.0 || cold_coef < 0.0 {
+        let clamp = DrainModel { base, wind_coef: wind_coef.max(0.0), cold_coef: cold_coef.max(0.0) };
+        let residual_mean = samples
+            .iter()
+            .map(|s| {
+                let conditions = Conditions {
+                    wind_speed_kmh: s.wind_speed_kmh.unwrap_or(mean_wind),
+                    temperature_c: s.temperature_c.unwrap_or(REFERENCE_TEMP_C - mean_cold),
+                };
+                s.drain_per_min() - (clamp.drain_per_min(conditions) - clamp.base)
+            })
+            .sum::<f64>()
+            / samples.len() as f64;
+        return Some((DrainModel { base: residual_mean, ..clamp }, true));
+    }
+    Some((DrainModel { base, wind_coef, cold_coef }, true))
+}
+
+/// Benchmark every aircraft + battery combination with enough flights
+pub fn benchmarks(
+    samples: &[EnduranceSample],
+    battery_names: &HashMap<String, String>,
+    conditions: Conditions,
+    reserve_percent: f64,
+) -> Vec<EnduranceBenchmark> {
+    let mut groups: BTreeMap<(String, String), Vec<EnduranceSample>> = BTreeMap::new();
+    for s in samples {
+        groups.entry((s.aircraft.clone(), s.battery_serial.clone())).or_default().push(s.clone());
+    }
+    groups
+        .into_iter()
+        .filter(|(_, list)| list.len() >= MIN_FLIGHTS)
+        .filter_map(|((aircraft, battery_serial), list)| {
+            let (model, weather_fitted) = fit(&list)?;
+            Some(EnduranceBenchmark {
+                battery_name: battery_names.get(&battery_serial).cloned(),
+                flights: list.len(),
+                flights_with_weather: list.iter().filter(|s| s.wind_speed_kmh.is_some() && s.temperature_c.is_some()).count(),
+                mean_flight_minutes: list.iter().map(|s| s.duration_secs / 60.0).sum::<f64>() / list.len() as f64,
+                model,
+                weather_fitted,
+                baseline_minutes: model.endurance_minutes(Conditions::default(), reserve_percent),
+                expected_minutes: model.endurance_minutes(conditions, reserve_percent),
+                aircraft,
+                battery_serial,
+            })
+        })
+        .collect()
+}
+
+/// Historical weather at a point and hour from Open-Meteo (archive for older
+/// flights, the forecast API's recent past otherwise)
+async fn fetch_historical(client: &reqwest::Client, lat: f64, lon: f64, time: DateTime<Utc>) -> Option<(f64, f64, Option<f64>)> {
+    let hour = time.format("%Y-%m-%dT%H:00").to_string();
+    let base = if Utc::now() - time > chrono::Duration::days(7) {
+        "https://archive-api.open-meteo.com/v1/archive"
+    } else {
+        "https://api.open-meteo.com/v1/forecast"
+    };
+    let url = format!(
+        "{}?latitude={:.4}&longitude={:.4}&hourly=temperature_2m,wind_speed_10m,wind_gusts_10m&start_hour={}&end_hour={}&timezone=GMT",
+        base, lat, lon, hour, hour
+    );
+    let body: serde_json::Value = match client.get(&url).send().await {
+        Ok(resp) if resp.status().is_success() => resp.json().await.ok()?,
+        Ok(resp) => {
+            log::debug!("Historical weather lookup failed: HTTP {}", resp.status());
+            return None;
+        }
+        Err(e) => {
+            log::debug!("Historical weather lookup failed: {}", e);
+            return None;
+        }
+    };
+    let hourly = body.get("hourly")?;
+    let value = |key: &str| hourly.get(key).and_then(|a| a.get(0)).and_then(|v| v.as_f64());
+    Some((value("temperature_2m")?, value("wind_speed_10m")?, value("wind_gusts_10m")))
+}
+
+/// Look up the weather of up to `limit` flights that have none yet.
+/// Returns the number of flights stored.
+pub async fn backfill_weather(db: &Database, limit: usize) -> Result<usize, DatabaseError> {
+    let pending = db.get_flights_missing_weather(limit)?;
+    if pending.is_empty() {
+        return Ok(0);
+    }
+    let Ok(client) = reqwest::Client::builder().timeout(LOOKUP_TIMEOUT).build() else {
+        return Ok(0);
+    };
+    let mut stored = 0;
+    for (flight_id, lat, lon, start) in pending {
+        let Some((temperature_c, wind_speed_kmh, wind_gusts_kmh)) = fetch_historical(&client, lat, lon, start).await else {
+            continue;
+        };
+        db.save_flight_weather(&FlightWeather {
+            flight_id,
+            temperature_c: Some(temperature_c),
+            wind_speed_kmh: Some(wind_speed_kmh),
+            wind_gusts_kmh,
+        })?;
+        stored += 1;
+    }
+    log::info!("Looked up the weather of {} flights for endurance benchmarks", stored);
+    Ok(stored)
+}
+
+/// Endurance report for the given conditions, or the current forecast at
+/// `location` when they are not given (calm and 20 °C as a last resort)
+pub async fn report(
+    db: &Database,
+    wind_speed_kmh: Option<f64>,
+    temperature_c: Option<f64>,
+    location: Option<(f64, f64)>,
+    reserve_percent: Option<f64>,
+) -> Result<EnduranceReport, String> {
+    let weather_backfilled = backfill_weather(db, WEATHER_BACKFILL_LIMIT)
+        .await
+        .map_err(|e| format!("Failed to store flight weather: {}", e))?;
+
+    let mut conditions = Conditions::default();
+    let mut conditions_source = "given".to_string();
+    if let (Some((lat, lon)), true) = (location, wind_speed_kmh.is_none() || temperature_c.is_none()) {
+        if let Some(forecast) = crate::preflight::fetch_forecast(lat, lon, Utc::now()).await {
+            conditions.wind_speed_kmh = forecast.get("windSpeedKmh").and_then(|v| v.as_f64()).unwrap_or(conditions.wind_speed_kmh);
+            conditions.temperature_c = forecast.get("temperatureC").and_then(|v| v.as_f64()).unwrap_or(conditions.temperature_c);
+            conditions_source = "forecast".to_string();
+        }
+    }
+    conditions.wind_speed_kmh = wind_speed_kmh.unwrap_or(conditions.wind_speed_kmh);
+    conditions.temperature_c = temperature_c.unwrap_or(conditions.temperature_c);
+    let reserve_percent = reserve_percent.unwrap_or(DEFAULT_RESERVE_PERCENT).clamp(0.0, 90.0);
+
+    let samples = db
+        .get_endurance_samples()
+        .map_err(|e| format!("Failed to load flights: {}", e))?;
+    let battery_names: HashMap<String, String> = db
+        .get_equipment_names("battery")
+        .map_err(|e| format!("Failed to load equipment names: {}", e))?
+        .into_iter()
+        .map(|(serial, name)| (serial.trim().to_uppercase(), name))
+        .collect();
+    Ok(EnduranceReport {
+        conditions,
+        conditions_source,
+        reserve_percent,
+        weather_backfilled,
+        benchmarks: benchmarks(&samples, &battery_names, conditions, reserve_percent),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(wind: Option<f64>, temp: Option<f64>, drain_per_min: f64) -> EnduranceSample {
+        EnduranceSample {
+            aircraft: "Mavic 3".to_string(),
+            battery_serial: "BAT1".to_string(),
+            duration_secs: 1200.0,
+            drain_percent: drain_per_min * 20.0,
+            wind_speed_kmh: wind,
+            temperature_c: temp,
+        }
+    }
+
+    #[test]
+    fn test_fit_recovers_wind_and_cold_costs() {
+        // drain = 2.5 + 0.05·wind + 0.04·cold
+        let truth = |wind: f64, temp: f64| 2.5 + 0.05 * wind + 0.04 * (REFERENCE_TEMP_C - temp).max(0.0);
+        let mut samples: Vec<EnduranceSample> = [(0.0, 25.0), (10.0, 22.0), (20.0, 15.0), (30.0, 5.0), (5.0, 0.0), (25.0, 18.0)]
+            .iter()
+            .map(|&(w, t)| sample(Some(w), Some(t), truth(w, t)))
+            .collect();
+        samples.push(sample(None, None, 2.9));
+
+        let (model, fitted) = fit(&samples).unwrap();
+        assert!(fitted);
+        assert!((model.wind_coef - 0.05).abs() < 0.01, "{:?}", model);
+        assert!((model.cold_coef - 0.04).abs() < 0.01, "{:?}", model);
+
+        let calm = model.endurance_minutes(Conditions::default(), 20.0).unwrap();
+        let windy_cold = model.endurance_minutes(Conditions { wind_speed_kmh: 30.0, temperature_c: 0.0 }, 20.0).unwrap();
+        assert!(calm > windy_cold);
+
+        let report = benchmarks(&samples, &Default::default(), Conditions::default(), 20.0);
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].flights, 7);
+        assert_eq!(report[0].flights_with_weather, 6);
+
+        // Too few flights with weather: only the base drain
+        let (model, fitted) = fit(&samples[4..]).unwrap();
+        assert!(!fitted);
+        assert_eq!(model.wind_coef, 0.0);
+    }
+}
@@ -0,0 +1,410 @@
+//! Configurable CSV export templates.
+//!
+//! Aviation authorities and clients often require flight records in a fixed
+//! layout: specific columns in a specific order, their own header names, units
+//! (feet, knots, decimal hours) and a locale's delimiter and decimal separator.
+//! A [`CsvTemplate`] describes such a layout; templates are stored in the
+//! `csv_export_templates` key of `config.json` and rendered against the
+//! flight list, one row per flight. Durations are rounded with the profile's
+//! duration rounding policy.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::duration_rounding::DurationRounding;
+use crate::models::Flight;
+
+const CONFIG_KEY: &str = "csv_export_templates";
+
+/// Flight fields a template column can reference
+pub const TEMPLATE_FIELDS: [&str; 22] = [
+    "id",
+    "display_name",
+    "file_name",
+    "date",
+    "takeoff_time",
+    "landing_time",
+    "duration",
+    "distance",
+    "max_altitude",
+    "max_speed",
+    "home_lat",
+    "home_lon",
+    "drone_model",
+    "drone_serial",
+    "aircraft_name",
+    "battery_serial",
+    "rc_serial",
+    "cycle_count",
+    "photo_count",
+    "video_count",
+    "tags",
+    "notes",
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum TemplateError {
+    #[error("Unknown template field: {0}")]
+    UnknownField(String),
+
+    #[error("Unit '{unit}' does not apply to field '{field}'")]
+    InvalidUnit { field: String, unit: String },
+
+    #[error("Template must have a name and at least one column")]
+    Empty,
+
+    #[error("Delimiter and decimal separator must be single, different characters")]
+    InvalidSeparators,
+
+    #[error("Template not found: {0}")]
+    NotFound(String),
+
+    #[error("Failed to write config: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// One output column
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateColumn {
+    /// One of [`TEMPLATE_FIELDS`]
+    pub field: String,
+    /// Header text; defaults to the field name
+    #[serde(default)]
+    pub header: Option<String>,
+    /// Unit for measurements: distance/altitude `m`, `km`, `ft`, `mi`, `nm`;
+    /// speed `ms`, `kmh`, `mph`, `kn`; duration `s`, `min`, `h`, `hms`
+    #[serde(default)]
+    pub unit: Option<String>,
+    /// Decimal places for measurements (default 2)
+    #[serde(default)]
+    pub decimals: Option<usize>,
+}
+
+/// A saved CSV export layout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvTemplate {
+    /// Generated on first save when empty
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub columns: Vec<TemplateColumn>,
+    #[serde(default = "default_delimiter")]
+    pub delimiter: String,
+    #[serde(default = "default_decimal_separator")]
+    pub decimal_separator: String,
+    /// chrono format string for the `date` column
+    #[serde(default = "default_date_format")]
+    pub date_format: String,
+    /// chrono format string for the takeoff/landing time columns
+    #[serde(default = "default_time_format")]
+    pub time_format: String,
+    #[serde(default = "default_true")]
+    pub include_header: bool,
+}
+
+fn default_delimiter() -> String {
+    ",".to_string()
+}
+
+fn default_decimal_separator() -> String {
+    ".".to_string()
+}
+
+fn default_date_format() -> String {
+    "%Y-%m-%d".to_string()
+}
+
+fn default_time_format() -> String {
+    "%H:%M:%S".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn single_char(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    let c = chars.next()?;
+    chars.next().is_none().then_some(c)
+}
+
+/// Conversion factor from the stored SI value, or None if the unit does not apply
+fn unit_factor(field: &str, unit: &str) -> Option<f64> {
+    match field {
+        "distance" | "max_altitude" => match unit {
+            "m" => Some(1.0),
+            "km" => Some(0.001),
+            "ft" => Some(3.280_84),
+            "mi" => Some(1.0 / 1609.344),
+            "nm" => Some(1.0 / 1852.0),
+            _ => None,
+        },
+        "max_speed" => match unit {
+            "ms" => Some(1.0),
+            "kmh" => Some(3.6),
+            "mph" => Some(2.236_936),
+            "kn" => Some(1.943_844),
+            _ => None,
+        },
+        "duration" => match unit {
+            "s" => Some(1.0),
+            "min" => Some(1.0 / 60.0),
+            "h" => Some(1.0 / 3600.0),
+            // Rendered as text, see `render_cell`
+            "hms" => Some(1.0),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl CsvTemplate {
+    /// Check field names, units and separators before saving or rendering
+    pub fn validate(&self) -> Result<(), TemplateError> {
+        if self.name.trim().is_empty() || self.columns.is_empty() {
+            return Err(TemplateError::Empty);
+        }
+        let delimiter = single_char(&self.delimiter).ok_or(TemplateError::InvalidSeparators)?;
+        let decimal = single_char(&self.decimal_separator).ok_or(TemplateError::InvalidSeparators)?;
+        if delimiter == decimal {
+            return Err(TemplateError::InvalidSeparators);
+        }
+        for col in &self.columns {
+            if !TEMPLATE_FIELDS.contains(&col.field.as_str()) {
+                return Err(TemplateError::UnknownField(col.field.clone()));
+            }
+            if let Some(unit) = &col.unit {
+                if unit_factor(&col.field, unit).is_none() {
+                    return Err(TemplateError::InvalidUnit { field: col.field.clone(), unit: unit.clone() });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn number(&self, value: f64, decimals: usize) -> String {
+        let s = format!("{:.*}", decimals, value);
+        if self.decimal_separator == "." {
+            s
+        } else {
+            s.replace('.', &self.decimal_separator)
+        }
+    }
+
+    fn render_cell(&self, col: &TemplateColumn, flight: &Flight, rounding: DurationRounding) -> String {
+        let start = flight.start_time_utc();
+        let duration = flight.duration_secs.map(|d| rounding.round_secs(d));
+        let decimals = col.decimals.unwrap_or(2);
+        let measure = |value: Option<f64>, default_unit: &str| {
+            value
+                .map(|v| {
+                    let unit = col.unit.as_deref().unwrap_or(default_unit);
+                    self.number(v * unit_factor(&col.field, unit).unwrap_or(1.0), decimals)
+                })
+                .unwrap_or_default()
+        };
+        let text = |value: &Option<String>| value.clone().unwrap_or_default();
+        let count = |value: Option<i32>| value.map(|v| v.to_string()).unwrap_or_default();
+
+        match col.field.as_str() {
+            "id" => flight.id.to_string(),
+            "display_name" => flight.display_name.clone(),
+            "file_name" => flight.file_name.clone(),
+            "date" => start.map(|t| t.format(&self.date_format).to_string()).unwrap_or_default(),
+            "takeoff_time" => start.map(|t| t.format(&self.time_format).to_string()).unwrap_or_default(),
+            "landing_time" => start
+                .zip(flight.duration_secs)
+                .map(|(t, d)| (t + chrono::Duration::milliseconds((d * 1000.0) as i64)).format(&self.time_format).to_string())
+                .unwrap_or_default(),
+            "duration" if col.unit.as_deref() == Some("hms") => duration
+                .map(|d| {
+                    let secs = d.round() as i64;
+                    format!("{}:{:02}:{:02}", secs / 3600, (secs % 3600) / 60, secs % 60)
+                })
+                .unwrap_or_default(),
+            "duration" => measure(duration, "s"),
+            "distance" => measure(flight.total_distance, "m"),
+            "max_altitude" => measure(flight.max_altitude, "m"),
+            "max_speed" => measure(flight.max_speed, "ms"),
+            "home_lat" => flight.home_lat.map(|v| self.number(v, col.decimals.unwrap_or(6))).unwrap_or_default(),
+            "home_lon" => flight.home_lon.map(|v| self.number(v, col.decimals.unwrap_or(6))).unwrap_or_default(),
+            "drone_model" => text(&flight.drone_model),
+            "drone_serial" => text(&flight.drone_serial),
+            "aircraft_name" => text(&flight.aircraft_name),
+            "battery_serial" => text(&flight.battery_serial),
+            "rc_serial" => text(&flight.rc_serial),
+            "cycle_count" => count(flight.cycle_count),
+            "photo_count" => count(flight.photo_count),
+            "video_count" => count(flight.video_count),
+            "tags" => flight.tags.iter().map(|t| t.tag.as_str()).collect::<Vec<_>>().join("; "),
+            "notes" => text(&flight.notes),
+            _ => String::new(),
+        }
+    }
+
+    /// Quote a field when it contains the delimiter, a quote or a line break
+    fn field(&self, value: &str) -> String {
+        if value.contains(self.delimiter.as_str()) || value.contains(['"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+
+    /// Render the flights as CSV, one row per flight
+    pub fn render(&self, flights: &[Flight], rounding: DurationRounding) -> Result<String, TemplateError> {
+        self.validate()?;
+        let mut out = String::new();
+        if self.include_header {
+            let headers: Vec<String> = self
+                .columns
+                .iter()
+                .map(|c| self.field(c.header.as_deref().unwrap_or(&c.field)))
+                .collect();
+            out.push_str(&headers.join(&self.delimiter));
+            out.push('\n');
+        }
+        for flight in flights {
+            let row: Vec<String> = self.columns.iter().map(|c| self.field(&self.render_cell(c, flight, rounding))).collect();
+            out.push_str(&row.join(&self.delimiter));
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+fn read_config(config_path: &Path) -> serde_json::Value {
+    std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}))
+}
+
+/// Load the saved templates (invalid entries are skipped)
+pub fn load_templates(config_path: &Path) -> Vec<CsvTemplate> {
+    read_config(config_path)
+        .get(CONFIG_KEY)
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| serde_json::from_value::<CsvTemplate>(v.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn store_templates(config_path: &Path, templates: &[CsvTemplate]) -> Result<(), TemplateError> {
+    let mut config = read_config(config_path);
+    config[CONFIG_KEY] = serde_json::json!(templates);
+    std::fs::write(config_path, serde_json::to_string_pretty(&config).unwrap_or_default())?;
+    Ok(())
+}
+
+/// Create or update a template (an empty id creates a new one)
+pub fn save_template(config_path: &Path, mut template: CsvTemplate) -> Result<CsvTemplate, TemplateError> {
+    template.validate()?;
+    let mut templates = load_templates(config_path);
+    if template.id.is_empty() {
+        template.id = uuid::Uuid::new_v4().to_string();
+    }
+    match templates.iter_mut().find(|t| t.id == template.id) {
+        Some(existing) => *existing = template.clone(),
+        None => templates.push(template.clone()),
+    }
+    store_templates(config_path, &templates)?;
+    Ok(template)
+}
+
+/// Delete a template; returns false if it did not exist
+pub fn delete_template(config_path: &Path, id: &str) -> Result<bool, TemplateError> {
+    let mut templates = load_templates(config_path);
+    let before = templates.len();
+    templates.retain(|t| t.id != id);
+    if templates.len() == before {
+        return Ok(false);
+    }
+    store_templates(config_path, &templates)?;
+    Ok(true)
+}
+
+/// Look up a saved template by id
+pub fn find_template(config_path: &Path, id: &str) -> Result<CsvTemplate, TemplateError> {
+    load_templates(config_path)
+        .into_iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| TemplateError::NotFound(id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FlightBuilder;
+
+    fn flight() -> Flight {
+        FlightBuilder::new(7)
+            .display_name("Roof; survey")
+            .start("2026-03-01T10:00:00Z")
+            .duration(754.0)
+            .distance(1852.0)
+            .max_altitude(100.0)
+            .build()
+    }
+
+    fn column(field: &str, header: Option<&str>, unit: Option<&str>, decimals: Option<usize>) -> TemplateColumn {
+        TemplateColumn {
+            field: field.to_string(),
+            header: header.map(str::to_string),
+            unit: unit.map(str::to_string),
+            decimals,
+        }
+    }
+
+    #[test]
+    fn test_render_with_units_and_locale() {
+        let template = CsvTemplate {
+            id: String::new(),
+            name: "Authority".to_string(),
+            columns: vec![
+                column("date", Some("Datum"), None, None),
+                column("display_name", Some("Flug"), None, None),
+                column("duration", Some("Dauer"), Some("hms"), None),
+                column("distance", Some("Strecke (NM)"), Some("nm"), Some(1)),
+                column("max_altitude", Some("Höhe (ft)"), Some("ft"), Some(0)),
+            ],
+            delimiter: ";".to_string(),
+            decimal_separator: ",".to_string(),
+            date_format: "%d.%m.%Y".to_string(),
+            time_format: default_time_format(),
+            include_header: true,
+        };
+        let csv = template.render(&[flight()], DurationRounding::Exact).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "Datum;Flug;Dauer;Strecke (NM);Höhe (ft)");
+        assert_eq!(lines[1], "01.03.2026;\"Roof; survey\";0:12:34;1,0;328");
+        let csv = template.render(&[flight()], DurationRounding::Minute).unwrap();
+        assert!(csv.lines().nth(1).unwrap().contains(";0:13:00;"));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_unit() {
+        let mut template = CsvTemplate {
+            id: String::new(),
+            name: "Bad".to_string(),
+            columns: vec![column("max_speed", None, Some("ft"), None)],
+            delimiter: default_delimiter(),
+            decimal_separator: default_decimal_separator(),
+            date_format: default_date_format(),
+            time_format: default_time_format(),
+            include_header: true,
+        };
+        assert!(matches!(template.validate(), Err(TemplateError::InvalidUnit { .. })));
+        template.columns[0].unit = Some("kn".to_string());
+        assert!(template.validate().is_ok());
+        template.decimal_separator = ",".to_string();
+        assert!(matches!(template.validate(), Err(TemplateError::InvalidSeparators)));
+    }
+}
@@ -0,0 +1,225 @@
+//! Flight fields from the log's file name.
+//!
+//! Users who name their logs consistently (`ACME_roof-survey_jdoe_001.txt`)
+//! can turn the naming into tags, a pilot, a job and notes. Rules are kept
+//! under the `filename_rules` key of `config.json`; each has a regular
+//! expression matched against the original file name, and the values it sets.
+//! Values can use the pattern's capture groups (`$1`, `${site}`). Every
+//! matching rule applies, in order:
+//!
+//! - tags are added as manual tags
+//! - pilot and notes are only set when the flight has none (the first
+//!   matching rule wins), so edits are never overwritten
+//! - the flight is assigned to the job unless it already belongs to one
+//!
+//! Applied by the `filename_rules` post-import hook, so rules also reach
+//! existing flights through `reprocess_flight`.
+
+use std::path::Path;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::database::Database;
+use crate::models::{Flight, FlightMetadataUpdate};
+
+const CONFIG_KEY: &str = "filename_rules";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilenameRule {
+    /// Regular expression matched against the file name (case-sensitive
+    /// unless it starts with `(?i)`)
+    pub pattern: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub pilot: Option<String>,
+    #[serde(default)]
+    pub job_id: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+/// What the matching rules set for one file name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuleMatch {
+    pub tags: Vec<String>,
+    pub pilot: Option<String>,
+    pub job_id: Option<String>,
+    pub notes: Option<String>,
+}
+
+impl RuleMatch {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.pilot.is_none() && self.job_id.is_none() && self.notes.is_none()
+    }
+}
+
+/// Read the rules from a `config.json` (none when missing)
+pub fn from_config(config: &serde_json::Value) -> Vec<FilenameRule> {
+    config
+        .get(CONFIG_KEY)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+pub fn load(config_path: &Path) -> Vec<FilenameRule> {
+    let config: serde_json::Value = std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    from_config(&config)
+}
+
+fn compile(rule: &FilenameRule) -> Result<Regex, String> {
+    Regex::new(&rule.pattern).map_err(|e| format!("Invalid pattern '{}': {}", rule.pattern, e))
+}
+
+/// Check and store the rules in config.json
+pub fn save_rules(config_path: &Path, rules: Vec<FilenameRule>) -> Result<Vec<FilenameRule>, String> {
+    let trimmed = |v: Option<String>| v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    let mut cleaned = Vec::with_capacity(rules.len());
+    for rule in rules {
+        let rule = FilenameRule {
+            pattern: rule.pattern.trim().to_string(),
+            tags: rule.tags.into_iter().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect(),
+            pilot: trimmed(rule.pilot),
+            job_id: trimmed(rule.job_id),
+            notes: trimmed(rule.notes),
+        };
+        if rule.pattern.is_empty() {
+            return Err("Every rule needs a pattern".to_string());
+        }
+        compile(&rule)?;
+        if rule.tags.is_empty() && rule.pilot.is_none() && rule.job_id.is_none() && rule.notes.is_none() {
+            return Err(format!("Rule '{}' sets nothing", rule.pattern));
+        }
+        cleaned.push(rule);
+    }
+
+    let mut config: serde_json::Value = std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    config[CONFIG_KEY] = serde_json::json!(cleaned);
+    std::fs::write(config_path, serde_json::to_string_pretty(&config).unwrap())
+        .map_err(|e| format!("Failed to write config: {}", e))?;
+    Ok(cleaned)
+}
+
+/// Values of all rules matching `file_name`, with capture groups expanded.
+/// Rules with an invalid pattern are skipped.
+pub fn evaluate(rules: &[FilenameRule], file_name: &str) -> RuleMatch {
+    let mut result = RuleMatch::default();
+    for rule in rules {
+        let Ok(re) = compile(rule) else {
+            continue;
+        };
+        let Some(caps) = re.captures(file_name) else {
+            continue;
+        };
+        let expand = |template: &str| {
+            let mut out = String::new();
+            caps.expand(template, &mut out);
+            Some(out.trim().to_string()).filter(|s| !s.is_empty())
+        };
+        for tag in rule.tags.iter().filter_map(|t| expand(t)) {
+            if !result.tags.contains(&tag) {
+                result.tags.push(tag);
+            }
+        }
+        if result.pilot.is_none() {
+            result.pilot = rule.pilot.as_deref().and_then(expand);
+        }
+        if result.notes.is_none() {
+            result.notes = rule.notes.as_deref().and_then(expand);
+        }
+        if result.job_id.is_none() {
+            result.job_id = rule.job_id.clone();
+        }
+    }
+    result
+}
+
+/// Apply the matching rules to a stored flight, returning a summary
+pub fn apply(db: &Database, flight: &mut Flight, rules: &[FilenameRule]) -> Result<String, String> {
+    let matched = evaluate(rules, &flight.file_name);
+    if matched.is_empty() {
+        return Ok("No matching rule".to_string());
+    }
+    let mut applied = Vec::new();
+
+    for tag in &matched.tags {
+        db.add_flight_tag(flight.id, tag).map_err(|e| format!("Failed to add tag: {}", e))?;
+    }
+    if !matched.tags.is_empty() {
+        applied.push(format!("{} tags", matched.tags.len()));
+    }
+
+    let is_blank = |v: &Option<String>| v.as_deref().map_or(true, |s| s.trim().is_empty());
+    if let Some(pilot) = matched.pilot.filter(|_| is_blank(&flight.pilot)) {
+        let update = FlightMetadataUpdate { pilot: Some(pilot.clone()), ..Default::default() };
+        db.update_flight_metadata(flight.id, &update)
+            .map_err(|e| format!("Failed to set pilot: {}", e))?;
+        applied.push(format!("pilot {}", pilot));
+        flight.pilot = Some(pilot);
+    }
+    if let Some(notes) = matched.notes.filter(|_| is_blank(&flight.notes)) {
+        db.update_flight_notes(flight.id, Some(&notes))
+            .map_err(|e| format!("Failed to set notes: {}", e))?;
+        applied.push("notes".to_string());
+        flight.notes = Some(notes);
+    }
+    if let Some(job_id) = matched.job_id {
+        let current = db.get_flight_job_id(flight.id).map_err(|e| format!("Failed to get job: {}", e))?;
+        if current.is_none() {
+            let job = db
+                .get_job(&job_id)
+                .map_err(|e| format!("Failed to get job: {}", e))?
+                .ok_or_else(|| format!("Job '{}' of a filename rule does not exist", job_id))?;
+            db.assign_flights_to_job(Some(&job.id), &[flight.id])
+                .map_err(|e| format!("Failed to assign job: {}", e))?;
+            applied.push(format!("job {}", job.name));
+        }
+    }
+
+    Ok(if applied.is_empty() { "Nothing to change".to_string() } else { applied.join(", ") })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_rules() {
+        let rules = vec![
+            FilenameRule {
+                pattern: r"^(?P<client>[A-Z]+)_(?P<site>[a-z-]+)_".to_string(),
+                tags: vec!["${client}".to_string(), "${site}".to_string()],
+                job_id: Some("job-1".to_string()),
+                ..Default::default()
+            },
+            FilenameRule {
+                pattern: r"(?i)_pilot-(\w+)\.".to_string(),
+                pilot: Some("$1".to_string()),
+                tags: vec!["ACME".to_string()],
+                ..Default::default()
+            },
+            FilenameRule { pattern: "^never".to_string(), notes: Some("x".to_string()), ..Default::default() },
+        ];
+        let matched = evaluate(&rules, "ACME_roof-survey_PILOT-jdoe.txt");
+        assert_eq!(matched.tags, vec!["ACME", "roof-survey"]);
+        assert_eq!(matched.pilot.as_deref(), Some("jdoe"));
+        assert_eq!(matched.job_id.as_deref(), Some("job-1"));
+        assert_eq!(matched.notes, None);
+        assert!(evaluate(&rules, "DJIFlightRecord_2024-05-01.txt").is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.json");
+        let bad = FilenameRule { pattern: "(".to_string(), tags: vec!["x".to_string()], ..Default::default() };
+        assert!(save_rules(&config_path, vec![bad]).is_err());
+        assert_eq!(save_rules(&config_path, rules).unwrap().len(), 3);
+        assert_eq!(load(&config_path).len(), 3);
+    }
+}
@@ -0,0 +1,524 @@
+//! Fleet dashboard for operators flying several aircraft.
+//!
+//! Unlike the personal overview stats, the fleet overview is organised by
+//! airframe, battery and pilot: utilization over a recent window, flights and
+//! hours since the last maintenance, battery pool health (cycles, capacity
+//! retention, discharge rate, voltage sag) and which pilots fly which
+//! aircraft. Display names come from the equipment registry, maintenance
+//! dates from the maintenance log kept next to it.
+//!
+//! Aircraft flown before they were logged here can be given an airframe
+//! seed (flights and time from elsewhere, also kept in the registry), which
+//! is added to their total airframe time.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::database::{Database, DatabaseError};
+use crate::models::Flight;
+
+/// Flights used for a battery's recent discharge rate
+const RECENT_DRAIN_FLIGHTS: usize = 10;
+/// Flights averaged for the first and the latest voltage sag
+const SAG_SAMPLE_FLIGHTS: usize = 5;
+
+/// One entry of the maintenance log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceRecord {
+    /// Generated on first save when empty
+    #[serde(default)]
+    pub id: String,
+    /// 'aircraft' or 'battery'
+    pub equipment_type: String,
+    pub serial: String,
+    /// `YYYY-MM-DD` or RFC 3339
+    pub performed_at: String,
+    pub notes: Option<String>,
+}
+
+/// Flights and time an aircraft flew before this logbook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AirframeSeed {
+    pub serial: String,
+    pub prior_duration_secs: f64,
+    #[serde(default)]
+    pub prior_flights: i64,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+/// Per-flight battery figures from the telemetry
+#[derive(Debug, Clone)]
+pub struct BatteryFlightCondition {
+    /// Charge used per minute of flight
+    pub drain_per_min: Option<f64>,
+    /// Largest full-charge capacity reported during the flight (mAh)
+    pub full_capacity_mah: Option<f64>,
+    /// Pack voltage drop under take-off load
+    pub sag_v: Option<f64>,
+}
+
+/// Flights and hours since the last maintenance (since the first flight
+/// when none is logged)
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceStatus {
+    pub last_maintenance: Option<String>,
+    pub flights_since: i64,
+    pub duration_since_secs: f64,
+    pub days_since: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetAircraft {
+    /// None for flights without an aircraft serial, grouped by model
+    pub serial: Option<String>,
+    pub display_name: Option<String>,
+    pub drone_model: Option<String>,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+    pub total_distance_m: f64,
+    pub last_flight: Option<String>,
+    pub recent_flights: i64,
+    pub recent_duration_secs: f64,
+    pub maintenance: MaintenanceStatus,
+    /// Pilots who flew the aircraft, most flights first
+    pub pilots: Vec<String>,
+    /// Airframe seed: flights and time before this logbook
+    pub prior_flights: i64,
+    pub prior_duration_secs: f64,
+    /// Total airframe time, the seed plus the logged flights
+    pub airframe_duration_secs: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetBattery {
+    pub serial: String,
+    pub display_name: Option<String>,
+    pub flight_count: i64,
+    /// Highest cycle count reported by the battery, else the flight count
+    pub cycles: i64,
+    pub total_duration_secs: f64,
+    pub first_flight: Option<String>,
+    pub last_flight: Option<String>,
+    pub recent_flights: i64,
+    pub first_full_capacity_mah: Option<f64>,
+    pub latest_full_capacity_mah: Option<f64>,
+    /// Latest full-charge capacity as a percentage of the first one seen
+    pub capacity_retention_pct: Option<f64>,
+    /// Mean charge used per minute over the last few flights
+    pub recent_drain_per_min: Option<f64>,
+    /// Mean take-off voltage sag over the battery's first few flights (V)
+    pub baseline_sag_v: Option<f64>,
+    /// Mean take-off voltage sag over its latest few flights (V)
+    pub recent_sag_v: Option<f64>,
+    pub maintenance: MaintenanceStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetPilot {
+    pub pilot: String,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+    pub last_flight: Option<String>,
+    pub recent_flights: i64,
+    /// Aircraft flown (serial, or model when unknown)
+    pub aircraft: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryPoolSummary {
+    pub battery_count: usize,
+    pub total_cycles: i64,
+    pub mean_capacity_retention_pct: Option<f64>,
+    pub lowest_capacity_retention_pct: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetOverview {
+    /// Length of the recent utilization window
+    pub window_days: i64,
+    pub aircraft: Vec<FleetAircraft>,
+    pub batteries: Vec<FleetBattery>,
+    pub battery_pool: BatteryPoolSummary,
+    pub pilots: Vec<FleetPilot>,
+    pub unassigned_flights: i64,
+}
+
+/// Parse a maintenance date (`YYYY-MM-DD` as midnight UTC, or RFC 3339)
+pub fn parse_performed_at(s: &str) -> Option<DateTime<Utc>> {
+    let s = s.trim();
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc()))
+}
+
+/// Check a maintenance record before saving it
+pub fn validate_maintenance(record: &MaintenanceRecord) -> Result<(), String> {
+    if record.equipment_type != "aircraft" && record.equipment_type != "battery" {
+        return Err(format!("Unknown equipment type '{}' (expected 'aircraft' or 'battery')", record.equipment_type));
+    }
+    if record.serial.trim().is_empty() {
+        return Err("Serial number cannot be empty".to_string());
+    }
+    if parse_performed_at(&record.performed_at).is_none() {
+        return Err(format!("Invalid maintenance date '{}' (expected YYYY-MM-DD or RFC 3339)", record.performed_at));
+    }
+    Ok(())
+}
+
+/// Check an airframe seed before saving it
+pub fn validate_airframe_seed(seed: &AirframeSeed) -> Result<(), String> {
+    if seed.serial.trim().is_empty() {
+        return Err("Serial number cannot be empty".to_string());
+    }
+    if !seed.prior_duration_secs.is_finite() || seed.prior_duration_secs < 0.0 || seed.prior_flights < 0 {
+        return Err("Prior flights and time cannot be negative".to_string());
+    }
+    Ok(())
+}
+
+fn non_empty(s: Option<&str>) -> Option<&str> {
+    s.map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// Latest maintenance date per `(equipment_type, serial)`
+fn latest_maintenance(records: &[MaintenanceRecord]) -> HashMap<(String, String), (DateTime<Utc>, String)> {
+    let mut latest: HashMap<(String, String), (DateTime<Utc>, String)> = HashMap::new();
+    for r in records {
+        let Some(at) = parse_performed_at(&r.performed_at) else {
+            continue;
+        };
+        let key = (r.equipment_type.clone(), r.serial.trim().to_uppercase());
+        if latest.get(&key).map_or(true, |(prev, _)| at > *prev) {
+            latest.insert(key, (at, r.performed_at.clone()));
+        }
+    }
+    latest
+}
+
+/// Without a logged maintenance, the airframe seed counts as flown since
+fn maintenance_status(
+    flights: &[&Flight],
+    last: Option<&(DateTime<Utc>, String)>,
+    seed: Option<&AirframeSeed>,
+    now: DateTime<Utc>,
+) -> MaintenanceStatus {
+    let since: Vec<&&Flight> = flights
+        .iter()
+        .filter(|f| match (last, f.start_time_utc()) {
+            (Some((at, _)), Some(start)) => start >= *at,
+            (Some(_), None) => false,
+            (None, _) => true,
+        })
+        .collect();
+    let reference = match last {
+        Some((at, _)) => Some(*at),
+        None => flights.iter().filter_map(|f| f.start_time_utc()).min(),
+    };
+    let (prior_flights, prior_secs) = match (last, seed) {
+        (None, Some(seed)) => (seed.prior_flights, seed.prior_duration_secs),
+        _ => (0, 0.0),
+    };
+    MaintenanceStatus {
+        last_maintenance: last.map(|(_, s)| s.clone()),
+        flights_since: since.len() as i64 + prior_flights,
+        duration_since_secs: prior_secs + since.iter().map(|f| f.duration_secs.unwrap_or(0.0)).sum::<f64>(),
+        days_since: reference.map(|t| (now - t).num_days()),
+    }
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+fn first_flight(flights: &[&Flight]) -> Option<String> {
+    flights.iter().filter_map(|f| f.start_time_utc()).min().map(|t| t.to_rfc3339())
+}
+
+fn last_flight(flights: &[&Flight]) -> Option<String> {
+    flights.iter().filter_map(|f| f.start_time_utc()).max().map(|t| t.to_rfc3339())
+}
+
+fn recent<'a>(flights: &[&'a Flight], window_start: DateTime<Utc>) -> Vec<&'a Flight> {
+    flights.iter().copied().filter(|f| f.start_time_utc().is_some_and(|t| t >= window_start)).collect()
+}
+
+fn total_duration(flights: &[&Flight]) -> f64 {
+    flights.iter().map(|f| f.duration_secs.unwrap_or(0.0)).sum()
+}
+
+/// Names ordered by how often they occur, then alphabetically
+fn by_frequency(counts: BTreeMap<String, i64>) -> Vec<String> {
+    let mut list: Vec<(String, i64)> = counts.into_iter().collect();
+    list.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    list.into_iter().map(|(name, _)| name).collect()
+}
+
+/// Aggregate the fleet overview. `names` maps `(equipment_type, serial)` to
+/// registry display names and `seeds` upper-cased aircraft serials to their
+/// airframe seed; `conditions` must be ordered by flight start time.
+pub fn fleet_overview(
+    flights: &[Flight],
+    names: &HashMap<(String, String), String>,
+    seeds: &HashMap<String, AirframeSeed>,
+    maintenance: &[MaintenanceRecord],
+    conditions: &HashMap<String, Vec<BatteryFlightCondition>>,
+    window_days: i64,
+    now: DateTime<Utc>,
+) -> FleetOverview {
+    let window_start = now - chrono::Duration::days(window_days);
+    let latest = latest_maintenance(maintenance);
+    let name_of = |kind: &str, serial: &str| names.get(&(kind.to_string(), serial.to_uppercase())).cloned();
+
+    // Aircraft: by serial, or by model for flights without one
+    let mut airframes: BTreeMap<(Option<String>, Option<String>), Vec<&Flight>> = BTreeMap::new();
+    let mut batteries: BTreeMap<String, Vec<&Flight>> = BTreeMap::new();
+    let mut pilots: BTreeMap<String, Vec<&Flight>> = BTreeMap::new();
+    let mut unassigned_flights = 0;
+    for f in flights {
+        let key = match non_empty(f.drone_serial.as_deref()) {
+            Some(serial) => (Some(serial.to_uppercase()), None),
+            None => (None, non_empty(f.drone_model.as_deref()).map(str::to_string)),
+        };
+        airframes.entry(key).or_default().push(f);
+        if let Some(serial) = non_empty(f.battery_serial.as_deref()) {
+            batteries.entry(serial.to_uppercase()).or_default().push(f);
+        }
+        match non_empty(f.pilot.as_deref()) {
+            Some(pilot) => pilots.entry(pilot.to_string()).or_default().push(f),
+            None => unassigned_flights += 1,
+        }
+    }
+    let airframe_label = |f: &Flight| {
+        non_empty(f.drone_serial.as_deref())
+            .map(str::to_uppercase)
+            .or_else(|| non_empty(f.drone_model.as_deref()).map(str::to_string))
+            .unwrap_or_else(|| "Unknown".to_string())
+    };
+
+    let mut aircraft: Vec<FleetAircraft> = airframes
+        .into_iter()
+        .map(|((serial, model), list)| {
+            let mut pilot_counts: BTreeMap<String, i64> = BTreeMap::new();
+            for f in &list {
+                if let Some(pilot) = non_empty(f.pilot.as_deref()) {
+                    *pilot_counts.entry(pilot.to_string()).or_default() += 1;
+                }
+            }
+            let drone_model = model.or_else(|| list.iter().rev().find_map(|f| non_empty(f.drone_model.as_deref()).map(str::to_string)));
+            let display_name = serial
+                .as_deref()
+                .and_then(|s| name_of("aircraft", s))
+                .or_else(|| list.iter().rev().find_map(|f| non_empty(f.aircraft_name.as_deref()).map(str::to_string)));
+            let last = serial.as_ref().and_then(|s| latest.get(&("aircraft".to_string(), s.clone())));
+            let recent_list = recent(&list, window_start);
+            let seed = serial.as_ref().and_then(|s| seeds.get(s));
+            let total_duration_secs = total_duration(&list);
+            let prior_duration_secs = seed.map_or(0.0, |s| s.prior_duration_secs);
+            FleetAircraft {
+                display_name,
+                drone_model,
+                flight_count: list.len() as i64,
+                total_duration_secs,
+                total_distance_m: list.iter().map(|f| f.total_distance.unwrap_or(0.0)).sum(),
+                last_flight: last_flight(&list),
+                recent_flights: recent_list.len() as i64,
+                recent_duration_secs: total_duration(&recent_list),
+                maintenance: maintenance_status(&list, last, seed, now),
+                pilots: by_frequency(pilot_counts),
+                prior_flights: seed.map_or(0, |s| s.prior_flights),
+                prior_duration_secs,
+                airframe_duration_secs: prior_duration_secs + total_duration_secs,
+                serial,
+            }
+        })
+        .collect();
+    aircraft.sort_by(|a, b| b.recent_duration_secs.total_cmp(&a.recent_duration_secs).then(b.flight_count.cmp(&a.flight_count)));
+
+    let no_conditions = Vec::new();
+    let mut battery_list: Vec<FleetBattery> = batteries
+        .into_iter()
+        .map(|(serial, list)| {
+            let points = conditions.get(&serial).unwrap_or(&no_conditions);
+            let capacities: Vec<f64> = points.iter().filter_map(|p| p.full_capacity_mah).collect();
+            let first_full_capacity_mah = capacities.first().copied();
+            let latest_full_capacity_mah = capacities.last().copied();
+            let capacity_retention_pct = match (first_full_capacity_mah, latest_full_capacity_mah) {
+                (Some(first), Some(latest)) if first > 0.0 => Some(latest / first * 100.0),
+                _ => None,
+            };
+            let drains: Vec<f64> = points.iter().rev().filter_map(|p| p.drain_per_min).take(RECENT_DRAIN_FLIGHTS).collect();
+            let sags: Vec<f64> = points.iter().filter_map(|p| p.sag_v).collect();
+            // Only a trend once the latest flights no longer overlap the first ones
+            let (baseline_sag_v, recent_sag_v) = if sags.len() >= 2 * SAG_SAMPLE_FLIGHTS {
+                (mean(&sags[..SAG_SAMPLE_FLIGHTS]), mean(&sags[sags.len() - SAG_SAMPLE_FLIGHTS..]))
+            } else {
+                (None, mean(&sags))
+            };
+            let reported_cycles = list.iter().filter_map(|f| f.cycle_count).max().map(i64::from);
+            let last = latest.get(&("battery".to_string(), serial.clone()));
+            FleetBattery {
+                display_name: name_of("battery", &serial),
+                flight_count: list.len() as i64,
+                cycles: reported_cycles.unwrap_or(list.len() as i64),
+                total_duration_secs: total_duration(&list),
+                first_flight: first_flight(&list),
+                last_flight: last_flight(&list),
+                recent_flights: recent(&list, window_start).len() as i64,
+                first_full_capacity_mah,
+                latest_full_capacity_mah,
+                capacity_retention_pct,
+                recent_drain_per_min: mean(&drains),
+                baseline_sag_v,
+                recent_sag_v,
+                maintenance: maintenance_status(&list, last, now),
+                serial,
+            }
+        })
+        .collect();
+    battery_list.sort_by(|a, b| b.cycles.cmp(&a.cycles).then_with(|| a.serial.cmp(&b.serial)));
+
+    let retentions: Vec<f64> = battery_list.iter().filter_map(|b| b.capacity_retention_pct).collect();
+    let battery_pool = BatteryPoolSummary {
+        battery_count: battery_list.len(),
+        total_cycles: battery_list.iter().map(|b| b.cycles).sum(),
+        mean_capacity_retention_pct: mean(&retentions),
+        lowest_capacity_retention_pct: retentions.iter().copied().reduce(f64::min),
+    };
+
+    let mut pilot_list: Vec<FleetPilot> = pilots
+        .into_iter()
+        .map(|(pilot, list)| {
+            let mut flown: BTreeMap<String, i64> = BTreeMap::new();
+            for f in &list {
+                *flown.entry(airframe_label(f)).or_default() += 1;
+            }
+            FleetPilot {
+                pilot,
+                flight_count: list.len() as i64,
+                total_duration_secs: total_duration(&list),
+                last_flight: last_flight(&list),
+                recent_flights: recent(&list, window_start).len() as i64,
+                aircraft: by_frequency(flown),
+            }
+        })
+        .collect();
+    pilot_list.sort_by(|a, b| b.flight_count.cmp(&a.flight_count).then_with(|| a.pilot.cmp(&b.pilot)));
+
+    FleetOverview {
+        window_days,
+        aircraft,
+        batteries: battery_list,
+        battery_pool,
+        pilots: pilot_list,
+        unassigned_flights,
+    }
+}
+
+/// Load everything the fleet overview needs and aggregate it
+pub fn load_fleet_overview(db: &Database, window_days: i64) -> Result<FleetOverview, DatabaseError> {
+    let flights = db.get_all_flights()?;
+    let (battery_names, aircraft_names) = db.get_all_equipment_names()?;
+    let mut names = HashMap::new();
+    for (kind, list) in [("battery", battery_names), ("aircraft", aircraft_names)] {
+        for (serial, name) in list {
+            names.insert((kind.to_string(), serial.trim().to_uppercase()), name);
+        }
+    }
+    let seeds = db
+        .get_airframe_seeds()?
+        .into_iter()
+        .map(|seed| (seed.serial.trim().to_uppercase(), seed))
+        .collect();
+    let maintenance = db.get_maintenance_records(None)?;
+    let conditions = db.get_battery_flight_conditions()?;
+    Ok(fleet_overview(&flights, &names, &seeds, &maintenance, &conditions, window_days, Utc::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FlightBuilder;
+
+    fn flight(id: i64, drone: &str, battery: &str, pilot: Option<&str>, start: &str, cycles: Option<i32>) -> Flight {
+        FlightBuilder::new(id)
+            .drone_serial(drone)
+            .battery_serial(battery)
+            .pilot(pilot)
+            .start(start)
+            .cycle_count(cycles)
+            .duration(1200.0)
+            .build()
+    }
+
+    #[test]
+    fn test_fleet_overview() {
+        let now = "2026-10-16T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let flights = vec![
+            flight(1, "1zn-a", "bat1", Some("Ana"), "2026-06-01T10:00:00Z", None),
+            flight(2, "1ZN-A", "bat1", Some("Ben"), "2026-10-01T10:00:00Z", Some(42)),
+            flight(3, "1ZN-A", "bat2", Some("Ben"), "2026-10-10T10:00:00Z", None),
+            flight(4, "1ZN-B", "bat2", None, "2026-10-12T10:00:00Z", None),
+        ];
+        let mut names = HashMap::new();
+        names.insert(("aircraft".to_string(), "1ZN-A".to_string()), "Inspection 1".to_string());
+        let maintenance = vec![MaintenanceRecord {
+            id: "m1".to_string(),
+            equipment_type: "aircraft".to_string(),
+            serial: "1zn-a".to_string(),
+            performed_at: "2026-09-15".to_string(),
+            notes: None,
+        }];
+        let mut conditions = HashMap::new();
+        conditions.insert(
+            "BAT2".to_string(),
+            vec![
+                BatteryFlightCondition { drain_per_min: Some(3.0), full_capacity_mah: Some(5000.0), sag_v: None },
+                BatteryFlightCondition { drain_per_min: Some(4.0), full_capacity_mah: Some(4500.0), sag_v: None },
+            ],
+        );
+
+        let mut seeds = HashMap::new();
+        for serial in ["1ZN-A", "1ZN-B"] {
+            seeds.insert(
+                serial.to_string(),
+                AirframeSeed { serial: serial.to_string(), prior_duration_secs: 36_000.0, prior_flights: 25, notes: None },
+            );
+        }
+
+        let fleet = fleet_overview(&flights, &names, &seeds, &maintenance, &conditions, 30, now);
+        let a = fleet.aircraft.iter().find(|a| a.serial.as_deref() == Some("1ZN-A")).unwrap();
+        assert_eq!(a.display_name.as_deref(), Some("Inspection 1"));
+        assert_eq!(a.flight_count, 3);
+        assert_eq!(a.recent_flights, 2);
+        assert_eq!(a.maintenance.flights_since, 2);
+        assert_eq!(a.maintenance.days_since, Some(31));
+        assert_eq!(a.airframe_duration_secs, 36_000.0 + 3.0 * 1200.0);
+        // Never maintained: the seed counts towards the maintenance interval
+        let b = fleet.aircraft.iter().find(|a| a.serial.as_deref() == Some("1ZN-B")).unwrap();
+        assert_eq!(b.maintenance.flights_since, 26);
+        assert_eq!(b.maintenance.duration_since_secs, 36_000.0 + 1200.0);
+        assert_eq!(a.pilots, vec!["Ben", "Ana"]);
+
+        let bat1 = fleet.batteries.iter().find(|b| b.serial == "BAT1").unwrap();
+        assert_eq!(bat1.cycles, 42);
+        let bat2 = fleet.batteries.iter().find(|b| b.serial == "BAT2").unwrap();
+        assert_eq!(bat2.capacity_retention_pct, Some(90.0));
+        assert_eq!(bat2.recent_drain_per_min, Some(3.5));
+        assert_eq!(fleet.battery_pool.total_cycles, 44);
+
+        assert_eq!(fleet.unassigned_flights, 1);
+        assert_eq!(fleet.pilots[0].pilot, "Ben");
+    }
+}
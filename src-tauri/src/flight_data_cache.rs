@@ -0,0 +1,245 @@
+//! On-disk cache of flight data responses (web mode).
+//!
+//! On a shared instance the same flights are opened over and over, and each
+//! open queries, downsamples and post-processes the telemetry. The serialized
+//! `GET /api/flight_data` response is therefore stored under
+//! `cache/flight_data/{profile}/{flight id}/` in the data directory, one file
+//! per `max_points` and encoding. Anonymized responses are never cached.
+//!
+//! Each file starts with a fingerprint of everything the response depends
+//! on: the cache format and app version, the flight row, its `updated_at`
+//! (bumped by edits, re-imports and telemetry changes), the profile's
+//! altitude reference and its `config.json` (derived metrics). A cached body
+//! is only served while the fingerprint still matches, so changes invalidate
+//! it without every write path having to know about the cache; deleted
+//! flights and profiles drop their files explicitly.
+//!
+//! `FLIGHT_DATA_CACHE_MAX_MB` caps the size of the cache (default 1024, `0`
+//! disables it); the oldest files are removed first.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use sha2::{Digest, Sha256};
+
+use crate::database::{Database, DatabaseError};
+use crate::models::Flight;
+
+/// Bump when the response format changes
+const CACHE_VERSION: u32 = 1;
+const DEFAULT_MAX_MB: u64 = 1024;
+/// Pruning brings the cache down to this share of the limit
+const PRUNE_TARGET_PERCENT: u64 = 90;
+
+/// Bytes written since the last size check
+static WRITTEN_SINCE_PRUNE: AtomicU64 = AtomicU64::new(u64::MAX);
+static PRUNE_LOCK: Mutex<()> = Mutex::new(());
+/// Distinguishes the temporary files of concurrent writes
+static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+
+/// Size limit in bytes, `None` when the cache is disabled
+fn max_bytes() -> Option<u64> {
+    static MAX: OnceLock<Option<u64>> = OnceLock::new();
+    *MAX.get_or_init(|| {
+        let mb = match std::env::var("FLIGHT_DATA_CACHE_MAX_MB") {
+            Ok(v) => v.trim().parse::<u64>().unwrap_or_else(|_| {
+                log::warn!("Invalid FLIGHT_DATA_CACHE_MAX_MB '{}', using {}", v, DEFAULT_MAX_MB);
+                DEFAULT_MAX_MB
+            }),
+            Err(_) => DEFAULT_MAX_MB,
+        };
+        if mb == 0 {
+            log::info!("Flight data cache disabled");
+            None
+        } else {
+            Some(mb * 1024 * 1024)
+        }
+    })
+}
+
+pub fn is_enabled() -> bool {
+    max_bytes().is_some()
+}
+
+fn cache_root(data_dir: &Path) -> PathBuf {
+    data_dir.join("cache").join("flight_data")
+}
+
+fn profile_dir(data_dir: &Path, profile: &str) -> PathBuf {
+    cache_root(data_dir).join(profile)
+}
+
+/// One cached response of a flight
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    path: PathBuf,
+    fingerprint: String,
+}
+
+impl CacheEntry {
+    /// Entry of a flight's response for `max_points` (`None` = full
+    /// resolution) and encoding, valid for the flight's current state
+    pub fn new(
+        db: &Database,
+        data_dir: &Path,
+        profile: &str,
+        flight: &Flight,
+        max_points: Option<usize>,
+        encoding: &str,
+    ) -> Result<Self, DatabaseError> {
+        let points = max_points.map_or_else(|| "all".to_string(), |n| n.to_string());
+        let path = profile_dir(data_dir, profile)
+            .join(flight.id.to_string())
+            .join(format!("{}.{}.json", points, encoding));
+
+        let mut hasher = Sha256::new();
+        hasher.update(format!("{}:{}:", CACHE_VERSION, env!("CARGO_PKG_VERSION")));
+        hasher.update(format!("{:?}:", db.get_flight_updated_ms(flight.id)?));
+        hasher.update(db.get_altitude_reference().as_str());
+        hasher.update(serde_json::to_vec(flight).unwrap_or_default());
+        hasher.update(std::fs::read(crate::database::config_path_for_profile(data_dir, profile)).unwrap_or_default());
+        let fingerprint = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+        Ok(Self { path, fingerprint })
+    }
+
+    /// The cached body, if it is still valid
+    pub fn get(&self) -> Option<Vec<u8>> {
+        let content = std::fs::read(&self.path).ok()?;
+        let newline = content.iter().position(|&b| b == b'\n')?;
+        if &content[..newline] != self.fingerprint.as_bytes() {
+            return None;
+        }
+        Some(content[newline + 1..].to_vec())
+    }
+
+    /// Store a body. Failures are logged: the cache is best-effort.
+    pub fn put(&self, body: &[u8]) {
+        let Some(max) = max_bytes() else {
+            return;
+        };
+        // Written aside and renamed, so concurrent readers never see a partial file
+        let temp = self.path.with_extension(format!("json.{}.tmp", NEXT_TEMP.fetch_add(1, Ordering::Relaxed)));
+        let result = self
+            .path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| {
+                let mut content = Vec::with_capacity(self.fingerprint.len() + 1 + body.len());
+                content.extend_from_slice(self.fingerprint.as_bytes());
+                content.push(b'\n');
+                content.extend_from_slice(body);
+                std::fs::write(&temp, content)
+            })
+            .and_then(|_| std::fs::rename(&temp, &self.path));
+        if let Err(e) = result {
+            let _ = std::fs::remove_file(&temp);
+            log::warn!("Failed to cache flight data at {}: {}", self.path.display(), e);
+            return;
+        }
+
+        // Check the total size once a tenth of the limit has been written
+        let written = WRITTEN_SINCE_PRUNE
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |w| Some(w.saturating_add(body.len() as u64)))
+            .unwrap_or(0);
+        if written.saturating_add(body.len() as u64) >= max / 10 {
+            WRITTEN_SINCE_PRUNE.store(0, Ordering::Relaxed);
+            if let Some(root) = self.path.ancestors().nth(3) {
+                prune(root, max * PRUNE_TARGET_PERCENT / 100);
+            }
+        }
+    }
+}
+
+/// Remove the oldest files until the cache under `root` fits in `target` bytes
+fn prune(root: &Path, target: u64) {
+    let Ok(_guard) = PRUNE_LOCK.try_lock() else {
+        return;
+    };
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                dirs.push(entry.path());
+            } else if let Ok(modified) = meta.modified() {
+                files.push((modified, meta.len(), entry.path()));
+            }
+        }
+    }
+    let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+    if total <= target {
+        return;
+    }
+    files.sort();
+    let mut removed = 0;
+    for (_, len, path) in files {
+        if total <= target {
+            break;
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            total = total.saturating_sub(len);
+            removed += 1;
+            if let Some(parent) = path.parent() {
+                // Only succeeds once the flight has no files left
+                let _ = std::fs::remove_dir(parent);
+            }
+        }
+    }
+    log::info!("Pruned {} cached flight data files ({} bytes kept)", removed, total);
+}
+
+/// Drop the cached responses of deleted flights
+pub fn invalidate_flights(data_dir: &Path, profile: &str, flight_ids: &[i64]) {
+    let dir = profile_dir(data_dir, profile);
+    for flight_id in flight_ids {
+        let _ = std::fs::remove_dir_all(dir.join(flight_id.to_string()));
+    }
+}
+
+/// Drop all cached responses of a profile
+pub fn invalidate_profile(data_dir: &Path, profile: &str) {
+    let dir = profile_dir(data_dir, profile);
+    if dir.exists() {
+        if let Err(e) = std::fs::remove_dir_all(&dir) {
+            log::warn!("Failed to clear the flight data cache of profile '{}': {}", profile, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_invalidated_by_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(dir.path().to_path_buf(), "default").unwrap();
+        let flight_id = crate::demo::seed_demo_data(&db, &serde_json::json!({})).unwrap()[0];
+        let flight = db.get_flight_by_id(flight_id).unwrap();
+
+        let entry = CacheEntry::new(&db, dir.path(), "default", &flight, Some(500), "json").unwrap();
+        assert_eq!(entry.get(), None);
+        entry.put(b"{\"flight\":{}}");
+        assert_eq!(entry.get().as_deref(), Some(&b"{\"flight\":{}}"[..]));
+
+        // A different resolution is a different entry
+        let other = CacheEntry::new(&db, dir.path(), "default", &flight, None, "json").unwrap();
+        assert_eq!(other.get(), None);
+
+        // Editing the flight changes the fingerprint
+        db.add_flight_tag(flight_id, "survey").unwrap();
+        let flight = db.get_flight_by_id(flight_id).unwrap();
+        let changed = CacheEntry::new(&db, dir.path(), "default", &flight, Some(500), "json").unwrap();
+        assert_eq!(changed.get(), None);
+
+        invalidate_flights(dir.path(), "default", &[flight_id]);
+        assert!(!entry.path.exists());
+    }
+}
@@ -0,0 +1,364 @@
+//! Printable flight list.
+//!
+//! Renders a list of flights (the ones currently shown in the filtered flight
+//! list, in the same order) with the columns the user picked, as CSV for
+//! spreadsheets or as a paginated A4 PDF table to hand to clients or
+//! examiners. Figures are in the display units (see `units`) and flight times
+//! are rounded with the profile's duration rounding policy. The PDF ends with
+//! the flight count and the total time and distance.
+
+use serde::{Deserialize, Serialize};
+
+use crate::bundle::{build_pdf_pages, pdf_text, PDF_MARGIN, PDF_PAGE_HEIGHT, PDF_PAGE_WIDTH};
+use crate::database::{Database, DatabaseError};
+use crate::duration_rounding::DurationRounding;
+use crate::models::Flight;
+use crate::operator_log::clip;
+use crate::stats_export::csv_field;
+use crate::units::{SpeedUnit, UnitSettings};
+
+const DEFAULT_TITLE: &str = "Flight summary";
+const ROW_HEIGHT: f64 = 12.0;
+const FONT_SIZE: f64 = 8.0;
+/// Average Helvetica character width at `FONT_SIZE`, to clip cells
+const CHAR_WIDTH: f64 = 4.4;
+/// Space between two columns
+const COLUMN_GAP: f64 = 6.0;
+
+/// A column of the list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlightColumn {
+    /// Take-off, UTC
+    Date,
+    Name,
+    Aircraft,
+    AircraftSerial,
+    BatterySerial,
+    Pilot,
+    Location,
+    Duration,
+    Distance,
+    MaxAltitude,
+    MaxSpeed,
+    Photos,
+    Videos,
+    Tags,
+    Notes,
+}
+
+/// Columns used when none are chosen
+pub const DEFAULT_COLUMNS: &[FlightColumn] = &[
+    FlightColumn::Date,
+    FlightColumn::Name,
+    FlightColumn::Aircraft,
+    FlightColumn::Location,
+    FlightColumn::Duration,
+    FlightColumn::Distance,
+    FlightColumn::MaxAltitude,
+];
+
+impl FlightColumn {
+    fn key(self) -> &'static str {
+        match self {
+            Self::Date => "date_utc",
+            Self::Name => "name",
+            Self::Aircraft => "aircraft",
+            Self::AircraftSerial => "aircraft_serial",
+            Self::BatterySerial => "battery_serial",
+            Self::Pilot => "pilot",
+            Self::Location => "location",
+            Self::Duration => "duration",
+            Self::Distance => "distance",
+            Self::MaxAltitude => "max_altitude",
+            Self::MaxSpeed => "max_speed",
+            Self::Photos => "photos",
+            Self::Videos => "videos",
+            Self::Tags => "tags",
+            Self::Notes => "notes",
+        }
+    }
+
+    /// CSV header, with the unit of numeric columns (`distance_km`, ...)
+    fn csv_header(self, units: &UnitSettings) -> String {
+        let unit = match self {
+            Self::Duration => "min",
+            Self::Distance => units.distance_label(),
+            Self::MaxAltitude => units.altitude_label(),
+            Self::MaxSpeed => match units.speed {
+                SpeedUnit::Ms => "ms",
+                SpeedUnit::Kmh => "kmh",
+                SpeedUnit::Mph => "mph",
+                SpeedUnit::Kn => "kn",
+            },
+            _ => return self.key().to_string(),
+        };
+        format!("{}_{}", self.key(), unit.to_ascii_lowercase())
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Date => "Take-off (UTC)",
+            Self::Name => "Flight",
+            Self::Aircraft => "Aircraft",
+            Self::AircraftSerial => "Aircraft serial",
+            Self::BatterySerial => "Battery",
+            Self::Pilot => "Pilot",
+            Self::Location => "Location",
+            Self::Duration => "Time",
+            Self::Distance => "Distance",
+            Self::MaxAltitude => "Max alt.",
+            Self::MaxSpeed => "Max speed",
+            Self::Photos => "Photos",
+            Self::Videos => "Videos",
+            Self::Tags => "Tags",
+            Self::Notes => "Notes",
+        }
+    }
+
+    /// Nominal PDF column width in points (scaled down when the columns don't fit)
+    fn width(self) -> f64 {
+        match self {
+            Self::Date => 62.0,
+            Self::Name | Self::Notes => 110.0,
+            Self::Aircraft | Self::Location | Self::Tags => 80.0,
+            Self::AircraftSerial | Self::BatterySerial => 70.0,
+            Self::Pilot => 55.0,
+            Self::Duration | Self::Distance | Self::MaxAltitude | Self::MaxSpeed => 42.0,
+            Self::Photos | Self::Videos => 30.0,
+        }
+    }
+
+    /// Formatted cell for the PDF
+    fn display(self, flight: &Flight, units: &UnitSettings, rounding: DurationRounding) -> String {
+        let dash = || "-".to_string();
+        match self {
+            Self::Duration => flight.duration_secs.map(|d| rounding.format(d)).unwrap_or_else(dash),
+            Self::Distance => flight.total_distance.map(|d| units.format_distance(d)).unwrap_or_else(dash),
+            Self::MaxAltitude => flight.max_altitude.map(|a| units.format_altitude(a)).unwrap_or_else(dash),
+            Self::MaxSpeed => flight.max_speed.map(|s| units.format_speed(s)).unwrap_or_else(dash),
+            _ => self.text(flight).unwrap_or_else(dash),
+        }
+    }
+
+    /// Raw value for the CSV (numbers in the display units)
+    fn csv_value(self, flight: &Flight, units: &UnitSettings, rounding: DurationRounding) -> String {
+        let number = |v: Option<f64>, decimals: usize| v.map(|v| format!("{:.*}", decimals, v)).unwrap_or_default();
+        match self {
+            Self::Duration => number(flight.duration_secs.map(|d| rounding.round_secs(d) / 60.0), 1),
+            Self::Distance => number(flight.total_distance.map(|d| units.distance_value(d)), 2),
+            Self::MaxAltitude => number(flight.max_altitude.map(|a| units.altitude_value(a)), 1),
+            Self::MaxSpeed => number(flight.max_speed.map(|s| units.speed_value(s)), 1),
+            _ => csv_field(&self.text(flight).unwrap_or_default()),
+        }
+    }
+
+    /// Value of the text columns
+    fn text(self, flight: &Flight) -> Option<String> {
+        let non_empty = |s: &Option<String>| s.clone().filter(|s| !s.trim().is_empty());
+        match self {
+            Self::Date => flight.start_time_utc().map(|t| t.format("%Y-%m-%d %H:%M").to_string()),
+            Self::Name => Some(flight.display_name.clone()),
+            Self::Aircraft => non_empty(&flight.aircraft_name).or_else(|| non_empty(&flight.drone_model)),
+            Self::AircraftSerial => non_empty(&flight.drone_serial),
+            Self::BatterySerial => non_empty(&flight.battery_serial),
+            Self::Pilot => non_empty(&flight.pilot),
+            Self::Location => non_empty(&flight.location_name),
+            Self::Photos => Some(flight.photo_count.unwrap_or(0).to_string()),
+            Self::Videos => Some(flight.video_count.unwrap_or(0).to_string()),
+            Self::Tags => Some(flight.tags.iter().map(|t| t.tag.as_str()).collect::<Vec<_>>().join(", "))
+                .filter(|t| !t.is_empty()),
+            Self::Notes => flight
+                .notes
+                .as_deref()
+                .map(|n| n.split_whitespace().collect::<Vec<_>>().join(" "))
+                .filter(|n| !n.is_empty()),
+            Self::Duration | Self::Distance | Self::MaxAltitude | Self::MaxSpeed => None,
+        }
+    }
+}
+
+/// The flights with the given ids, in that order (all flights, newest
+/// first, when `flight_ids` is empty)
+pub fn load_flights(db: &Database, flight_ids: &[i64]) -> Result<Vec<Flight>, DatabaseError> {
+    let flights = db.get_all_flights()?;
+    if flight_ids.is_empty() {
+        return Ok(flights);
+    }
+    let mut by_id: std::collections::HashMap<i64, Flight> = flights.into_iter().map(|f| (f.id, f)).collect();
+    Ok(flight_ids.iter().filter_map(|id| by_id.remove(id)).collect())
+}
+
+fn columns_or_default(columns: &[FlightColumn]) -> &[FlightColumn] {
+    if columns.is_empty() {
+        DEFAULT_COLUMNS
+    } else {
+        columns
+    }
+}
+
+/// Header and one row per flight
+pub fn list_csv(flights: &[Flight], columns: &[FlightColumn], units: &UnitSettings, rounding: DurationRounding) -> String {
+    let columns = columns_or_default(columns);
+    let mut out = columns.iter().map(|c| c.csv_header(units)).collect::<Vec<_>>().join(",");
+    out.push('\n');
+    for flight in flights {
+        let row: Vec<String> = columns.iter().map(|c| c.csv_value(flight, units, rounding)).collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// x offset and max characters of each column, fitted to the page width
+fn layout(columns: &[FlightColumn]) -> Vec<(f64, usize)> {
+    let available = PDF_PAGE_WIDTH - 2.0 * PDF_MARGIN;
+    let total: f64 = columns.iter().map(|c| c.width() + COLUMN_GAP).sum();
+    let scale = (available / total).min(1.0);
+    let mut x = 0.0;
+    columns
+        .iter()
+        .map(|c| {
+            let width = c.width() * scale;
+            let column = (x, ((width / CHAR_WIDTH) as usize).max(3));
+            x += width + COLUMN_GAP * scale;
+            column
+        })
+        .collect()
+}
+
+/// A4 PDF with a title, one table row per flight (the header repeated on
+/// each page) and a totals line
+pub fn list_pdf(
+    flights: &[Flight],
+    columns: &[FlightColumn],
+    title: Option<&str>,
+    units: &UnitSettings,
+    rounding: DurationRounding,
+) -> Vec<u8> {
+    let columns = columns_or_default(columns);
+    let layout = layout(columns);
+    let text = |content: &mut String, font: &str, size: f64, y: f64, s: &str| {
+        content.push_str(&format!("BT /{} {} Tf {:.1} {:.1} Td ({}) Tj ET\n", font, size, PDF_MARGIN, y, pdf_text(s)));
+    };
+    let row = |content: &mut String, font: &str, y: f64, cells: &[String]| {
+        for ((x, max), cell) in layout.iter().zip(cells) {
+            content.push_str(&format!(
+                "BT /{} {} Tf {:.1} {:.1} Td ({}) Tj ET\n",
+                font,
+                FONT_SIZE,
+                PDF_MARGIN + x,
+                y,
+                pdf_text(&clip(cell, *max))
+            ));
+        }
+    };
+    let header: Vec<String> = columns.iter().map(|c| c.title().to_string()).collect();
+    let top = PDF_PAGE_HEIGHT - PDF_MARGIN - 10.0;
+    let bottom = PDF_MARGIN + 24.0;
+
+    let mut pages = Vec::new();
+    let mut content = String::new();
+    let mut y = top;
+    text(&mut content, "F2", 18.0, y, title.map(str::trim).filter(|t| !t.is_empty()).unwrap_or(DEFAULT_TITLE));
+    y -= 24.0;
+    row(&mut content, "F2", y, &header);
+    y -= ROW_HEIGHT + 2.0;
+
+    for flight in flights {
+        if y < bottom {
+            pages.push(std::mem::take(&mut content));
+            y = top;
+            row(&mut content, "F2", y, &header);
+            y -= ROW_HEIGHT + 2.0;
+        }
+        let cells: Vec<String> = columns.iter().map(|c| c.display(flight, units, rounding)).collect();
+        row(&mut content, "F1", y, &cells);
+        y -= ROW_HEIGHT;
+    }
+
+    let secs = rounding.total_secs(flights.iter().filter_map(|f| f.duration_secs));
+    let meters: f64 = flights.iter().filter_map(|f| f.total_distance).sum();
+    let totals = format!(
+        "{} flights, {} h, {}",
+        flights.len(),
+        rounding.format(secs),
+        units.format_distance(meters)
+    );
+    if y - 6.0 < bottom {
+        pages.push(std::mem::take(&mut content));
+        y = top;
+    }
+    text(&mut content, "F2", 10.0, y - 6.0, &totals);
+    pages.push(content);
+
+    let generated = chrono::Utc::now().format("%Y-%m-%d %H:%M UTC");
+    let count = pages.len();
+    for (i, page) in pages.iter_mut().enumerate() {
+        let footer = format!("Generated by Open DroneLog on {} - page {} of {}", generated, i + 1, count);
+        text(page, "F1", 8.0, PDF_MARGIN, &footer);
+    }
+    build_pdf_pages(&pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_columns_and_layout() {
+        let flight: Flight = serde_json::from_value(serde_json::json!({
+            "id": 7,
+            "fileName": "f.txt",
+            "displayName": "Roof, north side",
+            "fileHash": null,
+            "droneModel": "DJI Mini 4 Pro",
+            "droneSerial": "SN1",
+            "aircraftName": null,
+            "batterySerial": null,
+            "cycleCount": null,
+            "startTime": "2026-05-01T10:00:00Z",
+            "durationSecs": 930.0,
+            "totalDistance": 2500.0,
+            "maxAltitude": 120.0,
+            "maxSpeed": 10.0,
+            "homeLat": null,
+            "homeLon": null,
+            "pointCount": 0,
+            "photoCount": 3,
+            "videoCount": 0,
+            "rcSerial": null,
+            "batteryLife": null,
+            "notes": null,
+            "pilot": null,
+            "locationName": null
+        }))
+        .unwrap();
+        let units: UnitSettings = serde_json::from_value(serde_json::json!({ "altitude": "ft" })).unwrap();
+        let columns = [FlightColumn::Date, FlightColumn::Name, FlightColumn::Distance, FlightColumn::MaxAltitude];
+        let csv = list_csv(&[flight.clone()], &columns, &units, DurationRounding::Exact);
+        assert_eq!(
+            csv,
+            "date_utc,name,distance_km,max_altitude_ft\n2026-05-01 10:00,\"Roof, north side\",2.50,393.7\n"
+        );
+        assert_eq!(FlightColumn::Aircraft.display(&flight, &units, DurationRounding::Exact), "DJI Mini 4 Pro");
+        assert_eq!(FlightColumn::Pilot.display(&flight, &units, DurationRounding::Exact), "-");
+
+        // All columns are squeezed onto the page
+        let all = [
+            FlightColumn::Date,
+            FlightColumn::Name,
+            FlightColumn::Aircraft,
+            FlightColumn::AircraftSerial,
+            FlightColumn::BatterySerial,
+            FlightColumn::Pilot,
+            FlightColumn::Location,
+            FlightColumn::Duration,
+            FlightColumn::Distance,
+            FlightColumn::Tags,
+            FlightColumn::Notes,
+        ];
+        let (x, max) = *layout(&all).last().unwrap();
+        assert!(x + max as f64 * CHAR_WIDTH <= PDF_PAGE_WIDTH - 2.0 * PDF_MARGIN + 1.0);
+    }
+}
@@ -0,0 +1,93 @@
+//! Hover / transit / ascend / descend breakdown of airtime.
+//!
+//! The telemetry is resampled to one-second steps (raw 10 Hz height deltas
+//! are too noisy for a vertical rate) and each airborne step is classified:
+//! climbing or sinking faster than `VERTICAL_RATE_MS` and faster than it
+//! moves sideways is ascend / descend, slower than `HOVER_SPEED_MS` sideways
+//! is hover, the rest is transit. Time on the ground is not counted. Computed
+//! at import and stored on the flight row, so overview stats can sum it.
+
+use crate::models::TelemetryPoint;
+
+pub use drone_logbook_types::flight_phases::PhaseTimes;
+
+const STEP_MS: i64 = 1_000;
+/// Longer gaps are not attributed to any phase
+const MAX_STEP_MS: i64 = 5_000;
+const VERTICAL_RATE_MS: f64 = 0.5;
+const HOVER_SPEED_MS: f64 = 1.0;
+/// Height above takeoff counted as airborne when the log has no airborne flag
+const AIRBORNE_HEIGHT_M: f64 = 1.0;
+
+/// Airborne flag of the log, else height above `AIRBORNE_HEIGHT_M`
+pub(crate) fn airborne(p: &TelemetryPoint) -> bool {
+    p.is_flying.unwrap_or_else(|| p.height.is_some_and(|h| h > AIRBORNE_HEIGHT_M))
+}
+
+/// Split the airborne time of a flight into phases (None without heights)
+pub fn segment(points: &[TelemetryPoint]) -> Option<PhaseTimes> {
+    let mut steps: Vec<&TelemetryPoint> = Vec::new();
+    for p in points.iter().filter(|p| p.height.is_some()) {
+        if steps.last().map_or(true, |last| p.timestamp_ms - last.timestamp_ms >= STEP_MS) {
+            steps.push(p);
+        }
+    }
+    if steps.is_empty() {
+        return None;
+    }
+
+    let mut times = PhaseTimes::default();
+    for pair in steps.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let dt_ms = b.timestamp_ms - a.timestamp_ms;
+        if dt_ms > MAX_STEP_MS || !airborne(a) || !airborne(b) {
+            continue;
+        }
+        let dt = dt_ms as f64 / 1000.0;
+        let vertical = (b.height.unwrap_or(0.0) - a.height.unwrap_or(0.0)) / dt;
+        let horizontal = b.speed.or(a.speed).unwrap_or(0.0);
+        let phase = if vertical.abs() >= VERTICAL_RATE_MS && vertical.abs() >= horizontal {
+            if vertical > 0.0 {
+                &mut times.ascend_secs
+            } else {
+                &mut times.descend_secs
+            }
+        } else if horizontal < HOVER_SPEED_MS {
+            &mut times.hover_secs
+        } else {
+            &mut times.transit_secs
+        };
+        *phase += dt;
+    }
+    Some(times)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(t_ms: i64, height: f64, speed: f64) -> TelemetryPoint {
+        TelemetryPoint {
+            timestamp_ms: t_ms,
+            height: Some(height),
+            speed: Some(speed),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_segment_phases() {
+        // Ground, climb 10 s, hover 5 s, transit 10 s, descend to the ground
+        let mut points = vec![point(0, 0.0, 0.0)];
+        points.extend((1..=10).map(|s| point(s * 1000, 3.0 * s as f64, 0.0)));
+        points.extend((11..=15).map(|s| point(s * 1000, 30.0, 0.2)));
+        points.extend((16..=25).map(|s| point(s * 1000, 30.0, 8.0)));
+        points.extend((26..=30).map(|s| point(s * 1000, 30.0 - 5.8 * (s - 25) as f64, 0.0)));
+
+        let times = segment(&points).unwrap();
+        assert_eq!(times.ascend_secs, 9.0);
+        assert_eq!(times.hover_secs, 5.0);
+        assert_eq!(times.transit_secs, 10.0);
+        assert_eq!(times.descend_secs, 4.0);
+    }
+}
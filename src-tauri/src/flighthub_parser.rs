@@ -0,0 +1,340 @@
+//! Parser module for DJI FlightHub 2 flight record exports.
+//!
+//! FlightHub 2 (and self-hosted DJI Cloud API servers) record the aircraft
+//! state as a stream of OSD messages. Exported as JSON, a record holds the
+//! flight's metadata (aircraft serial and model, project, task, pilot) at the
+//! top level and the messages in an array (`records`, `track`, `osd`,
+//! `points` or `data`), either flat or wrapped in the MQTT envelope
+//! (`{ "timestamp": ..., "data": { ...osd... } }`). Field names follow the
+//! Cloud API: `elevation` is the height above take-off, `height` the
+//! ellipsoid height, `attitude_head` the heading, `mode_code` the flight mode.
+//!
+//! Flight records downloaded from FlightHub 2 as DJI `.txt` logs go through
+//! the regular DJI parser.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::database::Database;
+use crate::formats::{has_extension, FlightLogFormat, ParseFuture};
+use crate::json_fields::{flatten_into, num, parse_time, text, Flat};
+use crate::models::{FlightMetadata, TelemetryPoint};
+use crate::parser::{LogParser, ParseInfo, ParseResult, ParserError};
+
+/// Bytes read from the start of a JSON file when sniffing for a FlightHub record
+const DETECT_BYTES: u64 = 8192;
+/// Keys that may hold the message array
+const RECORD_KEYS: [&str; 6] = ["records", "track", "osd", "points", "flight_records", "data"];
+
+/// Cloud API `domain-type-subtype` keys of common enterprise aircraft
+const MODEL_KEYS: [(&str, &str); 8] = [
+    ("0-60-0", "Matrice 300 RTK"),
+    ("0-89-0", "Matrice 350 RTK"),
+    ("0-67-0", "Matrice 30"),
+    ("0-67-1", "Matrice 30T"),
+    ("0-77-0", "Mavic 3 Enterprise"),
+    ("0-77-1", "Mavic 3 Thermal"),
+    ("0-91-0", "Matrice 3D"),
+    ("0-91-1", "Matrice 3TD"),
+];
+
+/// DJI FlightHub 2 / Cloud API JSON records
+pub struct FlightHubFormat;
+
+impl FlightLogFormat for FlightHubFormat {
+    fn name(&self) -> &'static str {
+        "DJI FlightHub 2 JSON"
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        if !has_extension(path, "json") {
+            return false;
+        }
+        let Ok(file) = std::fs::File::open(path) else {
+            return false;
+        };
+        let mut head = String::new();
+        let _ = file.take(DETECT_BYTES).read_to_string(&mut head);
+        let head = head.to_lowercase();
+        head.contains("flighthub") || (head.contains("\"attitude_head\"") && head.contains("\"mode_code\""))
+    }
+
+    fn parse<'a>(&'a self, ctx: &'a LogParser<'_>, path: &'a Path, file_hash: &'a str) -> ParseFuture<'a> {
+        Box::pin(async move { FlightHubParser::new(ctx.db()).parse(path, file_hash) })
+    }
+}
+
+/// Cloud API `mode_code` as a flight mode name, and whether the aircraft is airborne
+fn flight_mode(code: i64) -> (&'static str, bool) {
+    match code {
+        0 => ("Standby", false),
+        1 => ("Takeoff preparation", false),
+        2 => ("Takeoff ready", false),
+        3 => ("Manual", true),
+        4 => ("Auto takeoff", true),
+        5 => ("Waypoint", true),
+        6 => ("Panorama", true),
+        7 => ("Active track", true),
+        8 => ("ADS-B avoidance", true),
+        9 => ("Return to home", true),
+        10 => ("Auto landing", true),
+        11 => ("Forced landing", true),
+        12 => ("Three-propeller landing", true),
+        13 => ("Upgrading", false),
+        14 => ("Disconnected", false),
+        15 => ("APAS", true),
+        16 => ("Virtual stick", true),
+        17 => ("Live flight controls", true),
+        _ => ("Unknown", false),
+    }
+}
+
+/// A message flattened to `key → value`: the OSD payload (`data`) merged
+/// over the envelope
+fn flatten_message(message: &Value) -> Flat<'_> {
+    let mut flat = HashMap::new();
+    if let Some(data) = message.get("data").filter(|d| d.is_object()) {
+        flatten_into(&mut flat, data);
+    }
+    let mut envelope = HashMap::new();
+    flatten_into(&mut envelope, message);
+    for (k, v) in envelope {
+        flat.entry(k).or_insert(v);
+    }
+    flat
+}
+
+fn message_time(flat: &Flat<'_>) -> Option<DateTime<Utc>> {
+    ["timestamp", "time", "create_time", "ts"]
+        .iter()
+        .find_map(|k| flat.get(*k).and_then(|v| parse_time(v)))
+}
+
+/// First element of an array field (`batteries`, `payload`)
+fn first_of<'v>(flat: &Flat<'v>, key: &str) -> Flat<'v> {
+    let mut inner = HashMap::new();
+    if let Some(first) = flat.get(key).and_then(|v| v.as_array()).and_then(|a| a.first()) {
+        flatten_into(&mut inner, first);
+    }
+    inner
+}
+
+fn decode_messages(messages: &[Value]) -> (Vec<TelemetryPoint>, Option<DateTime<Utc>>) {
+    let mut points = Vec::with_capacity(messages.len());
+    let mut first: Option<DateTime<Utc>> = None;
+
+    for message in messages {
+        let flat = flatten_message(message);
+        let Some(t) = message_time(&flat) else {
+            continue;
+        };
+        let t0 = *first.get_or_insert(t);
+        // Other devices (dock, remote controller) share the stream
+        if num(&flat, &["latitude"]).is_none() && num(&flat, &["elevation", "mode_code"]).is_none() {
+            continue;
+        }
+
+        let (latitude, longitude) = match (num(&flat, &["latitude", "lat"]), num(&flat, &["longitude", "lon", "lng"])) {
+            (Some(lat), Some(lon)) if lat.abs() <= 90.0 && lon.abs() <= 180.0 && !(lat.abs() < 1e-6 && lon.abs() < 1e-6) => {
+                (Some(lat), Some(lon))
+            }
+            _ => (None, None),
+        };
+        let battery = first_of(&flat, "batteries");
+        let payload = first_of(&flat, "payload");
+        let gimbal = |key: &str| num(&payload, &[key]).or_else(|| num(&flat, &[key]));
+        let mode = num(&flat, &["mode_code"]).map(|c| flight_mode(c as i64));
+        let vertical = num(&flat, &["vertical_speed"]);
+
+        points.push(TelemetryPoint {
+            timestamp_ms: (t - t0).num_milliseconds(),
+            latitude,
+            longitude,
+            altitude: num(&flat, &["height", "altitude"]),
+            height: num(&flat, &["elevation", "relative_height"]),
+            altitude_abs: num(&flat, &["height", "altitude"]),
+            speed: num(&flat, &["horizontal_speed", "speed"]),
+            // NED: positive down
+            velocity_z: vertical.map(|v| -v),
+            roll: num(&flat, &["attitude_roll"]),
+            pitch: num(&flat, &["attitude_pitch"]),
+            yaw: num(&flat, &["attitude_head", "heading"]),
+            gimbal_pitch: gimbal("gimbal_pitch"),
+            gimbal_roll: gimbal("gimbal_roll"),
+            gimbal_yaw: gimbal("gimbal_yaw"),
+            battery_percent: num(&flat, &["capacity_percent", "battery_capacity_percent"]).map(|v| v.round() as i32),
+            // Cloud API voltages are in mV
+            battery_voltage: num(&battery, &["voltage"]).map(|v| if v > 100.0 { v / 1000.0 } else { v }),
+            battery_temp: num(&battery, &["temperature"]),
+            satellites: num(&flat, &["gps_number", "position_state_gps_number"]).map(|v| v as i32),
+            flight_mode: mode.map(|(name, _)| name.to_string()),
+            is_flying: mode.map(|(_, flying)| flying),
+            ..Default::default()
+        });
+    }
+    (points, first)
+}
+
+/// FlightHub 2 JSON parser
+pub struct FlightHubParser<'a> {
+    db: &'a Database,
+}
+
+impl<'a> FlightHubParser<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Parse a FlightHub 2 JSON flight record
+    pub fn parse(&self, file_path: &Path, file_hash: &str) -> Result<ParseResult, ParserError> {
+        let parse_start = std::time::Instant::now();
+        log::info!("Parsing FlightHub 2 JSON file: {:?}", file_path);
+
+        let content = std::fs::read_to_string(file_path)?;
+        let root: Value =
+            serde_json::from_str(&content).map_err(|e| ParserError::Parse(format!("Invalid JSON: {}", e)))?;
+
+        let messages = match &root {
+            Value::Array(a) => a.as_slice(),
+            _ => RECORD_KEYS
+                .iter()
+                .find_map(|k| root.get(*k).and_then(|v| v.as_array()))
+                .map(|a| a.as_slice())
+                .ok_or_else(|| ParserError::Parse("No OSD records found in FlightHub 2 export".to_string()))?,
+        };
+        let (points, first_message_time) = decode_messages(messages);
+        if !points.iter().any(|p| p.latitude.is_some()) {
+            log::warn!("No positioned records found in FlightHub 2 export");
+            return Err(ParserError::NoTelemetryData);
+        }
+
+        // Metadata may sit at the top level or in a `flight` / `device` group
+        let meta = flatten_message(&root);
+        let meta_group = |group: &str| root.get(group).map(flatten_message).unwrap_or_default();
+        let (flight_meta, device_meta) = (meta_group("flight"), meta_group("device"));
+        let lookup = |aliases: &[&str]| {
+            text(&meta, aliases)
+                .or_else(|| text(&flight_meta, aliases))
+                .or_else(|| text(&device_meta, aliases))
+        };
+
+        let start_time = ["start_time", "takeoff_time", "begin_time", "flight_start"]
+            .iter()
+            .find_map(|k| {
+                root.get(*k)
+                    .or_else(|| root.get("flight").and_then(|f| f.get(*k)))
+                    .and_then(parse_time)
+            })
+            .or(first_message_time);
+
+        let parser = LogParser::new(self.db);
+        let stats = parser.calculate_stats(&points);
+
+        let file_name = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let task = lookup(&["task_name", "flight_task_name", "wayline_name", "flight_name"]);
+        let display_name = task.clone().unwrap_or_else(|| {
+            file_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .filter(|s| !s.trim().is_empty())
+                .unwrap_or(&file_name)
+                .to_string()
+        });
+        let end_time = start_time.map(|t| t + chrono::Duration::milliseconds((stats.duration_secs * 1000.0) as i64));
+        let model = lookup(&["device_model", "aircraft_model", "model_name", "model"]).or_else(|| {
+            let key = lookup(&["device_model_key", "model_key", "device_type"])?;
+            MODEL_KEYS.iter().find(|(k, _)| *k == key).map(|(_, name)| name.to_string())
+        });
+
+        let metadata = FlightMetadata {
+            id: self.db.generate_flight_id(),
+            file_name,
+            display_name,
+            file_hash: Some(file_hash.to_string()),
+            drone_model: Some(model.unwrap_or_else(|| "DJI Enterprise".to_string())),
+            drone_serial: lookup(&["aircraft_sn", "device_sn", "sn", "serial_number"]),
+            aircraft_name: lookup(&["device_name", "nickname", "callsign"]),
+            battery_serial: None,
+            cycle_count: None,
+            start_time,
+            end_time,
+            duration_secs: Some(stats.duration_secs),
+            total_distance: Some(stats.total_distance_m),
+            max_altitude: Some(stats.max_altitude_m),
+            max_speed: Some(stats.max_speed_ms),
+            home_lat: stats.home_location.map(|h| h[1]),
+            home_lon: stats.home_location.map(|h| h[0]),
+            point_count: points.len() as i32,
+            photo_count: 0,
+            video_count: 0,
+            rc_serial: lookup(&["gateway_sn", "dock_sn", "rc_sn"]),
+            battery_life: None,
+        };
+
+        // Fleet context has no column of its own; keep it in the notes
+        let context: Vec<String> = [
+            ("Project", lookup(&["project_name", "workspace_name", "project"])),
+            ("Task", task),
+            ("Pilot", lookup(&["pilot_name", "pilot", "operator", "creator_name"])),
+        ]
+        .into_iter()
+        .filter_map(|(label, value)| value.map(|v| format!("{}: {}", label, v)))
+        .collect();
+        let notes = (!context.is_empty()).then(|| format!("FlightHub 2 — {}", context.join(", ")));
+
+        log::info!(
+            "FlightHub 2 parse complete in {:.1}s: duration={:.1}s, distance={:.0}m, max_alt={:.1}m, points={}",
+            parse_start.elapsed().as_secs_f64(),
+            stats.duration_secs,
+            stats.total_distance_m,
+            stats.max_altitude_m,
+            points.len()
+        );
+
+        let mut tags = LogParser::generate_smart_tags(&metadata, &stats);
+        tags.insert(0, "FlightHub 2".to_string());
+
+        Ok(ParseResult { metadata, points, tags, manual_tags: Vec::new(), notes, color: None, messages: Vec::new(), info: ParseInfo::new("DJI FlightHub 2 JSON") })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_messages() {
+        let root: Value = serde_json::json!({
+            "device_sn": "1581F5BKD223500A",
+            "records": [
+                { "timestamp": 1717236000000_i64, "data": { "latitude": 47.1, "longitude": 8.5, "elevation": 0.0, "height": 520.3, "mode_code": 0,
+                    "battery": { "capacity_percent": 98, "batteries": [{ "voltage": 52400, "temperature": 28.5 }] } } },
+                { "timestamp": 1717236000500_i64, "data": { "latitude": 47.1001, "longitude": 8.5, "elevation": 12.5, "mode_code": 5,
+                    "horizontal_speed": 6.0, "vertical_speed": 1.5, "attitude_head": -90.0, "position_state": { "gps_number": 21 },
+                    "payload": [{ "gimbal_pitch": -45.0 }] } },
+                { "timestamp": 1717236000600_i64, "data": { "mode_code": 2, "drone_in_dock": 0 } }
+            ]
+        });
+        let (points, first) = decode_messages(root["records"].as_array().unwrap());
+        assert_eq!(first.unwrap().timestamp_millis(), 1717236000000);
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[0].battery_percent, Some(98));
+        assert!((points[0].battery_voltage.unwrap() - 52.4).abs() < 1e-9);
+        assert_eq!(points[0].is_flying, Some(false));
+        assert_eq!(points[1].timestamp_ms, 500);
+        assert_eq!(points[1].height, Some(12.5));
+        assert_eq!(points[1].velocity_z, Some(-1.5));
+        assert_eq!(points[1].satellites, Some(21));
+        assert_eq!(points[1].gimbal_pitch, Some(-45.0));
+        assert_eq!(points[1].flight_mode.as_deref(), Some("Waypoint"));
+        assert_eq!(points[1].is_flying, Some(true));
+    }
+}
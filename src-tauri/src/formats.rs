@@ -0,0 +1,103 @@
+//! Flight log format registry.
+//!
+//! Every supported log format implements [`FlightLogFormat`]: a cheap `detect`
+//! check (extension, header line, magic bytes) and a `parse` that turns the file
+//! into a [`ParseResult`]. `LogParser::parse_log` walks the registered formats in
+//! order and hands the file to the first one that recognises it, so adding a new
+//! format (Autel, ArduPilot, SRT, ...) only needs a module implementing the trait
+//! and an entry in [`builtin_formats`] — the import pipeline stays untouched.
+
+use std::any::Any;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::airdata_parser::AirdataFormat;
+use crate::assistant_parser::DjiAssistantFormat;
+use crate::blackbox_parser::BlackboxFormat;
+use crate::dat_parser::DatFormat;
+use crate::dronelogbook_parser::DroneLogbookFormat;
+use crate::flighthub_parser::FlightHubFormat;
+use crate::litchi_parser::LitchiFormat;
+use crate::logbook_export_parser::{LogbookGpxFormat, LogbookJsonFormat};
+use crate::parrot_parser::ParrotFormat;
+use crate::parser::{DjiTxtFormat, LogParser, ParseResult, ParserError};
+use crate::skydio_parser::SkydioFormat;
+
+/// Boxed future returned by [`FlightLogFormat::parse`] (async fn in traits
+/// is not available on the minimum supported Rust version)
+pub type ParseFuture<'a> = Pin<Box<dyn Future<Output = Result<ParseResult, ParserError>> + Send + 'a>>;
+
+/// A flight log format the importer can recognise and parse
+pub trait FlightLogFormat: Send + Sync {
+    /// Human-readable format name, used in logs and import reports
+    fn name(&self) -> &'static str;
+
+    /// Return true if this format should handle the file. Must be cheap —
+    /// it is called for every registered format until one matches.
+    fn detect(&self, path: &Path) -> bool;
+
+    /// Parse the file. `ctx` gives access to the database and the shared
+    /// stats/tagging helpers on `LogParser`.
+    fn parse<'a>(&'a self, ctx: &'a LogParser<'_>, path: &'a Path, file_hash: &'a str) -> ParseFuture<'a>;
+}
+
+/// Built-in formats in detection order. Content-sniffed CSV formats come first
+/// so that a CSV is never claimed by a format that only checks the extension;
+/// the app's own JSON export precedes FlightHub 2 and Skydio JSON, which it
+/// may mention.
+pub fn builtin_formats() -> Vec<Box<dyn FlightLogFormat>> {
+    vec![
+        Box::new(DroneLogbookFormat),
+        Box::new(LogbookJsonFormat),
+        Box::new(LogbookGpxFormat),
+        Box::new(AirdataFormat),
+        Box::new(LitchiFormat),
+        Box::new(DjiAssistantFormat),
+        Box::new(FlightHubFormat),
+        Box::new(SkydioFormat),
+        Box::new(BlackboxFormat),
+        Box::new(DjiTxtFormat),
+        Box::new(DatFormat),
+        Box::new(ParrotFormat),
+    ]
+}
+
+/// Message of a caught panic payload
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<String>()
+        .cloned()
+        .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Parse future that turns a panic in the parser into `ParserError::Panic`
+struct CatchPanics<'a>(ParseFuture<'a>);
+
+impl Future for CatchPanics<'_> {
+    type Output = Result<ParseResult, ParserError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = &mut self.0;
+        match panic::catch_unwind(AssertUnwindSafe(|| inner.as_mut().poll(cx))) {
+            Ok(poll) => poll,
+            Err(payload) => Poll::Ready(Err(ParserError::Panic(panic_message(&*payload)))),
+        }
+    }
+}
+
+/// Guard a format's parse so that no input can take the process down with it
+/// (the DJI TXT decoder has its own guard with a timeout, see `parse_dji_txt`)
+pub fn catch_panics(parse: ParseFuture<'_>) -> ParseFuture<'_> {
+    Box::pin(CatchPanics(parse))
+}
+
+/// True if the file has the given extension (case-insensitive, without the dot)
+pub fn has_extension(path: &Path, ext: &str) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case(ext))
+}
@@ -0,0 +1,145 @@
+//! Recording gaps in telemetry.
+//!
+//! Logs stop when the remote loses the aircraft or the app is backgrounded,
+//! and pick up again later. Left alone, the charts draw a straight line across
+//! such a gap and downsampling averages over it, which passes for real data.
+//! A gap is an interval between two raw samples longer than `MIN_GAP_MS` and
+//! `INTERVAL_FACTOR` times the median sample interval of the log (logs are
+//! recorded at anything from 1 to 30 Hz, so a fixed threshold alone doesn't
+//! fit all of them).
+//!
+//! Gaps are detected on the raw samples, whatever resolution the series is
+//! returned at. Each one is marked in the series with a break record (a
+//! timestamp with every value null), so charts stop the line there, and
+//! listed with statistics in the flight data response.
+
+use crate::database::Database;
+use crate::models::TelemetryRecord;
+use crate::parser::haversine_distance;
+
+pub use drone_logbook_types::gaps::{GapReport, TelemetryGap};
+
+/// Shortest interval counted as a gap
+pub const MIN_GAP_MS: i64 = 2_000;
+/// Intervals this many times the median interval (and at least `MIN_GAP_MS`) are gaps
+pub const INTERVAL_FACTOR: i64 = 10;
+
+/// Median interval between consecutive samples with distinct timestamps
+fn median_interval(samples: &[(i64, Option<f64>, Option<f64>)]) -> Option<i64> {
+    let mut intervals: Vec<i64> = samples
+        .windows(2)
+        .map(|w| w[1].0 - w[0].0)
+        .filter(|dt| *dt > 0)
+        .collect();
+    if intervals.is_empty() {
+        return None;
+    }
+    let mid = intervals.len() / 2;
+    Some(*intervals.select_nth_unstable(mid).1)
+}
+
+/// Find the gaps in `(timestamp_ms, latitude, longitude)` samples sorted by time
+pub fn detect(samples: &[(i64, Option<f64>, Option<f64>)]) -> GapReport {
+    let threshold_ms = median_interval(samples).map_or(MIN_GAP_MS, |m| (m * INTERVAL_FACTOR).max(MIN_GAP_MS));
+
+    let gaps: Vec<TelemetryGap> = samples
+        .windows(2)
+        .filter(|w| w[1].0 - w[0].0 > threshold_ms)
+        .map(|w| {
+            let (start, end) = (&w[0], &w[1]);
+            let distance_m = match (start.1, start.2, end.1, end.2) {
+                (Some(lat1), Some(lon1), Some(lat2), Some(lon2)) => Some(haversine_distance(lat1, lon1, lat2, lon2)),
+                _ => None,
+            };
+            TelemetryGap { start_ms: start.0, end_ms: end.0, duration_ms: end.0 - start.0, distance_m }
+        })
+        .collect();
+
+    let total_gap_ms: i64 = gaps.iter().map(|g| g.duration_ms).sum();
+    let span_ms = match (samples.first(), samples.last()) {
+        (Some(first), Some(last)) => last.0 - first.0,
+        _ => 0,
+    };
+    GapReport {
+        longest_gap_ms: gaps.iter().map(|g| g.duration_ms).max().unwrap_or(0),
+        coverage_pct: if span_ms > 0 { 100.0 * (span_ms - total_gap_ms) as f64 / span_ms as f64 } else { 100.0 },
+        total_gap_ms,
+        threshold_ms,
+        gaps,
+    }
+}
+
+/// Gaps of a stored flight (none, with a warning, when the samples can't be read)
+pub fn for_flight(db: &Database, flight_id: i64) -> GapReport {
+    match db.get_telemetry_sample_times(flight_id) {
+        Ok(samples) => detect(&samples),
+        Err(e) => {
+            log::warn!("Failed to detect recording gaps for flight {}: {}", flight_id, e);
+            GapReport::default()
+        }
+    }
+}
+
+/// Insert a break record in the middle of each gap that falls between two
+/// records, so the series read null there instead of being drawn across it.
+/// Works on raw and downsampled records alike.
+pub fn mark_in_records(records: Vec<TelemetryRecord>, gaps: &[TelemetryGap]) -> Vec<TelemetryRecord> {
+    if gaps.is_empty() {
+        return records;
+    }
+    let mut marked = Vec::with_capacity(records.len() + gaps.len());
+    let mut midpoints = gaps.iter().map(|g| (g.start_ms + g.end_ms) / 2).peekable();
+    for record in records {
+        let mut needs_break = false;
+        while let Some(&mid) = midpoints.peek() {
+            if mid >= record.timestamp_ms {
+                break;
+            }
+            needs_break = !marked.is_empty();
+            midpoints.next();
+        }
+        if needs_break {
+            let previous_ms = marked.last().map(|r: &TelemetryRecord| r.timestamp_ms).unwrap_or_default();
+            marked.push(TelemetryRecord {
+                timestamp_ms: (previous_ms + record.timestamp_ms) / 2,
+                ..Default::default()
+            });
+        }
+        marked.push(record);
+    }
+    marked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_and_mark_gaps() {
+        // 1 Hz with a 30 s dropout after 10 s, and a 1.5 s hiccup that isn't a gap
+        let mut samples: Vec<(i64, Option<f64>, Option<f64>)> =
+            (0..=10).map(|i| (i * 1000, Some(46.0), Some(7.0))).collect();
+        samples.push((11_500, Some(46.0), Some(7.0)));
+        samples.extend((0..10).map(|i| (40_000 + i * 1000, Some(46.001), Some(7.0))));
+
+        let report = detect(&samples);
+        assert_eq!(report.threshold_ms, 10_000);
+        assert_eq!(report.gaps.len(), 1);
+        let gap = &report.gaps[0];
+        assert_eq!((gap.start_ms, gap.end_ms, gap.duration_ms), (11_500, 40_000, 28_500));
+        assert!((gap.distance_m.unwrap() - 111.2).abs() < 1.0);
+        assert_eq!(report.longest_gap_ms, 28_500);
+        assert!((report.coverage_pct - 100.0 * 20_500.0 / 49_000.0).abs() < 1e-9);
+
+        // Downsampled to 5 s buckets: one break between the buckets either side
+        let records: Vec<TelemetryRecord> = [0, 5_000, 10_000, 40_000, 45_000]
+            .iter()
+            .map(|&t| TelemetryRecord { timestamp_ms: t, height: Some(20.0), ..Default::default() })
+            .collect();
+        let marked = mark_in_records(records, &report.gaps);
+        assert_eq!(marked.len(), 6);
+        assert_eq!(marked[3].timestamp_ms, 25_000);
+        assert!(marked[3].height.is_none());
+        assert!(marked[4].height.is_some());
+    }
+}
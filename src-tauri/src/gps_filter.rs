@@ -0,0 +1,144 @@
+//! GPS glitch filter, run on every import before distances are computed.
+//!
+//! Two kinds of bad fixes are removed (latitude and longitude set to None,
+//! the rest of the sample is kept):
+//!
+//! - invalid coordinates — 0,0 ("null island", logged before the first
+//!   fix), non-finite or out-of-range values
+//! - teleports — fixes the aircraft couldn't have reached from the last good
+//!   one (implied speed over `MAX_IMPLIED_SPEED` and further than
+//!   `MIN_JUMP_M`), as long as the track comes back within `MAX_GLITCH_FIXES`
+//!   fixes. A jump the track doesn't come back from is kept: that is the
+//!   receiver regaining a lock, not a glitch.
+//!
+//! A single bad fix otherwise adds kilometres to the total distance and
+//! stretches the map bounds across the globe. The number removed is stored
+//! in the import report (`gpsOutliers`).
+
+use crate::models::TelemetryPoint;
+use crate::parser::haversine_distance;
+
+/// Faster than any multirotor or fixed-wing this app imports logs for (m/s)
+const MAX_IMPLIED_SPEED: f64 = 100.0;
+/// Jumps up to this far are never outliers (GPS noise at high sample rates)
+const MIN_JUMP_M: f64 = 25.0;
+/// Longest run of consecutive bad fixes that is removed
+const MAX_GLITCH_FIXES: usize = 5;
+
+/// Fixes removed by `filter`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GpsFilterReport {
+    pub invalid: usize,
+    pub teleports: usize,
+}
+
+impl GpsFilterReport {
+    pub fn total(&self) -> usize {
+        self.invalid + self.teleports
+    }
+}
+
+fn is_valid(lat: f64, lon: f64) -> bool {
+    lat.is_finite()
+        && lon.is_finite()
+        && lat.abs() <= 90.0
+        && lon.abs() <= 180.0
+        && !(lat.abs() < 1e-6 && lon.abs() < 1e-6)
+}
+
+fn plausible(from: &TelemetryPoint, to: &TelemetryPoint) -> bool {
+    let (Some(lat1), Some(lon1), Some(lat2), Some(lon2)) = (from.latitude, from.longitude, to.latitude, to.longitude) else {
+        return true;
+    };
+    let distance = haversine_distance(lat1, lon1, lat2, lon2);
+    if distance <= MIN_JUMP_M {
+        return true;
+    }
+    let dt = (to.timestamp_ms - from.timestamp_ms).max(1) as f64 / 1000.0;
+    distance / dt <= MAX_IMPLIED_SPEED
+}
+
+fn clear_position(point: &mut TelemetryPoint) {
+    point.latitude = None;
+    point.longitude = None;
+}
+
+/// Remove invalid and teleporting fixes from points sorted by time
+pub fn filter(points: &mut [TelemetryPoint]) -> GpsFilterReport {
+    let mut report = GpsFilterReport::default();
+
+    let mut fixes = Vec::new();
+    for (i, point) in points.iter_mut().enumerate() {
+        match (point.latitude, point.longitude) {
+            (Some(lat), Some(lon)) if is_valid(lat, lon) => fixes.push(i),
+            (None, None) => {}
+            _ => {
+                clear_position(point);
+                report.invalid += 1;
+            }
+        }
+    }
+
+    let Some(&first) = fixes.first() else {
+        return report;
+    };
+    let mut anchor = first;
+    let mut k = 1;
+    while k < fixes.len() {
+        if plausible(&points[anchor], &points[fixes[k]]) {
+            anchor = fixes[k];
+            k += 1;
+            continue;
+        }
+        // A glitch if the track comes back near the last good fix soon
+        let resumes = (k + 1..fixes.len().min(k + 1 + MAX_GLITCH_FIXES)).find(|&j| plausible(&points[anchor], &points[fixes[j]]));
+        match resumes {
+            Some(j) => {
+                for &i in &fixes[k..j] {
+                    clear_position(&mut points[i]);
+                }
+                report.teleports += j - k;
+                anchor = fixes[j];
+                k = j + 1;
+            }
+            None => {
+                anchor = fixes[k];
+                k += 1;
+            }
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(t: i64, lat: f64, lon: f64) -> TelemetryPoint {
+        TelemetryPoint { timestamp_ms: t, latitude: Some(lat), longitude: Some(lon), ..Default::default() }
+    }
+
+    #[test]
+    fn test_glitches_removed_and_relocks_kept() {
+        // 10 Hz, moving north at ~11 m/s
+        let mut points: Vec<TelemetryPoint> = (0..40).map(|i| point(i * 100, 46.0 + i as f64 * 1e-5, 7.0)).collect();
+        points[0] = point(0, 0.0, 0.0);
+        points[10].latitude = Some(46.5); // single teleport
+        points[20] = point(2000, 46.0002, 17.0); // two in a row
+        points[21] = point(2100, 46.0002, 17.0);
+        points[30].longitude = Some(f64::NAN);
+
+        let report = filter(&mut points);
+        assert_eq!(report, GpsFilterReport { invalid: 2, teleports: 3 });
+        assert!(points[0].latitude.is_none() && points[30].latitude.is_none());
+        assert!(points[10].latitude.is_none() && points[20].latitude.is_none() && points[21].latitude.is_none());
+        assert_eq!(points[11].latitude, Some(46.0 + 11.0 * 1e-5));
+
+        // A jump the track stays at (lock regained) is kept
+        let mut relock: Vec<TelemetryPoint> = (0..20)
+            .map(|i| point(i * 100, if i < 5 { 45.0 } else { 46.0 + i as f64 * 1e-5 }, 7.0))
+            .collect();
+        assert_eq!(filter(&mut relock).total(), 0);
+        assert!(relock.iter().all(|p| p.latitude.is_some()));
+    }
+}
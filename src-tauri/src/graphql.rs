@@ -0,0 +1,327 @@
+//! GraphQL API for the web server.
+//!
+//! Exposes flights, telemetry, statistics, batteries and tags from the same
+//! database layer as the REST routes at `POST /api/graphql`, so dashboards can
+//! fetch exactly the fields they need in one request. Requests go through the
+//! usual profile/session resolution; the profile's [`Database`] is attached to
+//! each request as context data. The API is read-only.
+
+use std::sync::{Arc, OnceLock};
+
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Result, Schema, SimpleObject};
+
+use crate::database::Database;
+use crate::models::{BatteryUsage, DroneUsage, Flight, FlightMessage, FlightTag, TelemetryRecord};
+
+pub type LogbookSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Maximum query nesting depth
+const MAX_DEPTH: usize = 8;
+/// Default telemetry samples per flight (same as the flight view)
+const DEFAULT_TELEMETRY_POINTS: i32 = 5000;
+/// Default page size for `flights`
+const DEFAULT_FLIGHT_LIMIT: i32 = 100;
+
+/// Shared schema instance (it holds no per-profile state)
+pub fn schema() -> &'static LogbookSchema {
+    static SCHEMA: OnceLock<LogbookSchema> = OnceLock::new();
+    SCHEMA.get_or_init(|| {
+        Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+            .limit_depth(MAX_DEPTH)
+            .finish()
+    })
+}
+
+fn db<'a>(ctx: &Context<'a>) -> Result<&'a Arc<Database>> {
+    ctx.data::<Arc<Database>>()
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Flights, newest first, optionally filtered by drone serial or tag
+    async fn flights(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<i32>,
+        offset: Option<i32>,
+        drone_serial: Option<String>,
+        tag: Option<String>,
+    ) -> Result<Vec<GqlFlight>> {
+        let flights = db(ctx)?.get_all_flights()?;
+        let limit = limit.unwrap_or(DEFAULT_FLIGHT_LIMIT).max(0) as usize;
+        let offset = offset.unwrap_or(0).max(0) as usize;
+        Ok(flights
+            .into_iter()
+            .filter(|f| drone_serial.as_ref().map_or(true, |s| f.drone_serial.as_ref() == Some(s)))
+            .filter(|f| tag.as_ref().map_or(true, |t| f.tags.iter().any(|ft| &ft.tag == t)))
+            .skip(offset)
+            .take(limit)
+            .map(GqlFlight)
+            .collect())
+    }
+
+    /// A single flight by ID
+    async fn flight(&self, ctx: &Context<'_>, id: i64) -> Result<Option<GqlFlight>> {
+        Ok(db(ctx)?.get_flight_by_id(id).ok().map(GqlFlight))
+    }
+
+    /// Logbook-wide totals
+    async fn overview(&self, ctx: &Context<'_>) -> Result<Overview> {
+        let s = db(ctx)?.get_overview_stats()?;
+        Ok(Overview {
+            total_flights: s.total_flights,
+            total_distance_m: s.total_distance_m,
+            total_duration_secs: s.total_duration_secs,
+            total_points: s.total_points,
+            total_photos: s.total_photos,
+            total_videos: s.total_videos,
+            max_altitude_m: s.max_altitude_m,
+            max_distance_from_home_m: s.max_distance_from_home_m,
+        })
+    }
+
+    /// Per-battery usage
+    async fn batteries(&self, ctx: &Context<'_>) -> Result<Vec<Battery>> {
+        let s = db(ctx)?.get_overview_stats()?;
+        Ok(s.batteries_used.into_iter().map(Battery::from).collect())
+    }
+
+    /// Per-aircraft usage
+    async fn drones(&self, ctx: &Context<'_>) -> Result<Vec<Drone>> {
+        let s = db(ctx)?.get_overview_stats()?;
+        Ok(s.drones_used.into_iter().map(Drone::from).collect())
+    }
+
+    /// Every distinct tag in the logbook
+    async fn tags(&self, ctx: &Context<'_>) -> Result<Vec<String>> {
+        Ok(db(ctx)?.get_all_unique_tags()?)
+    }
+}
+
+pub struct GqlFlight(Flight);
+
+#[Object(name = "Flight")]
+impl GqlFlight {
+    async fn id(&self) -> i64 {
+        self.0.id
+    }
+    async fn file_name(&self) -> &str {
+        &self.0.file_name
+    }
+    async fn display_name(&self) -> &str {
+        &self.0.display_name
+    }
+    async fn drone_model(&self) -> Option<&str> {
+        self.0.drone_model.as_deref()
+    }
+    async fn drone_serial(&self) -> Option<&str> {
+        self.0.drone_serial.as_deref()
+    }
+    async fn aircraft_name(&self) -> Option<&str> {
+        self.0.aircraft_name.as_deref()
+    }
+    async fn battery_serial(&self) -> Option<&str> {
+        self.0.battery_serial.as_deref()
+    }
+    async fn cycle_count(&self) -> Option<i32> {
+        self.0.cycle_count
+    }
+    async fn start_time(&self) -> Option<&str> {
+        self.0.start_time.as_deref()
+    }
+    async fn duration_secs(&self) -> Option<f64> {
+        self.0.duration_secs
+    }
+    async fn total_distance(&self) -> Option<f64> {
+        self.0.total_distance
+    }
+    async fn max_altitude(&self) -> Option<f64> {
+        self.0.max_altitude
+    }
+    async fn max_speed(&self) -> Option<f64> {
+        self.0.max_speed
+    }
+    async fn home_lat(&self) -> Option<f64> {
+        self.0.home_lat
+    }
+    async fn home_lon(&self) -> Option<f64> {
+        self.0.home_lon
+    }
+    async fn point_count(&self) -> Option<i32> {
+        self.0.point_count
+    }
+    async fn photo_count(&self) -> Option<i32> {
+        self.0.photo_count
+    }
+    async fn video_count(&self) -> Option<i32> {
+        self.0.video_count
+    }
+    async fn notes(&self) -> Option<&str> {
+        self.0.notes.as_deref()
+    }
+    async fn color(&self) -> Option<&str> {
+        self.0.color.as_deref()
+    }
+
+    async fn tags(&self) -> Vec<Tag> {
+        self.0.tags.iter().map(Tag::from).collect()
+    }
+
+    /// Tips, warnings and cautions recorded during the flight
+    async fn messages(&self, ctx: &Context<'_>) -> Result<Vec<Message>> {
+        Ok(db(ctx)?
+            .get_flight_messages(self.0.id)?
+            .into_iter()
+            .map(Message::from)
+            .collect())
+    }
+
+    /// Telemetry samples, downsampled to at most `maxPoints`
+    async fn telemetry(&self, ctx: &Context<'_>, max_points: Option<i32>) -> Result<Vec<TelemetrySample>> {
+        let max_points = max_points.unwrap_or(DEFAULT_TELEMETRY_POINTS).max(1) as usize;
+        let max_points = crate::server::resolve_max_points(Some(max_points))?;
+        let records = db(ctx)?.get_flight_telemetry(
+            self.0.id,
+            max_points,
+            self.0.point_count.map(|c| c as i64),
+        )?;
+        Ok(records.iter().map(TelemetrySample::from).collect())
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Tag {
+    pub tag: String,
+    /// "auto" or "manual"
+    pub tag_type: String,
+}
+
+impl From<&FlightTag> for Tag {
+    fn from(t: &FlightTag) -> Self {
+        Self { tag: t.tag.clone(), tag_type: t.tag_type.clone() }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Message {
+    pub timestamp_ms: i64,
+    pub message_type: String,
+    pub message: String,
+}
+
+impl From<FlightMessage> for Message {
+    fn from(m: FlightMessage) -> Self {
+        Self { timestamp_ms: m.timestamp_ms, message_type: m.message_type, message: m.message }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct TelemetrySample {
+    pub timestamp_ms: i64,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub altitude: Option<f64>,
+    pub height: Option<f64>,
+    pub vps_height: Option<f64>,
+    pub speed: Option<f64>,
+    pub velocity_x: Option<f64>,
+    pub velocity_y: Option<f64>,
+    pub velocity_z: Option<f64>,
+    pub battery_percent: Option<i32>,
+    pub battery_voltage: Option<f64>,
+    pub battery_temp: Option<f64>,
+    pub battery_current: Option<f64>,
+    pub cell_voltages: Option<Vec<f64>>,
+    pub pitch: Option<f64>,
+    pub roll: Option<f64>,
+    pub yaw: Option<f64>,
+    pub gimbal_pitch: Option<f64>,
+    pub satellites: Option<i32>,
+    pub flight_mode: Option<String>,
+    pub rc_signal: Option<i32>,
+    pub is_photo: Option<bool>,
+    pub is_video: Option<bool>,
+}
+
+impl From<&TelemetryRecord> for TelemetrySample {
+    fn from(r: &TelemetryRecord) -> Self {
+        Self {
+            timestamp_ms: r.timestamp_ms,
+            latitude: r.latitude,
+            longitude: r.longitude,
+            altitude: r.altitude,
+            height: r.height,
+            vps_height: r.vps_height,
+            speed: r.speed,
+            velocity_x: r.velocity_x,
+            velocity_y: r.velocity_y,
+            velocity_z: r.velocity_z,
+            battery_percent: r.battery_percent,
+            battery_voltage: r.battery_voltage,
+            battery_temp: r.battery_temp,
+            battery_current: r.battery_current,
+            cell_voltages: r.cell_voltages.clone(),
+            pitch: r.pitch,
+            roll: r.roll,
+            yaw: r.yaw,
+            gimbal_pitch: r.gimbal_pitch,
+            satellites: r.satellites,
+            flight_mode: r.flight_mode.clone(),
+            rc_signal: r.rc_signal,
+            is_photo: r.is_photo,
+            is_video: r.is_video,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Overview {
+    pub total_flights: i64,
+    pub total_distance_m: f64,
+    pub total_duration_secs: f64,
+    pub total_points: i64,
+    pub total_photos: i64,
+    pub total_videos: i64,
+    pub max_altitude_m: f64,
+    pub max_distance_from_home_m: f64,
+}
+
+#[derive(SimpleObject)]
+pub struct Battery {
+    pub battery_serial: String,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+    pub max_cycle_count: Option<i32>,
+}
+
+impl From<BatteryUsage> for Battery {
+    fn from(b: BatteryUsage) -> Self {
+        Self {
+            battery_serial: b.battery_serial,
+            flight_count: b.flight_count,
+            total_duration_secs: b.total_duration_secs,
+            max_cycle_count: b.max_cycle_count,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Drone {
+    pub drone_model: String,
+    pub drone_serial: Option<String>,
+    pub aircraft_name: Option<String>,
+    pub flight_count: i64,
+}
+
+impl From<DroneUsage> for Drone {
+    fn from(d: DroneUsage) -> Self {
+        Self {
+            drone_model: d.drone_model,
+            drone_serial: d.drone_serial,
+            aircraft_name: d.aircraft_name,
+            flight_count: d.flight_count,
+        }
+    }
+}
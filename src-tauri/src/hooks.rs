@@ -0,0 +1,556 @@
+//! Post-import processing pipeline.
+//!
+//! After a flight is stored, a list of registered hooks runs over it in order
+//! (stats derivation, smart tags / reverse geocoding, location overrides,
+//! filename rules, anomaly detection, RTH analysis, limit exceedances, list preview cache,
+//! battery retirement webhook). Each hook can be switched off through the
+//! `post_import_hook.<id>` setting, and the whole pipeline can be rerun on
+//! existing flights with `reprocess_flight` — e.g. after an update improves a
+//! processor or a hook is enabled.
+//!
+//! Stats and smart tags are already derived by the parsers during import, so
+//! those hooks only run when reprocessing; they recompute from stored telemetry.
+
+use serde::Serialize;
+
+use crate::battery_retirement;
+use crate::database::Database;
+use crate::filename_rules;
+use crate::locations;
+use crate::models::{Flight, FlightMessage, FlightMetadata, FlightStats, TelemetryRecord};
+use crate::notifications::{self, NotificationEvent, NotificationSettings};
+use crate::parser::{calculate_stats_from_records, LogParser};
+use crate::preview;
+use crate::rth;
+
+/// Settings key prefix for per-hook enable flags
+const SETTING_PREFIX: &str = "post_import_hook.";
+
+/// When the pipeline is being run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookTrigger {
+    /// Right after a log was imported
+    Import,
+    /// Explicit rerun on an existing flight
+    Reprocess,
+}
+
+/// Everything a hook can look at or modify for one flight
+pub struct HookContext<'a> {
+    pub db: &'a Database,
+    pub flight: Flight,
+    /// Full-resolution telemetry (empty for manual entries)
+    pub records: Vec<TelemetryRecord>,
+    /// Stats derived from `records`
+    pub stats: FlightStats,
+    /// Per-profile config.json (smart tag settings, filename rules)
+    pub config: &'a serde_json::Value,
+}
+
+/// A single post-import processor
+pub trait PostImportHook: Send + Sync {
+    /// Stable identifier used in settings and results
+    fn id(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    /// False for hooks whose work the parsers already do during import
+    fn runs_on_import(&self) -> bool {
+        true
+    }
+    /// Run the hook, returning a short summary of what it did
+    fn run(&self, ctx: &mut HookContext) -> Result<String, String>;
+}
+
+/// Registered hooks in execution order
+pub fn builtin_hooks() -> Vec<Box<dyn PostImportHook>> {
+    vec![
+        Box::new(StatsHook),
+        Box::new(SmartTagsHook),
+        Box::new(LocationsHook),
+        Box::new(FilenameRulesHook),
+        Box::new(AnomalyHook),
+        Box::new(RthHook),
+        Box::new(LimitsHook),
+        Box::new(PreviewHook),
+        Box::new(BatteryRetirementHook),
+    ]
+}
+
+/// Hook listing returned to the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookInfo {
+    pub id: String,
+    pub description: String,
+    pub enabled: bool,
+    pub runs_on_import: bool,
+}
+
+/// Result of running one hook
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookOutcome {
+    pub id: String,
+    /// "ok", "disabled", "skipped" or "error"
+    pub status: String,
+    pub message: String,
+}
+
+pub fn is_hook_enabled(db: &Database, id: &str) -> bool {
+    db.get_setting(&format!("{}{}", SETTING_PREFIX, id))
+        .ok()
+        .flatten()
+        .map_or(true, |v| v != "false")
+}
+
+pub fn set_hook_enabled(db: &Database, id: &str, enabled: bool) -> Result<(), String> {
+    if !builtin_hooks().iter().any(|h| h.id() == id) {
+        return Err(format!("Unknown post-import hook '{}'", id));
+    }
+    db.set_setting(&format!("{}{}", SETTING_PREFIX, id), if enabled { "true" } else { "false" })
+        .map_err(|e| format!("Failed to save hook setting: {}", e))
+}
+
+pub fn list_hooks(db: &Database) -> Vec<HookInfo> {
+    builtin_hooks()
+        .iter()
+        .map(|h| HookInfo {
+            id: h.id().to_string(),
+            description: h.description().to_string(),
+            enabled: is_hook_enabled(db, h.id()),
+            runs_on_import: h.runs_on_import(),
+        })
+        .collect()
+}
+
+/// Run the pipeline on a stored flight. A failing hook is reported in its
+/// outcome and does not stop the hooks after it.
+#[tracing::instrument(name = "post_import_hooks", skip_all, fields(flight_id = flight_id, trigger = ?trigger))]
+pub fn run_pipeline(
+    db: &Database,
+    flight_id: i64,
+    config: &serde_json::Value,
+    trigger: HookTrigger,
+) -> Result<Vec<HookOutcome>, String> {
+    let start = std::time::Instant::now();
+    let hooks = builtin_hooks();
+
+    let pending: Vec<&dyn PostImportHook> = hooks
+        .iter()
+        .map(|h| h.as_ref())
+        .filter(|h| trigger == HookTrigger::Reprocess || h.runs_on_import())
+        .collect();
+    if pending.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let flight = db
+        .get_flight_by_id(flight_id)
+        .map_err(|e| format!("Failed to get flight {}: {}", flight_id, e))?;
+    let records = db
+        .get_flight_telemetry(flight_id, None, None)
+        .map_err(|e| format!("Failed to get telemetry for flight {}: {}", flight_id, e))?;
+    let stats = calculate_stats_from_records(&records);
+
+    let mut ctx = HookContext { db, flight, records, stats, config };
+    let mut outcomes = Vec::with_capacity(pending.len());
+
+    for hook in pending {
+        let (status, message) = if !is_hook_enabled(db, hook.id()) {
+            ("disabled", String::new())
+        } else if ctx.records.is_empty() {
+            ("skipped", "No telemetry".to_string())
+        } else {
+            let _span = tracing::info_span!("hook", id = hook.id()).entered();
+            match hook.run(&mut ctx) {
+                Ok(msg) => ("ok", msg),
+                Err(e) => {
+                    log::warn!("Post-import hook '{}' failed for flight {}: {}", hook.id(), flight_id, e);
+                    ("error", e)
+                }
+            }
+        };
+        outcomes.push(HookOutcome {
+            id: hook.id().to_string(),
+            status: status.to_string(),
+            message,
+        });
+    }
+
+    log::debug!(
+        "Post-import pipeline ({:?}) for flight {} finished in {:.1}ms",
+        trigger,
+        flight_id,
+        start.elapsed().as_secs_f64() * 1000.0
+    );
+    Ok(outcomes)
+}
+
+/// Rebuild the parser-level metadata from a stored flight row
+pub fn metadata_from_flight(flight: &Flight) -> FlightMetadata {
+    FlightMetadata {
+        id: flight.id,
+        file_name: flight.file_name.clone(),
+        display_name: flight.display_name.clone(),
+        file_hash: None,
+        drone_model: flight.drone_model.clone(),
+        drone_serial: flight.drone_serial.clone(),
+        aircraft_name: flight.aircraft_name.clone(),
+        battery_serial: flight.battery_serial.clone(),
+        cycle_count: flight.cycle_count,
+        rc_serial: flight.rc_serial.clone(),
+        battery_life: flight.battery_life,
+        start_time: flight.start_time_utc(),
+        end_time: None,
+        duration_secs: flight.duration_secs,
+        total_distance: flight.total_distance,
+        max_altitude: flight.max_altitude,
+        max_speed: flight.max_speed,
+        home_lat: flight.home_lat,
+        home_lon: flight.home_lon,
+        point_count: flight.point_count.unwrap_or(0),
+        photo_count: flight.photo_count.unwrap_or(0),
+        video_count: flight.video_count.unwrap_or(0),
+    }
+}
+
+// ============================================================================
+// Built-in hooks
+// ============================================================================
+
+/// Recompute distance / max altitude / max speed from stored telemetry.
+/// Duration is left alone: DJI logs report it from the app, not the samples.
+struct StatsHook;
+
+impl PostImportHook for StatsHook {
+    fn id(&self) -> &'static str {
+        "stats"
+    }
+
+    fn description(&self) -> &'static str {
+        "Recompute distance, max altitude and max speed from telemetry"
+    }
+
+    fn runs_on_import(&self) -> bool {
+        false
+    }
+
+    fn run(&self, ctx: &mut HookContext) -> Result<String, String> {
+        ctx.db
+            .update_flight_stats(ctx.flight.id, &ctx.stats)
+            .map_err(|e| format!("Failed to update stats: {}", e))?;
+        ctx.flight.total_distance = Some(ctx.stats.total_distance_m);
+        ctx.flight.max_altitude = Some(ctx.stats.max_altitude_m);
+        ctx.flight.max_speed = Some(ctx.stats.max_speed_ms);
+        Ok(format!(
+            "{:.0} m, max {:.1} m, {:.1} m/s",
+            ctx.stats.total_distance_m, ctx.stats.max_altitude_m, ctx.stats.max_speed_ms
+        ))
+    }
+}
+
+/// Regenerate auto tags (including offline reverse geocoding), honouring the
+/// smart tag settings from config.json
+struct SmartTagsHook;
+
+impl PostImportHook for SmartTagsHook {
+    fn id(&self) -> &'static str {
+        "smart_tags"
+    }
+
+    fn description(&self) -> &'static str {
+        "Smart tags and location tags (offline reverse geocoding)"
+    }
+
+    fn runs_on_import(&self) -> bool {
+        false
+    }
+
+    fn run(&self, ctx: &mut HookContext) -> Result<String, String> {
+        let tags_enabled = ctx.config.get("smart_tags_enabled").and_then(|v| v.as_bool()).unwrap_or(true);
+        if !tags_enabled {
+            return Ok("Smart tags are disabled".to_string());
+        }
+
+        let metadata = metadata_from_flight(&ctx.flight);
+        let mut tags = LogParser::generate_smart_tags(&metadata, &ctx.stats);
+        if let Some(types) = ctx.config.get("enabled_tag_types").and_then(|v| v.as_array()) {
+            let enabled_types: Vec<String> = types.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect();
+            tags = LogParser::filter_smart_tags(tags, &enabled_types);
+        }
+        ctx.db
+            .replace_auto_tags(ctx.flight.id, &tags)
+            .map_err(|e| format!("Failed to replace tags: {}", e))?;
+        Ok(format!("{} tags", tags.len()))
+    }
+}
+
+/// Apply location renames and named sites to the auto tags and location name
+struct LocationsHook;
+
+impl PostImportHook for LocationsHook {
+    fn id(&self) -> &'static str {
+        "locations"
+    }
+
+    fn description(&self) -> &'static str {
+        "Location renames and named sites"
+    }
+
+    fn run(&self, ctx: &mut HookContext) -> Result<String, String> {
+        let overrides = locations::load(ctx.db).map_err(|e| format!("Failed to load location overrides: {}", e))?;
+        let tags: Vec<String> = ctx
+            .db
+            .get_flight_tags(ctx.flight.id)
+            .map_err(|e| format!("Failed to get tags: {}", e))?
+            .into_iter()
+            .filter(|t| t.tag_type == "auto")
+            .map(|t| t.tag)
+            .collect();
+        let resolved = overrides.apply(tags, ctx.stats.home_location);
+        ctx.db
+            .replace_auto_tags(ctx.flight.id, &resolved.tags)
+            .map_err(|e| format!("Failed to replace tags: {}", e))?;
+        if ctx.flight.location_name != resolved.location_name
+            && ctx
+                .db
+                .set_detected_location_name(ctx.flight.id, resolved.location_name.as_deref())
+                .map_err(|e| format!("Failed to set location name: {}", e))?
+        {
+            ctx.flight.location_name = resolved.location_name.clone();
+        }
+        Ok(resolved.location_name.unwrap_or_else(|| "No named site".to_string()))
+    }
+}
+
+/// Tags, pilot, job and notes from the file name (see `filename_rules`)
+struct FilenameRulesHook;
+
+impl PostImportHook for FilenameRulesHook {
+    fn id(&self) -> &'static str {
+        "filename_rules"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tags, pilot, job and notes from file name patterns"
+    }
+
+    fn run(&self, ctx: &mut HookContext) -> Result<String, String> {
+        let rules = filename_rules::from_config(ctx.config);
+        if rules.is_empty() {
+            return Ok("No filename rules".to_string());
+        }
+        filename_rules::apply(ctx.db, &mut ctx.flight, &rules)
+    }
+}
+
+/// Flag signal losses, sensor glitches and low-battery landings as caution messages
+struct AnomalyHook;
+
+/// Satellite count below which GPS positioning is considered lost
+const ANOMALY_MIN_SATELLITES: i32 = 4;
+/// Height change between consecutive samples (within 1 s) treated as a sensor glitch
+const ANOMALY_HEIGHT_JUMP_M: f64 = 30.0;
+/// Landing battery level that warrants a caution
+const ANOMALY_LOW_LANDING_BATTERY: i32 = 10;
+
+impl PostImportHook for AnomalyHook {
+    fn id(&self) -> &'static str {
+        "anomalies"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detect GPS/RC signal loss, altitude glitches and low-battery landings"
+    }
+
+    fn run(&self, ctx: &mut HookContext) -> Result<String, String> {
+        let messages = detect_anomalies(&ctx.records);
+        ctx.db
+            .insert_flight_messages(ctx.flight.id, &messages)
+            .map_err(|e| format!("Failed to store anomaly messages: {}", e))?;
+        Ok(format!("{} anomalies", messages.len()))
+    }
+}
+
+/// Find the first occurrence of each anomaly kind. Message text is
+/// deterministic so reprocessing does not create duplicates.
+fn detect_anomalies(records: &[TelemetryRecord]) -> Vec<FlightMessage> {
+    let mut messages = Vec::new();
+    let caution = |ts: i64, text: String| FlightMessage {
+        timestamp_ms: ts,
+        message_type: "caution".to_string(),
+        message: text,
+    };
+
+    let airborne = |r: &TelemetryRecord| r.height.is_some_and(|h| h > 2.0);
+
+    let mut had_gps = false;
+    if let Some(r) = records.iter().find(|r| {
+        let sats = r.satellites.unwrap_or(0);
+        had_gps |= sats >= ANOMALY_MIN_SATELLITES + 2;
+        had_gps && airborne(r) && r.satellites.is_some() && sats < ANOMALY_MIN_SATELLITES
+    }) {
+        messages.push(caution(
+            r.timestamp_ms,
+            format!("Anomaly: GPS lost in flight ({} satellites)", r.satellites.unwrap_or(0)),
+        ));
+    }
+
+    let mut had_rc = false;
+    if let Some(r) = records.iter().find(|r| {
+        had_rc |= r.rc_signal.is_some_and(|s| s > 0);
+        had_rc && airborne(r) && r.rc_signal == Some(0)
+    }) {
+        messages.push(caution(r.timestamp_ms, "Anomaly: RC signal lost in flight".to_string()));
+    }
+
+    if let Some(pair) = records.windows(2).find(|w| {
+        w[1].timestamp_ms - w[0].timestamp_ms <= 1000
+            && matches!((w[0].height, w[1].height), (Some(a), Some(b)) if (b - a).abs() > ANOMALY_HEIGHT_JUMP_M)
+    }) {
+        messages.push(caution(
+            pair[1].timestamp_ms,
+            format!(
+                "Anomaly: height jumped {:.0} m in one sample (sensor glitch?)",
+                pair[1].height.unwrap_or(0.0) - pair[0].height.unwrap_or(0.0)
+            ),
+        ));
+    }
+
+    if let Some((ts, pct)) = records.iter().rev().find_map(|r| r.battery_percent.map(|p| (r.timestamp_ms, p))) {
+        if pct > 0 && pct < ANOMALY_LOW_LANDING_BATTERY {
+            messages.push(caution(ts, format!("Anomaly: landed with {}% battery", pct)));
+        }
+    }
+
+    messages
+}
+
+/// Detect return-to-home activations and store their performance metrics
+struct RthHook;
+
+impl PostImportHook for RthHook {
+    fn id(&self) -> &'static str {
+        "rth"
+    }
+
+    fn description(&self) -> &'static str {
+        "Return-to-home analysis (trigger distance/battery, time to land, landing accuracy)"
+    }
+
+    fn run(&self, ctx: &mut HookContext) -> Result<String, String> {
+        let home = match (ctx.flight.home_lon, ctx.flight.home_lat) {
+            (Some(lon), Some(lat)) => Some([lon, lat]),
+            _ => ctx.stats.home_location,
+        };
+        let events = rth::analyze_rth(&ctx.records, home);
+        ctx.db
+            .replace_rth_events(ctx.flight.id, &events)
+            .map_err(|e| format!("Failed to store RTH events: {}", e))?;
+        Ok(format!("{} RTH events", events.len()))
+    }
+}
+
+/// Flag flights that went above the personal altitude or range limits
+struct LimitsHook;
+
+impl PostImportHook for LimitsHook {
+    fn id(&self) -> &'static str {
+        "limits"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flag flights exceeding the personal max altitude / max distance limits"
+    }
+
+    fn run(&self, ctx: &mut HookContext) -> Result<String, String> {
+        let count = ctx.db
+            .refresh_exceedances(Some(ctx.flight.id))
+            .map_err(|e| format!("Failed to check limits: {}", e))?;
+        if count > 0 {
+            log::info!("Flight {} exceeded {} personal limit(s)", ctx.flight.id, count);
+        }
+        Ok(format!("{} exceedances", count))
+    }
+}
+
+/// Cache the flight list preview (track polyline, sparklines, map thumbnail)
+struct PreviewHook;
+
+impl PostImportHook for PreviewHook {
+    fn id(&self) -> &'static str {
+        "preview"
+    }
+
+    fn description(&self) -> &'static str {
+        "Cache the flight list preview (simplified track, altitude/battery sparklines, thumbnail)"
+    }
+
+    fn run(&self, ctx: &mut HookContext) -> Result<String, String> {
+        let preview = preview::build_preview(ctx.flight.id, &ctx.records);
+        ctx.db
+            .save_flight_preview(&preview)
+            .map_err(|e| format!("Failed to store preview: {}", e))?;
+        Ok(format!("{} track points", preview.track.len()))
+    }
+}
+
+/// Notify (and post to the battery retirement webhook) the first time the
+/// flight's battery crosses a retirement threshold
+struct BatteryRetirementHook;
+
+impl PostImportHook for BatteryRetirementHook {
+    fn id(&self) -> &'static str {
+        "battery_retirement"
+    }
+
+    fn description(&self) -> &'static str {
+        "Notify and call the battery retirement webhook when a battery is first recommended for retirement"
+    }
+
+    fn run(&self, ctx: &mut HookContext) -> Result<String, String> {
+        let settings = battery_retirement::RetirementSettings::from_config(ctx.config);
+        let url = settings.webhook_url.clone();
+        if url.is_none() && !NotificationSettings::from_config(ctx.config).battery_health {
+            return Ok("No webhook or notification configured".to_string());
+        }
+        let Some(serial) = ctx.flight.battery_serial.as_deref().map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty()) else {
+            return Ok("No battery serial".to_string());
+        };
+        let fleet = crate::fleet::load_fleet_overview(ctx.db, 30).map_err(|e| format!("Failed to load fleet: {}", e))?;
+        let recommendation = fleet
+            .batteries
+            .iter()
+            .find(|b| b.serial == serial)
+            .and_then(|b| battery_retirement::assess(b, &settings, chrono::Utc::now()));
+        let Some(recommendation) = recommendation else {
+            return Ok(format!("{} within limits", serial));
+        };
+        if !battery_retirement::mark_notified(ctx.db, &serial) {
+            return Ok(format!("{} already reported", serial));
+        }
+        notifications::notify(
+            ctx.db,
+            ctx.config,
+            &NotificationEvent::BatteryHealth {
+                battery_serial: recommendation.battery_serial.clone(),
+                display_name: recommendation.display_name.clone(),
+                reasons: recommendation.reasons.clone(),
+            },
+        );
+        let Some(url) = url else {
+            return Ok(format!("{} recommended for retirement, notified", serial));
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return Err("No async runtime to send the webhook from".to_string());
+        };
+        let flight_id = ctx.flight.id;
+        runtime.spawn(async move {
+            if let Err(e) = battery_retirement::notify_webhook(&url, &recommendation, flight_id).await {
+                log::warn!("Battery retirement webhook for {} failed: {}", recommendation.battery_serial, e);
+            }
+        });
+        Ok(format!("{} recommended for retirement, webhook notified", serial))
+    }
+}
@@ -0,0 +1,242 @@
+//! Per-stage import timings.
+//!
+//! Every import records how long each stage took: reading and hashing the
+//! file, fetching keychains and decrypting (DJI v13+ logs), parsing, inserting
+//! the flight, and the post-import hooks. Profiles are kept in the
+//! `import_profiles` table (the most recent ones only) and summarized per
+//! format by the import diagnostics endpoint, so a slow-import report can be
+//! traced to the stage and format responsible.
+
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::database::Database;
+use crate::parser::ParseResult;
+
+/// Profiles kept in the table; older ones are pruned on insert
+pub const MAX_PROFILES: i64 = 2000;
+
+/// Timings measured by the parser
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseTimings {
+    pub file_size_bytes: u64,
+    /// Reading and hashing the file, duplicate check
+    pub read_ms: f64,
+    /// Keychain fetch and decryption (encrypted DJI logs only)
+    pub decrypt_ms: Option<f64>,
+    /// Parsing and normalization, excluding decryption
+    pub parse_ms: f64,
+}
+
+/// One import, stage by stage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportProfile {
+    pub flight_id: i64,
+    pub file_name: String,
+    pub format: String,
+    pub file_size_bytes: i64,
+    pub point_count: i64,
+    pub read_ms: f64,
+    pub decrypt_ms: Option<f64>,
+    pub parse_ms: f64,
+    /// Flight, telemetry, tags, messages and import report
+    pub insert_ms: f64,
+    /// Post-import hook pipeline
+    pub post_process_ms: f64,
+    pub total_ms: f64,
+    pub recorded_at: Option<String>,
+}
+
+/// Mean timings of the imports of one format
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatTimings {
+    pub format: String,
+    pub imports: usize,
+    pub mean_read_ms: f64,
+    /// Over the imports that needed decryption
+    pub mean_decrypt_ms: Option<f64>,
+    pub mean_parse_ms: f64,
+    pub mean_insert_ms: f64,
+    pub mean_post_process_ms: f64,
+    pub mean_total_ms: f64,
+    /// Total time per 1000 telemetry points, to compare files of different length
+    pub ms_per_1000_points: Option<f64>,
+}
+
+/// Share of the total import time spent in each stage, in percent
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageShares {
+    pub read_pct: f64,
+    pub decrypt_pct: f64,
+    pub parse_pct: f64,
+    pub insert_pct: f64,
+    pub post_process_pct: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportDiagnostics {
+    pub imports: usize,
+    pub stage_shares: StageShares,
+    pub by_format: Vec<FormatTimings>,
+    pub slowest: Vec<ImportProfile>,
+    pub recent: Vec<ImportProfile>,
+}
+
+/// Measures consecutive stages
+pub struct StageTimer {
+    last: Instant,
+}
+
+impl StageTimer {
+    pub fn start() -> Self {
+        Self { last: Instant::now() }
+    }
+
+    /// Milliseconds since the start or the previous lap
+    pub fn lap(&mut self) -> f64 {
+        let now = Instant::now();
+        let ms = now.duration_since(self.last).as_secs_f64() * 1000.0;
+        self.last = now;
+        ms
+    }
+}
+
+/// Store the profile of a finished import (failures are only logged)
+pub fn record(db: &Database, flight_id: i64, parse_result: &ParseResult, insert_ms: f64, post_process_ms: f64) {
+    let timings = &parse_result.info.timings;
+    let profile = ImportProfile {
+        flight_id,
+        file_name: parse_result.metadata.file_name.clone(),
+        format: parse_result.info.format.clone(),
+        file_size_bytes: timings.file_size_bytes as i64,
+        point_count: parse_result.points.len() as i64,
+        read_ms: timings.read_ms,
+        decrypt_ms: timings.decrypt_ms,
+        parse_ms: timings.parse_ms,
+        insert_ms,
+        post_process_ms,
+        total_ms: timings.read_ms + timings.decrypt_ms.unwrap_or(0.0) + timings.parse_ms + insert_ms + post_process_ms,
+        recorded_at: None,
+    };
+    log::debug!(
+        "Import profile for flight {}: read {:.0}ms, decrypt {:?}ms, parse {:.0}ms, insert {:.0}ms, post-process {:.0}ms",
+        flight_id,
+        profile.read_ms,
+        profile.decrypt_ms.map(|v| v.round()),
+        profile.parse_ms,
+        profile.insert_ms,
+        profile.post_process_ms
+    );
+    if let Err(e) = db.save_import_profile(&profile) {
+        log::warn!("Failed to save import profile for flight {}: {}", flight_id, e);
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, n) = values.fold((0.0, 0usize), |(s, n), v| (s + v, n + 1));
+    (n > 0).then(|| sum / n as f64)
+}
+
+/// Summarize profiles (newest first) per format and stage
+pub fn summarize(profiles: Vec<ImportProfile>, limit: usize) -> ImportDiagnostics {
+    let total: f64 = profiles.iter().map(|p| p.total_ms).sum();
+    let share = |f: &dyn Fn(&ImportProfile) -> f64| {
+        if total > 0.0 {
+            profiles.iter().map(f).sum::<f64>() / total * 100.0
+        } else {
+            0.0
+        }
+    };
+    let stage_shares = StageShares {
+        read_pct: share(&|p| p.read_ms),
+        decrypt_pct: share(&|p| p.decrypt_ms.unwrap_or(0.0)),
+        parse_pct: share(&|p| p.parse_ms),
+        insert_pct: share(&|p| p.insert_ms),
+        post_process_pct: share(&|p| p.post_process_ms),
+    };
+
+    let mut formats: Vec<String> = profiles.iter().map(|p| p.format.clone()).collect();
+    formats.sort();
+    formats.dedup();
+    let mut by_format: Vec<FormatTimings> = formats
+        .into_iter()
+        .map(|format| {
+            let list: Vec<&ImportProfile> = profiles.iter().filter(|p| p.format == format).collect();
+            let points: i64 = list.iter().map(|p| p.point_count).sum();
+            let total_ms: f64 = list.iter().map(|p| p.total_ms).sum();
+            FormatTimings {
+                imports: list.len(),
+                mean_read_ms: mean(list.iter().map(|p| p.read_ms)).unwrap_or(0.0),
+                mean_decrypt_ms: mean(list.iter().filter_map(|p| p.decrypt_ms)),
+                mean_parse_ms: mean(list.iter().map(|p| p.parse_ms)).unwrap_or(0.0),
+                mean_insert_ms: mean(list.iter().map(|p| p.insert_ms)).unwrap_or(0.0),
+                mean_post_process_ms: mean(list.iter().map(|p| p.post_process_ms)).unwrap_or(0.0),
+                mean_total_ms: total_ms / list.len() as f64,
+                ms_per_1000_points: (points > 0).then(|| total_ms / points as f64 * 1000.0),
+                format,
+            }
+        })
+        .collect();
+    by_format.sort_by(|a, b| b.mean_total_ms.total_cmp(&a.mean_total_ms));
+
+    let mut slowest = profiles.clone();
+    slowest.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+    slowest.truncate(limit);
+
+    ImportDiagnostics {
+        imports: profiles.len(),
+        stage_shares,
+        by_format,
+        slowest,
+        recent: profiles.into_iter().take(limit).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(flight_id: i64, format: &str, decrypt_ms: Option<f64>, parse_ms: f64, points: i64) -> ImportProfile {
+        let total_ms = 10.0 + decrypt_ms.unwrap_or(0.0) + parse_ms + 20.0 + 10.0;
+        ImportProfile {
+            flight_id,
+            file_name: format!("{}.txt", flight_id),
+            format: format.to_string(),
+            file_size_bytes: 1000,
+            point_count: points,
+            read_ms: 10.0,
+            decrypt_ms,
+            parse_ms,
+            insert_ms: 20.0,
+            post_process_ms: 10.0,
+            total_ms,
+            recorded_at: None,
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let profiles = vec![
+            profile(3, "DJI TXT", Some(800.0), 150.0, 5000),
+            profile(2, "Litchi CSV", None, 60.0, 1000),
+            profile(1, "DJI TXT", None, 50.0, 5000),
+        ];
+        let diag = summarize(profiles, 2);
+        assert_eq!(diag.imports, 3);
+        assert_eq!(diag.by_format[0].format, "DJI TXT");
+        assert_eq!(diag.by_format[0].imports, 2);
+        assert_eq!(diag.by_format[0].mean_decrypt_ms, Some(800.0));
+        assert_eq!(diag.by_format[0].ms_per_1000_points, Some(108.0));
+        assert_eq!(diag.slowest[0].flight_id, 3);
+        assert_eq!(diag.recent.len(), 2);
+        assert_eq!(diag.recent[0].flight_id, 3);
+        // 800 of 1180 ms were spent decrypting
+        assert!((diag.stage_shares.decrypt_pct - 800.0 / 1180.0 * 100.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,108 @@
+//! Persistent background import queue.
+//!
+//! Files submitted while another import is running are queued in the
+//! `import_queue` table instead of failing or blocking the caller. One worker
+//! per profile database imports them in order; entries left `running` by a
+//! crash or restart go back to `queued` when the database is opened (the import
+//! journal has already removed any partial flight), so the queue survives
+//! restarts. Desktop queues the user's file paths; web mode spools uploads into
+//! `import_queue/` in the data directory until they are imported.
+
+use std::future::Future;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::database::Database;
+use crate::models::ImportResult;
+
+/// State of a queued import
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+impl QueueStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Done => "done",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "running" => Self::Running,
+            "done" => Self::Done,
+            "failed" => Self::Failed,
+            _ => Self::Queued,
+        }
+    }
+}
+
+/// One entry of the import queue
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedImport {
+    pub id: String,
+    pub file_name: String,
+    /// Desktop: the user's file; web: the spooled upload
+    pub file_path: String,
+    pub status: QueueStatus,
+    /// Import result message (or error)
+    pub message: Option<String>,
+    pub flight_id: Option<i64>,
+    pub created_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    /// Web account that queued the file (multi-user mode)
+    pub owner: Option<String>,
+}
+
+/// Import every queued entry, one at a time, with `import`. Returns at once
+/// when a worker is already running for this database.
+pub async fn run_worker<F, Fut>(db: Arc<Database>, mut import: F)
+where
+    F: FnMut(QueuedImport) -> Fut,
+    Fut: Future<Output = Result<ImportResult, String>>,
+{
+    loop {
+        if db.import_worker_active.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        loop {
+            let item = match db.claim_next_import() {
+                Ok(Some(item)) => item,
+                Ok(None) => break,
+                Err(e) => {
+                    log::warn!("Import queue: failed to claim next entry: {}", e);
+                    db.import_worker_active.store(false, Ordering::SeqCst);
+                    return;
+                }
+            };
+            log::info!("Import queue: importing {}", item.file_name);
+            let (status, message, flight_id) = match import(item.clone()).await {
+                Ok(result) if result.success => (QueueStatus::Done, result.message, result.flight_id),
+                Ok(result) => (QueueStatus::Failed, result.message, None),
+                Err(e) => (QueueStatus::Failed, e, None),
+            };
+            if let Err(e) = db.complete_queued_import(&item.id, status, &message, flight_id) {
+                log::warn!("Import queue: failed to record result for {}: {}", item.file_name, e);
+            }
+        }
+        db.import_worker_active.store(false, Ordering::SeqCst);
+
+        // An entry queued between the last claim and releasing the flag would
+        // otherwise wait for the next submission
+        if !db.has_queued_imports().unwrap_or(false) {
+            return;
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Live import status, for a global progress indicator.
+//!
+//! Every web import (direct uploads and the background queue) registers
+//! itself while it runs and reports the stage it is in; the percentage is
+//! estimated from the stage, as the parser does not report finer progress.
+//! Finished imports are kept in a short in-memory list of recent results.
+//! Combined with the persistent import queue this gives a status that
+//! survives page reloads (but not server restarts, apart from the queue).
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+
+use crate::database::{Database, DatabaseError};
+use crate::import_queue::QueueStatus;
+
+/// Finished imports kept for the status
+const RECENT_LIMIT: usize = 20;
+
+/// Step of a running import
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStage {
+    Parsing,
+    Inserting,
+    PostProcessing,
+}
+
+impl ImportStage {
+    /// Rough progress at the start of the stage (parsing takes most of the time)
+    pub fn percent(&self) -> u8 {
+        match self {
+            Self::Parsing => 5,
+            Self::Inserting => 60,
+            Self::PostProcessing => 85,
+        }
+    }
+}
+
+/// An import in progress
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveImport {
+    #[serde(skip)]
+    id: u64,
+    pub file_name: String,
+    pub stage: ImportStage,
+    pub percent: u8,
+    pub started_at: String,
+}
+
+/// A finished import
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentImport {
+    pub file_name: String,
+    pub success: bool,
+    pub message: String,
+    pub flight_id: Option<i64>,
+    pub finished_at: String,
+}
+
+/// Running and recent imports of one profile database
+#[derive(Debug, Default)]
+pub struct ImportActivity {
+    active: Vec<ActiveImport>,
+    recent: VecDeque<RecentImport>,
+}
+
+/// Returned by `GET /api/import_status`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportStatus {
+    /// Whether any import is running or queued
+    pub busy: bool,
+    pub active: Vec<ActiveImport>,
+    /// Queue entries waiting for the worker
+    pub queued: usize,
+    /// File names of the waiting entries, oldest first
+    pub queued_files: Vec<String>,
+    /// Newest first
+    pub recent: Vec<RecentImport>,
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Registers an import while it runs; dropping it (on any return path)
+/// removes the import from the active list
+pub struct ImportTracker<'a> {
+    db: &'a Database,
+    id: u64,
+    file_name: String,
+}
+
+/// Start tracking an import of `file_name`
+pub fn track<'a>(db: &'a Database, file_name: &str) -> ImportTracker<'a> {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    db.import_activity.lock().unwrap().active.push(ActiveImport {
+        id,
+        file_name: file_name.to_string(),
+        stage: ImportStage::Parsing,
+        percent: ImportStage::Parsing.percent(),
+        started_at: now(),
+    });
+    ImportTracker { db, id, file_name: file_name.to_string() }
+}
+
+impl ImportTracker<'_> {
+    pub fn stage(&self, stage: ImportStage) {
+        let mut activity = self.db.import_activity.lock().unwrap();
+        if let Some(entry) = activity.active.iter_mut().find(|a| a.id == self.id) {
+            entry.stage = stage;
+            entry.percent = stage.percent();
+        }
+    }
+
+    /// Record the outcome in the recent results
+    pub fn finish(&self, success: bool, message: &str, flight_id: Option<i64>) {
+        let mut activity = self.db.import_activity.lock().unwrap();
+        activity.recent.push_front(RecentImport {
+            file_name: self.file_name.clone(),
+            success,
+            message: message.to_string(),
+            flight_id,
+            finished_at: now(),
+        });
+        activity.recent.truncate(RECENT_LIMIT);
+    }
+}
+
+impl Drop for ImportTracker<'_> {
+    fn drop(&mut self) {
+        self.db.import_activity.lock().unwrap().active.retain(|a| a.id != self.id);
+    }
+}
+
+/// Current import status of a profile
+pub fn status(db: &Database) -> Result<ImportStatus, DatabaseError> {
+    let queued_files: Vec<String> = db
+        .get_import_queue()?
+        .into_iter()
+        .filter(|e| e.status == QueueStatus::Queued)
+        .map(|e| e.file_name)
+        .collect();
+    let activity = db.import_activity.lock().unwrap();
+    Ok(ImportStatus {
+        busy: !activity.active.is_empty() || !queued_files.is_empty(),
+        active: activity.active.clone(),
+        queued: queued_files.len(),
+        queued_files,
+        recent: activity.recent.iter().cloned().collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(dir.path().to_path_buf(), "default").unwrap();
+        {
+            let tracker = track(&db, "flight.txt");
+            tracker.stage(ImportStage::Inserting);
+            let current = status(&db).unwrap();
+            assert!(current.busy);
+            assert_eq!(current.active[0].percent, 60);
+            tracker.finish(true, "ok", Some(1));
+        }
+        let after = status(&db).unwrap();
+        assert!(!after.busy);
+        assert_eq!(after.recent.len(), 1);
+        assert!(after.recent[0].success);
+    }
+}
@@ -0,0 +1,226 @@
+//! Tamper evidence for the logbook.
+//!
+//! Every import, metadata edit and deletion appends an entry to the
+//! `integrity_chain` table holding the SHA-256 of the raw log file (the
+//! flight's `file_hash`) and of the flight's record (the imported figures
+//! plus name, notes, pilot and the other editable fields). Each entry also
+//! hashes the previous entry, so rewriting, removing or reordering history
+//! breaks every later link.
+//!
+//! Verification walks the chain and then compares each flight as stored now
+//! with its latest entry: a flight changed outside the app (for example with
+//! a DuckDB client), or inserted or deleted that way, shows up as a mismatch.
+//! Operators can keep the chain head hash from a verification report
+//! elsewhere (an email, a printed logbook page) to later show that nothing
+//! before it was rewritten, including the chain itself.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// `prev_hash` of the first entry
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Flight record fields covered by the record hash, in hashing order
+pub const RECORD_FIELDS: &[&str] = &[
+    "file_name", "display_name", "notes", "drone_model", "drone_serial", "aircraft_name",
+    "battery_serial", "pilot", "location_name", "start_time_ms", "end_time_ms", "duration_secs",
+    "total_distance", "max_altitude", "max_speed", "home_lat", "home_lon", "point_count",
+];
+
+/// One link of the chain
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityEntry {
+    pub seq: i64,
+    /// RFC 3339, hashed as stored
+    pub recorded_at: String,
+    pub flight_id: i64,
+    /// 'import', 'update', 'delete', 'restore' or 'baseline'
+    pub event: String,
+    pub file_hash: Option<String>,
+    /// None for deletions
+    pub record_hash: Option<String>,
+    pub prev_hash: String,
+    pub entry_hash: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityIssue {
+    pub flight_id: Option<i64>,
+    pub seq: Option<i64>,
+    pub message: String,
+}
+
+/// Result of checking the chain and the flights against it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    /// No broken links and no flight differs from its latest entry
+    pub valid: bool,
+    pub entries: usize,
+    pub flights_checked: usize,
+    /// Hash of the last entry; keep it elsewhere to attest to the history up to now
+    pub chain_head: String,
+    /// Flights imported before the chain existed (see `seal`)
+    pub unrecorded_flights: Vec<i64>,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Hash of a flight record, given its `RECORD_FIELDS` values
+pub fn record_hash(values: &serde_json::Map<String, serde_json::Value>) -> String {
+    let canonical: Vec<serde_json::Value> = RECORD_FIELDS
+        .iter()
+        .map(|field| values.get(*field).cloned().unwrap_or(serde_json::Value::Null))
+        .collect();
+    sha256_hex(serde_json::Value::Array(canonical).to_string().as_bytes())
+}
+
+/// Hash of an entry over its contents and the previous entry's hash
+pub fn entry_hash(
+    prev_hash: &str,
+    seq: i64,
+    recorded_at: &str,
+    flight_id: i64,
+    event: &str,
+    file_hash: Option<&str>,
+    record_hash: Option<&str>,
+) -> String {
+    let line = [
+        prev_hash,
+        &seq.to_string(),
+        recorded_at,
+        &flight_id.to_string(),
+        event,
+        file_hash.unwrap_or(""),
+        record_hash.unwrap_or(""),
+    ]
+    .join("|");
+    sha256_hex(line.as_bytes())
+}
+
+/// Check the chain links, then each current flight `(file_hash, record_hash)`
+/// against the latest entry for it
+pub fn verify(entries: &[IntegrityEntry], flights: &HashMap<i64, (Option<String>, String)>) -> IntegrityReport {
+    let mut issues = Vec::new();
+    let mut prev = GENESIS_HASH.to_string();
+    let mut latest: HashMap<i64, &IntegrityEntry> = HashMap::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let issue = |message: String| IntegrityIssue { flight_id: Some(entry.flight_id), seq: Some(entry.seq), message };
+        if entry.seq != i as i64 + 1 {
+            issues.push(issue(format!("Entry out of sequence (expected {})", i + 1)));
+        }
+        if entry.prev_hash != prev {
+            issues.push(issue("Link to the previous entry is broken".to_string()));
+        }
+        let expected = entry_hash(
+            &entry.prev_hash,
+            entry.seq,
+            &entry.recorded_at,
+            entry.flight_id,
+            &entry.event,
+            entry.file_hash.as_deref(),
+            entry.record_hash.as_deref(),
+        );
+        if entry.entry_hash != expected {
+            issues.push(issue("Entry contents do not match its hash".to_string()));
+        }
+        prev = entry.entry_hash.clone();
+        latest.insert(entry.flight_id, entry);
+    }
+
+    let mut unrecorded_flights = Vec::new();
+    let mut flight_ids: Vec<&i64> = flights.keys().collect();
+    flight_ids.sort();
+    for id in flight_ids {
+        let (file_hash, record) = &flights[id];
+        let issue = |message: &str| IntegrityIssue { flight_id: Some(*id), seq: None, message: message.to_string() };
+        match latest.get(id) {
+            None => unrecorded_flights.push(*id),
+            Some(entry) if entry.event == "delete" => issues.push(issue("Flight was deleted but exists again")),
+            Some(entry) => {
+                if entry.file_hash != *file_hash {
+                    issues.push(issue("Raw log hash differs from the recorded one"));
+                }
+                if entry.record_hash.as_deref() != Some(record.as_str()) {
+                    issues.push(issue("Flight record was changed outside the app"));
+                }
+            }
+        }
+    }
+    let mut missing: Vec<i64> = latest
+        .values()
+        .filter(|e| e.event != "delete" && !flights.contains_key(&e.flight_id))
+        .map(|e| e.flight_id)
+        .collect();
+    missing.sort();
+    for id in missing {
+        issues.push(IntegrityIssue { flight_id: Some(id), seq: None, message: "Flight was removed outside the app".to_string() });
+    }
+
+    IntegrityReport {
+        valid: issues.is_empty(),
+        entries: entries.len(),
+        flights_checked: flights.len(),
+        chain_head: prev,
+        unrecorded_flights,
+        issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn append(chain: &mut Vec<IntegrityEntry>, flight_id: i64, event: &str, record: Option<&str>) {
+        let seq = chain.len() as i64 + 1;
+        let prev_hash = chain.last().map_or(GENESIS_HASH.to_string(), |e| e.entry_hash.clone());
+        let recorded_at = "2026-10-16T08:00:00+00:00".to_string();
+        let file_hash = Some(format!("file{}", flight_id));
+        let entry_hash = entry_hash(&prev_hash, seq, &recorded_at, flight_id, event, file_hash.as_deref(), record);
+        chain.push(IntegrityEntry {
+            seq,
+            recorded_at,
+            flight_id,
+            event: event.to_string(),
+            file_hash,
+            record_hash: record.map(str::to_string),
+            prev_hash,
+            entry_hash,
+        });
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        let mut chain = Vec::new();
+        append(&mut chain, 1, "import", Some("a"));
+        append(&mut chain, 2, "import", Some("b"));
+        append(&mut chain, 1, "update", Some("a2"));
+        append(&mut chain, 2, "delete", None);
+        let mut flights = HashMap::new();
+        flights.insert(1, (Some("file1".to_string()), "a2".to_string()));
+        flights.insert(3, (Some("file3".to_string()), "c".to_string()));
+
+        let report = verify(&chain, &flights);
+        assert!(report.valid);
+        assert_eq!(report.unrecorded_flights, vec![3]);
+        assert_eq!(report.chain_head, chain[3].entry_hash);
+
+        // Edited behind the app's back
+        flights.insert(1, (Some("file1".to_string()), "edited".to_string()));
+        assert_eq!(verify(&chain, &flights).issues.len(), 1);
+
+        // History rewritten: the first edit is undone in place
+        flights.insert(1, (Some("file1".to_string()), "a2".to_string()));
+        chain[2].record_hash = Some("a".to_string());
+        let report = verify(&chain, &flights);
+        assert!(!report.valid);
+        assert_eq!(report.issues[0].seq, Some(3));
+    }
+}
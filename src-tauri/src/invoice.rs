@@ -0,0 +1,332 @@
+//! Invoiceable time report per client and job.
+//!
+//! Billable time is the sum of flight durations, each rounded with the
+//! profile's duration rounding policy. Standby time is the ground
+//! time between consecutive flights of the same job within one session —
+//! flights whose gap is at most `session_gap_minutes` (battery swaps, repositioning).
+//! Longer gaps start a new session and are not billed. Only flights assigned
+//! to a job are reported; flights are filtered by take-off date.
+
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+use crate::bundle::{build_pdf, pdf_text, PDF_MARGIN, PDF_PAGE_HEIGHT};
+use crate::database::{Database, DatabaseError};
+use crate::duration_rounding::DurationRounding;
+use crate::jobs::Job;
+use crate::models::Flight;
+use crate::units::UnitSettings;
+
+/// Gaps up to this long between two flights belong to the same session
+pub const DEFAULT_SESSION_GAP_MINUTES: f64 = 60.0;
+const NO_CLIENT: &str = "(no client)";
+
+/// Report options
+#[derive(Debug, Clone, Default)]
+pub struct InvoiceOptions {
+    /// Inclusive take-off date range
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// Only this client (case-insensitive)
+    pub client: Option<String>,
+    pub session_gap_minutes: Option<f64>,
+}
+
+/// One job line of the report
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceJobLine {
+    pub job_id: String,
+    pub job_name: String,
+    pub site: Option<String>,
+    pub flight_count: i64,
+    pub session_count: i64,
+    pub billable_hours: f64,
+    pub standby_hours: f64,
+    pub distance_m: f64,
+}
+
+/// Totals for one client
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceClient {
+    pub client: String,
+    pub jobs: Vec<InvoiceJobLine>,
+    pub flight_count: i64,
+    pub billable_hours: f64,
+    pub standby_hours: f64,
+    pub distance_m: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceReport {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub session_gap_minutes: f64,
+    pub duration_rounding: DurationRounding,
+    pub clients: Vec<InvoiceClient>,
+    pub flight_count: i64,
+    pub billable_hours: f64,
+    pub standby_hours: f64,
+    pub distance_m: f64,
+}
+
+/// Parse an optional `YYYY-MM-DD` query value
+pub fn parse_date(value: Option<&str>) -> Result<Option<NaiveDate>, String> {
+    match value.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(s) => NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| format!("Invalid date (expected YYYY-MM-DD): {}", s)),
+        None => Ok(None),
+    }
+}
+
+fn job_line(job: &Job, flights: &[&Flight], gap_secs: f64, rounding: DurationRounding) -> InvoiceJobLine {
+    let mut timed: Vec<(chrono::DateTime<chrono::Utc>, f64)> = flights
+        .iter()
+        .filter_map(|f| f.start_time_utc().map(|t| (t, f.duration_secs.unwrap_or(0.0))))
+        .collect();
+    timed.sort_by_key(|(t, _)| *t);
+
+    let mut standby_secs = 0.0;
+    let mut session_count = if timed.is_empty() { 0 } else { 1 };
+    for pair in timed.windows(2) {
+        let (start, duration) = pair[0];
+        let prev_end = start + Duration::milliseconds((duration * 1000.0) as i64);
+        let gap = (pair[1].0 - prev_end).num_milliseconds() as f64 / 1000.0;
+        if gap <= gap_secs {
+            standby_secs += gap.max(0.0);
+        } else {
+            session_count += 1;
+        }
+    }
+
+    InvoiceJobLine {
+        job_id: job.id.clone(),
+        job_name: job.name.clone(),
+        site: job.site.clone(),
+        flight_count: flights.len() as i64,
+        session_count,
+        billable_hours: rounding.total_secs(flights.iter().map(|f| f.duration_secs.unwrap_or(0.0))) / 3600.0,
+        standby_hours: standby_secs / 3600.0,
+        distance_m: flights.iter().map(|f| f.total_distance.unwrap_or(0.0)).sum(),
+    }
+}
+
+/// Build the report from jobs and their flights
+pub fn build_report(jobs: &[(Job, Vec<Flight>)], options: &InvoiceOptions, rounding: DurationRounding) -> InvoiceReport {
+    let gap_minutes = options.session_gap_minutes.filter(|g| *g >= 0.0).unwrap_or(DEFAULT_SESSION_GAP_MINUTES);
+    let in_range = |f: &Flight| {
+        let Some(date) = f.start_time_utc().map(|t| t.date_naive()) else {
+            return options.from.is_none() && options.to.is_none();
+        };
+        options.from.map_or(true, |from| date >= from) && options.to.map_or(true, |to| date <= to)
+    };
+
+    let mut by_client: BTreeMap<String, Vec<InvoiceJobLine>> = BTreeMap::new();
+    for (job, flights) in jobs {
+        let client = job.client.as_deref().map(str::trim).filter(|c| !c.is_empty()).unwrap_or(NO_CLIENT);
+        if let Some(wanted) = options.client.as_deref() {
+            if !client.eq_ignore_ascii_case(wanted.trim()) {
+                continue;
+            }
+        }
+        let selected: Vec<&Flight> = flights.iter().filter(|f| in_range(f)).collect();
+        if selected.is_empty() {
+            continue;
+        }
+        by_client.entry(client.to_string()).or_default().push(job_line(job, &selected, gap_minutes * 60.0, rounding));
+    }
+
+    let clients: Vec<InvoiceClient> = by_client
+        .into_iter()
+        .map(|(client, mut jobs)| {
+            jobs.sort_by(|a, b| a.job_name.to_lowercase().cmp(&b.job_name.to_lowercase()));
+            InvoiceClient {
+                client,
+                flight_count: jobs.iter().map(|j| j.flight_count).sum(),
+                billable_hours: jobs.iter().map(|j| j.billable_hours).sum(),
+                standby_hours: jobs.iter().map(|j| j.standby_hours).sum(),
+                distance_m: jobs.iter().map(|j| j.distance_m).sum(),
+                jobs,
+            }
+        })
+        .collect();
+
+    InvoiceReport {
+        from: options.from.map(|d| d.to_string()),
+        to: options.to.map(|d| d.to_string()),
+        session_gap_minutes: gap_minutes,
+        duration_rounding: rounding,
+        flight_count: clients.iter().map(|c| c.flight_count).sum(),
+        billable_hours: clients.iter().map(|c| c.billable_hours).sum(),
+        standby_hours: clients.iter().map(|c| c.standby_hours).sum(),
+        distance_m: clients.iter().map(|c| c.distance_m).sum(),
+        clients,
+    }
+}
+
+/// Load every job with its flights and build the report
+pub fn load_report(db: &Database, options: &InvoiceOptions) -> Result<InvoiceReport, DatabaseError> {
+    let flights = db.get_all_flights()?;
+    let mut jobs = Vec::new();
+    for job in db.get_jobs()? {
+        let ids = db.get_job_flight_ids(&job.id)?;
+        let job_flights: Vec<Flight> = flights.iter().filter(|f| ids.contains(&f.id)).cloned().collect();
+        jobs.push((job, job_flights));
+    }
+    Ok(build_report(&jobs, options, db.get_duration_rounding()))
+}
+
+/// One row per job plus a subtotal row per client. The distance column is in
+/// the display distance unit and named after it (`distance_km`, `distance_mi`, ...).
+pub fn report_csv(report: &InvoiceReport, units: &UnitSettings) -> String {
+    use crate::stats_export::csv_field;
+
+    let mut out = format!(
+        "client,job,site,flights,sessions,billable_hours,standby_hours,distance_{}\n",
+        units.distance_label().to_ascii_lowercase()
+    );
+    for client in &report.clients {
+        for job in &client.jobs {
+            out.push_str(&format!(
+                "{},{},{},{},{},{:.2},{:.2},{:.2}\n",
+                csv_field(&client.client),
+                csv_field(&job.job_name),
+                csv_field(job.site.as_deref().unwrap_or("")),
+                job.flight_count,
+                job.session_count,
+                job.billable_hours,
+                job.standby_hours,
+                units.distance_value(job.distance_m)
+            ));
+        }
+        out.push_str(&format!(
+            "{},TOTAL,,{},,{:.2},{:.2},{:.2}\n",
+            csv_field(&client.client),
+            client.flight_count,
+            client.billable_hours,
+            client.standby_hours,
+            units.distance_value(client.distance_m)
+        ));
+    }
+    out
+}
+
+/// One-page PDF with a table per client. Rows that do not fit are summarised
+/// in a final line; the CSV export has them all.
+pub fn report_pdf(report: &InvoiceReport, units: &UnitSettings) -> Vec<u8> {
+    let columns = [0.0, 190.0, 270.0, 340.0, 420.0];
+    let mut content = String::new();
+    let mut y = PDF_PAGE_HEIGHT - PDF_MARGIN - 10.0;
+    let text = |content: &mut String, font: &str, size: f64, x: f64, y: f64, s: &str| {
+        content.push_str(&format!("BT /{} {} Tf {:.1} {:.1} Td ({}) Tj ET\n", font, size, PDF_MARGIN + x, y, pdf_text(s)));
+    };
+    let row = |content: &mut String, font: &str, y: f64, cells: [String; 5]| {
+        for (x, cell) in columns.iter().zip(cells.iter()) {
+            content.push_str(&format!("BT /{} 10 Tf {:.1} {:.1} Td ({}) Tj ET\n", font, PDF_MARGIN + x, y, pdf_text(cell)));
+        }
+    };
+    let figures = |flights: i64, billable: f64, standby: f64, distance: f64| {
+        [format!("{}", flights), format!("{:.2} h", billable), format!("{:.2} h", standby), format!("{:.1} {}", units.distance_value(distance), units.distance_label())]
+    };
+
+    text(&mut content, "F2", 18.0, 0.0, y, "Invoiceable time");
+    y -= 20.0;
+    let range = format!(
+        "{} to {} - sessions split after {:.0} min on the ground",
+        report.from.as_deref().unwrap_or("start"),
+        report.to.as_deref().unwrap_or("today"),
+        report.session_gap_minutes
+    );
+    text(&mut content, "F1", 10.0, 0.0, y, &range);
+    y -= 26.0;
+
+    let mut omitted = 0;
+    for client in &report.clients {
+        if y < PDF_MARGIN + 60.0 {
+            omitted += client.jobs.len();
+            continue;
+        }
+        text(&mut content, "F2", 12.0, 0.0, y, &client.client);
+        y -= 16.0;
+        row(&mut content, "F2", y, ["Job".into(), "Flights".into(), "Billable".into(), "Standby".into(), "Distance".into()]);
+        y -= 14.0;
+        for job in &client.jobs {
+            if y < PDF_MARGIN + 40.0 {
+                omitted += 1;
+                continue;
+            }
+            let [flights, billable, standby, distance] = figures(job.flight_count, job.billable_hours, job.standby_hours, job.distance_m);
+            row(&mut content, "F1", y, [job.job_name.clone(), flights, billable, standby, distance]);
+            y -= 14.0;
+        }
+        let [flights, billable, standby, distance] =
+            figures(client.flight_count, client.billable_hours, client.standby_hours, client.distance_m);
+        row(&mut content, "F2", y, ["Subtotal".into(), flights, billable, standby, distance]);
+        y -= 24.0;
+    }
+    if omitted > 0 {
+        text(&mut content, "F1", 10.0, 0.0, y, &format!("{} more job lines omitted - see the CSV export", omitted));
+        y -= 20.0;
+    }
+    let [flights, billable, standby, distance] =
+        figures(report.flight_count, report.billable_hours, report.standby_hours, report.distance_m);
+    row(&mut content, "F2", y.max(PDF_MARGIN + 16.0), ["Total".into(), flights, billable, standby, distance]);
+    text(
+        &mut content,
+        "F1",
+        8.0,
+        0.0,
+        PDF_MARGIN,
+        &format!("Generated by Open DroneLog on {}", chrono::Utc::now().format("%Y-%m-%d %H:%M UTC")),
+    );
+
+    build_pdf(&content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FlightBuilder;
+
+    fn flight(id: i64, start: &str, duration: f64) -> Flight {
+        FlightBuilder::new(id).start(start).duration(duration).distance(2000.0).build()
+    }
+
+    #[test]
+    fn test_standby_within_sessions_only() {
+        let job = Job {
+            id: "j".to_string(),
+            name: "Bridge inspection".to_string(),
+            client: Some("ACME".to_string()),
+            site: None,
+            start_date: None,
+            end_date: None,
+            notes: None,
+            flight_count: 0,
+        };
+        // Two flights 10 minutes apart, then a new session the next day
+        let flights = vec![
+            flight(1, "2026-05-01T10:00:00Z", 1200.0),
+            flight(2, "2026-05-01T10:30:00Z", 1200.0),
+            flight(3, "2026-05-02T09:00:00Z", 1800.0),
+            flight(4, "2026-06-01T09:00:00Z", 1800.0),
+        ];
+        let options = InvoiceOptions {
+            to: NaiveDate::from_ymd_opt(2026, 5, 31),
+            ..Default::default()
+        };
+        let report = build_report(&[(job, flights)], &options, DurationRounding::Exact);
+        assert_eq!(report.clients.len(), 1);
+        let line = &report.clients[0].jobs[0];
+        assert_eq!(line.flight_count, 3);
+        assert_eq!(line.session_count, 2);
+        assert!((line.billable_hours - 4200.0 / 3600.0).abs() < 1e-9);
+        assert!((line.standby_hours - 600.0 / 3600.0).abs() < 1e-9);
+        assert!((report.distance_m - 6000.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,215 @@
+//! Jobs (missions / contracts) for commercial operators.
+//!
+//! A job groups the flights flown for one client at one site over a date range.
+//! Each flight belongs to at most one job. Per-job statistics add up flight
+//! hours, distance and the batteries and aircraft consumed, so hours and
+//! battery cycles can be reported per contract; the job's flights can be
+//! exported as CSV (optionally through a CSV export template) or JSON.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::database::{Database, DatabaseError};
+use crate::models::Flight;
+
+/// A job / contract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Job {
+    /// Generated on first save when empty
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub client: Option<String>,
+    pub site: Option<String>,
+    /// `YYYY-MM-DD`
+    pub start_date: Option<String>,
+    /// `YYYY-MM-DD`
+    pub end_date: Option<String>,
+    pub notes: Option<String>,
+    /// Number of assigned flights (filled when listing)
+    #[serde(default)]
+    pub flight_count: i64,
+}
+
+/// Usage of one battery or aircraft within a job
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobEquipmentUsage {
+    pub serial: String,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+}
+
+/// Totals for one job
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobStats {
+    pub job: Job,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+    pub total_hours: f64,
+    pub total_distance_m: f64,
+    pub max_altitude_m: f64,
+    pub first_flight: Option<String>,
+    pub last_flight: Option<String>,
+    /// One flight = one battery cycle
+    pub batteries: Vec<JobEquipmentUsage>,
+    pub aircraft: Vec<JobEquipmentUsage>,
+    pub flight_ids: Vec<i64>,
+}
+
+fn usage(map: BTreeMap<String, (i64, f64)>) -> Vec<JobEquipmentUsage> {
+    let mut list: Vec<JobEquipmentUsage> = map
+        .into_iter()
+        .map(|(serial, (flight_count, total_duration_secs))| JobEquipmentUsage { serial, flight_count, total_duration_secs })
+        .collect();
+    list.sort_by(|a, b| b.flight_count.cmp(&a.flight_count).then_with(|| a.serial.cmp(&b.serial)));
+    list
+}
+
+/// Compute a job's totals from its flights
+pub fn job_stats(job: Job, flights: &[Flight]) -> JobStats {
+    let mut batteries: BTreeMap<String, (i64, f64)> = BTreeMap::new();
+    let mut aircraft: BTreeMap<String, (i64, f64)> = BTreeMap::new();
+    let mut total_duration_secs = 0.0;
+    let mut total_distance_m = 0.0;
+    let mut max_altitude_m: f64 = 0.0;
+
+    for f in flights {
+        let duration = f.duration_secs.unwrap_or(0.0);
+        total_duration_secs += duration;
+        total_distance_m += f.total_distance.unwrap_or(0.0);
+        max_altitude_m = max_altitude_m.max(f.max_altitude.unwrap_or(0.0));
+
+        if let Some(serial) = f.battery_serial.as_deref().filter(|s| !s.is_empty()) {
+            let entry = batteries.entry(serial.to_string()).or_default();
+            entry.0 += 1;
+            entry.1 += duration;
+        }
+        let airframe = f
+            .aircraft_name
+            .as_deref()
+            .or(f.drone_serial.as_deref())
+            .or(f.drone_model.as_deref())
+            .filter(|s| !s.is_empty());
+        if let Some(name) = airframe {
+            let entry = aircraft.entry(name.to_string()).or_default();
+            entry.0 += 1;
+            entry.1 += duration;
+        }
+    }
+
+    let mut starts: Vec<String> = flights.iter().filter_map(|f| f.start_time_utc()).map(|t| t.to_rfc3339()).collect();
+    starts.sort();
+
+    JobStats {
+        job,
+        flight_count: flights.len() as i64,
+        total_duration_secs,
+        total_hours: total_duration_secs / 3600.0,
+        total_distance_m,
+        max_altitude_m,
+        first_flight: starts.first().cloned(),
+        last_flight: starts.last().cloned(),
+        batteries: usage(batteries),
+        aircraft: usage(aircraft),
+        flight_ids: flights.iter().map(|f| f.id).collect(),
+    }
+}
+
+/// Load a job and its flights (None when the job does not exist)
+pub fn load_job(db: &Database, job_id: &str) -> Result<Option<(Job, Vec<Flight>)>, DatabaseError> {
+    let Some(job) = db.get_job(job_id)? else {
+        return Ok(None);
+    };
+    let ids = db.get_job_flight_ids(job_id)?;
+    let mut flights: Vec<Flight> = db.get_all_flights()?.into_iter().filter(|f| ids.contains(&f.id)).collect();
+    flights.sort_by_key(|f| f.start_time_utc());
+    Ok(Some((job, flights)))
+}
+
+/// Render a job export: `csv` (default layout, or a saved CSV export template)
+/// or `json` (statistics plus the flight list)
+pub fn export_job(
+    job: Job,
+    flights: &[Flight],
+    format: &str,
+    template: Option<&crate::export_templates::CsvTemplate>,
+    rounding: crate::duration_rounding::DurationRounding,
+) -> Result<String, String> {
+    match format.to_ascii_lowercase().as_str() {
+        "csv" => match template {
+            Some(t) => t.render(flights, rounding).map_err(|e| e.to_string()),
+            None => Ok(job_flights_csv(flights)),
+        },
+        "json" => {
+            let export = serde_json::json!({
+                "stats": job_stats(job, flights),
+                "flights": flights,
+            });
+            serde_json::to_string_pretty(&export).map_err(|e| format!("Failed to serialize job: {}", e))
+        }
+        other => Err(format!("Unsupported export format: {}", other)),
+    }
+}
+
+/// Default CSV for a job's flights: one row per flight
+pub fn job_flights_csv(flights: &[Flight]) -> String {
+    use crate::stats_export::csv_field;
+
+    let mut out = String::from("flight_id,name,start_time,duration_secs,distance_m,max_altitude_m,drone_model,aircraft_name,battery_serial\n");
+    for f in flights {
+        let row = [
+            f.id.to_string(),
+            csv_field(&f.display_name),
+            f.start_time_utc().map(|t| t.to_rfc3339()).unwrap_or_default(),
+            f.duration_secs.map(|v| format!("{:.0}", v)).unwrap_or_default(),
+            f.total_distance.map(|v| format!("{:.1}", v)).unwrap_or_default(),
+            f.max_altitude.map(|v| format!("{:.1}", v)).unwrap_or_default(),
+            csv_field(f.drone_model.as_deref().unwrap_or("")),
+            csv_field(f.aircraft_name.as_deref().unwrap_or("")),
+            csv_field(f.battery_serial.as_deref().unwrap_or("")),
+        ];
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FlightBuilder;
+
+    fn flight(id: i64, battery: &str, duration: f64, start: &str) -> Flight {
+        FlightBuilder::new(id).drone_serial("1ZN").battery_serial(battery).duration(duration).start(start).build()
+    }
+
+    #[test]
+    fn test_job_stats_totals() {
+        let job = Job {
+            id: "j".to_string(),
+            name: "Roof survey".to_string(),
+            client: Some("ACME".to_string()),
+            site: None,
+            start_date: None,
+            end_date: None,
+            notes: None,
+            flight_count: 0,
+        };
+        let flights = vec![
+            flight(2, "BAT-A", 1800.0, "2026-05-02T10:00:00Z"),
+            flight(1, "BAT-A", 1800.0, "2026-05-01T10:00:00Z"),
+            flight(3, "BAT-B", 900.0, "2026-05-03T10:00:00Z"),
+        ];
+        let stats = job_stats(job, &flights);
+        assert_eq!(stats.flight_count, 3);
+        assert!((stats.total_hours - 1.25).abs() < 1e-9);
+        assert_eq!(stats.batteries[0].serial, "BAT-A");
+        assert_eq!(stats.batteries[0].flight_count, 2);
+        assert_eq!(stats.aircraft.len(), 1);
+        assert!(stats.first_flight.unwrap().starts_with("2026-05-01"));
+    }
+}
@@ -0,0 +1,72 @@
+//! Field lookup in JSON telemetry exports (Skydio, FlightHub 2).
+//!
+//! Exports from different app and cloud versions name the same value
+//! differently and may nest groups such as `position` or `battery`, so a
+//! sample is flattened one level and fields are looked up by a list of known
+//! aliases.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+/// An object flattened to lowercase `key → value`; nested groups one level
+/// deep (their fields also as `group_key`)
+pub type Flat<'v> = HashMap<String, &'v Value>;
+
+/// Add the fields of `value` (if it is an object) to `flat`. Top-level keys
+/// win over fields of nested groups with the same name.
+pub fn flatten_into<'v>(flat: &mut Flat<'v>, value: &'v Value) {
+    let Some(obj) = value.as_object() else {
+        return;
+    };
+    for (k, v) in obj {
+        let key = k.to_lowercase();
+        if let Some(inner) = v.as_object() {
+            for (ik, iv) in inner {
+                let ik = ik.to_lowercase();
+                flat.entry(format!("{}_{}", key, ik)).or_insert(iv);
+                flat.entry(ik).or_insert(iv);
+            }
+        }
+        flat.insert(key, v);
+    }
+}
+
+pub fn flatten(value: &Value) -> Flat<'_> {
+    let mut flat = HashMap::new();
+    flatten_into(&mut flat, value);
+    flat
+}
+
+/// First of the aliases holding a finite number (or numeric text)
+pub fn num(flat: &Flat<'_>, aliases: &[&str]) -> Option<f64> {
+    aliases.iter().find_map(|a| {
+        let v = flat.get(*a)?;
+        v.as_f64()
+            .or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))
+            .filter(|x: &f64| x.is_finite())
+    })
+}
+
+/// First of the aliases holding text, if it is not blank
+pub fn text(flat: &Flat<'_>, aliases: &[&str]) -> Option<String> {
+    aliases
+        .iter()
+        .find_map(|a| flat.get(*a).and_then(|v| v.as_str()).map(str::to_string))
+        .filter(|s| !s.trim().is_empty())
+}
+
+/// Parse an absolute time given as RFC 3339 text or as epoch seconds/milliseconds
+pub fn parse_time(v: &Value) -> Option<DateTime<Utc>> {
+    if let Some(s) = v.as_str() {
+        if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+            return Some(dt.with_timezone(&Utc));
+        }
+        return s.trim().parse::<f64>().ok().and_then(|n| parse_time(&Value::from(n)));
+    }
+    let n = v.as_f64()?;
+    // Anything past year 2286 in seconds is really milliseconds
+    let ms = if n > 1e10 { n } else { n * 1000.0 };
+    DateTime::from_timestamp_millis(ms as i64)
+}
@@ -1,12 +1,99 @@
 pub mod airdata_parser;
+pub mod altitude;
 pub mod api;
+pub mod assistant_parser;
+pub mod battery_retirement;
+pub mod blackbox_parser;
+pub mod bundle;
+pub mod chase_export;
+pub mod cloud_push;
+pub mod crash;
+pub mod dat_parser;
+pub mod data_dir;
 pub mod database;
+pub mod db_maintenance;
+pub mod db_mirror;
+pub mod dedupe;
+pub mod demo;
+pub mod derived_metrics;
+pub mod duckdb_extensions;
 pub mod dronelogbook_parser;
+pub mod duration_rounding;
+pub mod endurance;
+pub mod export_templates;
+pub mod filename_rules;
+pub mod fleet;
+pub mod flight_list;
+pub mod flight_phases;
+pub mod flighthub_parser;
+pub mod formats;
+pub mod gaps;
+pub mod gps_filter;
+pub mod hooks;
+pub mod import_profile;
+pub mod import_queue;
+pub mod import_status;
+pub mod integrity;
+pub mod invoice;
+pub mod jobs;
+pub mod json_fields;
 pub mod litchi_parser;
+pub mod locations;
+pub mod log_sources;
+pub mod logbook_export_parser;
+pub mod manual_track;
+pub mod messages;
+pub mod mission_plan;
 pub mod models;
+pub mod notifications;
+pub mod operator_log;
+pub mod overflight;
+pub mod parrot_parser;
 pub mod parser;
 pub mod plugins;
+pub mod preflight;
+pub mod preview;
+pub mod privacy;
 pub mod profile_auth;
+pub mod quirks;
+pub mod recompute;
+pub mod recovery;
+pub mod render;
+pub mod reparse;
+pub mod requirements;
+pub mod rth;
+pub mod safe_mode;
+pub mod share;
+pub mod skydio_parser;
+pub mod smoothness;
+pub mod speed_fallback;
+pub mod sql_console;
+pub mod stats_export;
+pub mod support;
+pub mod telemetry_encoding;
+pub mod units;
+pub mod year_review;
+
+#[cfg(test)]
+mod test_support;
+
+#[cfg(feature = "web")]
+pub mod email_report;
+
+#[cfg(feature = "web")]
+pub mod flight_data_cache;
+
+#[cfg(feature = "web")]
+pub mod graphql;
+
+#[cfg(feature = "web")]
+pub mod login_audit;
+
+#[cfg(feature = "web")]
+pub mod oidc;
+
+#[cfg(feature = "otel")]
+pub mod otel;
 
 #[cfg(feature = "web")]
 pub mod server;
@@ -14,6 +101,15 @@ pub mod server;
 #[cfg(feature = "web")]
 pub mod session_store;
 
+#[cfg(feature = "web")]
+pub mod users;
+
+#[cfg(feature = "web")]
+pub mod web_push;
+
+#[cfg(feature = "web")]
+pub mod web_security;
+
 pub use database::Database;
 pub use models::*;
 pub use parser::LogParser;
@@ -12,7 +12,8 @@ use chrono::{DateTime, NaiveDateTime, Utc};
 
 use crate::database::Database;
 use crate::models::{FlightMetadata, FlightStats, TelemetryPoint};
-use crate::parser::{ParseResult, ParserError, LogParser};
+use crate::formats::{FlightLogFormat, ParseFuture};
+use crate::parser::{ParseInfo, ParseResult, ParserError, LogParser};
 
 /// Unit type detected from column headers
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -120,6 +121,23 @@ impl ColumnMap {
     }
 }
 
+/// Litchi flight log CSV exports
+pub struct LitchiFormat;
+
+impl FlightLogFormat for LitchiFormat {
+    fn name(&self) -> &'static str {
+        "Litchi CSV"
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        LitchiParser::is_litchi_csv(path)
+    }
+
+    fn parse<'a>(&'a self, ctx: &'a LogParser<'_>, path: &'a Path, file_hash: &'a str) -> ParseFuture<'a> {
+        Box::pin(async move { LitchiParser::new(ctx.db()).parse(path, file_hash) })
+    }
+}
+
 /// Litchi CSV Parser
 pub struct LitchiParser<'a> {
     db: &'a Database,
@@ -257,7 +275,7 @@ impl<'a> LitchiParser<'a> {
         tags.insert(0, "Litchi".to_string()); // Add Litchi tag at the beginning
         log::info!("Generated smart tags: {:?}", tags);
 
-        Ok(ParseResult { metadata, points, tags, manual_tags: Vec::new(), notes: None, color: None, messages: Vec::new() })
+        Ok(ParseResult { metadata, points, tags, manual_tags: Vec::new(), notes: None, color: None, messages: Vec::new(), info: ParseInfo::new("Litchi CSV") })
     }
 
     /// Parse a single CSV row into a TelemetryPoint
@@ -353,6 +371,7 @@ impl<'a> LitchiParser<'a> {
             // Camera state
             is_photo: col_map.get_bool(row, "istakingphoto"),
             is_video: col_map.get_bool(row, "isTakingVideo"),
+            is_flying: col_map.get_bool(row, "isflying"),
 
             // Battery capacity
             battery_full_capacity,
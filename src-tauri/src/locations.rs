@@ -0,0 +1,516 @@
+//! Location naming overrides.
+//!
+//! Location tags come from offline reverse geocoding of the home point
+//! (country and continent), which is coarse and can be wrong near borders.
+//! Two per-profile overrides refine it:
+//!
+//! - renames map a detected tag to another name (`"UAE"` → `"United Arab
+//!   Emirates"`); an empty name drops the tag
+//! - named sites are circles (center + radius) or polygons (`[lon, lat]`
+//!   vertices) with an optional parent site. A flight whose home point lies
+//!   in a site gets that site and its parents as auto tags, and the path
+//!   (`"Home Farm / North Field"`) as its location name unless the location
+//!   was edited by hand. The smallest matching site wins, so a site drawn
+//!   inside another one takes precedence.
+//!
+//! The overrides are stored as JSON in the `settings` table (so they are
+//! part of backups) and applied wherever auto tags are generated: the
+//! `locations` post-import hook and smart tag regeneration. Existing flights
+//! pick up changes when they are reprocessed.
+//!
+//! [`places`] aggregates flights per take-off place for the "places I fly"
+//! view: the innermost named site containing the home point, else a cluster
+//! of home points within `CLUSTER_RADIUS_M` of each other.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::database::{Database, DatabaseError};
+use crate::models::Flight;
+use crate::overflight::contains;
+use crate::parser::{haversine_distance, LogParser};
+
+pub(crate) const SETTING_KEY: &str = "location_overrides";
+/// Separator between the levels of a site path
+const PATH_SEPARATOR: &str = " / ";
+/// Meters per degree of latitude, for comparing polygon sizes
+const METERS_PER_DEGREE: f64 = 111_320.0;
+/// Home points closer than this to a cluster's center join the cluster
+const CLUSTER_RADIUS_M: f64 = 300.0;
+
+#[derive(Debug, thiserror::Error)]
+pub enum LocationError {
+    #[error("Site name is required")]
+    EmptyName,
+
+    #[error("Duplicate site id '{0}'")]
+    DuplicateId(String),
+
+    #[error("Site '{0}' has an unknown parent '{1}'")]
+    UnknownParent(String, String),
+
+    #[error("Site '{0}' is nested inside itself")]
+    ParentCycle(String),
+
+    #[error("Site '{0}' needs a radius above 0 m")]
+    InvalidRadius(String),
+
+    #[error("Site '{0}' needs at least 3 points")]
+    TooFewPoints(String),
+
+    #[error("Invalid coordinates in site '{0}'")]
+    InvalidPoint(String),
+
+    #[error("Rename of an empty location tag")]
+    EmptyRename,
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+}
+
+/// Area of a named site
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", rename_all_fields = "camelCase")]
+pub enum SiteShape {
+    Circle { lat: f64, lon: f64, radius_m: f64 },
+    /// `[lon, lat]` vertices
+    Polygon { points: Vec<[f64; 2]> },
+}
+
+impl SiteShape {
+    fn contains(&self, lat: f64, lon: f64) -> bool {
+        match self {
+            SiteShape::Circle { lat: c_lat, lon: c_lon, radius_m } => {
+                haversine_distance(*c_lat, *c_lon, lat, lon) <= *radius_m
+            }
+            SiteShape::Polygon { points } => contains(points, lon, lat),
+        }
+    }
+
+    /// Circle center or mean of the polygon vertices, as `(lat, lon)`
+    fn center(&self) -> (f64, f64) {
+        match self {
+            SiteShape::Circle { lat, lon, .. } => (*lat, *lon),
+            SiteShape::Polygon { points } => {
+                let n = points.len().max(1) as f64;
+                (points.iter().map(|p| p[1]).sum::<f64>() / n, points.iter().map(|p| p[0]).sum::<f64>() / n)
+            }
+        }
+    }
+
+    /// Approximate area in square meters, used to pick the innermost site
+    fn area_m2(&self) -> f64 {
+        match self {
+            SiteShape::Circle { radius_m, .. } => std::f64::consts::PI * radius_m * radius_m,
+            SiteShape::Polygon { points } => {
+                let mean_lat = points.iter().map(|p| p[1]).sum::<f64>() / points.len().max(1) as f64;
+                let mut twice_area = 0.0;
+                for (i, [x1, y1]) in points.iter().enumerate() {
+                    let [x2, y2] = points[(i + 1) % points.len()];
+                    twice_area += x1 * y2 - x2 * y1;
+                }
+                (twice_area / 2.0).abs() * METERS_PER_DEGREE * METERS_PER_DEGREE * mean_lat.to_radians().cos()
+            }
+        }
+    }
+}
+
+/// A user-defined place
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedSite {
+    /// Stable identifier for parent references (defaults to the name)
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// Id of the enclosing site
+    #[serde(default)]
+    pub parent: Option<String>,
+    pub shape: SiteShape,
+}
+
+/// Renames and named sites of a profile
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LocationOverrides {
+    /// Detected location tag → replacement (empty drops the tag)
+    pub renames: BTreeMap<String, String>,
+    pub sites: Vec<NamedSite>,
+}
+
+/// Auto tags and location name after applying the overrides
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedLocation {
+    pub tags: Vec<String>,
+    /// Path of the innermost matching site
+    pub location_name: Option<String>,
+}
+
+impl LocationOverrides {
+    /// Trim names, default ids to names and check shapes and parents
+    fn normalized(mut self) -> Result<Self, LocationError> {
+        let mut renames = BTreeMap::new();
+        for (from, to) in self.renames {
+            let from = from.trim().to_string();
+            if from.is_empty() {
+                return Err(LocationError::EmptyRename);
+            }
+            renames.insert(from, to.trim().to_string());
+        }
+        self.renames = renames;
+
+        let mut ids = HashSet::new();
+        for site in &mut self.sites {
+            site.name = site.name.trim().to_string();
+            if site.name.is_empty() {
+                return Err(LocationError::EmptyName);
+            }
+            site.id = Some(site.id.trim()).filter(|id| !id.is_empty()).unwrap_or(&site.name).to_string();
+            site.parent = site.parent.as_deref().map(str::trim).filter(|p| !p.is_empty()).map(str::to_string);
+            if !ids.insert(site.id.clone()) {
+                return Err(LocationError::DuplicateId(site.id.clone()));
+            }
+            match &site.shape {
+                SiteShape::Circle { lat, lon, radius_m } => {
+                    if !(-90.0..=90.0).contains(lat) || !(-180.0..=180.0).contains(lon) {
+                        return Err(LocationError::InvalidPoint(site.name.clone()));
+                    }
+                    if !radius_m.is_finite() || *radius_m <= 0.0 {
+                        return Err(LocationError::InvalidRadius(site.name.clone()));
+                    }
+                }
+                SiteShape::Polygon { points } => {
+                    if points.len() < 3 {
+                        return Err(LocationError::TooFewPoints(site.name.clone()));
+                    }
+                    if points.iter().any(|[lon, lat]| !(-180.0..=180.0).contains(lon) || !(-90.0..=90.0).contains(lat)) {
+                        return Err(LocationError::InvalidPoint(site.name.clone()));
+                    }
+                }
+            }
+        }
+        for site in &self.sites {
+            if let Some(parent) = &site.parent {
+                if !ids.contains(parent) {
+                    return Err(LocationError::UnknownParent(site.name.clone(), parent.clone()));
+                }
+            }
+            if self.path_of(site).is_none() {
+                return Err(LocationError::ParentCycle(site.name.clone()));
+            }
+        }
+        Ok(self)
+    }
+
+    /// Site names from the outermost parent down to `site` (None on a cycle)
+    fn path_of<'a>(&'a self, site: &'a NamedSite) -> Option<Vec<&'a str>> {
+        let mut path = vec![site.name.as_str()];
+        let mut seen = HashSet::from([site.id.as_str()]);
+        let mut current = site;
+        while let Some(parent) = current.parent.as_deref() {
+            let Some(next) = self.sites.iter().find(|s| s.id == parent) else { break };
+            if !seen.insert(next.id.as_str()) {
+                return None;
+            }
+            path.push(next.name.as_str());
+            current = next;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Innermost site containing the point
+    pub fn site_at(&self, lat: f64, lon: f64) -> Option<&NamedSite> {
+        self.sites
+            .iter()
+            .filter(|s| s.shape.contains(lat, lon))
+            .min_by(|a, b| a.shape.area_m2().total_cmp(&b.shape.area_m2()))
+    }
+
+    /// Apply the overrides to a flight's auto tags, `geocoded` being the
+    /// reverse geocoding tags of its home point (`[lon, lat]`)
+    pub fn apply_with(&self, tags: Vec<String>, geocoded: &[String], home: Option<[f64; 2]>) -> ResolvedLocation {
+        // Site tags from an earlier run are re-derived below
+        let site_names: HashSet<&str> = self.sites.iter().map(|s| s.name.as_str()).collect();
+        let mut resolved: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            if site_names.contains(tag.as_str()) {
+                continue;
+            }
+            let tag = match self.renames.get(&tag) {
+                Some(renamed) if geocoded.contains(&tag) => renamed.clone(),
+                _ => tag,
+            };
+            if !tag.is_empty() && !resolved.contains(&tag) {
+                resolved.push(tag);
+            }
+        }
+
+        let path = home
+            .and_then(|[lon, lat]| self.site_at(lat, lon))
+            .and_then(|site| self.path_of(site));
+        if let Some(path) = &path {
+            for name in path {
+                if !resolved.iter().any(|t| t == name) {
+                    resolved.push(name.to_string());
+                }
+            }
+        }
+        ResolvedLocation { tags: resolved, location_name: path.map(|p| p.join(PATH_SEPARATOR)) }
+    }
+
+    /// Apply the overrides to a flight's auto tags, reverse geocoding the
+    /// home point (`[lon, lat]`) when there are renames
+    pub fn apply(&self, tags: Vec<String>, home: Option<[f64; 2]>) -> ResolvedLocation {
+        let geocoded = match home {
+            Some([lon, lat]) if !self.renames.is_empty() => LogParser::reverse_geocode(lat, lon),
+            _ => Vec::new(),
+        };
+        self.apply_with(tags, &geocoded, home)
+    }
+}
+
+/// Stored overrides of the profile (empty when none are set or they can't be read)
+pub fn load(db: &Database) -> Result<LocationOverrides, DatabaseError> {
+    Ok(db
+        .get_setting(SETTING_KEY)?
+        .and_then(|json| {
+            serde_json::from_str(&json)
+                .map_err(|e| log::warn!("Ignoring invalid location overrides: {}", e))
+                .ok()
+        })
+        .unwrap_or_default())
+}
+
+/// Validate and store new overrides, returning them as stored
+pub fn save(db: &Database, overrides: LocationOverrides) -> Result<LocationOverrides, LocationError> {
+    let overrides = overrides.normalized()?;
+    db.set_setting(SETTING_KEY, &serde_json::to_string(&overrides).unwrap())?;
+    Ok(overrides)
+}
+
+/// Apply the overrides to freshly generated auto tags of `flight` and update
+/// its location name; returns the tags to store
+pub fn apply_to_flight(
+    db: &Database,
+    overrides: &LocationOverrides,
+    flight: &Flight,
+    tags: Vec<String>,
+    home: Option<[f64; 2]>,
+) -> Result<Vec<String>, DatabaseError> {
+    let resolved = overrides.apply(tags, home);
+    if flight.location_name != resolved.location_name {
+        db.set_detected_location_name(flight.id, resolved.location_name.as_deref())?;
+    }
+    Ok(resolved.tags)
+}
+
+/// Flights from one take-off place
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaceStats {
+    /// Site id, or `cluster:<lat>,<lon>` for home points outside all sites
+    pub key: String,
+    /// Site path; for clusters the most common location name of their flights
+    pub name: Option<String>,
+    pub is_site: bool,
+    /// Site center or mean home point of the cluster
+    pub lat: f64,
+    pub lon: f64,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+    pub last_flight: Option<String>,
+    /// For filtering the flight list, newest first
+    pub flight_ids: Vec<i64>,
+}
+
+/// Home point and totals of one flight, as needed by [`aggregate_places`]
+#[derive(Debug, Clone)]
+pub struct PlaceFlight {
+    pub id: i64,
+    pub lat: f64,
+    pub lon: f64,
+    pub duration_secs: f64,
+    pub start_time: Option<String>,
+    pub location_name: Option<String>,
+}
+
+/// Group flights (ordered by start time) by named site or home point cluster,
+/// most flown first
+pub fn aggregate_places(overrides: &LocationOverrides, flights: &[PlaceFlight]) -> Vec<PlaceStats> {
+    let mut sites: BTreeMap<&str, Vec<&PlaceFlight>> = BTreeMap::new();
+    // (center lat, center lon, flights)
+    let mut clusters: Vec<(f64, f64, Vec<&PlaceFlight>)> = Vec::new();
+    for flight in flights {
+        if let Some(site) = overrides.site_at(flight.lat, flight.lon) {
+            sites.entry(site.id.as_str()).or_default().push(flight);
+            continue;
+        }
+        let near = clusters
+            .iter_mut()
+            .find(|(lat, lon, _)| haversine_distance(*lat, *lon, flight.lat, flight.lon) <= CLUSTER_RADIUS_M);
+        match near {
+            Some((lat, lon, members)) => {
+                members.push(flight);
+                let n = members.len() as f64;
+                *lat += (flight.lat - *lat) / n;
+                *lon += (flight.lon - *lon) / n;
+            }
+            None => clusters.push((flight.lat, flight.lon, vec![flight])),
+        }
+    }
+
+    let stats = |key: String, name: Option<String>, is_site: bool, (lat, lon): (f64, f64), members: &[&PlaceFlight]| {
+        let mut newest_first: Vec<&&PlaceFlight> = members.iter().collect();
+        newest_first.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+        PlaceStats {
+            key,
+            name,
+            is_site,
+            lat,
+            lon,
+            flight_count: members.len() as i64,
+            total_duration_secs: members.iter().map(|f| f.duration_secs).sum(),
+            last_flight: newest_first.first().and_then(|f| f.start_time.clone()),
+            flight_ids: newest_first.iter().map(|f| f.id).collect(),
+        }
+    };
+
+    let mut places = Vec::with_capacity(sites.len() + clusters.len());
+    for site in &overrides.sites {
+        if let Some(members) = sites.get(site.id.as_str()) {
+            let name = overrides.path_of(site).map(|p| p.join(PATH_SEPARATOR)).unwrap_or_else(|| site.name.clone());
+            places.push(stats(site.id.clone(), Some(name), true, site.shape.center(), members));
+        }
+    }
+    for (lat, lon, members) in &clusters {
+        let mut names: BTreeMap<&str, usize> = BTreeMap::new();
+        for name in members.iter().filter_map(|f| f.location_name.as_deref()).filter(|n| !n.trim().is_empty()) {
+            *names.entry(name).or_default() += 1;
+        }
+        let name = names.into_iter().max_by_key(|(_, count)| *count).map(|(name, _)| name.to_string());
+        let key = format!("cluster:{:.4},{:.4}", lat, lon);
+        places.push(stats(key, name, false, (*lat, *lon), members));
+    }
+    places.sort_by(|a, b| b.total_duration_secs.total_cmp(&a.total_duration_secs).then(b.flight_count.cmp(&a.flight_count)));
+    places
+}
+
+/// Hours, flights and last visit per take-off place (flights without a home
+/// point are left out)
+pub fn places(db: &Database) -> Result<Vec<PlaceStats>, DatabaseError> {
+    let overrides = load(db)?;
+    let mut flights: Vec<PlaceFlight> = db
+        .get_all_flights()?
+        .into_iter()
+        .filter_map(|f| {
+            Some(PlaceFlight {
+                id: f.id,
+                lat: f.home_lat.filter(|lat| lat.abs() > 0.001)?,
+                lon: f.home_lon?,
+                duration_secs: f.duration_secs.unwrap_or(0.0),
+                start_time: f.start_time,
+                location_name: f.location_name,
+            })
+        })
+        .collect();
+    flights.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+    Ok(aggregate_places(&overrides, &flights))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sites_and_renames() {
+        let farm = NamedSite {
+            id: "farm".to_string(),
+            name: "Home Farm".to_string(),
+            parent: None,
+            shape: SiteShape::Polygon { points: vec![[10.0, 50.0], [10.1, 50.0], [10.1, 50.1], [10.0, 50.1]] },
+        };
+        let field = NamedSite {
+            id: String::new(),
+            name: " North Field ".to_string(),
+            parent: Some("farm".to_string()),
+            shape: SiteShape::Circle { lat: 50.08, lon: 10.05, radius_m: 500.0 },
+        };
+        let overrides = LocationOverrides {
+            renames: BTreeMap::from([("Germany".to_string(), "Deutschland".to_string()), ("Europe".to_string(), String::new())]),
+            sites: vec![field, farm],
+        }
+        .normalized()
+        .unwrap();
+        assert_eq!(overrides.sites[0].id, "North Field");
+
+        let geocoded = vec!["Germany".to_string(), "Europe".to_string()];
+        let tags = vec!["Night Flight".to_string(), "Germany".to_string(), "Europe".to_string()];
+
+        // Inside both sites: the smaller one names the flight
+        let resolved = overrides.apply_with(tags.clone(), &geocoded, Some([10.05, 50.08]));
+        assert_eq!(resolved.tags, ["Night Flight", "Deutschland", "Home Farm", "North Field"]);
+        assert_eq!(resolved.location_name.as_deref(), Some("Home Farm / North Field"));
+
+        // Reapplying to already resolved tags is stable
+        assert_eq!(overrides.apply_with(resolved.tags.clone(), &geocoded, Some([10.05, 50.08])), resolved);
+
+        // Outside the field, inside the farm
+        let resolved = overrides.apply_with(tags.clone(), &geocoded, Some([10.01, 50.01]));
+        assert_eq!(resolved.location_name.as_deref(), Some("Home Farm"));
+
+        // No site, no home point
+        let resolved = overrides.apply_with(tags, &[], None);
+        assert_eq!(resolved.tags, ["Night Flight", "Germany", "Europe"]);
+        assert_eq!(resolved.location_name, None);
+
+        let mut cyclic = overrides.clone();
+        cyclic.sites[1].parent = Some("North Field".to_string());
+        assert!(matches!(cyclic.normalized(), Err(LocationError::ParentCycle(_))));
+    }
+
+    #[test]
+    fn test_aggregate_places() {
+        let overrides = LocationOverrides {
+            sites: vec![NamedSite {
+                id: "field".to_string(),
+                name: "Field".to_string(),
+                parent: None,
+                shape: SiteShape::Circle { lat: 50.0, lon: 10.0, radius_m: 200.0 },
+            }],
+            ..Default::default()
+        };
+        let flight = |id: i64, lat: f64, lon: f64, start: &str, name: Option<&str>| PlaceFlight {
+            id,
+            lat,
+            lon,
+            duration_secs: 600.0,
+            start_time: Some(start.to_string()),
+            location_name: name.map(str::to_string),
+        };
+        let flights = vec![
+            flight(1, 50.0005, 10.0, "2024-05-01T10:00:00Z", None),
+            flight(2, 50.2, 10.2, "2024-05-02T10:00:00Z", Some("Lake")),
+            flight(3, 50.0, 10.0005, "2024-05-03T10:00:00Z", None),
+            // 150 m from flight 2: same cluster
+            flight(4, 50.2013, 10.2, "2024-05-04T10:00:00Z", None),
+            flight(5, 51.0, 11.0, "2024-05-05T10:00:00Z", None),
+        ];
+        let places = aggregate_places(&overrides, &flights);
+        assert_eq!(places.len(), 3);
+
+        assert_eq!(places[0].key, "field");
+        assert!(places[0].is_site);
+        assert_eq!(places[0].flight_ids, [3, 1]);
+        assert_eq!(places[0].last_flight.as_deref(), Some("2024-05-03T10:00:00Z"));
+
+        assert!(!places[1].is_site);
+        assert_eq!(places[1].name.as_deref(), Some("Lake"));
+        assert_eq!(places[1].flight_ids, [4, 2]);
+        assert_eq!(places[1].total_duration_secs, 1200.0);
+
+        assert_eq!(places[2].flight_ids, [5]);
+        assert_eq!(places[2].name, None);
+    }
+}
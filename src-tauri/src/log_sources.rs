@@ -0,0 +1,215 @@
+//! Flight log folders of DJI apps on this computer (desktop).
+//!
+//! Most pilots never look for their logs: DJI Fly and DJI GO 4 keep them in
+//! the app's storage on the phone or controller, DJI Assistant 2 in its own
+//! data folder. `detect` looks in the places these end up on a desktop and
+//! returns every folder holding DJI logs, with the files, so the app can offer
+//! them as one-click import sources:
+//!
+//! - mounted volumes (phone or controller storage in USB mass-storage mode,
+//!   SD cards, copies of a phone's storage): `Android/data/dji.go.v5/files/FlightRecord`
+//!   (DJI Fly, including its `MCDatFlightRecords` subfolder) and
+//!   `DJI/dji.go.v4/FlightRecord` (DJI GO 4). Windows drive letters, `/Volumes`
+//!   on macOS and `/media` / `/run/media` on Linux are checked.
+//! - DJI Fly running natively on Apple Silicon Macs: the app containers in
+//!   `~/Library/Containers` whose metadata names a DJI app.
+//! - DJI Assistant 2: its folders (`DJI Assistant 2*`) in the application
+//!   data and documents folders.
+//!
+//! Phones connected over MTP (the default on Android) have no file system
+//! path and can't be detected; the logs have to be copied off first.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// How deep below a DJI app folder logs are looked for
+const APP_FOLDER_DEPTH: usize = 5;
+/// Files listed per source, newest first
+const MAX_FILES_PER_SOURCE: usize = 5000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceApp {
+    DjiFly,
+    DjiGo4,
+    DjiAssistant,
+}
+
+impl SourceApp {
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Self::DjiFly => "DJI Fly",
+            Self::DjiGo4 => "DJI GO 4",
+            Self::DjiAssistant => "DJI Assistant 2",
+        }
+    }
+}
+
+/// A folder with DJI flight logs
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSource {
+    pub app: SourceApp,
+    pub name: String,
+    pub path: String,
+    pub file_count: usize,
+    pub latest_modified: Option<DateTime<Utc>>,
+    /// Log files in the folder, newest first
+    pub files: Vec<String>,
+}
+
+/// Whether a file name is a DJI flight log: an app log
+/// (`DJIFlightRecord_*.txt`) or an aircraft record (`*.DAT`)
+pub fn is_dji_log(file_name: &str) -> bool {
+    let lower = file_name.to_lowercase();
+    (lower.starts_with("djiflightrecord") && lower.ends_with(".txt")) || lower.ends_with(".dat")
+}
+
+/// Roots of mounted volumes
+fn volumes() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if cfg!(windows) {
+        roots.extend(('C'..='Z').map(|letter| PathBuf::from(format!("{}:\\", letter))));
+    } else if cfg!(target_os = "macos") {
+        roots.extend(subdirs(Path::new("/Volumes")));
+    } else {
+        let user = std::env::var("USER").unwrap_or_default();
+        for base in ["/media", "/run/media"] {
+            let base = Path::new(base).join(&user);
+            roots.extend(subdirs(&base));
+        }
+    }
+    roots
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect())
+        .unwrap_or_default()
+}
+
+/// Folders to search, with how deep to look below them
+fn candidates() -> Vec<(SourceApp, PathBuf, usize)> {
+    let mut candidates = Vec::new();
+    for volume in volumes() {
+        candidates.push((SourceApp::DjiFly, volume.join("Android/data/dji.go.v5/files/FlightRecord"), 1));
+        candidates.push((SourceApp::DjiGo4, volume.join("DJI/dji.go.v4/FlightRecord"), 1));
+    }
+
+    if cfg!(target_os = "macos") {
+        if let Some(home) = dirs::home_dir() {
+            for container in subdirs(&home.join("Library/Containers")) {
+                let metadata = std::fs::read(container.join(".com.apple.containermanagerd.metadata.plist"))
+                    .unwrap_or_default();
+                if metadata.windows(7).any(|w| w == b"com.dji") {
+                    candidates.push((SourceApp::DjiFly, container.join("Data"), APP_FOLDER_DEPTH));
+                }
+            }
+        }
+    }
+
+    let bases = [dirs::data_dir(), dirs::data_local_dir(), dirs::document_dir()];
+    for base in bases.into_iter().flatten() {
+        for dir in subdirs(&base) {
+            let is_assistant = dir
+                .file_name()
+                .map_or(false, |n| n.to_string_lossy().to_lowercase().starts_with("dji assistant"));
+            if is_assistant {
+                candidates.push((SourceApp::DjiAssistant, dir, APP_FOLDER_DEPTH));
+            }
+        }
+    }
+    candidates
+}
+
+/// Every folder at most `depth` levels below `root` (itself included) that
+/// holds DJI logs
+pub fn sources_in(app: SourceApp, root: &Path, depth: usize) -> Vec<LogSource> {
+    let mut sources = Vec::new();
+    let mut pending = vec![(root.to_path_buf(), 0)];
+    while let Some((dir, level)) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut files = Vec::new();
+        for entry in entries.flatten() {
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                if level < depth {
+                    pending.push((entry.path(), level + 1));
+                }
+            } else if is_dji_log(&entry.file_name().to_string_lossy()) {
+                files.push((meta.modified().ok(), entry.path()));
+            }
+        }
+        if files.is_empty() {
+            continue;
+        }
+        files.sort_by(|a, b| b.0.cmp(&a.0));
+        sources.push(LogSource {
+            app,
+            name: app.display_name().to_string(),
+            path: dir.to_string_lossy().to_string(),
+            file_count: files.len(),
+            latest_modified: files[0].0.map(DateTime::<Utc>::from),
+            files: files
+                .into_iter()
+                .take(MAX_FILES_PER_SOURCE)
+                .map(|(_, path)| path.to_string_lossy().to_string())
+                .collect(),
+        });
+    }
+    sources
+}
+
+/// Detect the DJI log folders on this computer, most recently used first
+pub fn detect() -> Vec<LogSource> {
+    let mut sources: Vec<LogSource> = Vec::new();
+    for (app, root, depth) in candidates() {
+        if !root.is_dir() {
+            continue;
+        }
+        for source in sources_in(app, &root, depth) {
+            if !sources.iter().any(|s| s.path == source.path) {
+                sources.push(source);
+            }
+        }
+    }
+    sources.sort_by(|a, b| b.latest_modified.cmp(&a.latest_modified));
+    log::info!("Detected {} DJI log folders", sources.len());
+    sources
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sources_in_app_folder() {
+        assert!(is_dji_log("DJIFlightRecord_2024-05-01_[12-30-45].txt"));
+        assert!(is_dji_log("FLY042.DAT"));
+        assert!(!is_dji_log("readme.txt"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let records = dir.path().join("FlightRecord");
+        let dat = records.join("MCDatFlightRecords");
+        std::fs::create_dir_all(&dat).unwrap();
+        std::fs::write(records.join("DJIFlightRecord_2024-05-01_[12-30-45].txt"), b"x").unwrap();
+        std::fs::write(records.join("notes.txt"), b"x").unwrap();
+        std::fs::write(dat.join("DJI_20240501_123045.DAT"), b"x").unwrap();
+
+        let mut sources = sources_in(SourceApp::DjiFly, &records, 1);
+        sources.sort_by_key(|s| s.path.len());
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].file_count, 1);
+        assert!(sources[0].files[0].ends_with(".txt"));
+        assert!(sources[1].path.ends_with("MCDatFlightRecords"));
+
+        // Not deep enough for the DAT folder
+        assert_eq!(sources_in(SourceApp::DjiFly, &records, 0).len(), 1);
+    }
+}
@@ -0,0 +1,487 @@
+//! Parser module for the app's own JSON and GPX flight exports.
+//!
+//! Together with the CSV export (see `dronelogbook_parser`), these let a
+//! logbook be rebuilt from exported files when the database backup is lost.
+//! The JSON export carries the full flight record, every telemetry series and
+//! the app messages; the GPX export carries the track plus the same flight
+//! record and messages in a `<metadata><extensions>` element. Display name,
+//! serials, notes, color and tags are restored as they were exported, and
+//! values exported in imperial units are converted back to metric.
+
+use std::io::Read;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::database::Database;
+use crate::dronelogbook_parser::parse_timestamp_flexible;
+use crate::formats::{has_extension, FlightLogFormat, ParseFuture};
+use crate::models::{FlightMessage, FlightMetadata, FlightTag, TelemetryPoint};
+use crate::parser::{LogParser, ParseInfo, ParseResult, ParserError};
+
+/// Bytes read from the start of a file when sniffing for an export marker
+const DETECT_BYTES: u64 = 4096;
+/// `_exportInfo.format` of the JSON export
+const JSON_EXPORT_MARKER: &str = "Drone Logbook JSON Export";
+/// `creator` attributes of the GPX export and of the flight bundle's track
+const GPX_EXPORT_MARKERS: [&str; 2] = ["creator=\"Drone Logbook\"", "creator=\"Open DroneLog\""];
+/// GPX extension element holding the exported flight record
+const GPX_FLIGHT_ELEMENT: &str = "dl:flight";
+
+const FT_PER_M: f64 = 3.28084;
+const MPH_PER_MS: f64 = 2.236936;
+
+fn head_contains(path: &Path, markers: &[&str]) -> bool {
+    let Ok(file) = std::fs::File::open(path) else {
+        return false;
+    };
+    let mut head = String::new();
+    let _ = file.take(DETECT_BYTES).read_to_string(&mut head);
+    markers.iter().any(|m| head.contains(m))
+}
+
+/// JSON flight exports of this app
+pub struct LogbookJsonFormat;
+
+impl FlightLogFormat for LogbookJsonFormat {
+    fn name(&self) -> &'static str {
+        "Open DroneLog JSON"
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        has_extension(path, "json") && head_contains(path, &[JSON_EXPORT_MARKER])
+    }
+
+    fn parse<'a>(&'a self, ctx: &'a LogParser<'_>, path: &'a Path, file_hash: &'a str) -> ParseFuture<'a> {
+        Box::pin(async move { LogbookExportParser::new(ctx.db()).parse_json(path, file_hash) })
+    }
+}
+
+/// GPX flight exports of this app
+pub struct LogbookGpxFormat;
+
+impl FlightLogFormat for LogbookGpxFormat {
+    fn name(&self) -> &'static str {
+        "Open DroneLog GPX"
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        has_extension(path, "gpx") && head_contains(path, &GPX_EXPORT_MARKERS)
+    }
+
+    fn parse<'a>(&'a self, ctx: &'a LogParser<'_>, path: &'a Path, file_hash: &'a str) -> ParseFuture<'a> {
+        Box::pin(async move { LogbookExportParser::new(ctx.db()).parse_gpx(path, file_hash) })
+    }
+}
+
+/// Flight record as written by the exports (the app's `Flight`, camelCase)
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct ExportedFlight {
+    display_name: Option<String>,
+    drone_model: Option<String>,
+    drone_serial: Option<String>,
+    aircraft_name: Option<String>,
+    battery_serial: Option<String>,
+    cycle_count: Option<i32>,
+    rc_serial: Option<String>,
+    battery_life: Option<i32>,
+    start_time: Option<String>,
+    duration_secs: Option<f64>,
+    home_lat: Option<f64>,
+    home_lon: Option<f64>,
+    notes: Option<String>,
+    color: Option<String>,
+    tags: Option<Vec<FlightTag>>,
+}
+
+/// Units the JSON export converted its values to
+#[derive(Debug, Default, Clone, Copy)]
+struct ExportUnits {
+    altitude_imperial: bool,
+    speed_imperial: bool,
+    temperature_imperial: bool,
+}
+
+impl ExportUnits {
+    fn from_export_info(info: Option<&Value>) -> Self {
+        let imperial = |key: &str| {
+            info.and_then(|i| i.get("units"))
+                .and_then(|u| u.get(key))
+                .and_then(Value::as_str)
+                == Some("imperial")
+        };
+        Self {
+            altitude_imperial: imperial("altitude"),
+            speed_imperial: imperial("speed"),
+            temperature_imperial: imperial("temperature"),
+        }
+    }
+}
+
+/// Column-wise access to the exported `telemetry` object
+struct Series<'v> {
+    telemetry: &'v Value,
+}
+
+impl<'v> Series<'v> {
+    fn value(&self, field: &str, i: usize) -> Option<&'v Value> {
+        self.telemetry.get(field)?.as_array()?.get(i).filter(|v| !v.is_null())
+    }
+
+    fn f64(&self, field: &str, i: usize) -> Option<f64> {
+        self.value(field, i)?.as_f64().filter(|v| v.is_finite())
+    }
+
+    fn scaled(&self, field: &str, i: usize, divisor: Option<f64>) -> Option<f64> {
+        self.f64(field, i).map(|v| divisor.map_or(v, |d| v / d))
+    }
+
+    fn i32(&self, field: &str, i: usize) -> Option<i32> {
+        self.f64(field, i).map(|v| v.round() as i32)
+    }
+
+    fn bool(&self, field: &str, i: usize) -> Option<bool> {
+        self.value(field, i)?.as_bool()
+    }
+
+    fn string(&self, field: &str, i: usize) -> Option<String> {
+        self.value(field, i)?.as_str().map(str::to_string)
+    }
+
+    fn f64_vec(&self, field: &str, i: usize) -> Option<Vec<f64>> {
+        let values = self.value(field, i)?.as_array()?;
+        Some(values.iter().filter_map(Value::as_f64).collect())
+    }
+}
+
+/// Decode the `telemetry` object of a JSON export
+fn decode_json_telemetry(telemetry: &Value, units: ExportUnits) -> Vec<TelemetryPoint> {
+    let series = Series { telemetry };
+    let Some(times) = telemetry.get("time").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    let alt = units.altitude_imperial.then_some(FT_PER_M);
+    let spd = units.speed_imperial.then_some(MPH_PER_MS);
+
+    times
+        .iter()
+        .enumerate()
+        .filter_map(|(i, t)| {
+            let t = t.as_f64()?;
+            let temp = series.f64("batteryTemp", i);
+            Some(TelemetryPoint {
+                timestamp_ms: (t * 1000.0).round() as i64,
+                latitude: series.f64("latitude", i),
+                longitude: series.f64("longitude", i),
+                altitude: series.scaled("altitude", i, alt),
+                height: series.scaled("height", i, alt),
+                vps_height: series.scaled("vpsHeight", i, alt),
+                speed: series.scaled("speed", i, spd),
+                velocity_x: series.scaled("velocityX", i, spd),
+                velocity_y: series.scaled("velocityY", i, spd),
+                velocity_z: series.scaled("velocityZ", i, spd),
+                pitch: series.f64("pitch", i),
+                roll: series.f64("roll", i),
+                yaw: series.f64("yaw", i),
+                gimbal_pitch: series.f64("gimbalPitch", i),
+                gimbal_roll: series.f64("gimbalRoll", i),
+                gimbal_yaw: series.f64("gimbalYaw", i),
+                battery_percent: series.i32("battery", i),
+                battery_voltage: series.f64("batteryVoltage", i),
+                battery_current: series.f64("batteryCurrent", i),
+                battery_temp: if units.temperature_imperial { temp.map(|f| (f - 32.0) * 5.0 / 9.0) } else { temp },
+                battery_full_capacity: series.f64("batteryFullCapacity", i),
+                battery_remained_capacity: series.f64("batteryRemainedCapacity", i),
+                cell_voltages: series.f64_vec("cellVoltages", i),
+                flight_mode: series.string("flightMode", i),
+                satellites: series.i32("satellites", i),
+                rc_signal: series.i32("rcSignal", i),
+                rc_uplink: series.i32("rcUplink", i),
+                rc_downlink: series.i32("rcDownlink", i),
+                rc_aileron: series.f64("rcAileron", i),
+                rc_elevator: series.f64("rcElevator", i),
+                rc_throttle: series.f64("rcThrottle", i),
+                rc_rudder: series.f64("rcRudder", i),
+                is_photo: series.bool("isPhoto", i),
+                is_video: series.bool("isVideo", i),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+fn unescape_xml(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Value of `name="..."` (or single-quoted) in an element's opening tag
+fn xml_attr<'s>(tag: &'s str, name: &str) -> Option<&'s str> {
+    let key = format!("{}=", name);
+    let at = tag
+        .match_indices(&key)
+        .map(|(i, _)| i)
+        .find(|&i| i == 0 || tag[..i].ends_with(char::is_whitespace))?;
+    let value = &tag[at + key.len()..];
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let len = value[1..].find(quote)?;
+    Some(&value[1..1 + len])
+}
+
+/// Text content of the first `<name>...</name>` element in `xml`
+fn xml_text<'s>(xml: &'s str, name: &str) -> Option<&'s str> {
+    let open = format!("<{}", name);
+    let mut start = xml.find(&open)?;
+    start += xml[start..].find('>')? + 1;
+    let len = xml[start..].find(&format!("</{}>", name))?;
+    Some(xml[start..start + len].trim())
+}
+
+/// Positions (`trkpt`, or the single `wpt` of a manual entry) as
+/// `(lat, lon, ele, time)`
+fn decode_gpx_points(xml: &str) -> Vec<(f64, f64, Option<f64>, Option<DateTime<Utc>>)> {
+    let element = if xml.contains("<trkpt") { "<trkpt" } else { "<wpt" };
+    xml.split(element)
+        .skip(1)
+        .filter_map(|chunk| {
+            let tag_end = chunk.find('>')?;
+            let (tag, rest) = chunk.split_at(tag_end);
+            let lat = xml_attr(tag, "lat")?.parse::<f64>().ok()?;
+            let lon = xml_attr(tag, "lon")?.parse::<f64>().ok()?;
+            let close = format!("</{}", &element[1..]);
+            let body = if tag.ends_with('/') { "" } else { &rest[..rest.find(&close).unwrap_or(rest.len())] };
+            let ele = xml_text(body, "ele").and_then(|e| e.parse::<f64>().ok());
+            let time = xml_text(body, "time").and_then(parse_timestamp_flexible);
+            Some((lat, lon, ele, time))
+        })
+        .collect()
+}
+
+/// Telemetry points of a GPX track, timed from `start` (or from the first
+/// timestamped point). Also used to give manual flights a track.
+pub(crate) fn gpx_track_points(xml: &str, start: Option<DateTime<Utc>>) -> Vec<TelemetryPoint> {
+    let gpx_points = decode_gpx_points(xml);
+    let start = start.or_else(|| gpx_points.iter().find_map(|p| p.3));
+    gpx_points
+        .iter()
+        .enumerate()
+        .map(|(i, &(lat, lon, ele, time))| TelemetryPoint {
+            // Untimed points are spaced at the export's 1 s default
+            timestamp_ms: match (start, time) {
+                (Some(start), Some(time)) => (time - start).num_milliseconds().max(0),
+                _ => i as i64 * 1000,
+            },
+            latitude: Some(lat),
+            longitude: Some(lon),
+            altitude: ele,
+            height: ele,
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Parser for the JSON and GPX exports
+pub struct LogbookExportParser<'a> {
+    db: &'a Database,
+}
+
+impl<'a> LogbookExportParser<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Parse a JSON flight export
+    pub fn parse_json(&self, file_path: &Path, file_hash: &str) -> Result<ParseResult, ParserError> {
+        log::info!("Parsing Open DroneLog JSON export: {:?}", file_path);
+        let content = std::fs::read_to_string(file_path)?;
+        let root: Value =
+            serde_json::from_str(&content).map_err(|e| ParserError::Parse(format!("Invalid JSON: {}", e)))?;
+
+        let flight: ExportedFlight = root
+            .get("flight")
+            .and_then(|f| serde_json::from_value(f.clone()).ok())
+            .unwrap_or_default();
+        let messages: Vec<FlightMessage> = root
+            .get("messages")
+            .and_then(|m| serde_json::from_value(m.clone()).ok())
+            .unwrap_or_default();
+        let units = ExportUnits::from_export_info(root.get("_exportInfo"));
+        let points = root
+            .get("telemetry")
+            .map(|t| decode_json_telemetry(t, units))
+            .unwrap_or_default();
+
+        self.build_result(file_path, file_hash, flight, points, messages, "Open DroneLog JSON")
+    }
+
+    /// Parse a GPX flight export
+    pub fn parse_gpx(&self, file_path: &Path, file_hash: &str) -> Result<ParseResult, ParserError> {
+        log::info!("Parsing Open DroneLog GPX export: {:?}", file_path);
+        let xml = std::fs::read_to_string(file_path)?;
+
+        // Exports from older versions have no flight record; the track name
+        // is then the only metadata
+        let record: Value = xml_text(&xml, GPX_FLIGHT_ELEMENT)
+            .and_then(|json| serde_json::from_str(&unescape_xml(json)).ok())
+            .unwrap_or(Value::Null);
+        let mut flight: ExportedFlight = record
+            .get("flight")
+            .and_then(|f| serde_json::from_value(f.clone()).ok())
+            .unwrap_or_default();
+        if flight.display_name.is_none() {
+            flight.display_name = xml_text(&xml, "name").map(unescape_xml).filter(|n| !n.is_empty());
+        }
+        let messages: Vec<FlightMessage> = record
+            .get("messages")
+            .and_then(|m| serde_json::from_value(m.clone()).ok())
+            .unwrap_or_default();
+
+        let start = flight.start_time.as_deref().and_then(parse_timestamp_flexible);
+        let points = gpx_track_points(&xml, start);
+
+        self.build_result(file_path, file_hash, flight, points, messages, "Open DroneLog GPX")
+    }
+
+    fn build_result(
+        &self,
+        file_path: &Path,
+        file_hash: &str,
+        flight: ExportedFlight,
+        points: Vec<TelemetryPoint>,
+        messages: Vec<FlightMessage>,
+        format: &str,
+    ) -> Result<ParseResult, ParserError> {
+        if points.is_empty() {
+            return Err(ParserError::NoTelemetryData);
+        }
+
+        let parser = LogParser::new(self.db);
+        let stats = parser.calculate_stats(&points);
+        let (photo_count, video_count) = crate::models::count_media_events(&points);
+
+        let file_name = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let display_name = flight.display_name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| {
+            file_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or(&file_name)
+                .to_string()
+        });
+        let serial = |s: Option<String>| s.map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty());
+        let start_time = flight.start_time.as_deref().and_then(parse_timestamp_flexible);
+        // A manual entry exports a single point; keep its recorded duration
+        let duration_secs = if points.len() > 1 { Some(stats.duration_secs) } else { flight.duration_secs };
+
+        let metadata = FlightMetadata {
+            id: self.db.generate_flight_id(),
+            file_name,
+            display_name,
+            file_hash: Some(file_hash.to_string()),
+            drone_model: flight.drone_model,
+            drone_serial: serial(flight.drone_serial),
+            aircraft_name: flight.aircraft_name,
+            battery_serial: serial(flight.battery_serial),
+            cycle_count: flight.cycle_count,
+            start_time,
+            end_time: start_time.map(|st| st + chrono::Duration::milliseconds((duration_secs.unwrap_or(0.0) * 1000.0) as i64)),
+            duration_secs,
+            total_distance: Some(stats.total_distance_m),
+            max_altitude: Some(stats.max_altitude_m),
+            max_speed: Some(stats.max_speed_ms),
+            home_lat: flight.home_lat.or(stats.home_location.map(|h| h[1])),
+            home_lon: flight.home_lon.or(stats.home_location.map(|h| h[0])),
+            point_count: points.len() as i32,
+            photo_count,
+            video_count,
+            rc_serial: serial(flight.rc_serial),
+            battery_life: flight.battery_life,
+        };
+
+        // Same tagging as a re-imported CSV: source tag, exported auto tags,
+        // then any fresh smart tags
+        let mut tags = vec!["Re-imported".to_string()];
+        let mut manual_tags = Vec::new();
+        for tag in flight.tags.unwrap_or_default() {
+            if tag.tag_type == "manual" {
+                manual_tags.push(tag.tag);
+            } else if !tags.contains(&tag.tag) {
+                tags.push(tag.tag);
+            }
+        }
+        for tag in LogParser::generate_smart_tags(&metadata, &stats) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        log::info!(
+            "{} parse complete: duration={:.1}s, distance={:.0}m, points={}, messages={}",
+            format,
+            metadata.duration_secs.unwrap_or(0.0),
+            stats.total_distance_m,
+            points.len(),
+            messages.len()
+        );
+
+        Ok(ParseResult {
+            metadata,
+            points,
+            tags,
+            manual_tags,
+            notes: flight.notes.filter(|n| !n.is_empty()),
+            color: flight.color.filter(|c| !c.is_empty()),
+            messages,
+            info: ParseInfo::new(format),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_exports() {
+        let telemetry = serde_json::json!({
+            "time": [0, 0.5],
+            "latitude": [47.1, 47.2],
+            "longitude": [8.1, null],
+            "height": [0, 32.8084],
+            "speed": [null, 22.36936],
+            "isPhoto": [false, true]
+        });
+        let units = ExportUnits { altitude_imperial: true, speed_imperial: true, temperature_imperial: false };
+        let points = decode_json_telemetry(&telemetry, units);
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[1].timestamp_ms, 500);
+        assert_eq!(points[1].longitude, None);
+        assert!((points[1].height.unwrap() - 10.0).abs() < 1e-6);
+        assert!((points[1].speed.unwrap() - 10.0).abs() < 1e-6);
+        assert_eq!(points[1].is_photo, Some(true));
+
+        let gpx = r#"<gpx version="1.1" creator="Drone Logbook"><trk><name>A &amp; B</name><trkseg>
+      <trkpt lat="47.1" lon="8.1">
+        <ele>12.5</ele>
+        <time>2024-05-01T10:00:00.000Z</time>
+      </trkpt>
+      <trkpt lat="47.2" lon="8.2">
+        <time>2024-05-01T10:00:02.000Z</time>
+      </trkpt></trkseg></trk></gpx>"#;
+        let points = decode_gpx_points(gpx);
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].2, Some(12.5));
+        assert_eq!(points[1].2, None);
+        assert_eq!((points[1].3.unwrap() - points[0].3.unwrap()).num_milliseconds(), 2000);
+        assert_eq!(unescape_xml(xml_text(gpx, "name").unwrap()), "A & B");
+    }
+}
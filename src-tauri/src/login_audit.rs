@@ -0,0 +1,246 @@
+//! Audit trail and throttling of password checks (web mode).
+//!
+//! Every sign-in attempt (account login, single sign-on, profile unlock,
+//! profile password changes and the master password) is appended to
+//! `login_audit.jsonl` in the data directory, with the client address and
+//! whether it succeeded, failed or was refused because of a lockout. The
+//! file is rotated to `login_audit.jsonl.1` at 5 MB. Unlike the audit log of
+//! a profile, it is global: failed attempts happen before any profile or
+//! account is known to be valid.
+//!
+//! A `LoginGuard` wraps a password check: it refuses the attempt while the
+//! target or the client is locked out (see `session_store`), counts failures
+//! against both, and records the outcome.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::session_store::{self, SessionStore};
+
+const FILE_NAME: &str = "login_audit.jsonl";
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Serializes appends and rotation
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// What was being authenticated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoginKind {
+    /// `POST /api/users/login`
+    UserLogin,
+    /// Single sign-on callback
+    Sso,
+    /// Opening a password-protected profile
+    ProfileUnlock,
+    /// Changing, removing or deleting with a profile password
+    ProfilePassword,
+    /// `PROFILE_CREATION_PASS`
+    MasterPassword,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoginOutcome {
+    Success,
+    Failure,
+    /// Refused without checking the password
+    LockedOut,
+}
+
+/// One entry of the login audit trail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginEvent {
+    pub time: String,
+    pub kind: LoginKind,
+    /// Username or profile name (empty for the master password)
+    pub subject: String,
+    pub client: Option<String>,
+    pub outcome: LoginOutcome,
+    pub detail: Option<String>,
+}
+
+fn audit_file(data_dir: &Path) -> PathBuf {
+    data_dir.join(FILE_NAME)
+}
+
+fn rotated_file(data_dir: &Path) -> PathBuf {
+    data_dir.join(format!("{}.1", FILE_NAME))
+}
+
+/// Append an event to the trail (failures are logged, never returned)
+pub fn record(data_dir: &Path, event: &LoginEvent) {
+    let _guard = WRITE_LOCK.lock().unwrap();
+    let path = audit_file(data_dir);
+    if std::fs::metadata(&path).map_or(false, |m| m.len() >= MAX_FILE_BYTES) {
+        if let Err(e) = std::fs::rename(&path, rotated_file(data_dir)) {
+            log::warn!("Failed to rotate the login audit trail: {}", e);
+        }
+    }
+    let line = match serde_json::to_string(event) {
+        Ok(line) => line,
+        Err(e) => {
+            log::warn!("Failed to serialize login audit event: {}", e);
+            return;
+        }
+    };
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| writeln!(file, "{}", line));
+    if let Err(e) = result {
+        log::warn!("Failed to write the login audit trail: {}", e);
+    }
+}
+
+/// The latest events, newest first, optionally of one outcome
+pub fn recent(data_dir: &Path, limit: usize, outcome: Option<LoginOutcome>) -> Vec<LoginEvent> {
+    let mut events = Vec::new();
+    for path in [audit_file(data_dir), rotated_file(data_dir)] {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        events.extend(
+            content
+                .lines()
+                .rev()
+                .filter_map(|line| serde_json::from_str::<LoginEvent>(line).ok())
+                .filter(|e| outcome.map_or(true, |o| e.outcome == o)),
+        );
+        if events.len() >= limit {
+            break;
+        }
+    }
+    events.truncate(limit);
+    events
+}
+
+/// A password check of one subject from one client
+pub struct LoginGuard<'a> {
+    sessions: &'a SessionStore,
+    data_dir: &'a Path,
+    kind: LoginKind,
+    subject: String,
+    /// Lockout key of the subject
+    key: String,
+    client: Option<String>,
+}
+
+impl<'a> LoginGuard<'a> {
+    pub fn new(
+        sessions: &'a SessionStore,
+        data_dir: &'a Path,
+        kind: LoginKind,
+        subject: &str,
+        client: Option<String>,
+    ) -> Self {
+        let key = match kind {
+            LoginKind::UserLogin | LoginKind::Sso => session_store::user_lockout_key(subject),
+            LoginKind::ProfileUnlock | LoginKind::ProfilePassword => subject.to_string(),
+            LoginKind::MasterPassword => session_store::MASTER_LOCKOUT_KEY.to_string(),
+        };
+        Self { sessions, data_dir, kind, subject: subject.to_string(), key, client }
+    }
+
+    fn client_key(&self) -> Option<String> {
+        self.client.as_deref().map(session_store::client_lockout_key)
+    }
+
+    fn record(&self, outcome: LoginOutcome, detail: Option<&str>) {
+        record(
+            self.data_dir,
+            &LoginEvent {
+                time: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+                kind: self.kind,
+                subject: self.subject.clone(),
+                client: self.client.clone(),
+                outcome,
+                detail: detail.map(str::to_string),
+            },
+        );
+    }
+
+    /// Refuse (and record) the attempt while the subject or client is locked
+    /// out; the error is the time left
+    pub fn check(&self) -> Result<(), Duration> {
+        let remaining = [Some(self.key.clone()), self.client_key()]
+            .into_iter()
+            .flatten()
+            .filter_map(|key| self.sessions.lockout_remaining(&key))
+            .max();
+        match remaining {
+            Some(remaining) => {
+                self.record(LoginOutcome::LockedOut, None);
+                Err(remaining)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Count and record a failed attempt
+    pub fn failed(&self, detail: &str) {
+        if self.sessions.record_failure(&self.key) {
+            log::warn!("{:?} '{}' locked out after too many failed attempts", self.kind, self.subject);
+        }
+        if let Some(client_key) = self.client_key() {
+            if self.sessions.record_failure(&client_key) {
+                log::warn!("Client {} locked out after too many failed attempts", client_key);
+            }
+        }
+        self.record(LoginOutcome::Failure, Some(detail));
+    }
+
+    /// Record a successful attempt and forget the subject's failures
+    pub fn succeeded(&self) {
+        self.sessions.clear_failures(&self.key);
+        self.record(LoginOutcome::Success, None);
+    }
+}
+
+/// Message for a refused attempt
+pub fn lockout_message(remaining: Duration) -> String {
+    let secs = remaining.as_secs().max(1);
+    if secs < 120 {
+        format!("Too many failed attempts — please wait {} seconds", secs)
+    } else {
+        format!("Too many failed attempts — please wait {} minutes", (secs + 59) / 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guard_locks_out_and_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = SessionStore::new();
+        let client = Some("203.0.113.7".to_string());
+        let guard = LoginGuard::new(&sessions, dir.path(), LoginKind::UserLogin, "alice", client.clone());
+        for _ in 0..5 {
+            assert!(guard.check().is_ok());
+            guard.failed("Invalid username or password");
+        }
+        let remaining = guard.check().unwrap_err();
+        assert!(remaining <= Duration::from_secs(60));
+
+        // Another account from the same client is not locked out yet
+        let other = LoginGuard::new(&sessions, dir.path(), LoginKind::UserLogin, "bob", client);
+        assert!(other.check().is_ok());
+        other.succeeded();
+
+        let events = recent(dir.path(), 10, None);
+        assert_eq!(events.len(), 7);
+        assert_eq!(events[0].outcome, LoginOutcome::Success);
+        assert_eq!(events[1].outcome, LoginOutcome::LockedOut);
+        assert_eq!(recent(dir.path(), 10, Some(LoginOutcome::Failure)).len(), 5);
+    }
+}
@@ -13,14 +13,100 @@
 )]
 
 mod airdata_parser;
+mod altitude;
 mod api;
+mod assistant_parser;
+mod battery_retirement;
+mod blackbox_parser;
+mod bundle;
+mod chase_export;
+mod cloud_push;
+mod crash;
+mod dat_parser;
+mod data_dir;
 mod database;
+mod db_maintenance;
+mod db_mirror;
+mod dedupe;
+mod demo;
+mod derived_metrics;
+mod duckdb_extensions;
 mod dronelogbook_parser;
+mod duration_rounding;
+mod endurance;
+mod export_templates;
+mod filename_rules;
+mod fleet;
+mod flight_list;
+mod flight_phases;
+mod flighthub_parser;
+mod formats;
+mod gaps;
+mod gps_filter;
+mod hooks;
+mod import_profile;
+mod import_queue;
+mod import_status;
+mod integrity;
+mod invoice;
+mod jobs;
+mod json_fields;
 mod litchi_parser;
+mod locations;
+mod logbook_export_parser;
+mod manual_track;
+mod messages;
+mod mission_plan;
 mod models;
+mod notifications;
+mod operator_log;
+mod overflight;
+mod parrot_parser;
 mod parser;
 mod plugins;
+mod preflight;
+mod preview;
+mod privacy;
 mod profile_auth;
+mod quirks;
+mod recompute;
+mod recovery;
+mod render;
+mod reparse;
+mod requirements;
+mod rth;
+mod safe_mode;
+mod share;
+mod skydio_parser;
+mod smoothness;
+mod speed_fallback;
+mod sql_console;
+mod stats_export;
+mod support;
+mod telemetry_encoding;
+mod units;
+mod year_review;
+
+#[cfg(test)]
+mod test_support;
+
+#[cfg(all(feature = "web", not(feature = "tauri-app")))]
+mod email_report;
+
+#[cfg(all(feature = "web", not(feature = "tauri-app")))]
+mod flight_data_cache;
+
+#[cfg(all(feature = "web", not(feature = "tauri-app")))]
+mod graphql;
+
+#[cfg(all(feature = "web", not(feature = "tauri-app")))]
+mod login_audit;
+
+#[cfg(all(feature = "web", not(feature = "tauri-app")))]
+mod oidc;
+
+#[cfg(all(feature = "otel", not(feature = "tauri-app")))]
+mod otel;
 
 #[cfg(all(feature = "web", not(feature = "tauri-app")))]
 mod server;
@@ -28,6 +114,18 @@ mod server;
 #[cfg(all(feature = "web", not(feature = "tauri-app")))]
 mod session_store;
 
+#[cfg(all(feature = "web", not(feature = "tauri-app")))]
+mod users;
+
+#[cfg(all(feature = "web", not(feature = "tauri-app")))]
+mod web_push;
+
+#[cfg(all(feature = "web", not(feature = "tauri-app")))]
+mod web_security;
+
+#[cfg(feature = "tauri-app")]
+mod log_sources;
+
 // ============================================================================
 // TAURI DESKTOP MODE
 // ============================================================================
@@ -42,7 +140,7 @@ mod tauri_app {
     use log::LevelFilter;
 
     use crate::database::{self, Database, DatabaseError};
-    use crate::models::{Flight, FlightDataResponse, FlightTag, ImportResult, OverviewStats, TelemetryData};
+    use crate::models::{ExceedanceLimits, Flight, FlightDataResponse, FlightMetadataUpdate, FlightTag, ImportReport, ImportResult, OverviewStats, TelemetryData};
     use crate::parser::LogParser;
     use crate::api::DjiApi;
     use crate::profile_auth;
@@ -53,6 +151,8 @@ mod tauri_app {
         pub data_dir: PathBuf,
         /// When true, data commands are blocked until the user authenticates.
         locked: RwLock<bool>,
+        /// Safe mode after repeated failed starts: only repair commands run
+        pub safe_mode: crate::safe_mode::SafeModeStatus,
     }
 
     impl AppState {
@@ -64,9 +164,24 @@ mod tauri_app {
         /// Get the database only if the app is not locked.  All data-access commands should use this.
         pub fn db_authenticated(&self) -> Result<Arc<Database>, String> {
             if *self.locked.read().unwrap() {
-                return Err("Profile is locked — please authenticate first".to_string());
+                return Err(crate::messages::text(crate::messages::default_locale(), "auth.profile_locked", &[]));
+            }
+            if self.safe_mode.active {
+                return Err(crate::safe_mode::BLOCKED_MESSAGE.to_string());
+            }
+            let db = self.active_db.read().unwrap().clone();
+            db.touch();
+            Ok(db)
+        }
+
+        /// Like `db_authenticated`, but also available in safe mode (repair commands).
+        pub fn db_repair(&self) -> Result<Arc<Database>, String> {
+            if *self.locked.read().unwrap() {
+                return Err(crate::messages::text(crate::messages::default_locale(), "auth.profile_locked", &[]));
             }
-            Ok(self.active_db.read().unwrap().clone())
+            let db = self.active_db.read().unwrap().clone();
+            db.touch();
+            Ok(db)
         }
 
         /// Swap the active database (used for profile switching).
@@ -91,6 +206,11 @@ mod tauri_app {
             *self.locked.read().unwrap()
         }
 
+        /// Record a data-modifying action in the active profile's audit log.
+        pub fn audit(&self, action: &str, target: Option<&str>, details: serde_json::Value) {
+            self.db().audit("desktop", action, target, details);
+        }
+
         /// Get the config file path for the currently active profile.
         pub fn config_path(&self) -> PathBuf {
             let profile = database::get_active_profile(&self.data_dir);
@@ -204,39 +324,68 @@ mod tauri_app {
         Ok(())
     }
 
-    /// Initialize the database in the app data directory
-    fn init_database(app: &AppHandle) -> Result<Database, String> {
-        let data_dir = app_data_dir_path(app)?;
+    /// Resolve the data directory: the OS app data folder, or the folder it was
+    /// relocated to with `move_data_dir`
+    fn resolve_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+        let default_dir = app_data_dir_path(app)?;
+        let data_dir = crate::data_dir::resolve(&default_dir);
+        if data_dir != default_dir {
+            log::info!("Using relocated data directory: {:?}", data_dir);
+            crate::data_dir::finish_pending_cleanup(&data_dir);
+        }
+        Ok(data_dir)
+    }
+
+    /// Initialize the database in the data directory
+    fn init_database(app: &AppHandle, data_dir: PathBuf, safe_mode: bool) -> Result<Database, String> {
         log::info!("Initializing database in: {:?}", data_dir);
 
-        // Attempt to migrate data from old app identifier
-        if let Err(e) = migrate_old_data(&data_dir) {
-            log::warn!("Migration from old data directory failed: {}", e);
-            // Continue anyway - this is not fatal
+        // Attempt to migrate data from old app identifier (only into the default location)
+        if !safe_mode && data_dir == app_data_dir_path(app)? {
+            if let Err(e) = migrate_old_data(&data_dir) {
+                log::warn!("Migration from old data directory failed: {}", e);
+                // Continue anyway - this is not fatal
+            }
         }
 
         // Read persisted active profile
         let profile = database::get_active_profile(&data_dir);
         log::info!("Active profile: {}", profile);
 
-        Database::new(data_dir, &profile).map_err(|e| format!("Failed to initialize database: {}", e))
+        let db = if safe_mode {
+            Database::open_safe_mode(data_dir, &profile)
+        } else {
+            Database::new(data_dir, &profile)
+        };
+        db.map_err(|e| format!("Failed to initialize database: {}", e))
     }
 
+    /// Import a log file. `on_conflict` settles a match with a flight already
+    /// in the logbook (see `dedupe`); without it the file is skipped and the
+    /// conflict returned.
     #[tauri::command]
-    pub async fn import_log(file_path: String, state: State<'_, AppState>) -> Result<ImportResult, String> {
+    pub async fn import_log(
+        file_path: String,
+        on_conflict: Option<crate::dedupe::ConflictResolution>,
+        state: State<'_, AppState>,
+    ) -> Result<ImportResult, String> {
         let import_start = std::time::Instant::now();
         log::info!("Importing log file: {}", file_path);
 
         let path = PathBuf::from(&file_path);
 
+        let locale = crate::messages::default_locale();
         if !path.exists() {
             log::warn!("File not found: {}", file_path);
             return Ok(ImportResult {
                 success: false,
                 flight_id: None,
-                message: "File not found".to_string(),
+                message: crate::messages::text(locale, "import.file_not_found", &[]),
+                code: Some("import.file_not_found".to_string()),
                 point_count: 0,
                 file_hash: None,
+                report: None,
+                conflict: None,
             });
         }
 
@@ -279,9 +428,12 @@ mod tauri_app {
                 return Ok(ImportResult {
                     success: false,
                     flight_id: None,
-                    message: format!("This flight log has already been imported (matches: {})", matching_flight),
+                    message: crate::messages::text(locale, "import.already_imported", &[("flight", &matching_flight)]),
+                    code: Some("import.already_imported".to_string()),
                     point_count: 0,
                     file_hash,
+                    report: None,
+                    conflict: None,
                 });
             }
             Err(e) => {
@@ -289,32 +441,42 @@ mod tauri_app {
                 return Ok(ImportResult {
                     success: false,
                     flight_id: None,
-                    message: format!("Failed to parse log: {}", e),
+                    message: crate::messages::text(locale, "import.parse_failed", &[("error", &e)]),
+                    code: Some("import.parse_failed".to_string()),
                     point_count: 0,
                     file_hash: None,
+                    report: None,
+                    conflict: None,
                 });
             }
         };
 
-        // Check for duplicate flight based on signature (drone_serial + battery_serial + start_time)
-        if let Some(matching_flight) = db.is_duplicate_flight(
-            parse_result.metadata.drone_serial.as_deref(),
-            parse_result.metadata.battery_serial.as_deref(),
-            parse_result.metadata.start_time,
-        ).unwrap_or(None) {
-            log::info!("Skipping duplicate flight (signature match): {} - matches flight '{}' in database", file_path, matching_flight);
-            // Still copy the file even though flight is a duplicate
-            try_copy_file(parse_result.metadata.file_hash.as_deref());
-            return Ok(ImportResult {
-                success: false,
-                flight_id: None,
-                message: format!("Duplicate flight: matches '{}' (same drone, battery, and start time)", matching_flight),
-                point_count: 0,
-                file_hash: parse_result.metadata.file_hash.clone(),
-            });
-        }
+        // Check for a copy of a flight already in the logbook (same drone, start time and duration)
+        let replaces = match crate::dedupe::check(&db, &parse_result.metadata, on_conflict, locale) {
+            Ok(crate::dedupe::DuplicateCheck::Import { replaces }) => replaces,
+            Ok(crate::dedupe::DuplicateCheck::Skip { message, conflict }) => {
+                log::info!("Skipping duplicate flight: {} - {}", file_path, message);
+                // Still copy the file even though flight is a duplicate
+                try_copy_file(parse_result.metadata.file_hash.as_deref());
+                return Ok(ImportResult {
+                    success: false,
+                    flight_id: None,
+                    message,
+                    code: Some("import.duplicate".to_string()),
+                    point_count: 0,
+                    file_hash: parse_result.metadata.file_hash.clone(),
+                    report: None,
+                    conflict: Some(conflict),
+                });
+            }
+            Err(e) => {
+                log::warn!("Duplicate check failed for {}: {}", file_path, e);
+                None
+            }
+        };
 
         log::debug!("Inserting flight metadata: id={}", parse_result.metadata.id);
+        let mut stages = crate::import_profile::StageTimer::start();
         let flight_id = db
             .insert_flight(&parse_result.metadata)
             .map_err(|e| format!("Failed to insert flight: {}", e))?;
@@ -331,9 +493,12 @@ mod tauri_app {
                 return Ok(ImportResult {
                     success: false,
                     flight_id: None,
-                    message: format!("Failed to insert telemetry data: {}", e),
+                    message: crate::messages::text(locale, "import.insert_failed", &[("error", &e)]),
+                    code: Some("import.insert_failed".to_string()),
                     point_count: 0,
                     file_hash: parse_result.metadata.file_hash.clone(),
+                    report: None,
+                    conflict: None,
                 });
             }
         };
@@ -403,12 +568,35 @@ mod tauri_app {
             }
         }
 
+        // Store the validation report (field coverage, record version, decryption path)
+        let report = parse_result.import_report(flight_id);
+        if let Err(e) = db.save_import_report(&report) {
+            log::warn!("Failed to save import report for flight {}: {}", flight_id, e);
+        }
+        let insert_ms = stages.lap();
+
+        // Run the post-import hook pipeline (anomaly detection, ...)
+        if let Err(e) = crate::hooks::run_pipeline(&db, flight_id, &config, crate::hooks::HookTrigger::Import) {
+            log::warn!("Post-import hooks failed for flight {}: {}", flight_id, e);
+        }
+        let post_process_ms = stages.lap();
+        crate::import_profile::record(&db, flight_id, &parse_result, insert_ms, post_process_ms);
+
+        // Upload to AirData / DroneLogbook.com in the background if enabled
+        crate::cloud_push::push_in_background(db.clone(), &config, flight_id, &path);
+
         // Restore any previously saved user customizations (display_name, notes, color, manual tags)
         if let Some(ref hash) = parse_result.metadata.file_hash {
             if let Err(e) = db.apply_saved_customizations(flight_id, hash) {
                 log::warn!("Failed to restore customizations for flight {}: {}", flight_id, e);
             }
         }
+        db.finish_import(flight_id);
+        if let Some(old_id) = replaces {
+            if let Err(e) = db.delete_flight(old_id) {
+                log::warn!("Failed to delete flight {} replaced by {}: {}", old_id, flight_id, e);
+            }
+        }
 
         log::info!(
             "Successfully imported flight {} with {} points in {:.1}s",
@@ -416,6 +604,12 @@ mod tauri_app {
             point_count,
             import_start.elapsed().as_secs_f64()
         );
+        db.audit(
+            "desktop",
+            "import",
+            Some(&flight_id.to_string()),
+            serde_json::json!({ "file": file_path, "points": point_count, "replaced": replaces }),
+        );
 
         // Copy uploaded file if setting is enabled
         try_copy_file(parse_result.metadata.file_hash.as_deref());
@@ -423,9 +617,12 @@ mod tauri_app {
         Ok(ImportResult {
             success: true,
             flight_id: Some(flight_id),
-            message: format!("Successfully imported {} telemetry points", point_count),
+            message: crate::messages::text(locale, "import.success", &[("points", &point_count)]),
+            code: Some("import.success".to_string()),
             point_count,
             file_hash: parse_result.metadata.file_hash.clone(),
+            report: Some(report),
+            conflict: None,
         })
     }
 
@@ -435,8 +632,8 @@ mod tauri_app {
     pub async fn create_manual_flight(
         flight_title: Option<String>,
         aircraft_name: String,
-        drone_serial: String,
-        battery_serial: String,
+        drone_serial: Option<String>,
+        battery_serial: Option<String>,
         start_time: String, // ISO 8601 format
         duration_secs: f64,
         total_distance: Option<f64>,
@@ -450,6 +647,16 @@ mod tauri_app {
         
         log::info!("Creating manual flight entry: {} @ {}", aircraft_name, start_time);
 
+        if aircraft_name.trim().is_empty() {
+            return Err("Aircraft name is required".to_string());
+        }
+        if !duration_secs.is_finite() || duration_secs <= 0.0 {
+            return Err("Duration must be greater than zero".to_string());
+        }
+        if !(-90.0..=90.0).contains(&home_lat) || !(-180.0..=180.0).contains(&home_lon) {
+            return Err("Invalid takeoff location".to_string());
+        }
+
         // Parse the start time
         let parsed_start_time = DateTime::parse_from_rfc3339(&start_time)
             .map(|dt| dt.with_timezone(&chrono::Utc))
@@ -473,9 +680,9 @@ mod tauri_app {
             display_name,
             file_hash: None, // Manual entries have no file hash
             drone_model: Some(format!("Manual Entry ({})", aircraft_name)),
-            drone_serial: Some(drone_serial.trim().to_uppercase()),
+            drone_serial: drone_serial.map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty()),
             aircraft_name: Some(aircraft_name),
-            battery_serial: Some(battery_serial.trim().to_uppercase()),
+            battery_serial: battery_serial.map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty()),
             cycle_count: None,
             rc_serial: None,
             battery_life: None,
@@ -495,7 +702,7 @@ mod tauri_app {
         // Insert flight
         state
             .db_authenticated()?
-            .insert_flight(&metadata)
+            .insert_manual_flight(&metadata)
             .map_err(|e| format!("Failed to insert flight: {}", e))?;
 
         // Update notes if provided
@@ -536,14 +743,58 @@ mod tauri_app {
             }
         }
 
+        state.db_authenticated()?.finish_import(flight_id);
         log::info!("Successfully created manual flight entry with ID: {}", flight_id);
+        state.audit(
+            "import",
+            Some(&flight_id.to_string()),
+            serde_json::json!({ "manual": true, "file": metadata.file_name }),
+        );
 
         Ok(ImportResult {
             success: true,
             flight_id: Some(flight_id),
-            message: "Manual flight entry created successfully".to_string(),
+            message: crate::messages::text(crate::messages::default_locale(), "import.manual_created", &[]),
+            code: Some("import.manual_created".to_string()),
             point_count: 0,
             file_hash: None,
+            report: None,
+            conflict: None,
+        })
+    }
+
+    /// Fuse a high-rate aircraft DAT log into an imported flight's telemetry.
+    /// If `flight_id` is omitted, the flight is matched by the DAT recording time.
+    #[tauri::command]
+    pub async fn fuse_dat_log(
+        file_path: String,
+        flight_id: Option<i64>,
+        state: State<'_, AppState>,
+    ) -> Result<ImportResult, String> {
+        let path = PathBuf::from(&file_path);
+        if !path.exists() {
+            return Err("File not found".to_string());
+        }
+
+        let db = state.db_authenticated()?;
+        let (flight_id, point_count) = crate::dat_parser::DatParser::fuse_into_flight(&db, &path, flight_id)
+            .map_err(|e| format!("Failed to fuse DAT log: {}", e))?;
+        db.audit(
+            "desktop",
+            "fuse_dat",
+            Some(&flight_id.to_string()),
+            serde_json::json!({ "file": file_path, "points": point_count }),
+        );
+
+        Ok(ImportResult {
+            success: true,
+            flight_id: Some(flight_id),
+            message: crate::messages::text(crate::messages::default_locale(), "import.dat_fused", &[("points", &point_count)]),
+            code: Some("import.dat_fused".to_string()),
+            point_count,
+            file_hash: LogParser::calculate_file_hash(&path).ok(),
+            report: None,
+            conflict: None,
         })
     }
 
@@ -564,6 +815,15 @@ mod tauri_app {
         Ok(crate::plugins::get_allowed_extensions(&state.data_dir))
     }
 
+    /// DJI app folders with flight logs on this computer (mounted phone or
+    /// controller storage, DJI Fly on Mac, DJI Assistant 2), for one-click import
+    #[tauri::command]
+    pub async fn detect_log_sources() -> Result<Vec<crate::log_sources::LogSource>, String> {
+        tauri::async_runtime::spawn_blocking(crate::log_sources::detect)
+            .await
+            .map_err(|e| format!("Failed to detect log folders: {}", e))
+    }
+
     #[tauri::command]
     pub async fn get_sync_blacklist(state: State<'_, AppState>) -> Result<Vec<String>, String> {
         state
@@ -600,77 +860,1440 @@ mod tauri_app {
     }
 
     #[tauri::command]
-    pub async fn get_flights(state: State<'_, AppState>) -> Result<Vec<Flight>, String> {
+    pub async fn get_flights(
+        exceeded_only: Option<bool>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<Flight>, String> {
         let start = std::time::Instant::now();
-        let flights = state
+        let mut flights = state
             .db_authenticated()?
             .get_all_flights()
             .map_err(|e| format!("Failed to get flights: {}", e))?;
+        if exceeded_only.unwrap_or(false) {
+            flights.retain(|f| !f.exceedances.is_empty());
+        }
         log::debug!("get_flights returned {} flights in {:.1}ms", flights.len(), start.elapsed().as_secs_f64() * 1000.0);
         Ok(flights)
     }
 
+    /// Flights imported, changed or deleted after `modified_since` (RFC 3339)
+    #[tauri::command]
+    pub async fn get_flight_changes(
+        modified_since: String,
+        state: State<'_, AppState>,
+    ) -> Result<crate::models::FlightChanges, String> {
+        let since = chrono::DateTime::parse_from_rfc3339(modified_since.trim())
+            .map_err(|e| format!("Invalid modified_since: {}", e))?
+            .with_timezone(&chrono::Utc);
+        state
+            .db_authenticated()?
+            .get_flight_changes(since)
+            .map_err(|e| format!("Failed to get flight changes: {}", e))
+    }
+
+    #[tauri::command]
+    pub async fn get_flight_data(
+        flight_id: i64,
+        max_points: Option<usize>,
+        anonymize: Option<bool>,
+        state: State<'_, AppState>,
+    ) -> Result<FlightDataResponse, String> {
+        let start = std::time::Instant::now();
+        log::debug!("Fetching flight data for ID: {} (max_points: {:?})", flight_id, max_points);
+
+        let db = state.db_authenticated()?;
+        let mut flight = db
+            .get_flight_by_id(flight_id)
+            .map_err(|e| match e {
+                DatabaseError::FlightNotFound(id) => format!("Flight {} not found", id),
+                _ => format!("Failed to get flight: {}", e),
+            })?;
+
+        let known_point_count = flight.point_count.map(|c| c as i64);
+
+        let telemetry_records = db
+            .get_flight_telemetry(flight_id, max_points, known_point_count)
+            .map_err(|e| match e {
+                DatabaseError::FlightNotFound(id) => format!("Flight {} not found", id),
+                _ => format!("Failed to get telemetry: {}", e),
+            })?;
+
+        // Break the series at recording gaps instead of drawing across them
+        let gaps = crate::gaps::for_flight(&db, flight_id);
+        let telemetry_records = crate::gaps::mark_in_records(telemetry_records, &gaps.gaps);
+
+        let mut telemetry = TelemetryData::from_records(&telemetry_records);
+        let anonymize = anonymize.unwrap_or(false);
+        // Anonymized exports stay relative to takeoff: sea-level altitude narrows down the location
+        let altitude_reference = if anonymize {
+            crate::altitude::AltitudeReference::Takeoff
+        } else {
+            crate::altitude::apply_to_telemetry(&db, flight_id, &mut telemetry)
+        };
+        let mut track = telemetry.extract_track(2000);
+        if anonymize {
+            let privacy = crate::privacy::PrivacySettings::load(&state.config_path());
+            privacy.apply_to_flight(&mut flight);
+            privacy.apply_to_flight_data(&mut telemetry, &mut track);
+        }
+        telemetry.prune_empty_series();
+
+        // Not in anonymized exports (see the web handler)
+        let derived = if anonymize {
+            Vec::new()
+        } else {
+            crate::derived_metrics::series_for_flight(&db, &state.config_path(), flight_id, &telemetry_records)
+        };
+
+        // Get flight messages (tips and warnings)
+        let messages = db
+            .get_flight_messages(flight_id)
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to get messages for flight {}: {}", flight_id, e);
+                Vec::new()
+            });
+
+        log::debug!(
+            "get_flight_data for flight {} complete in {:.1}ms: {} telemetry series, {} track points, {} messages",
+            flight_id,
+            start.elapsed().as_secs_f64() * 1000.0,
+            telemetry_records.len(),
+            track.len(),
+            messages.len()
+        );
+
+        Ok(FlightDataResponse {
+            flight,
+            telemetry,
+            track,
+            messages,
+            derived,
+            gaps,
+            altitude_reference,
+        })
+    }
+
+    /// Get the validation report recorded when a flight was imported
+    #[tauri::command]
+    pub async fn get_import_report(
+        flight_id: i64,
+        state: State<'_, AppState>,
+    ) -> Result<Option<ImportReport>, String> {
+        state
+            .db_authenticated()?
+            .get_import_report(flight_id)
+            .map_err(|e| format!("Failed to get import report: {}", e))
+    }
+
+    /// Get the personal altitude/range limits
+    #[tauri::command]
+    pub async fn get_exceedance_limits(state: State<'_, AppState>) -> Result<ExceedanceLimits, String> {
+        state
+            .db_authenticated()?
+            .get_exceedance_limits()
+            .map_err(|e| format!("Failed to get limits: {}", e))
+    }
+
+    /// Save the personal limits and re-flag all flights. Returns the number of exceedances.
+    #[tauri::command]
+    pub async fn set_exceedance_limits(
+        limits: ExceedanceLimits,
+        state: State<'_, AppState>,
+    ) -> Result<usize, String> {
+        let db = state.db_authenticated()?;
+        let flagged = db
+            .set_exceedance_limits(&limits)
+            .map_err(|e| format!("Failed to save limits: {}", e))?;
+        db.audit("desktop", "settings_change", Some("exceedance_limits"), serde_json::json!(limits));
+        Ok(flagged)
+    }
+
+    /// Get the reference for charts, tracks and max altitude
+    #[tauri::command]
+    pub async fn get_altitude_reference(state: State<'_, AppState>) -> Result<crate::altitude::AltitudeReference, String> {
+        Ok(state.db_authenticated()?.get_altitude_reference())
+    }
+
+    /// Set the altitude reference ("takeoff" or "msl")
+    #[tauri::command]
+    pub async fn set_altitude_reference(
+        reference: crate::altitude::AltitudeReference,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        let db = state.db_authenticated()?;
+        db.set_altitude_reference(reference)
+            .map_err(|e| format!("Failed to save altitude reference: {}", e))?;
+        db.audit("desktop", "settings_change", Some("altitude_reference"), serde_json::json!(reference));
+        Ok(())
+    }
+
+    /// Get the flight time rounding used in reports and exports
+    #[tauri::command]
+    pub async fn get_duration_rounding(state: State<'_, AppState>) -> Result<crate::duration_rounding::DurationRounding, String> {
+        Ok(state.db_authenticated()?.get_duration_rounding())
+    }
+
+    /// Set the flight time rounding ("exact", "minute" or "tenths")
+    #[tauri::command]
+    pub async fn set_duration_rounding(
+        rounding: crate::duration_rounding::DurationRounding,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        let db = state.db_authenticated()?;
+        db.set_duration_rounding(rounding)
+            .map_err(|e| format!("Failed to save duration rounding: {}", e))?;
+        db.audit("desktop", "settings_change", Some("duration_rounding"), serde_json::json!(rounding));
+        Ok(())
+    }
+
+    /// Get the display units (distance, altitude, speed, temperature) used in reports and exports
+    #[tauri::command]
+    pub async fn get_unit_settings(state: State<'_, AppState>) -> Result<crate::units::UnitSettings, String> {
+        Ok(crate::units::UnitSettings::load(&state.config_path()))
+    }
+
+    /// Save the display units
+    #[tauri::command]
+    pub async fn set_unit_settings(
+        settings: crate::units::UnitSettings,
+        state: State<'_, AppState>,
+    ) -> Result<crate::units::UnitSettings, String> {
+        let saved = crate::units::save_settings(&state.config_path(), settings)?;
+        state.audit("settings_change", Some("units"), serde_json::json!(saved));
+        Ok(saved)
+    }
+
+    /// Get the return-to-home events detected for a flight
+    #[tauri::command]
+    pub async fn get_rth_events(
+        flight_id: i64,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<crate::rth::RthEvent>, String> {
+        state
+            .db_authenticated()?
+            .get_rth_events(flight_id)
+            .map_err(|e| format!("Failed to get RTH events: {}", e))
+    }
+
+    /// Get the telemetry columns that are null for every point of a flight
+    #[tauri::command]
+    pub async fn get_null_telemetry_columns(
+        flight_id: i64,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<String>, String> {
+        state
+            .db_authenticated()?
+            .null_telemetry_columns(flight_id)
+            .map_err(|e| format!("Failed to analyze telemetry columns: {}", e))
+    }
+
+    /// Get the cached list previews (track polyline, sparklines, thumbnail),
+    /// for the given flights or for all of them
+    #[tauri::command]
+    pub async fn get_flight_previews(
+        flight_ids: Option<Vec<i64>>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<crate::models::FlightPreview>, String> {
+        state
+            .db_authenticated()?
+            .get_flight_previews(flight_ids.as_deref())
+            .map_err(|e| format!("Failed to get flight previews: {}", e))
+    }
+
+    /// Attach a local file (authorization, waiver, contract...) to a flight,
+    /// or to the logbook as a whole when `flight_id` is None
+    #[tauri::command]
+    pub async fn add_attachment(
+        file_path: String,
+        flight_id: Option<i64>,
+        description: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<crate::models::Attachment, String> {
+        let path = PathBuf::from(&file_path);
+        let data = std::fs::read(&path).map_err(|e| format!("Failed to read file: {}", e))?;
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        state
+            .db_authenticated()?
+            .add_attachment(flight_id, &file_name, &data, description.as_deref())
+            .map_err(|e| format!("Failed to store attachment: {}", e))
+    }
+
+    /// List the attachments of a flight, or all attachments when `flight_id` is None
+    #[tauri::command]
+    pub async fn get_attachments(
+        flight_id: Option<i64>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<crate::models::Attachment>, String> {
+        state
+            .db_authenticated()?
+            .get_attachments(flight_id)
+            .map_err(|e| format!("Failed to get attachments: {}", e))
+    }
+
+    /// Absolute path of a stored attachment (for opening it with the system viewer)
+    #[tauri::command]
+    pub async fn get_attachment_path(id: String, state: State<'_, AppState>) -> Result<String, String> {
+        let (_, path) = state
+            .db_authenticated()?
+            .get_attachment_file(&id)
+            .map_err(|e| format!("Failed to get attachment: {}", e))?
+            .ok_or_else(|| "Attachment not found".to_string())?;
+        Ok(path.to_string_lossy().to_string())
+    }
+
+    /// Delete an attachment and its stored file
+    #[tauri::command]
+    pub async fn delete_attachment(id: String, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db_authenticated()?
+            .delete_attachment(&id)
+            .map_err(|e| format!("Failed to delete attachment: {}", e))?;
+        Ok(true)
+    }
+
+    /// Get all pre/post-flight checklist templates
+    #[tauri::command]
+    pub async fn get_checklist_templates(
+        state: State<'_, AppState>,
+    ) -> Result<Vec<crate::models::ChecklistTemplate>, String> {
+        state
+            .db_authenticated()?
+            .get_checklist_templates()
+            .map_err(|e| format!("Failed to get checklist templates: {}", e))
+    }
+
+    /// Create or update a checklist template (an empty id creates a new one)
+    #[tauri::command]
+    pub async fn save_checklist_template(
+        template: crate::models::ChecklistTemplate,
+        state: State<'_, AppState>,
+    ) -> Result<crate::models::ChecklistTemplate, String> {
+        if template.name.trim().is_empty() {
+            return Err("Checklist name cannot be empty".to_string());
+        }
+        if template.phase != "preflight" && template.phase != "postflight" {
+            return Err("Checklist phase must be 'preflight' or 'postflight'".to_string());
+        }
+        state
+            .db_authenticated()?
+            .save_checklist_template(&template)
+            .map_err(|e| format!("Failed to save checklist template: {}", e))
+    }
+
+    /// Delete a checklist template
+    #[tauri::command]
+    pub async fn delete_checklist_template(id: String, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db_authenticated()?
+            .delete_checklist_template(&id)
+            .map_err(|e| format!("Failed to delete checklist template: {}", e))?;
+        Ok(true)
+    }
+
+    /// Record a completed checklist against a flight or session
+    #[tauri::command]
+    pub async fn record_checklist(
+        record: crate::models::ChecklistRecord,
+        state: State<'_, AppState>,
+    ) -> Result<crate::models::ChecklistRecord, String> {
+        if record.flight_id.is_none() && record.session.as_deref().map_or(true, |s| s.trim().is_empty()) {
+            return Err("A checklist must be attached to a flight or a session".to_string());
+        }
+        state
+            .db_authenticated()?
+            .record_checklist(&record)
+            .map_err(|e| format!("Failed to record checklist: {}", e))
+    }
+
+    /// Get completed checklists for a flight, or all of them when `flight_id` is None
+    #[tauri::command]
+    pub async fn get_checklist_records(
+        flight_id: Option<i64>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<crate::models::ChecklistRecord>, String> {
+        state
+            .db_authenticated()?
+            .get_checklist_records(flight_id)
+            .map_err(|e| format!("Failed to get checklists: {}", e))
+    }
+
+    /// Delete a completed checklist
+    #[tauri::command]
+    pub async fn delete_checklist_record(id: String, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db_authenticated()?
+            .delete_checklist_record(&id)
+            .map_err(|e| format!("Failed to delete checklist: {}", e))?;
+        Ok(true)
+    }
+
+    /// Export statistics as JSON (all tables) or CSV (one `table`:
+    /// overview, monthly, batteries, drones). Returns the file contents.
+    #[tauri::command]
+    pub async fn export_stats(
+        format: String,
+        table: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<String, String> {
+        use crate::stats_export::{StatsExport, StatsTable};
+
+        let db = state.db_authenticated()?;
+        let export = StatsExport::collect(&db).map_err(|e| format!("Failed to collect statistics: {}", e))?;
+        match format.to_ascii_lowercase().as_str() {
+            "json" => serde_json::to_string_pretty(&export).map_err(|e| format!("Failed to serialize statistics: {}", e)),
+            "csv" => {
+                let table = table.as_deref().unwrap_or("overview");
+                let table = StatsTable::parse(table).ok_or_else(|| format!("Unknown statistics table: {}", table))?;
+                Ok(export.to_csv(table))
+            }
+            other => Err(format!("Unsupported export format: {}", other)),
+        }
+    }
+
+    /// Saved CSV export templates
+    #[tauri::command]
+    pub async fn get_export_templates(state: State<'_, AppState>) -> Result<Vec<crate::export_templates::CsvTemplate>, String> {
+        Ok(crate::export_templates::load_templates(&state.config_path()))
+    }
+
+    /// Create or update a CSV export template (an empty id creates a new one)
+    #[tauri::command]
+    pub async fn save_export_template(
+        template: crate::export_templates::CsvTemplate,
+        state: State<'_, AppState>,
+    ) -> Result<crate::export_templates::CsvTemplate, String> {
+        let saved = crate::export_templates::save_template(&state.config_path(), template).map_err(|e| e.to_string())?;
+        state.audit("settings_change", Some("csv_export_templates"), serde_json::json!({ "saved": saved.id }));
+        Ok(saved)
+    }
+
+    /// Delete a CSV export template
+    #[tauri::command]
+    pub async fn delete_export_template(id: String, state: State<'_, AppState>) -> Result<bool, String> {
+        let deleted = crate::export_templates::delete_template(&state.config_path(), &id).map_err(|e| e.to_string())?;
+        if deleted {
+            state.audit("settings_change", Some("csv_export_templates"), serde_json::json!({ "deleted": id }));
+        }
+        Ok(deleted)
+    }
+
+    /// Render flights (all when `flight_ids` is omitted) as CSV with a saved template
+    #[tauri::command]
+    pub async fn export_with_template(
+        template_id: String,
+        flight_ids: Option<Vec<i64>>,
+        state: State<'_, AppState>,
+    ) -> Result<String, String> {
+        let template = crate::export_templates::find_template(&state.config_path(), &template_id).map_err(|e| e.to_string())?;
+        let db = state.db_authenticated()?;
+        let mut flights = db
+            .get_all_flights()
+            .map_err(|e| format!("Failed to get flights: {}", e))?;
+        if let Some(ids) = flight_ids {
+            flights.retain(|f| ids.contains(&f.id));
+        }
+        template.render(&flights, db.get_duration_rounding()).map_err(|e| e.to_string())
+    }
+
+    /// Flights, duration and distance per day, over all years or the
+    /// inclusive `YYYY-MM-DD` range given
+    #[tauri::command]
+    pub async fn get_activity_calendar(
+        from: Option<String>,
+        to: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<crate::models::ActivityDay>, String> {
+        state
+            .db_authenticated()?
+            .get_activity_calendar(from.as_deref(), to.as_deref())
+            .map_err(|e| format!("Failed to get activity calendar: {}", e))
+    }
+
+    /// Flying streaks, days since the last flight and busiest day/week
+    #[tauri::command]
+    pub async fn get_activity_stats(
+        state: State<'_, AppState>,
+    ) -> Result<crate::models::ActivityStats, String> {
+        state
+            .db_authenticated()?
+            .get_activity_stats()
+            .map_err(|e| format!("Failed to get activity stats: {}", e))
+    }
+
+    /// Summary of one calendar year for the "year in review" recap card
+    #[tauri::command]
+    pub async fn get_year_review(
+        year: i32,
+        state: State<'_, AppState>,
+    ) -> Result<crate::year_review::YearReview, String> {
+        let flights = state
+            .db_authenticated()?
+            .get_all_flights()
+            .map_err(|e| format!("Failed to get flights: {}", e))?;
+        Ok(crate::year_review::build_year_review(year, &flights))
+    }
+
+    /// Render a flight track as a static PNG or SVG image (PNG bytes are
+    /// returned as an ArrayBuffer, SVG as UTF-8 text bytes)
+    #[tauri::command]
+    pub async fn render_flight_image(
+        flight_id: i64,
+        format: Option<String>,
+        width: Option<u32>,
+        height: Option<u32>,
+        state: State<'_, AppState>,
+    ) -> Result<tauri::ipc::Response, String> {
+        use crate::render::{self, ImageFormat, RenderOptions};
+
+        let format = format.as_deref().unwrap_or("png");
+        let format = ImageFormat::parse(format).ok_or_else(|| format!("Unsupported image format: {}", format))?;
+        let db = state.db_authenticated()?;
+        let track = render::flight_track(&db, flight_id).map_err(|e| format!("Failed to load flight track: {}", e))?;
+        let opts = RenderOptions::sized(width.unwrap_or(640), height.unwrap_or(400));
+        Ok(tauri::ipc::Response::new(render::render_track(&track, format, &opts)))
+    }
+
+    /// Write a zip with everything about one flight (raw log if kept, CSV,
+    /// GPX/KML, events, weather and a PDF summary) to `dest_path`
+    #[tauri::command]
+    pub async fn export_flight_bundle(
+        flight_id: i64,
+        dest_path: String,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let db = state.db_authenticated()?;
+        let config: serde_json::Value = std::fs::read_to_string(state.config_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+        let upload_folder = config
+            .get("uploaded_files_path")
+            .and_then(|v| v.as_str())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| state.default_upload_folder());
+
+        let privacy = crate::privacy::PrivacySettings::from_config(&config);
+        let units = crate::units::UnitSettings::from_config(&config);
+        let bundle = crate::bundle::export_flight_bundle(&db, flight_id, Some(&upload_folder), &privacy, &units)
+            .await
+            .map_err(|e| format!("Failed to build flight bundle: {}", e))?;
+        std::fs::write(&dest_path, bundle.bytes).map_err(|e| format!("Failed to write flight bundle: {}", e))?;
+        log::info!("Exported bundle for flight {} to {}", flight_id, dest_path);
+        Ok(true)
+    }
+
+    /// Write two flights flown at the same time, time-aligned in one KML or
+    /// CZML file, to `dest_path`
+    #[tauri::command]
+    pub async fn export_chase(
+        flight_id: i64,
+        other_flight_id: i64,
+        format: crate::chase_export::ChaseFormat,
+        dest_path: String,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let db = state.db_authenticated()?;
+        let privacy = crate::privacy::PrivacySettings::load(&state.config_path());
+        let export = crate::chase_export::export_chase(&db, [flight_id, other_flight_id], format, &privacy)
+            .map_err(|e| format!("Failed to build chase export: {}", e))?;
+        std::fs::write(&dest_path, export.bytes).map_err(|e| format!("Failed to write chase export: {}", e))?;
+        log::info!("Exported chase of flights {} and {} to {}", flight_id, other_flight_id, dest_path);
+        Ok(true)
+    }
+
+    /// Get the home location anonymization settings
+    #[tauri::command]
+    pub async fn get_privacy_settings(state: State<'_, AppState>) -> Result<crate::privacy::PrivacySettings, String> {
+        Ok(crate::privacy::PrivacySettings::load(&state.config_path()))
+    }
+
+    /// Save the home location anonymization settings
+    #[tauri::command]
+    pub async fn set_privacy_settings(
+        settings: crate::privacy::PrivacySettings,
+        state: State<'_, AppState>,
+    ) -> Result<crate::privacy::PrivacySettings, String> {
+        let saved = crate::privacy::save_settings(&state.config_path(), settings)?;
+        state.audit(
+            "settings_change",
+            Some("privacy"),
+            serde_json::json!({ "enabled": saved.enabled, "mode": saved.mode, "zones": saved.zones.len() }),
+        );
+        Ok(saved)
+    }
+
+    /// Rules setting tags, pilot, job and notes from file names at import
+    #[tauri::command]
+    pub async fn get_filename_rules(state: State<'_, AppState>) -> Result<Vec<crate::filename_rules::FilenameRule>, String> {
+        Ok(crate::filename_rules::load(&state.config_path()))
+    }
+
+    /// Replace the filename rules
+    #[tauri::command]
+    pub async fn set_filename_rules(
+        rules: Vec<crate::filename_rules::FilenameRule>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<crate::filename_rules::FilenameRule>, String> {
+        let saved = crate::filename_rules::save_rules(&state.config_path(), rules)?;
+        state.audit("settings_change", Some("filename_rules"), serde_json::json!({ "rules": saved.len() }));
+        Ok(saved)
+    }
+
+    /// User-defined telemetry columns (DuckDB SQL expressions)
+    #[tauri::command]
+    pub async fn get_derived_metrics(state: State<'_, AppState>) -> Result<Vec<crate::derived_metrics::DerivedMetric>, String> {
+        Ok(crate::derived_metrics::load(&state.config_path()))
+    }
+
+    /// Validate and replace the derived column definitions
+    #[tauri::command]
+    pub async fn set_derived_metrics(
+        metrics: Vec<crate::derived_metrics::DerivedMetric>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<crate::derived_metrics::DerivedMetric>, String> {
+        let saved = crate::derived_metrics::save_settings(&state.config_path(), &state.db_authenticated()?, metrics)?;
+        state.audit(
+            "settings_change",
+            Some("derived_metrics"),
+            serde_json::json!({ "metrics": saved.iter().map(|m| &m.name).collect::<Vec<_>>() }),
+        );
+        Ok(saved)
+    }
+
+    /// Sun times, forecast, airspace and zone rules for a planned flight
+    /// (`time` is RFC 3339; now when omitted)
+    #[tauri::command]
+    pub async fn get_preflight_briefing(
+        lat: f64,
+        lon: f64,
+        time: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<crate::preflight::PreflightReport, String> {
+        let time = match time.as_deref() {
+            Some(t) => chrono::DateTime::parse_from_rfc3339(t)
+                .map_err(|e| format!("Invalid time '{}': {}", t, e))?
+                .with_timezone(&chrono::Utc),
+            None => chrono::Utc::now(),
+        };
+        crate::preflight::briefing(&state.config_path(), lat, lon, time).await
+    }
+
+    /// Get the pre-flight zone rules (the OpenAIP key is not returned)
+    #[tauri::command]
+    pub async fn get_preflight_settings(state: State<'_, AppState>) -> Result<crate::preflight::PreflightSettings, String> {
+        Ok(crate::preflight::PreflightSettings::load(&state.config_path()))
+    }
+
+    /// Save the pre-flight zone rules (empty OpenAIP key keeps the stored one, "-" removes it)
+    #[tauri::command]
+    pub async fn set_preflight_settings(
+        settings: crate::preflight::PreflightSettings,
+        state: State<'_, AppState>,
+    ) -> Result<crate::preflight::PreflightSettings, String> {
+        let saved = crate::preflight::save_settings(&state.config_path(), settings)?;
+        state.audit(
+            "settings_change",
+            Some("preflight"),
+            serde_json::json!({ "zones": saved.zones.len(), "hasOpenaipApiKey": saved.has_openaip_api_key }),
+        );
+        Ok(saved)
+    }
+
+    /// Get the AirData / DroneLogbook.com push settings (the API key is not returned)
+    #[tauri::command]
+    pub async fn get_cloud_push_settings(state: State<'_, AppState>) -> Result<crate::cloud_push::CloudPushSettings, String> {
+        let config: serde_json::Value = std::fs::read_to_string(state.config_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+        Ok(crate::cloud_push::CloudPushSettings::from_config(&config))
+    }
+
+    /// Save the cloud push settings (an empty API key keeps the stored one)
+    #[tauri::command]
+    pub async fn set_cloud_push_settings(
+        settings: crate::cloud_push::CloudPushSettings,
+        state: State<'_, AppState>,
+    ) -> Result<crate::cloud_push::CloudPushSettings, String> {
+        let saved = crate::cloud_push::save_settings(&state.config_path(), settings)?;
+        state.audit(
+            "settings_change",
+            Some("cloud_push"),
+            serde_json::json!({ "enabled": saved.enabled, "service": saved.service }),
+        );
+        Ok(saved)
+    }
+
+    /// Upload a stored flight's original log to the configured cloud service
+    #[tauri::command]
+    pub async fn push_flight_to_cloud(flight_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+        let db = state.db_authenticated()?;
+        let config: serde_json::Value = std::fs::read_to_string(state.config_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+        let upload_folder = config
+            .get("uploaded_files_path")
+            .and_then(|v| v.as_str())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| state.default_upload_folder());
+        crate::cloud_push::push_flight(&db, &config, &upload_folder, flight_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+
+    /// Get where shared flight tracks are published (the password is not returned)
+    #[tauri::command]
+    pub async fn get_sharing_settings(state: State<'_, AppState>) -> Result<crate::share::SharingSettings, String> {
+        let config: serde_json::Value = std::fs::read_to_string(state.config_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+        Ok(crate::share::SharingSettings::from_config(&config))
+    }
+
+    /// Save the sharing target (an empty password keeps the stored one)
+    #[tauri::command]
+    pub async fn set_sharing_settings(
+        settings: crate::share::SharingSettings,
+        state: State<'_, AppState>,
+    ) -> Result<crate::share::SharingSettings, String> {
+        let saved = crate::share::save_settings(&state.config_path(), settings)?;
+        state.audit(
+            "settings_change",
+            Some("sharing"),
+            serde_json::json!({ "target": saved.target, "url": saved.url, "publicUrl": saved.public_url }),
+        );
+        Ok(saved)
+    }
+
+    /// Publish a flight's track as GPX and return the share URL
+    #[tauri::command]
+    pub async fn share_flight(flight_id: i64, state: State<'_, AppState>) -> Result<crate::share::SharedFlight, String> {
+        let db = state.db_authenticated()?;
+        let config: serde_json::Value = std::fs::read_to_string(state.config_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+        crate::share::share_flight(&db, &config, flight_id, "desktop")
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Set the language of backend messages (called with the app language)
+    #[tauri::command]
+    pub fn set_locale(locale: String) -> Result<String, String> {
+        crate::messages::set_default_locale(&locale).map(str::to_string)
+    }
+
+    /// Attach a planned mission (Litchi CSV, DJI waypoint KMZ or KML) to a flight
+    #[tauri::command]
+    pub async fn attach_flight_plan(
+        flight_id: i64,
+        file_path: String,
+        state: State<'_, AppState>,
+    ) -> Result<crate::mission_plan::FlightPlan, String> {
+        let db = state.db_authenticated()?;
+        let path = PathBuf::from(&file_path);
+        let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read mission file: {}", e))?;
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| file_path.clone());
+        let plan = crate::mission_plan::attach_plan(&db, flight_id, &file_name, &bytes).map_err(|e| e.to_string())?;
+        state.audit(
+            "attach_flight_plan",
+            Some(&flight_id.to_string()),
+            serde_json::json!({ "file": file_name, "waypoints": plan.waypoints.len() }),
+        );
+        Ok(plan)
+    }
+
+    /// Attach a GPX track (watch, phone) to a manual flight
+    #[tauri::command]
+    pub async fn attach_manual_track(
+        flight_id: i64,
+        file_path: String,
+        state: State<'_, AppState>,
+    ) -> Result<Flight, String> {
+        let db = state.db_authenticated()?;
+        let bytes = std::fs::read(&file_path).map_err(|e| format!("Failed to read track file: {}", e))?;
+        let flight = crate::manual_track::attach_track(&db, flight_id, &bytes).map_err(|e| e.to_string())?;
+        state.audit(
+            "attach_manual_track",
+            Some(&flight_id.to_string()),
+            serde_json::json!({ "file": file_path, "points": flight.point_count }),
+        );
+        Ok(flight)
+    }
+
+    /// Planned mission, actual track and deviation statistics for a flight
+    #[tauri::command]
+    pub async fn get_flight_plan(
+        flight_id: i64,
+        state: State<'_, AppState>,
+    ) -> Result<Option<crate::mission_plan::PlanComparison>, String> {
+        let db = state.db_authenticated()?;
+        crate::mission_plan::load_comparison(&db, flight_id).map_err(|e| e.to_string())
+    }
+
+    /// Detach the planned mission from a flight
+    #[tauri::command]
+    pub async fn delete_flight_plan(flight_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db_authenticated()?
+            .delete_flight_plan(flight_id)
+            .map_err(|e| format!("Failed to delete flight plan: {}", e))?;
+        Ok(true)
+    }
+
+    /// Per-aircraft utilization and maintenance, battery pool health and
+    /// pilot assignments (`window_days` defaults to 30)
+    #[tauri::command]
+    pub async fn get_fleet_overview(
+        window_days: Option<i64>,
+        state: State<'_, AppState>,
+    ) -> Result<crate::fleet::FleetOverview, String> {
+        let window_days = window_days.unwrap_or(30).clamp(1, 3650);
+        crate::fleet::load_fleet_overview(&state.db_authenticated()?, window_days)
+            .map_err(|e| format!("Failed to get fleet overview: {}", e))
+    }
+
+    /// Expected endurance per aircraft + battery under the given wind and
+    /// temperature, or the current forecast at `lat`/`lon`
+    #[tauri::command]
+    pub async fn get_endurance_benchmarks(
+        wind_kmh: Option<f64>,
+        temperature_c: Option<f64>,
+        lat: Option<f64>,
+        lon: Option<f64>,
+        reserve_percent: Option<f64>,
+        state: State<'_, AppState>,
+    ) -> Result<crate::endurance::EnduranceReport, String> {
+        let db = state.db_authenticated()?;
+        crate::endurance::report(&db, wind_kmh, temperature_c, lat.zip(lon), reserve_percent).await
+    }
+
+    /// Batteries past a retirement threshold, with the reasons
+    #[tauri::command]
+    pub async fn get_battery_retirement(
+        state: State<'_, AppState>,
+    ) -> Result<Vec<crate::battery_retirement::RetirementRecommendation>, String> {
+        crate::battery_retirement::load_recommendations(&state.db_authenticated()?, &state.config_path())
+            .map_err(|e| format!("Failed to assess batteries: {}", e))
+    }
+
+    /// Get the battery retirement thresholds and webhook URL
+    #[tauri::command]
+    pub async fn get_battery_retirement_settings(
+        state: State<'_, AppState>,
+    ) -> Result<crate::battery_retirement::RetirementSettings, String> {
+        Ok(crate::battery_retirement::RetirementSettings::load(&state.config_path()))
+    }
+
+    /// Save the battery retirement thresholds (0 disables a check)
+    #[tauri::command]
+    pub async fn set_battery_retirement_settings(
+        settings: crate::battery_retirement::RetirementSettings,
+        state: State<'_, AppState>,
+    ) -> Result<crate::battery_retirement::RetirementSettings, String> {
+        let saved = crate::battery_retirement::save_settings(&state.config_path(), settings)?;
+        state.audit(
+            "settings_change",
+            Some("battery_retirement"),
+            serde_json::json!({
+                "maxCycles": saved.max_cycles,
+                "minCapacityRetentionPct": saved.min_capacity_retention_pct,
+                "maxSagIncreasePct": saved.max_sag_increase_pct,
+                "maxAgeDays": saved.max_age_days,
+                "hasWebhook": saved.webhook_url.is_some(),
+            }),
+        );
+        Ok(saved)
+    }
+
+    /// Get which background events raise a notification
+    #[tauri::command]
+    pub async fn get_notification_settings(
+        state: State<'_, AppState>,
+    ) -> Result<crate::notifications::NotificationSettings, String> {
+        Ok(crate::notifications::NotificationSettings::load(&state.config_path()))
+    }
+
+    /// Switch notifications for background events on or off
+    #[tauri::command]
+    pub async fn set_notification_settings(
+        settings: crate::notifications::NotificationSettings,
+        state: State<'_, AppState>,
+    ) -> Result<crate::notifications::NotificationSettings, String> {
+        let saved = crate::notifications::save_settings(&state.config_path(), settings)?;
+        state.audit("settings_change", Some("notifications"), serde_json::json!(saved));
+        Ok(saved)
+    }
+
+    /// Notify an event finished by the frontend (the folder sync at startup).
+    /// Returns whether a notification was shown.
+    #[tauri::command]
+    pub async fn send_notification(
+        event: crate::notifications::NotificationEvent,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let db = state.db_authenticated()?;
+        Ok(crate::notifications::notify_profile(&db, &state.config_path(), &event))
+    }
+
+    /// Per-stage import timings, by format, with the slowest and latest imports
+    #[tauri::command]
+    pub async fn get_import_diagnostics(
+        limit: Option<usize>,
+        state: State<'_, AppState>,
+    ) -> Result<crate::import_profile::ImportDiagnostics, String> {
+        let profiles = state
+            .db_authenticated()?
+            .get_import_profiles()
+            .map_err(|e| format!("Failed to get import profiles: {}", e))?;
+        Ok(crate::import_profile::summarize(profiles, limit.unwrap_or(20).clamp(1, 500)))
+    }
+
+    /// Maintenance log, newest first (optionally for one aircraft or battery)
+    #[tauri::command]
+    pub async fn get_maintenance_records(
+        serial: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<crate::fleet::MaintenanceRecord>, String> {
+        state
+            .db_authenticated()?
+            .get_maintenance_records(serial.as_deref())
+            .map_err(|e| format!("Failed to get maintenance log: {}", e))
+    }
+
+    /// Log maintenance on an aircraft or battery (an empty id creates a record)
+    #[tauri::command]
+    pub async fn save_maintenance_record(
+        record: crate::fleet::MaintenanceRecord,
+        state: State<'_, AppState>,
+    ) -> Result<crate::fleet::MaintenanceRecord, String> {
+        crate::fleet::validate_maintenance(&record)?;
+        let saved = state
+            .db_authenticated()?
+            .save_maintenance_record(&record)
+            .map_err(|e| format!("Failed to save maintenance record: {}", e))?;
+        state.audit(
+            "save_maintenance",
+            Some(&saved.serial),
+            serde_json::json!({ "id": saved.id, "equipmentType": saved.equipment_type, "performedAt": saved.performed_at }),
+        );
+        Ok(saved)
+    }
+
+    /// Delete a maintenance record
+    #[tauri::command]
+    pub async fn delete_maintenance_record(id: String, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db_authenticated()?
+            .delete_maintenance_record(&id)
+            .map_err(|e| format!("Failed to delete maintenance record: {}", e))?;
+        state.audit("delete_maintenance", Some(&id), serde_json::Value::Null);
+        Ok(true)
+    }
+
+    /// Flights and time each aircraft flew before this logbook
+    #[tauri::command]
+    pub async fn get_airframe_seeds(state: State<'_, AppState>) -> Result<Vec<crate::fleet::AirframeSeed>, String> {
+        state
+            .db_authenticated()?
+            .get_airframe_seeds()
+            .map_err(|e| format!("Failed to get airframe seeds: {}", e))
+    }
+
+    /// Set an aircraft's airframe seed (zero flights and time removes it)
+    #[tauri::command]
+    pub async fn set_airframe_seed(seed: crate::fleet::AirframeSeed, state: State<'_, AppState>) -> Result<bool, String> {
+        crate::fleet::validate_airframe_seed(&seed)?;
+        state
+            .db_authenticated()?
+            .set_airframe_seed(&seed)
+            .map_err(|e| format!("Failed to set airframe seed: {}", e))?;
+        state.audit(
+            "settings_change",
+            Some("airframe_seed"),
+            serde_json::json!({ "serial": seed.serial, "priorDurationSecs": seed.prior_duration_secs, "priorFlights": seed.prior_flights }),
+        );
+        Ok(true)
+    }
+
+    /// Hours, flights and last visit per named site or take-off cluster
+    #[tauri::command]
+    pub async fn get_place_stats(state: State<'_, AppState>) -> Result<Vec<crate::locations::PlaceStats>, String> {
+        crate::locations::places(&state.db_authenticated()?).map_err(|e| format!("Failed to get place stats: {}", e))
+    }
+
+    /// Location tag renames and named sites
+    #[tauri::command]
+    pub async fn get_location_overrides(state: State<'_, AppState>) -> Result<crate::locations::LocationOverrides, String> {
+        crate::locations::load(&state.db_authenticated()?).map_err(|e| format!("Failed to get location overrides: {}", e))
+    }
+
+    /// Replace the location tag renames and named sites
+    #[tauri::command]
+    pub async fn set_location_overrides(
+        overrides: crate::locations::LocationOverrides,
+        state: State<'_, AppState>,
+    ) -> Result<crate::locations::LocationOverrides, String> {
+        let saved = crate::locations::save(&state.db_authenticated()?, overrides).map_err(|e| e.to_string())?;
+        state.audit(
+            "settings_change",
+            Some("location_overrides"),
+            serde_json::json!({ "renames": saved.renames.len(), "sites": saved.sites.len() }),
+        );
+        Ok(saved)
+    }
+
+    /// Get all jobs with their flight counts
+    #[tauri::command]
+    pub async fn get_jobs(state: State<'_, AppState>) -> Result<Vec<crate::jobs::Job>, String> {
+        state
+            .db_authenticated()?
+            .get_jobs()
+            .map_err(|e| format!("Failed to get jobs: {}", e))
+    }
+
+    /// Create or update a job (an empty id creates a new one)
+    #[tauri::command]
+    pub async fn save_job(job: crate::jobs::Job, state: State<'_, AppState>) -> Result<crate::jobs::Job, String> {
+        if job.name.trim().is_empty() {
+            return Err("Job name cannot be empty".to_string());
+        }
+        let saved = state
+            .db_authenticated()?
+            .save_job(&job)
+            .map_err(|e| format!("Failed to save job: {}", e))?;
+        state.audit("save_job", Some(&saved.id), serde_json::json!({ "name": saved.name }));
+        Ok(saved)
+    }
+
+    /// Delete a job (its flights are kept, unassigned)
+    #[tauri::command]
+    pub async fn delete_job(id: String, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db_authenticated()?
+            .delete_job(&id)
+            .map_err(|e| format!("Failed to delete job: {}", e))?;
+        state.audit("delete_job", Some(&id), serde_json::Value::Null);
+        Ok(true)
+    }
+
+    /// Assign flights to a job, or unassign them when `job_id` is omitted
+    #[tauri::command]
+    pub async fn assign_flights_to_job(
+        job_id: Option<String>,
+        flight_ids: Vec<i64>,
+        state: State<'_, AppState>,
+    ) -> Result<usize, String> {
+        let db = state.db_authenticated()?;
+        if let Some(id) = job_id.as_deref() {
+            if db.get_job(id).map_err(|e| format!("Failed to get job: {}", e))?.is_none() {
+                return Err(format!("Job not found: {}", id));
+            }
+        }
+        let changed = db
+            .assign_flights_to_job(job_id.as_deref(), &flight_ids)
+            .map_err(|e| format!("Failed to assign flights: {}", e))?;
+        state.audit("assign_job", job_id.as_deref(), serde_json::json!({ "flights": flight_ids }));
+        Ok(changed)
+    }
+
+    /// Hours, distance, batteries and aircraft used for one job
+    #[tauri::command]
+    pub async fn get_job_stats(job_id: String, state: State<'_, AppState>) -> Result<crate::jobs::JobStats, String> {
+        let db = state.db_authenticated()?;
+        let (job, flights) = crate::jobs::load_job(&db, &job_id)
+            .map_err(|e| format!("Failed to get job: {}", e))?
+            .ok_or_else(|| format!("Job not found: {}", job_id))?;
+        Ok(crate::jobs::job_stats(job, &flights))
+    }
+
+    /// Export a job's flights as CSV (optionally through a CSV export template) or JSON
+    #[tauri::command]
+    pub async fn export_job(
+        job_id: String,
+        format: String,
+        template_id: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<String, String> {
+        let db = state.db_authenticated()?;
+        let (job, flights) = crate::jobs::load_job(&db, &job_id)
+            .map_err(|e| format!("Failed to get job: {}", e))?
+            .ok_or_else(|| format!("Job not found: {}", job_id))?;
+        let template = match template_id {
+            Some(id) => Some(crate::export_templates::find_template(&state.config_path(), &id).map_err(|e| e.to_string())?),
+            None => None,
+        };
+        crate::jobs::export_job(job, &flights, &format, template.as_ref(), db.get_duration_rounding())
+    }
+
+    /// Billable flight hours, standby time and distance per client and job
+    /// over an inclusive date range (`YYYY-MM-DD`)
+    #[tauri::command]
+    pub async fn get_invoice_report(
+        from: Option<String>,
+        to: Option<String>,
+        client: Option<String>,
+        session_gap_minutes: Option<f64>,
+        state: State<'_, AppState>,
+    ) -> Result<crate::invoice::InvoiceReport, String> {
+        let options = crate::invoice::InvoiceOptions {
+            from: crate::invoice::parse_date(from.as_deref())?,
+            to: crate::invoice::parse_date(to.as_deref())?,
+            client,
+            session_gap_minutes,
+        };
+        crate::invoice::load_report(&state.db_authenticated()?, &options)
+            .map_err(|e| format!("Failed to build invoice report: {}", e))
+    }
+
+    /// Write the invoice report as CSV or PDF to `dest_path`
+    #[tauri::command]
+    pub async fn export_invoice_report(
+        from: Option<String>,
+        to: Option<String>,
+        client: Option<String>,
+        session_gap_minutes: Option<f64>,
+        format: String,
+        dest_path: String,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let options = crate::invoice::InvoiceOptions {
+            from: crate::invoice::parse_date(from.as_deref())?,
+            to: crate::invoice::parse_date(to.as_deref())?,
+            client,
+            session_gap_minutes,
+        };
+        let report = crate::invoice::load_report(&state.db_authenticated()?, &options)
+            .map_err(|e| format!("Failed to build invoice report: {}", e))?;
+        let units = crate::units::UnitSettings::load(&state.config_path());
+        let bytes = match format.to_ascii_lowercase().as_str() {
+            "csv" => crate::invoice::report_csv(&report, &units).into_bytes(),
+            "pdf" => crate::invoice::report_pdf(&report, &units),
+            other => return Err(format!("Unsupported export format: {}", other)),
+        };
+        std::fs::write(&dest_path, bytes).map_err(|e| format!("Failed to write report: {}", e))?;
+        Ok(true)
+    }
+
+    /// Operator flight log for EASA-territory authorities over an inclusive
+    /// date range (`YYYY-MM-DD`)
+    #[tauri::command]
+    pub async fn get_operator_log(
+        from: Option<String>,
+        to: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<crate::operator_log::OperatorLog, String> {
+        let from = crate::invoice::parse_date(from.as_deref())?;
+        let to = crate::invoice::parse_date(to.as_deref())?;
+        crate::operator_log::load_log(&state.db_authenticated()?, &state.config_path(), from, to)
+            .map_err(|e| format!("Failed to build operator log: {}", e))
+    }
+
+    /// Write the operator log as CSV or PDF to `dest_path`
+    #[tauri::command]
+    pub async fn export_operator_log(
+        from: Option<String>,
+        to: Option<String>,
+        format: String,
+        dest_path: String,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let from = crate::invoice::parse_date(from.as_deref())?;
+        let to = crate::invoice::parse_date(to.as_deref())?;
+        let log = crate::operator_log::load_log(&state.db_authenticated()?, &state.config_path(), from, to)
+            .map_err(|e| format!("Failed to build operator log: {}", e))?;
+        let bytes = match format.to_ascii_lowercase().as_str() {
+            "csv" => crate::operator_log::log_csv(&log).into_bytes(),
+            "pdf" => crate::operator_log::log_pdf(&log),
+            other => return Err(format!("Unsupported export format: {}", other)),
+        };
+        std::fs::write(&dest_path, bytes).map_err(|e| format!("Failed to write operator log: {}", e))?;
+        Ok(true)
+    }
+
+    /// Write the filtered flight list (`flight_ids` in display order, all
+    /// flights when empty) with the chosen columns as CSV or PDF to `dest_path`
+    #[tauri::command]
+    pub async fn export_flight_list(
+        flight_ids: Vec<i64>,
+        columns: Vec<crate::flight_list::FlightColumn>,
+        title: Option<String>,
+        format: String,
+        dest_path: String,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        let db = state.db_authenticated()?;
+        let flights = crate::flight_list::load_flights(&db, &flight_ids)
+            .map_err(|e| format!("Failed to get flights: {}", e))?;
+        let units = crate::units::UnitSettings::load(&state.config_path());
+        let rounding = db.get_duration_rounding();
+        let bytes = match format.to_ascii_lowercase().as_str() {
+            "csv" => crate::flight_list::list_csv(&flights, &columns, &units, rounding).into_bytes(),
+            "pdf" => crate::flight_list::list_pdf(&flights, &columns, title.as_deref(), &units, rounding),
+            other => return Err(format!("Unsupported export format: {}", other)),
+        };
+        std::fs::write(&dest_path, bytes).map_err(|e| format!("Failed to write flight list: {}", e))?;
+        Ok(true)
+    }
+
+    /// Flights, minutes and heights over an area (`[lon, lat]` vertices) for
+    /// flights taken off within an inclusive date range (`YYYY-MM-DD`)
+    #[tauri::command]
+    pub async fn get_overflight_report(
+        polygon: Vec<[f64; 2]>,
+        from: Option<String>,
+        to: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<crate::overflight::OverflightReport, String> {
+        let from = crate::invoice::parse_date(from.as_deref())?;
+        let to = crate::invoice::parse_date(to.as_deref())?;
+        crate::overflight::build_report(&state.db_authenticated()?, &polygon, from, to).map_err(|e| e.to_string())
+    }
+
+    /// Run a read-only SELECT against the database (one statement, at most
+    /// `max_rows` rows, cancelled after 10 s)
+    #[tauri::command]
+    pub async fn run_sql_query(
+        sql: String,
+        max_rows: Option<usize>,
+        state: State<'_, AppState>,
+    ) -> Result<crate::sql_console::QueryResult, String> {
+        crate::sql_console::run(&state.db_authenticated()?, &sql, max_rows).map_err(|e| e.to_string())
+    }
+
+    /// Get the operator details and aircraft class marks for the operator log
+    #[tauri::command]
+    pub async fn get_operator_settings(state: State<'_, AppState>) -> Result<crate::operator_log::OperatorSettings, String> {
+        Ok(crate::operator_log::OperatorSettings::load(&state.config_path()))
+    }
+
+    /// Save the operator details and aircraft class marks
+    #[tauri::command]
+    pub async fn set_operator_settings(
+        settings: crate::operator_log::OperatorSettings,
+        state: State<'_, AppState>,
+    ) -> Result<crate::operator_log::OperatorSettings, String> {
+        let saved = crate::operator_log::save_settings(&state.config_path(), settings)?;
+        state.audit(
+            "settings_change",
+            Some("operator_log"),
+            serde_json::json!({ "operatorId": saved.operator_id, "aircraft": saved.uas_classes.len() }),
+        );
+        Ok(saved)
+    }
+
+    /// Start the background import queue worker on the active profile (no-op when one is running)
+    fn spawn_import_queue(app: AppHandle) {
+        let Some(state) = app.try_state::<AppState>() else {
+            return;
+        };
+        let db = state.db();
+        if db.is_read_only() {
+            return;
+        }
+        tauri::async_runtime::spawn(async move {
+            let queue_db = db.clone();
+            crate::import_queue::run_worker(db, |item| {
+                let app = app.clone();
+                let queue_db = queue_db.clone();
+                async move {
+                    // import_log works on the active profile, which must still own this queue
+                    let state = app.state::<AppState>();
+                    if !Arc::ptr_eq(&state.db(), &queue_db) {
+                        return Err("The profile was switched before this file was imported".to_string());
+                    }
+                    import_log(item.file_path, None, state).await
+                }
+            })
+            .await;
+        });
+    }
+
+    /// Queue files for background import; returns the new queue entries.
+    /// Files queued while an import is running are imported after it.
+    #[tauri::command]
+    pub async fn enqueue_imports(
+        file_paths: Vec<String>,
+        app: AppHandle,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<crate::import_queue::QueuedImport>, String> {
+        let db = state.db_authenticated()?;
+        let mut queued = Vec::with_capacity(file_paths.len());
+        for file_path in file_paths {
+            let file_name = PathBuf::from(&file_path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| file_path.clone());
+            queued.push(
+                db.enqueue_import(&file_name, &file_path, None)
+                    .map_err(|e| format!("Failed to queue import: {}", e))?,
+            );
+        }
+        spawn_import_queue(app);
+        Ok(queued)
+    }
+
+    /// All import queue entries with their status, oldest first
+    #[tauri::command]
+    pub async fn get_import_queue(state: State<'_, AppState>) -> Result<Vec<crate::import_queue::QueuedImport>, String> {
+        state
+            .db_authenticated()?
+            .get_import_queue()
+            .map_err(|e| format!("Failed to get import queue: {}", e))
+    }
+
+    /// Remove finished (done / failed) import queue entries
+    #[tauri::command]
+    pub async fn clear_import_queue(state: State<'_, AppState>) -> Result<usize, String> {
+        state
+            .db_authenticated()?
+            .clear_finished_imports()
+            .map(|cleared| cleared.len())
+            .map_err(|e| format!("Failed to clear import queue: {}", e))
+    }
+
+    /// Get the audit log of data-modifying actions, newest first
+    #[tauri::command]
+    pub async fn get_audit_log(
+        limit: Option<usize>,
+        offset: Option<usize>,
+        action: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<crate::models::AuditEntry>, String> {
+        state
+            .db_authenticated()?
+            .get_audit_log(limit.unwrap_or(200), offset.unwrap_or(0), action.as_deref())
+            .map_err(|e| format!("Failed to get audit log: {}", e))
+    }
+
+    /// Get the integrity chain entries, oldest first, optionally for one flight
+    #[tauri::command]
+    pub async fn get_integrity_chain(
+        flight_id: Option<i64>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<crate::integrity::IntegrityEntry>, String> {
+        state
+            .db_authenticated()?
+            .get_integrity_chain(flight_id)
+            .map_err(|e| format!("Failed to get integrity chain: {}", e))
+    }
+
+    /// Check the integrity chain and every flight against it
+    #[tauri::command]
+    pub async fn verify_integrity(state: State<'_, AppState>) -> Result<crate::integrity::IntegrityReport, String> {
+        state
+            .db_repair()?
+            .verify_integrity()
+            .map_err(|e| format!("Failed to verify integrity: {}", e))
+    }
+
+    /// Add flights imported before the integrity chain existed
+    #[tauri::command]
+    pub async fn seal_integrity_chain(state: State<'_, AppState>) -> Result<Vec<i64>, String> {
+        let sealed = state
+            .db_authenticated()?
+            .seal_unrecorded_flights()
+            .map_err(|e| format!("Failed to seal flights: {}", e))?;
+        if !sealed.is_empty() {
+            state.audit("seal_integrity_chain", None, serde_json::json!({ "flights": sealed }));
+        }
+        Ok(sealed)
+    }
+
+    /// Add the synthetic demo flights (skips the ones already present)
+    #[tauri::command]
+    pub async fn seed_demo_data(state: State<'_, AppState>) -> Result<Vec<i64>, String> {
+        let db = state.db_authenticated()?;
+        let config: serde_json::Value = std::fs::read_to_string(state.config_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+        let ids = crate::demo::seed_demo_data(&db, &config)?;
+        if !ids.is_empty() {
+            db.audit("desktop", "seed_demo_data", None, serde_json::json!({ "flights": ids }));
+        }
+        Ok(ids)
+    }
+
+    /// Get the currency/recency requirement rules
+    #[tauri::command]
+    pub async fn get_requirements(
+        state: State<'_, AppState>,
+    ) -> Result<Vec<crate::requirements::Requirement>, String> {
+        state
+            .db_authenticated()?
+            .get_requirements()
+            .map_err(|e| format!("Failed to get requirements: {}", e))
+    }
+
+    /// Create or update a requirement rule (an empty id creates a new rule)
+    #[tauri::command]
+    pub async fn save_requirement(
+        requirement: crate::requirements::Requirement,
+        state: State<'_, AppState>,
+    ) -> Result<crate::requirements::Requirement, String> {
+        if requirement.name.trim().is_empty() {
+            return Err("Requirement name cannot be empty".to_string());
+        }
+        if requirement.min_value <= 0.0 || requirement.window_days <= 0 {
+            return Err("Requirement minimum and window must be positive".to_string());
+        }
+        state
+            .db_authenticated()?
+            .save_requirement(&requirement)
+            .map_err(|e| format!("Failed to save requirement: {}", e))
+    }
+
+    /// Delete a requirement rule
+    #[tauri::command]
+    pub async fn delete_requirement(id: String, state: State<'_, AppState>) -> Result<bool, String> {
+        state
+            .db_authenticated()?
+            .delete_requirement(&id)
+            .map_err(|e| format!("Failed to delete requirement: {}", e))?;
+        Ok(true)
+    }
+
+    /// Evaluate every requirement rule against the logbook as of now
     #[tauri::command]
-    pub async fn get_flight_data(
-        flight_id: i64,
-        max_points: Option<usize>,
+    pub async fn evaluate_requirements(
         state: State<'_, AppState>,
-    ) -> Result<FlightDataResponse, String> {
-        let start = std::time::Instant::now();
-        log::debug!("Fetching flight data for ID: {} (max_points: {:?})", flight_id, max_points);
-
+    ) -> Result<Vec<crate::requirements::RequirementStatus>, String> {
         let db = state.db_authenticated()?;
-        let flight = db
-            .get_flight_by_id(flight_id)
-            .map_err(|e| match e {
-                DatabaseError::FlightNotFound(id) => format!("Flight {} not found", id),
-                _ => format!("Failed to get flight: {}", e),
-            })?;
-
-        let known_point_count = flight.point_count.map(|c| c as i64);
-
-        let telemetry_records = db
-            .get_flight_telemetry(flight_id, max_points, known_point_count)
-            .map_err(|e| match e {
-                DatabaseError::FlightNotFound(id) => format!("Flight {} not found", id),
-                _ => format!("Failed to get telemetry: {}", e),
-            })?;
-
-        let telemetry = TelemetryData::from_records(&telemetry_records);
-        let track = telemetry.extract_track(2000);
-
-        // Get flight messages (tips and warnings)
-        let messages = db
-            .get_flight_messages(flight_id)
-            .unwrap_or_else(|e| {
-                log::warn!("Failed to get messages for flight {}: {}", flight_id, e);
-                Vec::new()
-            });
-
-        log::debug!(
-            "get_flight_data for flight {} complete in {:.1}ms: {} telemetry series, {} track points, {} messages",
-            flight_id,
-            start.elapsed().as_secs_f64() * 1000.0,
-            telemetry_records.len(),
-            track.len(),
-            messages.len()
-        );
-
-        Ok(FlightDataResponse {
-            flight,
-            telemetry,
-            track,
-            messages,
-        })
+        let requirements = db
+            .get_requirements()
+            .map_err(|e| format!("Failed to get requirements: {}", e))?;
+        let flights = db
+            .get_all_flights()
+            .map_err(|e| format!("Failed to get flights: {}", e))?;
+        let now = chrono::Utc::now();
+        Ok(requirements
+            .iter()
+            .map(|r| crate::requirements::evaluate(r, &flights, now))
+            .collect())
     }
 
     #[tauri::command]
     pub async fn get_overview_stats(state: State<'_, AppState>) -> Result<OverviewStats, String> {
         let start = std::time::Instant::now();
-        let stats = state
-            .db_authenticated()?
+        let db = state.db_authenticated()?;
+        let mut stats = db
             .get_overview_stats()
             .map_err(|e| format!("Failed to get overview stats: {}", e))?;
+        match crate::battery_retirement::load_recommendations(&db, &state.config_path()) {
+            Ok(recommendations) => stats.battery_retirement = recommendations,
+            Err(e) => log::warn!("Failed to assess battery retirement: {}", e),
+        }
         log::debug!(
             "get_overview_stats complete in {:.1}ms: {} flights, {:.0}m total distance",
             start.elapsed().as_secs_f64() * 1000.0,
@@ -680,6 +2303,43 @@ mod tauri_app {
         Ok(stats)
     }
 
+    /// Get the top flights by a metric (`duration`, `distance`,
+    /// `distance_from_home`, `max_speed`, `max_altitude`, `coldest_temperature`,
+    /// `most_events` or `smoothness`); `limit` defaults to 10 (at most 100)
+    #[tauri::command]
+    pub async fn get_top_flights(
+        metric: String,
+        limit: Option<usize>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<crate::models::RankedFlight>, String> {
+        let metric = crate::models::TopFlightMetric::parse(&metric)
+            .ok_or_else(|| format!("Unknown metric: {}", metric))?;
+        state
+            .db_authenticated()?
+            .get_top_flights(metric, limit.unwrap_or(10).clamp(1, 100))
+            .map_err(|e| format!("Failed to get top flights: {}", e))
+    }
+
+    /// Time spent per band of `metric` (`height` or `speed`) for one flight,
+    /// or all flights when `flight_id` is None
+    #[tauri::command]
+    pub async fn get_histogram(
+        metric: String,
+        flight_id: Option<i64>,
+        bin_width: Option<f64>,
+        state: State<'_, AppState>,
+    ) -> Result<crate::models::Histogram, String> {
+        let metric = crate::models::HistogramMetric::parse(&metric)
+            .ok_or_else(|| format!("Unknown metric: {}", metric))?;
+        let bin_width = metric
+            .bin_width(bin_width)
+            .ok_or_else(|| "bin_width must be at least 0.1".to_string())?;
+        state
+            .db_authenticated()?
+            .get_histogram(metric, flight_id, bin_width)
+            .map_err(|e| format!("Failed to get histogram: {}", e))
+    }
+
     #[tauri::command]
     pub async fn get_battery_full_capacity_history(
         battery_serial: String,
@@ -694,30 +2354,36 @@ mod tauri_app {
     #[tauri::command]
     pub async fn delete_flight(flight_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
         log::info!("Deleting flight: {}", flight_id);
-        state
-            .db_authenticated()?
-            .delete_flight(flight_id)
-            .map(|_| true)
-            .map_err(|e| format!("Failed to delete flight: {}", e))
+        let db = state.db_authenticated()?;
+        let name = db.get_flight_by_id(flight_id).ok().map(|f| f.display_name);
+        db.delete_flight(flight_id)
+            .map_err(|e| format!("Failed to delete flight: {}", e))?;
+        db.audit("desktop", "delete_flight", Some(&flight_id.to_string()), serde_json::json!({ "name": name }));
+        Ok(true)
     }
 
     #[tauri::command]
     pub async fn delete_all_flights(state: State<'_, AppState>) -> Result<bool, String> {
         log::warn!("Deleting ALL flights and telemetry");
-        state
-            .db_authenticated()?
-            .delete_all_flights()
-            .map(|_| true)
-            .map_err(|e| format!("Failed to delete all flights: {}", e))
+        let db = state.db_authenticated()?;
+        let count = db.get_all_flights().map(|f| f.len()).unwrap_or(0);
+        db.delete_all_flights()
+            .map_err(|e| format!("Failed to delete all flights: {}", e))?;
+        db.audit("desktop", "delete_all_flights", None, serde_json::json!({ "flights": count }));
+        Ok(true)
     }
 
     #[tauri::command]
     pub async fn deduplicate_flights(state: State<'_, AppState>) -> Result<usize, String> {
         log::info!("Running flight deduplication");
-        state
-            .db_authenticated()?
+        let db = state.db_authenticated()?;
+        let removed = db
             .deduplicate_flights()
-            .map_err(|e| format!("Failed to deduplicate flights: {}", e))
+            .map_err(|e| format!("Failed to deduplicate flights: {}", e))?;
+        if removed > 0 {
+            db.audit("desktop", "deduplicate_flights", None, serde_json::json!({ "removed": removed }));
+        }
+        Ok(removed)
     }
 
     #[tauri::command]
@@ -733,11 +2399,17 @@ mod tauri_app {
 
         log::info!("Renaming flight {} to '{}'", flight_id, trimmed);
 
-        state
-            .db_authenticated()?
-            .update_flight_name(flight_id, trimmed)
-            .map(|_| true)
-            .map_err(|e| format!("Failed to update flight name: {}", e))
+        let db = state.db_authenticated()?;
+        let old_name = db.get_flight_by_id(flight_id).ok().map(|f| f.display_name);
+        db.update_flight_name(flight_id, trimmed)
+            .map_err(|e| format!("Failed to update flight name: {}", e))?;
+        db.audit(
+            "desktop",
+            "rename_flight",
+            Some(&flight_id.to_string()),
+            serde_json::json!({ "from": old_name, "to": trimmed }),
+        );
+        Ok(true)
     }
 
     #[tauri::command]
@@ -780,6 +2452,34 @@ mod tauri_app {
             .map_err(|e| format!("Failed to update flight color: {}", e))
     }
 
+    #[tauri::command]
+    pub async fn update_flight_metadata(
+        flight_id: i64,
+        update: FlightMetadataUpdate,
+        state: State<'_, AppState>,
+    ) -> Result<Flight, String> {
+        update.validate()?;
+        log::info!("Updating metadata for flight {}", flight_id);
+
+        let db = state.db_authenticated()?;
+        let flight = db
+            .update_flight_metadata(flight_id, &update)
+            .map_err(|e| format!("Failed to update flight metadata: {}", e))?;
+        db.audit(
+            "desktop",
+            "edit_flight_metadata",
+            Some(&flight_id.to_string()),
+            serde_json::json!({
+                "droneModel": update.drone_model,
+                "batterySerial": update.battery_serial,
+                "pilot": update.pilot,
+                "locationName": update.location_name,
+                "timeOffsetSecs": update.time_offset_secs,
+            }),
+        );
+        Ok(flight)
+    }
+
     #[tauri::command]
     pub async fn has_api_key(state: State<'_, AppState>) -> Result<bool, String> {
         let api = DjiApi::with_app_data_dir(state.data_dir.clone());
@@ -796,16 +2496,18 @@ mod tauri_app {
     pub async fn set_api_key(api_key: String, state: State<'_, AppState>) -> Result<bool, String> {
         let api = DjiApi::with_app_data_dir(state.data_dir.clone());
         api.save_api_key(&api_key)
-            .map(|_| true)
-            .map_err(|e| format!("Failed to save API key: {}", e))
+            .map_err(|e| format!("Failed to save API key: {}", e))?;
+        state.audit("settings_change", Some("api_key"), serde_json::json!({ "set": true }));
+        Ok(true)
     }
 
     #[tauri::command]
     pub async fn remove_api_key(state: State<'_, AppState>) -> Result<bool, String> {
         let api = DjiApi::with_app_data_dir(state.data_dir.clone());
         api.remove_api_key()
-            .map(|_| true)
-            .map_err(|e| format!("Failed to remove API key: {}", e))
+            .map_err(|e| format!("Failed to remove API key: {}", e))?;
+        state.audit("settings_change", Some("api_key"), serde_json::json!({ "set": false }));
+        Ok(true)
     }
 
     #[tauri::command]
@@ -813,6 +2515,155 @@ mod tauri_app {
         Ok(state.data_dir.to_string_lossy().to_string())
     }
 
+    /// Move the data directory (databases, keychains, configs, uploaded logs,
+    /// attachments) to `new_path`. Files are copied and verified before the app
+    /// switches over; the app then restarts on the new folder. With `remove_old`
+    /// the old copy is deleted on that restart.
+    #[tauri::command]
+    pub async fn move_data_dir(
+        new_path: String,
+        remove_old: Option<bool>,
+        app: AppHandle,
+        state: State<'_, AppState>,
+    ) -> Result<crate::data_dir::RelocationReport, String> {
+        let new_path = PathBuf::from(new_path.trim());
+        if !new_path.is_absolute() {
+            return Err("The new data folder must be an absolute path".to_string());
+        }
+        let db = state.db_authenticated()?;
+        db.checkpoint().map_err(|e| format!("Failed to checkpoint database: {}", e))?;
+
+        let default_dir = app_data_dir_path(&app)?;
+        // The session marker must not travel with the data (the restart is a clean exit)
+        crate::crash::mark_clean_exit(&state.data_dir);
+        let report = crate::data_dir::relocate(&default_dir, &state.data_dir, &new_path, remove_old.unwrap_or(false))
+            .map_err(|e| e.to_string())?;
+        log::info!("Data directory moved to {}, restarting", report.to);
+
+        // Give the frontend time to receive the report before restarting
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            app.restart();
+        });
+        Ok(report)
+    }
+
+    /// Whether the database is a read-only snapshot because another process
+    /// (e.g. a second app instance) holds its lock
+    #[tauri::command]
+    pub async fn get_db_lock_status(state: State<'_, AppState>) -> Result<database::DbLockStatus, String> {
+        Ok(state.db().lock_status())
+    }
+
+    /// Recoveries of the database that haven't been dismissed, and the restore
+    /// scheduled for the next start
+    #[tauri::command]
+    pub async fn get_recovery_status(state: State<'_, AppState>) -> Result<crate::recovery::RecoveryStatus, String> {
+        Ok(crate::recovery::status(state.db().path()))
+    }
+
+    /// Hide a recovery report (its copies are kept)
+    #[tauri::command]
+    pub async fn dismiss_recovery_report(id: String, state: State<'_, AppState>) -> Result<bool, String> {
+        crate::recovery::dismiss(state.db().path(), &id).map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+
+    /// Restore a recovery copy of the database; the app restarts to do it
+    #[tauri::command]
+    pub async fn restore_recovery_copy(id: String, app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+        let db = state.db_repair()?;
+        crate::recovery::schedule_restore(db.path(), &id).map_err(|e| e.to_string())?;
+        state.audit("restore_recovery_copy", Some(&id), serde_json::json!({ "database": db.path().to_string_lossy() }));
+        // The database being replaced is copied aside on the restart; flush it first
+        if let Err(e) = db.checkpoint() {
+            log::warn!("Checkpoint before restore failed (non-fatal): {}", e);
+        }
+        app.restart();
+    }
+
+    /// Recovery copies, snapshots and `.bak` files the database can be restored
+    /// from; `prompt` is set when the database is empty and there is at least one
+    #[tauri::command]
+    pub async fn list_recovery_candidates(state: State<'_, AppState>) -> Result<crate::recovery::RecoveryCandidates, String> {
+        let db = state.db_repair()?;
+        let flight_count = db.flight_count().map_err(|e| e.to_string())?;
+        Ok(crate::recovery::list_candidates(db.path(), None, flight_count))
+    }
+
+    /// Restore a candidate from `list_recovery_candidates`; the app restarts to do it
+    #[tauri::command]
+    pub async fn restore_recovery_candidate(id: String, app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+        let db = state.db_repair()?;
+        let candidate = crate::recovery::find_candidate(db.path(), None, &id).map_err(|e| e.to_string())?;
+        crate::recovery::schedule_candidate_restore(db.path(), &candidate).map_err(|e| e.to_string())?;
+        state.audit("restore_recovery_candidate", Some(&id), serde_json::json!({ "path": candidate.path }));
+        if let Err(e) = db.checkpoint() {
+            log::warn!("Checkpoint before restore failed (non-fatal): {}", e);
+        }
+        app.restart();
+    }
+
+    /// Database file and WAL size, and the last idle checkpoint / compaction
+    #[tauri::command]
+    pub async fn get_database_info(state: State<'_, AppState>) -> Result<crate::db_maintenance::DatabaseInfo, String> {
+        Ok(crate::db_maintenance::database_info(&state.db()))
+    }
+
+    /// Settings of the read-only database mirror
+    #[tauri::command]
+    pub async fn get_db_mirror_settings(state: State<'_, AppState>) -> Result<crate::db_mirror::MirrorSettings, String> {
+        Ok(crate::db_mirror::MirrorSettings::load(&state.config_path()))
+    }
+
+    /// Enable the scheduled mirror to a file, or disable it
+    #[tauri::command]
+    pub async fn set_db_mirror_settings(
+        settings: crate::db_mirror::MirrorSettings,
+        state: State<'_, AppState>,
+    ) -> Result<crate::db_mirror::MirrorSettings, String> {
+        state.db_authenticated()?;
+        let saved = crate::db_mirror::save_settings(&state.config_path(), settings, true)?;
+        state.audit(
+            "settings_change",
+            Some("db_mirror"),
+            serde_json::json!({ "enabled": saved.enabled, "path": saved.path, "intervalMinutes": saved.interval_minutes }),
+        );
+        Ok(saved)
+    }
+
+    /// Publish the mirror now, to `path` or the configured file
+    #[tauri::command]
+    pub async fn publish_db_mirror(
+        path: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<crate::db_mirror::MirrorReport, String> {
+        let db = state.db_authenticated()?;
+        let dest = path
+            .or_else(|| crate::db_mirror::MirrorSettings::load(&state.config_path()).path)
+            .ok_or_else(|| "Choose a file for the mirror".to_string())?;
+        let report = crate::db_mirror::publish(&db, std::path::Path::new(&dest))?;
+        state.audit("publish_db_mirror", Some(&report.path), serde_json::json!({ "tables": report.tables }));
+        Ok(report)
+    }
+
+    /// DuckDB offline mode and the loaded / installed extensions
+    #[tauri::command]
+    pub async fn get_duckdb_extensions(state: State<'_, AppState>) -> Result<crate::duckdb_extensions::ExtensionStatus, String> {
+        crate::duckdb_extensions::status(&state.db_repair()?, &state.config_path()).map_err(|e| e.to_string())
+    }
+
+    /// Switch DuckDB's offline mode (no extension downloads) on or off
+    #[tauri::command]
+    pub async fn set_duckdb_offline_mode(
+        enabled: bool,
+        state: State<'_, AppState>,
+    ) -> Result<crate::duckdb_extensions::ExtensionStatus, String> {
+        let status = crate::duckdb_extensions::set_offline(&state.db_repair()?, &state.config_path(), enabled)?;
+        state.audit("set_duckdb_offline_mode", None, serde_json::json!({ "enabled": enabled }));
+        Ok(status)
+    }
+
     #[tauri::command]
     pub async fn get_app_log_dir(app: AppHandle) -> Result<String, String> {
         app.path()
@@ -821,6 +2672,97 @@ mod tauri_app {
             .map(|dir| dir.to_string_lossy().to_string())
     }
 
+    /// Whether the app started in safe mode, and why
+    #[tauri::command]
+    pub async fn get_safe_mode_status(state: State<'_, AppState>) -> Result<crate::safe_mode::SafeModeStatus, String> {
+        Ok(state.safe_mode.clone())
+    }
+
+    /// Run a safe-mode repair action (see `safe_mode::repair`)
+    #[tauri::command]
+    pub async fn run_safe_mode_repair(action: String, state: State<'_, AppState>) -> Result<String, String> {
+        let message = crate::safe_mode::repair(&state.db_repair()?, &action)?;
+        state.audit("safe_mode_repair", Some(&action), serde_json::json!({ "result": message }));
+        Ok(message)
+    }
+
+    /// Leave safe mode and restart normally
+    #[tauri::command]
+    pub async fn leave_safe_mode(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+        crate::safe_mode::leave(&state.data_dir);
+        crate::crash::mark_clean_exit(&state.data_dir);
+        if let Err(e) = state.db().checkpoint() {
+            log::warn!("Checkpoint before leaving safe mode failed (non-fatal): {}", e);
+        }
+        app.restart();
+    }
+
+    /// Whether crash reporting is enabled
+    #[tauri::command]
+    pub async fn get_crash_reporting_enabled(state: State<'_, AppState>) -> Result<bool, String> {
+        Ok(crate::crash::is_enabled(&state.data_dir))
+    }
+
+    /// Opt in or out of crash reporting (takes effect at the next start)
+    #[tauri::command]
+    pub async fn set_crash_reporting_enabled(enabled: bool, state: State<'_, AppState>) -> Result<bool, String> {
+        crate::crash::set_enabled(&state.data_dir, enabled)?;
+        state.audit("settings_change", Some("crash_reporting"), serde_json::json!({ "enabled": enabled }));
+        Ok(enabled)
+    }
+
+    /// Stored crash reports, newest first, with prefilled GitHub issue links
+    #[tauri::command]
+    pub async fn get_crash_reports(state: State<'_, AppState>) -> Result<Vec<crate::crash::CrashReportEntry>, String> {
+        Ok(crate::crash::list_reports(&state.data_dir))
+    }
+
+    /// Delete one crash report, or all of them when no file name is given
+    #[tauri::command]
+    pub async fn delete_crash_reports(file_name: Option<String>, state: State<'_, AppState>) -> Result<usize, String> {
+        crate::crash::delete_reports(&state.data_dir, file_name.as_deref())
+    }
+
+    /// Current runtime log level
+    #[tauri::command]
+    pub async fn get_log_level() -> Result<String, String> {
+        Ok(crate::support::current_level())
+    }
+
+    /// Change the runtime log level (kept across restarts)
+    #[tauri::command]
+    pub async fn set_log_level(level: String, state: State<'_, AppState>) -> Result<String, String> {
+        let level = crate::support::set_level(&state.data_dir, &level)?;
+        state.audit("settings_change", Some("log_level"), serde_json::json!({ "level": level }));
+        Ok(level)
+    }
+
+    /// Last lines of the current log file
+    #[tauri::command]
+    pub async fn tail_log(lines: Option<usize>, app: AppHandle) -> Result<crate::support::LogTail, String> {
+        let log_dir = app
+            .path()
+            .app_log_dir()
+            .map_err(|e| format!("Failed to get app log directory: {}", e))?;
+        crate::support::tail(&log_dir, lines.unwrap_or(crate::support::DEFAULT_TAIL_LINES))
+            .map_err(|e| format!("Failed to read log: {}", e))
+    }
+
+    /// Write a support bundle (logs, system/database info, redacted settings) to `dest_path`
+    #[tauri::command]
+    pub async fn export_support_bundle(dest_path: String, app: AppHandle, state: State<'_, AppState>) -> Result<bool, String> {
+        let log_dir = app
+            .path()
+            .app_log_dir()
+            .map_err(|e| format!("Failed to get app log directory: {}", e))?;
+        let profile = database::get_active_profile(&state.data_dir);
+        let bundle = crate::support::build_bundle(&state.db(), &profile, &state.config_path(), &log_dir)
+            .map_err(|e| format!("Failed to build support bundle: {}", e))?;
+        std::fs::write(&dest_path, bundle.bytes).map_err(|e| format!("Failed to write support bundle: {}", e))?;
+        log::info!("Exported support bundle to {}", dest_path);
+        Ok(true)
+    }
+
     #[tauri::command]
     pub async fn get_equipment_names(state: State<'_, AppState>) -> Result<(Vec<(String, String)>, Vec<(String, String)>), String> {
         state.db_authenticated()?.get_all_equipment_names()
@@ -834,18 +2776,27 @@ mod tauri_app {
         display_name: String,
         state: State<'_, AppState>,
     ) -> Result<bool, String> {
-        state.db_authenticated()?.set_equipment_name(&serial, &equipment_type, &display_name)
-            .map(|_| true)
-            .map_err(|e| format!("Failed to set equipment name: {}", e))
+        let db = state.db_authenticated()?;
+        db.set_equipment_name(&serial, &equipment_type, &display_name)
+            .map_err(|e| format!("Failed to set equipment name: {}", e))?;
+        db.audit(
+            "desktop",
+            "settings_change",
+            Some("equipment_name"),
+            serde_json::json!({ "serial": serial, "type": equipment_type, "name": display_name }),
+        );
+        Ok(true)
     }
 
+    /// Export a backup; `scrub` hashes serials, shifts coordinates and leaves
+    /// out identifying tables so the backup can be shared for debugging
     #[tauri::command]
-    pub async fn export_backup(dest_path: String, state: State<'_, AppState>) -> Result<bool, String> {
+    pub async fn export_backup(dest_path: String, scrub: Option<bool>, state: State<'_, AppState>) -> Result<bool, String> {
         let path = std::path::PathBuf::from(&dest_path);
         log::info!("Exporting database backup to: {}", dest_path);
         state
-            .db_authenticated()?
-            .export_backup(&path)
+            .db_repair()?
+            .export_backup(&path, scrub.unwrap_or(false))
             .map(|_| true)
             .map_err(|e| format!("Failed to export backup: {}", e))
     }
@@ -854,10 +2805,12 @@ mod tauri_app {
     pub async fn import_backup(src_path: String, state: State<'_, AppState>) -> Result<String, String> {
         let path = std::path::PathBuf::from(&src_path);
         log::info!("Importing database backup from: {}", src_path);
-        state
-            .db_authenticated()?
+        let db = state.db_repair()?;
+        let message = db
             .import_backup(&path)
-            .map_err(|e| format!("Failed to import backup: {}", e))
+            .map_err(|e| format!("Failed to import backup: {}", e))?;
+        db.audit("desktop", "restore_backup", Some(&src_path), serde_json::json!({ "result": message }));
+        Ok(message)
     }
 
     #[tauri::command]
@@ -924,6 +2877,7 @@ mod tauri_app {
         config["smart_tags_enabled"] = serde_json::json!(enabled);
         std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
             .map_err(|e| format!("Failed to write config: {}", e))?;
+        state.audit("settings_change", Some("smart_tags_enabled"), serde_json::json!({ "value": enabled }));
         Ok(enabled)
     }
 
@@ -963,6 +2917,7 @@ mod tauri_app {
         config["enabled_tag_types"] = serde_json::json!(types.clone());
         std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
             .map_err(|e| format!("Failed to write config: {}", e))?;
+        state.audit("settings_change", Some("enabled_tag_types"), serde_json::json!({ "value": types }));
         Ok(types)
     }
 
@@ -1014,6 +2969,11 @@ mod tauri_app {
         
         std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
             .map_err(|e| format!("Failed to write config: {}", e))?;
+        state.audit(
+            "settings_change",
+            Some("keep_uploaded_files"),
+            serde_json::json!({ "enabled": enabled, "folder": actual_folder }),
+        );
         
         Ok(KeepUploadSettings { enabled, folder_path: actual_folder })
     }
@@ -1044,6 +3004,7 @@ mod tauri_app {
         config["auto_logout"] = serde_json::json!(enabled);
         std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
             .map_err(|e| format!("Failed to write config: {}", e))?;
+        state.audit("settings_change", Some("auto_logout"), serde_json::json!({ "value": enabled }));
         Ok(enabled)
     }
 
@@ -1161,6 +3122,10 @@ mod tauri_app {
                 if let Some(ref types) = enabled_tag_types {
                     tags = LogParser::filter_smart_tags(tags, types);
                 }
+                let overrides = crate::locations::load(&db)
+                    .map_err(|e| format!("Failed to load location overrides: {}", e))?;
+                let tags = crate::locations::apply_to_flight(&db, &overrides, &flight, tags, stats.home_location)
+                    .map_err(|e| format!("Failed to apply location overrides for flight {}: {}", flight_id, e))?;
                 db.replace_auto_tags(flight_id, &tags)
                     .map_err(|e| format!("Failed to replace tags for flight {}: {}", flight_id, e))?;
             }
@@ -1189,6 +3154,8 @@ mod tauri_app {
         let _total = flight_ids.len();
         let mut processed = 0usize;
         let mut errors = 0usize;
+        let overrides = crate::locations::load(&db)
+            .map_err(|e| format!("Failed to load location overrides: {}", e))?;
 
         for flight_id in &flight_ids {
             match db.get_flight_by_id(*flight_id) {
@@ -1230,7 +3197,9 @@ mod tauri_app {
                         Ok(records) if !records.is_empty() => {
                             let stats = calculate_stats_from_records(&records);
                             let tags = LogParser::generate_smart_tags(&metadata, &stats);
-                            if let Err(e) = db.replace_auto_tags(*flight_id, &tags) {
+                            let result = crate::locations::apply_to_flight(&db, &overrides, &flight, tags, stats.home_location)
+                                .and_then(|tags| db.replace_auto_tags(*flight_id, &tags));
+                            if let Err(e) = result {
                                 log::warn!("Failed to replace tags for flight {}: {}", flight_id, e);
                                 errors += 1;
                             }
@@ -1262,6 +3231,93 @@ mod tauri_app {
         Ok(msg)
     }
 
+    /// Rerun the post-import hook pipeline on an existing flight
+    #[tauri::command]
+    pub async fn reprocess_flight(
+        flight_id: i64,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<crate::hooks::HookOutcome>, String> {
+        let db = state.db_authenticated()?;
+        let config_path = state.config_path();
+        let config: serde_json::Value = std::fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+        log::info!("Reprocessing flight {}", flight_id);
+        crate::hooks::run_pipeline(&db, flight_id, &config, crate::hooks::HookTrigger::Reprocess)
+    }
+
+    /// Re-parse a flight from its original log with the current parsers and
+    /// report what changed (points added, fields newly populated, stats)
+    #[tauri::command]
+    pub async fn reparse_flight(
+        flight_id: i64,
+        file_path: String,
+        state: State<'_, AppState>,
+    ) -> Result<crate::reparse::ReparseDiff, String> {
+        let path = PathBuf::from(&file_path);
+        if !path.exists() {
+            return Err("File not found".to_string());
+        }
+        let db = state.db_authenticated()?;
+        let config: serde_json::Value = std::fs::read_to_string(state.config_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+        log::info!("Re-parsing flight {} from {}", flight_id, file_path);
+        let diff = crate::reparse::reparse_flight(&db, flight_id, &path, &config).await?;
+        db.audit(
+            "desktop",
+            "reparse",
+            Some(&flight_id.to_string()),
+            serde_json::json!({ "file": file_path, "diff": diff }),
+        );
+        Ok(diff)
+    }
+
+    /// Recompute distance, max speed, max altitude and duration from stored
+    /// telemetry, for one flight or all of them (`flight_id` None)
+    #[tauri::command]
+    pub async fn recompute_flight_stats(
+        flight_id: Option<i64>,
+        state: State<'_, AppState>,
+    ) -> Result<crate::recompute::RecomputeSummary, String> {
+        let db = state.db_authenticated()?;
+        let summary = crate::recompute::recompute_flights(&db, flight_id.map(|id| vec![id]))?;
+        let target = flight_id.map_or_else(|| "all".to_string(), |id| id.to_string());
+        db.audit(
+            "desktop",
+            "recompute_stats",
+            Some(&target),
+            serde_json::json!({ "processed": summary.processed, "changed": summary.changes }),
+        );
+        Ok(summary)
+    }
+
+    /// List the registered post-import hooks and whether each is enabled
+    #[tauri::command]
+    pub async fn get_post_import_hooks(state: State<'_, AppState>) -> Result<Vec<crate::hooks::HookInfo>, String> {
+        let db = state.db_authenticated()?;
+        Ok(crate::hooks::list_hooks(&db))
+    }
+
+    #[tauri::command]
+    pub async fn set_post_import_hook_enabled(
+        hook_id: String,
+        enabled: bool,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        let db = state.db_authenticated()?;
+        crate::hooks::set_hook_enabled(&db, &hook_id, enabled)?;
+        db.audit(
+            "desktop",
+            "settings_change",
+            Some(&format!("post_import_hook.{}", hook_id)),
+            serde_json::json!({ "value": enabled }),
+        );
+        Ok(())
+    }
+
     // ========================================================================
     // PROFILE MANAGEMENT COMMANDS
     // ========================================================================
@@ -1315,6 +3371,7 @@ mod tauri_app {
         create: bool,
         password: Option<String>,
         new_password: Option<String>,
+        app: AppHandle,
         state: State<'_, AppState>,
     ) -> Result<String, String> {
         let profile = name.trim().to_string();
@@ -1356,8 +3413,12 @@ mod tauri_app {
         }
 
         // Create / open the target database
-        let new_db = Database::new(state.data_dir.clone(), &profile)
-            .map_err(|e| format!("Failed to open profile '{}': {}", profile, e))?;
+        let new_db = if state.safe_mode.active {
+            Database::open_safe_mode(state.data_dir.clone(), &profile)
+        } else {
+            Database::new(state.data_dir.clone(), &profile)
+        };
+        let new_db = new_db.map_err(|e| format!("Failed to open profile '{}': {}", profile, e))?;
 
         // Swap the active database
         state.swap_db(new_db);
@@ -1378,6 +3439,11 @@ mod tauri_app {
             }
         }
 
+        // Resume imports queued in the profile switched to
+        if !state.safe_mode.active && state.db().has_queued_imports().unwrap_or(false) {
+            spawn_import_queue(app);
+        }
+
         log::info!("Switched to profile '{}'", profile);
         Ok(profile)
     }
@@ -1555,10 +3621,10 @@ mod tauri_app {
         if trimmed.is_empty() {
             return Err("Setting key cannot be empty".to_string());
         }
-        state
-            .db_authenticated()?
-            .set_setting(trimmed, &value)
+        let db = state.db_authenticated()?;
+        db.set_setting(trimmed, &value)
             .map_err(|e| format!("Failed to save setting: {}", e))?;
+        db.audit("desktop", "settings_change", Some(trimmed), serde_json::json!({ "value": value }));
         Ok(true)
     }
 
@@ -1578,7 +3644,8 @@ mod tauri_app {
                         Target::new(TargetKind::LogDir { file_name: None }),
                         Target::new(TargetKind::Stdout),
                     ])
-                    .level(LevelFilter::Debug)
+                    // Filtered at runtime by the saved level (see `support`)
+                    .level(LevelFilter::Trace)
                     .build(),
             )
             .plugin(tauri_plugin_shell::init())
@@ -1586,9 +3653,13 @@ mod tauri_app {
             .plugin(tauri_plugin_fs::init())
             .plugin(tauri_plugin_http::init())
             .plugin(tauri_plugin_window_state::Builder::new().build())
+            .plugin(tauri_plugin_notification::init())
             .setup(|app| {
-                let data_dir = app_data_dir_path(app.handle())?;
-                let db = init_database(app.handle())?;
+                let data_dir = resolve_data_dir(app.handle())?;
+                crate::support::apply_saved_level(&data_dir);
+                crate::crash::install(&data_dir, app.path().app_log_dir().ok().as_deref());
+                let safe_mode = crate::safe_mode::begin_startup(&data_dir);
+                let db = init_database(app.handle(), data_dir.clone(), safe_mode.active)?;
 
                 // Determine if the app should start locked.
                 // Lock when the active profile has a password AND auto_logout is enabled.
@@ -1617,6 +3688,7 @@ mod tauri_app {
                     active_db: RwLock::new(Arc::new(db)),
                     data_dir,
                     locked: RwLock::new(start_locked),
+                    safe_mode: safe_mode.clone(),
                 });
 
                 if let Some(state) = app.try_state::<AppState>() {
@@ -1625,6 +3697,52 @@ mod tauri_app {
                     log::info!("Allowed import extensions at startup: {:?}", allowed_extensions);
                 }
 
+                // Show notifications for background events in the system notification centre
+                let notify_handle = app.handle().clone();
+                crate::notifications::set_delivery(Box::new(move |_db, notification| {
+                    use tauri_plugin_notification::NotificationExt;
+                    if let Err(e) = notify_handle
+                        .notification()
+                        .builder()
+                        .title(&notification.title)
+                        .body(&notification.body)
+                        .show()
+                    {
+                        log::warn!("Failed to show notification: {}", e);
+                    }
+                }));
+
+                // Resume imports queued before the last shutdown
+                if !safe_mode.active && app.state::<AppState>().db().has_queued_imports().unwrap_or(false) {
+                    spawn_import_queue(app.handle().clone());
+                }
+
+                // Checkpoint and compact the database while the app is idle
+                if !safe_mode.active {
+                    let maintenance_handle = app.handle().clone();
+                    tauri::async_runtime::spawn(crate::db_maintenance::run_scheduler(move || {
+                        vec![maintenance_handle.state::<AppState>().db()]
+                    }));
+
+                    // Publish the read-only mirror when it is enabled
+                    let mirror_handle = app.handle().clone();
+                    tauri::async_runtime::spawn(crate::db_mirror::run_scheduler(move || {
+                        let state = mirror_handle.state::<AppState>();
+                        let settings = crate::db_mirror::MirrorSettings::load(&state.config_path());
+                        match (settings.path.filter(|_| settings.enabled), state.db_authenticated()) {
+                            (Some(path), Ok(db)) => vec![(db, std::path::PathBuf::from(path), settings.interval_minutes)],
+                            _ => Vec::new(),
+                        }
+                    }));
+                }
+
+                // The start counts as successful once the app has kept running for a while
+                let stable_dir = app.state::<AppState>().data_dir.clone();
+                tauri::async_runtime::spawn(async move {
+                    tokio::time::sleep(crate::safe_mode::STABLE_AFTER).await;
+                    crate::safe_mode::mark_stable(&stable_dir, &safe_mode);
+                });
+
                 log::info!("Open DroneLog initialized successfully");
                 Ok(())
             })
@@ -1664,16 +3782,118 @@ mod tauri_app {
             })
             .invoke_handler(tauri::generate_handler![
                 import_log,
+                enqueue_imports,
+                get_import_queue,
+                clear_import_queue,
                 create_manual_flight,
+                fuse_dat_log,
                 compute_file_hash,
                 get_allowed_log_extensions,
+                detect_log_sources,
                 get_sync_blacklist,
                 add_to_sync_blacklist,
                 remove_from_sync_blacklist,
                 clear_sync_blacklist,
                 get_flights,
+                get_flight_changes,
                 get_flight_data,
+                get_import_report,
+                get_rth_events,
+                get_null_telemetry_columns,
+                get_flight_previews,
+                get_exceedance_limits,
+                set_exceedance_limits,
+                get_altitude_reference,
+                set_altitude_reference,
+                get_duration_rounding,
+                set_duration_rounding,
+                get_unit_settings,
+                set_unit_settings,
+                get_requirements,
+                save_requirement,
+                delete_requirement,
+                evaluate_requirements,
+                add_attachment,
+                get_attachments,
+                get_attachment_path,
+                delete_attachment,
+                get_checklist_templates,
+                save_checklist_template,
+                delete_checklist_template,
+                record_checklist,
+                get_checklist_records,
+                delete_checklist_record,
                 get_overview_stats,
+                get_top_flights,
+                get_histogram,
+                export_stats,
+                get_export_templates,
+                save_export_template,
+                delete_export_template,
+                export_with_template,
+                get_year_review,
+                get_activity_calendar,
+                get_activity_stats,
+                render_flight_image,
+                export_flight_bundle,
+                export_chase,
+                get_privacy_settings,
+                set_privacy_settings,
+                get_filename_rules,
+                set_filename_rules,
+                get_derived_metrics,
+                set_derived_metrics,
+                get_preflight_briefing,
+                get_preflight_settings,
+                set_preflight_settings,
+                get_cloud_push_settings,
+                set_cloud_push_settings,
+                get_sharing_settings,
+                set_sharing_settings,
+                share_flight,
+                set_locale,
+                push_flight_to_cloud,
+                attach_flight_plan,
+                attach_manual_track,
+                get_flight_plan,
+                delete_flight_plan,
+                get_fleet_overview,
+                get_endurance_benchmarks,
+                get_maintenance_records,
+                get_battery_retirement,
+                get_battery_retirement_settings,
+                set_battery_retirement_settings,
+                get_notification_settings,
+                set_notification_settings,
+                send_notification,
+                get_import_diagnostics,
+                save_maintenance_record,
+                delete_maintenance_record,
+                get_airframe_seeds,
+                set_airframe_seed,
+                get_place_stats,
+                get_location_overrides,
+                set_location_overrides,
+                get_jobs,
+                save_job,
+                delete_job,
+                assign_flights_to_job,
+                get_job_stats,
+                export_job,
+                get_invoice_report,
+                export_invoice_report,
+                get_operator_log,
+                export_operator_log,
+                export_flight_list,
+                get_overflight_report,
+                run_sql_query,
+                get_operator_settings,
+                set_operator_settings,
+                get_audit_log,
+                get_integrity_chain,
+                verify_integrity,
+                seal_integrity_chain,
+                seed_demo_data,
                 get_battery_full_capacity_history,
                 delete_flight,
                 delete_all_flights,
@@ -1681,12 +3901,37 @@ mod tauri_app {
                 update_flight_name,
                 update_flight_notes,
                 update_flight_color,
+                update_flight_metadata,
                 has_api_key,
                 get_api_key_type,
                 set_api_key,
                 remove_api_key,
                 get_app_data_dir,
+                get_db_lock_status,
+                get_database_info,
+                get_db_mirror_settings,
+                set_db_mirror_settings,
+                publish_db_mirror,
+                get_duckdb_extensions,
+                set_duckdb_offline_mode,
+                get_recovery_status,
+                dismiss_recovery_report,
+                restore_recovery_copy,
+                list_recovery_candidates,
+                restore_recovery_candidate,
+                move_data_dir,
                 get_app_log_dir,
+                get_safe_mode_status,
+                run_safe_mode_repair,
+                leave_safe_mode,
+                get_crash_reporting_enabled,
+                set_crash_reporting_enabled,
+                get_crash_reports,
+                delete_crash_reports,
+                get_log_level,
+                set_log_level,
+                tail_log,
+                export_support_bundle,
                 get_equipment_names,
                 set_equipment_name,
                 export_backup,
@@ -1706,6 +3951,11 @@ mod tauri_app {
                 unlock_profile,
                 is_app_locked,
                 regenerate_flight_smart_tags,
+                reprocess_flight,
+                reparse_flight,
+                recompute_flight_stats,
+                get_post_import_hooks,
+                set_post_import_hook_enabled,
                 regenerate_all_smart_tags,
                 list_profiles,
                 get_active_profile,
@@ -1721,8 +3971,16 @@ mod tauri_app {
                 get_setting_value,
                 set_setting_value,
             ])
-            .run(tauri::generate_context!())
-            .expect("Failed to run Open DroneLog");
+            .build(tauri::generate_context!())
+            .expect("Failed to run Open DroneLog")
+            .run(|app, event| {
+                if let tauri::RunEvent::Exit = event {
+                    if let Some(state) = app.try_state::<AppState>() {
+                        crate::crash::mark_clean_exit(&state.data_dir);
+                        crate::safe_mode::mark_stable(&state.data_dir, &state.safe_mode);
+                    }
+                }
+            });
     }
 }
 
@@ -1732,9 +3990,6 @@ mod tauri_app {
 
 #[cfg(all(feature = "web", not(feature = "tauri-app")))]
 async fn run_web() {
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug"))
-        .init();
-
     let data_dir = std::env::var("DATA_DIR")
         .map(std::path::PathBuf::from)
         .unwrap_or_else(|_| {
@@ -1743,10 +3998,24 @@ async fn run_web() {
                 .join("drone-logbook")
         });
 
+    // Log to stdout and logs/ in the data dir; RUST_LOG caps what the runtime
+    // level (see `support`) can show
+    let log_file = crate::support::LogTee::open(&crate::support::web_log_dir(&data_dir));
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("trace"))
+        .target(env_logger::Target::Pipe(Box::new(log_file)))
+        .init();
+    crate::support::apply_saved_level(&data_dir);
+
+    // Export tracing spans when OTEL_EXPORTER_OTLP_ENDPOINT is set
+    #[cfg(feature = "otel")]
+    let otel_guard = crate::otel::init();
+
     log::info!("Data directory: {:?}", data_dir);
 
     if let Err(e) = server::start_server(data_dir).await {
         log::error!("Server failed: {}", e);
+        #[cfg(feature = "otel")]
+        drop(otel_guard);
         std::process::exit(1);
     }
 }
@@ -0,0 +1,60 @@
+//! GPX tracks attached to manual flights.
+//!
+//! A manual entry has no telemetry, so it shows no map path and only the
+//! distance typed in by hand. A GPX track recorded alongside the flight (sport
+//! watch, phone app) can be attached to it: the track goes through the same
+//! GPX reader as the app's own GPX exports, is stored as the flight's
+//! telemetry (position and elevation only) and the distance, max altitude and
+//! max speed are recomputed from it. The entered start time, duration and
+//! takeoff point are kept. Attaching another track replaces the previous one.
+
+use crate::database::{Database, DatabaseError};
+use crate::logbook_export_parser::gpx_track_points;
+use crate::models::Flight;
+use crate::parser::LogParser;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TrackError {
+    #[error("Flight {0} is not a manual entry; only manual flights can have a track attached")]
+    NotManual(i64),
+
+    #[error("The file is not a GPX track")]
+    NotGpx,
+
+    #[error("The GPX file contains no track points")]
+    NoTrackPoints,
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+}
+
+/// Store the GPX track in `bytes` as the telemetry of manual flight
+/// `flight_id` and return the updated flight
+pub fn attach_track(db: &Database, flight_id: i64, bytes: &[u8]) -> Result<Flight, TrackError> {
+    let flight = db.get_flight_by_id(flight_id)?;
+    if !flight.is_manual {
+        return Err(TrackError::NotManual(flight_id));
+    }
+    let xml = std::str::from_utf8(bytes).map_err(|_| TrackError::NotGpx)?;
+    if !xml.contains("<gpx") {
+        return Err(TrackError::NotGpx);
+    }
+
+    // Timed from the track's own first fix, since watch and phone clocks
+    // rarely match the entered start time to the second
+    let points = gpx_track_points(xml, None);
+    if points.is_empty() {
+        return Err(TrackError::NoTrackPoints);
+    }
+    let stats = LogParser::new(db).calculate_stats(&points);
+
+    let inserted = db.replace_flight_telemetry(flight_id, &points)?;
+    db.update_flight_stats(flight_id, &stats)?;
+    log::info!(
+        "Attached GPX track to manual flight {}: {} points, {:.0} m",
+        flight_id,
+        inserted,
+        stats.total_distance_m
+    );
+    Ok(db.get_flight_by_id(flight_id)?)
+}
@@ -0,0 +1,329 @@
+//! Localized backend messages.
+//!
+//! Messages that end up in the UI (import results, sign-in and access
+//! errors) are looked up in a catalog keyed by a stable code such as
+//! `import.already_imported`, so the frontend can match on the code while
+//! the text is served in the user's language. The locales are the ones the
+//! frontend ships (`src/i18n/locales`).
+//!
+//! The web server negotiates the locale per request from `Accept-Language`;
+//! requests without one, background jobs and the desktop app use the default
+//! locale (`LOCALE` env var in web mode, the app language on the desktop via
+//! `set_locale`). Unknown codes and missing translations fall back to
+//! English, then to the code itself.
+
+use std::fmt::Display;
+use std::sync::RwLock;
+
+/// Supported locales, in catalog column order
+pub const SUPPORTED: [&str; 11] = ["en", "de", "fr", "es", "it", "nl", "pl", "pt", "ja", "zh", "ko"];
+
+static DEFAULT_LOCALE: RwLock<&'static str> = RwLock::new("en");
+
+/// Message templates per code, one column per `SUPPORTED` locale.
+/// Placeholders are `{name}`.
+const CATALOG: &[(&str, [&str; 11])] = &[
+    ("import.file_not_found", [
+        "File not found",
+        "Datei nicht gefunden",
+        "Fichier introuvable",
+        "Archivo no encontrado",
+        "File non trovato",
+        "Bestand niet gevonden",
+        "Nie znaleziono pliku",
+        "Arquivo não encontrado",
+        "ファイルが見つかりません",
+        "未找到文件",
+        "파일을 찾을 수 없습니다",
+    ]),
+    ("import.already_imported", [
+        "This flight log has already been imported (matches: {flight})",
+        "Dieses Fluglog wurde bereits importiert (entspricht: {flight})",
+        "Ce journal de vol a déjà été importé (correspond à : {flight})",
+        "Este registro de vuelo ya se ha importado (coincide con: {flight})",
+        "Questo registro di volo è già stato importato (corrisponde a: {flight})",
+        "Dit vluchtlog is al geïmporteerd (komt overeen met: {flight})",
+        "Ten dziennik lotu został już zaimportowany (pasuje do: {flight})",
+        "Este registro de voo já foi importado (corresponde a: {flight})",
+        "このフライトログは既にインポートされています(一致: {flight})",
+        "此飞行日志已导入(匹配:{flight})",
+        "이 비행 로그는 이미 가져왔습니다 (일치: {flight})",
+    ]),
+    ("import.duplicate", [
+        "Duplicate flight: matches '{flight}' ({reason})",
+        "Doppelter Flug: entspricht „{flight}“ ({reason})",
+        "Vol en double : correspond à « {flight} » ({reason})",
+        "Vuelo duplicado: coincide con «{flight}» ({reason})",
+        "Volo duplicato: corrisponde a «{flight}» ({reason})",
+        "Dubbele vlucht: komt overeen met '{flight}' ({reason})",
+        "Zduplikowany lot: pasuje do „{flight}” ({reason})",
+        "Voo duplicado: corresponde a '{flight}' ({reason})",
+        "重複したフライト:「{flight}」と一致({reason})",
+        "重复的飞行:与“{flight}”匹配({reason})",
+        "중복된 비행: '{flight}'와(과) 일치 ({reason})",
+    ]),
+    ("dedupe.same_battery_start", [
+        "same drone, battery, and start time",
+        "gleiche Drohne, gleicher Akku und gleiche Startzeit",
+        "même drone, même batterie et même heure de début",
+        "mismo dron, batería y hora de inicio",
+        "stesso drone, batteria e orario di inizio",
+        "zelfde drone, accu en starttijd",
+        "ten sam dron, akumulator i czas startu",
+        "mesmo drone, bateria e horário de início",
+        "同じ機体・バッテリー・開始時刻",
+        "相同的无人机、电池和开始时间",
+        "동일한 드론, 배터리, 시작 시간",
+    ]),
+    ("dedupe.same_start_duration", [
+        "same drone, start time, and duration",
+        "gleiche Drohne, Startzeit und Dauer",
+        "même drone, même heure de début et même durée",
+        "mismo dron, hora de inicio y duración",
+        "stesso drone, orario di inizio e durata",
+        "zelfde drone, starttijd en duur",
+        "ten sam dron, czas startu i czas trwania",
+        "mesmo drone, horário de início e duração",
+        "同じ機体・開始時刻・飛行時間",
+        "相同的无人机、开始时间和时长",
+        "동일한 드론, 시작 시간, 비행 시간",
+    ]),
+    ("import.parse_failed", [
+        "Failed to parse log: {error}",
+        "Log konnte nicht gelesen werden: {error}",
+        "Impossible d'analyser le journal : {error}",
+        "No se pudo analizar el registro: {error}",
+        "Impossibile analizzare il registro: {error}",
+        "Log kon niet worden verwerkt: {error}",
+        "Nie udało się przetworzyć dziennika: {error}",
+        "Falha ao analisar o registro: {error}",
+        "ログを解析できませんでした: {error}",
+        "无法解析日志:{error}",
+        "로그를 분석하지 못했습니다: {error}",
+    ]),
+    ("import.insert_failed", [
+        "Failed to insert telemetry data: {error}",
+        "Telemetriedaten konnten nicht gespeichert werden: {error}",
+        "Impossible d'enregistrer les données de télémétrie : {error}",
+        "No se pudieron guardar los datos de telemetría: {error}",
+        "Impossibile salvare i dati di telemetria: {error}",
+        "Telemetriegegevens konden niet worden opgeslagen: {error}",
+        "Nie udało się zapisać danych telemetrycznych: {error}",
+        "Falha ao salvar os dados de telemetria: {error}",
+        "テレメトリデータを保存できませんでした: {error}",
+        "无法保存遥测数据:{error}",
+        "텔레메트리 데이터를 저장하지 못했습니다: {error}",
+    ]),
+    ("import.success", [
+        "Successfully imported {points} telemetry points",
+        "{points} Telemetriepunkte erfolgreich importiert",
+        "{points} points de télémétrie importés",
+        "Se importaron {points} puntos de telemetría",
+        "{points} punti di telemetria importati",
+        "{points} telemetriepunten geïmporteerd",
+        "Zaimportowano {points} punktów telemetrii",
+        "{points} pontos de telemetria importados",
+        "{points} 件のテレメトリポイントをインポートしました",
+        "已成功导入 {points} 个遥测点",
+        "텔레메트리 포인트 {points}개를 가져왔습니다",
+    ]),
+    ("import.manual_created", [
+        "Manual flight entry created successfully",
+        "Manueller Flugeintrag erstellt",
+        "Vol manuel créé",
+        "Entrada de vuelo manual creada",
+        "Voce di volo manuale creata",
+        "Handmatige vlucht aangemaakt",
+        "Utworzono ręczny wpis lotu",
+        "Registro de voo manual criado",
+        "手動フライト記録を作成しました",
+        "已创建手动飞行记录",
+        "수동 비행 기록을 만들었습니다",
+    ]),
+    ("import.dat_fused", [
+        "Fused {points} high-rate telemetry points from DAT log",
+        "{points} hochfrequente Telemetriepunkte aus dem DAT-Log übernommen",
+        "{points} points de télémétrie haute fréquence fusionnés depuis le journal DAT",
+        "Se fusionaron {points} puntos de telemetría de alta frecuencia del registro DAT",
+        "Uniti {points} punti di telemetria ad alta frequenza dal registro DAT",
+        "{points} hoogfrequente telemetriepunten uit het DAT-log samengevoegd",
+        "Scalono {points} punktów telemetrii o wysokiej częstotliwości z dziennika DAT",
+        "{points} pontos de telemetria de alta frequência mesclados do registro DAT",
+        "DAT ログから高頻度テレメトリポイント {points} 件を統合しました",
+        "已从 DAT 日志合并 {points} 个高频遥测点",
+        "DAT 로그에서 고주파 텔레메트리 포인트 {points}개를 병합했습니다",
+    ]),
+    ("auth.profile_locked", [
+        "Profile is locked — please authenticate first",
+        "Profil ist gesperrt — bitte melden Sie sich zuerst an",
+        "Le profil est verrouillé — veuillez d'abord vous authentifier",
+        "El perfil está bloqueado: autentíquese primero",
+        "Il profilo è bloccato — effettua prima l'autenticazione",
+        "Profiel is vergrendeld — meld je eerst aan",
+        "Profil jest zablokowany — najpierw się uwierzytelnij",
+        "O perfil está bloqueado — autentique-se primeiro",
+        "プロファイルはロックされています — 先に認証してください",
+        "配置文件已锁定 — 请先进行身份验证",
+        "프로필이 잠겨 있습니다 — 먼저 인증하세요",
+    ]),
+    ("auth.profile_protected", [
+        "This profile is password-protected — please authenticate first",
+        "Dieses Profil ist passwortgeschützt — bitte melden Sie sich zuerst an",
+        "Ce profil est protégé par un mot de passe — veuillez d'abord vous authentifier",
+        "Este perfil está protegido con contraseña: autentíquese primero",
+        "Questo profilo è protetto da password — effettua prima l'autenticazione",
+        "Dit profiel is met een wachtwoord beveiligd — meld je eerst aan",
+        "Ten profil jest chroniony hasłem — najpierw się uwierzytelnij",
+        "Este perfil é protegido por senha — autentique-se primeiro",
+        "このプロファイルはパスワードで保護されています — 先に認証してください",
+        "此配置文件受密码保护 — 请先进行身份验证",
+        "이 프로필은 비밀번호로 보호되어 있습니다 — 먼저 인증하세요",
+    ]),
+    ("auth.session_expired", [
+        "Session expired or invalid — please re-authenticate",
+        "Sitzung abgelaufen oder ungültig — bitte melden Sie sich erneut an",
+        "Session expirée ou invalide — veuillez vous authentifier à nouveau",
+        "La sesión ha caducado o no es válida: vuelva a autenticarse",
+        "Sessione scaduta o non valida — effettua di nuovo l'autenticazione",
+        "Sessie verlopen of ongeldig — meld je opnieuw aan",
+        "Sesja wygasła lub jest nieprawidłowa — uwierzytelnij się ponownie",
+        "Sessão expirada ou inválida — autentique-se novamente",
+        "セッションの有効期限が切れたか無効です — 再度認証してください",
+        "会话已过期或无效 — 请重新进行身份验证",
+        "세션이 만료되었거나 유효하지 않습니다 — 다시 인증하세요",
+    ]),
+    ("auth.sign_in_required", [
+        "Sign in required",
+        "Anmeldung erforderlich",
+        "Connexion requise",
+        "Es necesario iniciar sesión",
+        "Accesso richiesto",
+        "Aanmelden vereist",
+        "Wymagane zalogowanie",
+        "É necessário entrar",
+        "サインインが必要です",
+        "需要登录",
+        "로그인이 필요합니다",
+    ]),
+    ("auth.account_removed", [
+        "Account no longer exists — please sign in again",
+        "Das Konto existiert nicht mehr — bitte melden Sie sich erneut an",
+        "Ce compte n'existe plus — veuillez vous reconnecter",
+        "La cuenta ya no existe: vuelva a iniciar sesión",
+        "L'account non esiste più — accedi di nuovo",
+        "Account bestaat niet meer — meld je opnieuw aan",
+        "Konto już nie istnieje — zaloguj się ponownie",
+        "A conta não existe mais — entre novamente",
+        "アカウントは存在しません — 再度サインインしてください",
+        "账户已不存在 — 请重新登录",
+        "계정이 더 이상 존재하지 않습니다 — 다시 로그인하세요",
+    ]),
+    ("auth.role_required", [
+        "This action requires the {role} role",
+        "Diese Aktion erfordert die Rolle {role}",
+        "Cette action nécessite le rôle {role}",
+        "Esta acción requiere el rol {role}",
+        "Questa azione richiede il ruolo {role}",
+        "Deze actie vereist de rol {role}",
+        "Ta czynność wymaga roli {role}",
+        "Esta ação requer a função {role}",
+        "この操作には {role} ロールが必要です",
+        "此操作需要 {role} 角色",
+        "이 작업에는 {role} 역할이 필요합니다",
+    ]),
+    ("flight.access_denied", [
+        "You can only change flights you imported",
+        "Sie können nur Flüge ändern, die Sie importiert haben",
+        "Vous ne pouvez modifier que les vols que vous avez importés",
+        "Solo puede modificar los vuelos que ha importado",
+        "Puoi modificare solo i voli che hai importato",
+        "Je kunt alleen vluchten wijzigen die je zelf hebt geïmporteerd",
+        "Możesz zmieniać tylko loty, które zostały przez Ciebie zaimportowane",
+        "Você só pode alterar voos que importou",
+        "自分がインポートしたフライトのみ変更できます",
+        "只能修改自己导入的飞行记录",
+        "직접 가져온 비행만 변경할 수 있습니다",
+    ]),
+];
+
+/// The supported locale for a language tag (`de-AT` → `de`), if any
+pub fn supported(tag: &str) -> Option<&'static str> {
+    let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+    SUPPORTED.iter().copied().find(|l| *l == primary)
+}
+
+/// Locale used when a request does not ask for one
+pub fn default_locale() -> &'static str {
+    *DEFAULT_LOCALE.read().unwrap()
+}
+
+/// Change the default locale; unsupported tags are rejected
+pub fn set_default_locale(tag: &str) -> Result<&'static str, String> {
+    let locale = supported(tag).ok_or_else(|| format!("Unsupported locale: {}", tag))?;
+    *DEFAULT_LOCALE.write().unwrap() = locale;
+    Ok(locale)
+}
+
+/// Pick the best supported locale from an `Accept-Language` header
+/// (`de-CH, de;q=0.9, en;q=0.5`), else the default locale
+pub fn negotiate(accept_language: Option<&str>) -> &'static str {
+    let mut ranges: Vec<(&str, f32)> = accept_language
+        .unwrap_or("")
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let tag = parts.next()?.trim();
+            let q = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            (!tag.is_empty() && tag != "*" && q > 0.0).then_some((tag, q))
+        })
+        .collect();
+    // Stable sort keeps the header order among equal weights
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranges
+        .into_iter()
+        .find_map(|(tag, _)| supported(tag))
+        .unwrap_or_else(default_locale)
+}
+
+/// The message for `code` in `locale`, with `{name}` placeholders filled from `args`
+pub fn text(locale: &str, code: &str, args: &[(&str, &dyn Display)]) -> String {
+    let column = SUPPORTED.iter().position(|l| *l == locale).unwrap_or(0);
+    let mut message = match CATALOG.iter().find(|(c, _)| *c == code) {
+        Some((_, templates)) => templates[column].to_string(),
+        None => return code.to_string(),
+    };
+    for (name, value) in args {
+        message = message.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_and_text() {
+        assert_eq!(negotiate(Some("de-CH, de;q=0.9, en;q=0.5")), "de");
+        assert_eq!(negotiate(Some("sv-SE, pt-BR;q=0.8, en;q=0.9")), "en");
+        assert_eq!(negotiate(Some("ko_KR")), "ko");
+        assert_eq!(negotiate(Some("fr;q=0, *")), default_locale());
+        assert_eq!(negotiate(None), default_locale());
+
+        assert_eq!(
+            text("en", "import.already_imported", &[("flight", &"DJI_0042")]),
+            "This flight log has already been imported (matches: DJI_0042)"
+        );
+        assert_eq!(text("fr", "import.success", &[("points", &1200)]), "1200 points de télémétrie importés");
+        assert_eq!(text("xx", "auth.sign_in_required", &[]), "Sign in required");
+        assert_eq!(text("de", "no.such_code", &[]), "no.such_code");
+
+        // Every code has a non-empty template per locale
+        for (code, templates) in CATALOG {
+            assert!(templates.iter().all(|t| !t.is_empty()), "{} has an empty translation", code);
+        }
+    }
+}
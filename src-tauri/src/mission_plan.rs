@@ -0,0 +1,457 @@
+//! Planned missions (flight plans) attached to flights.
+//!
+//! A waypoint mission can be imported from a Litchi mission CSV, a DJI Pilot 2 /
+//! FlightHub waypoint `.kmz` (WPML) or a plain `.kml` with placemarks or a line,
+//! and attached to the flight that flew it. The plan is stored as its waypoint
+//! list; comparing it with the recorded track gives the cross-track deviation
+//! (how far off the planned path the aircraft was), the altitude deviation when
+//! the plan has heights, and which waypoints were actually reached.
+
+use std::io::Read;
+
+use serde::{Deserialize, Serialize};
+
+use crate::database::{Database, DatabaseError};
+use crate::models::TelemetryRecord;
+use crate::parser::haversine_distance;
+
+/// A waypoint counts as reached when the track passes within this distance
+const WAYPOINT_RADIUS_M: f64 = 10.0;
+/// Actual track returned alongside the plan is thinned to at most this many points
+const MAX_TRACK_POINTS: usize = 2000;
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PlanError {
+    #[error("Unsupported mission file: {0} (expected Litchi CSV, DJI waypoint KMZ or KML)")]
+    UnsupportedFormat(String),
+
+    #[error("Mission file contains no waypoints")]
+    NoWaypoints,
+
+    #[error("Invalid mission file: {0}")]
+    Invalid(String),
+
+    #[error("Failed to read KMZ archive: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+}
+
+/// One planned waypoint; altitude is relative to the takeoff point
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedWaypoint {
+    pub lat: f64,
+    pub lon: f64,
+    pub altitude_m: Option<f64>,
+    pub speed_ms: Option<f64>,
+}
+
+/// A mission attached to a flight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightPlan {
+    pub flight_id: i64,
+    /// Mission file name
+    pub name: String,
+    /// "litchi_csv", "dji_wpml" or "kml"
+    pub source_format: String,
+    pub waypoints: Vec<PlannedWaypoint>,
+    pub created_at: Option<String>,
+}
+
+/// Closest approach of the track to one waypoint
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaypointVisit {
+    pub index: usize,
+    pub closest_distance_m: Option<f64>,
+    /// Flight time (ms) of the closest approach
+    pub closest_at_ms: Option<i64>,
+    pub reached: bool,
+}
+
+/// How closely the flight followed the plan
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanDeviation {
+    pub planned_length_m: f64,
+    pub flown_length_m: f64,
+    /// Horizontal distance from the planned path
+    pub mean_cross_track_m: f64,
+    pub rms_cross_track_m: f64,
+    pub p95_cross_track_m: f64,
+    pub max_cross_track_m: f64,
+    /// Height difference to the planned altitude at the nearest path position
+    /// (None when the plan or the log has no heights)
+    pub mean_altitude_deviation_m: Option<f64>,
+    pub max_altitude_deviation_m: Option<f64>,
+    pub waypoints_reached: usize,
+    pub waypoints_total: usize,
+    pub waypoints: Vec<WaypointVisit>,
+}
+
+/// Plan, actual track and deviation statistics for one flight
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanComparison {
+    pub plan: FlightPlan,
+    /// `[lon, lat, height]` of the recorded track (thinned)
+    pub actual_track: Vec<[f64; 3]>,
+    /// None when the flight has no GPS track
+    pub deviation: Option<PlanDeviation>,
+}
+
+/// Parse a mission file into waypoints. Returns the source format and waypoints.
+pub fn parse_plan(file_name: &str, bytes: &[u8]) -> Result<(&'static str, Vec<PlannedWaypoint>), PlanError> {
+    let ext = std::path::Path::new(file_name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let (format, waypoints) = match ext.as_str() {
+        "csv" => ("litchi_csv", parse_litchi_csv(&String::from_utf8_lossy(bytes))?),
+        "kmz" => ("dji_wpml", parse_kmz(bytes)?),
+        "kml" | "wpml" => ("kml", parse_kml(&String::from_utf8_lossy(bytes))),
+        _ => return Err(PlanError::UnsupportedFormat(file_name.to_string())),
+    };
+    if waypoints.is_empty() {
+        return Err(PlanError::NoWaypoints);
+    }
+    Ok((format, waypoints))
+}
+
+/// Parse a mission file and attach it to a flight (replacing any previous plan)
+pub fn attach_plan(db: &Database, flight_id: i64, file_name: &str, bytes: &[u8]) -> Result<FlightPlan, PlanError> {
+    db.get_flight_by_id(flight_id)?;
+    let (source_format, waypoints) = parse_plan(file_name, bytes)?;
+    let plan = FlightPlan {
+        flight_id,
+        name: file_name.to_string(),
+        source_format: source_format.to_string(),
+        waypoints,
+        created_at: None,
+    };
+    db.save_flight_plan(&plan)?;
+    log::info!("Attached mission '{}' ({} waypoints) to flight {}", file_name, plan.waypoints.len(), flight_id);
+    Ok(plan)
+}
+
+/// Load a flight's plan and compare it with the recorded track (None without a plan)
+pub fn load_comparison(db: &Database, flight_id: i64) -> Result<Option<PlanComparison>, PlanError> {
+    let Some(plan) = db.get_flight_plan(flight_id)? else {
+        return Ok(None);
+    };
+    let records = db.get_flight_telemetry(flight_id, None, None)?;
+    Ok(Some(compare(plan, &records)))
+}
+
+/// Litchi Mission Hub CSV export: `latitude,longitude,altitude(m),heading(deg),...,speed(m/s),...`
+fn parse_litchi_csv(text: &str) -> Result<Vec<PlannedWaypoint>, PlanError> {
+    let mut lines = text.lines().filter(|l| !l.trim().is_empty());
+    let header: Vec<String> = lines
+        .next()
+        .ok_or(PlanError::NoWaypoints)?
+        .split(',')
+        .map(|h| h.trim().trim_start_matches('\u{feff}').to_ascii_lowercase())
+        .collect();
+    let col = |name: &str| header.iter().position(|h| h == name || h.starts_with(&format!("{}(", name)));
+    let (Some(lat_col), Some(lon_col)) = (col("latitude"), col("longitude")) else {
+        return Err(PlanError::Invalid("missing latitude/longitude columns".to_string()));
+    };
+    let alt_col = col("altitude");
+    let speed_col = col("speed");
+
+    let mut waypoints = Vec::new();
+    for line in lines {
+        let cells: Vec<&str> = line.split(',').map(str::trim).collect();
+        let num = |i: Option<usize>| i.and_then(|i| cells.get(i)).and_then(|v| v.parse::<f64>().ok());
+        let (Some(lat), Some(lon)) = (num(Some(lat_col)), num(Some(lon_col))) else {
+            continue;
+        };
+        waypoints.push(PlannedWaypoint {
+            lat,
+            lon,
+            altitude_m: num(alt_col),
+            // Litchi writes 0 for "use the cruising speed"
+            speed_ms: num(speed_col).filter(|s| *s > 0.0),
+        });
+    }
+    Ok(waypoints)
+}
+
+/// DJI waypoint KMZ: `wpmz/waylines.wpml` (executable heights) or `wpmz/template.kml`
+fn parse_kmz(bytes: &[u8]) -> Result<Vec<PlannedWaypoint>, PlanError> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))?;
+    let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    let preferred = ["waylines.wpml", "template.kml"]
+        .iter()
+        .find_map(|wanted| names.iter().find(|n| n.ends_with(wanted)))
+        .or_else(|| names.iter().find(|n| n.to_ascii_lowercase().ends_with(".kml")))
+        .cloned()
+        .ok_or_else(|| PlanError::Invalid("no KML or WPML file in archive".to_string()))?;
+    let mut text = String::new();
+    archive.by_name(&preferred)?.read_to_string(&mut text)?;
+    Ok(parse_kml(&text))
+}
+
+/// Text content of the first `<tag>` element in `xml` (namespace prefix included in `tag`)
+fn element_text<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{}>", tag);
+    let start = xml.find(&open)? + open.len();
+    let end = xml[start..].find(&format!("</{}>", tag))? + start;
+    Some(xml[start..end].trim())
+}
+
+/// Every `<tag ...>...</tag>` block in `xml`
+fn element_blocks<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut blocks = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        // Skip longer tag names sharing the prefix (e.g. <PlacemarkX>)
+        if !after.starts_with(['>', ' ', '\n', '\r', '\t']) {
+            rest = after;
+            continue;
+        }
+        let Some(end) = after.find(&close) else {
+            break;
+        };
+        blocks.push(&after[..end]);
+        rest = &after[end + close.len()..];
+    }
+    blocks
+}
+
+/// Parse a `lon,lat[,alt]` KML coordinate tuple
+fn parse_coordinate(tuple: &str) -> Option<(f64, f64, Option<f64>)> {
+    let mut parts = tuple.split(',').map(|p| p.trim().parse::<f64>());
+    let lon = parts.next()?.ok()?;
+    let lat = parts.next()?.ok()?;
+    let alt = parts.next().and_then(|a| a.ok());
+    Some((lon, lat, alt))
+}
+
+/// KML / WPML: point placemarks in document order, or the first line string
+fn parse_kml(text: &str) -> Vec<PlannedWaypoint> {
+    let mut waypoints: Vec<(Option<i64>, PlannedWaypoint)> = Vec::new();
+    for placemark in element_blocks(text, "Placemark") {
+        let Some(point) = element_blocks(placemark, "Point").into_iter().next() else {
+            continue;
+        };
+        let Some((lon, lat, alt)) = element_text(point, "coordinates").and_then(parse_coordinate) else {
+            continue;
+        };
+        let num = |tag: &str| element_text(placemark, tag).and_then(|v| v.parse::<f64>().ok());
+        waypoints.push((
+            element_text(placemark, "wpml:index").and_then(|v| v.parse().ok()),
+            PlannedWaypoint {
+                lat,
+                lon,
+                altitude_m: num("wpml:executeHeight").or_else(|| num("wpml:height")).or(alt),
+                speed_ms: num("wpml:waypointSpeed"),
+            },
+        ));
+    }
+    if !waypoints.is_empty() {
+        // WPML numbers waypoints explicitly; keep file order otherwise
+        if waypoints.iter().all(|(i, _)| i.is_some()) {
+            waypoints.sort_by_key(|(i, _)| *i);
+        }
+        return waypoints.into_iter().map(|(_, w)| w).collect();
+    }
+
+    element_blocks(text, "LineString")
+        .into_iter()
+        .next()
+        .and_then(|line| element_text(line, "coordinates"))
+        .map(|coords| {
+            coords
+                .split_whitespace()
+                .filter_map(parse_coordinate)
+                .map(|(lon, lat, alt)| PlannedWaypoint { lat, lon, altitude_m: alt, speed_ms: None })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Local east/north metres around a reference latitude
+fn project(lat: f64, lon: f64, ref_lat: f64) -> (f64, f64) {
+    let x = lon.to_radians() * EARTH_RADIUS_M * ref_lat.to_radians().cos();
+    let y = lat.to_radians() * EARTH_RADIUS_M;
+    (x, y)
+}
+
+/// Distance from p to segment ab, and the position along it (0..1)
+fn segment_distance(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 > 0.0 {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len2).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (cx, cy) = (a.0 + t * dx, a.1 + t * dy);
+    (((p.0 - cx).powi(2) + (p.1 - cy).powi(2)).sqrt(), t)
+}
+
+/// Compare the recorded track with the plan
+pub fn compare(plan: FlightPlan, records: &[TelemetryRecord]) -> PlanComparison {
+    let positioned: Vec<&TelemetryRecord> = records
+        .iter()
+        .filter(|r| r.latitude.zip(r.longitude).is_some_and(|(lat, lon)| lat.abs() > 1e-6 || lon.abs() > 1e-6))
+        .collect();
+
+    let step = (positioned.len() / MAX_TRACK_POINTS).max(1);
+    let actual_track = positioned
+        .iter()
+        .step_by(step)
+        .map(|r| [r.longitude.unwrap_or(0.0), r.latitude.unwrap_or(0.0), r.height.unwrap_or(0.0)])
+        .collect();
+
+    let deviation = (!positioned.is_empty() && !plan.waypoints.is_empty()).then(|| deviation(&plan.waypoints, &positioned));
+    PlanComparison { plan, actual_track, deviation }
+}
+
+fn deviation(waypoints: &[PlannedWaypoint], track: &[&TelemetryRecord]) -> PlanDeviation {
+    let ref_lat = waypoints[0].lat;
+    let planned: Vec<(f64, f64)> = waypoints.iter().map(|w| project(w.lat, w.lon, ref_lat)).collect();
+    let planned_length_m = waypoints
+        .windows(2)
+        .map(|w| haversine_distance(w[0].lat, w[0].lon, w[1].lat, w[1].lon))
+        .sum();
+    let flown_length_m = track
+        .windows(2)
+        .map(|w| {
+            haversine_distance(
+                w[0].latitude.unwrap_or(0.0),
+                w[0].longitude.unwrap_or(0.0),
+                w[1].latitude.unwrap_or(0.0),
+                w[1].longitude.unwrap_or(0.0),
+            )
+        })
+        .sum();
+
+    let mut cross_track = Vec::with_capacity(track.len());
+    let mut altitude_diffs = Vec::new();
+    let mut visits: Vec<WaypointVisit> = (0..waypoints.len())
+        .map(|index| WaypointVisit { index, closest_distance_m: None, closest_at_ms: None, reached: false })
+        .collect();
+
+    for r in track {
+        let p = project(r.latitude.unwrap_or(0.0), r.longitude.unwrap_or(0.0), ref_lat);
+
+        // Nearest point on the planned path (a single waypoint is a degenerate segment)
+        let (dist, seg, t) = if planned.len() == 1 {
+            let (d, _) = segment_distance(p, planned[0], planned[0]);
+            (d, 0, 0.0)
+        } else {
+            planned
+                .windows(2)
+                .enumerate()
+                .map(|(i, s)| {
+                    let (d, t) = segment_distance(p, s[0], s[1]);
+                    (d, i, t)
+                })
+                .fold((f64::INFINITY, 0, 0.0), |best, cur| if cur.0 < best.0 { cur } else { best })
+        };
+        cross_track.push(dist);
+
+        let next = (seg + 1).min(waypoints.len() - 1);
+        let planned_alt = match (waypoints[seg].altitude_m, waypoints[next].altitude_m) {
+            (Some(a), Some(b)) => Some(a + (b - a) * t),
+            (a, b) => a.or(b),
+        };
+        if let (Some(planned_alt), Some(height)) = (planned_alt, r.height) {
+            altitude_diffs.push((height - planned_alt).abs());
+        }
+
+        for (visit, wp) in visits.iter_mut().zip(&planned) {
+            let d = ((p.0 - wp.0).powi(2) + (p.1 - wp.1).powi(2)).sqrt();
+            if visit.closest_distance_m.map_or(true, |best| d < best) {
+                visit.closest_distance_m = Some(d);
+                visit.closest_at_ms = Some(r.timestamp_ms);
+            }
+        }
+    }
+    for visit in &mut visits {
+        visit.reached = visit.closest_distance_m.is_some_and(|d| d <= WAYPOINT_RADIUS_M);
+    }
+
+    let n = cross_track.len().max(1) as f64;
+    let mut sorted = cross_track.clone();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let p95_index = ((sorted.len() as f64 * 0.95).ceil() as usize).saturating_sub(1).min(sorted.len().saturating_sub(1));
+
+    PlanDeviation {
+        planned_length_m,
+        flown_length_m,
+        mean_cross_track_m: cross_track.iter().sum::<f64>() / n,
+        rms_cross_track_m: (cross_track.iter().map(|d| d * d).sum::<f64>() / n).sqrt(),
+        p95_cross_track_m: sorted.get(p95_index).copied().unwrap_or(0.0),
+        max_cross_track_m: sorted.last().copied().unwrap_or(0.0),
+        mean_altitude_deviation_m: (!altitude_diffs.is_empty())
+            .then(|| altitude_diffs.iter().sum::<f64>() / altitude_diffs.len() as f64),
+        max_altitude_deviation_m: altitude_diffs.iter().copied().reduce(f64::max),
+        waypoints_reached: visits.iter().filter(|v| v.reached).count(),
+        waypoints_total: visits.len(),
+        waypoints: visits,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wpml_placemarks() {
+        let wpml = r#"<kml><Document><Folder>
+            <Placemark><Point><coordinates>8.5500,47.3700</coordinates></Point>
+              <wpml:index>1</wpml:index><wpml:executeHeight>60</wpml:executeHeight></Placemark>
+            <Placemark><Point><coordinates>
+                8.5400,47.3600
+            </coordinates></Point>
+              <wpml:index>0</wpml:index><wpml:executeHeight>50</wpml:executeHeight>
+              <wpml:waypointSpeed>8</wpml:waypointSpeed></Placemark>
+        </Folder></Document></kml>"#;
+        let wps = parse_kml(wpml);
+        assert_eq!(wps.len(), 2);
+        assert!((wps[0].lat - 47.36).abs() < 1e-9);
+        assert_eq!(wps[0].altitude_m, Some(50.0));
+        assert_eq!(wps[0].speed_ms, Some(8.0));
+        assert_eq!(wps[1].altitude_m, Some(60.0));
+    }
+
+    #[test]
+    fn test_cross_track_deviation() {
+        let plan = FlightPlan {
+            flight_id: 1,
+            name: "m.csv".to_string(),
+            source_format: "litchi_csv".to_string(),
+            waypoints: parse_litchi_csv("latitude,longitude,altitude(m),speed(m/s)\n47.0,8.0,50,0\n47.0,8.01,50,5\n").unwrap(),
+            created_at: None,
+        };
+        // Fly the leg ~11 m north of the planned line, at 55 m
+        let records: Vec<TelemetryRecord> = (0..=10)
+            .map(|i| TelemetryRecord {
+                timestamp_ms: i * 1000,
+                latitude: Some(47.0001),
+                longitude: Some(8.0 + 0.001 * i as f64),
+                height: Some(55.0),
+                ..Default::default()
+            })
+            .collect();
+        let dev = compare(plan, &records).deviation.unwrap();
+        assert!((dev.max_cross_track_m - 11.1).abs() < 0.2);
+        assert!((dev.mean_altitude_deviation_m.unwrap() - 5.0).abs() < 1e-9);
+        assert_eq!(dev.waypoints_total, 2);
+        assert_eq!(dev.waypoints_reached, 0);
+    }
+}
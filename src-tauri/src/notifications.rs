@@ -0,0 +1,226 @@
+//! Notifications for events that happen in the background.
+//!
+//! Three events are notified:
+//!
+//! - `import_completed` — a sync folder import finished (the web server's
+//!   scheduled sync, or the desktop sync at startup, reported by the frontend)
+//! - `backup_completed` — a scheduled backup was written, or failed (web
+//!   server, `BACKUP_PATH` / `BACKUP_INTERVAL`)
+//! - `battery_health` — a battery is recommended for retirement for the first
+//!   time (post-import pipeline, see `battery_retirement`)
+//!
+//! Each event can be switched off under the `notifications` key of
+//! `config.json`. How a notification is shown depends on the build, which
+//! registers its delivery with [`set_delivery`] at startup: the desktop app
+//! uses the system notification centre (Tauri notification plugin), the web
+//! server sends a Web Push message to every browser subscribed to the profile
+//! (see `web_push`). Before that, events are only logged.
+
+use std::path::Path;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::database::Database;
+
+const CONFIG_KEY: &str = "notifications";
+
+/// A background event worth telling the user about
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NotificationEvent {
+    #[serde(rename_all = "camelCase")]
+    ImportCompleted {
+        /// Sync folder (or profile) the files came from
+        source: String,
+        imported: usize,
+        skipped: usize,
+        errors: usize,
+    },
+    #[serde(rename_all = "camelCase")]
+    BackupCompleted {
+        /// Backup file written
+        destination: String,
+        /// Set when the backup failed
+        error: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    BatteryHealth {
+        battery_serial: String,
+        display_name: Option<String>,
+        /// One sentence per threshold crossed
+        reasons: Vec<String>,
+    },
+}
+
+/// What is shown, and the Web Push payload
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    pub kind: &'static str,
+    pub title: String,
+    pub body: String,
+}
+
+impl NotificationEvent {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ImportCompleted { .. } => "import_completed",
+            Self::BackupCompleted { .. } => "backup_completed",
+            Self::BatteryHealth { .. } => "battery_health",
+        }
+    }
+
+    pub fn notification(&self) -> Notification {
+        let (title, body) = match self {
+            Self::ImportCompleted { source, imported, skipped, errors } => {
+                let mut parts = vec![format!("{} imported", imported)];
+                if *skipped > 0 {
+                    parts.push(format!("{} skipped", skipped));
+                }
+                if *errors > 0 {
+                    parts.push(format!("{} failed", errors));
+                }
+                (format!("Sync from {} finished", source), parts.join(", "))
+            }
+            Self::BackupCompleted { destination, error: None } => ("Backup completed".to_string(), destination.clone()),
+            Self::BackupCompleted { destination, error: Some(e) } => {
+                ("Backup failed".to_string(), format!("{}: {}", destination, e))
+            }
+            Self::BatteryHealth { battery_serial, display_name, reasons } => (
+                format!("Retire battery {}", display_name.as_deref().unwrap_or(battery_serial)),
+                reasons.join("; "),
+            ),
+        };
+        Notification { kind: self.kind(), title, body }
+    }
+}
+
+/// A browser's Web Push subscription, as returned by `PushSubscription.toJSON()`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushSubscription {
+    pub endpoint: String,
+    pub keys: PushKeys,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushKeys {
+    pub p256dh: String,
+    pub auth: String,
+}
+
+/// Which events are notified
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationSettings {
+    pub import_completed: bool,
+    pub backup_completed: bool,
+    pub battery_health: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self { import_completed: true, backup_completed: true, battery_health: true }
+    }
+}
+
+impl NotificationSettings {
+    pub fn from_config(config: &serde_json::Value) -> Self {
+        config
+            .get(CONFIG_KEY)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Read the settings from a `config.json` (defaults when missing)
+    pub fn load(config_path: &Path) -> Self {
+        Self::from_config(&read_config(config_path))
+    }
+
+    pub fn allows(&self, event: &NotificationEvent) -> bool {
+        match event {
+            NotificationEvent::ImportCompleted { .. } => self.import_completed,
+            NotificationEvent::BackupCompleted { .. } => self.backup_completed,
+            NotificationEvent::BatteryHealth { .. } => self.battery_health,
+        }
+    }
+}
+
+fn read_config(config_path: &Path) -> serde_json::Value {
+    std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}))
+}
+
+/// Store the settings in config.json
+pub fn save_settings(config_path: &Path, settings: NotificationSettings) -> Result<NotificationSettings, String> {
+    let mut config = read_config(config_path);
+    config[CONFIG_KEY] = serde_json::json!(settings);
+    std::fs::write(config_path, serde_json::to_string_pretty(&config).unwrap())
+        .map_err(|e| format!("Failed to write config: {}", e))?;
+    Ok(settings)
+}
+
+/// Shows a notification for a profile (its database)
+pub type Delivery = Box<dyn Fn(&Database, &Notification) + Send + Sync>;
+
+static DELIVERY: OnceLock<Delivery> = OnceLock::new();
+
+/// Register how this build shows notifications (once, at startup)
+pub fn set_delivery(delivery: Delivery) {
+    if DELIVERY.set(delivery).is_err() {
+        log::warn!("Notification delivery already registered");
+    }
+}
+
+/// Notify `event` unless the profile switched it off. Returns whether it was delivered.
+pub fn notify(db: &Database, config: &serde_json::Value, event: &NotificationEvent) -> bool {
+    if !NotificationSettings::from_config(config).allows(event) {
+        return false;
+    }
+    let notification = event.notification();
+    log::info!("Notification: {} - {}", notification.title, notification.body);
+    match DELIVERY.get() {
+        Some(deliver) => {
+            deliver(db, &notification);
+            true
+        }
+        None => false,
+    }
+}
+
+/// `notify` with the settings read from the profile's `config.json`
+pub fn notify_profile(db: &Database, config_path: &Path, event: &NotificationEvent) -> bool {
+    notify(db, &read_config(config_path), event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_text_and_settings() {
+        let import = NotificationEvent::ImportCompleted { source: "DJI".to_string(), imported: 3, skipped: 1, errors: 0 };
+        let n = import.notification();
+        assert_eq!((n.kind, n.title.as_str(), n.body.as_str()), ("import_completed", "Sync from DJI finished", "3 imported, 1 skipped"));
+
+        let battery = NotificationEvent::BatteryHealth {
+            battery_serial: "BAT1".to_string(),
+            display_name: Some("Yellow".to_string()),
+            reasons: vec!["310 cycles (limit 300)".to_string()],
+        };
+        assert_eq!(battery.notification().title, "Retire battery Yellow");
+
+        let config = serde_json::json!({ "notifications": { "batteryHealth": false } });
+        let settings = NotificationSettings::from_config(&config);
+        assert!(settings.allows(&import));
+        assert!(!settings.allows(&battery));
+
+        let event: NotificationEvent = serde_json::from_value(serde_json::json!({
+            "kind": "import_completed", "source": "SD card", "imported": 2, "skipped": 0, "errors": 1
+        }))
+        .unwrap();
+        assert_eq!(event.notification().body, "2 imported, 1 failed");
+    }
+}
@@ -0,0 +1,311 @@
+//! OpenID Connect sign-in for the web deployment (Authelia, Keycloak,
+//! Google, ...).
+//!
+//! Enabled by the `OIDC_ISSUER_URL`, `OIDC_CLIENT_ID` and `OIDC_REDIRECT_URL`
+//! environment variables (`OIDC_CLIENT_SECRET` for confidential clients). The
+//! server runs the authorization code flow with PKCE: `/api/auth/oidc/login`
+//! redirects to the provider, which sends the browser back to
+//! `/api/auth/oidc/callback`. The code is exchanged at the token endpoint and
+//! the identity read from the userinfo endpoint, both over direct TLS
+//! connections to the provider, so no ID token signature handling is needed.
+//!
+//! External identities are mapped to local accounts (see `users`): the account
+//! already linked to the identity's subject. With `OIDC_LINK_BY_USERNAME=true`
+//! an identity with a verified email may also claim the password-less account
+//! whose username equals the `OIDC_USERNAME_CLAIM` claim (linked on first
+//! sign-in). Unknown identities get an account with `OIDC_DEFAULT_ROLE` when
+//! that is set, and are refused otherwise. Roles stay managed locally.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::session_store::{base64_url_encode, generate_token};
+use crate::users::Role;
+
+const DEFAULT_SCOPES: &str = "openid profile email";
+const DEFAULT_USERNAME_CLAIM: &str = "preferred_username";
+/// How long the provider may take to send the browser back
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(600);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, thiserror::Error)]
+pub enum OidcError {
+    #[error("OpenID provider discovery failed: {0}")]
+    Discovery(String),
+
+    #[error("Sign-in request expired or unknown — please try again")]
+    UnknownState,
+
+    #[error("OpenID provider returned an error: {0}")]
+    Provider(String),
+
+    #[error("Request to the OpenID provider failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("The identity has no '{0}' claim")]
+    MissingClaim(String),
+}
+
+/// Settings read from the environment
+#[derive(Debug, Clone)]
+pub struct OidcConfig {
+    pub issuer_url: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub redirect_url: String,
+    pub scopes: String,
+    pub username_claim: String,
+    /// Link password-less accounts by username on first sign-in
+    pub link_by_username: bool,
+    /// Role for identities without a local account (None = refuse them)
+    pub default_role: Option<Role>,
+    /// Where the browser goes after signing in
+    pub post_login_url: String,
+}
+
+impl OidcConfig {
+    /// None unless the issuer, client ID and redirect URL are all set
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let issuer_url = var("OIDC_ISSUER_URL")?;
+        let client_id = var("OIDC_CLIENT_ID")?;
+        let redirect_url = var("OIDC_REDIRECT_URL")?;
+        let default_role = match var("OIDC_DEFAULT_ROLE").as_deref() {
+            None => None,
+            Some("viewer") => Some(Role::Viewer),
+            Some("editor") => Some(Role::Editor),
+            Some("admin") => Some(Role::Admin),
+            Some(other) => {
+                log::warn!("Ignoring invalid OIDC_DEFAULT_ROLE '{}' (expected viewer, editor or admin)", other);
+                None
+            }
+        };
+        Some(Self {
+            issuer_url: issuer_url.trim_end_matches('/').to_string(),
+            client_id,
+            client_secret: var("OIDC_CLIENT_SECRET"),
+            redirect_url,
+            scopes: var("OIDC_SCOPES").unwrap_or_else(|| DEFAULT_SCOPES.to_string()),
+            username_claim: var("OIDC_USERNAME_CLAIM").unwrap_or_else(|| DEFAULT_USERNAME_CLAIM.to_string()),
+            link_by_username: var("OIDC_LINK_BY_USERNAME").is_some_and(|v| v.eq_ignore_ascii_case("true")),
+            default_role,
+            post_login_url: var("OIDC_POST_LOGIN_URL").unwrap_or_else(|| "/".to_string()),
+        })
+    }
+}
+
+/// The parts of the provider's discovery document we use
+#[derive(Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+/// A signed-in identity at the provider
+#[derive(Debug, Clone)]
+pub struct ExternalIdentity {
+    /// `<issuer>|<sub>`, stable across username changes
+    pub id: String,
+    /// Value of the configured username claim
+    pub username: String,
+    /// Whether the provider vouches for the identity's `email`
+    pub email_verified: bool,
+}
+
+struct PendingLogin {
+    code_verifier: String,
+    created: Instant,
+}
+
+pub struct OidcClient {
+    pub config: OidcConfig,
+    http: reqwest::Client,
+    metadata: tokio::sync::OnceCell<ProviderMetadata>,
+    /// state → login in progress
+    pending: Mutex<HashMap<String, PendingLogin>>,
+}
+
+impl OidcClient {
+    pub fn new(config: OidcConfig) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            config,
+            http,
+            metadata: tokio::sync::OnceCell::new(),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Fetch the discovery document once and keep it
+    async fn metadata(&self) -> Result<&ProviderMetadata, OidcError> {
+        self.metadata
+            .get_or_try_init(|| async {
+                let url = format!("{}/.well-known/openid-configuration", self.config.issuer_url);
+                let metadata: ProviderMetadata = self
+                    .http
+                    .get(&url)
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status())
+                    .map_err(|e| OidcError::Discovery(format!("{}: {}", url, e)))?
+                    .json()
+                    .await
+                    .map_err(|e| OidcError::Discovery(format!("{}: {}", url, e)))?;
+                if metadata.issuer.trim_end_matches('/') != self.config.issuer_url {
+                    return Err(OidcError::Discovery(format!(
+                        "issuer mismatch (configured {}, provider says {})",
+                        self.config.issuer_url, metadata.issuer
+                    )));
+                }
+                Ok(metadata)
+            })
+            .await
+    }
+
+    /// Start a sign-in: the provider URL to send the browser to
+    pub async fn authorization_url(&self) -> Result<String, OidcError> {
+        let metadata = self.metadata().await?;
+        let state = generate_token();
+        let code_verifier = generate_token();
+        let code_challenge = base64_url_encode(&Sha256::digest(code_verifier.as_bytes()));
+        {
+            let mut pending = self.pending.lock().unwrap();
+            pending.retain(|_, p| p.created.elapsed() < LOGIN_TIMEOUT);
+            pending.insert(state.clone(), PendingLogin { code_verifier, created: Instant::now() });
+        }
+
+        let mut url = reqwest::Url::parse(&metadata.authorization_endpoint)
+            .map_err(|e| OidcError::Discovery(format!("invalid authorization endpoint: {}", e)))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.config.client_id)
+            .append_pair("redirect_uri", &self.config.redirect_url)
+            .append_pair("scope", &self.config.scopes)
+            .append_pair("state", &state)
+            .append_pair("code_challenge", &code_challenge)
+            .append_pair("code_challenge_method", "S256");
+        Ok(url.into())
+    }
+
+    /// Finish a sign-in from the callback's `code` and `state`
+    pub async fn complete(&self, code: &str, state: &str) -> Result<ExternalIdentity, OidcError> {
+        let login = self
+            .pending
+            .lock()
+            .unwrap()
+            .remove(state)
+            .filter(|p| p.created.elapsed() < LOGIN_TIMEOUT)
+            .ok_or(OidcError::UnknownState)?;
+        let metadata = self.metadata().await?;
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.config.redirect_url.as_str()),
+            ("code_verifier", login.code_verifier.as_str()),
+        ];
+        let mut request = self.http.post(&metadata.token_endpoint);
+        match &self.config.client_secret {
+            Some(secret) => {
+                request = request.basic_auth(form_encode(&self.config.client_id), Some(form_encode(secret)));
+            }
+            None => form.push(("client_id", self.config.client_id.as_str())),
+        }
+        let response = request
+            .header(reqwest::header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(encode_form(&form))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(OidcError::Provider(format!("token endpoint returned {}: {}", status, body)));
+        }
+        let token: TokenResponse = response.json().await?;
+
+        let userinfo_endpoint = metadata
+            .userinfo_endpoint
+            .as_deref()
+            .ok_or_else(|| OidcError::Provider("the provider has no userinfo endpoint".to_string()))?;
+        let claims: serde_json::Value = self
+            .http
+            .get(userinfo_endpoint)
+            .bearer_auth(&token.access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let claim = |name: &str| {
+            claims
+                .get(name)
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .ok_or_else(|| OidcError::MissingClaim(name.to_string()))
+        };
+        let subject = claim("sub")?;
+        let username = claim(&self.config.username_claim)?;
+        let email_verified = claim("email").is_ok()
+            && claims.get("email_verified").and_then(|v| v.as_bool()) == Some(true);
+        Ok(ExternalIdentity {
+            id: format!("{}|{}", self.config.issuer_url, subject),
+            username,
+            email_verified,
+        })
+    }
+
+    /// Where to send the browser after a sign-in attempt: the post-login URL
+    /// with the result in the fragment, which the frontend picks up
+    pub fn result_url(&self, key: &str, value: &str) -> String {
+        format!("{}#{}", self.config.post_login_url, encode_form(&[(key, value)]))
+    }
+}
+
+/// `application/x-www-form-urlencoded` encoding of one value
+fn form_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'*' => (b as char).to_string(),
+            b' ' => "+".to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn encode_form(pairs: &[(&str, &str)]) -> String {
+    pairs
+        .iter()
+        .map(|(k, v)| format!("{}={}", form_encode(k), form_encode(v)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_form() {
+        assert_eq!(
+            encode_form(&[("redirect_uri", "https://x.example/api/v1/auth?a=1"), ("scope", "openid email")]),
+            "redirect_uri=https%3A%2F%2Fx.example%2Fapi%2Fv1%2Fauth%3Fa%3D1&scope=openid+email"
+        );
+    }
+}
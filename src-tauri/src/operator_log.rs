@@ -0,0 +1,396 @@
+//! Operator flight log for EASA-territory authorities.
+//!
+//! National aviation authorities in the EU ask UAS operators to keep a record
+//! of their flights: who operates (the operator registration number), with
+//! which aircraft and class mark, where, when, for how long, and any remarks.
+//! The operator details and the class mark of each aircraft (by serial
+//! number) are kept under the `operator_log` key of `config.json`; the rest
+//! comes from the flights. Flights are filtered by take-off date, oldest first.
+//! Flight times are rounded with the profile's duration rounding policy.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::bundle::{build_pdf_pages, pdf_text, PDF_MARGIN, PDF_PAGE_HEIGHT};
+use crate::database::{Database, DatabaseError};
+use crate::duration_rounding::DurationRounding;
+use crate::models::Flight;
+
+const CONFIG_KEY: &str = "operator_log";
+const DEFAULT_CATEGORY: &str = "Open";
+const ROW_HEIGHT: f64 = 12.0;
+
+/// UAS class identification label (Delegated Regulation (EU) 2019/945)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UasClass {
+    C0,
+    C1,
+    C2,
+    C3,
+    C4,
+    C5,
+    C6,
+    /// Placed on the market before class marks applied
+    #[serde(rename = "legacy")]
+    Legacy,
+    #[serde(rename = "privately_built")]
+    PrivatelyBuilt,
+}
+
+impl UasClass {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::C0 => "C0",
+            Self::C1 => "C1",
+            Self::C2 => "C2",
+            Self::C3 => "C3",
+            Self::C4 => "C4",
+            Self::C5 => "C5",
+            Self::C6 => "C6",
+            Self::Legacy => "Legacy",
+            Self::PrivatelyBuilt => "Privately built",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorSettings {
+    /// Operator registration number, without the secret digits
+    #[serde(default)]
+    pub operator_id: Option<String>,
+    #[serde(default)]
+    pub operator_name: Option<String>,
+    /// Operational category (Open, Specific); "Open" when unset
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Aircraft serial → class mark
+    #[serde(default)]
+    pub uas_classes: BTreeMap<String, UasClass>,
+}
+
+impl OperatorSettings {
+    pub fn from_config(config: &serde_json::Value) -> Self {
+        config
+            .get(CONFIG_KEY)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Read the settings from a `config.json` (defaults when missing)
+    pub fn load(config_path: &Path) -> Self {
+        let config: serde_json::Value = std::fs::read_to_string(config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+        Self::from_config(&config)
+    }
+}
+
+/// Store new settings in config.json
+pub fn save_settings(config_path: &Path, mut settings: OperatorSettings) -> Result<OperatorSettings, String> {
+    if let Some(id) = settings.operator_id.as_mut() {
+        *id = id.trim().to_string();
+        // e.g. FIN87astrdge12k8 — the 3 secret digits after the dash are not recorded
+        if id.contains('-') {
+            return Err("Enter the operator number without the secret digits after the '-'".to_string());
+        }
+        if !id.is_empty() && (id.len() != 16 || !id.chars().all(|c| c.is_ascii_alphanumeric())) {
+            return Err("Operator registration number must be 16 letters and digits".to_string());
+        }
+    }
+    settings.operator_id = settings.operator_id.filter(|id| !id.is_empty());
+    settings.uas_classes = settings
+        .uas_classes
+        .into_iter()
+        .map(|(serial, class)| (serial.trim().to_string(), class))
+        .filter(|(serial, _)| !serial.is_empty())
+        .collect();
+
+    let mut config: serde_json::Value = std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    config[CONFIG_KEY] = serde_json::json!(settings);
+    std::fs::write(config_path, serde_json::to_string_pretty(&config).unwrap())
+        .map_err(|e| format!("Failed to write config: {}", e))?;
+    Ok(settings)
+}
+
+/// One flight of the log
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorLogEntry {
+    pub flight_id: i64,
+    /// Take-off and landing, UTC (`YYYY-MM-DD HH:MM`)
+    pub takeoff_utc: Option<String>,
+    pub landing_utc: Option<String>,
+    /// Rounded with the log's `duration_rounding`
+    pub duration_secs: f64,
+    pub aircraft: String,
+    pub aircraft_serial: Option<String>,
+    pub uas_class: Option<UasClass>,
+    pub pilot: Option<String>,
+    /// Location name, else take-off coordinates
+    pub location: Option<String>,
+    pub remarks: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorLog {
+    pub operator_id: Option<String>,
+    pub operator_name: Option<String>,
+    pub category: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub entries: Vec<OperatorLogEntry>,
+    pub flight_count: i64,
+    pub total_hours: f64,
+    pub duration_rounding: DurationRounding,
+    /// Serials flown in the period without a class mark in the settings
+    pub unclassified_serials: Vec<String>,
+}
+
+fn entry(flight: &Flight, settings: &OperatorSettings, rounding: DurationRounding) -> OperatorLogEntry {
+    let start = flight.start_time_utc();
+    let duration = flight.duration_secs.unwrap_or(0.0);
+    let landing = start.map(|t| t + chrono::Duration::milliseconds((duration * 1000.0) as i64));
+    let serial = flight.drone_serial.clone().filter(|s| !s.is_empty());
+    let location = flight.location_name.clone().filter(|l| !l.trim().is_empty()).or_else(|| {
+        match (flight.home_lat, flight.home_lon) {
+            (Some(lat), Some(lon)) => Some(format!("{:.5}, {:.5}", lat, lon)),
+            _ => None,
+        }
+    });
+    OperatorLogEntry {
+        flight_id: flight.id,
+        takeoff_utc: start.map(|t| t.format("%Y-%m-%d %H:%M").to_string()),
+        landing_utc: landing.map(|t| t.format("%Y-%m-%d %H:%M").to_string()),
+        duration_secs: rounding.round_secs(duration),
+        aircraft: flight
+            .aircraft_name
+            .clone()
+            .or_else(|| flight.drone_model.clone())
+            .unwrap_or_else(|| "Unknown".to_string()),
+        uas_class: serial.as_ref().and_then(|s| settings.uas_classes.get(s).copied()),
+        aircraft_serial: serial,
+        pilot: flight.pilot.clone().filter(|p| !p.trim().is_empty()),
+        location,
+        remarks: flight
+            .notes
+            .as_deref()
+            .map(|n| n.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|n| !n.is_empty()),
+    }
+}
+
+/// Build the log from the flights taken off within `from..=to`
+pub fn build_log(
+    flights: &[Flight],
+    settings: &OperatorSettings,
+    rounding: DurationRounding,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> OperatorLog {
+    let mut selected: Vec<&Flight> = flights
+        .iter()
+        .filter(|f| match f.start_time_utc().map(|t| t.date_naive()) {
+            Some(date) => from.map_or(true, |from| date >= from) && to.map_or(true, |to| date <= to),
+            None => from.is_none() && to.is_none(),
+        })
+        .collect();
+    selected.sort_by_key(|f| (f.start_time_utc(), f.id));
+
+    let entries: Vec<OperatorLogEntry> = selected.iter().map(|f| entry(f, settings, rounding)).collect();
+    let mut unclassified_serials: Vec<String> = entries
+        .iter()
+        .filter(|e| e.uas_class.is_none())
+        .filter_map(|e| e.aircraft_serial.clone())
+        .collect();
+    unclassified_serials.sort();
+    unclassified_serials.dedup();
+
+    OperatorLog {
+        operator_id: settings.operator_id.clone(),
+        operator_name: settings.operator_name.clone(),
+        category: settings
+            .category
+            .clone()
+            .filter(|c| !c.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_CATEGORY.to_string()),
+        from: from.map(|d| d.to_string()),
+        to: to.map(|d| d.to_string()),
+        flight_count: entries.len() as i64,
+        total_hours: entries.iter().map(|e| e.duration_secs).sum::<f64>() / 3600.0,
+        duration_rounding: rounding,
+        entries,
+        unclassified_serials,
+    }
+}
+
+/// Load the flights and settings and build the log
+pub fn load_log(
+    db: &Database,
+    config_path: &Path,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<OperatorLog, DatabaseError> {
+    let flights = db.get_all_flights()?;
+    Ok(build_log(&flights, &OperatorSettings::load(config_path), db.get_duration_rounding(), from, to))
+}
+
+/// One row per flight
+pub fn log_csv(log: &OperatorLog) -> String {
+    use crate::stats_export::csv_field;
+
+    let mut out = String::from(
+        "operator_id,category,takeoff_utc,landing_utc,duration_min,aircraft,aircraft_serial,uas_class,pilot,location,remarks\n",
+    );
+    for e in &log.entries {
+        out.push_str(&format!(
+            "{},{},{},{},{:.1},{},{},{},{},{},{}\n",
+            csv_field(log.operator_id.as_deref().unwrap_or("")),
+            csv_field(&log.category),
+            e.takeoff_utc.as_deref().unwrap_or(""),
+            e.landing_utc.as_deref().unwrap_or(""),
+            e.duration_secs / 60.0,
+            csv_field(&e.aircraft),
+            csv_field(e.aircraft_serial.as_deref().unwrap_or("")),
+            e.uas_class.map(|c| c.label()).unwrap_or(""),
+            csv_field(e.pilot.as_deref().unwrap_or("")),
+            csv_field(e.location.as_deref().unwrap_or("")),
+            csv_field(e.remarks.as_deref().unwrap_or(""))
+        ));
+    }
+    out
+}
+
+/// Shorten `s` to `max` characters for a fixed-width PDF column
+pub(crate) fn clip(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        format!("{}...", s.chars().take(max.saturating_sub(3)).collect::<String>())
+    }
+}
+
+/// A4 PDF with the operator details on top and one table row per flight,
+/// continued over as many pages as needed
+pub fn log_pdf(log: &OperatorLog) -> Vec<u8> {
+    // x offset and max characters of each column
+    let columns: [(f64, usize); 7] = [(0.0, 16), (78.0, 8), (108.0, 18), (200.0, 7), (236.0, 14), (306.0, 22), (414.0, 18)];
+    let text = |content: &mut String, font: &str, size: f64, x: f64, y: f64, s: &str| {
+        content.push_str(&format!("BT /{} {} Tf {:.1} {:.1} Td ({}) Tj ET\n", font, size, PDF_MARGIN + x, y, pdf_text(s)));
+    };
+    let row = |content: &mut String, font: &str, y: f64, cells: [&str; 7]| {
+        for ((x, max), cell) in columns.iter().zip(cells) {
+            content.push_str(&format!("BT /{} 8 Tf {:.1} {:.1} Td ({}) Tj ET\n", font, PDF_MARGIN + x, y, pdf_text(&clip(cell, *max))));
+        }
+    };
+    let header = ["Take-off (UTC)", "Time", "Aircraft", "Class", "Pilot", "Location", "Remarks"];
+    let top = PDF_PAGE_HEIGHT - PDF_MARGIN - 10.0;
+    let bottom = PDF_MARGIN + 24.0;
+
+    let mut pages = Vec::new();
+    let mut content = String::new();
+    let mut y = top;
+    text(&mut content, "F2", 18.0, 0.0, y, "UAS operator flight log");
+    y -= 20.0;
+    let operator = match (&log.operator_id, &log.operator_name) {
+        (Some(id), Some(name)) => format!("Operator: {} ({})", name, id),
+        (Some(id), None) => format!("Operator: {}", id),
+        (None, Some(name)) => format!("Operator: {} (registration number not set)", name),
+        (None, None) => "Operator: not set".to_string(),
+    };
+    text(&mut content, "F1", 10.0, 0.0, y, &operator);
+    y -= 14.0;
+    let period = format!(
+        "Period: {} to {} - category: {} - {} flights, {} h total",
+        log.from.as_deref().unwrap_or("start"),
+        log.to.as_deref().unwrap_or("today"),
+        log.category,
+        log.flight_count,
+        log.duration_rounding.format(log.total_hours * 3600.0)
+    );
+    text(&mut content, "F1", 10.0, 0.0, y, &period);
+    y -= 24.0;
+    row(&mut content, "F2", y, header);
+    y -= ROW_HEIGHT + 2.0;
+
+    for e in &log.entries {
+        if y < bottom {
+            pages.push(std::mem::take(&mut content));
+            y = top;
+            row(&mut content, "F2", y, header);
+            y -= ROW_HEIGHT + 2.0;
+        }
+        let duration = log.duration_rounding.format(e.duration_secs);
+        row(
+            &mut content,
+            "F1",
+            y,
+            [
+                e.takeoff_utc.as_deref().unwrap_or("-"),
+                &duration,
+                &e.aircraft,
+                e.uas_class.map(|c| c.label()).unwrap_or("-"),
+                e.pilot.as_deref().unwrap_or("-"),
+                e.location.as_deref().unwrap_or("-"),
+                e.remarks.as_deref().unwrap_or(""),
+            ],
+        );
+        y -= ROW_HEIGHT;
+    }
+    pages.push(content);
+
+    let generated = chrono::Utc::now().format("%Y-%m-%d %H:%M UTC");
+    let count = pages.len();
+    for (i, page) in pages.iter_mut().enumerate() {
+        let footer = format!("Generated by Open DroneLog on {} - page {} of {}", generated, i + 1, count);
+        text(page, "F1", 8.0, 0.0, PDF_MARGIN, &footer);
+    }
+    build_pdf_pages(&pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FlightBuilder;
+
+    fn flight(id: i64, start: &str, serial: &str) -> Flight {
+        FlightBuilder::new(id)
+            .start(start)
+            .drone_serial(serial)
+            .duration(900.0)
+            .home(60.1699, 24.9384)
+            .notes("Roof\ninspection")
+            .build()
+    }
+
+    #[test]
+    fn test_log_period_and_classes() {
+        let settings = OperatorSettings {
+            operator_id: Some("FIN87astrdge12k8".to_string()),
+            uas_classes: BTreeMap::from([("SN1".to_string(), UasClass::C0)]),
+            ..Default::default()
+        };
+        let flights = vec![
+            flight(2, "2026-05-02T09:00:00Z", "SN2"),
+            flight(1, "2026-05-01T10:00:00Z", "SN1"),
+            flight(3, "2026-06-01T09:00:00Z", "SN1"),
+        ];
+        let log = build_log(&flights, &settings, DurationRounding::Exact, None, NaiveDate::from_ymd_opt(2026, 5, 31));
+        assert_eq!(log.entries.iter().map(|e| e.flight_id).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(log.entries[0].uas_class, Some(UasClass::C0));
+        assert_eq!(log.entries[0].landing_utc.as_deref(), Some("2026-05-01 10:15"));
+        assert_eq!(log.entries[0].location.as_deref(), Some("60.16990, 24.93840"));
+        assert_eq!(log.entries[0].remarks.as_deref(), Some("Roof inspection"));
+        assert_eq!(log.unclassified_serials, vec!["SN2".to_string()]);
+        assert_eq!(log.category, "Open");
+        assert!(log_csv(&log).lines().nth(1).unwrap().starts_with("FIN87astrdge12k8,Open,2026-05-01 10:00,"));
+    }
+}
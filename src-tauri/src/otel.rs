@@ -0,0 +1,82 @@
+//! OpenTelemetry trace export (the `otel` feature, web server only).
+//!
+//! The server (one span per API request, named after the route), the import
+//! pipeline (upload, parser, post-import hooks) and the heavier database calls
+//! are instrumented with `tracing` spans. Without this feature, or when
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` isn't set, nothing collects them. Otherwise
+//! they are exported over OTLP/gRPC (e.g. to Jaeger or Tempo):
+//!
+//! - `OTEL_EXPORTER_OTLP_ENDPOINT`: collector address, e.g. `http://tempo:4317`
+//! - `OTEL_SERVICE_NAME`: service name (default `open-dronelog`)
+//! - `OTEL_TRACES_FILTER`: which spans to export, in `RUST_LOG` syntax
+//!   (default `info`; `info,open_dronelog::database=off` drops the database spans)
+//!
+//! Log output is unchanged and still goes through `log` / `env_logger`.
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::{runtime, Resource};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+const DEFAULT_SERVICE_NAME: &str = "open-dronelog";
+const DEFAULT_FILTER: &str = "info";
+
+/// Flushes the spans still queued and stops the exporter when dropped
+pub struct OtelGuard(TracerProvider);
+
+impl Drop for OtelGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.0.shutdown() {
+            log::warn!("Failed to flush OpenTelemetry spans: {}", e);
+        }
+    }
+}
+
+/// Start exporting spans if an OTLP endpoint is configured. Must be called
+/// from within the Tokio runtime; keep the guard until shutdown.
+pub fn init() -> Option<OtelGuard> {
+    let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+        .ok()
+        .filter(|e| !e.trim().is_empty())?;
+    let exporter = match opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint.clone())
+        .build()
+    {
+        Ok(exporter) => exporter,
+        Err(e) => {
+            log::error!("Failed to create OTLP exporter for {}: {}", endpoint, e);
+            return None;
+        }
+    };
+
+    let service_name = std::env::var("OTEL_SERVICE_NAME")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string());
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new(vec![
+            KeyValue::new("service.name", service_name.clone()),
+            KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+        ]))
+        .build();
+
+    let filter = match EnvFilter::try_from_env("OTEL_TRACES_FILTER") {
+        Ok(filter) => filter,
+        Err(_) => EnvFilter::new(DEFAULT_FILTER),
+    };
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer(DEFAULT_SERVICE_NAME));
+    if let Err(e) = tracing_subscriber::registry().with(filter).with(layer).try_init() {
+        log::error!("Failed to install the OpenTelemetry trace subscriber: {}", e);
+        return None;
+    }
+    opentelemetry::global::set_tracer_provider(provider.clone());
+
+    log::info!("Exporting OpenTelemetry traces to {} as '{}'", endpoint, service_name);
+    Some(OtelGuard(provider))
+}
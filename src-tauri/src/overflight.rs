@@ -0,0 +1,267 @@
+//! Overflight report for an area drawn on the map.
+//!
+//! Answers "how often, how long and how high did I fly over this place" —
+//! for replying to a neighbour's complaint or checking flights against local
+//! rules. The area is a polygon of `[lon, lat]` vertices. Telemetry positions
+//! inside its bounding box are loaded from the database and tested against
+//! the polygon; time inside is the sum of the intervals between consecutive
+//! inside samples, so a flight that leaves and comes back is not credited
+//! for the time spent outside. Flights are filtered by take-off date.
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::database::{Database, DatabaseError};
+
+/// Longer gaps between two inside samples are treated as leaving the area
+const MAX_SAMPLE_GAP_MS: i64 = 5_000;
+/// Upper edges of the height bands, in meters (the last band is open-ended)
+const HEIGHT_BANDS_M: [f64; 4] = [30.0, 60.0, 90.0, 120.0];
+
+#[derive(Debug, thiserror::Error)]
+pub enum OverflightError {
+    #[error("The area needs at least 3 points")]
+    TooFewPoints,
+
+    #[error("Invalid coordinates in the area: {0}, {1}")]
+    InvalidPoint(f64, f64),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+}
+
+/// A telemetry position inside the area's bounding box
+#[derive(Debug, Clone)]
+pub struct AreaSample {
+    pub flight_id: i64,
+    pub timestamp_ms: i64,
+    pub lat: f64,
+    pub lon: f64,
+    /// Height above takeoff
+    pub height: Option<f64>,
+}
+
+/// Time spent in one height band
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeightBand {
+    pub min_m: f64,
+    /// None for the top band
+    pub max_m: Option<f64>,
+    pub seconds: f64,
+}
+
+/// One flight that entered the area
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AreaFlight {
+    pub flight_id: i64,
+    pub display_name: String,
+    pub start_time: Option<String>,
+    pub seconds_inside: f64,
+    pub min_height_m: Option<f64>,
+    pub max_height_m: Option<f64>,
+    pub mean_height_m: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverflightReport {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub flight_count: i64,
+    pub total_minutes: f64,
+    pub max_height_m: Option<f64>,
+    pub height_bands: Vec<HeightBand>,
+    /// Newest first
+    pub flights: Vec<AreaFlight>,
+}
+
+/// Ray casting test with longitude as x and latitude as y
+pub(crate) fn contains(polygon: &[[f64; 2]], lon: f64, lat: f64) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let [xi, yi] = polygon[i];
+        let [xj, yj] = polygon[j];
+        if (yi > lat) != (yj > lat) && lon < (xj - xi) * (lat - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+fn validate(polygon: &[[f64; 2]]) -> Result<(), OverflightError> {
+    if polygon.len() < 3 {
+        return Err(OverflightError::TooFewPoints);
+    }
+    for &[lon, lat] in polygon {
+        if !(-180.0..=180.0).contains(&lon) || !(-90.0..=90.0).contains(&lat) {
+            return Err(OverflightError::InvalidPoint(lon, lat));
+        }
+    }
+    Ok(())
+}
+
+fn band_index(height: f64) -> usize {
+    HEIGHT_BANDS_M.iter().position(|edge| height < *edge).unwrap_or(HEIGHT_BANDS_M.len())
+}
+
+/// Per flight: seconds inside, and the inside seconds and heights for the bands
+struct FlightTally {
+    seconds: f64,
+    heights: Vec<f64>,
+    band_seconds: [f64; HEIGHT_BANDS_M.len() + 1],
+}
+
+/// Tally the samples (ordered by flight and time) that fall inside the polygon
+fn tally(polygon: &[[f64; 2]], samples: &[AreaSample]) -> BTreeMap<i64, FlightTally> {
+    let mut flights: BTreeMap<i64, FlightTally> = BTreeMap::new();
+    let mut prev: Option<&AreaSample> = None;
+    for sample in samples {
+        if !contains(polygon, sample.lon, sample.lat) {
+            prev = None;
+            continue;
+        }
+        let flight = flights.entry(sample.flight_id).or_insert_with(|| FlightTally {
+            seconds: 0.0,
+            heights: Vec::new(),
+            band_seconds: [0.0; HEIGHT_BANDS_M.len() + 1],
+        });
+        if let Some(h) = sample.height {
+            flight.heights.push(h);
+        }
+        if let Some(p) = prev.filter(|p| p.flight_id == sample.flight_id) {
+            let dt = sample.timestamp_ms - p.timestamp_ms;
+            if (0..=MAX_SAMPLE_GAP_MS).contains(&dt) {
+                let secs = dt as f64 / 1000.0;
+                flight.seconds += secs;
+                let height = p.height.or(sample.height).unwrap_or(0.0);
+                flight.band_seconds[band_index(height)] += secs;
+            }
+        }
+        prev = Some(sample);
+    }
+    flights
+}
+
+/// Build the report for the flights that entered `polygon` (`[lon, lat]`
+/// vertices) with a take-off date within `from..=to`
+pub fn build_report(
+    db: &Database,
+    polygon: &[[f64; 2]],
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<OverflightReport, OverflightError> {
+    validate(polygon)?;
+    let min_lon = polygon.iter().map(|p| p[0]).fold(f64::INFINITY, f64::min);
+    let max_lon = polygon.iter().map(|p| p[0]).fold(f64::NEG_INFINITY, f64::max);
+    let min_lat = polygon.iter().map(|p| p[1]).fold(f64::INFINITY, f64::min);
+    let max_lat = polygon.iter().map(|p| p[1]).fold(f64::NEG_INFINITY, f64::max);
+
+    let from_str = from.map(|d| d.to_string());
+    let to_str = to.map(|d| d.to_string());
+    let samples = db.get_positions_in_box(
+        (min_lat, max_lat),
+        (min_lon, max_lon),
+        from_str.as_deref(),
+        to_str.as_deref(),
+    )?;
+    let tallies = tally(polygon, &samples);
+
+    let mut band_seconds = [0.0; HEIGHT_BANDS_M.len() + 1];
+    let mut flights = Vec::new();
+    for (flight_id, t) in tallies {
+        for (total, secs) in band_seconds.iter_mut().zip(t.band_seconds) {
+            *total += secs;
+        }
+        let flight = db.get_flight_by_id(flight_id)?;
+        flights.push(AreaFlight {
+            flight_id,
+            display_name: flight.display_name,
+            start_time: flight.start_time,
+            seconds_inside: t.seconds,
+            min_height_m: t.heights.iter().copied().reduce(f64::min),
+            max_height_m: t.heights.iter().copied().reduce(f64::max),
+            mean_height_m: (!t.heights.is_empty()).then(|| t.heights.iter().sum::<f64>() / t.heights.len() as f64),
+        });
+    }
+    flights.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+
+    let height_bands = band_seconds
+        .iter()
+        .enumerate()
+        .map(|(i, secs)| HeightBand {
+            min_m: if i == 0 { 0.0 } else { HEIGHT_BANDS_M[i - 1] },
+            max_m: HEIGHT_BANDS_M.get(i).copied(),
+            seconds: *secs,
+        })
+        .collect();
+
+    Ok(OverflightReport {
+        from: from_str,
+        to: to_str,
+        flight_count: flights.len() as i64,
+        total_minutes: flights.iter().map(|f| f.seconds_inside).sum::<f64>() / 60.0,
+        max_height_m: flights.iter().filter_map(|f| f.max_height_m).reduce(f64::max),
+        height_bands,
+        flights,
+    })
+}
+
+/// One row per flight
+pub fn report_csv(report: &OverflightReport) -> String {
+    use crate::stats_export::csv_field;
+
+    let mut out = String::from("flight_id,name,start_time,minutes_inside,min_height_m,max_height_m,mean_height_m\n");
+    let height = |h: Option<f64>| h.map(|h| format!("{:.1}", h)).unwrap_or_default();
+    for f in &report.flights {
+        out.push_str(&format!(
+            "{},{},{},{:.2},{},{},{}\n",
+            f.flight_id,
+            csv_field(&f.display_name),
+            f.start_time.as_deref().unwrap_or(""),
+            f.seconds_inside / 60.0,
+            height(f.min_height_m),
+            height(f.max_height_m),
+            height(f.mean_height_m)
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(flight_id: i64, timestamp_ms: i64, lon: f64, height: f64) -> AreaSample {
+        AreaSample { flight_id, timestamp_ms, lat: 0.5, lon, height: Some(height) }
+    }
+
+    #[test]
+    fn test_time_inside_skips_excursions() {
+        let square = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+        assert!(contains(&square, 0.5, 0.5));
+        assert!(!contains(&square, 1.5, 0.5));
+
+        // In for 2 s, out, back in for 1 s at a higher band
+        let samples = vec![
+            sample(1, 0, 0.5, 20.0),
+            sample(1, 1000, 0.5, 20.0),
+            sample(1, 2000, 0.5, 20.0),
+            sample(1, 3000, 1.5, 20.0),
+            sample(1, 4000, 0.5, 100.0),
+            sample(1, 5000, 0.5, 100.0),
+            sample(2, 0, 1.5, 10.0),
+        ];
+        let tallies = tally(&square, &samples);
+        assert_eq!(tallies.len(), 1);
+        let t = &tallies[&1];
+        assert_eq!(t.seconds, 3.0);
+        assert_eq!(t.band_seconds[0], 2.0);
+        assert_eq!(t.band_seconds[3], 1.0);
+    }
+}
@@ -0,0 +1,336 @@
+//! Parser module for Parrot (Anafi, Bebop) `.pud` flight logs.
+//!
+//! A PUD file starts with a NUL-terminated JSON header describing the flight
+//! (product name, serial number, date) and the layout of the binary records
+//! that follow: `details_headers` lists every field with its name, type and
+//! size in bytes. Records are fixed-size, little-endian, one per sample.
+//! Field names differ slightly between firmware generations, so fields are
+//! looked up by a list of known aliases.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::database::Database;
+use crate::formats::{has_extension, FlightLogFormat, ParseFuture};
+use crate::models::{FlightMetadata, TelemetryPoint};
+use crate::parser::{LogParser, ParseInfo, ParseResult, ParserError};
+
+/// Coordinate reported while the aircraft has no GPS fix
+const NO_FIX_COORDINATE: f64 = 500.0;
+
+/// Parrot `.pud` flight logs (FreeFlight)
+pub struct ParrotFormat;
+
+impl FlightLogFormat for ParrotFormat {
+    fn name(&self) -> &'static str {
+        "Parrot PUD"
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        has_extension(path, "pud")
+    }
+
+    fn parse<'a>(&'a self, ctx: &'a LogParser<'_>, path: &'a Path, file_hash: &'a str) -> ParseFuture<'a> {
+        Box::pin(async move { ParrotParser::new(ctx.db()).parse(path, file_hash) })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PudHeader {
+    #[serde(default)]
+    product_name: Option<String>,
+    #[serde(default)]
+    serial_number: Option<String>,
+    #[serde(default)]
+    date: Option<String>,
+    details_headers: Vec<PudField>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PudField {
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    size: usize,
+}
+
+/// Decoded record layout: field name → (offset, type, size)
+struct Layout {
+    fields: HashMap<String, (usize, String, usize)>,
+    record_size: usize,
+}
+
+impl Layout {
+    fn new(headers: &[PudField]) -> Self {
+        let mut fields = HashMap::new();
+        let mut offset = 0;
+        for f in headers {
+            fields.insert(f.name.clone(), (offset, f.kind.clone(), f.size));
+            offset += f.size;
+        }
+        Self { fields, record_size: offset }
+    }
+
+    /// Read the first present alias as a number
+    fn get(&self, record: &[u8], aliases: &[&str]) -> Option<f64> {
+        aliases.iter().find_map(|name| {
+            let (offset, kind, size) = self.fields.get(*name)?;
+            read_value(record.get(*offset..*offset + *size)?, kind)
+        })
+    }
+}
+
+/// Decode one little-endian value of a PUD field
+fn read_value(b: &[u8], kind: &str) -> Option<f64> {
+    let v = match (kind, b.len()) {
+        ("double", 8) => f64::from_le_bytes(b.try_into().ok()?),
+        ("float", 4) => f32::from_le_bytes(b.try_into().ok()?) as f64,
+        ("boolean", _) | ("bool", _) => f64::from(u8::from(b.iter().any(|&x| x != 0))),
+        (_, 1) => b[0] as i8 as f64,
+        (_, 2) => i16::from_le_bytes(b.try_into().ok()?) as f64,
+        (_, 4) => i32::from_le_bytes(b.try_into().ok()?) as f64,
+        (_, 8) => i64::from_le_bytes(b.try_into().ok()?) as f64,
+        _ => return None,
+    };
+    v.is_finite().then_some(v)
+}
+
+/// Parrot `flying_state` values
+fn flying_state_label(state: i32) -> &'static str {
+    match state {
+        0 => "Landed",
+        1 => "TakingOff",
+        2 => "Hovering",
+        3 => "Flying",
+        4 => "Landing",
+        5 => "Emergency",
+        6 => "UserTakeOff",
+        7 => "MotorRamping",
+        8 => "EmergencyLanding",
+        _ => "Unknown",
+    }
+}
+
+/// Parse the header date, e.g. `2016-04-04T172236+0200`
+fn parse_pud_date(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_str(s, "%Y-%m-%dT%H%M%S%z")
+        .or_else(|_| DateTime::parse_from_rfc3339(s))
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Split a PUD file into its JSON header and the record bytes
+fn split_header(data: &[u8]) -> Result<(PudHeader, &[u8]), ParserError> {
+    let end = data
+        .iter()
+        .position(|&b| b == 0)
+        .ok_or_else(|| ParserError::Parse("PUD header is not terminated".to_string()))?;
+    let header: PudHeader = serde_json::from_slice(&data[..end])
+        .map_err(|e| ParserError::Parse(format!("Invalid PUD header: {}", e)))?;
+    Ok((header, &data[end + 1..]))
+}
+
+/// Decode all records into telemetry points (timestamps relative to the first record)
+fn decode_records(layout: &Layout, data: &[u8]) -> Vec<TelemetryPoint> {
+    if layout.record_size == 0 {
+        return Vec::new();
+    }
+    let mut points = Vec::with_capacity(data.len() / layout.record_size);
+    let mut first_time: Option<f64> = None;
+
+    for record in data.chunks_exact(layout.record_size) {
+        let Some(time) = layout.get(record, &["time"]) else {
+            continue;
+        };
+        let t0 = *first_time.get_or_insert(time);
+
+        let gps_ok = layout.get(record, &["product_gps_available", "gps_available"]).map_or(true, |v| v != 0.0);
+        let lat = layout.get(record, &["product_gps_latitude", "gps_latitude"]);
+        let lon = layout.get(record, &["product_gps_longitude", "gps_longitude"]);
+        let (latitude, longitude) = match (lat, lon) {
+            (Some(lat), Some(lon))
+                if gps_ok
+                    && lat.abs() <= 90.0
+                    && lon.abs() <= 180.0
+                    && lat != NO_FIX_COORDINATE
+                    && !(lat.abs() < 1e-6 && lon.abs() < 1e-6) =>
+            {
+                (Some(lat), Some(lon))
+            }
+            _ => (None, None),
+        };
+
+        let (vx, vy, vz) = (
+            layout.get(record, &["speed_vx"]),
+            layout.get(record, &["speed_vy"]),
+            layout.get(record, &["speed_vz"]),
+        );
+        // Altitude is recorded in millimetres above take-off
+        let height = layout.get(record, &["altitude"]).map(|mm| mm / 1000.0);
+
+        points.push(TelemetryPoint {
+            timestamp_ms: (time - t0).round() as i64,
+            latitude,
+            longitude,
+            altitude: height,
+            height,
+            speed: vx.zip(vy).map(|(x, y)| (x * x + y * y).sqrt()),
+            velocity_x: vx,
+            velocity_y: vy,
+            velocity_z: vz,
+            roll: layout.get(record, &["angle_phi"]).map(f64::to_degrees),
+            pitch: layout.get(record, &["angle_theta"]).map(f64::to_degrees),
+            yaw: layout.get(record, &["angle_psi"]).map(f64::to_degrees),
+            battery_percent: layout.get(record, &["battery_level"]).map(|v| v as i32),
+            satellites: layout.get(record, &["product_gps_sv_number", "gps_sv_number"]).map(|v| v as i32),
+            flight_mode: layout.get(record, &["flying_state"]).map(|v| flying_state_label(v as i32).to_string()),
+            rc_signal: layout.get(record, &["wifi_signal"]).map(|v| v as i32),
+            ..Default::default()
+        });
+    }
+    points
+}
+
+/// Parrot PUD parser
+pub struct ParrotParser<'a> {
+    db: &'a Database,
+}
+
+impl<'a> ParrotParser<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Parse a PUD file
+    pub fn parse(&self, file_path: &Path, file_hash: &str) -> Result<ParseResult, ParserError> {
+        let parse_start = std::time::Instant::now();
+        log::info!("Parsing Parrot PUD file: {:?}", file_path);
+
+        let data = std::fs::read(file_path)?;
+        let (header, records) = split_header(&data)?;
+        let layout = Layout::new(&header.details_headers);
+        let points = decode_records(&layout, records);
+        if !points.iter().any(|p| p.latitude.is_some()) {
+            log::warn!("No positioned records found in PUD file");
+            return Err(ParserError::NoTelemetryData);
+        }
+
+        let parser = LogParser::new(self.db);
+        let stats = parser.calculate_stats(&points);
+
+        let file_name = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let display_name = file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(&file_name)
+            .to_string();
+
+        let start_time = header.date.as_deref().and_then(parse_pud_date);
+        let end_time = start_time.map(|t| t + chrono::Duration::milliseconds((stats.duration_secs * 1000.0) as i64));
+
+        let metadata = FlightMetadata {
+            id: self.db.generate_flight_id(),
+            file_name,
+            display_name,
+            file_hash: Some(file_hash.to_string()),
+            drone_model: Some(header.product_name.clone().unwrap_or_else(|| "Parrot".to_string())),
+            drone_serial: header.serial_number.clone().filter(|s| !s.is_empty()),
+            aircraft_name: None,
+            battery_serial: None,
+            cycle_count: None,
+            start_time,
+            end_time,
+            duration_secs: Some(stats.duration_secs),
+            total_distance: Some(stats.total_distance_m),
+            max_altitude: Some(stats.max_altitude_m),
+            max_speed: Some(stats.max_speed_ms),
+            home_lat: stats.home_location.map(|h| h[1]),
+            home_lon: stats.home_location.map(|h| h[0]),
+            point_count: points.len() as i32,
+            photo_count: 0,
+            video_count: 0,
+            rc_serial: None,
+            battery_life: None,
+        };
+
+        log::info!(
+            "Parrot parse complete in {:.1}s: duration={:.1}s, distance={:.0}m, max_alt={:.1}m, points={}",
+            parse_start.elapsed().as_secs_f64(),
+            stats.duration_secs,
+            stats.total_distance_m,
+            stats.max_altitude_m,
+            points.len()
+        );
+
+        let mut tags = LogParser::generate_smart_tags(&metadata, &stats);
+        tags.insert(0, "Parrot".to_string());
+
+        Ok(ParseResult { metadata, points, tags, manual_tags: Vec::new(), notes: None, color: None, messages: Vec::new(), info: ParseInfo::new("Parrot PUD") })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_pud() -> Vec<u8> {
+        let header = r#"{"product_name":"ANAFI","serial_number":"PI0001","date":"2023-06-01T120000+0200","details_headers":[
+            {"name":"time","type":"integer","size":4},
+            {"name":"battery_level","type":"integer","size":4},
+            {"name":"product_gps_available","type":"boolean","size":1},
+            {"name":"product_gps_longitude","type":"double","size":8},
+            {"name":"product_gps_latitude","type":"double","size":8},
+            {"name":"speed_vx","type":"float","size":4},
+            {"name":"speed_vy","type":"float","size":4},
+            {"name":"altitude","type":"integer","size":4},
+            {"name":"flying_state","type":"integer","size":1}]}"#;
+        let mut data = header.as_bytes().to_vec();
+        data.push(0);
+        for (t, gps, alt_mm) in [(1000i32, false, 0i32), (1200, true, 12_500)] {
+            data.extend_from_slice(&t.to_le_bytes());
+            data.extend_from_slice(&87i32.to_le_bytes());
+            data.push(u8::from(gps));
+            let (lon, lat) = if gps { (2.35, 48.85) } else { (NO_FIX_COORDINATE, NO_FIX_COORDINATE) };
+            data.extend_from_slice(&f64::to_le_bytes(lon));
+            data.extend_from_slice(&f64::to_le_bytes(lat));
+            data.extend_from_slice(&3.0f32.to_le_bytes());
+            data.extend_from_slice(&4.0f32.to_le_bytes());
+            data.extend_from_slice(&alt_mm.to_le_bytes());
+            data.push(3);
+        }
+        data
+    }
+
+    #[test]
+    fn test_decode_pud() {
+        let data = sample_pud();
+        let (header, records) = split_header(&data).unwrap();
+        assert_eq!(header.product_name.as_deref(), Some("ANAFI"));
+        let layout = Layout::new(&header.details_headers);
+        assert_eq!(layout.record_size, 38);
+
+        let points = decode_records(&layout, records);
+        assert_eq!(points.len(), 2);
+        assert!(points[0].latitude.is_none());
+        assert_eq!(points[1].timestamp_ms, 200);
+        assert!((points[1].latitude.unwrap() - 48.85).abs() < 1e-9);
+        assert!((points[1].height.unwrap() - 12.5).abs() < 1e-9);
+        assert!((points[1].speed.unwrap() - 5.0).abs() < 1e-6);
+        assert_eq!(points[1].battery_percent, Some(87));
+        assert_eq!(points[1].flight_mode.as_deref(), Some("Flying"));
+    }
+
+    #[test]
+    fn test_pud_date() {
+        let dt = parse_pud_date("2023-06-01T120000+0200").unwrap();
+        assert_eq!(dt.to_rfc3339(), "2023-06-01T10:00:00+00:00");
+    }
+}
@@ -5,6 +5,7 @@
 //! - Extracting telemetry data points
 //! - File hash calculation for duplicate detection
 //! - V13+ encrypted log handling with API key fetching
+//! - DJI Goggles (Avata / DJI FPV) flight records without RC data or a synced clock
 //! - Panic/timeout protection for untrusted file parsing
 
 use std::fs::{self, File};
@@ -13,7 +14,7 @@ use std::panic;
 use std::path::Path;
 use std::time::Duration;
 
-use chrono::{DateTime, Utc, Timelike};
+use chrono::{DateTime, Datelike, Utc, Timelike};
 use sha2::{Digest, Sha256};
 use thiserror::Error;
 use tokio::time::timeout;
@@ -28,10 +29,10 @@ use dji_log_parser::DJILog;
 
 use crate::api::DjiApi;
 use crate::database::Database;
-use crate::airdata_parser::AirdataParser;
 use crate::dronelogbook_parser::DroneLogbookParser;
-use crate::litchi_parser::LitchiParser;
-use crate::models::{FlightMessage, FlightMetadata, FlightStats, TelemetryPoint};
+use crate::formats::{builtin_formats, has_extension, FlightLogFormat, ParseFuture};
+use crate::models::{compute_field_coverage, FlightMessage, FlightMetadata, FlightStats, ImportReport, TelemetryPoint};
+use crate::quirks::{self, ModelQuirk};
 
 /// Maximum time allowed for parsing a single log file (seconds)
 const PARSE_TIMEOUT_SECS: u64 = 40;
@@ -126,7 +127,7 @@ pub enum ParserError {
     #[error("Parsing timed out after {0} seconds — file may be corrupt or unsupported")]
     Timeout(u64),
 
-    #[error("Incompatible file format — only DJI flight logs (.txt), Litchi CSV exports, Airdata CSV exports, and Open DroneLog CSV exports are supported")]
+    #[error("Incompatible file format — only DJI flight logs (.txt), DJI aircraft logs (.DAT) and their DJI Assistant 2 CSV exports, Litchi CSV exports, Airdata CSV exports, Skydio and DJI FlightHub 2 JSON logs, Parrot PUD logs, Betaflight/INAV blackbox logs (.bbl/.bfl), and Open DroneLog CSV/JSON/GPX exports are supported")]
     IncompatibleFile,
 }
 
@@ -143,12 +144,107 @@ pub struct ParseResult {
     pub color: Option<String>,
     /// App messages (tips and warnings) from the flight log
     pub messages: Vec<FlightMessage>,
+    /// How the file was decoded (stored in the import report)
+    pub info: ParseInfo,
+}
+
+/// Decoding details recorded alongside each imported flight
+#[derive(Debug, Clone, Default)]
+pub struct ParseInfo {
+    /// Parser that produced the flight (e.g. "DJI TXT", "Litchi CSV")
+    pub format: String,
+    /// DJI FlightRecord version, when the format has one
+    pub record_version: Option<i32>,
+    /// Decryption path: "none", "keychain" or "keychain (DJIFly fallback)"
+    pub decryption: Option<String>,
+    /// Model quirk applied during normalization (see `quirks`)
+    pub quirk: Option<String>,
+    /// Logged speed replaced by speed from positions (see `speed_fallback`)
+    pub speed_derived: bool,
+    /// GPS fixes removed as glitches (see `gps_filter`)
+    pub gps_outliers: usize,
+    /// Per-stage timings, stored as the flight's import profile
+    pub timings: crate::import_profile::ParseTimings,
+}
+
+impl ParseInfo {
+    pub fn new(format: &str) -> Self {
+        Self {
+            format: format.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+impl ParseResult {
+    /// Build the validation report for this parse once the flight has been inserted
+    pub fn import_report(&self, flight_id: i64) -> ImportReport {
+        ImportReport {
+            flight_id,
+            format: self.info.format.clone(),
+            record_version: self.info.record_version,
+            decryption: self.info.decryption.clone(),
+            quirk: self.info.quirk.clone(),
+            speed_derived: self.info.speed_derived,
+            gps_outliers: self.info.gps_outliers,
+            point_count: self.points.len(),
+            field_coverage: compute_field_coverage(&self.points),
+            created_at: None,
+        }
+    }
+
+    /// Format-independent cleanup after parsing: remove GPS glitches (see
+    /// `gps_filter`), then replace a junk speed field with speed from
+    /// positions (see `speed_fallback`), updating the stats they change
+    fn clean_telemetry(&mut self, parser: &LogParser) {
+        let first_fix = |points: &[TelemetryPoint]| points.iter().find_map(|p| p.latitude.zip(p.longitude));
+        let first_fix_before = first_fix(&self.points);
+        let gps = crate::gps_filter::filter(&mut self.points);
+        if gps.total() > 0 {
+            log::info!("Removed {} invalid and {} teleporting GPS fixes", gps.invalid, gps.teleports);
+            let stats = parser.calculate_stats(&self.points);
+            self.metadata.total_distance = Some(stats.total_distance_m);
+            // Parsers without a home record take the first fix, which may have been removed
+            let home = self.metadata.home_lat.zip(self.metadata.home_lon);
+            let home_removed = home.is_none() || (home == first_fix_before && first_fix(&self.points) != first_fix_before);
+            if home_removed {
+                self.metadata.home_lat = stats.home_location.map(|h| h[1]);
+                self.metadata.home_lon = stats.home_location.map(|h| h[0]);
+            }
+            self.info.gps_outliers = gps.total();
+        }
+
+        if crate::speed_fallback::apply(&mut self.points) {
+            log::info!("Logged speed doesn't match the GPS positions, using speed derived from positions");
+            self.metadata.max_speed = self.points.iter().filter_map(|p| p.speed).reduce(f64::max);
+            self.info.speed_derived = true;
+        }
+    }
+}
+
+/// DJI FlightRecord `.txt` logs from the DJI GO / DJI Fly / Goggles apps
+pub struct DjiTxtFormat;
+
+impl FlightLogFormat for DjiTxtFormat {
+    fn name(&self) -> &'static str {
+        "DJI TXT"
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        has_extension(path, "txt")
+    }
+
+    fn parse<'a>(&'a self, ctx: &'a LogParser<'_>, path: &'a Path, file_hash: &'a str) -> ParseFuture<'a> {
+        Box::pin(ctx.parse_dji_txt(path, file_hash))
+    }
 }
 
 /// DJI Log Parser wrapper
 pub struct LogParser<'a> {
     db: &'a Database,
     api: DjiApi,
+    /// Registered log formats, tried in order (see `formats`)
+    formats: Vec<Box<dyn FlightLogFormat>>,
 }
 
 impl<'a> LogParser<'a> {
@@ -156,9 +252,15 @@ impl<'a> LogParser<'a> {
         Self {
             db,
             api: DjiApi::with_app_data_dir(db.data_dir.clone()),
+            formats: builtin_formats(),
         }
     }
 
+    /// Database handle for format implementations
+    pub fn db(&self) -> &'a Database {
+        self.db
+    }
+
     /// Calculate SHA256 hash of a file for duplicate detection
     pub fn calculate_file_hash(path: &Path) -> Result<String, ParserError> {
         let file = File::open(path)?;
@@ -177,8 +279,19 @@ impl<'a> LogParser<'a> {
         Ok(format!("{:x}", hasher.finalize()))
     }
 
-    /// Parse a flight log file (DJI .txt or Litchi .csv) and extract all telemetry data
+    /// Parse a flight log file (DJI .txt/.DAT or supported CSV) and extract all telemetry data
     pub async fn parse_log(&self, file_path: &Path) -> Result<ParseResult, ParserError> {
+        self.parse_log_checked(file_path, true).await
+    }
+
+    /// Parse a log that may already be imported, to re-parse an existing flight
+    /// with the current parsers (see `reparse`)
+    pub async fn reparse_log(&self, file_path: &Path) -> Result<ParseResult, ParserError> {
+        self.parse_log_checked(file_path, false).await
+    }
+
+    #[tracing::instrument(name = "parse_log", skip_all, fields(file = %file_path.display(), format = tracing::field::Empty))]
+    async fn parse_log_checked(&self, file_path: &Path, reject_imported: bool) -> Result<ParseResult, ParserError> {
         let parse_start = std::time::Instant::now();
         let file_size = fs::metadata(file_path).map(|m| m.len()).unwrap_or(0);
         log::info!(
@@ -191,49 +304,41 @@ impl<'a> LogParser<'a> {
         let file_hash = Self::calculate_file_hash(file_path)?;
         log::debug!("File hash: {}", file_hash);
 
-        if let Some(matching_flight) = self
-            .db
-            .is_file_imported(&file_hash)
-            .map_err(|e| ParserError::Parse(e.to_string()))?
-        {
+        let already_imported = if reject_imported {
+            self.db.is_file_imported(&file_hash).map_err(|e| ParserError::Parse(e.to_string()))?
+        } else {
+            None
+        };
+        if let Some(matching_flight) = already_imported {
             log::info!("File already imported (hash match), skipping — matches flight: {}", matching_flight);
             return Err(ParserError::AlreadyImported(matching_flight));
         }
-
-        // Detect file format and route to appropriate parser
-        let builtin_err;
-
-        // Try built-in CSV parsers first
-        if DroneLogbookParser::is_dronelogbook_csv(file_path) {
-            log::info!("Detected Drone Logbook CSV format, using DroneLogbookParser");
-            match DroneLogbookParser::new(self.db).parse(file_path, &file_hash) {
-                Ok(res) => return Ok(res),
-                Err(e) => builtin_err = e,
-            }
-        } else if AirdataParser::is_airdata_csv(file_path) {
-            log::info!("Detected Airdata CSV format, using AirdataParser");
-            match AirdataParser::new(self.db).parse(file_path, &file_hash) {
-                Ok(res) => return Ok(res),
-                Err(e) => builtin_err = e,
-            }
-        } else if LitchiParser::is_litchi_csv(file_path) {
-            log::info!("Detected Litchi CSV format, using LitchiParser");
-            match LitchiParser::new(self.db).parse(file_path, &file_hash) {
-                Ok(res) => return Ok(res),
-                Err(e) => builtin_err = e,
-            }
-        } else {
-            let ext = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
-            if ext.eq_ignore_ascii_case("txt") {
-                // Try DJI log parser
-                match self.parse_dji_txt(file_path, &file_hash, parse_start).await {
-                    Ok(res) => return Ok(res),
-                    Err(e) => builtin_err = e,
+        let read_ms = parse_start.elapsed().as_secs_f64() * 1000.0;
+        let stage_start = std::time::Instant::now();
+
+        // Detect file format and route to the first registered format that claims it
+        let builtin_err = match self.formats.iter().find(|f| f.detect(file_path)) {
+            Some(format) => {
+                log::info!("Detected {} format", format.name());
+                tracing::Span::current().record("format", format.name());
+                match crate::formats::catch_panics(format.parse(self, file_path, &file_hash)).await {
+                    Ok(mut res) => {
+                        if res.info.format.is_empty() {
+                            res.info.format = format.name().to_string();
+                        }
+                        let elapsed_ms = stage_start.elapsed().as_secs_f64() * 1000.0;
+                        res.info.timings.file_size_bytes = file_size;
+                        res.info.timings.read_ms = read_ms;
+                        res.info.timings.parse_ms = (elapsed_ms - res.info.timings.decrypt_ms.unwrap_or(0.0)).max(0.0);
+                        res.clean_telemetry(self);
+                        log::debug!("{} parse finished in {:.1}s", format.name(), parse_start.elapsed().as_secs_f64());
+                        return Ok(res);
+                    }
+                    Err(e) => e,
                 }
-            } else {
-                builtin_err = ParserError::IncompatibleFile;
             }
-        }
+            None => ParserError::IncompatibleFile,
+        };
 
         // Custom Plugin Fallback
         let err = builtin_err;
@@ -267,7 +372,8 @@ impl<'a> LogParser<'a> {
                 let output_csv = temp_dir.join(format!("{}_plugin_out.csv", uuid::Uuid::new_v4()));
                 log::debug!("Custom parser output temp path: {:?}", output_csv);
 
-                if let Err(plugin_err) = crate::plugins::run_plugin(mapping, file_path, &output_csv).await {
+                let plugin_span = tracing::info_span!("custom_parser", extension = %ext);
+                if let Err(plugin_err) = tracing::Instrument::instrument(crate::plugins::run_plugin(mapping, file_path, &output_csv), plugin_span).await {
                     log::error!(
                         "Custom parser subprocess failed for extension '.{}': {}",
                         ext,
@@ -297,8 +403,13 @@ impl<'a> LogParser<'a> {
                 let _ = fs::remove_file(&output_csv); // Clean up temp file
 
                 match result {
-                    Ok(res) => {
+                    Ok(mut res) => {
                         log::info!("Custom parser fallback succeeded for '.{}'", ext);
+                        res.info = ParseInfo::new(&format!("Custom plugin (.{})", ext));
+                        res.info.timings.file_size_bytes = file_size;
+                        res.info.timings.read_ms = read_ms;
+                        res.info.timings.parse_ms = stage_start.elapsed().as_secs_f64() * 1000.0;
+                        res.clean_telemetry(self);
                         return Ok(res);
                     }
                     Err(e) => {
@@ -323,7 +434,9 @@ impl<'a> LogParser<'a> {
     }
 
     /// Parse a DJI TXT log file
-    async fn parse_dji_txt(&self, file_path: &Path, file_hash: &str, parse_start: std::time::Instant) -> Result<ParseResult, ParserError> {
+    #[tracing::instrument(skip_all)]
+    async fn parse_dji_txt(&self, file_path: &Path, file_hash: &str) -> Result<ParseResult, ParserError> {
+        let parse_start = std::time::Instant::now();
         // Read the file
         let file_data = fs::read(file_path)?;
 
@@ -367,7 +480,9 @@ impl<'a> LogParser<'a> {
         );
 
         // Check if we need an encryption key for V13+ logs
+        let frames_start = std::time::Instant::now();
         let (frames, used_djifly_fallback, component_serials) = self.get_frames(&parser).await?;
+        let decrypt_ms = (parser.version >= 13).then(|| frames_start.elapsed().as_secs_f64() * 1000.0);
         log::info!("Extracted {} frames from log", frames.len());
 
         // Log when ComponentSerial provides a longer serial than the header
@@ -397,7 +512,8 @@ impl<'a> LogParser<'a> {
 
         // Extract telemetry points
         let details_total_time_secs = parser.details.total_time as f64;
-        let points = self.extract_telemetry(&frames, details_total_time_secs);
+        let quirk = self.detect_quirk(&parser, &frames);
+        let points = self.extract_telemetry(&frames, details_total_time_secs, quirk);
         log::info!(
             "Extracted {} valid telemetry points from {} frames ({} skipped)",
             points.len(),
@@ -434,27 +550,40 @@ impl<'a> LogParser<'a> {
         // Count photo and video capture events from telemetry transitions
         let (photo_count, video_count) = crate::models::count_media_events(&points);
 
+        // Goggles records (Avata / DJI FPV) are written before the goggles sync their clock
+        // with a phone, leaving an unset details timestamp. Fall back to the timestamp in the
+        // record's file name in that case.
+        let drone_model = self.extract_drone_model(&parser);
+        let is_fpv = drone_model.as_deref().is_some_and(is_fpv_product);
+        let start_time = match self.extract_start_time(&parser) {
+            Some(t) => Some(t),
+            None => {
+                let fallback = start_time_from_file_name(file_path);
+                log::info!("Details header has no valid start time, using file name timestamp: {:?}", fallback);
+                fallback
+            }
+        };
+        let flight_duration_secs = if details_total_time_secs > 0.0 {
+            details_total_time_secs
+        } else {
+            stats.duration_secs
+        };
+
         let metadata = FlightMetadata {
             id: self.db.generate_flight_id(),
             file_name,
             display_name,
             file_hash: Some(file_hash.to_string()),
-            drone_model: self.extract_drone_model(&parser),
+            drone_model,
             drone_serial: component_serials.aircraft.clone()
                 .or_else(|| self.extract_serial(&parser)),
             aircraft_name: self.extract_aircraft_name(&parser),
             battery_serial: component_serials.battery.clone()
                 .or_else(|| self.extract_battery_serial(&parser)),
             cycle_count: component_serials.cycle_count,
-            start_time: self.extract_start_time(&parser),
-            end_time: self.extract_end_time(&parser),
-            duration_secs: Some(
-                if details_total_time_secs > 0.0 {
-                    details_total_time_secs
-                } else {
-                    stats.duration_secs
-                }
-            ),
+            start_time,
+            end_time: start_time.map(|t| t + chrono::Duration::milliseconds((flight_duration_secs * 1000.0) as i64)),
+            duration_secs: Some(flight_duration_secs),
             total_distance: Some(stats.total_distance_m),
             max_altitude: Some(stats.max_altitude_m),
             max_speed: Some(stats.max_speed_ms),
@@ -490,10 +619,32 @@ impl<'a> LogParser<'a> {
             tags.push("M-SDK".to_string());
             log::info!("Added M-SDK tag (third-party app detected via DJIFly fallback)");
         }
+
+        // Tag FPV-class aircraft recorded through the goggles
+        if is_fpv {
+            tags.insert(0, "FPV".to_string());
+        }
         
         log::info!("Generated smart tags: {:?}", tags);
 
-        Ok(ParseResult { metadata, points, tags, manual_tags: Vec::new(), notes: None, color: None, messages })
+        let info = ParseInfo {
+            format: "DJI TXT".to_string(),
+            record_version: Some(parser.version as i32),
+            decryption: Some(
+                if parser.version < 13 {
+                    "none"
+                } else if used_djifly_fallback {
+                    "keychain (DJIFly fallback)"
+                } else {
+                    "keychain"
+                }
+                .to_string(),
+            ),
+            quirk: quirk.map(|q| q.id.to_string()),
+            timings: crate::import_profile::ParseTimings { decrypt_ms, ..Default::default() },
+        };
+
+        Ok(ParseResult { metadata, points, tags, manual_tags: Vec::new(), notes: None, color: None, messages, info })
 
     }
 
@@ -503,15 +654,7 @@ impl<'a> LogParser<'a> {
 
         // Night Flight: if local flying time is after 7 PM (19:00) or before 6 AM
         if let Some(start_time) = metadata.start_time {
-            // Use home location to estimate timezone offset (rough: 1 hour per 15° longitude)
-            let utc_hour = start_time.hour();
-            let tz_offset_hours = if let Some(home) = stats.home_location {
-                (home[0] / 15.0).round() as i32 // lon / 15 = approx TZ offset
-            } else {
-                0
-            };
-            let local_hour = ((utc_hour as i32 + tz_offset_hours) % 24 + 24) % 24;
-            if local_hour >= 19 || local_hour < 6 {
+            if is_night_start(start_time, stats.home_location.map(|home| home[0])) {
                 tags.push("Night Flight".to_string());
             }
         }
@@ -801,6 +944,7 @@ impl<'a> LogParser<'a> {
     /// to prevent panics from crashing the application.
     /// Returns (frames, used_djifly_fallback) where used_djifly_fallback indicates
     /// if the DJIFly department override was needed (third-party app like Dronelink).
+    #[tracing::instrument(skip_all, fields(log_version = parser.version))]
     async fn get_frames(&self, parser: &DJILog) -> Result<(Vec<Frame>, bool, ComponentSerials), ParserError> {
         // Version 13+ requires keychains for decryption
         let (keychains, used_djifly_fallback) = if parser.version >= 13 {
@@ -921,8 +1065,34 @@ impl<'a> LogParser<'a> {
         }
     }
 
+    /// Look up a model-specific normalization rule for this log (see `quirks`).
+    /// The raw height/speed maxima feed the quirk's plausibility guard.
+    fn detect_quirk(&self, parser: &DJILog, frames: &[Frame]) -> Option<&'static ModelQuirk> {
+        let model = format!("{:?}", parser.details.product_type);
+        let raw_max_height = frames
+            .iter()
+            .map(|f| f.osd.height)
+            .filter(|h| h.is_finite())
+            .fold(0.0_f32, f32::max);
+        let raw_max_speed = frames
+            .iter()
+            .map(|f| (f.osd.x_speed.powi(2) + f.osd.y_speed.powi(2)).sqrt())
+            .filter(|s| s.is_finite())
+            .fold(0.0_f32, f32::max);
+
+        let quirk = quirks::find_quirk(&model, parser.version, raw_max_height, raw_max_speed);
+        if let Some(q) = quirk {
+            log::info!(
+                "Applying model quirk '{}' to {} (log v{}): height x{}, speed x{} (raw max height={:.1}, raw max speed={:.1})",
+                q.id, model, parser.version, q.height_scale, q.speed_scale, raw_max_height, raw_max_speed
+            );
+        }
+        quirk
+    }
+
     /// Extract telemetry points from parsed frames
-    fn extract_telemetry(&self, frames: &[Frame], details_total_time_secs: f64) -> Vec<TelemetryPoint> {
+    #[tracing::instrument(skip_all, fields(frames = frames.len()))]
+    fn extract_telemetry(&self, frames: &[Frame], details_total_time_secs: f64, quirk: Option<&ModelQuirk>) -> Vec<TelemetryPoint> {
         let mut points = Vec::with_capacity(frames.len());
         let mut timestamp_ms: i64 = 0;
 
@@ -949,6 +1119,9 @@ impl<'a> LogParser<'a> {
         let mut prev_is_photo = false;
         let mut prev_is_video = false;
 
+        // Model-specific unit corrections (1.0 when no quirk applies)
+        let (height_scale, speed_scale) = quirk.map_or((1.0_f32, 1.0_f32), |q| (q.height_scale, q.speed_scale));
+
         for frame in frames {
             let osd = &frame.osd;
             let gimbal = &frame.gimbal;
@@ -1016,27 +1189,33 @@ impl<'a> LogParser<'a> {
             // else: latitude/longitude remain None (from Default)
 
             // Clamp altitude/height to physically plausible range (reject garbage)
-            let alt = osd.altitude as f64;
-            let height = osd.height as f64;
+            let alt = (osd.altitude * height_scale) as f64;
+            let height = (osd.height * height_scale) as f64;
             point.altitude = if alt.abs() < 10_000.0 { Some(alt) } else { skipped_alt_clamp += 1; None };
             point.height = if height.abs() < 10_000.0 { Some(height) } else { skipped_alt_clamp += 1; None };
-            point.vps_height = Some(osd.vps_height as f64);
+            point.vps_height = Some((osd.vps_height * height_scale) as f64);
 
+            let (x_speed, y_speed, z_speed) = (
+                osd.x_speed * speed_scale,
+                osd.y_speed * speed_scale,
+                osd.z_speed * speed_scale,
+            );
             point.speed = if has_gps_lock && gps_in_range {
-                let spd = (osd.x_speed.powi(2) + osd.y_speed.powi(2)).sqrt() as f64;
+                let spd = (x_speed.powi(2) + y_speed.powi(2)).sqrt() as f64;
                 if spd < 100.0 { Some(spd) } else { skipped_speed_clamp += 1; None } // >100 m/s is clearly garbage
             } else {
                 None // Speed from 0,0 origin is meaningless
             };
-            point.velocity_x = if has_gps_lock && gps_in_range { Some(osd.x_speed as f64) } else { None };
-            point.velocity_y = if has_gps_lock && gps_in_range { Some(osd.y_speed as f64) } else { None };
-            point.velocity_z = if has_gps_lock && gps_in_range { Some(osd.z_speed as f64) } else { None };
+            point.velocity_x = if has_gps_lock && gps_in_range { Some(x_speed as f64) } else { None };
+            point.velocity_y = if has_gps_lock && gps_in_range { Some(y_speed as f64) } else { None };
+            point.velocity_z = if has_gps_lock && gps_in_range { Some(z_speed as f64) } else { None };
             point.pitch = Some(osd.pitch as f64);
             point.roll = Some(osd.roll as f64);
             point.yaw = Some(osd.yaw as f64);
             point.satellites = Some(osd.gps_num as i32);
             point.gps_signal = Some(osd.gps_level as i32);
             point.flight_mode = osd.flyc_state.map(|state| format!("{:?}", state));
+            point.is_flying = Some(osd.is_motor_on && !osd.is_on_ground);
 
             point.gimbal_pitch = Some(gimbal.pitch as f64);
             point.gimbal_roll = Some(gimbal.roll as f64);
@@ -1066,11 +1245,16 @@ impl<'a> LogParser<'a> {
             point.rc_downlink = rc.downlink_signal.map(i32::from);
             point.rc_signal = rc.downlink_signal.or(rc.uplink_signal).map(i32::from);
 
-            // RC stick inputs: raw u16 centered at 1024 (range 0..2048) → normalized to -100..+100
-            point.rc_aileron = Some(((rc.aileron as f64) - 1024.0) / 1024.0 * 100.0);
-            point.rc_elevator = Some(((rc.elevator as f64) - 1024.0) / 1024.0 * 100.0);
-            point.rc_throttle = Some(((rc.throttle as f64) - 1024.0) / 1024.0 * 100.0);
-            point.rc_rudder = Some(((rc.rudder as f64) - 1024.0) / 1024.0 * 100.0);
+            // RC stick inputs: raw u16 centered at 1024 (range 0..2048) → normalized to -100..+100.
+            // Goggles records flown with the Motion Controller carry no RC record at all, which
+            // leaves every channel at 0 — store those as missing rather than full-deflection sticks.
+            let has_rc_record = rc.aileron != 0 || rc.elevator != 0 || rc.throttle != 0 || rc.rudder != 0;
+            if has_rc_record {
+                point.rc_aileron = Some(((rc.aileron as f64) - 1024.0) / 1024.0 * 100.0);
+                point.rc_elevator = Some(((rc.elevator as f64) - 1024.0) / 1024.0 * 100.0);
+                point.rc_throttle = Some(((rc.throttle as f64) - 1024.0) / 1024.0 * 100.0);
+                point.rc_rudder = Some(((rc.rudder as f64) - 1024.0) / 1024.0 * 100.0);
+            }
 
             // Camera state: extract rising edge transitions to guarantee exactly one "true" per event
             let camera = &frame.camera;
@@ -1479,17 +1663,48 @@ impl<'a> LogParser<'a> {
         }
     }
 
-    /// Extract flight start time
+    /// Extract flight start time (None when the header carries an unset clock)
     fn extract_start_time(&self, parser: &DJILog) -> Option<DateTime<Utc>> {
-        Some(parser.details.start_time)
+        let start = parser.details.start_time;
+        if start.year() < 2010 {
+            None
+        } else {
+            Some(start)
+        }
     }
+}
 
-    /// Extract flight end time
-    fn extract_end_time(&self, parser: &DJILog) -> Option<DateTime<Utc>> {
-        let start = self.extract_start_time(parser)?;
-        let duration_ms = (parser.details.total_time * 1000.0) as i64;
-        Some(start + chrono::Duration::milliseconds(duration_ms))
-    }
+/// Whether a product is an FPV-class aircraft flown through DJI Goggles (Avata, DJI FPV, Neo)
+pub fn is_fpv_product(model: &str) -> bool {
+    let m = model.to_ascii_lowercase();
+    m.contains("avata") || m.contains("fpv") || m.contains("goggles") || m.contains("neo")
+}
+
+/// Parse the timestamp embedded in DJI record file names,
+/// e.g. `DJIFlightRecord_2023-06-12_[15-03-44].txt` or `FlightRecord_2023-06-12_15-03-44.txt`.
+/// The file name uses the device's local clock, so this is only a best-effort fallback.
+fn start_time_from_file_name(path: &Path) -> Option<DateTime<Utc>> {
+    let stem: String = path
+        .file_stem()?
+        .to_str()?
+        .chars()
+        .filter(|c| *c != '[' && *c != ']')
+        .collect();
+    const PATTERN_LEN: usize = 19; // "YYYY-MM-DD_HH-MM-SS"
+    (0..stem.len().saturating_sub(PATTERN_LEN - 1))
+        .filter(|&i| stem.is_char_boundary(i) && stem.is_char_boundary(i + PATTERN_LEN))
+        .find_map(|i| {
+            chrono::NaiveDateTime::parse_from_str(&stem[i..i + PATTERN_LEN], "%Y-%m-%d_%H-%M-%S").ok()
+        })
+        .map(|ndt| ndt.and_utc())
+}
+
+/// True if a flight started after 19:00 or before 06:00 local time. The local
+/// offset is estimated from the home longitude (1 hour per 15°).
+pub fn is_night_start(start_time: DateTime<Utc>, home_lon: Option<f64>) -> bool {
+    let tz_offset_hours = home_lon.map_or(0, |lon| (lon / 15.0).round() as i32);
+    let local_hour = ((start_time.hour() as i32 + tz_offset_hours) % 24 + 24) % 24;
+    !(6..19).contains(&local_hour)
 }
 
 /// Calculate FlightStats from stored TelemetryRecords (for tag regeneration without re-parsing files)
@@ -1568,6 +1783,62 @@ pub fn calculate_stats_from_records(records: &[crate::models::TelemetryRecord])
 }
 
 /// Haversine distance calculation in meters
+/// Takeoffs and landings in a log, so flights with several hops on one
+/// battery count correctly for currency. Uses the aircraft's airborne flag
+/// when the log has one, else height above takeoff with hysteresis. A state
+/// must hold for `CYCLE_DEBOUNCE_MS` to count, except a landing at the very
+/// end of the log. A log that starts in the air counts its takeoff; one that
+/// ends in the air has no final landing. None without flag or height data.
+pub fn count_takeoffs_landings(points: &[TelemetryPoint]) -> Option<(i32, i32)> {
+    const CYCLE_DEBOUNCE_MS: i64 = 3_000;
+    const TAKEOFF_HEIGHT_M: f64 = 1.5;
+    const LANDED_HEIGHT_M: f64 = 0.5;
+
+    let states: Vec<(i64, bool)> = if points.iter().any(|p| p.is_flying.is_some()) {
+        points.iter().filter_map(|p| p.is_flying.map(|f| (p.timestamp_ms, f))).collect()
+    } else {
+        let mut airborne = false;
+        points
+            .iter()
+            .filter_map(|p| {
+                let h = p.height?;
+                airborne = if airborne {
+                    !(h < LANDED_HEIGHT_M && p.speed.unwrap_or(0.0) < 0.5)
+                } else {
+                    h > TAKEOFF_HEIGHT_M
+                };
+                Some((p.timestamp_ms, airborne))
+            })
+            .collect()
+    };
+    let &(_, mut airborne) = states.first()?;
+
+    let (mut takeoffs, mut landings) = (airborne as i32, 0);
+    // Start of a run in the other state
+    let mut pending: Option<i64> = None;
+    for &(ts, state) in &states {
+        if state == airborne {
+            pending = None;
+            continue;
+        }
+        let since = *pending.get_or_insert(ts);
+        if ts - since >= CYCLE_DEBOUNCE_MS {
+            airborne = state;
+            pending = None;
+            if state {
+                takeoffs += 1;
+            } else {
+                landings += 1;
+            }
+        }
+    }
+    // Logs often stop right after touchdown
+    if airborne && pending.is_some() {
+        landings += 1;
+    }
+    Some((takeoffs, landings))
+}
+
 pub fn haversine_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
     const R: f64 = 6_371_000.0; // Earth's radius in meters
 
@@ -6,7 +6,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use tokio::process::Command;
 
-pub const BUILTIN_ALLOWED_EXTENSIONS: [&str; 4] = ["txt", "dat", "log", "csv"];
+pub const BUILTIN_ALLOWED_EXTENSIONS: [&str; 9] = ["txt", "dat", "log", "csv", "json", "gpx", "pud", "bbl", "bfl"];
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PluginConfig {
@@ -0,0 +1,464 @@
+//! Pre-flight briefing for a planned location and time.
+//!
+//! Combines what the logbook can tell before a flight:
+//!
+//! - sun — sunrise, sunset and civil twilight (computed locally), and whether
+//!   the planned time is in daylight
+//! - weather — hourly forecast for that hour from Open-Meteo, when reachable
+//! - airspace — airspaces containing the point from OpenAIP, when an OpenAIP
+//!   API key is configured, plus the classes the user set on their own zones
+//! - zones — the user's own zone rules (circles with a max height, an
+//!   airspace class and notes, e.g. "club field: 120 m, call tower first")
+//!   that contain or lie within 5 km of the point
+//!
+//! Zones and the OpenAIP key live under the `preflight` key of `config.json`.
+//! Network lookups are best effort: the briefing is still returned, with the
+//! failed part left out.
+
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::parser::haversine_distance;
+
+const CONFIG_KEY: &str = "preflight";
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(10);
+const OPENAIP_AIRSPACES_URL: &str = "https://api.core.openaip.net/api/airspaces";
+/// Zones whose edge is within this distance are listed as nearby
+const NEARBY_ZONE_M: f64 = 5_000.0;
+/// Sun altitude at sunrise/sunset (refraction and solar disc) and at civil twilight
+const SUNRISE_ALTITUDE_DEG: f64 = -0.833;
+const CIVIL_TWILIGHT_ALTITUDE_DEG: f64 = -6.0;
+
+/// A user-defined zone rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightZone {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub radius_m: f64,
+    /// Airspace class to report inside the zone (e.g. "G")
+    #[serde(default)]
+    pub airspace_class: Option<String>,
+    #[serde(default)]
+    pub max_height_m: Option<f64>,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+/// Pre-flight settings as shown to the frontend (the API key is never returned)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightSettings {
+    #[serde(default)]
+    pub zones: Vec<PreflightZone>,
+    /// Write-only: empty keeps the stored key
+    #[serde(default, skip_serializing)]
+    pub openaip_api_key: String,
+    /// Read-only: whether a key is stored
+    #[serde(default)]
+    pub has_openaip_api_key: bool,
+}
+
+impl PreflightSettings {
+    /// Read the settings from a parsed config.json
+    pub fn from_config(config: &serde_json::Value) -> Self {
+        let Some(section) = config.get(CONFIG_KEY) else {
+            return Self::default();
+        };
+        let openaip_api_key = section.get("openaip_api_key").and_then(|v| v.as_str()).unwrap_or("").to_string();
+        Self {
+            zones: section
+                .get("zones")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default(),
+            has_openaip_api_key: !openaip_api_key.is_empty(),
+            openaip_api_key,
+        }
+    }
+
+    /// Read the settings from a `config.json` (defaults when missing)
+    pub fn load(config_path: &Path) -> Self {
+        let config: serde_json::Value = std::fs::read_to_string(config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+        Self::from_config(&config)
+    }
+}
+
+/// Store new settings in config.json; an empty `openaip_api_key` keeps the
+/// existing one and `"-"` removes it
+pub fn save_settings(config_path: &Path, mut settings: PreflightSettings) -> Result<PreflightSettings, String> {
+    for zone in &mut settings.zones {
+        zone.name = zone.name.trim().to_string();
+        if zone.name.is_empty() {
+            return Err("Zone names cannot be empty".to_string());
+        }
+        if !(-90.0..=90.0).contains(&zone.lat) || !(-180.0..=180.0).contains(&zone.lon) {
+            return Err(format!("Invalid coordinates for zone '{}'", zone.name));
+        }
+        if !(zone.radius_m > 0.0) {
+            return Err(format!("Radius of zone '{}' must be positive", zone.name));
+        }
+        zone.airspace_class = zone.airspace_class.take().map(|c| c.trim().to_uppercase()).filter(|c| !c.is_empty());
+        zone.notes = zone.notes.take().map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    }
+
+    let mut config: serde_json::Value = std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    settings.openaip_api_key = match settings.openaip_api_key.trim() {
+        "" => PreflightSettings::from_config(&config).openaip_api_key,
+        "-" => String::new(),
+        key => key.to_string(),
+    };
+    settings.has_openaip_api_key = !settings.openaip_api_key.is_empty();
+    config[CONFIG_KEY] = serde_json::json!({
+        "zones": settings.zones,
+        "openaip_api_key": settings.openaip_api_key,
+    });
+    std::fs::write(config_path, serde_json::to_string_pretty(&config).unwrap())
+        .map_err(|e| format!("Failed to write config: {}", e))?;
+    Ok(settings)
+}
+
+/// Sun events of the planned day (UTC). Missing events mean the sun stays
+/// above or below that altitude all day (polar day or night).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SunTimes {
+    pub civil_dawn: Option<DateTime<Utc>>,
+    pub sunrise: Option<DateTime<Utc>>,
+    pub sunset: Option<DateTime<Utc>>,
+    pub civil_dusk: Option<DateTime<Utc>>,
+    /// The planned time is between sunrise and sunset
+    pub daylight: bool,
+}
+
+/// An airspace containing the planned point
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AirspaceInfo {
+    pub name: String,
+    /// ICAO class letter, or "unclassified"
+    pub class: String,
+    pub lower_limit: Option<String>,
+    pub upper_limit: Option<String>,
+    /// 'openaip' or 'zone'
+    pub source: String,
+}
+
+/// A user zone near the planned point
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneMatch {
+    #[serde(flatten)]
+    pub zone: PreflightZone,
+    pub inside: bool,
+    /// Distance from the point to the zone's edge (0 inside)
+    pub distance_m: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightReport {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub time: DateTime<Utc>,
+    pub sun: SunTimes,
+    /// Forecast for the planned hour (None when unavailable)
+    pub weather: Option<serde_json::Value>,
+    pub airspaces: Vec<AirspaceInfo>,
+    /// 'openaip', 'not_configured' or 'failed'
+    pub airspace_lookup: String,
+    pub zones: Vec<ZoneMatch>,
+}
+
+/// Julian date of a Unix time and back
+fn julian(t: DateTime<Utc>) -> f64 {
+    t.timestamp() as f64 / 86_400.0 + 2_440_587.5
+}
+
+fn from_julian(jd: f64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(((jd - 2_440_587.5) * 86_400.0).round() as i64, 0)
+}
+
+/// When the sun crosses an altitude on a given day
+enum Crossings {
+    /// Rising, then setting
+    Times(DateTime<Utc>, DateTime<Utc>),
+    /// Above the altitude all day (polar day)
+    AlwaysAbove,
+    /// Below it all day (polar night)
+    AlwaysBelow,
+}
+
+impl Crossings {
+    fn times(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        match *self {
+            Self::Times(rise, set) => Some((rise, set)),
+            _ => None,
+        }
+    }
+}
+
+/// Times the sun crosses `altitude_deg` on `date` at a location (sunrise
+/// equation; accurate to a minute or two)
+fn sun_crossings(lat: f64, lon: f64, date: NaiveDate, altitude_deg: f64) -> Crossings {
+    let noon = date.and_hms_opt(12, 0, 0).unwrap_or_default().and_utc();
+    let n = (julian(noon) - 2_451_545.0 + 0.0008).round();
+    let mean_solar_noon = n - lon / 360.0;
+    let anomaly = (357.5291 + 0.985_600_28 * mean_solar_noon).rem_euclid(360.0).to_radians();
+    let center = 1.9148 * anomaly.sin() + 0.02 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+    let ecliptic_lon = (anomaly.to_degrees() + center + 180.0 + 102.9372).rem_euclid(360.0).to_radians();
+    let transit = 2_451_545.0 + mean_solar_noon + 0.0053 * anomaly.sin() - 0.0069 * (2.0 * ecliptic_lon).sin();
+    let declination = (ecliptic_lon.sin() * 23.4397_f64.to_radians().sin()).asin();
+    let lat = lat.to_radians();
+    let cos_hour_angle =
+        (altitude_deg.to_radians().sin() - lat.sin() * declination.sin()) / (lat.cos() * declination.cos());
+    if cos_hour_angle < -1.0 {
+        return Crossings::AlwaysAbove;
+    }
+    if cos_hour_angle > 1.0 {
+        return Crossings::AlwaysBelow;
+    }
+    let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+    match (from_julian(transit - half_day), from_julian(transit + half_day)) {
+        (Some(rise), Some(set)) => Crossings::Times(rise, set),
+        _ => Crossings::AlwaysBelow,
+    }
+}
+
+/// Sunrise, sunset and civil twilight on the UTC day of `time`
+pub fn sun_times(lat: f64, lon: f64, time: DateTime<Utc>) -> SunTimes {
+    let date = time.date_naive();
+    let sun = sun_crossings(lat, lon, date, SUNRISE_ALTITUDE_DEG);
+    let civil = sun_crossings(lat, lon, date, CIVIL_TWILIGHT_ALTITUDE_DEG).times();
+    let daylight = match sun {
+        Crossings::Times(rise, set) => time >= rise && time <= set,
+        Crossings::AlwaysAbove => true,
+        Crossings::AlwaysBelow => false,
+    };
+    let sun = sun.times();
+    SunTimes {
+        civil_dawn: civil.map(|c| c.0),
+        sunrise: sun.map(|s| s.0),
+        sunset: sun.map(|s| s.1),
+        civil_dusk: civil.map(|c| c.1),
+        daylight,
+    }
+}
+
+/// User zones containing or near the point, nearest first
+pub fn matching_zones(zones: &[PreflightZone], lat: f64, lon: f64) -> Vec<ZoneMatch> {
+    let mut matches: Vec<ZoneMatch> = zones
+        .iter()
+        .filter_map(|zone| {
+            let edge = haversine_distance(lat, lon, zone.lat, zone.lon) - zone.radius_m;
+            (edge <= NEARBY_ZONE_M).then(|| ZoneMatch {
+                zone: zone.clone(),
+                inside: edge <= 0.0,
+                distance_m: edge.max(0.0),
+            })
+        })
+        .collect();
+    matches.sort_by(|a, b| a.distance_m.total_cmp(&b.distance_m));
+    matches
+}
+
+/// Hourly forecast for the planned hour, or `None` if the service is
+/// unreachable or the time is outside its range
+pub(crate) async fn fetch_forecast(lat: f64, lon: f64, time: DateTime<Utc>) -> Option<serde_json::Value> {
+    let hour = time.format("%Y-%m-%dT%H:00").to_string();
+    let url = format!(
+        "https://api.open-meteo.com/v1/forecast?latitude={:.4}&longitude={:.4}&hourly=temperature_2m,wind_speed_10m,wind_gusts_10m,wind_direction_10m,precipitation_probability,precipitation,cloud_cover,visibility,weather_code&start_hour={}&end_hour={}&timezone=GMT",
+        lat, lon, hour, hour
+    );
+    let client = reqwest::Client::builder().timeout(LOOKUP_TIMEOUT).build().ok()?;
+    let body: serde_json::Value = match client.get(&url).send().await {
+        Ok(resp) if resp.status().is_success() => resp.json().await.ok()?,
+        Ok(resp) => {
+            log::warn!("Pre-flight forecast lookup failed: HTTP {}", resp.status());
+            return None;
+        }
+        Err(e) => {
+            log::warn!("Pre-flight forecast lookup failed: {}", e);
+            return None;
+        }
+    };
+
+    let hourly = body.get("hourly")?;
+    let value = |key: &str| hourly.get(key).and_then(|a| a.get(0)).cloned().unwrap_or(serde_json::Value::Null);
+    Some(serde_json::json!({
+        "source": "open-meteo forecast",
+        "time": format!("{}Z", hour),
+        "temperatureC": value("temperature_2m"),
+        "windSpeedKmh": value("wind_speed_10m"),
+        "windGustsKmh": value("wind_gusts_10m"),
+        "windDirectionDeg": value("wind_direction_10m"),
+        "precipitationProbabilityPercent": value("precipitation_probability"),
+        "precipitationMm": value("precipitation"),
+        "cloudCoverPercent": value("cloud_cover"),
+        "visibilityM": value("visibility"),
+        "weatherCode": value("weather_code"),
+    }))
+}
+
+/// OpenAIP limit object (`{ value, unit, referenceDatum }`) as text
+fn openaip_limit(limit: Option<&serde_json::Value>) -> Option<String> {
+    let limit = limit?;
+    let value = limit.get("value")?.as_f64()?;
+    let unit = match limit.get("unit").and_then(|u| u.as_i64()) {
+        Some(0) => "m",
+        Some(1) => "ft",
+        Some(6) => return Some(format!("FL{}", value)),
+        _ => "",
+    };
+    let datum = match limit.get("referenceDatum").and_then(|d| d.as_i64()) {
+        Some(0) => " GND",
+        Some(1) => " MSL",
+        Some(2) => " STD",
+        _ => "",
+    };
+    Some(if value == 0.0 && datum == " GND" { "GND".to_string() } else { format!("{} {}{}", value, unit, datum) })
+}
+
+/// Airspaces from OpenAIP whose polygon contains the point
+async fn fetch_airspaces(api_key: &str, lat: f64, lon: f64) -> Result<Vec<AirspaceInfo>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(LOOKUP_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let resp = client
+        .get(OPENAIP_AIRSPACES_URL)
+        .header("x-openaip-api-key", api_key)
+        .query(&[("pos", format!("{},{}", lat, lon)), ("dist", "1000".to_string()), ("limit", "100".to_string())])
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!("HTTP {}", resp.status()));
+    }
+    let body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+    const CLASSES: [&str; 7] = ["A", "B", "C", "D", "E", "F", "G"];
+    let airspaces = body
+        .get("items")
+        .and_then(|i| i.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter(|item| {
+                    let ring = item.pointer("/geometry/coordinates/0").and_then(|r| r.as_array());
+                    let polygon: Vec<[f64; 2]> = ring
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|p| Some([p.get(0)?.as_f64()?, p.get(1)?.as_f64()?]))
+                        .collect();
+                    polygon.len() >= 3 && crate::overflight::contains(&polygon, lon, lat)
+                })
+                .map(|item| AirspaceInfo {
+                    name: item.get("name").and_then(|n| n.as_str()).unwrap_or("").to_string(),
+                    class: item
+                        .get("icaoClass")
+                        .and_then(|c| c.as_u64())
+                        .and_then(|c| CLASSES.get(c as usize))
+                        .map_or("unclassified", |c| *c)
+                        .to_string(),
+                    lower_limit: openaip_limit(item.get("lowerLimit")),
+                    upper_limit: openaip_limit(item.get("upperLimit")),
+                    source: "openaip".to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(airspaces)
+}
+
+/// Build the briefing for a point and time
+pub async fn briefing(config_path: &Path, lat: f64, lon: f64, time: DateTime<Utc>) -> Result<PreflightReport, String> {
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err("Invalid coordinates".to_string());
+    }
+    let settings = PreflightSettings::load(config_path);
+    let zones = matching_zones(&settings.zones, lat, lon);
+
+    let weather = fetch_forecast(lat, lon, time);
+    let (weather, (mut airspaces, airspace_lookup)) = if settings.openaip_api_key.is_empty() {
+        (weather.await, (Vec::new(), "not_configured"))
+    } else {
+        let (weather, airspaces) = tokio::join!(weather, fetch_airspaces(&settings.openaip_api_key, lat, lon));
+        let airspaces = match airspaces {
+            Ok(a) => (a, "openaip"),
+            Err(e) => {
+                log::warn!("Pre-flight airspace lookup failed: {}", e);
+                (Vec::new(), "failed")
+            }
+        };
+        (weather, airspaces)
+    };
+    airspaces.extend(zones.iter().filter(|m| m.inside).filter_map(|m| {
+        Some(AirspaceInfo {
+            name: m.zone.name.clone(),
+            class: m.zone.airspace_class.clone()?,
+            lower_limit: Some("GND".to_string()),
+            upper_limit: m.zone.max_height_m.map(|h| format!("{} m GND", h)),
+            source: "zone".to_string(),
+        })
+    }));
+
+    Ok(PreflightReport {
+        latitude: lat,
+        longitude: lon,
+        time,
+        sun: sun_times(lat, lon, time),
+        weather,
+        airspaces,
+        airspace_lookup: airspace_lookup.to_string(),
+        zones,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sun_times_and_zones() {
+        // London, summer solstice: sunrise 03:43 UTC, sunset 20:21 UTC
+        let noon = "2026-06-21T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let sun = sun_times(51.5074, -0.1278, noon);
+        let minutes = |t: Option<DateTime<Utc>>| {
+            let t = t.unwrap();
+            (t - noon.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc()).num_minutes()
+        };
+        assert!((minutes(sun.sunrise) - (3 * 60 + 43)).abs() <= 3);
+        assert!((minutes(sun.sunset) - (20 * 60 + 21)).abs() <= 3);
+        assert!(minutes(sun.civil_dawn) < minutes(sun.sunrise));
+        assert!(sun.daylight);
+
+        // Tromsø in December: no sunrise
+        let polar = sun_times(69.65, 18.96, "2026-12-21T12:00:00Z".parse().unwrap());
+        assert!(polar.sunrise.is_none() && !polar.daylight);
+
+        let zone = |name: &str, lat: f64| PreflightZone {
+            name: name.to_string(),
+            lat,
+            lon: 0.0,
+            radius_m: 1_000.0,
+            airspace_class: None,
+            max_height_m: Some(60.0),
+            notes: None,
+        };
+        // ~111 m per 0.001° of latitude
+        let zones = [zone("far", 1.0), zone("near", 0.03), zone("here", 0.0)];
+        let matches = matching_zones(&zones, 0.0, 0.0);
+        assert_eq!(matches.len(), 2);
+        assert!(matches[0].inside && matches[0].zone.name == "here");
+        assert!(!matches[1].inside && (matches[1].distance_m - 2_336.0).abs() < 20.0);
+    }
+}
@@ -0,0 +1,106 @@
+//! Per-flight preview cache.
+//!
+//! At import a small [`FlightPreview`] is derived from the telemetry — a
+//! simplified track polyline, altitude and battery sparklines and an SVG map
+//! thumbnail — and stored in `flight_previews`, so the flight list can show
+//! rich rows from one bulk query instead of loading telemetry per flight.
+//! Previews are a cache: they are rebuilt by reprocessing a flight and are not
+//! part of backups.
+
+use crate::models::{FlightPreview, TelemetryData, TelemetryRecord};
+use crate::render::{self, RenderOptions};
+
+/// Vertices kept in the cached track polyline
+const PREVIEW_TRACK_POINTS: usize = 120;
+/// Values per sparkline
+const SPARKLINE_POINTS: usize = 48;
+/// Thumbnail size in pixels
+const THUMBNAIL_WIDTH: u32 = 160;
+const THUMBNAIL_HEIGHT: u32 = 100;
+
+/// Build the preview of a flight from its full-resolution telemetry
+pub fn build_preview(flight_id: i64, records: &[TelemetryRecord]) -> FlightPreview {
+    let track = TelemetryData::from_records(records).extract_track(PREVIEW_TRACK_POINTS * 4);
+    let track = simplify(&track, PREVIEW_TRACK_POINTS);
+
+    let altitude: Vec<Option<f64>> = records
+        .iter()
+        .map(|r| r.height.or(r.vps_height).or(r.altitude))
+        .collect();
+    let battery: Vec<Option<f64>> = records.iter().map(|r| r.battery_percent.map(f64::from)).collect();
+
+    FlightPreview {
+        flight_id,
+        track: track.iter().map(|p| [p[0], p[1]]).collect(),
+        altitude_sparkline: sparkline(&altitude, SPARKLINE_POINTS),
+        battery_sparkline: sparkline(&battery, SPARKLINE_POINTS),
+        thumbnail_svg: if track.len() >= 2 {
+            Some(render::render_svg(&track, &RenderOptions::sized(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT)))
+        } else {
+            None
+        },
+    }
+}
+
+/// Reduce a track to at most `max_points` vertices, always keeping the first
+/// and last point
+fn simplify(track: &[[f64; 3]], max_points: usize) -> Vec<[f64; 3]> {
+    if track.len() <= max_points || max_points < 2 {
+        return track.to_vec();
+    }
+    let last = track.len() - 1;
+    (0..max_points)
+        .map(|i| track[i * last / (max_points - 1)])
+        .collect()
+}
+
+/// Average a series into `buckets` equal-width buckets, skipping missing
+/// samples. Empty buckets take the nearest earlier value (or the first value
+/// for leading gaps); a series without any value yields an empty sparkline.
+fn sparkline(values: &[Option<f64>], buckets: usize) -> Vec<f64> {
+    let n = buckets.min(values.len());
+    let averaged: Vec<Option<f64>> = (0..n)
+        .map(|b| {
+            let bucket = &values[b * values.len() / n..(b + 1) * values.len() / n];
+            let (sum, count) = bucket.iter().flatten().fold((0.0, 0usize), |(s, c), v| (s + v, c + 1));
+            (count > 0).then(|| sum / count as f64)
+        })
+        .collect();
+
+    let Some(first) = averaged.iter().flatten().next().copied() else {
+        return Vec::new();
+    };
+    let mut prev = first;
+    averaged
+        .into_iter()
+        .map(|v| {
+            prev = v.unwrap_or(prev);
+            prev
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simplify_keeps_endpoints() {
+        let track: Vec<[f64; 3]> = (0..1000).map(|i| [i as f64, 0.0, 0.0]).collect();
+        let out = simplify(&track, 10);
+        assert_eq!(out.len(), 10);
+        assert!(out[0][0].abs() < 1e-9);
+        assert!((out[9][0] - 999.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sparkline_buckets() {
+        let values: Vec<Option<f64>> = vec![None, None, Some(10.0), Some(20.0), Some(30.0), None];
+        let out = sparkline(&values, 3);
+        assert_eq!(out.len(), 3);
+        assert!((out[0] - 15.0).abs() < 1e-9);
+        assert!((out[1] - 15.0).abs() < 1e-9);
+        assert!((out[2] - 30.0).abs() < 1e-9);
+        assert!(sparkline(&[None, None], 4).is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+//! Home location anonymization for exports.
+//!
+//! Flights shared publicly usually start and end at the pilot's home. When
+//! enabled (the `privacy` key of `config.json`), positions within `radius_m` of
+//! a saved home zone are either removed (`truncate`: the track starts and ends
+//! at the zone's edge) or shifted by a fixed vector of `radius_m` in a random
+//! direction picked once per zone (`offset`: the track stays continuous but the
+//! real home is not where it appears). The flight's home point is treated the
+//! same way. Applied to flight bundles and to flight data requested with
+//! `anonymize` (the client-side GPX/KML/CSV/JSON exports); the in-app views
+//! always show true positions.
+
+use std::path::Path;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Flight, TelemetryData, TelemetryRecord};
+use crate::parser::haversine_distance;
+
+const CONFIG_KEY: &str = "privacy";
+const DEFAULT_RADIUS_M: f64 = 500.0;
+/// Meters per degree of latitude
+const METERS_PER_DEGREE: f64 = 111_320.0;
+
+/// What happens to positions inside a home zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivacyMode {
+    /// Drop them
+    #[default]
+    Truncate,
+    /// Shift them by the zone's fixed offset
+    Offset,
+}
+
+/// A location to hide (home, family, ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HomeZone {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    /// Direction of the `offset` shift, chosen on save and kept so repeated
+    /// exports can't be averaged back to the real position
+    #[serde(default)]
+    pub offset_bearing_deg: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacySettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub mode: PrivacyMode,
+    #[serde(default = "default_radius")]
+    pub radius_m: f64,
+    #[serde(default)]
+    pub zones: Vec<HomeZone>,
+}
+
+fn default_radius() -> f64 {
+    DEFAULT_RADIUS_M
+}
+
+impl Default for PrivacySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: PrivacyMode::Truncate,
+            radius_m: DEFAULT_RADIUS_M,
+            zones: Vec::new(),
+        }
+    }
+}
+
+impl PrivacySettings {
+    pub fn from_config(config: &serde_json::Value) -> Self {
+        config
+            .get(CONFIG_KEY)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Read the settings from a `config.json` (defaults when missing)
+    pub fn load(config_path: &Path) -> Self {
+        let config: serde_json::Value = std::fs::read_to_string(config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+        Self::from_config(&config)
+    }
+
+    /// True when enabled and there is something to hide
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.zones.is_empty() && self.radius_m > 0.0
+    }
+
+    /// Position to export in place of `lat`/`lon` (None = hidden)
+    pub fn anonymize(&self, lat: f64, lon: f64) -> Option<(f64, f64)> {
+        if !self.is_active() {
+            return Some((lat, lon));
+        }
+        let Some(zone) = self
+            .zones
+            .iter()
+            .find(|z| haversine_distance(z.lat, z.lon, lat, lon) <= self.radius_m)
+        else {
+            return Some((lat, lon));
+        };
+        match self.mode {
+            PrivacyMode::Truncate => None,
+            PrivacyMode::Offset => {
+                let bearing = zone.offset_bearing_deg.unwrap_or(0.0).to_radians();
+                let dlat = self.radius_m * bearing.cos() / METERS_PER_DEGREE;
+                let dlon = self.radius_m * bearing.sin() / (METERS_PER_DEGREE * lat.to_radians().cos().max(0.01));
+                Some((lat + dlat, lon + dlon))
+            }
+        }
+    }
+
+    fn anonymize_opt(&self, lat: Option<f64>, lon: Option<f64>) -> (Option<f64>, Option<f64>) {
+        match (lat, lon) {
+            (Some(lat), Some(lon)) => match self.anonymize(lat, lon) {
+                Some((lat, lon)) => (Some(lat), Some(lon)),
+                None => (None, None),
+            },
+            other => other,
+        }
+    }
+
+    /// Anonymize the home point of a flight
+    pub fn apply_to_flight(&self, flight: &mut Flight) {
+        (flight.home_lat, flight.home_lon) = self.anonymize_opt(flight.home_lat, flight.home_lon);
+    }
+
+    /// Anonymize telemetry records (hidden points keep their other values)
+    pub fn apply_to_records(&self, records: &mut [TelemetryRecord]) {
+        for r in records {
+            (r.latitude, r.longitude) = self.anonymize_opt(r.latitude, r.longitude);
+        }
+    }
+
+    /// Anonymize a `[lon, lat, height]` track (hidden points are removed)
+    pub fn apply_to_track(&self, track: &mut Vec<[f64; 3]>) {
+        track.retain_mut(|point| match self.anonymize(point[1], point[0]) {
+            Some((lat, lon)) => {
+                point[0] = lon;
+                point[1] = lat;
+                true
+            }
+            None => false,
+        });
+    }
+
+    /// Anonymize a flight data response: telemetry positions and the map track
+    pub fn apply_to_flight_data(&self, telemetry: &mut TelemetryData, track: &mut Vec<[f64; 3]>) {
+        for (lat, lon) in telemetry.latitude.iter_mut().zip(telemetry.longitude.iter_mut()) {
+            (*lat, *lon) = self.anonymize_opt(*lat, *lon);
+        }
+        self.apply_to_track(track);
+    }
+}
+
+/// Store new settings in config.json, picking an offset direction for new zones
+pub fn save_settings(config_path: &Path, mut settings: PrivacySettings) -> Result<PrivacySettings, String> {
+    if !settings.radius_m.is_finite() || settings.radius_m < 0.0 {
+        return Err("Radius must be a positive number of meters".to_string());
+    }
+    let mut rng = rand::thread_rng();
+    for zone in &mut settings.zones {
+        if !(-90.0..=90.0).contains(&zone.lat) || !(-180.0..=180.0).contains(&zone.lon) {
+            return Err(format!("Invalid coordinates for zone '{}'", zone.name));
+        }
+        if zone.offset_bearing_deg.is_none() {
+            zone.offset_bearing_deg = Some(rng.gen_range(0.0..360.0));
+        }
+    }
+
+    let mut config: serde_json::Value = std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    config[CONFIG_KEY] = serde_json::json!(settings);
+    std::fs::write(config_path, serde_json::to_string_pretty(&config).unwrap())
+        .map_err(|e| format!("Failed to write config: {}", e))?;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(mode: PrivacyMode) -> PrivacySettings {
+        PrivacySettings {
+            enabled: true,
+            mode,
+            radius_m: 500.0,
+            zones: vec![HomeZone {
+                name: "Home".to_string(),
+                lat: 48.0,
+                lon: 11.0,
+                offset_bearing_deg: Some(0.0),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_anonymize_inside_and_outside_zone() {
+        let truncate = settings(PrivacyMode::Truncate);
+        assert_eq!(truncate.anonymize(48.001, 11.001), None);
+        assert_eq!(truncate.anonymize(48.1, 11.0), Some((48.1, 11.0)));
+
+        let (lat, lon) = settings(PrivacyMode::Offset).anonymize(48.0, 11.0).unwrap();
+        assert!((haversine_distance(48.0, 11.0, lat, lon) - 500.0).abs() < 5.0);
+        assert!(lat > 48.0);
+    }
+}
@@ -0,0 +1,129 @@
+//! Model-specific normalization rules for DJI flight records.
+//!
+//! Some aircraft/firmware combinations write OSD height or speed with a
+//! different scale than dji-log-parser assumes (e.g. Mini 2 firmware logging
+//! height in decimeters, early FlightRecord versions logging speed in dm/s).
+//! Each entry in [`QUIRKS`] names the affected products and log versions, and
+//! carries a plausibility guard so that correctly encoded logs from the same
+//! model are never rescaled.
+
+/// A single normalization rule
+#[derive(Debug)]
+pub struct ModelQuirk {
+    /// Stable identifier (logged and stored with the import)
+    pub id: &'static str,
+    /// Lowercase substrings matched against the product type name; empty matches any model
+    pub models: &'static [&'static str],
+    /// Inclusive log version range the rule applies to
+    pub min_version: u8,
+    pub max_version: u8,
+    /// Multiplier applied to altitude, height and VPS height
+    pub height_scale: f32,
+    /// Multiplier applied to the velocity components
+    pub speed_scale: f32,
+    /// Only apply when the raw max height exceeds this value (the model's physical ceiling)
+    pub min_raw_max_height: Option<f32>,
+    /// Only apply when the raw max horizontal speed exceeds this value
+    pub min_raw_max_speed: Option<f32>,
+}
+
+/// Known encoding quirks, checked in order — the first match wins.
+pub const QUIRKS: &[ModelQuirk] = &[
+    // Mini 2 / Mini 2 SE firmware before the v12 record format logs OSD height in
+    // decimeters. These aircraft are limited to 500 m, so anything above is scaled.
+    ModelQuirk {
+        id: "mini2_height_dm",
+        models: &["mini2"],
+        min_version: 0,
+        max_version: 11,
+        height_scale: 0.1,
+        speed_scale: 1.0,
+        min_raw_max_height: Some(500.0),
+        min_raw_max_speed: None,
+    },
+    // Original Mavic Mini / Mini SE share the Mini 2 OSD encoding issue.
+    ModelQuirk {
+        id: "mavic_mini_height_dm",
+        models: &["mavicmini", "minise"],
+        min_version: 0,
+        max_version: 11,
+        height_scale: 0.1,
+        speed_scale: 1.0,
+        min_raw_max_height: Some(500.0),
+        min_raw_max_speed: None,
+    },
+    // FlightRecord versions up to 5 (DJI GO era) store velocity in dm/s.
+    // No aircraft of that generation exceeds ~22 m/s.
+    ModelQuirk {
+        id: "legacy_speed_dms",
+        models: &[],
+        min_version: 0,
+        max_version: 5,
+        height_scale: 1.0,
+        speed_scale: 0.1,
+        min_raw_max_height: None,
+        min_raw_max_speed: Some(40.0),
+    },
+];
+
+impl ModelQuirk {
+    fn matches_model(&self, model: &str) -> bool {
+        if self.models.is_empty() {
+            return true;
+        }
+        let normalized: String = model
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        self.models.iter().any(|m| normalized.contains(m))
+    }
+}
+
+/// Find the quirk that applies to a log, given the product name, log version and
+/// the raw (uncorrected) maximum height and horizontal speed seen in the log.
+pub fn find_quirk(
+    model: &str,
+    version: u8,
+    raw_max_height: f32,
+    raw_max_speed: f32,
+) -> Option<&'static ModelQuirk> {
+    QUIRKS.iter().find(|q| {
+        q.matches_model(model)
+            && (q.min_version..=q.max_version).contains(&version)
+            && q.min_raw_max_height.map_or(true, |limit| raw_max_height > limit)
+            && q.min_raw_max_speed.map_or(true, |limit| raw_max_speed > limit)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mini2_height_quirk_requires_implausible_height() {
+        // Decimeter-encoded log: 1200 dm = 120 m
+        let q = find_quirk("Mini2", 10, 1200.0, 12.0).expect("quirk should apply");
+        assert_eq!(q.id, "mini2_height_dm");
+        assert!((q.height_scale - 0.1).abs() < f32::EPSILON);
+
+        // Correctly encoded log from the same model is left alone
+        assert!(find_quirk("Mini2", 10, 120.0, 12.0).is_none());
+        // Newer record format is not affected
+        assert!(find_quirk("Mini2SE", 13, 1200.0, 12.0).is_none());
+    }
+
+    #[test]
+    fn test_legacy_speed_quirk_applies_to_any_model() {
+        let q = find_quirk("Phantom3Standard", 4, 80.0, 150.0).expect("quirk should apply");
+        assert_eq!(q.id, "legacy_speed_dms");
+        assert!(find_quirk("Phantom3Standard", 4, 80.0, 15.0).is_none());
+        assert!(find_quirk("Mavic3", 14, 80.0, 150.0).is_none());
+    }
+
+    #[test]
+    fn test_model_matching_ignores_case_and_punctuation() {
+        assert!(find_quirk("DJI Mini 2", 8, 900.0, 5.0).is_some());
+        assert!(find_quirk("Mini 3 Pro", 8, 900.0, 5.0).is_none());
+    }
+}
@@ -0,0 +1,180 @@
+//! Recompute flight stats from stored telemetry.
+//!
+//! Total distance, max speed, max altitude and duration are computed once at
+//! import. When the algorithms improve (GPS glitch filtering, speed derived
+//! from positions), flights imported before keep their old values until
+//! recomputed here: the stored telemetry is run through the same cleanup as
+//! an import (`gps_filter`, `speed_fallback`) and `LogParser::calculate_stats`,
+//! and the flights table is updated. The stored telemetry itself is left
+//! unchanged. Manual entries are skipped.
+//!
+//! Each recomputed flight reports its values before and after, so the effect
+//! of a change can be audited.
+
+use serde::Serialize;
+
+use crate::database::Database;
+use crate::models::{TelemetryPoint, TelemetryRecord};
+use crate::parser::LogParser;
+
+/// Changes smaller than this are not counted as changed
+const EPSILON: f64 = 1e-6;
+
+/// The summary columns recomputed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightStatsValues {
+    pub total_distance: Option<f64>,
+    pub max_altitude: Option<f64>,
+    pub max_speed: Option<f64>,
+    pub duration_secs: Option<f64>,
+}
+
+impl FlightStatsValues {
+    fn differs(&self, other: &Self) -> bool {
+        let pairs = [
+            (self.total_distance, other.total_distance),
+            (self.max_altitude, other.max_altitude),
+            (self.max_speed, other.max_speed),
+            (self.duration_secs, other.duration_secs),
+        ];
+        pairs.iter().any(|(a, b)| match (a, b) {
+            (Some(a), Some(b)) => (a - b).abs() > EPSILON,
+            (a, b) => a.is_some() != b.is_some(),
+        })
+    }
+}
+
+/// Outcome for one flight
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsChange {
+    pub flight_id: i64,
+    pub before: FlightStatsValues,
+    pub after: FlightStatsValues,
+    pub changed: bool,
+    /// GPS fixes left out as glitches
+    pub gps_outliers: usize,
+    /// Logged speed replaced by speed from positions
+    pub speed_derived: bool,
+}
+
+/// Outcome of a recompute over several flights
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecomputeSummary {
+    pub processed: usize,
+    pub changed: usize,
+    /// Manual entries and flights without telemetry
+    pub skipped: usize,
+    pub errors: Vec<String>,
+    /// Flights whose values changed
+    pub changes: Vec<StatsChange>,
+}
+
+/// The fields of a stored record the stats and cleanup look at
+fn to_point(r: &TelemetryRecord) -> TelemetryPoint {
+    TelemetryPoint {
+        timestamp_ms: r.timestamp_ms,
+        latitude: r.latitude,
+        longitude: r.longitude,
+        altitude: r.altitude,
+        height: r.height,
+        vps_height: r.vps_height,
+        speed: r.speed,
+        velocity_x: r.velocity_x,
+        velocity_y: r.velocity_y,
+        velocity_z: r.velocity_z,
+        battery_percent: r.battery_percent,
+        battery_temp: r.battery_temp,
+        ..Default::default()
+    }
+}
+
+/// Recompute one flight. Ok(None) for manual entries and flights without telemetry.
+pub fn recompute_flight(db: &Database, flight_id: i64) -> Result<Option<StatsChange>, String> {
+    let flight = db
+        .get_flight_by_id(flight_id)
+        .map_err(|e| format!("Failed to get flight {}: {}", flight_id, e))?;
+    if flight.is_manual {
+        return Ok(None);
+    }
+    let records = db
+        .get_flight_telemetry(flight_id, None, None)
+        .map_err(|e| format!("Failed to get telemetry for flight {}: {}", flight_id, e))?;
+    if records.is_empty() {
+        return Ok(None);
+    }
+
+    let mut points: Vec<TelemetryPoint> = records.iter().map(to_point).collect();
+    let gps = crate::gps_filter::filter(&mut points);
+    let speed_derived = crate::speed_fallback::apply(&mut points);
+    let mut stats = LogParser::new(db).calculate_stats(&points);
+    let first_ms = points.first().map_or(0, |p| p.timestamp_ms);
+    stats.duration_secs = points.last().map_or(0, |p| p.timestamp_ms - first_ms) as f64 / 1000.0;
+
+    let before = db
+        .replace_flight_stats(flight_id, &stats)
+        .map_err(|e| format!("Failed to update flight {}: {}", flight_id, e))?;
+    let after = FlightStatsValues {
+        total_distance: Some(stats.total_distance_m),
+        max_altitude: Some(stats.max_altitude_m),
+        max_speed: Some(stats.max_speed_ms),
+        duration_secs: Some(stats.duration_secs),
+    };
+    Ok(Some(StatsChange {
+        flight_id,
+        changed: before.differs(&after),
+        before,
+        after,
+        gps_outliers: gps.total(),
+        speed_derived,
+    }))
+}
+
+/// Recompute the given flights, or all of them. A failing flight is
+/// reported and doesn't stop the rest.
+pub fn recompute_flights(db: &Database, flight_ids: Option<Vec<i64>>) -> Result<RecomputeSummary, String> {
+    let ids = match flight_ids {
+        Some(ids) => ids,
+        None => db.get_all_flight_ids().map_err(|e| format!("Failed to get flight IDs: {}", e))?,
+    };
+    let mut summary = RecomputeSummary::default();
+    for id in ids {
+        match recompute_flight(db, id) {
+            Ok(Some(change)) => {
+                summary.processed += 1;
+                if change.changed {
+                    summary.changed += 1;
+                    summary.changes.push(change);
+                }
+            }
+            Ok(None) => summary.skipped += 1,
+            Err(e) => {
+                log::warn!("{}", e);
+                summary.errors.push(e);
+            }
+        }
+    }
+    log::info!(
+        "Recomputed stats for {} flights: {} changed, {} skipped, {} errors",
+        summary.processed,
+        summary.changed,
+        summary.skipped,
+        summary.errors.len()
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values_differ() {
+        let a = FlightStatsValues { total_distance: Some(1200.0), max_altitude: Some(80.0), max_speed: Some(12.0), duration_secs: Some(600.0) };
+        assert!(!a.differs(&a));
+        assert!(a.differs(&FlightStatsValues { total_distance: Some(950.0), ..a }));
+        assert!(a.differs(&FlightStatsValues { max_speed: None, ..a }));
+    }
+}
@@ -0,0 +1,510 @@
+//! Non-destructive recovery of a database that fails to open.
+//!
+//! When DuckDB can't open a profile database (typically a WAL left corrupt by
+//! a crash), `Database::open_with_recovery` first copies the database file and
+//! its WAL to `recovery/{file name}/{id}/` in the data directory, then opens
+//! the database without the WAL, and only if that fails as well starts over
+//! with an empty database. Nothing is removed before the copy succeeded; if
+//! the copy fails, opening fails.
+//!
+//! Each recovery writes a `report.json` next to the copies. Reports that
+//! haven't been dismissed are returned by `get_recovery_status` so the app can
+//! tell the user what happened and offer to restore. A restore is scheduled
+//! in `restore.json` and carried out the next time the database is opened
+//! (after a restart), before DuckDB touches the file. The database it
+//! replaces is preserved the same way, so a restore can be undone.
+//!
+//! A database that opens fine but empty is the other way data goes missing
+//! (e.g. a data directory restored without its WAL, or a recreated database
+//! whose report was dismissed). `candidates` lists everything a database can
+//! be restored from: recovery copies, pre-migration snapshots, the
+//! `{file name}.bak.{timestamp}` copies older versions wrote on WAL recovery
+//! and the scheduled backup archives, so the app can offer a restore at
+//! startup. Snapshot and `.bak` files are first copied into a recovery folder
+//! (outcome `staged`) and restored from there like a recovery copy.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+const RECOVERY_DIR: &str = "recovery";
+const REPORT_FILE: &str = "report.json";
+const RESTORE_FILE: &str = "restore.json";
+const SNAPSHOT_DIR: &str = "snapshots";
+/// Extension of the scheduled backup archives (`export_backup`)
+const ARCHIVE_SUFFIX: &str = ".db.backup";
+
+#[derive(Debug, thiserror::Error)]
+pub enum RecoveryError {
+    #[error("Recovery copy '{0}' not found")]
+    NotFound(String),
+
+    #[error("Invalid recovery id '{0}'")]
+    InvalidId(String),
+
+    #[error("Restore candidate '{0}' not found")]
+    CandidateNotFound(String),
+
+    #[error("Restore candidate '{0}' is a backup archive")]
+    Archive(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// What happened to the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryOutcome {
+    /// Opened without its WAL; changes since the last checkpoint are only in the copy
+    WalSetAside,
+    /// Could not be opened at all and was replaced by an empty database
+    Recreated,
+    /// Replaced by restoring another copy
+    Replaced,
+    /// Copied from a snapshot or `.bak` file to be restored
+    Staged,
+}
+
+/// A recovery and the copies it kept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryReport {
+    pub id: String,
+    /// Database file name
+    pub database: String,
+    pub occurred_at: DateTime<Utc>,
+    pub outcome: RecoveryOutcome,
+    /// DuckDB's errors when opening failed
+    pub errors: Vec<String>,
+    pub backup_dir: String,
+    /// Copied files (the database and, if there was one, its WAL)
+    pub files: Vec<String>,
+    #[serde(default)]
+    pub dismissed: bool,
+    /// File the copy was taken from when it isn't the database itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// Reports to show and the restore waiting for the next start
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryStatus {
+    /// Not dismissed, newest first
+    pub reports: Vec<RecoveryReport>,
+    /// Id of the copy restored at the next start
+    pub pending_restore: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingRestore {
+    id: String,
+}
+
+fn file_name(db_path: &Path) -> String {
+    db_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+fn wal_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension("db.wal")
+}
+
+/// `recovery/{file name}/` in the directory of the database
+fn recovery_root(db_path: &Path) -> PathBuf {
+    db_path.parent().unwrap_or(Path::new(".")).join(RECOVERY_DIR).join(file_name(db_path))
+}
+
+fn copy_dir(db_path: &Path, id: &str) -> Result<PathBuf, RecoveryError> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err(RecoveryError::InvalidId(id.to_string()));
+    }
+    Ok(recovery_root(db_path).join(id))
+}
+
+fn save(dir: &Path, report: &RecoveryReport) -> Result<(), RecoveryError> {
+    fs::write(dir.join(REPORT_FILE), serde_json::to_string_pretty(report).unwrap_or_default())?;
+    Ok(())
+}
+
+/// Copy `files` (source path, name in the folder) to a new recovery folder
+fn copy_to_new_folder(
+    db_path: &Path,
+    files: Vec<(PathBuf, String)>,
+    outcome: RecoveryOutcome,
+    errors: Vec<String>,
+    source: Option<String>,
+) -> Result<RecoveryReport, RecoveryError> {
+    let now = Utc::now();
+    let base = now.format("%Y%m%d_%H%M%S").to_string();
+    let root = recovery_root(db_path);
+    let mut id = base.clone();
+    let mut n = 1;
+    while root.join(&id).exists() {
+        n += 1;
+        id = format!("{}-{}", base, n);
+    }
+    let dir = root.join(&id);
+    fs::create_dir_all(&dir)?;
+
+    let mut copied = Vec::new();
+    for (path, name) in files {
+        if path.exists() {
+            fs::copy(&path, dir.join(&name))?;
+            copied.push(name);
+        }
+    }
+
+    let report = RecoveryReport {
+        id,
+        database: file_name(db_path),
+        occurred_at: now,
+        outcome,
+        errors,
+        backup_dir: dir.to_string_lossy().to_string(),
+        files: copied,
+        dismissed: matches!(outcome, RecoveryOutcome::Replaced | RecoveryOutcome::Staged),
+        source,
+    };
+    save(&dir, &report)?;
+    Ok(report)
+}
+
+/// Copy the database file and its WAL to a new recovery folder and record why
+pub fn preserve(db_path: &Path, outcome: RecoveryOutcome, errors: Vec<String>) -> Result<RecoveryReport, RecoveryError> {
+    let wal = wal_path(db_path);
+    let files = vec![(db_path.to_path_buf(), file_name(db_path)), (wal.clone(), file_name(&wal))];
+    let report = copy_to_new_folder(db_path, files, outcome, errors, None)?;
+    log::warn!("Copied {:?} to {:?} before recovery ({:?})", db_path, report.backup_dir, outcome);
+    Ok(report)
+}
+
+/// Update a stored report (e.g. its outcome once known)
+pub fn update(db_path: &Path, report: &RecoveryReport) -> Result<(), RecoveryError> {
+    save(&copy_dir(db_path, &report.id)?, report)
+}
+
+/// All recoveries of a database, newest first
+pub fn reports(db_path: &Path) -> Vec<RecoveryReport> {
+    let mut reports: Vec<RecoveryReport> = fs::read_dir(recovery_root(db_path))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| fs::read_to_string(e.path().join(REPORT_FILE)).ok())
+                .filter_map(|s| serde_json::from_str(&s).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    reports.sort_by(|a, b| b.occurred_at.cmp(&a.occurred_at));
+    reports
+}
+
+fn pending_restore(db_path: &Path) -> Option<String> {
+    fs::read_to_string(recovery_root(db_path).join(RESTORE_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str::<PendingRestore>(&s).ok())
+        .map(|p| p.id)
+}
+
+/// Reports to show for a database
+pub fn status(db_path: &Path) -> RecoveryStatus {
+    RecoveryStatus {
+        reports: reports(db_path).into_iter().filter(|r| !r.dismissed).collect(),
+        pending_restore: pending_restore(db_path),
+    }
+}
+
+fn find(db_path: &Path, id: &str) -> Result<RecoveryReport, RecoveryError> {
+    let dir = copy_dir(db_path, id)?;
+    fs::read_to_string(dir.join(REPORT_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .ok_or_else(|| RecoveryError::NotFound(id.to_string()))
+}
+
+/// Hide a report (the copies are kept)
+pub fn dismiss(db_path: &Path, id: &str) -> Result<(), RecoveryError> {
+    let mut report = find(db_path, id)?;
+    report.dismissed = true;
+    update(db_path, &report)
+}
+
+/// Restore a copy the next time the database is opened
+pub fn schedule_restore(db_path: &Path, id: &str) -> Result<(), RecoveryError> {
+    let report = find(db_path, id)?;
+    if !report.files.contains(&report.database) {
+        return Err(RecoveryError::NotFound(id.to_string()));
+    }
+    fs::write(
+        recovery_root(db_path).join(RESTORE_FILE),
+        serde_json::to_string(&PendingRestore { id: id.to_string() }).unwrap_or_default(),
+    )?;
+    log::info!("Scheduled restore of {:?} from recovery copy {}", db_path, id);
+    Ok(())
+}
+
+/// Carry out a scheduled restore before the database is opened: preserve the
+/// current files, then copy the chosen ones into place. Returns whether a
+/// restore was done.
+pub fn apply_pending_restore(db_path: &Path) -> Result<bool, RecoveryError> {
+    let Some(id) = pending_restore(db_path) else {
+        return Ok(false);
+    };
+    let marker = recovery_root(db_path).join(RESTORE_FILE);
+    let report = match find(db_path, &id) {
+        Ok(report) => report,
+        Err(e) => {
+            log::warn!("Dropping scheduled restore of {:?}: {}", db_path, e);
+            fs::remove_file(&marker)?;
+            return Ok(false);
+        }
+    };
+    let dir = copy_dir(db_path, &report.id)?;
+
+    if db_path.exists() {
+        preserve(db_path, RecoveryOutcome::Replaced, Vec::new())?;
+    }
+    let wal = wal_path(db_path);
+    if wal.exists() {
+        fs::remove_file(&wal)?;
+    }
+    for name in &report.files {
+        let target = if *name == report.database { db_path.to_path_buf() } else { wal.clone() };
+        fs::copy(dir.join(name), target)?;
+    }
+    fs::remove_file(&marker)?;
+    log::warn!("Restored {:?} from recovery copy {}", db_path, id);
+    Ok(true)
+}
+
+/// Where a restore candidate comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandidateKind {
+    RecoveryCopy,
+    /// Copy written before a schema migration (`snapshots/{file stem}/`)
+    MigrationSnapshot,
+    /// `{file name}.bak.{timestamp}` written by older versions on WAL recovery
+    LegacyBackup,
+    /// Scheduled backup archive, restored with `import_backup`
+    BackupArchive,
+}
+
+/// Something the database can be restored from
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryCandidate {
+    /// `{kind}:{name}`, passed back to restore it
+    pub id: String,
+    pub kind: CandidateKind,
+    pub path: String,
+    pub modified_at: Option<DateTime<Utc>>,
+    pub size_bytes: u64,
+    /// Flights in the file (None when it couldn't be opened; archives aren't)
+    pub flight_count: Option<i64>,
+}
+
+/// Returned by `list_recovery_candidates`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryCandidates {
+    /// Flights in the open database
+    pub flight_count: i64,
+    /// The database is empty and there is something to restore it from
+    pub prompt: bool,
+    /// Newest first
+    pub candidates: Vec<RecoveryCandidate>,
+}
+
+fn kind_prefix(kind: CandidateKind) -> &'static str {
+    match kind {
+        CandidateKind::RecoveryCopy => "recovery_copy",
+        CandidateKind::MigrationSnapshot => "migration_snapshot",
+        CandidateKind::LegacyBackup => "legacy_backup",
+        CandidateKind::BackupArchive => "backup_archive",
+    }
+}
+
+/// Number of flights in a database file, opened read-only
+fn count_flights(path: &Path) -> Option<i64> {
+    let config = duckdb::Config::default().access_mode(duckdb::AccessMode::ReadOnly).ok()?;
+    let conn = duckdb::Connection::open_with_flags(path, config).ok()?;
+    conn.query_row("SELECT COUNT(*) FROM flights", [], |row| row.get(0)).ok()
+}
+
+fn candidate(kind: CandidateKind, name: &str, path: &Path) -> Option<RecoveryCandidate> {
+    let meta = fs::metadata(path).ok().filter(|m| m.is_file())?;
+    Some(RecoveryCandidate {
+        id: format!("{}:{}", kind_prefix(kind), name),
+        kind,
+        path: path.to_string_lossy().to_string(),
+        modified_at: meta.modified().ok().map(DateTime::<Utc>::from),
+        size_bytes: meta.len(),
+        flight_count: if kind == CandidateKind::BackupArchive { None } else { count_flights(path) },
+    })
+}
+
+fn file_names(dir: &Path) -> Vec<String> {
+    fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.file_name().to_string_lossy().to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// Everything `db_path` can be restored from, newest first. `archive_dir` is
+/// the folder of the database's scheduled backups, if there is one.
+pub fn candidates(db_path: &Path, archive_dir: Option<&Path>) -> Vec<RecoveryCandidate> {
+    let data_dir = db_path.parent().unwrap_or(Path::new("."));
+    let db_name = file_name(db_path);
+    let mut found = Vec::new();
+
+    // Staged copies are listed under the file they were taken from
+    for report in reports(db_path).into_iter().filter(|r| r.outcome != RecoveryOutcome::Staged) {
+        if report.files.contains(&report.database) {
+            let path = Path::new(&report.backup_dir).join(&report.database);
+            found.extend(candidate(CandidateKind::RecoveryCopy, &report.id, &path));
+        }
+    }
+
+    let stem = db_path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let snapshot_dir = data_dir.join(SNAPSHOT_DIR).join(stem);
+    for name in file_names(&snapshot_dir).into_iter().filter(|n| n.ends_with(".db")) {
+        found.extend(candidate(CandidateKind::MigrationSnapshot, &name, &snapshot_dir.join(&name)));
+    }
+
+    let bak_prefix = format!("{}.bak.", db_name);
+    for name in file_names(data_dir).into_iter().filter(|n| n.starts_with(&bak_prefix)) {
+        found.extend(candidate(CandidateKind::LegacyBackup, &name, &data_dir.join(&name)));
+    }
+
+    if let Some(dir) = archive_dir {
+        for name in file_names(dir).into_iter().filter(|n| n.ends_with(ARCHIVE_SUFFIX)) {
+            found.extend(candidate(CandidateKind::BackupArchive, &name, &dir.join(&name)));
+        }
+    }
+
+    found.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
+    found
+}
+
+/// Restore candidates of a database holding `flight_count` flights
+pub fn list_candidates(db_path: &Path, archive_dir: Option<&Path>, flight_count: i64) -> RecoveryCandidates {
+    let candidates = candidates(db_path, archive_dir);
+    RecoveryCandidates {
+        flight_count,
+        prompt: flight_count == 0 && !candidates.is_empty(),
+        candidates,
+    }
+}
+
+/// Look up a candidate by id
+pub fn find_candidate(db_path: &Path, archive_dir: Option<&Path>, id: &str) -> Result<RecoveryCandidate, RecoveryError> {
+    candidates(db_path, archive_dir)
+        .into_iter()
+        .find(|c| c.id == id)
+        .ok_or_else(|| RecoveryError::CandidateNotFound(id.to_string()))
+}
+
+/// Restore a database file candidate the next time the database is opened.
+/// Snapshot and `.bak` files are staged in a recovery folder first (with the
+/// WAL of a `.bak` file, if it was kept); archives can't be restored this way.
+pub fn schedule_candidate_restore(db_path: &Path, candidate: &RecoveryCandidate) -> Result<(), RecoveryError> {
+    let id = match candidate.kind {
+        CandidateKind::RecoveryCopy => candidate.id.trim_start_matches("recovery_copy:").to_string(),
+        CandidateKind::MigrationSnapshot | CandidateKind::LegacyBackup => {
+            let source = PathBuf::from(&candidate.path);
+            let wal = wal_path(db_path);
+            let mut files = vec![(source.clone(), file_name(db_path))];
+            if candidate.kind == CandidateKind::LegacyBackup {
+                // flights.db.bak.{ts} came with flights.db.wal.bak.{ts}
+                let suffix = file_name(&source).trim_start_matches(&format!("{}.bak.", file_name(db_path))).to_string();
+                files.push((source.with_file_name(format!("{}.bak.{}", file_name(&wal), suffix)), file_name(&wal)));
+            }
+            copy_to_new_folder(db_path, files, RecoveryOutcome::Staged, Vec::new(), Some(candidate.path.clone()))?.id
+        }
+        CandidateKind::BackupArchive => return Err(RecoveryError::Archive(candidate.id.clone())),
+    };
+    schedule_restore(db_path, &id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preserve_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("flights.db");
+        fs::write(&db_path, b"good").unwrap();
+        fs::write(wal_path(&db_path), b"wal").unwrap();
+
+        let report = preserve(&db_path, RecoveryOutcome::WalSetAside, vec!["corrupt WAL".to_string()]).unwrap();
+        assert_eq!(report.files, ["flights.db", "flights.db.wal"]);
+        assert_eq!(status(&db_path).reports.len(), 1);
+
+        // The database was recreated empty in the meantime
+        fs::write(&db_path, b"empty").unwrap();
+        fs::remove_file(wal_path(&db_path)).unwrap();
+
+        assert!(matches!(schedule_restore(&db_path, "../x"), Err(RecoveryError::InvalidId(_))));
+        schedule_restore(&db_path, &report.id).unwrap();
+        assert_eq!(status(&db_path).pending_restore.as_deref(), Some(report.id.as_str()));
+        assert!(apply_pending_restore(&db_path).unwrap());
+        assert_eq!(fs::read(&db_path).unwrap(), b"good");
+        assert_eq!(fs::read(wal_path(&db_path)).unwrap(), b"wal");
+        assert!(status(&db_path).pending_restore.is_none());
+
+        // The replaced database is kept, but not reported
+        let all = reports(&db_path);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all.iter().filter(|r| r.outcome == RecoveryOutcome::Replaced).count(), 1);
+        dismiss(&db_path, &report.id).unwrap();
+        assert!(status(&db_path).reports.is_empty());
+    }
+
+    #[test]
+    fn test_candidates() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("flights.db");
+        fs::write(&db_path, b"empty").unwrap();
+        fs::write(dir.path().join("flights.db.bak.20240101_000000"), b"old").unwrap();
+        fs::write(dir.path().join("flights.db.wal.bak.20240101_000000"), b"old wal").unwrap();
+        fs::write(dir.path().join("flights_work.db.bak.20240101_000000"), b"other profile").unwrap();
+        let snapshots = dir.path().join("snapshots").join("flights");
+        fs::create_dir_all(&snapshots).unwrap();
+        fs::write(snapshots.join("v0.9.0_20240201_000000.db"), b"snapshot").unwrap();
+        let archives = dir.path().join("backups");
+        fs::create_dir_all(&archives).unwrap();
+        fs::write(archives.join("2024-03-01_00-00-00_Open_Dronelog.db.backup"), b"archive").unwrap();
+
+        let listed = list_candidates(&db_path, Some(&archives), 0);
+        assert!(listed.prompt);
+        let mut ids: Vec<&str> = listed.candidates.iter().map(|c| c.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(
+            ids,
+            [
+                "backup_archive:2024-03-01_00-00-00_Open_Dronelog.db.backup",
+                "legacy_backup:flights.db.bak.20240101_000000",
+                "migration_snapshot:v0.9.0_20240201_000000.db",
+            ]
+        );
+        assert!(!list_candidates(&db_path, None, 12).prompt);
+
+        let archive = find_candidate(&db_path, Some(&archives), ids[0]).unwrap();
+        assert!(matches!(schedule_candidate_restore(&db_path, &archive), Err(RecoveryError::Archive(_))));
+        assert!(matches!(find_candidate(&db_path, None, "legacy_backup:../x"), Err(RecoveryError::CandidateNotFound(_))));
+
+        // A .bak file is restored together with its WAL
+        let bak = find_candidate(&db_path, None, ids[1]).unwrap();
+        schedule_candidate_restore(&db_path, &bak).unwrap();
+        assert!(apply_pending_restore(&db_path).unwrap());
+        assert_eq!(fs::read(&db_path).unwrap(), b"old");
+        assert_eq!(fs::read(wal_path(&db_path)).unwrap(), b"old wal");
+
+        // The replaced empty database is now a recovery copy; the staged one isn't listed
+        let kinds: Vec<CandidateKind> = candidates(&db_path, None).iter().map(|c| c.kind).collect();
+        assert_eq!(kinds.iter().filter(|k| **k == CandidateKind::RecoveryCopy).count(), 1);
+    }
+}
@@ -0,0 +1,373 @@
+//! Static flight path images.
+//!
+//! Renders a flight track as SVG or PNG for PDF reports, share links and list
+//! thumbnails. The track is drawn on a plain background and coloured along an
+//! altitude gradient (low = blue, high = red), with start and end markers.
+//! No basemap tiles are fetched, so rendering works offline and in the
+//! Docker image without any extra configuration.
+//!
+//! PNG output uses a small built-in encoder (RGB, zlib via `flate2`) rather
+//! than pulling in an imaging crate for a single line drawing.
+
+use std::io::Write;
+
+use crate::database::{Database, DatabaseError};
+use crate::models::TelemetryData;
+
+/// Telemetry points loaded to build a track before rendering
+const RENDER_TELEMETRY_POINTS: usize = 5000;
+/// Track vertices kept for drawing
+const RENDER_TRACK_POINTS: usize = 2000;
+
+/// Output image format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "png" => Some(Self::Png),
+            "svg" => Some(Self::Svg),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Svg => "image/svg+xml",
+        }
+    }
+}
+
+/// Image size and styling
+#[derive(Debug, Clone, Copy)]
+pub struct RenderOptions {
+    pub width: u32,
+    pub height: u32,
+    /// Blank margin around the track, in pixels
+    pub padding: u32,
+    /// Track stroke width, in pixels
+    pub line_width: u32,
+    pub background: [u8; 3],
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            width: 640,
+            height: 400,
+            padding: 16,
+            line_width: 3,
+            background: [0x0f, 0x17, 0x2a],
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Options for a given size, with padding and stroke scaled for thumbnails
+    pub fn sized(width: u32, height: u32) -> Self {
+        let width = width.clamp(32, 4096);
+        let height = height.clamp(32, 4096);
+        let short = width.min(height);
+        Self {
+            width,
+            height,
+            padding: (short / 25).max(2),
+            line_width: (short / 130).clamp(1, 8),
+            ..Self::default()
+        }
+    }
+}
+
+/// Load the `[lon, lat, height]` track of a flight for rendering
+pub fn flight_track(db: &Database, flight_id: i64) -> Result<Vec<[f64; 3]>, DatabaseError> {
+    let flight = db.get_flight_by_id(flight_id)?;
+    let records = db.get_flight_telemetry(
+        flight_id,
+        Some(RENDER_TELEMETRY_POINTS),
+        flight.point_count.map(|c| c as i64),
+    )?;
+    Ok(TelemetryData::from_records(&records).extract_track(RENDER_TRACK_POINTS))
+}
+
+/// Render a track in the requested format
+pub fn render_track(track: &[[f64; 3]], format: ImageFormat, opts: &RenderOptions) -> Vec<u8> {
+    match format {
+        ImageFormat::Svg => render_svg(track, opts).into_bytes(),
+        ImageFormat::Png => render_png(track, opts),
+    }
+}
+
+/// Track point projected to pixel space, with altitude normalised to 0..1
+#[derive(Debug, Clone, Copy)]
+struct Projected {
+    x: f64,
+    y: f64,
+    t: f64,
+}
+
+/// Fit the track into the image (equirectangular, longitude scaled by
+/// cos(latitude), aspect ratio preserved and centred)
+fn project(track: &[[f64; 3]], opts: &RenderOptions) -> Vec<Projected> {
+    if track.is_empty() {
+        return Vec::new();
+    }
+    let mean_lat = track.iter().map(|p| p[1]).sum::<f64>() / track.len() as f64;
+    let kx = mean_lat.to_radians().cos().max(0.01);
+
+    let (mut min_x, mut max_x, mut min_y, mut max_y) = (f64::MAX, f64::MIN, f64::MAX, f64::MIN);
+    let (mut min_h, mut max_h) = (f64::MAX, f64::MIN);
+    for p in track {
+        let x = p[0] * kx;
+        min_x = min_x.min(x);
+        max_x = max_x.max(x);
+        min_y = min_y.min(p[1]);
+        max_y = max_y.max(p[1]);
+        min_h = min_h.min(p[2]);
+        max_h = max_h.max(p[2]);
+    }
+
+    let pad = opts.padding as f64;
+    let avail_w = (opts.width as f64 - 2.0 * pad).max(1.0);
+    let avail_h = (opts.height as f64 - 2.0 * pad).max(1.0);
+    let span_x = (max_x - min_x).max(1e-9);
+    let span_y = (max_y - min_y).max(1e-9);
+    let scale = (avail_w / span_x).min(avail_h / span_y);
+    let off_x = pad + (avail_w - span_x * scale) / 2.0;
+    let off_y = pad + (avail_h - span_y * scale) / 2.0;
+    let span_h = max_h - min_h;
+
+    track
+        .iter()
+        .map(|p| Projected {
+            x: off_x + (p[0] * kx - min_x) * scale,
+            // Screen y grows downwards, latitude grows upwards
+            y: off_y + (max_y - p[1]) * scale,
+            t: if span_h > 1e-6 { (p[2] - min_h) / span_h } else { 0.0 },
+        })
+        .collect()
+}
+
+/// Altitude gradient: blue → cyan → green → yellow → red
+fn gradient_color(t: f64) -> [u8; 3] {
+    const STOPS: [[f64; 3]; 5] = [
+        [59.0, 130.0, 246.0],
+        [34.0, 211.0, 238.0],
+        [34.0, 197.0, 94.0],
+        [250.0, 204.0, 21.0],
+        [239.0, 68.0, 68.0],
+    ];
+    let t = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f64;
+    let i = (t.floor() as usize).min(STOPS.len() - 2);
+    let f = t - i as f64;
+    let mix = |c: usize| (STOPS[i][c] + (STOPS[i + 1][c] - STOPS[i][c]) * f).round() as u8;
+    [mix(0), mix(1), mix(2)]
+}
+
+const START_COLOR: [u8; 3] = [34, 197, 94];
+const END_COLOR: [u8; 3] = [239, 68, 68];
+
+fn hex(c: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", c[0], c[1], c[2])
+}
+
+/// Render the track as an SVG document
+pub fn render_svg(track: &[[f64; 3]], opts: &RenderOptions) -> String {
+    let points = project(track, opts);
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}"><rect width="100%" height="100%" fill="{bg}"/>"#,
+        w = opts.width,
+        h = opts.height,
+        bg = hex(opts.background)
+    );
+    svg.push_str(&format!(
+        r#"<g fill="none" stroke-width="{}" stroke-linecap="round">"#,
+        opts.line_width
+    ));
+    for pair in points.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        svg.push_str(&format!(
+            r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}"/>"#,
+            a.x,
+            a.y,
+            b.x,
+            b.y,
+            hex(gradient_color((a.t + b.t) / 2.0))
+        ));
+    }
+    svg.push_str("</g>");
+    if let (Some(first), Some(last)) = (points.first(), points.last()) {
+        let r = (opts.line_width * 2).max(3);
+        svg.push_str(&format!(
+            r#"<circle cx="{:.1}" cy="{:.1}" r="{}" fill="{}"/><circle cx="{:.1}" cy="{:.1}" r="{}" fill="{}"/>"#,
+            first.x,
+            first.y,
+            r,
+            hex(START_COLOR),
+            last.x,
+            last.y,
+            r,
+            hex(END_COLOR)
+        ));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// RGB raster with a round brush
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32, background: [u8; 3]) -> Self {
+        let mut pixels = Vec::with_capacity((width * height * 3) as usize);
+        for _ in 0..width * height {
+            pixels.extend_from_slice(&background);
+        }
+        Self { width, height, pixels }
+    }
+
+    fn dot(&mut self, cx: f64, cy: f64, radius: f64, color: [u8; 3]) {
+        let r2 = radius * radius;
+        let x0 = (cx - radius).floor().max(0.0) as i64;
+        let x1 = (cx + radius).ceil().min(self.width as f64 - 1.0) as i64;
+        let y0 = (cy - radius).floor().max(0.0) as i64;
+        let y1 = (cy + radius).ceil().min(self.height as f64 - 1.0) as i64;
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                let (dx, dy) = (x as f64 + 0.5 - cx, y as f64 + 0.5 - cy);
+                if dx * dx + dy * dy <= r2 {
+                    let i = ((y as u32 * self.width + x as u32) * 3) as usize;
+                    self.pixels[i..i + 3].copy_from_slice(&color);
+                }
+            }
+        }
+    }
+
+    fn line(&mut self, a: Projected, b: Projected, radius: f64, color: [u8; 3]) {
+        let len = ((b.x - a.x).powi(2) + (b.y - a.y).powi(2)).sqrt();
+        let steps = (len / (radius * 0.5).max(0.5)).ceil().max(1.0) as usize;
+        for s in 0..=steps {
+            let f = s as f64 / steps as f64;
+            self.dot(a.x + (b.x - a.x) * f, a.y + (b.y - a.y) * f, radius, color);
+        }
+    }
+}
+
+/// Render the track as a PNG image
+pub fn render_png(track: &[[f64; 3]], opts: &RenderOptions) -> Vec<u8> {
+    let points = project(track, opts);
+    let mut canvas = Canvas::new(opts.width, opts.height, opts.background);
+    let radius = opts.line_width.max(1) as f64 / 2.0;
+    for pair in points.windows(2) {
+        canvas.line(pair[0], pair[1], radius, gradient_color((pair[0].t + pair[1].t) / 2.0));
+    }
+    if let (Some(first), Some(last)) = (points.first(), points.last()) {
+        let r = (opts.line_width * 2).max(3) as f64;
+        canvas.dot(first.x, first.y, r, START_COLOR);
+        canvas.dot(last.x, last.y, r, END_COLOR);
+    }
+    encode_png(canvas.width, canvas.height, &canvas.pixels)
+}
+
+// ============================================================================
+// PNG encoding
+// ============================================================================
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &b in bytes {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Encode 8-bit RGB pixels as a PNG file
+fn encode_png(width: u32, height: u32, rgb: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&width.to_be_bytes());
+    ihdr.extend_from_slice(&height.to_be_bytes());
+    ihdr.extend_from_slice(&[8, 2, 0, 0, 0]); // 8-bit, truecolour, deflate, no filter, no interlace
+    write_chunk(&mut out, b"IHDR", &ihdr);
+
+    // Every scanline starts with filter type 0 (none)
+    let row = (width * 3) as usize;
+    let mut raw = Vec::with_capacity((row + 1) * height as usize);
+    for line in rgb.chunks(row) {
+        raw.push(0);
+        raw.extend_from_slice(line);
+    }
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    // Writing to a Vec cannot fail
+    let _ = encoder.write_all(&raw);
+    let idat = encoder.finish().unwrap_or_default();
+    write_chunk(&mut out, b"IDAT", &idat);
+    write_chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_track() -> Vec<[f64; 3]> {
+        vec![[8.0, 47.0, 0.0], [8.001, 47.001, 50.0], [8.002, 47.0, 100.0]]
+    }
+
+    #[test]
+    fn test_projection_fits_image() {
+        let opts = RenderOptions::sized(200, 100);
+        let points = project(&sample_track(), &opts);
+        for p in &points {
+            assert!(p.x >= opts.padding as f64 - 1e-6 && p.x <= (opts.width - opts.padding) as f64 + 1e-6);
+            assert!(p.y >= opts.padding as f64 - 1e-6 && p.y <= (opts.height - opts.padding) as f64 + 1e-6);
+        }
+        assert!(points[0].t.abs() < 1e-9);
+        assert!((points[2].t - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_png_header() {
+        let png = render_png(&sample_track(), &RenderOptions::sized(64, 48));
+        assert_eq!(&png[..8], &[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a]);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(u32::from_be_bytes([png[16], png[17], png[18], png[19]]), 64);
+        assert_eq!(u32::from_be_bytes([png[20], png[21], png[22], png[23]]), 48);
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+    }
+
+    #[test]
+    fn test_svg_output() {
+        let svg = render_svg(&sample_track(), &RenderOptions::default());
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<line").count(), 2);
+        assert_eq!(svg.matches("<circle").count(), 2);
+        assert!(render_svg(&[], &RenderOptions::default()).ends_with("</svg>"));
+    }
+}
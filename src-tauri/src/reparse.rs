@@ -0,0 +1,324 @@
+//! Re-parse a flight from its original log file.
+//!
+//! Parsers improve: a newer DJI record version gets decoded, a field that was
+//! skipped gets mapped, GPS cleanup gets smarter. Re-parsing runs the current
+//! parsers over the original log of an imported flight and replaces its
+//! telemetry, app messages, import report and summary stats. Metadata the
+//! user may have edited (name, notes, tags, color, pilot...) is kept, and the
+//! post-import pipeline is rerun so derived data follows the new telemetry.
+//!
+//! Each re-parse returns a structured diff — points added, fields newly
+//! populated, stats changed — so users can see what an upgraded parser
+//! actually recovered. The diff is stored with the `reparse` audit entry.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::database::Database;
+use crate::models::{FieldCoverage, TelemetryRecord};
+use crate::parser::LogParser;
+use crate::recompute::FlightStatsValues;
+
+/// Largest gap between the stored and the re-parsed start time for a log to
+/// count as the same flight
+const START_TIME_SLACK_SECS: i64 = 120;
+
+/// Changes smaller than this are not reported
+const EPSILON: f64 = 1e-6;
+
+/// A telemetry field whose number of values changed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    pub field: String,
+    /// Points with a value before and after the re-parse
+    pub before: usize,
+    pub after: usize,
+}
+
+/// A summary column whose value changed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatChange {
+    pub field: &'static str,
+    pub before: Option<f64>,
+    pub after: Option<f64>,
+}
+
+/// What a re-parse changed for one flight
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReparseDiff {
+    pub flight_id: i64,
+    /// Parser of the stored import report (None for flights imported before reports existed)
+    pub format_before: Option<String>,
+    pub format_after: String,
+    pub points_before: usize,
+    pub points_after: usize,
+    /// Negative when the new parser drops points (e.g. GPS glitches)
+    pub points_added: i64,
+    /// Fields with no value before that have values now
+    pub fields_populated: Vec<String>,
+    /// Fields that had values before and have none now
+    pub fields_lost: Vec<String>,
+    /// Every field whose number of values changed
+    pub coverage_changes: Vec<FieldChange>,
+    pub stats_changed: Vec<StatChange>,
+    pub messages_before: usize,
+    pub messages_after: usize,
+}
+
+/// One side of the comparison
+pub struct Snapshot {
+    pub format: Option<String>,
+    pub coverage: Vec<FieldCoverage>,
+    pub points: usize,
+    pub stats: FlightStatsValues,
+    pub messages: usize,
+}
+
+/// Value counts of the stored telemetry, for flights without an import report.
+/// Columns the telemetry table does not keep are left out.
+fn record_coverage(records: &[TelemetryRecord]) -> Vec<FieldCoverage> {
+    let fields: [(&str, fn(&TelemetryRecord) -> bool); 33] = [
+        ("latitude", |r| r.latitude.is_some()),
+        ("longitude", |r| r.longitude.is_some()),
+        ("altitude", |r| r.altitude.is_some()),
+        ("height", |r| r.height.is_some()),
+        ("vps_height", |r| r.vps_height.is_some()),
+        ("speed", |r| r.speed.is_some()),
+        ("velocity_x", |r| r.velocity_x.is_some()),
+        ("velocity_y", |r| r.velocity_y.is_some()),
+        ("velocity_z", |r| r.velocity_z.is_some()),
+        ("pitch", |r| r.pitch.is_some()),
+        ("roll", |r| r.roll.is_some()),
+        ("yaw", |r| r.yaw.is_some()),
+        ("gimbal_pitch", |r| r.gimbal_pitch.is_some()),
+        ("gimbal_roll", |r| r.gimbal_roll.is_some()),
+        ("gimbal_yaw", |r| r.gimbal_yaw.is_some()),
+        ("battery_percent", |r| r.battery_percent.is_some()),
+        ("battery_voltage", |r| r.battery_voltage.is_some()),
+        ("battery_current", |r| r.battery_current.is_some()),
+        ("battery_temp", |r| r.battery_temp.is_some()),
+        ("battery_full_capacity", |r| r.battery_full_capacity.is_some()),
+        ("battery_remained_capacity", |r| r.battery_remained_capacity.is_some()),
+        ("cell_voltages", |r| r.cell_voltages.is_some()),
+        ("flight_mode", |r| r.flight_mode.is_some()),
+        ("satellites", |r| r.satellites.is_some()),
+        ("rc_signal", |r| r.rc_signal.is_some()),
+        ("rc_uplink", |r| r.rc_uplink.is_some()),
+        ("rc_downlink", |r| r.rc_downlink.is_some()),
+        ("rc_aileron", |r| r.rc_aileron.is_some()),
+        ("rc_elevator", |r| r.rc_elevator.is_some()),
+        ("rc_throttle", |r| r.rc_throttle.is_some()),
+        ("rc_rudder", |r| r.rc_rudder.is_some()),
+        ("is_photo", |r| r.is_photo.is_some()),
+        ("is_video", |r| r.is_video.is_some()),
+    ];
+    let total = records.len();
+    fields
+        .iter()
+        .map(|(name, is_present)| {
+            let present_count = records.iter().filter(|r| is_present(r)).count();
+            FieldCoverage {
+                field: name.to_string(),
+                present_count,
+                null_percent: if total == 0 { 100.0 } else { (total - present_count) as f64 * 100.0 / total as f64 },
+            }
+        })
+        .collect()
+}
+
+fn stat_changes(before: &FlightStatsValues, after: &FlightStatsValues) -> Vec<StatChange> {
+    [
+        ("totalDistance", before.total_distance, after.total_distance),
+        ("maxAltitude", before.max_altitude, after.max_altitude),
+        ("maxSpeed", before.max_speed, after.max_speed),
+        ("durationSecs", before.duration_secs, after.duration_secs),
+    ]
+    .into_iter()
+    .filter(|(_, b, a)| match (b, a) {
+        (Some(b), Some(a)) => (a - b).abs() > EPSILON,
+        (b, a) => b.is_some() != a.is_some(),
+    })
+    .map(|(field, before, after)| StatChange { field, before, after })
+    .collect()
+}
+
+/// Compare a flight before and after re-parsing
+pub fn diff(flight_id: i64, before: &Snapshot, after: &Snapshot) -> ReparseDiff {
+    let counts_before: HashMap<&str, usize> =
+        before.coverage.iter().map(|c| (c.field.as_str(), c.present_count)).collect();
+
+    let mut fields_populated = Vec::new();
+    let mut fields_lost = Vec::new();
+    let mut coverage_changes = Vec::new();
+    for coverage in &after.coverage {
+        // Fields unknown before (not kept by the telemetry table) are not compared
+        let Some(&count_before) = counts_before.get(coverage.field.as_str()) else {
+            continue;
+        };
+        let count_after = coverage.present_count;
+        if count_before == count_after {
+            continue;
+        }
+        if count_before == 0 {
+            fields_populated.push(coverage.field.clone());
+        } else if count_after == 0 {
+            fields_lost.push(coverage.field.clone());
+        }
+        coverage_changes.push(FieldChange { field: coverage.field.clone(), before: count_before, after: count_after });
+    }
+
+    ReparseDiff {
+        flight_id,
+        format_before: before.format.clone(),
+        format_after: after.format.clone().unwrap_or_default(),
+        points_before: before.points,
+        points_after: after.points,
+        points_added: after.points as i64 - before.points as i64,
+        fields_populated,
+        fields_lost,
+        coverage_changes,
+        stats_changed: stat_changes(&before.stats, &after.stats),
+        messages_before: before.messages,
+        messages_after: after.messages,
+    }
+}
+
+/// Re-parse `file_path` into flight `flight_id` and report what changed.
+/// The log must be the flight's: same file, or the same start time.
+pub async fn reparse_flight(
+    db: &Database,
+    flight_id: i64,
+    file_path: &Path,
+    config: &serde_json::Value,
+) -> Result<ReparseDiff, String> {
+    let flight = db
+        .get_flight_by_id(flight_id)
+        .map_err(|e| format!("Failed to get flight {}: {}", flight_id, e))?;
+    if flight.is_manual {
+        return Err("Manual entries have no log to re-parse".to_string());
+    }
+
+    let parsed = LogParser::new(db)
+        .reparse_log(file_path)
+        .await
+        .map_err(|e| format!("Failed to parse log: {}", e))?;
+
+    let same_file = flight.file_hash.is_some() && flight.file_hash == parsed.metadata.file_hash;
+    let same_start = match (flight.start_time_utc(), parsed.metadata.start_time) {
+        (Some(stored), Some(parsed)) => (stored - parsed).num_seconds().abs() <= START_TIME_SLACK_SECS,
+        _ => false,
+    };
+    if !same_file && !same_start {
+        return Err(format!("The log does not match flight {} (different file and start time)", flight_id));
+    }
+
+    // Before
+    let report_before = db
+        .get_import_report(flight_id)
+        .map_err(|e| format!("Failed to get import report: {}", e))?;
+    let records = db
+        .get_flight_telemetry(flight_id, None, None)
+        .map_err(|e| format!("Failed to get telemetry for flight {}: {}", flight_id, e))?;
+    let messages_before = db
+        .get_flight_messages(flight_id)
+        .map_err(|e| format!("Failed to get messages: {}", e))?
+        .len();
+    let mut before = Snapshot {
+        coverage: match &report_before {
+            Some(report) => report.field_coverage.clone(),
+            None => record_coverage(&records),
+        },
+        format: report_before.map(|r| r.format),
+        points: records.len(),
+        stats: FlightStatsValues::default(),
+        messages: messages_before,
+    };
+
+    // Store the new parse
+    let mut stats = LogParser::new(db).calculate_stats(&parsed.points);
+    stats.duration_secs = parsed.metadata.duration_secs.unwrap_or(stats.duration_secs);
+    stats.total_distance_m = parsed.metadata.total_distance.unwrap_or(stats.total_distance_m);
+    stats.max_altitude_m = parsed.metadata.max_altitude.unwrap_or(stats.max_altitude_m);
+    stats.max_speed_ms = parsed.metadata.max_speed.unwrap_or(stats.max_speed_ms);
+
+    db.replace_flight_telemetry(flight_id, &parsed.points)
+        .map_err(|e| format!("Failed to store telemetry: {}", e))?;
+    before.stats = db
+        .replace_flight_stats(flight_id, &stats)
+        .map_err(|e| format!("Failed to update flight {}: {}", flight_id, e))?;
+    db.delete_flight_messages(flight_id)
+        .and_then(|_| db.insert_flight_messages(flight_id, &parsed.messages))
+        .map_err(|e| format!("Failed to store messages: {}", e))?;
+    let report = parsed.import_report(flight_id);
+    db.save_import_report(&report)
+        .map_err(|e| format!("Failed to save import report: {}", e))?;
+
+    if let Err(e) = crate::hooks::run_pipeline(db, flight_id, config, crate::hooks::HookTrigger::Reprocess) {
+        log::warn!("Post-import hooks failed after re-parsing flight {}: {}", flight_id, e);
+    }
+
+    let after = Snapshot {
+        format: Some(report.format.clone()),
+        coverage: report.field_coverage,
+        points: report.point_count,
+        stats: FlightStatsValues {
+            total_distance: Some(stats.total_distance_m),
+            max_altitude: Some(stats.max_altitude_m),
+            max_speed: Some(stats.max_speed_ms),
+            duration_secs: Some(stats.duration_secs),
+        },
+        messages: parsed.messages.len(),
+    };
+    let diff = diff(flight_id, &before, &after);
+    log::info!(
+        "Re-parsed flight {}: {} points ({:+}), {} fields newly populated, {} stats changed",
+        flight_id,
+        diff.points_after,
+        diff.points_added,
+        diff.fields_populated.len(),
+        diff.stats_changed.len()
+    );
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coverage(field: &str, present_count: usize) -> FieldCoverage {
+        FieldCoverage { field: field.to_string(), present_count, null_percent: 0.0 }
+    }
+
+    #[test]
+    fn test_diff_reports_recovered_fields_and_stats() {
+        let stats = FlightStatsValues { total_distance: Some(1000.0), max_altitude: Some(50.0), max_speed: Some(10.0), duration_secs: Some(300.0) };
+        let before = Snapshot {
+            format: None,
+            coverage: vec![coverage("latitude", 100), coverage("battery_voltage", 0), coverage("rc_signal", 40)],
+            points: 100,
+            stats,
+            messages: 2,
+        };
+        let after = Snapshot {
+            format: Some("DJI TXT".to_string()),
+            // altitude_abs is not kept by the telemetry table, so it is not compared
+            coverage: vec![coverage("latitude", 120), coverage("battery_voltage", 120), coverage("rc_signal", 0), coverage("altitude_abs", 120)],
+            points: 120,
+            stats: FlightStatsValues { total_distance: Some(1100.0), ..stats },
+            messages: 2,
+        };
+
+        let d = diff(7, &before, &after);
+        assert_eq!(d.points_added, 20);
+        assert_eq!(d.fields_populated, vec!["battery_voltage".to_string()]);
+        assert_eq!(d.fields_lost, vec!["rc_signal".to_string()]);
+        assert_eq!(d.coverage_changes.len(), 3);
+        assert_eq!(d.stats_changed, vec![StatChange { field: "totalDistance", before: Some(1000.0), after: Some(1100.0) }]);
+        assert_eq!(d.format_after, "DJI TXT");
+    }
+}
@@ -0,0 +1,213 @@
+//! Currency / training requirement tracking.
+//!
+//! Users define recency rules such as "3 takeoffs and landings in 90 days" or
+//! "1 night flight in 90 days", optionally restricted to one aircraft model.
+//! Rules are stored in the `requirements` table and evaluated against the
+//! flight database on demand: each evaluation reports the current value, whether
+//! the rule is met, and the date on which it lapses if no further flights are logged.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::Flight;
+use crate::parser::is_night_start;
+
+/// What a rule counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequirementMetric {
+    /// Takeoff/landing cycles (detected per flight; one for flights not counted)
+    Takeoffs,
+    /// Flights started at night (same heuristic as the "Night Flight" tag)
+    NightFlights,
+    /// Total flight time in hours
+    FlightHours,
+}
+
+impl RequirementMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Takeoffs => "takeoffs",
+            Self::NightFlights => "night_flights",
+            Self::FlightHours => "flight_hours",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "takeoffs" => Some(Self::Takeoffs),
+            "night_flights" => Some(Self::NightFlights),
+            "flight_hours" => Some(Self::FlightHours),
+            _ => None,
+        }
+    }
+}
+
+/// A user-defined recency rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Requirement {
+    /// Generated on first save when empty
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub metric: RequirementMetric,
+    /// Amount required within the window (count, or hours for `FlightHours`)
+    pub min_value: f64,
+    pub window_days: i64,
+    /// Only count flights whose drone model contains this text (case-insensitive)
+    pub drone_model: Option<String>,
+}
+
+/// Evaluation of a rule at a point in time
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequirementStatus {
+    pub requirement: Requirement,
+    /// Amount achieved within the window
+    pub current_value: f64,
+    pub compliant: bool,
+    /// When the rule lapses if nothing else is flown (None when not compliant)
+    pub expires_at: Option<String>,
+    /// Most recent qualifying flight
+    pub last_qualifying_at: Option<String>,
+}
+
+/// Per-flight facts the rules are evaluated on
+struct FlightSample {
+    start_time: DateTime<Utc>,
+    value: f64,
+}
+
+fn samples_for(requirement: &Requirement, flights: &[Flight]) -> Vec<FlightSample> {
+    let model_filter = requirement
+        .drone_model
+        .as_deref()
+        .map(|m| m.trim().to_lowercase())
+        .filter(|m| !m.is_empty());
+
+    let mut samples: Vec<FlightSample> = flights
+        .iter()
+        .filter(|f| match &model_filter {
+            Some(m) => f.drone_model.as_deref().is_some_and(|d| d.to_lowercase().contains(m)),
+            None => true,
+        })
+        .filter_map(|f| {
+            let start_time = f.start_time_utc()?;
+            let value = match requirement.metric {
+                RequirementMetric::Takeoffs => f.takeoff_count.map_or(1.0, f64::from),
+                RequirementMetric::NightFlights => {
+                    if is_night_start(start_time, f.home_lon) {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                }
+                RequirementMetric::FlightHours => f.duration_secs.unwrap_or(0.0) / 3600.0,
+            };
+            (value > 0.0).then_some(FlightSample { start_time, value })
+        })
+        .collect();
+    // Newest first
+    samples.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+    samples
+}
+
+/// Evaluate a rule against the flight list at `now`
+pub fn evaluate(requirement: &Requirement, flights: &[Flight], now: DateTime<Utc>) -> RequirementStatus {
+    let window = Duration::days(requirement.window_days.max(0));
+    let window_start = now - window;
+    let samples: Vec<FlightSample> = samples_for(requirement, flights)
+        .into_iter()
+        .filter(|s| s.start_time >= window_start && s.start_time <= now)
+        .collect();
+
+    let current_value: f64 = samples.iter().map(|s| s.value).sum();
+    let compliant = current_value + 1e-9 >= requirement.min_value;
+
+    // Walking back from the newest flight, the one that brings the running total
+    // up to the minimum is the oldest flight the rule depends on; the rule lapses
+    // when that flight leaves the window.
+    let expires_at = if compliant {
+        let mut running = 0.0;
+        samples
+            .iter()
+            .find(|s| {
+                running += s.value;
+                running + 1e-9 >= requirement.min_value
+            })
+            .map(|s| s.start_time + window)
+            .or(Some(now))
+    } else {
+        None
+    };
+
+    RequirementStatus {
+        requirement: requirement.clone(),
+        current_value,
+        compliant,
+        expires_at: expires_at.map(|t| t.to_rfc3339()),
+        last_qualifying_at: samples.first().map(|s| s.start_time.to_rfc3339()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FlightBuilder;
+    use chrono::TimeZone;
+
+    fn flight(start: &str, duration_secs: f64, model: &str) -> Flight {
+        // Night is judged by the sun at the home longitude
+        FlightBuilder::new(1).start(start).duration(duration_secs).drone_model(model).home(0.0, 0.0).build()
+    }
+
+    fn rule(metric: RequirementMetric, min_value: f64, model: Option<&str>) -> Requirement {
+        Requirement {
+            id: "r".to_string(),
+            name: "rule".to_string(),
+            metric,
+            min_value,
+            window_days: 90,
+            drone_model: model.map(|m| m.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_takeoffs_compliance_and_expiry() {
+        let now = Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap();
+        let flights = vec![
+            flight("2026-05-30T10:00:00Z", 600.0, "Mavic 3"),
+            flight("2026-05-01T10:00:00Z", 600.0, "Mavic 3"),
+            flight("2026-04-01T10:00:00Z", 600.0, "Mini 4 Pro"),
+            flight("2025-12-01T10:00:00Z", 600.0, "Mavic 3"), // outside the window
+        ];
+
+        let status = evaluate(&rule(RequirementMetric::Takeoffs, 3.0, None), &flights, now);
+        assert!(status.compliant);
+        assert!((status.current_value - 3.0).abs() < 1e-9);
+        // Third most recent flight (April 1st) drops out 90 days later
+        assert_eq!(status.expires_at.as_deref(), Some("2026-06-30T10:00:00+00:00"));
+
+        let status = evaluate(&rule(RequirementMetric::Takeoffs, 3.0, Some("mavic")), &flights, now);
+        assert!(!status.compliant);
+        assert!(status.expires_at.is_none());
+    }
+
+    #[test]
+    fn test_night_and_hours_metrics() {
+        let now = Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap();
+        let flights = vec![
+            flight("2026-05-20T22:30:00Z", 1800.0, "Mavic 3"),
+            flight("2026-05-21T12:00:00Z", 5400.0, "Mavic 3"),
+        ];
+
+        let night = evaluate(&rule(RequirementMetric::NightFlights, 1.0, None), &flights, now);
+        assert!(night.compliant);
+        assert_eq!(night.last_qualifying_at.as_deref(), Some("2026-05-20T22:30:00+00:00"));
+
+        let hours = evaluate(&rule(RequirementMetric::FlightHours, 3.0, None), &flights, now);
+        assert!(!hours.compliant);
+        assert!((hours.current_value - 2.0).abs() < 1e-9);
+    }
+}
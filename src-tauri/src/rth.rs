@@ -0,0 +1,183 @@
+//! Return-to-home analysis.
+//!
+//! Finds RTH activations in a flight's flight-mode stream and measures how
+//! each one played out: how far out and on how much battery it triggered, how
+//! long the aircraft took to get down, and whether it landed on the home point.
+//! Useful for checking that RTH altitude and low-battery settings leave enough
+//! margin for where you fly.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::TelemetryRecord;
+use crate::parser::haversine_distance;
+
+/// Landing within this radius of the home point counts as landing at home
+const HOME_LANDING_RADIUS_M: f64 = 10.0;
+/// Height at or below which the aircraft is considered on the ground
+const LANDED_HEIGHT_M: f64 = 1.0;
+
+/// One RTH activation and its outcome
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RthEvent {
+    /// Flight time (ms) at which RTH engaged
+    pub trigger_ms: i64,
+    /// Flight mode reported at the trigger (e.g. "GoHome")
+    pub trigger_mode: String,
+    pub trigger_distance_m: Option<f64>,
+    pub trigger_height_m: Option<f64>,
+    pub trigger_battery_percent: Option<i32>,
+    /// Seconds from trigger to touchdown (None if the RTH was cancelled)
+    pub time_to_land_secs: Option<f64>,
+    /// Battery left at touchdown
+    pub landing_battery_percent: Option<i32>,
+    /// Distance between touchdown and the home point
+    pub landing_distance_from_home_m: Option<f64>,
+    pub landed_at_home: bool,
+    /// Pilot took back control before the aircraft landed
+    pub cancelled: bool,
+}
+
+fn normalized_mode(mode: &str) -> String {
+    mode.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// True for go-home flight modes across DJI, Litchi and Airdata naming
+pub fn is_rth_mode(mode: &str) -> bool {
+    let m = normalized_mode(mode);
+    m.contains("gohome") || m.contains("returntohome") || m == "rth"
+}
+
+/// Descent/landing phases that follow a go-home leg
+fn is_landing_mode(mode: &str) -> bool {
+    normalized_mode(mode).contains("landing")
+}
+
+/// Detect RTH activations. `home` is `[lon, lat]`; when absent, the first
+/// valid GPS fix is used.
+pub fn analyze_rth(records: &[TelemetryRecord], home: Option<[f64; 2]>) -> Vec<RthEvent> {
+    let home = home.or_else(|| {
+        records.iter().find_map(|r| match (r.longitude, r.latitude) {
+            (Some(lon), Some(lat)) if lat.abs() > 0.0001 || lon.abs() > 0.0001 => Some([lon, lat]),
+            _ => None,
+        })
+    });
+    let distance_from_home = |r: &TelemetryRecord| match (home, r.latitude, r.longitude) {
+        (Some(h), Some(lat), Some(lon)) if lat.abs() > 0.0001 || lon.abs() > 0.0001 => {
+            Some(haversine_distance(h[1], h[0], lat, lon))
+        }
+        _ => None,
+    };
+
+    let mut events = Vec::new();
+    let mut i = 0;
+    while i < records.len() {
+        let mode = records[i].flight_mode.as_deref().unwrap_or("");
+        if !is_rth_mode(mode) {
+            i += 1;
+            continue;
+        }
+
+        let trigger = &records[i];
+        // The event runs through the go-home leg and any landing phase after it
+        let mut end = i;
+        while end + 1 < records.len() {
+            let next_mode = records[end + 1].flight_mode.as_deref().unwrap_or("");
+            if is_rth_mode(next_mode) || is_landing_mode(next_mode) {
+                end += 1;
+            } else {
+                break;
+            }
+        }
+
+        let last = &records[end];
+        let ended_by_log = end + 1 == records.len();
+        let on_ground = last.height.map_or(ended_by_log, |h| h <= LANDED_HEIGHT_M);
+        let cancelled = !on_ground && !ended_by_log;
+
+        let landing_distance = if cancelled { None } else { distance_from_home(last) };
+        events.push(RthEvent {
+            trigger_ms: trigger.timestamp_ms,
+            trigger_mode: mode.to_string(),
+            trigger_distance_m: distance_from_home(trigger),
+            trigger_height_m: trigger.height,
+            trigger_battery_percent: trigger.battery_percent,
+            time_to_land_secs: if cancelled {
+                None
+            } else {
+                Some((last.timestamp_ms - trigger.timestamp_ms) as f64 / 1000.0)
+            },
+            landing_battery_percent: if cancelled { None } else { last.battery_percent },
+            landing_distance_from_home_m: landing_distance,
+            landed_at_home: landing_distance.is_some_and(|d| d <= HOME_LANDING_RADIUS_M),
+            cancelled,
+        });
+
+        i = end + 1;
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(ts: i64, mode: &str, lat: f64, height: f64, battery: i32) -> TelemetryRecord {
+        TelemetryRecord {
+            timestamp_ms: ts,
+            latitude: Some(lat),
+            longitude: Some(8.0),
+            height: Some(height),
+            battery_percent: Some(battery),
+            flight_mode: Some(mode.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rth_landing_at_home() {
+        let records = vec![
+            record(0, "GPSAtti", 47.0, 0.0, 90),
+            record(60_000, "GPSAtti", 47.01, 80.0, 30),
+            record(61_000, "GoHome", 47.01, 80.0, 29),
+            record(150_000, "GoHome", 47.0, 30.0, 22),
+            record(170_000, "AutoLanding", 47.0, 0.2, 20),
+        ];
+        let events = analyze_rth(&records, Some([8.0, 47.0]));
+        assert_eq!(events.len(), 1);
+        let e = &events[0];
+        assert_eq!(e.trigger_ms, 61_000);
+        assert_eq!(e.trigger_battery_percent, Some(29));
+        assert!((e.trigger_distance_m.unwrap() - 1112.0).abs() < 5.0);
+        assert!((e.time_to_land_secs.unwrap() - 109.0).abs() < 1e-9);
+        assert!(e.landed_at_home);
+        assert!(!e.cancelled);
+    }
+
+    #[test]
+    fn test_rth_cancelled_by_pilot() {
+        let records = vec![
+            record(0, "P-GPS", 47.0, 0.0, 90),
+            record(10_000, "Go Home", 47.005, 50.0, 60),
+            record(20_000, "P-GPS", 47.004, 50.0, 58),
+            record(60_000, "P-GPS", 47.0, 0.0, 50),
+        ];
+        let events = analyze_rth(&records, None);
+        assert_eq!(events.len(), 1);
+        assert!(events[0].cancelled);
+        assert!(events[0].time_to_land_secs.is_none());
+        assert!(!events[0].landed_at_home);
+    }
+
+    #[test]
+    fn test_mode_matching() {
+        assert!(is_rth_mode("GoHome"));
+        assert!(is_rth_mode("Go Home"));
+        assert!(is_rth_mode("RTH"));
+        assert!(!is_rth_mode("AutoLanding"));
+        assert!(!is_rth_mode("GPSAtti"));
+    }
+}
@@ -0,0 +1,157 @@
+//! Safe-mode startup after repeated crashes.
+//!
+//! Each desktop start increments a counter in `startup_state.json` in the data
+//! directory; the counter is reset once the app has run for `STABLE_AFTER` or
+//! exits cleanly. When `SAFE_MODE_THRESHOLD` starts in a row never got that
+//! far, the next start is in safe mode: the database is opened without
+//! migrations, deduplication, backfills or vacuum, queued imports aren't
+//! resumed, and data commands are refused. Only repair actions are offered —
+//! backup and restore, integrity check, the actions in `repair`, support
+//! bundles and switching profiles — until the user leaves safe mode.
+//!
+//! Setting `DRONELOG_SAFE_MODE=1` forces safe mode for one start.
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::database::Database;
+
+/// Consecutive failed starts before safe mode kicks in
+pub const SAFE_MODE_THRESHOLD: u32 = 3;
+/// A start counts as successful once the app has run this long
+pub const STABLE_AFTER: Duration = Duration::from_secs(30);
+const STATE_FILE: &str = "startup_state.json";
+const FORCE_ENV: &str = "DRONELOG_SAFE_MODE";
+/// Returned by data commands while in safe mode
+pub const BLOCKED_MESSAGE: &str = "The app started in safe mode after repeated crashes — only repair actions are available";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct StartupState {
+    consecutive_failures: u32,
+    last_started_at: Option<String>,
+}
+
+fn read_state(data_dir: &Path) -> StartupState {
+    fs::read_to_string(data_dir.join(STATE_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn write_state(data_dir: &Path, state: &StartupState) {
+    let result = fs::create_dir_all(data_dir)
+        .and_then(|_| fs::write(data_dir.join(STATE_FILE), serde_json::to_string_pretty(state).unwrap_or_default()));
+    if let Err(e) = result {
+        log::warn!("Failed to write startup state: {}", e);
+    }
+}
+
+/// Whether this start is in safe mode, and why
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeModeStatus {
+    pub active: bool,
+    /// Starts in a row that didn't reach a stable state before this one
+    pub consecutive_failures: u32,
+    pub threshold: u32,
+    pub reason: Option<String>,
+}
+
+/// Record a start and decide whether it is in safe mode
+pub fn begin_startup(data_dir: &Path) -> SafeModeStatus {
+    let mut state = read_state(data_dir);
+    let forced = std::env::var(FORCE_ENV).map(|v| v == "1" || v.eq_ignore_ascii_case("true")).unwrap_or(false);
+    let failures = state.consecutive_failures;
+
+    let reason = if forced {
+        Some(format!("{} is set", FORCE_ENV))
+    } else if failures >= SAFE_MODE_THRESHOLD {
+        Some(format!("The last {} starts did not complete", failures))
+    } else {
+        None
+    };
+    if let Some(reason) = &reason {
+        log::warn!("Starting in safe mode: {}", reason);
+    }
+
+    state.consecutive_failures = failures.saturating_add(1);
+    state.last_started_at = Some(Utc::now().to_rfc3339());
+    write_state(data_dir, &state);
+
+    SafeModeStatus {
+        active: reason.is_some(),
+        consecutive_failures: failures,
+        threshold: SAFE_MODE_THRESHOLD,
+        reason,
+    }
+}
+
+/// The start succeeded: reset the counter. In safe mode the counter is kept,
+/// so the next start stays in safe mode until the user leaves it.
+pub fn mark_stable(data_dir: &Path, status: &SafeModeStatus) {
+    if status.active {
+        return;
+    }
+    write_state(data_dir, &StartupState { consecutive_failures: 0, last_started_at: read_state(data_dir).last_started_at });
+}
+
+/// Leave safe mode: the next start is a normal one
+pub fn leave(data_dir: &Path) {
+    write_state(data_dir, &StartupState::default());
+    log::info!("Leaving safe mode at the next start");
+}
+
+/// Run a repair action on the database. Actions: `checkpoint` (flush the
+/// WAL into the database file), `recover_interrupted_imports` (remove
+/// half-imported flights) and `cancel_import_queue` (mark queued and running
+/// imports failed so they aren't resumed).
+pub fn repair(db: &Database, action: &str) -> Result<String, String> {
+    match action {
+        "checkpoint" => db
+            .checkpoint()
+            .map(|_| "WAL checkpoint completed".to_string())
+            .map_err(|e| format!("Checkpoint failed: {}", e)),
+        "recover_interrupted_imports" => {
+            let removed = db.recover_interrupted_imports();
+            Ok(format!("Removed {} half-imported flight(s)", removed))
+        }
+        "cancel_import_queue" => db
+            .cancel_queued_imports()
+            .map(|n| format!("Cancelled {} queued import(s)", n))
+            .map_err(|e| format!("Failed to cancel queued imports: {}", e)),
+        other => Err(format!(
+            "Unknown repair action '{}' (use checkpoint, recover_interrupted_imports or cancel_import_queue)",
+            other
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_mode_after_repeated_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        for _ in 0..SAFE_MODE_THRESHOLD {
+            assert!(!begin_startup(dir.path()).active);
+        }
+        let status = begin_startup(dir.path());
+        assert!(status.active);
+        assert_eq!(status.consecutive_failures, SAFE_MODE_THRESHOLD);
+
+        // A stable safe-mode run doesn't end safe mode, leaving it does
+        mark_stable(dir.path(), &status);
+        assert!(begin_startup(dir.path()).active);
+        leave(dir.path());
+        let status = begin_startup(dir.path());
+        assert!(!status.active);
+        mark_stable(dir.path(), &status);
+        assert_eq!(read_state(dir.path()).consecutive_failures, 0);
+    }
+}
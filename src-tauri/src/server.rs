@@ -8,21 +8,28 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use axum::{
-    extract::{DefaultBodyLimit, FromRequestParts, Multipart, Path, Query, State as AxumState},
+    extract::{DefaultBodyLimit, FromRequestParts, MatchedPath, Multipart, Path, Query, Request, State as AxumState},
     http::StatusCode,
+    middleware::Next,
+    response::{Redirect, Response},
     routing::{delete, get, post, put},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::trace::{DefaultOnResponse, TraceLayer};
 use tokio_cron_scheduler::{Job, JobScheduler};
 
 use crate::api::DjiApi;
 use crate::database::{self, Database};
-use crate::models::{FlightDataResponse, FlightTag, ImportResult, OverviewStats, TelemetryData};
+use crate::login_audit::{LoginGuard, LoginKind, LoginOutcome};
+use crate::messages;
+use crate::models::{Attachment, ChecklistRecord, ChecklistTemplate, ExceedanceLimits, FlightDataResponse, FlightMetadataUpdate, FlightTag, ImportReport, ImportResult, OverviewStats, TelemetryData};
+use crate::oidc::OidcClient;
 use crate::parser::LogParser;
 use crate::profile_auth;
+use crate::requirements::{Requirement, RequirementStatus};
 use crate::session_store::SessionStore;
+use crate::users::{self, CurrentUser, Role};
 
 /// Shared application state for Axum handlers.
 ///
@@ -35,6 +42,14 @@ pub struct WebAppState {
     pub sessions: Arc<SessionStore>,
     /// Argon2id hash of PROFILE_CREATION_PASS (None if env var not set).
     pub master_password_hash: Option<String>,
+    /// SHA256 of SUMMARY_API_TOKEN (None if env var not set).
+    pub summary_token_hash: Option<String>,
+    /// OpenID Connect sign-in (None unless the OIDC_* env vars are set).
+    pub oidc: Option<Arc<OidcClient>>,
+    /// Outgoing mail for the summary reports (None unless the SMTP_* env vars are set).
+    pub smtp: Option<Arc<crate::email_report::SmtpConfig>>,
+    /// Public key browsers subscribe to Web Push with (None unless the VAPID_* env vars are set).
+    pub vapid_public_key: Option<String>,
 }
 
 impl WebAppState {
@@ -57,6 +72,12 @@ impl WebAppState {
             return Ok(existing.clone());
         }
         dbs.insert(profile.to_string(), db.clone());
+        drop(dbs);
+
+        // Resume imports queued before the last shutdown
+        if db.has_queued_imports().unwrap_or(false) {
+            spawn_import_queue(db.clone(), profile.to_string(), self.data_dir.clone());
+        }
         Ok(db)
     }
 
@@ -86,6 +107,13 @@ pub struct ProfileDb {
     pub db: Arc<Database>,
     pub profile: String,
     pub data_dir: PathBuf,
+    /// Audit log actor: "web" (or "web:<user>") plus the client address when
+    /// the proxy forwards it
+    pub actor: String,
+    /// Signed-in account, set by the role middleware in multi-user mode
+    pub user: Option<CurrentUser>,
+    /// Locale for messages, negotiated from `Accept-Language`
+    pub locale: &'static str,
 }
 
 impl ProfileDb {
@@ -103,6 +131,40 @@ impl ProfileDb {
     pub fn sync_path(&self) -> Option<PathBuf> {
         database::sync_path_for_profile(&self.profile)
     }
+
+    /// Record a data-modifying action in this profile's audit log.
+    pub fn audit(&self, action: &str, target: Option<&str>, details: serde_json::Value) {
+        self.db.audit(&self.actor, action, target, details);
+    }
+
+    /// Reject changes to a flight the caller may not modify. Only applies in
+    /// multi-user mode, where editors may only change flights they imported.
+    pub fn check_flight_access(&self, flight_id: i64) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+        let Some(user) = &self.user else {
+            return Ok(());
+        };
+        if user.role == Role::Admin {
+            return Ok(());
+        }
+        let owner = self.db.get_flight_owner(flight_id).map_err(|e| match e {
+            database::DatabaseError::FlightNotFound(_) => err_response(StatusCode::NOT_FOUND, e.to_string()),
+            _ => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get flight: {}", e)),
+        })?;
+        if user.can_modify(owner.as_deref()) {
+            Ok(())
+        } else {
+            Err(err_message(StatusCode::FORBIDDEN, self.locale, "flight.access_denied", &[]))
+        }
+    }
+
+    /// Mark a flight as imported by the signed-in account (multi-user mode)
+    fn record_owner(&self, flight_id: i64) {
+        if let Some(user) = &self.user {
+            if let Err(e) = self.db.set_flight_owner(flight_id, &user.username) {
+                log::warn!("Failed to record owner of flight {}: {}", flight_id, e);
+            }
+        }
+    }
 }
 
 #[axum::async_trait]
@@ -113,6 +175,8 @@ impl FromRequestParts<WebAppState> for ProfileDb {
         parts: &mut axum::http::request::Parts,
         state: &WebAppState,
     ) -> Result<Self, Self::Rejection> {
+        let locale = request_locale(&parts.headers);
+
         // 1. If X-Session is present, validate it — this takes priority
         let profile = if let Some(token) = parts
             .headers
@@ -121,12 +185,7 @@ impl FromRequestParts<WebAppState> for ProfileDb {
         {
             match state.sessions.validate(token) {
                 Some(p) => p,
-                None => {
-                    return Err(err_response(
-                        StatusCode::UNAUTHORIZED,
-                        "Session expired or invalid — please re-authenticate",
-                    ))
-                }
+                None => return Err(err_message(StatusCode::UNAUTHORIZED, locale, "auth.session_expired", &[])),
             }
         } else {
             // 2. Fall back to X-Profile header (or server default)
@@ -139,10 +198,7 @@ impl FromRequestParts<WebAppState> for ProfileDb {
 
             // 3. If this profile is password-protected, reject unauthenticated access
             if profile_auth::profile_is_protected(&state.data_dir, &p) {
-                return Err(err_response(
-                    StatusCode::UNAUTHORIZED,
-                    "This profile is password-protected — please authenticate first",
-                ));
+                return Err(err_message(StatusCode::UNAUTHORIZED, locale, "auth.profile_protected", &[]));
             }
             p
         };
@@ -150,19 +206,240 @@ impl FromRequestParts<WebAppState> for ProfileDb {
         let db = state
             .db_for_profile(&profile)
             .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        db.touch();
+
+        let client = client_addr(parts);
+        let user = parts.extensions.get::<CurrentUser>().cloned();
+        let who = match &user {
+            Some(u) => format!("web:{}", u.username),
+            None => "web".to_string(),
+        };
+        let actor = match client {
+            Some(addr) => format!("{} ({})", who, addr),
+            None => who,
+        };
 
         Ok(ProfileDb {
             db,
             profile,
             data_dir: state.data_dir.clone(),
+            actor,
+            user,
+            locale,
         })
     }
 }
 
+/// A `TRUSTED_PROXIES` entry: an address, or a network in CIDR notation
+#[derive(Debug, Clone, Copy)]
+struct TrustedProxy {
+    network: std::net::IpAddr,
+    prefix: u32,
+}
+
+impl TrustedProxy {
+    fn parse(entry: &str) -> Option<Self> {
+        let (addr, prefix) = match entry.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix.parse::<u32>().ok()?)),
+            None => (entry, None),
+        };
+        let network: std::net::IpAddr = addr.parse().ok()?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+        (prefix <= max).then_some(Self { network, prefix })
+    }
+
+    fn contains(&self, ip: std::net::IpAddr) -> bool {
+        use std::net::IpAddr;
+        match (self.network, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Proxies whose X-Real-IP / X-Forwarded-For headers are believed, from the
+/// comma-separated `TRUSTED_PROXIES` (default: loopback, i.e. the nginx in
+/// the Docker image)
+fn trusted_proxies() -> &'static [TrustedProxy] {
+    use std::sync::OnceLock;
+    static PROXIES: OnceLock<Vec<TrustedProxy>> = OnceLock::new();
+    PROXIES.get_or_init(|| {
+        let list = std::env::var("TRUSTED_PROXIES")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .unwrap_or_else(|| "127.0.0.0/8,::1".to_string());
+        list.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| {
+                let proxy = TrustedProxy::parse(entry);
+                if proxy.is_none() {
+                    log::warn!("Ignoring invalid TRUSTED_PROXIES entry '{}'", entry);
+                }
+                proxy
+            })
+            .collect()
+    })
+}
+
+/// Address of the client: the socket peer, or, when the peer is one of the
+/// `trusted_proxies`, the X-Real-IP / X-Forwarded-For header it set. Of
+/// X-Forwarded-For only the last entry is used: the one the proxy appended,
+/// while the entries before it come from the client.
+fn client_addr(parts: &axum::http::request::Parts) -> Option<String> {
+    let peer = parts
+        .extensions
+        .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
+        .map(|info| match info.0.ip() {
+            std::net::IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(std::net::IpAddr::V6(v6), std::net::IpAddr::V4),
+            ip => ip,
+        });
+    let via_proxy = peer.is_some_and(|ip| trusted_proxies().iter().any(|proxy| proxy.contains(ip)));
+    let forwarded = if via_proxy {
+        let header = |name: &str| parts.headers.get(name).and_then(|v| v.to_str().ok());
+        header("X-Real-IP")
+            .or_else(|| header("X-Forwarded-For").and_then(|v| v.rsplit(',').next()))
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    } else {
+        None
+    };
+    forwarded.or_else(|| peer.map(|ip| ip.to_string()))
+}
+
+/// The client address of a request (see `client_addr`), for login throttling
+struct ClientAddr(Option<String>);
+
+#[axum::async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ClientAddr {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut axum::http::request::Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(ClientAddr(client_addr(parts)))
+    }
+}
+
+/// A `LoginGuard` for a password check from this request; refused with 429
+/// while the subject or client is locked out
+fn login_guard<'a>(
+    state: &'a WebAppState,
+    kind: LoginKind,
+    subject: &str,
+    client: Option<String>,
+) -> Result<LoginGuard<'a>, (StatusCode, Json<ErrorResponse>)> {
+    let guard = LoginGuard::new(&state.sessions, &state.data_dir, kind, subject, client);
+    guard
+        .check()
+        .map_err(|remaining| err_response(StatusCode::TOO_MANY_REQUESTS, crate::login_audit::lockout_message(remaining)))?;
+    Ok(guard)
+}
+
+/// Check the master password (`PROFILE_CREATION_PASS`), throttled and audited
+fn check_master_password(
+    state: &WebAppState,
+    client: Option<String>,
+    master_password: Option<&str>,
+    missing: &str,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    let Some(ref hash) = state.master_password_hash else {
+        return Ok(());
+    };
+    let Some(mp) = master_password else {
+        return Err(err_response(StatusCode::FORBIDDEN, missing));
+    };
+    let guard = login_guard(state, LoginKind::MasterPassword, "", client)?;
+    if profile_auth::verify_password(mp, hash) {
+        guard.succeeded();
+        Ok(())
+    } else {
+        guard.failed("Invalid master password");
+        Err(err_response(StatusCode::FORBIDDEN, "Invalid master password"))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Roles (multi-user mode) — each route group requires a minimum role; the
+// signed-in account is passed on to handlers as a request extension.
+// ---------------------------------------------------------------------------
+
+/// Resolve the account behind `X-User-Session`. None in single-user mode.
+fn resolve_user(
+    headers: &axum::http::HeaderMap,
+    state: &WebAppState,
+) -> Result<Option<CurrentUser>, (StatusCode, Json<ErrorResponse>)> {
+    if !users::multi_user_enabled(&state.data_dir) {
+        return Ok(None);
+    }
+    let locale = request_locale(headers);
+    let username = headers
+        .get("X-User-Session")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|token| state.sessions.validate_user(token))
+        .ok_or_else(|| err_message(StatusCode::UNAUTHORIZED, locale, "auth.sign_in_required", &[]))?;
+    let role = users::get_role(&state.data_dir, &username)
+        .ok_or_else(|| err_message(StatusCode::UNAUTHORIZED, locale, "auth.account_removed", &[]))?;
+    Ok(Some(CurrentUser { username, role }))
+}
+
+async fn authorize(
+    state: WebAppState,
+    min_role: Role,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    if let Some(user) = resolve_user(req.headers(), &state)? {
+        if user.role < min_role {
+            return Err(err_message(
+                StatusCode::FORBIDDEN,
+                request_locale(req.headers()),
+                "auth.role_required",
+                &[("role", &min_role.as_str())],
+            ));
+        }
+        req.extensions_mut().insert(user);
+    }
+    Ok(next.run(req).await)
+}
+
+async fn require_viewer(
+    AxumState(state): AxumState<WebAppState>,
+    req: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    authorize(state, Role::Viewer, req, next).await
+}
+
+async fn require_editor(
+    AxumState(state): AxumState<WebAppState>,
+    req: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    authorize(state, Role::Editor, req, next).await
+}
+
+async fn require_admin(
+    AxumState(state): AxumState<WebAppState>,
+    req: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    authorize(state, Role::Admin, req, next).await
+}
+
 /// Standard error response
 #[derive(Serialize)]
 pub struct ErrorResponse {
     error: String,
+    /// Message code for catalog messages (see `messages`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<&'static str>,
 }
 
 fn err_response(status: StatusCode, msg: impl Into<String>) -> (StatusCode, Json<ErrorResponse>) {
@@ -170,10 +447,32 @@ fn err_response(status: StatusCode, msg: impl Into<String>) -> (StatusCode, Json
         status,
         Json(ErrorResponse {
             error: msg.into(),
+            code: None,
+        }),
+    )
+}
+
+/// Error response with a localized catalog message
+fn err_message(
+    status: StatusCode,
+    locale: &str,
+    code: &'static str,
+    args: &[(&str, &dyn std::fmt::Display)],
+) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        status,
+        Json(ErrorResponse {
+            error: messages::text(locale, code, args),
+            code: Some(code),
         }),
     )
 }
 
+/// Locale negotiated from the request's `Accept-Language` header
+fn request_locale(headers: &axum::http::HeaderMap) -> &'static str {
+    messages::negotiate(headers.get(axum::http::header::ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok()))
+}
+
 /// Compute SHA256 hash of a file
 fn compute_file_hash(path: &std::path::Path) -> Result<String, String> {
     LogParser::calculate_file_hash(path)
@@ -256,10 +555,17 @@ fn copy_uploaded_file_web(src_path: &std::path::PathBuf, dest_folder: &std::path
 // ROUTE HANDLERS
 // ============================================================================
 
-/// POST /api/import — Upload and import a DJI flight log file
+#[derive(Deserialize)]
+struct ImportQuery {
+    /// Settles a match with a flight already in the logbook (see `dedupe`)
+    on_conflict: Option<crate::dedupe::ConflictResolution>,
+}
+
+/// POST /api/import?on_conflict=skip|replace|keep_both — Upload and import a DJI flight log file
 async fn import_log(
     AxumState(_state): AxumState<WebAppState>,
     pdb: ProfileDb,
+    Query(query): Query<ImportQuery>,
     mut multipart: Multipart,
 ) -> Result<Json<ImportResult>, (StatusCode, Json<ErrorResponse>)> {
     // Read the uploaded file from multipart form data
@@ -278,13 +584,41 @@ async fn import_log(
         .await
         .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read file: {}", e)))?;
 
+    import_upload(&pdb, file_name, &data, query.on_conflict).await.map(Json)
+}
+
+/// Import one uploaded file (shared by `/api/import` and the import queue),
+/// reporting its progress to `/api/import_status`
+#[tracing::instrument(name = "import", skip_all, fields(profile = %pdb.profile, file = %file_name, bytes = data.len()))]
+async fn import_upload(
+    pdb: &ProfileDb,
+    file_name: String,
+    data: &[u8],
+    on_conflict: Option<crate::dedupe::ConflictResolution>,
+) -> Result<ImportResult, (StatusCode, Json<ErrorResponse>)> {
+    let tracker = crate::import_status::track(&pdb.db, &file_name);
+    let result = run_import_upload(pdb, &tracker, file_name, data, on_conflict).await;
+    match &result {
+        Ok(r) => tracker.finish(r.success, &r.message, r.flight_id),
+        Err((_, Json(e))) => tracker.finish(false, &e.error, None),
+    }
+    result
+}
+
+async fn run_import_upload(
+    pdb: &ProfileDb,
+    tracker: &crate::import_status::ImportTracker<'_>,
+    file_name: String,
+    data: &[u8],
+    on_conflict: Option<crate::dedupe::ConflictResolution>,
+) -> Result<ImportResult, (StatusCode, Json<ErrorResponse>)> {
     // Write to a temp file so the parser can read it
     let temp_dir = std::env::temp_dir().join("drone-logbook-uploads");
     std::fs::create_dir_all(&temp_dir)
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create temp dir: {}", e)))?;
 
     let temp_path = temp_dir.join(&file_name);
-    std::fs::write(&temp_path, &data)
+    std::fs::write(&temp_path, data)
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write temp file: {}", e)))?;
 
     let import_start = std::time::Instant::now();
@@ -331,24 +665,30 @@ async fn import_log(
             try_copy_file(file_hash.as_deref());
             // Clean up temp file
             let _ = std::fs::remove_file(&temp_path);
-            return Ok(Json(ImportResult {
+            return Ok(ImportResult {
                 success: false,
                 flight_id: None,
-                message: format!("This flight log has already been imported (matches: {})", matching_flight),
+                message: messages::text(pdb.locale, "import.already_imported", &[("flight", &matching_flight)]),
+                code: Some("import.already_imported".to_string()),
                 point_count: 0,
                 file_hash,
-            }));
+                report: None,
+                conflict: None,
+            });
         }
         Err(e) => {
             let _ = std::fs::remove_file(&temp_path);
             log::error!("Failed to parse log {}: {}", file_name, e);
-            return Ok(Json(ImportResult {
+            return Ok(ImportResult {
                 success: false,
                 flight_id: None,
-                message: format!("Failed to parse log: {}", e),
+                message: messages::text(pdb.locale, "import.parse_failed", &[("error", &e)]),
+                code: Some("import.parse_failed".to_string()),
                 point_count: 0,
                 file_hash: None,
-            }));
+                report: None,
+                conflict: None,
+            });
         }
     };
 
@@ -358,23 +698,31 @@ async fn import_log(
     // Clean up temp file
     let _ = std::fs::remove_file(&temp_path);
 
-    // Check for duplicate flight based on signature (drone_serial + battery_serial + start_time)
-    if let Some(matching_flight) = pdb.db.is_duplicate_flight(
-        parse_result.metadata.drone_serial.as_deref(),
-        parse_result.metadata.battery_serial.as_deref(),
-        parse_result.metadata.start_time,
-    ).unwrap_or(None) {
-        log::info!("Skipping duplicate flight (signature match): {} - matches flight '{}' in database", file_name, matching_flight);
-        return Ok(Json(ImportResult {
-            success: false,
-            flight_id: None,
-            message: format!("Duplicate flight: matches '{}' (same drone, battery, and start time)", matching_flight),
-            point_count: 0,
-            file_hash: parse_result.metadata.file_hash.clone(),
-        }));
-    }
+    // Check for a copy of a flight already in the logbook (same drone, start time and duration)
+    let replaces = match crate::dedupe::check(&pdb.db, &parse_result.metadata, on_conflict, pdb.locale) {
+        Ok(crate::dedupe::DuplicateCheck::Import { replaces }) => replaces,
+        Ok(crate::dedupe::DuplicateCheck::Skip { message, conflict }) => {
+            log::info!("Skipping duplicate flight: {} - {}", file_name, message);
+            return Ok(ImportResult {
+                success: false,
+                flight_id: None,
+                message,
+                code: Some("import.duplicate".to_string()),
+                point_count: 0,
+                file_hash: parse_result.metadata.file_hash.clone(),
+                report: None,
+                conflict: Some(conflict),
+            });
+        }
+        Err(e) => {
+            log::warn!("Duplicate check failed for {}: {}", file_name, e);
+            None
+        }
+    };
 
     // Insert flight metadata
+    tracker.stage(crate::import_status::ImportStage::Inserting);
+    let mut stages = crate::import_profile::StageTimer::start();
     let flight_id = pdb.db
         .insert_flight(&parse_result.metadata)
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to insert flight: {}", e)))?;
@@ -387,13 +735,16 @@ async fn import_log(
             if let Err(cleanup_err) = pdb.db.delete_flight(flight_id) {
                 log::error!("Failed to clean up flight {}: {}", flight_id, cleanup_err);
             }
-            return Ok(Json(ImportResult {
+            return Ok(ImportResult {
                 success: false,
                 flight_id: None,
-                message: format!("Failed to insert telemetry data: {}", e),
+                message: messages::text(pdb.locale, "import.insert_failed", &[("error", &e)]),
+                code: Some("import.insert_failed".to_string()),
                 point_count: 0,
                 file_hash: parse_result.metadata.file_hash.clone(),
-            }));
+                report: None,
+                conflict: None,
+            });
         }
     };
 
@@ -459,12 +810,37 @@ async fn import_log(
         }
     }
 
+    // Store the validation report (field coverage, record version, decryption path)
+    let report = parse_result.import_report(flight_id);
+    if let Err(e) = pdb.db.save_import_report(&report) {
+        log::warn!("Failed to save import report for flight {}: {}", flight_id, e);
+    }
+    let insert_ms = stages.lap();
+    tracker.stage(crate::import_status::ImportStage::PostProcessing);
+
+    // Run the post-import hook pipeline (anomaly detection, ...)
+    if let Err(e) = crate::hooks::run_pipeline(&pdb.db, flight_id, &config, crate::hooks::HookTrigger::Import) {
+        log::warn!("Post-import hooks failed for flight {}: {}", flight_id, e);
+    }
+    let post_process_ms = stages.lap();
+    crate::import_profile::record(&pdb.db, flight_id, &parse_result, insert_ms, post_process_ms);
+
+    // Upload to AirData / DroneLogbook.com in the background if enabled
+    crate::cloud_push::push_bytes_in_background(pdb.db.clone(), &config, flight_id, &file_name, data);
+
     // Restore any previously saved user customizations (display_name, notes, color, manual tags)
     if let Some(ref hash) = parse_result.metadata.file_hash {
         if let Err(e) = pdb.db.apply_saved_customizations(flight_id, hash) {
             log::warn!("Failed to restore customizations for flight {}: {}", flight_id, e);
         }
     }
+    pdb.db.finish_import(flight_id);
+    pdb.record_owner(flight_id);
+    if let Some(old_id) = replaces {
+        if let Err(e) = pdb.db.delete_flight(old_id) {
+            log::warn!("Failed to delete flight {} replaced by {}: {}", old_id, flight_id, e);
+        }
+    }
 
     log::info!(
         "Successfully imported flight {} with {} points in {:.1}s",
@@ -472,14 +848,22 @@ async fn import_log(
         point_count,
         import_start.elapsed().as_secs_f64()
     );
+    pdb.audit(
+        "import",
+        Some(&flight_id.to_string()),
+        serde_json::json!({ "file": file_name, "points": point_count, "replaced": replaces }),
+    );
 
-    Ok(Json(ImportResult {
+    Ok(ImportResult {
         success: true,
         flight_id: Some(flight_id),
-        message: format!("Successfully imported {} telemetry points", point_count),
+        message: messages::text(pdb.locale, "import.success", &[("points", &point_count)]),
+        code: Some("import.success".to_string()),
         point_count,
         file_hash: parse_result.metadata.file_hash.clone(),
-    }))
+        report: Some(report),
+        conflict: None,
+    })
 }
 
 /// Request payload for manual flight creation
@@ -487,8 +871,8 @@ async fn import_log(
 struct CreateManualFlightPayload {
     flight_title: Option<String>,
     aircraft_name: String,
-    drone_serial: String,
-    battery_serial: String,
+    drone_serial: Option<String>,
+    battery_serial: Option<String>,
     start_time: String, // ISO 8601 format
     duration_secs: f64,
     total_distance: Option<f64>,
@@ -511,11 +895,11 @@ async fn create_manual_flight(
     if payload.aircraft_name.trim().is_empty() {
         return Err(err_response(StatusCode::BAD_REQUEST, "Aircraft name is required"));
     }
-    if payload.drone_serial.trim().is_empty() {
-        return Err(err_response(StatusCode::BAD_REQUEST, "Drone serial is required"));
+    if !payload.duration_secs.is_finite() || payload.duration_secs <= 0.0 {
+        return Err(err_response(StatusCode::BAD_REQUEST, "Duration must be greater than zero"));
     }
-    if payload.battery_serial.trim().is_empty() {
-        return Err(err_response(StatusCode::BAD_REQUEST, "Battery serial is required"));
+    if !(-90.0..=90.0).contains(&payload.home_lat) || !(-180.0..=180.0).contains(&payload.home_lon) {
+        return Err(err_response(StatusCode::BAD_REQUEST, "Invalid takeoff location"));
     }
 
     // Parse the start time
@@ -541,9 +925,9 @@ async fn create_manual_flight(
         display_name,
         file_hash: None,
         drone_model: Some(format!("Manual Entry ({})", payload.aircraft_name)),
-        drone_serial: Some(payload.drone_serial.trim().to_uppercase()),
+        drone_serial: payload.drone_serial.as_deref().map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty()),
         aircraft_name: Some(payload.aircraft_name.clone()),
-        battery_serial: Some(payload.battery_serial.trim().to_uppercase()),
+        battery_serial: payload.battery_serial.as_deref().map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty()),
         start_time: Some(parsed_start_time),
         end_time: Some(end_time),
         duration_secs: Some(payload.duration_secs),
@@ -560,8 +944,9 @@ async fn create_manual_flight(
         battery_life: None,
     };
     pdb.db
-        .insert_flight(&metadata)
+        .insert_manual_flight(&metadata)
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to insert flight: {}", e)))?;
+    pdb.record_owner(flight_id);
 
     // Update notes if provided
     if let Some(notes_text) = &payload.notes {
@@ -600,75 +985,2273 @@ async fn create_manual_flight(
         }
     }
 
-    log::info!("Successfully created manual flight entry with ID: {}", flight_id);
+    pdb.db.finish_import(flight_id);
+    log::info!("Successfully created manual flight entry with ID: {}", flight_id);
+    pdb.audit(
+        "import",
+        Some(&flight_id.to_string()),
+        serde_json::json!({ "manual": true, "file": metadata.file_name }),
+    );
+
+    Ok(Json(ImportResult {
+        success: true,
+        flight_id: Some(flight_id),
+        message: messages::text(pdb.locale, "import.manual_created", &[]),
+        code: Some("import.manual_created".to_string()),
+        point_count: 0,
+        file_hash: None,
+        report: None,
+        conflict: None,
+    }))
+}
+
+/// Query parameters for DAT fusion
+#[derive(Deserialize)]
+struct FuseDatQuery {
+    flight_id: Option<i64>,
+}
+
+/// POST /api/flights/fuse_dat?flight_id=... — Fuse an uploaded aircraft DAT log (or DJI Assistant 2 CSV export) into a flight
+async fn fuse_dat_log(
+    pdb: ProfileDb,
+    Query(query): Query<FuseDatQuery>,
+    mut multipart: Multipart,
+) -> Result<Json<ImportResult>, (StatusCode, Json<ErrorResponse>)> {
+    match query.flight_id {
+        Some(id) => pdb.check_flight_access(id)?,
+        // The matching flight is only known after parsing
+        None if pdb.user.as_ref().is_some_and(|u| u.role < Role::Admin) => {
+            return Err(err_response(StatusCode::BAD_REQUEST, "Choose the flight to fuse the DAT log into"));
+        }
+        None => {}
+    }
+    let field = multipart
+        .next_field()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Multipart error: {}", e)))?
+        .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, "No file uploaded"))?;
+
+    // Keep the extension so Assistant 2 CSV exports are decoded as CSV
+    let is_csv = field
+        .file_name()
+        .is_some_and(|n| crate::formats::has_extension(std::path::Path::new(n), "csv"));
+
+    let data = field
+        .bytes()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read file: {}", e)))?;
+
+    let temp_path = std::env::temp_dir().join(format!(
+        "dji-logbook-fuse-{}.{}",
+        uuid::Uuid::new_v4(),
+        if is_csv { "csv" } else { "DAT" }
+    ));
+    std::fs::write(&temp_path, &data)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write temp file: {}", e)))?;
+
+    let file_hash = compute_file_hash(&temp_path).ok();
+    let result = crate::dat_parser::DatParser::fuse_into_flight(&pdb.db, &temp_path, query.flight_id);
+    let _ = std::fs::remove_file(&temp_path);
+
+    let (flight_id, point_count) = result
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to fuse DAT log: {}", e)))?;
+    pdb.audit("fuse_dat", Some(&flight_id.to_string()), serde_json::json!({ "points": point_count }));
+
+    Ok(Json(ImportResult {
+        success: true,
+        flight_id: Some(flight_id),
+        message: messages::text(pdb.locale, "import.dat_fused", &[("points", &point_count)]),
+        code: Some("import.dat_fused".to_string()),
+        point_count,
+        file_hash,
+        report: None,
+        conflict: None,
+    }))
+}
+
+/// GET /api/flights — List all flights. With `modified_since` (RFC 3339),
+/// only the flights imported, changed or deleted since then, as `FlightChanges`
+#[derive(Deserialize)]
+struct FlightsQuery {
+    /// Only return flights that exceeded a personal limit
+    exceeded_only: Option<bool>,
+    /// Delta sync: `syncedAt` of the previous response
+    modified_since: Option<String>,
+}
+
+async fn get_flights(
+    pdb: ProfileDb,
+    Query(params): Query<FlightsQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    use axum::response::IntoResponse;
+    let exceeded_only = params.exceeded_only.unwrap_or(false);
+    if let Some(since) = params.modified_since.as_deref() {
+        let since = chrono::DateTime::parse_from_rfc3339(since.trim())
+            .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Invalid modified_since: {}", e)))?
+            .with_timezone(&chrono::Utc);
+        let mut changes = pdb.db
+            .get_flight_changes(since)
+            .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get flight changes: {}", e)))?;
+        if exceeded_only {
+            changes.flights.retain(|f| !f.exceedances.is_empty());
+        }
+        return Ok(Json(changes).into_response());
+    }
+    let mut flights = pdb.db
+        .get_all_flights()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get flights: {}", e)))?;
+    if exceeded_only {
+        flights.retain(|f| !f.exceedances.is_empty());
+    }
+    Ok(Json(flights).into_response())
+}
+
+/// Per-deployment bounds on telemetry points per request, so small servers
+/// can cap query cost: `MAX_POINTS_DEFAULT` applies when a request gives no
+/// `max_points` (unset = full resolution), `MAX_POINTS_LIMIT` is the largest
+/// accepted value (unset = no limit)
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MaxPointsConfig {
+    pub default_max_points: Option<usize>,
+    pub max_points_limit: Option<usize>,
+}
+
+/// Read the max_points settings from the environment (once)
+pub(crate) fn max_points_config() -> MaxPointsConfig {
+    use std::sync::OnceLock;
+    static CONFIG: OnceLock<MaxPointsConfig> = OnceLock::new();
+    *CONFIG.get_or_init(|| {
+        let read = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .filter(|&n| n > 0)
+        };
+        let max_points_limit = read("MAX_POINTS_LIMIT");
+        let default_max_points = match (read("MAX_POINTS_DEFAULT"), max_points_limit) {
+            (Some(d), Some(limit)) => Some(d.min(limit)),
+            (None, Some(limit)) => Some(limit),
+            (d, None) => d,
+        };
+        log::info!(
+            "Telemetry max_points: default {:?}, limit {:?}",
+            default_max_points,
+            max_points_limit
+        );
+        MaxPointsConfig { default_max_points, max_points_limit }
+    })
+}
+
+/// Apply the deployment's default and limit to a requested `max_points`
+pub(crate) fn resolve_max_points(requested: Option<usize>) -> Result<Option<usize>, String> {
+    let config = max_points_config();
+    match (requested, config.max_points_limit) {
+        (None, _) => Ok(config.default_max_points),
+        (Some(0), Some(limit)) => Err(format!("max_points must be between 1 and {}", limit)),
+        (Some(n), Some(limit)) if n > limit => Err(format!(
+            "max_points {} exceeds this server's limit of {}",
+            n, limit
+        )),
+        (Some(n), _) => Ok(Some(n)),
+    }
+}
+
+/// GET /api/flight_data/limits — Get the deployment's max_points default and limit
+async fn get_max_points_config() -> Json<MaxPointsConfig> {
+    Json(max_points_config())
+}
+
+/// GET /api/flights/:id — Get flight data for visualization
+#[derive(Deserialize)]
+struct FlightDataQuery {
+    flight_id: i64,
+    max_points: Option<usize>,
+    /// `compact` for the quantized, delta-encoded telemetry format
+    encoding: Option<String>,
+    /// Apply home location anonymization (for exports)
+    anonymize: Option<bool>,
+}
+
+async fn get_flight_data(
+    pdb: ProfileDb,
+    Query(params): Query<FlightDataQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    use axum::response::IntoResponse;
+
+    let encoding = match params.encoding.as_deref() {
+        None | Some("") | Some("json") => "json",
+        Some("compact") => "compact",
+        Some(other) => {
+            return Err(err_response(
+                StatusCode::BAD_REQUEST,
+                format!("Unsupported encoding: {}", other),
+            ))
+        }
+    };
+
+    let mut flight = pdb.db
+        .get_flight_by_id(params.flight_id)
+        .map_err(|e| err_response(StatusCode::NOT_FOUND, format!("Flight not found: {}", e)))?;
+
+    let max_points = resolve_max_points(params.max_points)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    let known_point_count = flight.point_count.map(|c| c as i64);
+    let anonymize = params.anonymize.unwrap_or(false);
+
+    // Repeat opens are served from the on-disk cache while the flight is unchanged
+    let cache_entry = if anonymize || !crate::flight_data_cache::is_enabled() {
+        None
+    } else {
+        crate::flight_data_cache::CacheEntry::new(&pdb.db, &pdb.data_dir, &pdb.profile, &flight, max_points, encoding)
+            .map_err(|e| log::warn!("Flight data cache unavailable for flight {}: {}", params.flight_id, e))
+            .ok()
+    };
+    if let Some(body) = cache_entry.as_ref().and_then(|entry| entry.get()) {
+        return Ok(([(axum::http::header::CONTENT_TYPE, "application/json")], body).into_response());
+    }
+
+    let telemetry_records = pdb.db
+        .get_flight_telemetry(params.flight_id, max_points, known_point_count)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get telemetry: {}", e)))?;
+
+    // Break the series at recording gaps instead of drawing across them
+    let gaps = crate::gaps::for_flight(&pdb.db, params.flight_id);
+    let telemetry_records = crate::gaps::mark_in_records(telemetry_records, &gaps.gaps);
+
+    let mut telemetry = TelemetryData::from_records(&telemetry_records);
+    // Anonymized exports stay relative to takeoff: sea-level altitude narrows down the location
+    let altitude_reference = if anonymize {
+        crate::altitude::AltitudeReference::Takeoff
+    } else {
+        crate::altitude::apply_to_telemetry(&pdb.db, params.flight_id, &mut telemetry)
+    };
+    let mut track = telemetry.extract_track(2000);
+    if anonymize {
+        let privacy = crate::privacy::PrivacySettings::load(&pdb.config_path());
+        privacy.apply_to_flight(&mut flight);
+        privacy.apply_to_flight_data(&mut telemetry, &mut track);
+    }
+    telemetry.prune_empty_series();
+
+    // Left out of anonymized exports: they could be computed from positions,
+    // and truncation no longer lines up with the raw samples
+    let derived = if anonymize {
+        Vec::new()
+    } else {
+        crate::derived_metrics::series_for_flight(&pdb.db, &pdb.config_path(), params.flight_id, &telemetry_records)
+    };
+
+    // Get flight messages (tips and warnings)
+    let messages = pdb.db
+        .get_flight_messages(params.flight_id)
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to get messages for flight {}: {}", params.flight_id, e);
+            Vec::new()
+        });
+
+    let body = if encoding == "compact" {
+        serde_json::to_vec(&serde_json::json!({
+            "flight": flight,
+            "telemetry": crate::telemetry_encoding::encode_compact(&telemetry),
+            "track": track,
+            "messages": messages,
+            "derived": derived,
+            "gaps": gaps,
+        }))
+    } else {
+        serde_json::to_vec(&FlightDataResponse {
+            flight,
+            telemetry,
+            track,
+            messages,
+            derived,
+            gaps,
+            altitude_reference,
+        })
+    }
+    .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to serialize flight data: {}", e)))?;
+
+    if let Some(entry) = &cache_entry {
+        entry.put(&body);
+    }
+    Ok(([(axum::http::header::CONTENT_TYPE, "application/json")], body).into_response())
+}
+
+#[derive(Deserialize)]
+struct TopFlightsQuery {
+    metric: String,
+    limit: Option<usize>,
+}
+
+/// GET /api/flights/top — Get the top flights by a metric (default limit 10, at most 100)
+async fn get_top_flights(
+    pdb: ProfileDb,
+    Query(params): Query<TopFlightsQuery>,
+) -> Result<Json<Vec<crate::models::RankedFlight>>, (StatusCode, Json<ErrorResponse>)> {
+    let metric = crate::models::TopFlightMetric::parse(&params.metric)
+        .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, format!("Unknown metric: {}", params.metric)))?;
+    let flights = pdb.db
+        .get_top_flights(metric, params.limit.unwrap_or(10).clamp(1, 100))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get top flights: {}", e)))?;
+    Ok(Json(flights))
+}
+
+#[derive(Deserialize)]
+struct HistogramQuery {
+    metric: String,
+    flight_id: Option<i64>,
+    bin_width: Option<f64>,
+}
+
+/// GET /api/histogram?metric=height|speed&flight_id=&bin_width= — Time spent per band, for one flight or all
+async fn get_histogram(
+    pdb: ProfileDb,
+    Query(params): Query<HistogramQuery>,
+) -> Result<Json<crate::models::Histogram>, (StatusCode, Json<ErrorResponse>)> {
+    let metric = crate::models::HistogramMetric::parse(&params.metric)
+        .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, format!("Unknown metric: {}", params.metric)))?;
+    let bin_width = metric
+        .bin_width(params.bin_width)
+        .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, "bin_width must be at least 0.1".to_string()))?;
+    let histogram = pdb.db
+        .get_histogram(metric, params.flight_id, bin_width)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get histogram: {}", e)))?;
+    Ok(Json(histogram))
+}
+
+/// GET /api/overview — Get overview statistics
+async fn get_overview_stats(
+    pdb: ProfileDb,
+) -> Result<Json<OverviewStats>, (StatusCode, Json<ErrorResponse>)> {
+    let mut stats = pdb.db
+        .get_overview_stats()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get overview stats: {}", e)))?;
+    match crate::battery_retirement::load_recommendations(&pdb.db, &pdb.config_path()) {
+        Ok(recommendations) => stats.battery_retirement = recommendations,
+        Err(e) => log::warn!("Failed to assess battery retirement: {}", e),
+    }
+    Ok(Json(stats))
+}
+
+/// Hex SHA256 of a token, for comparing without keeping the plaintext
+fn sha256_hex(value: &str) -> String {
+    use sha2::{Sha256, Digest};
+    Sha256::digest(value.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct SummaryQuery {
+    token: Option<String>,
+    profile: Option<String>,
+}
+
+/// GET /api/summary — Headline numbers for dashboard widgets (Homepage, Dashy, ...)
+///
+/// With `SUMMARY_API_TOKEN` set, a matching `Authorization: Bearer <token>`
+/// header (or `?token=`) opens this endpoint only, even for password-protected
+/// profiles; the profile comes from `?profile=` / `X-Profile` or the server
+/// default. Without a token the usual session/profile (and, in multi-user
+/// mode, user session) rules apply.
+async fn get_summary(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<SummaryQuery>,
+    mut parts: axum::http::request::Parts,
+) -> Result<Json<crate::models::LogbookSummary>, (StatusCode, Json<ErrorResponse>)> {
+    let bearer = parts
+        .headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|v| v.trim().to_string());
+
+    let db = match bearer.or(params.token) {
+        Some(token) => {
+            let expected = state
+                .summary_token_hash
+                .as_deref()
+                .ok_or_else(|| err_response(StatusCode::UNAUTHORIZED, "Summary token access is not enabled"))?;
+            if sha256_hex(&token) != expected {
+                return Err(err_response(StatusCode::UNAUTHORIZED, "Invalid summary token"));
+            }
+            let profile = params
+                .profile
+                .or_else(|| {
+                    parts
+                        .headers
+                        .get("X-Profile")
+                        .and_then(|v| v.to_str().ok())
+                        .map(|s| s.to_string())
+                })
+                .unwrap_or_else(|| database::get_active_profile(&state.data_dir));
+            if !database::profile_exists(&state.data_dir, &profile) {
+                return Err(err_response(StatusCode::NOT_FOUND, format!("Profile '{}' not found", profile)));
+            }
+            state
+                .db_for_profile(&profile)
+                .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e))?
+        }
+        None => {
+            resolve_user(&parts.headers, &state)?;
+            ProfileDb::from_request_parts(&mut parts, &state).await?.db
+        }
+    };
+
+    let summary = db
+        .get_logbook_summary()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get summary: {}", e)))?;
+    Ok(Json(summary))
+}
+
+/// GET /api/flights/import_report — Get the validation report recorded when a flight was imported
+#[derive(Deserialize)]
+struct ImportReportQuery {
+    flight_id: i64,
+}
+
+async fn get_import_report(
+    pdb: ProfileDb,
+    Query(params): Query<ImportReportQuery>,
+) -> Result<Json<Option<ImportReport>>, (StatusCode, Json<ErrorResponse>)> {
+    let report = pdb.db
+        .get_import_report(params.flight_id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get import report: {}", e)))?;
+    Ok(Json(report))
+}
+
+/// GET /api/settings/limits — Get the personal altitude/range limits
+async fn get_exceedance_limits(
+    pdb: ProfileDb,
+) -> Result<Json<ExceedanceLimits>, (StatusCode, Json<ErrorResponse>)> {
+    let limits = pdb.db
+        .get_exceedance_limits()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get limits: {}", e)))?;
+    Ok(Json(limits))
+}
+
+/// POST /api/settings/limits — Save the personal limits and re-flag all flights
+async fn set_exceedance_limits(
+    pdb: ProfileDb,
+    Json(limits): Json<ExceedanceLimits>,
+) -> Result<Json<usize>, (StatusCode, Json<ErrorResponse>)> {
+    let count = pdb.db
+        .set_exceedance_limits(&limits)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save limits: {}", e)))?;
+    pdb.audit("settings_change", Some("exceedance_limits"), serde_json::json!(limits));
+    Ok(Json(count))
+}
+
+/// GET /api/settings/altitude_reference — Get the reference for charts, tracks and max altitude
+async fn get_altitude_reference(
+    pdb: ProfileDb,
+) -> Result<Json<crate::altitude::AltitudeReference>, (StatusCode, Json<ErrorResponse>)> {
+    Ok(Json(pdb.db.get_altitude_reference()))
+}
+
+#[derive(Deserialize)]
+struct AltitudeReferencePayload {
+    reference: crate::altitude::AltitudeReference,
+}
+
+/// POST /api/settings/altitude_reference — Set the altitude reference ("takeoff" or "msl")
+async fn set_altitude_reference(
+    pdb: ProfileDb,
+    Json(payload): Json<AltitudeReferencePayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db
+        .set_altitude_reference(payload.reference)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save altitude reference: {}", e)))?;
+    pdb.audit("settings_change", Some("altitude_reference"), serde_json::json!(payload.reference));
+    Ok(Json(true))
+}
+
+/// GET /api/settings/duration_rounding — Get the flight time rounding used in reports and exports
+async fn get_duration_rounding(
+    pdb: ProfileDb,
+) -> Result<Json<crate::duration_rounding::DurationRounding>, (StatusCode, Json<ErrorResponse>)> {
+    Ok(Json(pdb.db.get_duration_rounding()))
+}
+
+#[derive(Deserialize)]
+struct DurationRoundingPayload {
+    rounding: crate::duration_rounding::DurationRounding,
+}
+
+/// POST /api/settings/duration_rounding — Set the flight time rounding ("exact", "minute" or "tenths")
+async fn set_duration_rounding(
+    pdb: ProfileDb,
+    Json(payload): Json<DurationRoundingPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db
+        .set_duration_rounding(payload.rounding)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save duration rounding: {}", e)))?;
+    pdb.audit("settings_change", Some("duration_rounding"), serde_json::json!(payload.rounding));
+    Ok(Json(true))
+}
+
+/// GET /api/settings/units — Get the display units used in reports and exports
+async fn get_unit_settings(pdb: ProfileDb) -> Json<crate::units::UnitSettings> {
+    Json(crate::units::UnitSettings::load(&pdb.config_path()))
+}
+
+/// POST /api/settings/units — Save the display units (distance, altitude, speed, temperature)
+async fn set_unit_settings(
+    pdb: ProfileDb,
+    Json(settings): Json<crate::units::UnitSettings>,
+) -> Result<Json<crate::units::UnitSettings>, (StatusCode, Json<ErrorResponse>)> {
+    let saved = crate::units::save_settings(&pdb.config_path(), settings)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    pdb.audit("settings_change", Some("units"), serde_json::json!(saved));
+    Ok(Json(saved))
+}
+
+/// GET /api/flights/rth — Get the return-to-home events detected for a flight
+async fn get_rth_events(
+    pdb: ProfileDb,
+    Query(params): Query<ImportReportQuery>,
+) -> Result<Json<Vec<crate::rth::RthEvent>>, (StatusCode, Json<ErrorResponse>)> {
+    let events = pdb.db
+        .get_rth_events(params.flight_id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get RTH events: {}", e)))?;
+    Ok(Json(events))
+}
+
+/// GET /api/flights/null_columns — Get the telemetry columns that are null for every point of a flight
+async fn get_null_telemetry_columns(
+    pdb: ProfileDb,
+    Query(params): Query<ImportReportQuery>,
+) -> Result<Json<Vec<String>>, (StatusCode, Json<ErrorResponse>)> {
+    let columns = pdb.db
+        .null_telemetry_columns(params.flight_id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to analyze telemetry columns: {}", e)))?;
+    Ok(Json(columns))
+}
+
+#[derive(Deserialize)]
+struct FlightPreviewsQuery {
+    /// Comma-separated flight IDs; all flights when omitted
+    ids: Option<String>,
+}
+
+/// GET /api/flights/previews?ids=1,2,3 — Cached list previews (track polyline, sparklines, thumbnail)
+async fn get_flight_previews(
+    pdb: ProfileDb,
+    Query(params): Query<FlightPreviewsQuery>,
+) -> Result<Json<Vec<crate::models::FlightPreview>>, (StatusCode, Json<ErrorResponse>)> {
+    let ids: Option<Vec<i64>> = match params.ids.as_deref() {
+        Some(raw) => Some(
+            raw.split(',')
+                .filter(|s| !s.trim().is_empty())
+                .map(|s| s.trim().parse::<i64>())
+                .collect::<Result<_, _>>()
+                .map_err(|_| err_response(StatusCode::BAD_REQUEST, "Invalid flight ID list"))?,
+        ),
+        None => None,
+    };
+    let previews = pdb.db
+        .get_flight_previews(ids.as_deref())
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get flight previews: {}", e)))?;
+    Ok(Json(previews))
+}
+
+/// GET /api/requirements — List the currency/recency requirement rules
+async fn get_requirements(
+    pdb: ProfileDb,
+) -> Result<Json<Vec<Requirement>>, (StatusCode, Json<ErrorResponse>)> {
+    let requirements = pdb.db
+        .get_requirements()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get requirements: {}", e)))?;
+    Ok(Json(requirements))
+}
+
+/// POST /api/requirements — Create or update a requirement rule (empty id creates one)
+async fn save_requirement(
+    pdb: ProfileDb,
+    Json(requirement): Json<Requirement>,
+) -> Result<Json<Requirement>, (StatusCode, Json<ErrorResponse>)> {
+    if requirement.name.trim().is_empty() {
+        return Err(err_response(StatusCode::BAD_REQUEST, "Requirement name cannot be empty"));
+    }
+    if requirement.min_value <= 0.0 || requirement.window_days <= 0 {
+        return Err(err_response(StatusCode::BAD_REQUEST, "Requirement minimum and window must be positive"));
+    }
+    let saved = pdb.db
+        .save_requirement(&requirement)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save requirement: {}", e)))?;
+    Ok(Json(saved))
+}
+
+#[derive(Deserialize)]
+struct DeleteRequirementQuery {
+    id: String,
+}
+
+/// DELETE /api/requirements?id=... — Delete a requirement rule
+async fn delete_requirement(
+    pdb: ProfileDb,
+    Query(params): Query<DeleteRequirementQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db
+        .delete_requirement(&params.id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete requirement: {}", e)))?;
+    Ok(Json(true))
+}
+
+/// GET /api/requirements/status — Evaluate every requirement rule as of now
+async fn evaluate_requirements(
+    pdb: ProfileDb,
+) -> Result<Json<Vec<RequirementStatus>>, (StatusCode, Json<ErrorResponse>)> {
+    let requirements = pdb.db
+        .get_requirements()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get requirements: {}", e)))?;
+    let flights = pdb.db
+        .get_all_flights()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get flights: {}", e)))?;
+    let now = chrono::Utc::now();
+    Ok(Json(
+        requirements
+            .iter()
+            .map(|r| crate::requirements::evaluate(r, &flights, now))
+            .collect(),
+    ))
+}
+
+/// POST /api/attachments — Upload a document (multipart: `file`, optional `flight_id`, `description`)
+async fn add_attachment(
+    pdb: ProfileDb,
+    mut multipart: Multipart,
+) -> Result<Json<Attachment>, (StatusCode, Json<ErrorResponse>)> {
+    let mut file: Option<(String, Vec<u8>)> = None;
+    let mut flight_id: Option<i64> = None;
+    let mut description: Option<String> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Multipart error: {}", e)))?
+    {
+        match field.name().unwrap_or("") {
+            "flight_id" => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read flight_id: {}", e)))?;
+                if !text.trim().is_empty() {
+                    flight_id = Some(
+                        text.trim()
+                            .parse()
+                            .map_err(|_| err_response(StatusCode::BAD_REQUEST, "Invalid flight_id"))?,
+                    );
+                }
+            }
+            "description" => {
+                description = field.text().await.ok();
+            }
+            _ => {
+                let file_name = field.file_name().unwrap_or("attachment").to_string();
+                let data = field
+                    .bytes()
+                    .await
+                    .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read file: {}", e)))?;
+                file = Some((file_name, data.to_vec()));
+            }
+        }
+    }
+
+    let (file_name, data) = file.ok_or_else(|| err_response(StatusCode::BAD_REQUEST, "No file uploaded"))?;
+    if let Some(id) = flight_id {
+        pdb.check_flight_access(id)?;
+    }
+    let attachment = pdb.db
+        .add_attachment(flight_id, &file_name, &data, description.as_deref())
+        .map_err(|e| match e {
+            database::DatabaseError::FlightNotFound(_) => err_response(StatusCode::NOT_FOUND, e.to_string()),
+            _ => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to store attachment: {}", e)),
+        })?;
+    Ok(Json(attachment))
+}
+
+#[derive(Deserialize)]
+struct AttachmentsQuery {
+    flight_id: Option<i64>,
+}
+
+/// GET /api/attachments?flight_id=... — List a flight's attachments (all attachments without flight_id)
+async fn get_attachments(
+    pdb: ProfileDb,
+    Query(params): Query<AttachmentsQuery>,
+) -> Result<Json<Vec<Attachment>>, (StatusCode, Json<ErrorResponse>)> {
+    let attachments = pdb.db
+        .get_attachments(params.flight_id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get attachments: {}", e)))?;
+    Ok(Json(attachments))
+}
+
+#[derive(Deserialize)]
+struct AttachmentIdQuery {
+    id: String,
+}
+
+/// GET /api/attachments/download?id=... — Download a stored attachment
+async fn download_attachment(
+    pdb: ProfileDb,
+    Query(params): Query<AttachmentIdQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    use axum::body::Body;
+    use axum::response::IntoResponse;
+
+    let (attachment, path) = pdb.db
+        .get_attachment_file(&params.id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get attachment: {}", e)))?
+        .ok_or_else(|| err_response(StatusCode::NOT_FOUND, "Attachment not found"))?;
+
+    let file_bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read attachment file: {}", e)))?;
+
+    let filename = attachment.file_name.replace('"', "");
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, attachment.content_type),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        Body::from(file_bytes),
+    ).into_response())
+}
+
+/// DELETE /api/attachments?id=... — Delete an attachment and its stored file
+async fn delete_attachment(
+    pdb: ProfileDb,
+    Query(params): Query<AttachmentIdQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db
+        .delete_attachment(&params.id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete attachment: {}", e)))?;
+    Ok(Json(true))
+}
+
+/// GET /api/checklists/templates — List pre/post-flight checklist templates
+async fn get_checklist_templates(
+    pdb: ProfileDb,
+) -> Result<Json<Vec<ChecklistTemplate>>, (StatusCode, Json<ErrorResponse>)> {
+    let templates = pdb.db
+        .get_checklist_templates()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get checklist templates: {}", e)))?;
+    Ok(Json(templates))
+}
+
+/// POST /api/checklists/templates — Create or update a checklist template
+async fn save_checklist_template(
+    pdb: ProfileDb,
+    Json(template): Json<ChecklistTemplate>,
+) -> Result<Json<ChecklistTemplate>, (StatusCode, Json<ErrorResponse>)> {
+    if template.name.trim().is_empty() {
+        return Err(err_response(StatusCode::BAD_REQUEST, "Checklist name cannot be empty"));
+    }
+    if template.phase != "preflight" && template.phase != "postflight" {
+        return Err(err_response(StatusCode::BAD_REQUEST, "Checklist phase must be 'preflight' or 'postflight'"));
+    }
+    let saved = pdb.db
+        .save_checklist_template(&template)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save checklist template: {}", e)))?;
+    Ok(Json(saved))
+}
+
+#[derive(Deserialize)]
+struct ChecklistIdQuery {
+    id: String,
+}
+
+/// DELETE /api/checklists/templates?id=... — Delete a checklist template
+async fn delete_checklist_template(
+    pdb: ProfileDb,
+    Query(params): Query<ChecklistIdQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db
+        .delete_checklist_template(&params.id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete checklist template: {}", e)))?;
+    Ok(Json(true))
+}
+
+/// POST /api/checklists/records — Record a completed checklist against a flight or session
+async fn record_checklist(
+    pdb: ProfileDb,
+    Json(record): Json<ChecklistRecord>,
+) -> Result<Json<ChecklistRecord>, (StatusCode, Json<ErrorResponse>)> {
+    if record.flight_id.is_none() && record.session.as_deref().map_or(true, |s| s.trim().is_empty()) {
+        return Err(err_response(StatusCode::BAD_REQUEST, "A checklist must be attached to a flight or a session"));
+    }
+    let saved = pdb.db
+        .record_checklist(&record)
+        .map_err(|e| match e {
+            database::DatabaseError::FlightNotFound(_) => err_response(StatusCode::NOT_FOUND, e.to_string()),
+            _ => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to record checklist: {}", e)),
+        })?;
+    Ok(Json(saved))
+}
+
+#[derive(Deserialize)]
+struct ChecklistRecordsQuery {
+    flight_id: Option<i64>,
+}
+
+/// GET /api/checklists/records?flight_id=... — Completed checklists of a flight (all without flight_id)
+async fn get_checklist_records(
+    pdb: ProfileDb,
+    Query(params): Query<ChecklistRecordsQuery>,
+) -> Result<Json<Vec<ChecklistRecord>>, (StatusCode, Json<ErrorResponse>)> {
+    let records = pdb.db
+        .get_checklist_records(params.flight_id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get checklists: {}", e)))?;
+    Ok(Json(records))
+}
+
+/// DELETE /api/checklists/records?id=... — Delete a completed checklist
+async fn delete_checklist_record(
+    pdb: ProfileDb,
+    Query(params): Query<ChecklistIdQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db
+        .delete_checklist_record(&params.id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete checklist: {}", e)))?;
+    Ok(Json(true))
+}
+
+#[derive(Deserialize)]
+struct StatsExportQuery {
+    format: Option<String>,
+    table: Option<String>,
+}
+
+/// GET /api/stats/export?format=json|csv&table=... — Download statistics
+/// (JSON contains every table; CSV exports one of overview, monthly, batteries, drones)
+async fn export_stats(
+    pdb: ProfileDb,
+    Query(params): Query<StatsExportQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    use axum::response::IntoResponse;
+    use crate::stats_export::{StatsExport, StatsTable};
+
+    let export = StatsExport::collect(&pdb.db)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to collect statistics: {}", e)))?;
+    let date = chrono::Local::now().format("%Y-%m-%d");
+
+    let (content_type, filename, body) = match params.format.as_deref().unwrap_or("json").to_ascii_lowercase().as_str() {
+        "json" => {
+            let body = serde_json::to_string_pretty(&export)
+                .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to serialize statistics: {}", e)))?;
+            ("application/json", format!("{}_flight_stats.json", date), body)
+        }
+        "csv" => {
+            let name = params.table.as_deref().unwrap_or("overview");
+            let table = StatsTable::parse(name)
+                .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, format!("Unknown statistics table: {}", name)))?;
+            ("text/csv; charset=utf-8", format!("{}_flight_stats_{}.csv", date, table.as_str()), export.to_csv(table))
+        }
+        other => return Err(err_response(StatusCode::BAD_REQUEST, format!("Unsupported export format: {}", other))),
+    };
+
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, content_type.to_string()),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        body,
+    ).into_response())
+}
+
+/// GET /api/export_templates — Saved CSV export templates
+async fn get_export_templates(pdb: ProfileDb) -> Json<Vec<crate::export_templates::CsvTemplate>> {
+    Json(crate::export_templates::load_templates(&pdb.config_path()))
+}
+
+/// POST /api/export_templates — Create or update a CSV export template (empty id creates one)
+async fn save_export_template(
+    pdb: ProfileDb,
+    Json(template): Json<crate::export_templates::CsvTemplate>,
+) -> Result<Json<crate::export_templates::CsvTemplate>, (StatusCode, Json<ErrorResponse>)> {
+    use crate::export_templates::TemplateError;
+
+    let saved = crate::export_templates::save_template(&pdb.config_path(), template).map_err(|e| match e {
+        TemplateError::Io(_) => err_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        _ => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+    })?;
+    pdb.audit("settings_change", Some("csv_export_templates"), serde_json::json!({ "saved": saved.id }));
+    Ok(Json(saved))
+}
+
+#[derive(Deserialize)]
+struct DeleteExportTemplateQuery {
+    id: String,
+}
+
+/// DELETE /api/export_templates?id=... — Delete a CSV export template
+async fn delete_export_template(
+    pdb: ProfileDb,
+    Query(params): Query<DeleteExportTemplateQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    let deleted = crate::export_templates::delete_template(&pdb.config_path(), &params.id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if deleted {
+        pdb.audit("settings_change", Some("csv_export_templates"), serde_json::json!({ "deleted": params.id }));
+    }
+    Ok(Json(deleted))
+}
+
+#[derive(Deserialize)]
+struct TemplateExportQuery {
+    template_id: String,
+    /// Comma-separated flight ids; all flights when omitted
+    flight_ids: Option<String>,
+}
+
+/// GET /api/export_templates/render?template_id=...&flight_ids=1,2 — Download
+/// flights as CSV using a saved template
+async fn export_with_template(
+    pdb: ProfileDb,
+    Query(params): Query<TemplateExportQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    use axum::response::IntoResponse;
+
+    let template = crate::export_templates::find_template(&pdb.config_path(), &params.template_id)
+        .map_err(|e| err_response(StatusCode::NOT_FOUND, e.to_string()))?;
+    let mut flights = pdb.db
+        .get_all_flights()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get flights: {}", e)))?;
+    if let Some(ids) = params.flight_ids.as_deref().filter(|s| !s.trim().is_empty()) {
+        let ids: Vec<i64> = ids.split(',').filter_map(|s| s.trim().parse().ok()).collect();
+        flights.retain(|f| ids.contains(&f.id));
+    }
+    let csv = template
+        .render(&flights, pdb.db.get_duration_rounding())
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e.to_string()))?;
+    let filename = format!("{}_{}.csv", chrono::Local::now().format("%Y-%m-%d"), template.name.replace(|c: char| !c.is_ascii_alphanumeric(), "_"));
+
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        csv,
+    ).into_response())
+}
+
+#[derive(Deserialize)]
+struct ActivityCalendarQuery {
+    from: Option<String>,
+    to: Option<String>,
+}
+
+/// GET /api/activity/calendar?from=...&to=... — Flights per day over all years (or the range)
+async fn get_activity_calendar(
+    pdb: ProfileDb,
+    Query(params): Query<ActivityCalendarQuery>,
+) -> Result<Json<Vec<crate::models::ActivityDay>>, (StatusCode, Json<ErrorResponse>)> {
+    for date in [&params.from, &params.to].into_iter().flatten() {
+        if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+            return Err(err_response(StatusCode::BAD_REQUEST, format!("Invalid date: {}", date)));
+        }
+    }
+    let days = pdb.db
+        .get_activity_calendar(params.from.as_deref(), params.to.as_deref())
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get activity calendar: {}", e)))?;
+    Ok(Json(days))
+}
+
+/// GET /api/activity/stats — Flying streaks, days since last flight, busiest day/week
+async fn get_activity_stats(
+    pdb: ProfileDb,
+) -> Result<Json<crate::models::ActivityStats>, (StatusCode, Json<ErrorResponse>)> {
+    let stats = pdb.db
+        .get_activity_stats()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get activity stats: {}", e)))?;
+    Ok(Json(stats))
+}
+
+#[derive(Deserialize)]
+struct YearReviewQuery {
+    year: i32,
+}
+
+/// GET /api/year_review?year=... — Summary of one calendar year for the recap card
+async fn get_year_review(
+    pdb: ProfileDb,
+    Query(params): Query<YearReviewQuery>,
+) -> Result<Json<crate::year_review::YearReview>, (StatusCode, Json<ErrorResponse>)> {
+    let flights = pdb.db
+        .get_all_flights()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get flights: {}", e)))?;
+    Ok(Json(crate::year_review::build_year_review(params.year, &flights)))
+}
+
+#[derive(Deserialize)]
+struct RenderFlightQuery {
+    flight_id: i64,
+    format: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+/// GET /api/flights/render?flight_id=...&format=png|svg&width=...&height=... — Static track image
+async fn render_flight_image(
+    pdb: ProfileDb,
+    Query(params): Query<RenderFlightQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    use crate::render::{self, ImageFormat, RenderOptions};
+
+    let format = params.format.as_deref().unwrap_or("png");
+    let format = ImageFormat::parse(format)
+        .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, format!("Unsupported image format: {}", format)))?;
+    let track = render::flight_track(&pdb.db, params.flight_id)
+        .map_err(|e| err_response(StatusCode::NOT_FOUND, format!("Failed to load flight track: {}", e)))?;
+    let opts = RenderOptions::sized(params.width.unwrap_or(640), params.height.unwrap_or(400));
+
+    Ok((
+        [(axum::http::header::CONTENT_TYPE, format.content_type().to_string())],
+        render::render_track(&track, format, &opts),
+    ).into_response())
+}
+
+#[derive(Deserialize)]
+struct FlightBundleQuery {
+    flight_id: i64,
+}
+
+/// GET /api/flights/bundle?flight_id=... — Zip with everything about one flight
+async fn export_flight_bundle(
+    pdb: ProfileDb,
+    Query(params): Query<FlightBundleQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    use axum::response::IntoResponse;
+
+    let config: serde_json::Value = std::fs::read_to_string(pdb.config_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    let upload_folder = config
+        .get("uploaded_files_path")
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| pdb.default_upload_folder());
+
+    let privacy = crate::privacy::PrivacySettings::from_config(&config);
+    let units = crate::units::UnitSettings::from_config(&config);
+    let bundle = crate::bundle::export_flight_bundle(&pdb.db, params.flight_id, Some(&upload_folder), &privacy, &units)
+        .await
+        .map_err(|e| match e {
+            crate::bundle::BundleError::Database(database::DatabaseError::FlightNotFound(_)) => {
+                err_response(StatusCode::NOT_FOUND, e.to_string())
+            }
+            _ => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to build flight bundle: {}", e)),
+        })?;
+
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, "application/zip".to_string()),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", bundle.file_name)),
+        ],
+        bundle.bytes,
+    ).into_response())
+}
+
+#[derive(Deserialize)]
+struct ChaseQuery {
+    flight_id: i64,
+    other_flight_id: i64,
+    format: crate::chase_export::ChaseFormat,
+}
+
+/// GET /api/flights/chase?flight_id=...&other_flight_id=...&format=kml|czml —
+/// Two flights flown at the same time, time-aligned in one KML or CZML file
+async fn export_chase(
+    pdb: ProfileDb,
+    Query(params): Query<ChaseQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    use axum::response::IntoResponse;
+    use crate::chase_export::ChaseError;
+
+    let privacy = crate::privacy::PrivacySettings::load(&pdb.config_path());
+    let export = crate::chase_export::export_chase(&pdb.db, [params.flight_id, params.other_flight_id], params.format, &privacy)
+        .map_err(|e| match e {
+            ChaseError::Database(database::DatabaseError::FlightNotFound(_)) => err_response(StatusCode::NOT_FOUND, e.to_string()),
+            ChaseError::Database(_) => err_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+            ChaseError::SameFlight | ChaseError::NoTimedTrack(_) | ChaseError::NoOverlap => {
+                err_response(StatusCode::BAD_REQUEST, e.to_string())
+            }
+        })?;
+
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, export.content_type.to_string()),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", export.file_name)),
+        ],
+        export.bytes,
+    ).into_response())
+}
+
+/// POST /api/graphql — GraphQL queries over flights, telemetry, stats, batteries and tags
+async fn graphql_handler(
+    pdb: ProfileDb,
+    req: async_graphql_axum::GraphQLRequest,
+) -> async_graphql_axum::GraphQLResponse {
+    crate::graphql::schema()
+        .execute(req.into_inner().data(pdb.db))
+        .await
+        .into()
+}
+
+/// GET /api/privacy/settings — Home location anonymization settings
+async fn get_privacy_settings(pdb: ProfileDb) -> Json<crate::privacy::PrivacySettings> {
+    Json(crate::privacy::PrivacySettings::load(&pdb.config_path()))
+}
+
+/// POST /api/privacy/settings — Save the home location anonymization settings
+async fn set_privacy_settings(
+    pdb: ProfileDb,
+    Json(settings): Json<crate::privacy::PrivacySettings>,
+) -> Result<Json<crate::privacy::PrivacySettings>, (StatusCode, Json<ErrorResponse>)> {
+    let saved = crate::privacy::save_settings(&pdb.config_path(), settings)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    pdb.audit(
+        "settings_change",
+        Some("privacy"),
+        serde_json::json!({ "enabled": saved.enabled, "mode": saved.mode, "zones": saved.zones.len() }),
+    );
+    Ok(Json(saved))
+}
+
+/// GET /api/filename_rules — Rules setting tags, pilot, job and notes from file names at import
+async fn get_filename_rules(pdb: ProfileDb) -> Json<Vec<crate::filename_rules::FilenameRule>> {
+    Json(crate::filename_rules::load(&pdb.config_path()))
+}
+
+/// POST /api/filename_rules — Replace the filename rules
+async fn set_filename_rules(
+    pdb: ProfileDb,
+    Json(rules): Json<Vec<crate::filename_rules::FilenameRule>>,
+) -> Result<Json<Vec<crate::filename_rules::FilenameRule>>, (StatusCode, Json<ErrorResponse>)> {
+    let saved = crate::filename_rules::save_rules(&pdb.config_path(), rules)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    pdb.audit("settings_change", Some("filename_rules"), serde_json::json!({ "rules": saved.len() }));
+    Ok(Json(saved))
+}
+
+/// GET /api/derived_metrics — User-defined telemetry columns
+async fn get_derived_metrics(pdb: ProfileDb) -> Json<Vec<crate::derived_metrics::DerivedMetric>> {
+    Json(crate::derived_metrics::load(&pdb.config_path()))
+}
+
+/// POST /api/derived_metrics — Replace the derived column definitions
+async fn set_derived_metrics(
+    pdb: ProfileDb,
+    Json(metrics): Json<Vec<crate::derived_metrics::DerivedMetric>>,
+) -> Result<Json<Vec<crate::derived_metrics::DerivedMetric>>, (StatusCode, Json<ErrorResponse>)> {
+    let saved = crate::derived_metrics::save_settings(&pdb.config_path(), &pdb.db, metrics)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    pdb.audit(
+        "settings_change",
+        Some("derived_metrics"),
+        serde_json::json!({ "metrics": saved.iter().map(|m| &m.name).collect::<Vec<_>>() }),
+    );
+    Ok(Json(saved))
+}
+
+#[derive(Deserialize)]
+struct PreflightQuery {
+    lat: f64,
+    lon: f64,
+    /// RFC 3339; now when omitted
+    time: Option<String>,
+}
+
+/// GET /api/preflight?lat=&lon=&time= — Sun times, forecast, airspace and zone rules for a planned flight
+async fn get_preflight_briefing(
+    pdb: ProfileDb,
+    Query(params): Query<PreflightQuery>,
+) -> Result<Json<crate::preflight::PreflightReport>, (StatusCode, Json<ErrorResponse>)> {
+    let time = match params.time.as_deref() {
+        Some(t) => chrono::DateTime::parse_from_rfc3339(t)
+            .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Invalid time '{}': {}", t, e)))?
+            .with_timezone(&chrono::Utc),
+        None => chrono::Utc::now(),
+    };
+    let report = crate::preflight::briefing(&pdb.config_path(), params.lat, params.lon, time)
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    Ok(Json(report))
+}
+
+/// GET /api/preflight/settings — Zone rules and whether an OpenAIP key is stored (the key is not returned)
+async fn get_preflight_settings(pdb: ProfileDb) -> Json<crate::preflight::PreflightSettings> {
+    Json(crate::preflight::PreflightSettings::load(&pdb.config_path()))
+}
+
+/// POST /api/preflight/settings — Save the zone rules (empty openaipApiKey keeps the stored one, "-" removes it)
+async fn set_preflight_settings(
+    pdb: ProfileDb,
+    Json(settings): Json<crate::preflight::PreflightSettings>,
+) -> Result<Json<crate::preflight::PreflightSettings>, (StatusCode, Json<ErrorResponse>)> {
+    let saved = crate::preflight::save_settings(&pdb.config_path(), settings)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    pdb.audit(
+        "settings_change",
+        Some("preflight"),
+        serde_json::json!({ "zones": saved.zones.len(), "hasOpenaipApiKey": saved.has_openaip_api_key }),
+    );
+    Ok(Json(saved))
+}
+
+/// GET /api/email_report/settings — Summary email settings, and whether SMTP is configured
+async fn get_email_report_settings(
+    AxumState(state): AxumState<WebAppState>,
+    pdb: ProfileDb,
+) -> Json<serde_json::Value> {
+    let settings = crate::email_report::EmailReportSettings::load(&pdb.config_path());
+    Json(serde_json::json!({ "settings": settings, "smtpConfigured": state.smtp.is_some() }))
+}
+
+/// POST /api/email_report/settings — Save the summary email settings
+async fn set_email_report_settings(
+    pdb: ProfileDb,
+    Json(settings): Json<crate::email_report::EmailReportSettings>,
+) -> Result<Json<crate::email_report::EmailReportSettings>, (StatusCode, Json<ErrorResponse>)> {
+    let saved = crate::email_report::save_settings(&pdb.config_path(), settings)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    pdb.audit(
+        "settings_change",
+        Some("email_report"),
+        serde_json::json!({ "enabled": saved.enabled, "frequency": saved.frequency, "recipients": saved.recipients }),
+    );
+    Ok(Json(saved))
+}
+
+/// GET /api/notifications/settings — Which events are notified, and the VAPID public key for Web Push
+async fn get_notification_settings(
+    AxumState(state): AxumState<WebAppState>,
+    pdb: ProfileDb,
+) -> Json<serde_json::Value> {
+    let settings = crate::notifications::NotificationSettings::load(&pdb.config_path());
+    Json(serde_json::json!({ "settings": settings, "vapidPublicKey": state.vapid_public_key }))
+}
+
+/// POST /api/notifications/settings — Switch notifications for background events on or off
+async fn set_notification_settings(
+    pdb: ProfileDb,
+    Json(settings): Json<crate::notifications::NotificationSettings>,
+) -> Result<Json<crate::notifications::NotificationSettings>, (StatusCode, Json<ErrorResponse>)> {
+    let saved = crate::notifications::save_settings(&pdb.config_path(), settings)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    pdb.audit("settings_change", Some("notifications"), serde_json::json!(saved));
+    Ok(Json(saved))
+}
+
+/// POST /api/notifications/subscribe — Register this browser's push subscription with the profile
+async fn subscribe_push(
+    AxumState(state): AxumState<WebAppState>,
+    pdb: ProfileDb,
+    Json(subscription): Json<crate::notifications::PushSubscription>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    if state.vapid_public_key.is_none() {
+        return Err(err_response(StatusCode::SERVICE_UNAVAILABLE, "Web Push is not configured (set VAPID_PUBLIC_KEY and VAPID_PRIVATE_KEY)"));
+    }
+    if !subscription.endpoint.starts_with("https://") {
+        return Err(err_response(StatusCode::BAD_REQUEST, "Push endpoint must be an https URL"));
+    }
+    pdb.db
+        .add_push_subscription(&subscription, pdb.user.as_ref().map(|u| u.username.as_str()))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save subscription: {}", e)))?;
+    crate::web_push::clear_expired(&subscription.endpoint);
+    Ok(Json(serde_json::json!({ "subscribed": true })))
+}
+
+#[derive(Deserialize)]
+struct UnsubscribePayload {
+    endpoint: String,
+}
+
+/// POST /api/notifications/unsubscribe — Stop sending notifications to a browser
+async fn unsubscribe_push(
+    pdb: ProfileDb,
+    Json(payload): Json<UnsubscribePayload>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let removed = pdb.db
+        .remove_push_subscriptions(&[payload.endpoint])
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to remove subscription: {}", e)))?;
+    Ok(Json(serde_json::json!({ "removed": removed })))
+}
+
+/// POST /api/email_report/send — Send the summary email for the last period now
+async fn send_email_report(
+    AxumState(state): AxumState<WebAppState>,
+    pdb: ProfileDb,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let smtp = state.smtp.clone().ok_or_else(|| {
+        err_response(StatusCode::BAD_REQUEST, crate::email_report::EmailError::NotConfigured.to_string())
+    })?;
+    let settings = crate::email_report::EmailReportSettings::load(&pdb.config_path());
+    let units = crate::units::UnitSettings::load(&pdb.config_path());
+    let (subject, body) =
+        crate::email_report::build_report(&pdb.db, &pdb.profile, settings.frequency, &units, chrono::Utc::now().date_naive())
+            .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    smtp.send(&settings.recipients, &subject, body).await.map_err(|e| {
+        let status = match e {
+            crate::email_report::EmailError::Smtp(_) => StatusCode::BAD_GATEWAY,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        err_response(status, e.to_string())
+    })?;
+    pdb.audit("email_report_sent", Some("email_report"), serde_json::json!({ "recipients": settings.recipients }));
+    Ok(Json(serde_json::json!({ "sent": true, "subject": subject })))
+}
+
+/// GET /api/sharing/settings — Where shared flight tracks are published (password not returned)
+async fn get_sharing_settings(pdb: ProfileDb) -> Json<crate::share::SharingSettings> {
+    let config: serde_json::Value = std::fs::read_to_string(pdb.config_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    Json(crate::share::SharingSettings::from_config(&config))
+}
+
+/// POST /api/sharing/settings — Save the sharing target (empty password keeps the stored one)
+async fn set_sharing_settings(
+    pdb: ProfileDb,
+    Json(settings): Json<crate::share::SharingSettings>,
+) -> Result<Json<crate::share::SharingSettings>, (StatusCode, Json<ErrorResponse>)> {
+    let saved = crate::share::save_settings(&pdb.config_path(), settings)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    pdb.audit(
+        "settings_change",
+        Some("sharing"),
+        serde_json::json!({ "target": saved.target, "url": saved.url, "publicUrl": saved.public_url }),
+    );
+    Ok(Json(saved))
+}
+
+#[derive(Deserialize)]
+struct ShareFlightPayload {
+    flight_id: i64,
+}
+
+/// POST /api/flights/share — Publish a flight's track as GPX and return the share URL
+async fn share_flight(
+    pdb: ProfileDb,
+    Json(payload): Json<ShareFlightPayload>,
+) -> Result<Json<crate::share::SharedFlight>, (StatusCode, Json<ErrorResponse>)> {
+    use crate::share::ShareError;
+
+    pdb.check_flight_access(payload.flight_id)?;
+    let config: serde_json::Value = std::fs::read_to_string(pdb.config_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    let shared = crate::share::share_flight(&pdb.db, &config, payload.flight_id, &pdb.actor)
+        .await
+        .map_err(|e| {
+            let status = match e {
+                ShareError::NotConfigured | ShareError::NoTrack => StatusCode::BAD_REQUEST,
+                ShareError::Database(database::DatabaseError::FlightNotFound(_)) => StatusCode::NOT_FOUND,
+                ShareError::Http(_) | ShareError::Rejected { .. } | ShareError::NoShareUrl => StatusCode::BAD_GATEWAY,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            err_response(status, e.to_string())
+        })?;
+    Ok(Json(shared))
+}
+
+/// GET /api/cloud_push/settings — AirData / DroneLogbook.com push settings (API key not returned)
+async fn get_cloud_push_settings(pdb: ProfileDb) -> Json<crate::cloud_push::CloudPushSettings> {
+    let config: serde_json::Value = std::fs::read_to_string(pdb.config_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    Json(crate::cloud_push::CloudPushSettings::from_config(&config))
+}
+
+/// POST /api/cloud_push/settings — Save the push settings (empty apiKey keeps the stored one)
+async fn set_cloud_push_settings(
+    pdb: ProfileDb,
+    Json(settings): Json<crate::cloud_push::CloudPushSettings>,
+) -> Result<Json<crate::cloud_push::CloudPushSettings>, (StatusCode, Json<ErrorResponse>)> {
+    let saved = crate::cloud_push::save_settings(&pdb.config_path(), settings)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    pdb.audit(
+        "settings_change",
+        Some("cloud_push"),
+        serde_json::json!({ "enabled": saved.enabled, "service": saved.service }),
+    );
+    Ok(Json(saved))
+}
+
+#[derive(Deserialize)]
+struct CloudPushFlightPayload {
+    flight_id: i64,
+}
+
+/// POST /api/cloud_push/flight — Upload a stored flight's original log to the cloud service
+async fn push_flight_to_cloud(
+    pdb: ProfileDb,
+    Json(payload): Json<CloudPushFlightPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    use crate::cloud_push::PushError;
+
+    pdb.check_flight_access(payload.flight_id)?;
+    let config: serde_json::Value = std::fs::read_to_string(pdb.config_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    let upload_folder = config
+        .get("uploaded_files_path")
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| pdb.default_upload_folder());
+    crate::cloud_push::push_flight(&pdb.db, &config, &upload_folder, payload.flight_id)
+        .await
+        .map_err(|e| {
+            let status = match e {
+                PushError::NotConfigured | PushError::UnsupportedFile(_) => StatusCode::BAD_REQUEST,
+                PushError::RawFileMissing | PushError::Database(database::DatabaseError::FlightNotFound(_)) => StatusCode::NOT_FOUND,
+                PushError::Http(_) | PushError::Rejected { .. } => StatusCode::BAD_GATEWAY,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            err_response(status, e.to_string())
+        })?;
+    Ok(Json(true))
+}
+
+#[derive(Deserialize)]
+struct FlightPlanQuery {
+    flight_id: i64,
+}
+
+/// POST /api/flights/plan?flight_id=... — Attach a planned mission (multipart `file`:
+/// Litchi mission CSV, DJI waypoint KMZ or KML), replacing any previous one
+async fn attach_flight_plan(
+    pdb: ProfileDb,
+    Query(params): Query<FlightPlanQuery>,
+    mut multipart: Multipart,
+) -> Result<Json<crate::mission_plan::FlightPlan>, (StatusCode, Json<ErrorResponse>)> {
+    use crate::mission_plan::PlanError;
+
+    pdb.check_flight_access(params.flight_id)?;
+
+    let field = multipart
+        .next_field()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Multipart error: {}", e)))?
+        .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, "No file uploaded"))?;
+    let file_name = field.file_name().unwrap_or("mission").to_string();
+    let data = field
+        .bytes()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read file: {}", e)))?;
+
+    let plan = crate::mission_plan::attach_plan(&pdb.db, params.flight_id, &file_name, &data).map_err(|e| {
+        let status = match e {
+            PlanError::Database(database::DatabaseError::FlightNotFound(_)) => StatusCode::NOT_FOUND,
+            PlanError::Database(_) | PlanError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        err_response(status, e.to_string())
+    })?;
+    pdb.audit(
+        "attach_flight_plan",
+        Some(&params.flight_id.to_string()),
+        serde_json::json!({ "file": file_name, "waypoints": plan.waypoints.len() }),
+    );
+    Ok(Json(plan))
+}
+
+/// POST /api/flights/manual_track?flight_id=... — Attach a GPX track (multipart
+/// `file`) to a manual flight, replacing any previous one
+async fn attach_manual_track(
+    pdb: ProfileDb,
+    Query(params): Query<FlightPlanQuery>,
+    mut multipart: Multipart,
+) -> Result<Json<crate::models::Flight>, (StatusCode, Json<ErrorResponse>)> {
+    use crate::manual_track::TrackError;
+
+    pdb.check_flight_access(params.flight_id)?;
+
+    let field = multipart
+        .next_field()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Multipart error: {}", e)))?
+        .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, "No file uploaded"))?;
+    let file_name = field.file_name().unwrap_or("track.gpx").to_string();
+    let data = field
+        .bytes()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read file: {}", e)))?;
+
+    let flight = crate::manual_track::attach_track(&pdb.db, params.flight_id, &data).map_err(|e| {
+        let status = match e {
+            TrackError::Database(database::DatabaseError::FlightNotFound(_)) => StatusCode::NOT_FOUND,
+            TrackError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        err_response(status, e.to_string())
+    })?;
+    pdb.audit(
+        "attach_manual_track",
+        Some(&params.flight_id.to_string()),
+        serde_json::json!({ "file": file_name, "points": flight.point_count }),
+    );
+    Ok(Json(flight))
+}
+
+/// GET /api/flights/plan?flight_id=... — Planned mission, actual track and deviation
+/// statistics (null when no plan is attached)
+async fn get_flight_plan(
+    pdb: ProfileDb,
+    Query(params): Query<FlightPlanQuery>,
+) -> Result<Json<Option<crate::mission_plan::PlanComparison>>, (StatusCode, Json<ErrorResponse>)> {
+    crate::mission_plan::load_comparison(&pdb.db, params.flight_id)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// DELETE /api/flights/plan?flight_id=... — Detach the planned mission from a flight
+async fn delete_flight_plan(
+    pdb: ProfileDb,
+    Query(params): Query<FlightPlanQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.check_flight_access(params.flight_id)?;
+    pdb.db
+        .delete_flight_plan(params.flight_id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete flight plan: {}", e)))?;
+    Ok(Json(true))
+}
+
+#[derive(Deserialize)]
+struct FleetQuery {
+    /// Recent utilization window (default 30 days)
+    window_days: Option<i64>,
+}
+
+/// GET /api/fleet?window_days=30 — Per-aircraft utilization and maintenance, battery pool health and pilot assignments
+async fn get_fleet_overview(
+    pdb: ProfileDb,
+    Query(params): Query<FleetQuery>,
+) -> Result<Json<crate::fleet::FleetOverview>, (StatusCode, Json<ErrorResponse>)> {
+    let window_days = params.window_days.unwrap_or(30).clamp(1, 3650);
+    let overview = crate::fleet::load_fleet_overview(&pdb.db, window_days)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get fleet overview: {}", e)))?;
+    Ok(Json(overview))
+}
+
+#[derive(Deserialize)]
+struct EnduranceQuery {
+    wind_kmh: Option<f64>,
+    temperature_c: Option<f64>,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    reserve_percent: Option<f64>,
+}
+
+/// GET /api/analytics/endurance?wind_kmh=&temperature_c=&lat=&lon=&reserve_percent= — Expected endurance per aircraft + battery under given or forecast conditions
+async fn get_endurance_benchmarks(
+    pdb: ProfileDb,
+    Query(params): Query<EnduranceQuery>,
+) -> Result<Json<crate::endurance::EnduranceReport>, (StatusCode, Json<ErrorResponse>)> {
+    let report = crate::endurance::report(
+        &pdb.db,
+        params.wind_kmh,
+        params.temperature_c,
+        params.lat.zip(params.lon),
+        params.reserve_percent,
+    )
+    .await
+    .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    Ok(Json(report))
+}
+
+/// GET /api/battery_retirement — Batteries past a retirement threshold, with the reasons
+async fn get_battery_retirement(
+    pdb: ProfileDb,
+) -> Result<Json<Vec<crate::battery_retirement::RetirementRecommendation>>, (StatusCode, Json<ErrorResponse>)> {
+    let recommendations = crate::battery_retirement::load_recommendations(&pdb.db, &pdb.config_path())
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to assess batteries: {}", e)))?;
+    Ok(Json(recommendations))
+}
+
+/// GET /api/battery_retirement/settings — Retirement thresholds and webhook URL
+async fn get_battery_retirement_settings(pdb: ProfileDb) -> Json<crate::battery_retirement::RetirementSettings> {
+    Json(crate::battery_retirement::RetirementSettings::load(&pdb.config_path()))
+}
+
+/// POST /api/battery_retirement/settings — Save the retirement thresholds (0 disables a check)
+async fn set_battery_retirement_settings(
+    pdb: ProfileDb,
+    Json(settings): Json<crate::battery_retirement::RetirementSettings>,
+) -> Result<Json<crate::battery_retirement::RetirementSettings>, (StatusCode, Json<ErrorResponse>)> {
+    let saved = crate::battery_retirement::save_settings(&pdb.config_path(), settings)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    pdb.audit(
+        "settings_change",
+        Some("battery_retirement"),
+        serde_json::json!({
+            "maxCycles": saved.max_cycles,
+            "minCapacityRetentionPct": saved.min_capacity_retention_pct,
+            "maxSagIncreasePct": saved.max_sag_increase_pct,
+            "maxAgeDays": saved.max_age_days,
+            "hasWebhook": saved.webhook_url.is_some(),
+        }),
+    );
+    Ok(Json(saved))
+}
+
+#[derive(Deserialize)]
+struct ImportDiagnosticsQuery {
+    limit: Option<usize>,
+}
+
+/// GET /api/diagnostics/imports — Per-stage import timings, by format, with the slowest and latest imports
+async fn get_import_diagnostics(
+    pdb: ProfileDb,
+    Query(params): Query<ImportDiagnosticsQuery>,
+) -> Result<Json<crate::import_profile::ImportDiagnostics>, (StatusCode, Json<ErrorResponse>)> {
+    let profiles = pdb.db.get_import_profiles()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get import profiles: {}", e)))?;
+    Ok(Json(crate::import_profile::summarize(profiles, params.limit.unwrap_or(20).clamp(1, 500))))
+}
+
+#[derive(Deserialize)]
+struct MaintenanceQuery {
+    serial: Option<String>,
+}
+
+/// GET /api/maintenance?serial=... — Maintenance log, newest first (optionally for one aircraft or battery)
+async fn get_maintenance_records(
+    pdb: ProfileDb,
+    Query(params): Query<MaintenanceQuery>,
+) -> Result<Json<Vec<crate::fleet::MaintenanceRecord>>, (StatusCode, Json<ErrorResponse>)> {
+    let records = pdb.db
+        .get_maintenance_records(params.serial.as_deref())
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get maintenance log: {}", e)))?;
+    Ok(Json(records))
+}
+
+/// POST /api/maintenance — Log maintenance on an aircraft or battery (empty id creates a record)
+async fn save_maintenance_record(
+    pdb: ProfileDb,
+    Json(record): Json<crate::fleet::MaintenanceRecord>,
+) -> Result<Json<crate::fleet::MaintenanceRecord>, (StatusCode, Json<ErrorResponse>)> {
+    crate::fleet::validate_maintenance(&record).map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    let saved = pdb.db
+        .save_maintenance_record(&record)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save maintenance record: {}", e)))?;
+    pdb.audit(
+        "save_maintenance",
+        Some(&saved.serial),
+        serde_json::json!({ "id": saved.id, "equipmentType": saved.equipment_type, "performedAt": saved.performed_at }),
+    );
+    Ok(Json(saved))
+}
+
+#[derive(Deserialize)]
+struct MaintenanceIdQuery {
+    id: String,
+}
+
+/// DELETE /api/maintenance?id=... — Delete a maintenance record
+async fn delete_maintenance_record(
+    pdb: ProfileDb,
+    Query(params): Query<MaintenanceIdQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db
+        .delete_maintenance_record(&params.id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete maintenance record: {}", e)))?;
+    pdb.audit("delete_maintenance", Some(&params.id), serde_json::Value::Null);
+    Ok(Json(true))
+}
+
+/// GET /api/airframe_seeds — Flights and time each aircraft flew before this logbook
+async fn get_airframe_seeds(pdb: ProfileDb) -> Result<Json<Vec<crate::fleet::AirframeSeed>>, (StatusCode, Json<ErrorResponse>)> {
+    let seeds = pdb.db
+        .get_airframe_seeds()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get airframe seeds: {}", e)))?;
+    Ok(Json(seeds))
+}
+
+/// POST /api/airframe_seeds — Set an aircraft's airframe seed (zero flights and time removes it)
+async fn set_airframe_seed(
+    pdb: ProfileDb,
+    Json(seed): Json<crate::fleet::AirframeSeed>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    crate::fleet::validate_airframe_seed(&seed).map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    pdb.db
+        .set_airframe_seed(&seed)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to set airframe seed: {}", e)))?;
+    pdb.audit(
+        "settings_change",
+        Some("airframe_seed"),
+        serde_json::json!({ "serial": seed.serial, "priorDurationSecs": seed.prior_duration_secs, "priorFlights": seed.prior_flights }),
+    );
+    Ok(Json(true))
+}
+
+/// GET /api/analytics/places — Hours, flights and last visit per named site or take-off cluster
+async fn get_place_stats(pdb: ProfileDb) -> Result<Json<Vec<crate::locations::PlaceStats>>, (StatusCode, Json<ErrorResponse>)> {
+    let places = crate::locations::places(&pdb.db)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get place stats: {}", e)))?;
+    Ok(Json(places))
+}
+
+/// GET /api/location_overrides — Location tag renames and named sites
+async fn get_location_overrides(pdb: ProfileDb) -> Result<Json<crate::locations::LocationOverrides>, (StatusCode, Json<ErrorResponse>)> {
+    let overrides = crate::locations::load(&pdb.db)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get location overrides: {}", e)))?;
+    Ok(Json(overrides))
+}
+
+/// POST /api/location_overrides — Replace the location tag renames and named sites
+async fn set_location_overrides(
+    pdb: ProfileDb,
+    Json(overrides): Json<crate::locations::LocationOverrides>,
+) -> Result<Json<crate::locations::LocationOverrides>, (StatusCode, Json<ErrorResponse>)> {
+    use crate::locations::{self, LocationError};
+
+    let saved = locations::save(&pdb.db, overrides).map_err(|e| match e {
+        LocationError::Database(_) => err_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        _ => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+    })?;
+    pdb.audit(
+        "settings_change",
+        Some("location_overrides"),
+        serde_json::json!({ "renames": saved.renames.len(), "sites": saved.sites.len() }),
+    );
+    Ok(Json(saved))
+}
+
+/// GET /api/jobs — All jobs with their flight counts
+async fn get_jobs(pdb: ProfileDb) -> Result<Json<Vec<crate::jobs::Job>>, (StatusCode, Json<ErrorResponse>)> {
+    let jobs = pdb.db
+        .get_jobs()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get jobs: {}", e)))?;
+    Ok(Json(jobs))
+}
+
+/// POST /api/jobs — Create or update a job (empty id creates one)
+async fn save_job(
+    pdb: ProfileDb,
+    Json(job): Json<crate::jobs::Job>,
+) -> Result<Json<crate::jobs::Job>, (StatusCode, Json<ErrorResponse>)> {
+    if job.name.trim().is_empty() {
+        return Err(err_response(StatusCode::BAD_REQUEST, "Job name cannot be empty"));
+    }
+    let saved = pdb.db
+        .save_job(&job)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save job: {}", e)))?;
+    pdb.audit("save_job", Some(&saved.id), serde_json::json!({ "name": saved.name }));
+    Ok(Json(saved))
+}
+
+#[derive(Deserialize)]
+struct JobQuery {
+    id: String,
+}
+
+/// DELETE /api/jobs?id=... — Delete a job (its flights are kept, unassigned)
+async fn delete_job(
+    pdb: ProfileDb,
+    Query(params): Query<JobQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.db
+        .delete_job(&params.id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete job: {}", e)))?;
+    pdb.audit("delete_job", Some(&params.id), serde_json::Value::Null);
+    Ok(Json(true))
+}
+
+#[derive(Deserialize)]
+struct AssignJobPayload {
+    job_id: Option<String>,
+    flight_ids: Vec<i64>,
+}
+
+/// POST /api/jobs/assign — Assign flights to a job, or unassign them when `job_id` is null
+async fn assign_flights_to_job(
+    pdb: ProfileDb,
+    Json(payload): Json<AssignJobPayload>,
+) -> Result<Json<usize>, (StatusCode, Json<ErrorResponse>)> {
+    for &flight_id in &payload.flight_ids {
+        pdb.check_flight_access(flight_id)?;
+    }
+    if let Some(id) = payload.job_id.as_deref() {
+        let job = pdb.db
+            .get_job(id)
+            .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get job: {}", e)))?;
+        if job.is_none() {
+            return Err(err_response(StatusCode::NOT_FOUND, format!("Job not found: {}", id)));
+        }
+    }
+    let changed = pdb.db
+        .assign_flights_to_job(payload.job_id.as_deref(), &payload.flight_ids)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to assign flights: {}", e)))?;
+    pdb.audit("assign_job", payload.job_id.as_deref(), serde_json::json!({ "flights": payload.flight_ids }));
+    Ok(Json(changed))
+}
+
+fn load_job_or_404(
+    pdb: &ProfileDb,
+    id: &str,
+) -> Result<(crate::jobs::Job, Vec<crate::models::Flight>), (StatusCode, Json<ErrorResponse>)> {
+    crate::jobs::load_job(&pdb.db, id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get job: {}", e)))?
+        .ok_or_else(|| err_response(StatusCode::NOT_FOUND, format!("Job not found: {}", id)))
+}
+
+/// GET /api/jobs/stats?id=... — Hours, distance, batteries and aircraft used for one job
+async fn get_job_stats(
+    pdb: ProfileDb,
+    Query(params): Query<JobQuery>,
+) -> Result<Json<crate::jobs::JobStats>, (StatusCode, Json<ErrorResponse>)> {
+    let (job, flights) = load_job_or_404(&pdb, &params.id)?;
+    Ok(Json(crate::jobs::job_stats(job, &flights)))
+}
+
+#[derive(Deserialize)]
+struct JobExportQuery {
+    id: String,
+    format: Option<String>,
+    template_id: Option<String>,
+}
+
+/// GET /api/jobs/export?id=...&format=csv|json&template_id=... — Download a job's flights
+async fn export_job(
+    pdb: ProfileDb,
+    Query(params): Query<JobExportQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    use axum::response::IntoResponse;
+
+    let (job, flights) = load_job_or_404(&pdb, &params.id)?;
+    let template = match params.template_id.as_deref() {
+        Some(id) => Some(
+            crate::export_templates::find_template(&pdb.config_path(), id)
+                .map_err(|e| err_response(StatusCode::NOT_FOUND, e.to_string()))?,
+        ),
+        None => None,
+    };
+    let format = params.format.as_deref().unwrap_or("csv").to_ascii_lowercase();
+    let stem = job.name.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+    let body = crate::jobs::export_job(job, &flights, &format, template.as_ref(), pdb.db.get_duration_rounding())
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    let content_type = if format == "json" { "application/json" } else { "text/csv; charset=utf-8" };
+
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, content_type.to_string()),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"job_{}.{}\"", stem, format)),
+        ],
+        body,
+    ).into_response())
+}
+
+#[derive(Deserialize)]
+struct InvoiceQuery {
+    from: Option<String>,
+    to: Option<String>,
+    client: Option<String>,
+    session_gap_minutes: Option<f64>,
+    format: Option<String>,
+}
+
+/// GET /api/reports/invoice?from=&to=&client=&session_gap_minutes=&format=json|csv|pdf
+/// — Billable flight hours, standby time and distance per client and job
+async fn invoice_report(
+    pdb: ProfileDb,
+    Query(params): Query<InvoiceQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    use axum::response::IntoResponse;
+    use crate::invoice;
+
+    let options = invoice::InvoiceOptions {
+        from: invoice::parse_date(params.from.as_deref()).map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?,
+        to: invoice::parse_date(params.to.as_deref()).map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?,
+        client: params.client,
+        session_gap_minutes: params.session_gap_minutes,
+    };
+    let report = invoice::load_report(&pdb.db, &options)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to build invoice report: {}", e)))?;
+    let units = crate::units::UnitSettings::load(&pdb.config_path());
+
+    let (content_type, ext, body) = match params.format.as_deref().unwrap_or("json").to_ascii_lowercase().as_str() {
+        "json" => return Ok(Json(report).into_response()),
+        "csv" => ("text/csv; charset=utf-8", "csv", invoice::report_csv(&report, &units).into_bytes()),
+        "pdf" => ("application/pdf", "pdf", invoice::report_pdf(&report, &units)),
+        other => return Err(err_response(StatusCode::BAD_REQUEST, format!("Unsupported export format: {}", other))),
+    };
+    let filename = format!(
+        "invoice_{}_{}.{}",
+        report.from.as_deref().unwrap_or("start"),
+        report.to.as_deref().unwrap_or("today"),
+        ext
+    );
+
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, content_type.to_string()),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        body,
+    ).into_response())
+}
+
+#[derive(Deserialize)]
+struct OperatorLogQuery {
+    from: Option<String>,
+    to: Option<String>,
+    format: Option<String>,
+}
+
+/// GET /api/reports/operator_log?from=&to=&format=json|csv|pdf
+/// — Operator flight log for EASA-territory authorities
+async fn operator_log_report(
+    pdb: ProfileDb,
+    Query(params): Query<OperatorLogQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    use axum::response::IntoResponse;
+    use crate::{invoice::parse_date, operator_log};
+
+    let from = parse_date(params.from.as_deref()).map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    let to = parse_date(params.to.as_deref()).map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    let log = operator_log::load_log(&pdb.db, &pdb.config_path(), from, to)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to build operator log: {}", e)))?;
+
+    let (content_type, ext, body) = match params.format.as_deref().unwrap_or("json").to_ascii_lowercase().as_str() {
+        "json" => return Ok(Json(log).into_response()),
+        "csv" => ("text/csv; charset=utf-8", "csv", operator_log::log_csv(&log).into_bytes()),
+        "pdf" => ("application/pdf", "pdf", operator_log::log_pdf(&log)),
+        other => return Err(err_response(StatusCode::BAD_REQUEST, format!("Unsupported export format: {}", other))),
+    };
+    let filename = format!(
+        "operator_log_{}_{}.{}",
+        log.from.as_deref().unwrap_or("start"),
+        log.to.as_deref().unwrap_or("today"),
+        ext
+    );
+
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, content_type.to_string()),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        body,
+    ).into_response())
+}
+
+#[derive(Deserialize)]
+struct SqlQueryPayload {
+    sql: String,
+    max_rows: Option<usize>,
+}
+
+/// POST /api/sql/query — Run a read-only SELECT (one statement, row and time limits)
+async fn run_sql_query(
+    pdb: ProfileDb,
+    Json(payload): Json<SqlQueryPayload>,
+) -> Result<Json<crate::sql_console::QueryResult>, (StatusCode, Json<ErrorResponse>)> {
+    use crate::sql_console::SqlConsoleError;
+
+    let result = crate::sql_console::run(&pdb.db, &payload.sql, payload.max_rows).map_err(|e| {
+        let status = match e {
+            SqlConsoleError::Timeout => StatusCode::REQUEST_TIMEOUT,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        err_response(status, e.to_string())
+    })?;
+    Ok(Json(result))
+}
+
+#[derive(Deserialize)]
+struct OverflightPayload {
+    /// `[lon, lat]` vertices
+    polygon: Vec<[f64; 2]>,
+    from: Option<String>,
+    to: Option<String>,
+    format: Option<String>,
+}
+
+/// POST /api/reports/overflight — Flights, minutes and heights over a drawn area
+async fn overflight_report(
+    pdb: ProfileDb,
+    Json(payload): Json<OverflightPayload>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    use axum::response::IntoResponse;
+    use crate::{invoice::parse_date, overflight};
+
+    let from = parse_date(payload.from.as_deref()).map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    let to = parse_date(payload.to.as_deref()).map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    let report = overflight::build_report(&pdb.db, &payload.polygon, from, to).map_err(|e| match e {
+        overflight::OverflightError::Database(_) => err_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        _ => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+    })?;
+
+    match payload.format.as_deref().unwrap_or("json").to_ascii_lowercase().as_str() {
+        "json" => Ok(Json(report).into_response()),
+        "csv" => Ok((
+            [
+                (axum::http::header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (axum::http::header::CONTENT_DISPOSITION, "attachment; filename=\"overflight.csv\"".to_string()),
+            ],
+            overflight::report_csv(&report),
+        ).into_response()),
+        other => Err(err_response(StatusCode::BAD_REQUEST, format!("Unsupported export format: {}", other))),
+    }
+}
+
+#[derive(Deserialize)]
+struct FlightListPayload {
+    /// Flights of the filtered list, in display order; all flights when empty
+    #[serde(default)]
+    flight_ids: Vec<i64>,
+    #[serde(default)]
+    columns: Vec<crate::flight_list::FlightColumn>,
+    title: Option<String>,
+    format: Option<String>,
+}
+
+/// POST /api/reports/flight_list — The filtered flight list with the chosen
+/// columns as a CSV file or a paginated PDF table
+async fn flight_list_report(
+    pdb: ProfileDb,
+    Json(payload): Json<FlightListPayload>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    use axum::response::IntoResponse;
+    use crate::flight_list;
+
+    let flights = flight_list::load_flights(&pdb.db, &payload.flight_ids)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get flights: {}", e)))?;
+    let units = crate::units::UnitSettings::load(&pdb.config_path());
+    let rounding = pdb.db.get_duration_rounding();
+
+    let (content_type, ext, body) = match payload.format.as_deref().unwrap_or("pdf").to_ascii_lowercase().as_str() {
+        "csv" => (
+            "text/csv; charset=utf-8",
+            "csv",
+            flight_list::list_csv(&flights, &payload.columns, &units, rounding).into_bytes(),
+        ),
+        "pdf" => (
+            "application/pdf",
+            "pdf",
+            flight_list::list_pdf(&flights, &payload.columns, payload.title.as_deref(), &units, rounding),
+        ),
+        other => return Err(err_response(StatusCode::BAD_REQUEST, format!("Unsupported export format: {}", other))),
+    };
+    let filename = format!("flights_{}.{}", chrono::Local::now().format("%Y-%m-%d"), ext);
+
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, content_type.to_string()),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        body,
+    ).into_response())
+}
+
+/// GET /api/operator/settings — Operator details and aircraft class marks for the operator log
+async fn get_operator_settings(pdb: ProfileDb) -> Json<crate::operator_log::OperatorSettings> {
+    Json(crate::operator_log::OperatorSettings::load(&pdb.config_path()))
+}
+
+/// POST /api/operator/settings — Save the operator details and aircraft class marks
+async fn set_operator_settings(
+    pdb: ProfileDb,
+    Json(settings): Json<crate::operator_log::OperatorSettings>,
+) -> Result<Json<crate::operator_log::OperatorSettings>, (StatusCode, Json<ErrorResponse>)> {
+    let saved = crate::operator_log::save_settings(&pdb.config_path(), settings)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    pdb.audit(
+        "settings_change",
+        Some("operator_log"),
+        serde_json::json!({ "operatorId": saved.operator_id, "aircraft": saved.uas_classes.len() }),
+    );
+    Ok(Json(saved))
+}
+
+/// Start the background import queue worker for a profile (no-op when one is running)
+fn spawn_import_queue(db: Arc<Database>, profile: String, data_dir: PathBuf) {
+    if tokio::runtime::Handle::try_current().is_err() || db.is_read_only() {
+        return;
+    }
+    tokio::spawn(async move {
+        let pdb = ProfileDb {
+            db: db.clone(),
+            profile,
+            data_dir,
+            actor: "import_queue".to_string(),
+            user: None,
+            locale: messages::default_locale(),
+        };
+        crate::import_queue::run_worker(db, |item| {
+            let pdb = &pdb;
+            async move {
+                let data = std::fs::read(&item.file_path)
+                    .map_err(|e| format!("Failed to read queued file: {}", e))?;
+                let result = import_upload(pdb, item.file_name.clone(), &data, None)
+                    .await
+                    .map_err(|(_, Json(e))| e.error);
+                let _ = std::fs::remove_file(&item.file_path);
+                if let (Ok(ImportResult { flight_id: Some(flight_id), .. }), Some(owner)) = (&result, &item.owner) {
+                    if let Err(e) = pdb.db.set_flight_owner(*flight_id, owner) {
+                        log::warn!("Failed to record owner of flight {}: {}", flight_id, e);
+                    }
+                }
+                result
+            }
+        })
+        .await;
+    });
+}
+
+/// POST /api/import_queue — Queue uploaded files (multipart, any number of
+/// parts) for background import; returns the new queue entries
+async fn enqueue_imports(
+    pdb: ProfileDb,
+    mut multipart: Multipart,
+) -> Result<Json<Vec<crate::import_queue::QueuedImport>>, (StatusCode, Json<ErrorResponse>)> {
+    let spool_dir = pdb.data_dir.join("import_queue").join(&pdb.profile);
+    std::fs::create_dir_all(&spool_dir)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create queue folder: {}", e)))?;
 
-    Ok(Json(ImportResult {
-        success: true,
-        flight_id: Some(flight_id),
-        message: "Manual flight entry created successfully".to_string(),
-        point_count: 0,
-        file_hash: None,
-    }))
+    let mut queued = Vec::new();
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Multipart error: {}", e)))?
+    {
+        let file_name = field
+            .file_name()
+            .and_then(|n| std::path::Path::new(n).file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown.txt".to_string());
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read file: {}", e)))?;
+        let spool_path = spool_dir.join(format!("{}_{}", uuid::Uuid::new_v4().simple(), file_name));
+        std::fs::write(&spool_path, &data)
+            .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to store queued file: {}", e)))?;
+        let entry = pdb.db
+            .enqueue_import(&file_name, &spool_path.to_string_lossy(), pdb.user.as_ref().map(|u| u.username.as_str()))
+            .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to queue import: {}", e)))?;
+        queued.push(entry);
+    }
+    if queued.is_empty() {
+        return Err(err_response(StatusCode::BAD_REQUEST, "No file uploaded"));
+    }
+
+    spawn_import_queue(pdb.db.clone(), pdb.profile.clone(), pdb.data_dir.clone());
+    Ok(Json(queued))
 }
 
-/// GET /api/flights — List all flights
-async fn get_flights(
+/// GET /api/import_queue — All queue entries with their status, oldest first
+async fn get_import_queue(
     pdb: ProfileDb,
-) -> Result<Json<Vec<crate::models::Flight>>, (StatusCode, Json<ErrorResponse>)> {
-    let flights = pdb.db
-        .get_all_flights()
-        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get flights: {}", e)))?;
-    Ok(Json(flights))
+) -> Result<Json<Vec<crate::import_queue::QueuedImport>>, (StatusCode, Json<ErrorResponse>)> {
+    let queue = pdb.db
+        .get_import_queue()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get import queue: {}", e)))?;
+    Ok(Json(queue))
+}
+
+/// GET /api/import_status — Running imports with their progress, the number
+/// of queued files and the latest results, polled for a global progress indicator
+async fn get_import_status(
+    pdb: ProfileDb,
+) -> Result<Json<crate::import_status::ImportStatus>, (StatusCode, Json<ErrorResponse>)> {
+    let status = crate::import_status::status(&pdb.db)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get import status: {}", e)))?;
+    Ok(Json(status))
+}
+
+/// DELETE /api/import_queue — Remove finished (done / failed) entries
+async fn clear_import_queue(pdb: ProfileDb) -> Result<Json<usize>, (StatusCode, Json<ErrorResponse>)> {
+    let cleared = pdb.db
+        .clear_finished_imports()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to clear import queue: {}", e)))?;
+    for entry in &cleared {
+        // Failed uploads keep their spooled file until cleared
+        let _ = std::fs::remove_file(&entry.file_path);
+    }
+    Ok(Json(cleared.len()))
 }
 
-/// GET /api/flights/:id — Get flight data for visualization
 #[derive(Deserialize)]
-struct FlightDataQuery {
-    flight_id: i64,
-    max_points: Option<usize>,
+struct AuditLogQuery {
+    limit: Option<usize>,
+    offset: Option<usize>,
+    action: Option<String>,
 }
 
-async fn get_flight_data(
+/// GET /api/audit_log?limit=&offset=&action= — Data-modifying actions, newest first
+async fn get_audit_log(
     pdb: ProfileDb,
-    Query(params): Query<FlightDataQuery>,
-) -> Result<Json<FlightDataResponse>, (StatusCode, Json<ErrorResponse>)> {
-    let flight = pdb.db
-        .get_flight_by_id(params.flight_id)
-        .map_err(|e| err_response(StatusCode::NOT_FOUND, format!("Flight not found: {}", e)))?;
-
-    let known_point_count = flight.point_count.map(|c| c as i64);
+    Query(params): Query<AuditLogQuery>,
+) -> Result<Json<Vec<crate::models::AuditEntry>>, (StatusCode, Json<ErrorResponse>)> {
+    let entries = pdb.db
+        .get_audit_log(params.limit.unwrap_or(200), params.offset.unwrap_or(0), params.action.as_deref())
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get audit log: {}", e)))?;
+    Ok(Json(entries))
+}
 
-    let telemetry_records = pdb.db
-        .get_flight_telemetry(params.flight_id, params.max_points, known_point_count)
-        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get telemetry: {}", e)))?;
+#[derive(Deserialize)]
+struct IntegrityChainQuery {
+    flight_id: Option<i64>,
+}
 
-    let telemetry = TelemetryData::from_records(&telemetry_records);
-    let track = telemetry.extract_track(2000);
+/// GET /api/integrity/chain?flight_id= — Integrity chain entries, oldest first
+async fn get_integrity_chain(
+    pdb: ProfileDb,
+    Query(params): Query<IntegrityChainQuery>,
+) -> Result<Json<Vec<crate::integrity::IntegrityEntry>>, (StatusCode, Json<ErrorResponse>)> {
+    let entries = pdb.db
+        .get_integrity_chain(params.flight_id)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get integrity chain: {}", e)))?;
+    Ok(Json(entries))
+}
 
-    // Get flight messages (tips and warnings)
-    let messages = pdb.db
-        .get_flight_messages(params.flight_id)
-        .unwrap_or_else(|e| {
-            log::warn!("Failed to get messages for flight {}: {}", params.flight_id, e);
-            Vec::new()
-        });
+/// GET /api/integrity/verify — Check the integrity chain and every flight against it
+async fn verify_integrity(
+    pdb: ProfileDb,
+) -> Result<Json<crate::integrity::IntegrityReport>, (StatusCode, Json<ErrorResponse>)> {
+    let report = pdb.db
+        .verify_integrity()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to verify integrity: {}", e)))?;
+    Ok(Json(report))
+}
 
-    Ok(Json(FlightDataResponse {
-        flight,
-        telemetry,
-        track,
-        messages,
-    }))
+/// POST /api/integrity/seal — Add flights imported before the chain existed
+async fn seal_integrity_chain(
+    pdb: ProfileDb,
+) -> Result<Json<Vec<i64>>, (StatusCode, Json<ErrorResponse>)> {
+    let sealed = pdb.db
+        .seal_unrecorded_flights()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to seal flights: {}", e)))?;
+    if !sealed.is_empty() {
+        pdb.audit("seal_integrity_chain", None, serde_json::json!({ "flights": sealed }));
+    }
+    Ok(Json(sealed))
 }
 
-/// GET /api/overview — Get overview statistics
-async fn get_overview_stats(
+/// POST /api/demo/seed — Add the synthetic demo flights (skips the ones already present)
+async fn seed_demo_data(
     pdb: ProfileDb,
-) -> Result<Json<OverviewStats>, (StatusCode, Json<ErrorResponse>)> {
-    let stats = pdb.db
-        .get_overview_stats()
-        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get overview stats: {}", e)))?;
-    Ok(Json(stats))
+) -> Result<Json<Vec<i64>>, (StatusCode, Json<ErrorResponse>)> {
+    let config: serde_json::Value = std::fs::read_to_string(pdb.config_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    let ids = crate::demo::seed_demo_data(&pdb.db, &config)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    if !ids.is_empty() {
+        pdb.audit("seed_demo_data", None, serde_json::json!({ "flights": ids }));
+    }
+    Ok(Json(ids))
 }
 
 /// GET /api/battery_capacity_history — Get battery full capacity history for a battery serial
@@ -697,11 +3280,15 @@ async fn delete_flight(
     pdb: ProfileDb,
     Query(params): Query<DeleteFlightQuery>,
 ) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.check_flight_access(params.flight_id)?;
     log::info!("Deleting flight: {}", params.flight_id);
+    let name = pdb.db.get_flight_by_id(params.flight_id).ok().map(|f| f.display_name);
     pdb.db
         .delete_flight(params.flight_id)
-        .map(|_| Json(true))
-        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete flight: {}", e)))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete flight: {}", e)))?;
+    crate::flight_data_cache::invalidate_flights(&pdb.data_dir, &pdb.profile, &[params.flight_id]);
+    pdb.audit("delete_flight", Some(&params.flight_id.to_string()), serde_json::json!({ "name": name }));
+    Ok(Json(true))
 }
 
 /// DELETE /api/flights — Delete all flights
@@ -709,10 +3296,13 @@ async fn delete_all_flights(
     pdb: ProfileDb,
 ) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
     log::warn!("Deleting ALL flights and telemetry");
+    let count = pdb.db.get_all_flights().map(|f| f.len()).unwrap_or(0);
     pdb.db
         .delete_all_flights()
-        .map(|_| Json(true))
-        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete all flights: {}", e)))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete all flights: {}", e)))?;
+    crate::flight_data_cache::invalidate_profile(&pdb.data_dir, &pdb.profile);
+    pdb.audit("delete_all_flights", None, serde_json::json!({ "flights": count }));
+    Ok(Json(true))
 }
 
 /// POST /api/flights/deduplicate — Remove duplicate flights
@@ -720,10 +3310,13 @@ async fn deduplicate_flights(
     pdb: ProfileDb,
 ) -> Result<Json<usize>, (StatusCode, Json<ErrorResponse>)> {
     log::info!("Running flight deduplication");
-    pdb.db
+    let removed = pdb.db
         .deduplicate_flights()
-        .map(Json)
-        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to deduplicate flights: {}", e)))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to deduplicate flights: {}", e)))?;
+    if removed > 0 {
+        pdb.audit("deduplicate_flights", None, serde_json::json!({ "removed": removed }));
+    }
+    Ok(Json(removed))
 }
 
 /// PUT /api/flights/name — Update flight display name
@@ -737,6 +3330,7 @@ async fn update_flight_name(
     pdb: ProfileDb,
     Json(payload): Json<UpdateNamePayload>,
 ) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.check_flight_access(payload.flight_id)?;
     let trimmed = payload.display_name.trim();
     if trimmed.is_empty() {
         return Err(err_response(StatusCode::BAD_REQUEST, "Display name cannot be empty"));
@@ -744,10 +3338,16 @@ async fn update_flight_name(
 
     log::info!("Renaming flight {} to '{}'", payload.flight_id, trimmed);
 
+    let old_name = pdb.db.get_flight_by_id(payload.flight_id).ok().map(|f| f.display_name);
     pdb.db
         .update_flight_name(payload.flight_id, trimmed)
-        .map(|_| Json(true))
-        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to update flight name: {}", e)))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to update flight name: {}", e)))?;
+    pdb.audit(
+        "rename_flight",
+        Some(&payload.flight_id.to_string()),
+        serde_json::json!({ "from": old_name, "to": trimmed }),
+    );
+    Ok(Json(true))
 }
 
 #[derive(Deserialize)]
@@ -760,6 +3360,7 @@ async fn update_flight_notes(
     pdb: ProfileDb,
     Json(payload): Json<UpdateNotesPayload>,
 ) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.check_flight_access(payload.flight_id)?;
     let notes_ref = payload.notes.as_ref().map(|s| {
         let trimmed = s.trim();
         if trimmed.is_empty() { None } else { Some(trimmed) }
@@ -784,6 +3385,7 @@ async fn update_flight_color(
     pdb: ProfileDb,
     Json(payload): Json<UpdateColorPayload>,
 ) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.check_flight_access(payload.flight_id)?;
     let trimmed = payload.color.trim();
     if trimmed.is_empty() {
         return Err(err_response(StatusCode::BAD_REQUEST, "Color cannot be empty"));
@@ -797,6 +3399,41 @@ async fn update_flight_color(
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to update flight color: {}", e)))
 }
 
+#[derive(Deserialize)]
+struct UpdateMetadataPayload {
+    flight_id: i64,
+    update: FlightMetadataUpdate,
+}
+
+/// PUT /api/flights/metadata — Correct drone model, battery serial, pilot,
+/// takeoff location name or start time offset (parsed values are kept)
+async fn update_flight_metadata(
+    pdb: ProfileDb,
+    Json(payload): Json<UpdateMetadataPayload>,
+) -> Result<Json<crate::models::Flight>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.check_flight_access(payload.flight_id)?;
+    let update = payload.update;
+    update.validate().map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    log::info!("Updating metadata for flight {}", payload.flight_id);
+
+    let flight = pdb.db.update_flight_metadata(payload.flight_id, &update).map_err(|e| match e {
+        database::DatabaseError::FlightNotFound(_) => err_response(StatusCode::NOT_FOUND, e.to_string()),
+        _ => err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to update flight metadata: {}", e)),
+    })?;
+    pdb.audit(
+        "edit_flight_metadata",
+        Some(&payload.flight_id.to_string()),
+        serde_json::json!({
+            "droneModel": update.drone_model,
+            "batterySerial": update.battery_serial,
+            "pilot": update.pilot,
+            "locationName": update.location_name,
+            "timeOffsetSecs": update.time_offset_secs,
+        }),
+    );
+    Ok(Json(flight))
+}
+
 /// GET /api/has_api_key — Check if DJI API key is configured
 async fn has_api_key(
     AxumState(state): AxumState<WebAppState>,
@@ -823,24 +3460,26 @@ struct SetApiKeyPayload {
 
 async fn set_api_key(
     AxumState(state): AxumState<WebAppState>,
-    _pdb: ProfileDb,
+    pdb: ProfileDb,
     Json(payload): Json<SetApiKeyPayload>,
 ) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
     let api = DjiApi::with_app_data_dir(state.data_dir.clone());
     api.save_api_key(&payload.api_key)
-        .map(|_| Json(true))
-        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save API key: {}", e)))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save API key: {}", e)))?;
+    pdb.audit("settings_change", Some("api_key"), serde_json::json!({ "set": true }));
+    Ok(Json(true))
 }
 
 /// DELETE /api/remove_api_key — Remove the custom API key (fall back to default)
 async fn remove_api_key(
     AxumState(state): AxumState<WebAppState>,
-    _pdb: ProfileDb,
+    pdb: ProfileDb,
 ) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
     let api = DjiApi::with_app_data_dir(state.data_dir.clone());
     api.remove_api_key()
-        .map(|_| Json(true))
-        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to remove API key: {}", e)))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to remove API key: {}", e)))?;
+    pdb.audit("settings_change", Some("api_key"), serde_json::json!({ "set": false }));
+    Ok(Json(true))
 }
 
 /// GET /api/app_data_dir — Get the app data directory path
@@ -851,18 +3490,268 @@ async fn get_app_data_dir(
     Json(state.data_dir.to_string_lossy().to_string())
 }
 
+/// GET /api/db_lock_status — Whether the profile database is a read-only
+/// snapshot because another process holds its lock
+async fn get_db_lock_status(pdb: ProfileDb) -> Json<database::DbLockStatus> {
+    Json(pdb.db.lock_status())
+}
+
+/// GET /api/database/info — Database file and WAL size, and the last idle
+/// checkpoint / compaction
+async fn get_database_info(pdb: ProfileDb) -> Json<crate::db_maintenance::DatabaseInfo> {
+    Json(crate::db_maintenance::database_info(&pdb.db))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DbMirrorInfo {
+    #[serde(flatten)]
+    settings: crate::db_mirror::MirrorSettings,
+    /// Where the server publishes this profile's mirror
+    mirror_path: String,
+    /// When the mirror file was last written
+    last_published: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+fn db_mirror_info(pdb: &ProfileDb) -> DbMirrorInfo {
+    let dest = crate::db_mirror::web_mirror_path(&pdb.data_dir, &pdb.profile);
+    let last_published = std::fs::metadata(&dest)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(chrono::DateTime::<chrono::Utc>::from);
+    let settings = crate::db_mirror::MirrorSettings::load(&pdb.config_path());
+    DbMirrorInfo {
+        settings: crate::db_mirror::MirrorSettings { path: None, ..settings },
+        mirror_path: dest.display().to_string(),
+        last_published,
+    }
+}
+
+/// GET /api/db_mirror — Read-only mirror settings of the profile and where it
+/// is published
+async fn get_db_mirror(pdb: ProfileDb) -> Json<DbMirrorInfo> {
+    Json(db_mirror_info(&pdb))
+}
+
+/// POST /api/db_mirror — Enable or disable the scheduled mirror and set its
+/// interval (the destination comes from `DB_MIRROR_PATH`)
+async fn set_db_mirror(
+    pdb: ProfileDb,
+    Json(settings): Json<crate::db_mirror::MirrorSettings>,
+) -> Result<Json<DbMirrorInfo>, (StatusCode, Json<ErrorResponse>)> {
+    let settings = crate::db_mirror::MirrorSettings { path: None, ..settings };
+    let saved = crate::db_mirror::save_settings(&pdb.config_path(), settings, false)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    pdb.audit(
+        "settings_change",
+        Some("db_mirror"),
+        serde_json::json!({ "enabled": saved.enabled, "intervalMinutes": saved.interval_minutes }),
+    );
+    Ok(Json(db_mirror_info(&pdb)))
+}
+
+/// POST /api/db_mirror/publish — Publish the mirror now
+async fn publish_db_mirror(
+    pdb: ProfileDb,
+) -> Result<Json<crate::db_mirror::MirrorReport>, (StatusCode, Json<ErrorResponse>)> {
+    let dest = crate::db_mirror::web_mirror_path(&pdb.data_dir, &pdb.profile);
+    let db = pdb.db.clone();
+    let report = tokio::task::spawn_blocking(move || crate::db_mirror::publish(&db, &dest))
+        .await
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    pdb.audit("publish_db_mirror", Some(&report.path), serde_json::json!({ "tables": report.tables }));
+    Ok(Json(report))
+}
+
+/// GET /api/duckdb/extensions — DuckDB offline mode and the loaded /
+/// installed extensions
+async fn get_duckdb_extensions(
+    pdb: ProfileDb,
+) -> Result<Json<crate::duckdb_extensions::ExtensionStatus>, (StatusCode, Json<ErrorResponse>)> {
+    crate::duckdb_extensions::status(&pdb.db, &pdb.config_path())
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+#[derive(Deserialize)]
+struct OfflineModePayload {
+    enabled: bool,
+}
+
+/// POST /api/duckdb/offline — Switch DuckDB's offline mode (no extension
+/// downloads) on or off for the profile
+async fn set_duckdb_offline_mode(
+    pdb: ProfileDb,
+    Json(payload): Json<OfflineModePayload>,
+) -> Result<Json<crate::duckdb_extensions::ExtensionStatus>, (StatusCode, Json<ErrorResponse>)> {
+    let status = crate::duckdb_extensions::set_offline(&pdb.db, &pdb.config_path(), payload.enabled)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    pdb.audit("set_duckdb_offline_mode", None, serde_json::json!({ "enabled": payload.enabled }));
+    Ok(Json(status))
+}
+
+/// GET /api/recovery — Recoveries of the profile database that haven't been
+/// dismissed, and the restore scheduled for the next start
+async fn get_recovery_status(pdb: ProfileDb) -> Json<crate::recovery::RecoveryStatus> {
+    Json(crate::recovery::status(pdb.db.path()))
+}
+
+#[derive(Deserialize)]
+struct RecoveryIdPayload {
+    id: String,
+}
+
+fn recovery_error(e: crate::recovery::RecoveryError) -> (StatusCode, Json<ErrorResponse>) {
+    use crate::recovery::RecoveryError;
+    match e {
+        RecoveryError::NotFound(_) | RecoveryError::CandidateNotFound(_) => err_response(StatusCode::NOT_FOUND, e.to_string()),
+        RecoveryError::InvalidId(_) | RecoveryError::Archive(_) => err_response(StatusCode::BAD_REQUEST, e.to_string()),
+        RecoveryError::Io(_) => err_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// POST /api/recovery/dismiss — Hide a recovery report (its copies are kept)
+async fn dismiss_recovery_report(
+    pdb: ProfileDb,
+    Json(payload): Json<RecoveryIdPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    crate::recovery::dismiss(pdb.db.path(), &payload.id).map_err(recovery_error)?;
+    Ok(Json(true))
+}
+
+/// POST /api/recovery/restore — Restore a recovery copy when the profile
+/// database is next opened (server restart)
+async fn restore_recovery_copy(
+    pdb: ProfileDb,
+    Json(payload): Json<RecoveryIdPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    crate::recovery::schedule_restore(pdb.db.path(), &payload.id).map_err(recovery_error)?;
+    pdb.audit("restore_recovery_copy", Some(&payload.id), serde_json::json!({ "database": pdb.db.path().to_string_lossy() }));
+    Ok(Json(true))
+}
+
+/// Folder of the profile's scheduled backups, when BACKUP_PATH is set
+fn scheduled_backup_dir(profile: &str) -> Option<PathBuf> {
+    std::env::var("BACKUP_PATH").ok().map(|p| PathBuf::from(p).join(profile))
+}
+
+/// GET /api/recovery/candidates — Recovery copies, snapshots, `.bak` files and
+/// scheduled backups the profile database can be restored from; `prompt` is
+/// set when the database is empty and there is at least one
+async fn list_recovery_candidates(
+    pdb: ProfileDb,
+) -> Result<Json<crate::recovery::RecoveryCandidates>, (StatusCode, Json<ErrorResponse>)> {
+    let flight_count = pdb
+        .db
+        .flight_count()
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let archive_dir = scheduled_backup_dir(&pdb.profile);
+    Ok(Json(crate::recovery::list_candidates(pdb.db.path(), archive_dir.as_deref(), flight_count)))
+}
+
+/// POST /api/recovery/candidates/restore — Restore a candidate. Backup
+/// archives are imported right away; database files replace the profile
+/// database when it is next opened (server restart).
+async fn restore_recovery_candidate(
+    pdb: ProfileDb,
+    Json(payload): Json<RecoveryIdPayload>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    use crate::recovery::CandidateKind;
+
+    let archive_dir = scheduled_backup_dir(&pdb.profile);
+    let candidate =
+        crate::recovery::find_candidate(pdb.db.path(), archive_dir.as_deref(), &payload.id).map_err(recovery_error)?;
+    let response = if candidate.kind == CandidateKind::BackupArchive {
+        let message = pdb
+            .db
+            .import_backup(std::path::Path::new(&candidate.path))
+            .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Restore failed: {}", e)))?;
+        serde_json::json!({ "restartRequired": false, "message": message })
+    } else {
+        crate::recovery::schedule_candidate_restore(pdb.db.path(), &candidate).map_err(recovery_error)?;
+        serde_json::json!({ "restartRequired": true })
+    };
+    pdb.audit("restore_recovery_candidate", Some(&candidate.id), serde_json::json!({ "path": candidate.path }));
+    Ok(Json(response))
+}
+
 /// GET /api/app_log_dir — Get the app log directory path
 async fn get_app_log_dir(
     AxumState(state): AxumState<WebAppState>,
     _pdb: ProfileDb,
 ) -> Json<String> {
-    // In web mode, logs go to stdout/the data dir
-    Json(state.data_dir.to_string_lossy().to_string())
+    // In web mode, logs go to stdout and logs/ in the data dir
+    Json(crate::support::web_log_dir(&state.data_dir).to_string_lossy().to_string())
+}
+
+/// GET /api/logs/level — Current runtime log level
+async fn get_log_level(_pdb: ProfileDb) -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "level": crate::support::current_level() }))
+}
+
+#[derive(Deserialize)]
+struct LogLevelPayload {
+    level: String,
+}
+
+/// POST /api/logs/level — Change the runtime log level (kept across restarts)
+async fn set_log_level(
+    pdb: ProfileDb,
+    Json(payload): Json<LogLevelPayload>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let level = crate::support::set_level(&pdb.data_dir, &payload.level)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    pdb.audit("settings_change", Some("log_level"), serde_json::json!({ "level": level }));
+    Ok(Json(serde_json::json!({ "level": level })))
+}
+
+#[derive(Deserialize)]
+struct LogTailQuery {
+    lines: Option<usize>,
+}
+
+/// GET /api/logs/tail?lines=N — Last lines of the current server log
+async fn tail_log(
+    pdb: ProfileDb,
+    Query(params): Query<LogTailQuery>,
+) -> Result<Json<crate::support::LogTail>, (StatusCode, Json<ErrorResponse>)> {
+    let lines = params.lines.unwrap_or(crate::support::DEFAULT_TAIL_LINES);
+    let tail = crate::support::tail(&crate::support::web_log_dir(&pdb.data_dir), lines)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read log: {}", e)))?;
+    Ok(Json(tail))
+}
+
+/// GET /api/support_bundle — Download logs, system/database info and redacted settings as a zip
+async fn export_support_bundle(
+    pdb: ProfileDb,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    use axum::response::IntoResponse;
+
+    let log_dir = crate::support::web_log_dir(&pdb.data_dir);
+    let bundle = crate::support::build_bundle(&pdb.db, &pdb.profile, &pdb.config_path(), &log_dir)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to build support bundle: {}", e)))?;
+    pdb.audit("support_bundle", None, serde_json::json!({ "bytes": bundle.bytes.len() }));
+
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, "application/zip".to_string()),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", bundle.file_name)),
+        ],
+        bundle.bytes,
+    ).into_response())
+}
+
+#[derive(Deserialize)]
+struct BackupQuery {
+    /// Hash serials, shift coordinates and leave out identifying tables
+    scrub: Option<bool>,
 }
 
-/// GET /api/backup — Download a compressed database backup
+/// GET /api/backup?scrub=... — Download a compressed database backup
 async fn export_backup(
     pdb: ProfileDb,
+    Query(params): Query<BackupQuery>,
 ) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
     use axum::body::Body;
     use axum::response::IntoResponse;
@@ -870,7 +3759,7 @@ async fn export_backup(
     let temp_path = std::env::temp_dir().join(format!("dji-logbook-dl-{}.db.backup", uuid::Uuid::new_v4()));
 
     pdb.db
-        .export_backup(&temp_path)
+        .export_backup(&temp_path, params.scrub.unwrap_or(false))
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Backup failed: {}", e)))?;
 
     let file_bytes = tokio::fs::read(&temp_path)
@@ -881,7 +3770,11 @@ async fn export_backup(
 
     // Generate timestamped filename
     let now = chrono::Local::now();
-    let filename = format!("{}_Open_Dronelog.db.backup", now.format("%Y-%m-%d_%H-%M-%S"));
+    let filename = format!(
+        "{}_Open_Dronelog{}.db.backup",
+        now.format("%Y-%m-%d_%H-%M-%S"),
+        if params.scrub.unwrap_or(false) { "_scrubbed" } else { "" }
+    );
 
     Ok((
         [
@@ -918,6 +3811,7 @@ async fn import_backup(
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Restore failed: {}", e)))?;
 
     let _ = std::fs::remove_file(&temp_path);
+    pdb.audit("restore_backup", None, serde_json::json!({ "bytes": data.len(), "result": msg }));
 
     Ok(Json(msg))
 }
@@ -937,6 +3831,7 @@ async fn add_flight_tag(
     pdb: ProfileDb,
     Json(payload): Json<AddTagPayload>,
 ) -> Result<Json<Vec<FlightTag>>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.check_flight_access(payload.flight_id)?;
     pdb.db
         .add_flight_tag(payload.flight_id, &payload.tag)
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to add tag: {}", e)))?;
@@ -957,6 +3852,7 @@ async fn remove_flight_tag(
     pdb: ProfileDb,
     Json(payload): Json<RemoveTagPayload>,
 ) -> Result<Json<Vec<FlightTag>>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.check_flight_access(payload.flight_id)?;
     pdb.db
         .remove_flight_tag(payload.flight_id, &payload.tag)
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to remove tag: {}", e)))?;
@@ -1024,6 +3920,7 @@ async fn set_smart_tags_enabled(
     config["smart_tags_enabled"] = serde_json::json!(payload.enabled);
     std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write config: {}", e)))?;
+    pdb.audit("settings_change", Some("smart_tags_enabled"), serde_json::json!({ "value": payload.enabled }));
     Ok(Json(payload.enabled))
 }
 
@@ -1074,6 +3971,7 @@ async fn set_enabled_tag_types(
     config["enabled_tag_types"] = serde_json::json!(payload.types.clone());
     std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write config: {}", e)))?;
+    pdb.audit("settings_change", Some("enabled_tag_types"), serde_json::json!({ "value": payload.types }));
     Ok(Json(payload.types))
 }
 
@@ -1130,6 +4028,7 @@ async fn set_setting_value(
     pdb.db
         .set_setting(key, &payload.value)
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save setting: {}", e)))?;
+    pdb.audit("settings_change", Some(key), serde_json::json!({ "value": payload.value }));
 
     Ok(Json(true))
 }
@@ -1149,6 +4048,7 @@ async fn regenerate_flight_smart_tags(
 ) -> Result<Json<String>, (StatusCode, Json<ErrorResponse>)> {
     use crate::parser::{LogParser, calculate_stats_from_records};
 
+    pdb.check_flight_access(flight_id)?;
     let flight = pdb.db.get_flight_by_id(flight_id)
         .map_err(|e| err_response(StatusCode::NOT_FOUND, format!("Failed to get flight {}: {}", flight_id, e)))?;
 
@@ -1191,6 +4091,10 @@ async fn regenerate_flight_smart_tags(
             if let Some(ref types) = payload.enabled_tag_types {
                 tags = LogParser::filter_smart_tags(tags, types);
             }
+            let overrides = crate::locations::load(&pdb.db)
+                .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load location overrides: {}", e)))?;
+            let tags = crate::locations::apply_to_flight(&pdb.db, &overrides, &flight, tags, stats.home_location)
+                .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to apply location overrides: {}", e)))?;
             pdb.db.replace_auto_tags(flight_id, &tags)
                 .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to replace tags: {}", e)))?;
         }
@@ -1220,6 +4124,8 @@ async fn regenerate_smart_tags(
     let _total = flight_ids.len();
     let mut processed = 0usize;
     let mut errors = 0usize;
+    let overrides = crate::locations::load(&pdb.db)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load location overrides: {}", e)))?;
 
     for flight_id in &flight_ids {
         match pdb.db.get_flight_by_id(*flight_id) {
@@ -1259,7 +4165,9 @@ async fn regenerate_smart_tags(
                     Ok(records) if !records.is_empty() => {
                         let stats = calculate_stats_from_records(&records);
                         let tags = LogParser::generate_smart_tags(&metadata, &stats);
-                        if let Err(e) = pdb.db.replace_auto_tags(*flight_id, &tags) {
+                        let result = crate::locations::apply_to_flight(&pdb.db, &overrides, &flight, tags, stats.home_location)
+                            .and_then(|tags| pdb.db.replace_auto_tags(*flight_id, &tags));
+                        if let Err(e) = result {
                             log::warn!("Failed to replace tags for flight {}: {}", flight_id, e);
                             errors += 1;
                         }
@@ -1286,8 +4194,142 @@ async fn regenerate_smart_tags(
         "Regenerated smart tags for {} flights ({} errors) in {:.1}s",
         processed, errors, elapsed
     );
-    log::info!("{}", msg);
-    Ok(Json(msg))
+    log::info!("{}", msg);
+    Ok(Json(msg))
+}
+
+/// Request payload for rerunning the post-import pipeline
+#[derive(Deserialize)]
+struct ReprocessFlightPayload {
+    flight_id: i64,
+}
+
+/// POST /api/flights/reprocess — Rerun the post-import hook pipeline on a flight
+async fn reprocess_flight(
+    pdb: ProfileDb,
+    Json(payload): Json<ReprocessFlightPayload>,
+) -> Result<Json<Vec<crate::hooks::HookOutcome>>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.check_flight_access(payload.flight_id)?;
+    let config_path = pdb.config_path();
+    let config: serde_json::Value = std::fs::read_to_string(&config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    log::info!("Reprocessing flight {}", payload.flight_id);
+    crate::hooks::run_pipeline(&pdb.db, payload.flight_id, &config, crate::hooks::HookTrigger::Reprocess)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+/// Query parameters for re-parsing a flight
+#[derive(Deserialize)]
+struct ReparseFlightQuery {
+    flight_id: i64,
+}
+
+/// POST /api/flights/reparse?flight_id=... — Re-parse a flight from its uploaded original log and report what changed
+async fn reparse_flight(
+    pdb: ProfileDb,
+    Query(query): Query<ReparseFlightQuery>,
+    mut multipart: Multipart,
+) -> Result<Json<crate::reparse::ReparseDiff>, (StatusCode, Json<ErrorResponse>)> {
+    pdb.check_flight_access(query.flight_id)?;
+    let field = multipart
+        .next_field()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Multipart error: {}", e)))?
+        .ok_or_else(|| err_response(StatusCode::BAD_REQUEST, "No file uploaded"))?;
+
+    // Keep the original file name, the format is detected from its extension
+    let file_name = field
+        .file_name()
+        .and_then(|n| std::path::Path::new(n).file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "unknown.txt".to_string());
+    let data = field
+        .bytes()
+        .await
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, format!("Failed to read file: {}", e)))?;
+
+    let temp_dir = std::env::temp_dir().join(format!("dji-logbook-reparse-{}", uuid::Uuid::new_v4()));
+    let temp_path = temp_dir.join(&file_name);
+    std::fs::create_dir_all(&temp_dir)
+        .and_then(|_| std::fs::write(&temp_path, &data))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write temp file: {}", e)))?;
+
+    let config: serde_json::Value = std::fs::read_to_string(pdb.config_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    log::info!("Re-parsing flight {} from {}", query.flight_id, file_name);
+    let result = crate::reparse::reparse_flight(&pdb.db, query.flight_id, &temp_path, &config).await;
+    let _ = std::fs::remove_dir_all(&temp_dir);
+
+    let diff = result.map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    pdb.audit(
+        "reparse",
+        Some(&query.flight_id.to_string()),
+        serde_json::json!({ "file": file_name, "diff": diff }),
+    );
+    Ok(Json(diff))
+}
+
+/// Request payload for recomputing flight stats (all flights when `flight_id` is omitted)
+#[derive(Deserialize)]
+struct RecomputeStatsPayload {
+    flight_id: Option<i64>,
+}
+
+/// POST /api/flights/recompute_stats — Recompute distance, speed, altitude and duration from telemetry
+async fn recompute_flight_stats(
+    pdb: ProfileDb,
+    Json(payload): Json<RecomputeStatsPayload>,
+) -> Result<Json<crate::recompute::RecomputeSummary>, (StatusCode, Json<ErrorResponse>)> {
+    match payload.flight_id {
+        Some(id) => pdb.check_flight_access(id)?,
+        None if pdb.user.as_ref().is_some_and(|u| u.role < Role::Admin) => {
+            return Err(err_response(StatusCode::FORBIDDEN, "Only admins can recompute all flights"));
+        }
+        None => {}
+    }
+    let summary = crate::recompute::recompute_flights(&pdb.db, payload.flight_id.map(|id| vec![id]))
+        .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    let target = payload.flight_id.map_or_else(|| "all".to_string(), |id| id.to_string());
+    pdb.audit(
+        "recompute_stats",
+        Some(&target),
+        serde_json::json!({ "processed": summary.processed, "changed": summary.changes }),
+    );
+    Ok(Json(summary))
+}
+
+/// GET /api/hooks — List post-import hooks and their enable flags
+async fn get_post_import_hooks(
+    pdb: ProfileDb,
+) -> Result<Json<Vec<crate::hooks::HookInfo>>, (StatusCode, Json<ErrorResponse>)> {
+    Ok(Json(crate::hooks::list_hooks(&pdb.db)))
+}
+
+/// Request payload for toggling a post-import hook
+#[derive(Deserialize)]
+struct HookEnabledPayload {
+    hook_id: String,
+    enabled: bool,
+}
+
+/// POST /api/hooks/enabled — Enable or disable a post-import hook
+async fn set_post_import_hook_enabled(
+    pdb: ProfileDb,
+    Json(payload): Json<HookEnabledPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    crate::hooks::set_hook_enabled(&pdb.db, &payload.hook_id, payload.enabled)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    pdb.audit(
+        "settings_change",
+        Some(&format!("post_import_hook.{}", payload.hook_id)),
+        serde_json::json!({ "value": payload.enabled }),
+    );
+    Ok(Json(true))
 }
 
 // ============================================================================
@@ -1592,19 +4634,16 @@ async fn sync_single_file(
     };
 
     // Check for duplicate flight
-    if let Some(matching_flight) = pdb.db.is_duplicate_flight(
-        parse_result.metadata.drone_serial.as_deref(),
-        parse_result.metadata.battery_serial.as_deref(),
-        parse_result.metadata.start_time,
-    ).unwrap_or(None) {
+    if let Ok(Some(conflict)) = crate::dedupe::find_duplicate(&pdb.db, &parse_result.metadata, pdb.locale) {
         return Ok(Json(SyncFileResponse {
             success: false,
-            message: format!("Duplicate flight (matches '{}')", matching_flight),
+            message: format!("Duplicate flight (matches '{}')", conflict.existing_name),
             file_hash: parse_result.metadata.file_hash.clone(),
         }));
     }
 
     // Insert flight
+    let mut stages = crate::import_profile::StageTimer::start();
     let flight_id = match pdb.db.insert_flight(&parse_result.metadata) {
         Ok(id) => id,
         Err(e) => {
@@ -1677,6 +4716,19 @@ async fn sync_single_file(
         }
     }
 
+    if let Err(e) = pdb.db.save_import_report(&parse_result.import_report(flight_id)) {
+        log::warn!("Failed to save import report: {}", e);
+    }
+    let insert_ms = stages.lap();
+
+    if let Err(e) = crate::hooks::run_pipeline(&pdb.db, flight_id, &config, crate::hooks::HookTrigger::Import) {
+        log::warn!("Post-import hooks failed: {}", e);
+    }
+    crate::import_profile::record(&pdb.db, flight_id, &parse_result, insert_ms, stages.lap());
+    crate::cloud_push::push_in_background(pdb.db.clone(), &config, flight_id, &file_path);
+    pdb.db.finish_import(flight_id);
+    pdb.audit("import", Some(&flight_id.to_string()), serde_json::json!({ "file": filename, "source": "sync" }));
+
     Ok(Json(SyncFileResponse {
         success: true,
         message: "OK".to_string(),
@@ -1808,17 +4860,14 @@ async fn sync_from_folder(
         };
 
         // Check for duplicate flight
-        if let Some(matching_flight) = pdb.db.is_duplicate_flight(
-            parse_result.metadata.drone_serial.as_deref(),
-            parse_result.metadata.battery_serial.as_deref(),
-            parse_result.metadata.start_time,
-        ).unwrap_or(None) {
-            log::debug!("Skipping duplicate flight: {} — matches flight '{}'", file_name, matching_flight);
+        if let Ok(Some(conflict)) = crate::dedupe::find_duplicate(&pdb.db, &parse_result.metadata, pdb.locale) {
+            log::debug!("Skipping duplicate flight: {} — matches flight '{}'", file_name, conflict.existing_name);
             skipped += 1;
             continue;
         }
 
         // Insert flight
+        let mut stages = crate::import_profile::StageTimer::start();
         let flight_id = match pdb.db.insert_flight(&parse_result.metadata) {
             Ok(id) => id,
             Err(e) => {
@@ -1887,6 +4936,19 @@ async fn sync_from_folder(
             }
         }
 
+        if let Err(e) = pdb.db.save_import_report(&parse_result.import_report(flight_id)) {
+            log::warn!("Failed to save import report for {}: {}", file_name, e);
+        }
+        let insert_ms = stages.lap();
+
+        if let Err(e) = crate::hooks::run_pipeline(&pdb.db, flight_id, &config, crate::hooks::HookTrigger::Import) {
+            log::warn!("Post-import hooks failed for {}: {}", file_name, e);
+        }
+        crate::import_profile::record(&pdb.db, flight_id, &parse_result, insert_ms, stages.lap());
+        crate::cloud_push::push_in_background(pdb.db.clone(), &config, flight_id, &file_path);
+        pdb.db.finish_import(flight_id);
+        pdb.audit("import", Some(&flight_id.to_string()), serde_json::json!({ "file": file_name, "source": "sync" }));
+
         processed += 1;
         log::debug!("Synced: {}", file_name);
     }
@@ -1947,6 +5009,11 @@ async fn set_equipment_name(
 ) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
     pdb.db.set_equipment_name(&payload.serial, &payload.equipment_type, &payload.display_name)
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to set equipment name: {}", e)))?;
+    pdb.audit(
+        "settings_change",
+        Some("equipment_name"),
+        serde_json::json!({ "serial": payload.serial, "type": payload.equipment_type, "name": payload.display_name }),
+    );
     Ok(Json(true))
 }
 
@@ -2005,10 +5072,17 @@ struct SwitchProfileResponse {
 
 async fn switch_profile(
     AxumState(state): AxumState<WebAppState>,
+    user: Option<axum::Extension<CurrentUser>>,
+    ClientAddr(client): ClientAddr,
     Json(payload): Json<SwitchProfilePayload>,
 ) -> Result<Json<SwitchProfileResponse>, (StatusCode, Json<ErrorResponse>)> {
     let profile = payload.name.trim().to_string();
 
+    // Any signed-in account may switch, but only admins create profiles
+    if payload.create && user.is_some_and(|u| u.role < Role::Admin) {
+        return Err(err_response(StatusCode::FORBIDDEN, "Only admins can create profiles"));
+    }
+
     // Validate (unless default)
     if profile != "default" {
         database::validate_profile_name(&profile)
@@ -2017,18 +5091,16 @@ async fn switch_profile(
 
     // ── Master password gate (create only) ──
     if payload.create {
-        if let Some(ref hash) = state.master_password_hash {
-            match &payload.master_password {
-                Some(mp) if profile_auth::verify_password(mp, hash) => { /* ok */ }
-                Some(_) => {
-                    log::warn!("Failed master password attempt for profile creation '{}'", profile);
-                    return Err(err_response(StatusCode::FORBIDDEN, "Invalid master password"));
-                }
-                None => {
-                    return Err(err_response(StatusCode::FORBIDDEN, "Master password is required to create profiles"));
-                }
-            }
-        }
+        check_master_password(
+            &state,
+            client.clone(),
+            payload.master_password.as_deref(),
+            "Master password is required to create profiles",
+        )
+        .map_err(|e| {
+            log::warn!("Refused master password for profile creation '{}'", profile);
+            e
+        })?;
     }
 
     // If this is a create request, reject if profile already exists
@@ -2036,30 +5108,17 @@ async fn switch_profile(
         return Err(err_response(StatusCode::CONFLICT, format!("Profile '{}' already exists", profile)));
     }
 
-    // ── Lockout check ──
-    if state.sessions.is_locked_out(&profile) {
-        return Err(err_response(
-            StatusCode::TOO_MANY_REQUESTS,
-            "Too many failed attempts — please wait 60 seconds",
-        ));
-    }
-
-    // ── Password verification for existing protected profiles ──
+    // ── Password verification for existing protected profiles (throttled) ──
     if !payload.create && profile_auth::profile_is_protected(&state.data_dir, &profile) {
-        match &payload.password {
-            Some(pw) => {
-                if let Err(msg) = profile_auth::verify_profile_password(&state.data_dir, &profile, pw) {
-                    let locked = state.sessions.record_failure(&profile);
-                    if locked {
-                        log::warn!("Profile '{}' locked out after too many failed attempts", profile);
-                    }
-                    return Err(err_response(StatusCode::UNAUTHORIZED, msg));
-                }
-            }
-            None => {
-                return Err(err_response(StatusCode::UNAUTHORIZED, "Password is required for this profile"));
-            }
+        let Some(pw) = &payload.password else {
+            return Err(err_response(StatusCode::UNAUTHORIZED, "Password is required for this profile"));
+        };
+        let guard = login_guard(&state, LoginKind::ProfileUnlock, &profile, client)?;
+        if let Err(msg) = profile_auth::verify_profile_password(&state.data_dir, &profile, pw) {
+            guard.failed(&msg);
+            return Err(err_response(StatusCode::UNAUTHORIZED, msg));
         }
+        guard.succeeded();
     }
 
     log::info!("Ensuring profile '{}' exists", profile);
@@ -2102,6 +5161,7 @@ struct DeleteProfileParams {
 
 async fn delete_profile_endpoint(
     AxumState(state): AxumState<WebAppState>,
+    ClientAddr(client): ClientAddr,
     Json(params): Json<DeleteProfileParams>,
 ) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
     let profile = params.name.trim().to_string();
@@ -2111,30 +5171,23 @@ async fn delete_profile_endpoint(
     }
 
     // ── Master password gate ──
-    if let Some(ref hash) = state.master_password_hash {
-        match &params.master_password {
-            Some(mp) if profile_auth::verify_password(mp, hash) => { /* ok */ }
-            Some(_) => {
-                log::warn!("Failed master password attempt for profile deletion '{}'", profile);
-                return Err(err_response(StatusCode::FORBIDDEN, "Invalid master password"));
-            }
-            None => {
-                return Err(err_response(StatusCode::FORBIDDEN, "Master password is required to delete profiles"));
-            }
-        }
-    }
+    check_master_password(
+        &state,
+        client.clone(),
+        params.master_password.as_deref(),
+        "Master password is required to delete profiles",
+    )
+    .map_err(|e| {
+        log::warn!("Refused master password for profile deletion '{}'", profile);
+        e
+    })?;
 
     // ── Profile password gate ──
     if profile_auth::profile_is_protected(&state.data_dir, &profile) {
-        match &params.password {
-            Some(pw) => {
-                profile_auth::verify_profile_password(&state.data_dir, &profile, pw)
-                    .map_err(|e| err_response(StatusCode::UNAUTHORIZED, e))?;
-            }
-            None => {
-                return Err(err_response(StatusCode::UNAUTHORIZED, "Password is required to delete this profile"));
-            }
-        }
+        let Some(pw) = &params.password else {
+            return Err(err_response(StatusCode::UNAUTHORIZED, "Password is required to delete this profile"));
+        };
+        verify_profile_password_guarded(&state, &profile, pw, client)?;
     }
 
     let active = database::get_active_profile(&state.data_dir);
@@ -2153,6 +5206,7 @@ async fn delete_profile_endpoint(
 
     database::delete_profile(&state.data_dir, &profile)
         .map_err(|e| err_response(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    crate::flight_data_cache::invalidate_profile(&state.data_dir, &profile);
 
     Ok(Json(true))
 }
@@ -2161,6 +5215,26 @@ async fn delete_profile_endpoint(
 // PASSWORD MANAGEMENT
 // ============================================================================
 
+/// Check the password of a profile for a change or deletion, throttled and audited
+fn verify_profile_password_guarded(
+    state: &WebAppState,
+    profile: &str,
+    password: &str,
+    client: Option<String>,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    let guard = login_guard(state, LoginKind::ProfilePassword, profile, client)?;
+    match profile_auth::verify_profile_password(&state.data_dir, profile, password) {
+        Ok(()) => {
+            guard.succeeded();
+            Ok(())
+        }
+        Err(e) => {
+            guard.failed(&e);
+            Err(err_response(StatusCode::UNAUTHORIZED, e))
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct SetPasswordPayload {
     profile: String,
@@ -2174,6 +5248,7 @@ struct SetPasswordPayload {
 /// Requires either a valid session token or the current password.
 async fn set_profile_password(
     AxumState(state): AxumState<WebAppState>,
+    ClientAddr(client): ClientAddr,
     Json(payload): Json<SetPasswordPayload>,
 ) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
     let profile = payload.profile.trim().to_string();
@@ -2199,6 +5274,11 @@ async fn set_profile_password(
         None
     };
 
+    // Guessing the current password is throttled like unlocking the profile
+    if let Some(pw) = cur_pw.filter(|_| profile_auth::has_password(&state.data_dir, &profile)) {
+        verify_profile_password_guarded(&state, &profile, pw, client)?;
+    }
+
     profile_auth::set_password(&state.data_dir, &profile, &payload.new_password, cur_pw)
         .map_err(|e| err_response(StatusCode::UNAUTHORIZED, e))?;
 
@@ -2219,10 +5299,14 @@ struct RemovePasswordPayload {
 /// POST /api/profiles/remove_password — Remove a profile password.
 async fn remove_profile_password(
     AxumState(state): AxumState<WebAppState>,
+    ClientAddr(client): ClientAddr,
     Json(payload): Json<RemovePasswordPayload>,
 ) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
     let profile = payload.profile.trim().to_string();
 
+    if profile_auth::has_password(&state.data_dir, &profile) {
+        verify_profile_password_guarded(&state, &profile, &payload.current_password, client)?;
+    }
     profile_auth::remove_password(&state.data_dir, &profile, &payload.current_password)
         .map_err(|e| err_response(StatusCode::UNAUTHORIZED, e))?;
 
@@ -2240,6 +5324,272 @@ async fn has_master_password(
     Json(state.master_password_hash.is_some())
 }
 
+// ============================================================================
+// USERS AND ROLES (multi-user mode)
+// ============================================================================
+
+#[derive(Deserialize)]
+struct LoginPayload {
+    username: String,
+    password: String,
+}
+
+#[derive(Serialize)]
+struct LoginResponse {
+    user: users::UserInfo,
+    /// Token for the `X-User-Session` header
+    session: String,
+}
+
+/// POST /api/users/login — Sign in and get a user session token
+async fn login_user(
+    AxumState(state): AxumState<WebAppState>,
+    ClientAddr(client): ClientAddr,
+    Json(payload): Json<LoginPayload>,
+) -> Result<Json<LoginResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let username = payload.username.trim();
+    let guard = login_guard(&state, LoginKind::UserLogin, username, client)?;
+    let role = match users::verify_login(&state.data_dir, username, &payload.password) {
+        Ok(role) => role,
+        Err(msg) => {
+            guard.failed(&msg);
+            return Err(err_response(StatusCode::UNAUTHORIZED, msg));
+        }
+    };
+    guard.succeeded();
+    let session = state.sessions.create_user_session(username);
+    log::info!("User '{}' signed in", username);
+    Ok(Json(LoginResponse {
+        user: users::UserInfo { username: username.to_string(), role, sso: false },
+        session,
+    }))
+}
+
+/// POST /api/users/logout — End the current user session
+async fn logout_user(
+    AxumState(state): AxumState<WebAppState>,
+    headers: axum::http::HeaderMap,
+) -> Json<bool> {
+    if let Some(token) = headers.get("X-User-Session").and_then(|v| v.to_str().ok()) {
+        state.sessions.revoke_user_token(token);
+    }
+    Json(true)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CurrentUserResponse {
+    /// Whether accounts exist (sign-in required)
+    multi_user: bool,
+    /// The signed-in account, if the session is valid
+    user: Option<users::UserInfo>,
+    /// Whether "Sign in with SSO" is available
+    sso: bool,
+}
+
+/// GET /api/users/me — Whether sign-in is required and who is signed in
+async fn get_current_user(
+    AxumState(state): AxumState<WebAppState>,
+    headers: axum::http::HeaderMap,
+) -> Json<CurrentUserResponse> {
+    let user = resolve_user(&headers, &state)
+        .ok()
+        .flatten()
+        .map(|u| users::UserInfo { username: u.username, role: u.role, sso: false });
+    Json(CurrentUserResponse {
+        multi_user: users::multi_user_enabled(&state.data_dir),
+        user,
+        sso: state.oidc.is_some(),
+    })
+}
+
+#[derive(Deserialize)]
+struct OidcCallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+/// GET /api/auth/oidc/login — Redirect the browser to the OpenID provider
+async fn oidc_login(
+    AxumState(state): AxumState<WebAppState>,
+) -> Result<Redirect, (StatusCode, Json<ErrorResponse>)> {
+    let oidc = state
+        .oidc
+        .as_ref()
+        .ok_or_else(|| err_response(StatusCode::NOT_FOUND, "Single sign-on is not configured"))?;
+    let url = oidc.authorization_url().await.map_err(|e| {
+        log::error!("{}", e);
+        err_response(StatusCode::BAD_GATEWAY, e.to_string())
+    })?;
+    Ok(Redirect::to(&url))
+}
+
+/// GET /api/auth/oidc/callback — Where the OpenID provider sends the browser
+/// back. Signs in the mapped local account and redirects to the app with the
+/// session token (or the error) in the URL fragment.
+async fn oidc_callback(
+    AxumState(state): AxumState<WebAppState>,
+    ClientAddr(client): ClientAddr,
+    Query(params): Query<OidcCallbackQuery>,
+) -> Result<Redirect, (StatusCode, Json<ErrorResponse>)> {
+    let oidc = state
+        .oidc
+        .as_ref()
+        .ok_or_else(|| err_response(StatusCode::NOT_FOUND, "Single sign-on is not configured"))?;
+
+    let result = async {
+        if let Some(error) = params.error {
+            return Err(params.error_description.unwrap_or(error));
+        }
+        let (Some(code), Some(oidc_state)) = (params.code, params.state) else {
+            return Err("Missing code or state".to_string());
+        };
+        let identity = oidc.complete(&code, &oidc_state).await.map_err(|e| e.to_string())?;
+        users::resolve_external(
+            &state.data_dir,
+            &identity.id,
+            &identity.username,
+            oidc.config.default_role,
+            oidc.config.link_by_username && identity.email_verified,
+        )
+    }
+    .await;
+
+    // The provider authenticates, so single sign-on is audited but not throttled
+    let audit = |subject: &str, outcome: LoginOutcome, detail: Option<&str>| {
+        crate::login_audit::record(
+            &state.data_dir,
+            &crate::login_audit::LoginEvent {
+                time: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+                kind: LoginKind::Sso,
+                subject: subject.to_string(),
+                client: client.clone(),
+                outcome,
+                detail: detail.map(str::to_string),
+            },
+        )
+    };
+    match result {
+        Ok(user) => {
+            let session = state.sessions.create_user_session(&user.username);
+            log::info!("User '{}' signed in with single sign-on", user.username);
+            audit(&user.username, LoginOutcome::Success, None);
+            Ok(Redirect::to(&oidc.result_url("user_session", &session)))
+        }
+        Err(msg) => {
+            log::warn!("Single sign-on failed: {}", msg);
+            audit("", LoginOutcome::Failure, Some(&msg));
+            Ok(Redirect::to(&oidc.result_url("sso_error", &msg)))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LoginAuditQuery {
+    limit: Option<usize>,
+    /// `success`, `failure` or `locked_out`
+    outcome: Option<LoginOutcome>,
+}
+
+/// GET /api/auth/login_audit — Latest sign-in attempts, newest first (admin)
+async fn get_login_audit(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<LoginAuditQuery>,
+) -> Json<Vec<crate::login_audit::LoginEvent>> {
+    let limit = params.limit.unwrap_or(200).clamp(1, 5000);
+    Json(crate::login_audit::recent(&state.data_dir, limit, params.outcome))
+}
+
+/// GET /api/users — List accounts (admin)
+async fn list_users(AxumState(state): AxumState<WebAppState>) -> Json<Vec<users::UserInfo>> {
+    Json(users::list_users(&state.data_dir))
+}
+
+#[derive(Deserialize)]
+struct CreateUserPayload {
+    username: String,
+    /// Omitted for accounts that only sign in with single sign-on
+    password: Option<String>,
+    role: Role,
+    /// Required for the first account when PROFILE_CREATION_PASS is set
+    master_password: Option<String>,
+}
+
+/// POST /api/users — Create an account (admin). The first account must be
+/// an admin and switches the server to multi-user mode.
+async fn create_user(
+    AxumState(state): AxumState<WebAppState>,
+    ClientAddr(client): ClientAddr,
+    Json(payload): Json<CreateUserPayload>,
+) -> Result<Json<users::UserInfo>, (StatusCode, Json<ErrorResponse>)> {
+    if !users::multi_user_enabled(&state.data_dir) {
+        check_master_password(
+            &state,
+            client,
+            payload.master_password.as_deref(),
+            "Master password is required to create the first account",
+        )?;
+    }
+    if payload.password.is_none() && state.oidc.is_none() {
+        return Err(err_response(StatusCode::BAD_REQUEST, "Password is required"));
+    }
+    users::create_user(&state.data_dir, &payload.username, payload.password.as_deref(), payload.role)
+        .map(Json)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))
+}
+
+#[derive(Deserialize)]
+struct UsernameQuery {
+    username: String,
+}
+
+/// DELETE /api/users?username=... — Delete an account (admin)
+async fn delete_user(
+    AxumState(state): AxumState<WebAppState>,
+    Query(params): Query<UsernameQuery>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    users::delete_user(&state.data_dir, &params.username)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    state.sessions.revoke_user(&params.username);
+    Ok(Json(true))
+}
+
+#[derive(Deserialize)]
+struct SetUserRolePayload {
+    username: String,
+    role: Role,
+}
+
+/// POST /api/users/role — Change an account's role (admin)
+async fn set_user_role(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<SetUserRolePayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    users::set_role(&state.data_dir, &payload.username, payload.role)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    Ok(Json(true))
+}
+
+#[derive(Deserialize)]
+struct SetUserPasswordPayload {
+    username: String,
+    new_password: String,
+}
+
+/// POST /api/users/password — Reset an account's password (admin); signs
+/// the account out everywhere
+async fn set_user_password(
+    AxumState(state): AxumState<WebAppState>,
+    Json(payload): Json<SetUserPasswordPayload>,
+) -> Result<Json<bool>, (StatusCode, Json<ErrorResponse>)> {
+    users::set_password(&state.data_dir, &payload.username, &payload.new_password)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, e))?;
+    state.sessions.revoke_user(&payload.username);
+    Ok(Json(true))
+}
+
 // ============================================================================
 // SUPPORTER BADGE (server-side verification)
 // ============================================================================
@@ -2338,67 +5688,269 @@ async fn set_donation_acknowledged(
 // SERVER SETUP
 // ============================================================================
 
+/// Current version of the REST API, served under `/api/v<N>/`
+pub const API_VERSION: u32 = 1;
+
+/// All REST routes, relative to the API prefix, grouped by the role they
+/// require in multi-user mode
+fn api_routes(state: &WebAppState) -> Router<WebAppState> {
+    // Reachable without signing in
+    let public = Router::new()
+        .route("/summary", get(get_summary))
+        .route("/users/login", post(login_user))
+        .route("/users/logout", post(logout_user))
+        .route("/users/me", get(get_current_user))
+        .route("/auth/oidc/login", get(oidc_login))
+        .route("/auth/oidc/callback", get(oidc_callback))
+        .route("/profiles/has_master_password", get(has_master_password));
+
+    // Read-only access to everything
+    let viewer = Router::new()
+        .route("/import_queue", get(get_import_queue))
+        .route("/import_status", get(get_import_status))
+        .route("/flights", get(get_flights))
+        .route("/flight_data", get(get_flight_data))
+        .route("/flight_data/limits", get(get_max_points_config))
+        .route("/flights/import_report", get(get_import_report))
+        .route("/flights/rth", get(get_rth_events))
+        .route("/flights/top", get(get_top_flights))
+        .route("/flights/null_columns", get(get_null_telemetry_columns))
+        .route("/flights/previews", get(get_flight_previews))
+        .route("/graphql", post(graphql_handler))
+        .route("/overview", get(get_overview_stats))
+        .route("/histogram", get(get_histogram))
+        .route("/battery_capacity_history", get(get_battery_full_capacity_history))
+        .route("/tags", get(get_all_tags))
+        .route("/settings/smart_tags", get(get_smart_tags_enabled))
+        .route("/settings/enabled_tag_types", get(get_enabled_tag_types))
+        .route("/settings/value", get(get_setting_value))
+        .route("/settings/limits", get(get_exceedance_limits))
+        .route("/settings/altitude_reference", get(get_altitude_reference))
+        .route("/settings/duration_rounding", get(get_duration_rounding))
+        .route("/settings/units", get(get_unit_settings))
+        .route("/requirements", get(get_requirements))
+        .route("/requirements/status", get(evaluate_requirements))
+        .route("/stats/export", get(export_stats))
+        .route("/export_templates", get(get_export_templates))
+        .route("/export_templates/render", get(export_with_template))
+        .route("/year_review", get(get_year_review))
+        .route("/activity/calendar", get(get_activity_calendar))
+        .route("/activity/stats", get(get_activity_stats))
+        .route("/flights/render", get(render_flight_image))
+        .route("/flights/bundle", get(export_flight_bundle))
+        .route("/flights/chase", get(export_chase))
+        .route("/flights/plan", get(get_flight_plan))
+        .route("/privacy/settings", get(get_privacy_settings))
+        .route("/filename_rules", get(get_filename_rules))
+        .route("/derived_metrics", get(get_derived_metrics))
+        .route("/preflight", get(get_preflight_briefing))
+        .route("/preflight/settings", get(get_preflight_settings))
+        .route("/cloud_push/settings", get(get_cloud_push_settings))
+        .route("/sharing/settings", get(get_sharing_settings))
+        .route("/notifications/settings", get(get_notification_settings))
+        .route("/notifications/subscribe", post(subscribe_push))
+        .route("/notifications/unsubscribe", post(unsubscribe_push))
+        .route("/fleet", get(get_fleet_overview))
+        .route("/analytics/endurance", get(get_endurance_benchmarks))
+        .route("/analytics/places", get(get_place_stats))
+        .route("/maintenance", get(get_maintenance_records))
+        .route("/airframe_seeds", get(get_airframe_seeds))
+        .route("/location_overrides", get(get_location_overrides))
+        .route("/battery_retirement", get(get_battery_retirement))
+        .route("/diagnostics/imports", get(get_import_diagnostics))
+        .route("/jobs", get(get_jobs))
+        .route("/jobs/stats", get(get_job_stats))
+        .route("/jobs/export", get(export_job))
+        .route("/reports/invoice", get(invoice_report))
+        .route("/reports/operator_log", get(operator_log_report))
+        .route("/reports/overflight", post(overflight_report))
+        .route("/reports/flight_list", post(flight_list_report))
+        .route("/integrity/chain", get(get_integrity_chain))
+        .route("/integrity/verify", get(verify_integrity))
+        .route("/operator/settings", get(get_operator_settings))
+        .route("/attachments", get(get_attachments))
+        .route("/attachments/download", get(download_attachment))
+        .route("/checklists/templates", get(get_checklist_templates))
+        .route("/checklists/records", get(get_checklist_records))
+        .route("/hooks", get(get_post_import_hooks))
+        .route("/has_api_key", get(has_api_key))
+        .route("/api_key_type", get(get_api_key_type))
+        .route("/app_data_dir", get(get_app_data_dir))
+        .route("/db_lock_status", get(get_db_lock_status))
+        .route("/database/info", get(get_database_info))
+        .route("/db_mirror", get(get_db_mirror))
+        .route("/duckdb/extensions", get(get_duckdb_extensions))
+        .route("/recovery", get(get_recovery_status))
+        .route("/recovery/candidates", get(list_recovery_candidates))
+        .route("/app_log_dir", get(get_app_log_dir))
+        .route("/logs/level", get(get_log_level))
+        .route("/allowed_log_extensions", get(get_allowed_log_extensions))
+        .route("/sync/config", get(get_sync_config))
+        .route("/sync/blacklist", get(get_sync_blacklist))
+        .route("/sync/files", get(get_sync_files))
+        .route("/equipment_names", get(get_equipment_names))
+        .route("/profiles", get(list_profiles))
+        .route("/profiles/active", get(get_active_profile))
+        .route("/profiles/switch", post(switch_profile))
+        .route("/supporter/status", get(get_supporter_status))
+        .route("/supporter/donation", get(get_donation_acknowledged))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), require_viewer));
+
+    // Importing, and editing the caller's own flights (checked per handler)
+    let editor = Router::new()
+        .route("/import", post(import_log))
+        .route("/import_queue", post(enqueue_imports))
+        .route("/manual_flight", post(create_manual_flight))
+        .route("/flights/fuse_dat", post(fuse_dat_log))
+        .route("/flights/delete", delete(delete_flight))
+        .route("/flights/name", put(update_flight_name))
+        .route("/flights/notes", put(update_flight_notes))
+        .route("/flights/color", put(update_flight_color))
+        .route("/flights/metadata", put(update_flight_metadata))
+        .route("/flights/tags/add", post(add_flight_tag))
+        .route("/flights/tags/remove", post(remove_flight_tag))
+        .route("/flights/plan", post(attach_flight_plan).delete(delete_flight_plan))
+        .route("/flights/manual_track", post(attach_manual_track))
+        .route("/flights/reprocess", post(reprocess_flight))
+        .route("/flights/reparse", post(reparse_flight))
+        .route("/flights/recompute_stats", post(recompute_flight_stats))
+        .route("/regenerate_flight_smart_tags/:id", post(regenerate_flight_smart_tags))
+        .route("/cloud_push/flight", post(push_flight_to_cloud))
+        .route("/flights/share", post(share_flight))
+        .route("/maintenance", post(save_maintenance_record).delete(delete_maintenance_record))
+        .route("/airframe_seeds", post(set_airframe_seed))
+        .route("/location_overrides", post(set_location_overrides))
+        .route("/jobs", post(save_job).delete(delete_job))
+        .route("/jobs/assign", post(assign_flights_to_job))
+        .route("/requirements", post(save_requirement).delete(delete_requirement))
+        .route("/export_templates", post(save_export_template).delete(delete_export_template))
+        .route("/attachments", post(add_attachment))
+        .route("/checklists/templates", post(save_checklist_template).delete(delete_checklist_template))
+        .route("/checklists/records", post(record_checklist).delete(delete_checklist_record))
+        .route("/equipment_names", post(set_equipment_name))
+        .route("/sync/file", post(sync_single_file))
+        .route("/sync", post(sync_from_folder))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), require_editor));
+
+    // Logbook-wide changes, server configuration and accounts
+    let admin = Router::new()
+        .route("/import_queue", delete(clear_import_queue))
+        .route("/logs/level", post(set_log_level))
+        .route("/logs/tail", get(tail_log))
+        .route("/support_bundle", get(export_support_bundle))
+        .route("/flights/delete_all", delete(delete_all_flights))
+        .route("/flights/deduplicate", post(deduplicate_flights))
+        .route("/tags/remove_auto", post(remove_all_auto_tags))
+        .route("/regenerate_smart_tags", post(regenerate_smart_tags))
+        .route("/settings/smart_tags", post(set_smart_tags_enabled))
+        .route("/settings/enabled_tag_types", post(set_enabled_tag_types))
+        .route("/settings/value", post(set_setting_value))
+        .route("/settings/limits", post(set_exceedance_limits))
+        .route("/settings/altitude_reference", post(set_altitude_reference))
+        .route("/settings/duration_rounding", post(set_duration_rounding))
+        .route("/settings/units", post(set_unit_settings))
+        .route("/privacy/settings", post(set_privacy_settings))
+        .route("/filename_rules", post(set_filename_rules))
+        .route("/derived_metrics", post(set_derived_metrics))
+        .route("/preflight/settings", post(set_preflight_settings))
+        .route("/battery_retirement/settings", get(get_battery_retirement_settings).post(set_battery_retirement_settings))
+        .route("/email_report/settings", get(get_email_report_settings).post(set_email_report_settings))
+        .route("/email_report/send", post(send_email_report))
+        .route("/notifications/settings", post(set_notification_settings))
+        .route("/operator/settings", post(set_operator_settings))
+        .route("/cloud_push/settings", post(set_cloud_push_settings))
+        .route("/sharing/settings", post(set_sharing_settings))
+        .route("/audit_log", get(get_audit_log))
+        .route("/sql/query", post(run_sql_query))
+        .route("/integrity/seal", post(seal_integrity_chain))
+        .route("/demo/seed", post(seed_demo_data))
+        .route("/attachments", delete(delete_attachment))
+        .route("/hooks/enabled", post(set_post_import_hook_enabled))
+        .route("/set_api_key", post(set_api_key))
+        .route("/remove_api_key", delete(remove_api_key))
+        .route("/backup", get(export_backup))
+        .route("/backup/restore", post(import_backup))
+        .route("/db_mirror", post(set_db_mirror))
+        .route("/db_mirror/publish", post(publish_db_mirror))
+        .route("/duckdb/offline", post(set_duckdb_offline_mode))
+        .route("/recovery/dismiss", post(dismiss_recovery_report))
+        .route("/recovery/restore", post(restore_recovery_copy))
+        .route("/recovery/candidates/restore", post(restore_recovery_candidate))
+        .route("/sync/blacklist", post(add_sync_blacklist).delete(remove_sync_blacklist))
+        .route("/sync/blacklist/all", delete(clear_sync_blacklist))
+        .route("/profiles/delete", post(delete_profile_endpoint))
+        .route("/profiles/set_password", post(set_profile_password))
+        .route("/profiles/remove_password", post(remove_profile_password))
+        .route("/supporter/verify", post(verify_supporter_code))
+        .route("/supporter/remove", post(remove_supporter_badge))
+        .route("/supporter/donation", post(set_donation_acknowledged))
+        .route("/users", get(list_users).post(create_user).delete(delete_user))
+        .route("/users/role", post(set_user_role))
+        .route("/users/password", post(set_user_password))
+        .route("/auth/login_audit", get(get_login_audit))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), require_admin));
+
+    public.merge(viewer).merge(editor).merge(admin)
+}
+
+/// Report the API version on every response
+async fn add_api_version_header(mut response: axum::response::Response) -> axum::response::Response {
+    response.headers_mut().insert(
+        axum::http::HeaderName::from_static("x-api-version"),
+        axum::http::HeaderValue::from(API_VERSION),
+    );
+    response
+}
+
+/// Mark responses served from the unversioned `/api/...` paths as deprecated
+async fn mark_unversioned_deprecated(mut response: axum::response::Response) -> axum::response::Response {
+    response.headers_mut().insert(
+        axum::http::HeaderName::from_static("deprecation"),
+        axum::http::HeaderValue::from_static("true"),
+    );
+    response
+}
+
+/// GET /api/v1/version — API and application version
+async fn get_api_version() -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "apiVersion": API_VERSION,
+        "appVersion": env!("CARGO_PKG_VERSION"),
+    }))
+}
+
+/// Span of an API request, named after its route (exported by `otel`)
+fn request_span(request: &Request) -> tracing::Span {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or(request.uri().path(), |p| p.as_str());
+    tracing::info_span!(
+        "request",
+        otel.name = %format!("{} {}", request.method(), route),
+        http.method = %request.method(),
+        http.route = route,
+    )
+}
+
 /// Build the Axum router with all API routes
 pub fn build_router(state: WebAppState) -> Router {
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+    let cors = crate::web_security::cors_layer(crate::web_security::security_config());
+
+    // Layered on the routes (not the outer router) so the route is known
+    let api = api_routes(&state).route("/version", get(get_api_version)).layer(
+        TraceLayer::new_for_http()
+            .make_span_with(request_span)
+            .on_response(DefaultOnResponse::new().level(tracing::Level::INFO)),
+    );
 
     Router::new()
-        .route("/api/import", post(import_log))
-        .route("/api/manual_flight", post(create_manual_flight))
-        .route("/api/flights", get(get_flights))
-        .route("/api/flight_data", get(get_flight_data))
-        .route("/api/overview", get(get_overview_stats))
-        .route("/api/battery_capacity_history", get(get_battery_full_capacity_history))
-        .route("/api/flights/delete", delete(delete_flight))
-        .route("/api/flights/delete_all", delete(delete_all_flights))
-        .route("/api/flights/deduplicate", post(deduplicate_flights))
-        .route("/api/flights/name", put(update_flight_name))
-        .route("/api/flights/notes", put(update_flight_notes))
-        .route("/api/flights/color", put(update_flight_color))
-        .route("/api/flights/tags/add", post(add_flight_tag))
-        .route("/api/flights/tags/remove", post(remove_flight_tag))
-        .route("/api/tags", get(get_all_tags))
-        .route("/api/tags/remove_auto", post(remove_all_auto_tags))
-        .route("/api/settings/smart_tags", get(get_smart_tags_enabled))
-        .route("/api/settings/smart_tags", post(set_smart_tags_enabled))
-        .route("/api/settings/enabled_tag_types", get(get_enabled_tag_types))
-        .route("/api/settings/enabled_tag_types", post(set_enabled_tag_types))
-        .route("/api/settings/value", get(get_setting_value))
-        .route("/api/settings/value", post(set_setting_value))
-        .route("/api/regenerate_smart_tags", post(regenerate_smart_tags))
-        .route("/api/regenerate_flight_smart_tags/:id", post(regenerate_flight_smart_tags))
-        .route("/api/has_api_key", get(has_api_key))
-        .route("/api/api_key_type", get(get_api_key_type))
-        .route("/api/set_api_key", post(set_api_key))
-        .route("/api/remove_api_key", delete(remove_api_key))
-        .route("/api/app_data_dir", get(get_app_data_dir))
-        .route("/api/app_log_dir", get(get_app_log_dir))
-        .route("/api/allowed_log_extensions", get(get_allowed_log_extensions))
-        .route("/api/backup", get(export_backup))
-        .route("/api/backup/restore", post(import_backup))
-        .route("/api/sync/config", get(get_sync_config))
-        .route("/api/sync/blacklist", get(get_sync_blacklist).post(add_sync_blacklist).delete(remove_sync_blacklist))
-        .route("/api/sync/blacklist/all", delete(clear_sync_blacklist))
-        .route("/api/sync/files", get(get_sync_files))
-        .route("/api/sync/file", post(sync_single_file))
-        .route("/api/sync", post(sync_from_folder))
-        .route("/api/equipment_names", get(get_equipment_names))
-        .route("/api/equipment_names", post(set_equipment_name))
-        .route("/api/profiles", get(list_profiles))
-        .route("/api/profiles/active", get(get_active_profile))
-        .route("/api/profiles/switch", post(switch_profile))
-        .route("/api/profiles/delete", post(delete_profile_endpoint))
-        .route("/api/profiles/set_password", post(set_profile_password))
-        .route("/api/profiles/remove_password", post(remove_profile_password))
-        .route("/api/profiles/has_master_password", get(has_master_password))
-        .route("/api/supporter/verify", post(verify_supporter_code))
-        .route("/api/supporter/status", get(get_supporter_status))
-        .route("/api/supporter/remove", post(remove_supporter_badge))
-        .route("/api/supporter/donation", get(get_donation_acknowledged))
-        .route("/api/supporter/donation", post(set_donation_acknowledged))
+        .nest(&format!("/api/v{}", API_VERSION), api.clone())
+        // Compatibility shim: the pre-versioning paths keep working as an
+        // alias of v1 so existing scripts against the Docker image don't break
+        .nest("/api", api.layer(axum::middleware::map_response(mark_unversioned_deprecated)))
+        .layer(axum::middleware::map_response(add_api_version_header))
+        .layer(axum::middleware::map_response(crate::web_security::add_security_headers))
         .layer(cors)
         .layer(DefaultBodyLimit::max(250 * 1024 * 1024)) // 250 MB
         .with_state(state)
@@ -2412,6 +5964,30 @@ pub async fn start_server(data_dir: PathBuf) -> Result<(), Box<dyn std::error::E
 
     let db = Database::new(data_dir.clone(), &profile)?;
 
+    // Language of messages for requests without Accept-Language and for background jobs
+    if let Ok(locale) = std::env::var("LOCALE") {
+        match messages::set_default_locale(&locale) {
+            Ok(locale) => log::info!("Default message locale: {}", locale),
+            Err(e) => log::warn!("Ignoring LOCALE: {}", e),
+        }
+    }
+
+    // Public demo instances start with sample flights instead of an empty logbook
+    let demo_mode = std::env::var("DEMO_MODE").map_or(false, |v| v == "true" || v == "1");
+    if demo_mode && db.get_all_flights().map_or(false, |f| f.is_empty()) {
+        let config: serde_json::Value = std::fs::read_to_string(database::config_path_for_profile(&data_dir, &profile))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+        match crate::demo::seed_demo_data(&db, &config) {
+            Ok(ids) => {
+                log::info!("DEMO_MODE: seeded {} demo flights", ids.len());
+                db.audit("startup", "seed_demo_data", None, serde_json::json!({ "flights": ids }));
+            }
+            Err(e) => log::error!("DEMO_MODE: failed to seed demo flights: {}", e),
+        }
+    }
+
     crate::plugins::log_plugin_registration(&data_dir);
     let allowed_extensions = crate::plugins::get_allowed_extensions(&data_dir);
     log::info!("Allowed import extensions at startup: {:?}", allowed_extensions);
@@ -2431,11 +6007,48 @@ pub async fn start_server(data_dir: PathBuf) -> Result<(), Box<dyn std::error::E
         _ => None,
     };
 
+    // ── Read-only token for /api/summary, kept only as a hash ──
+    let summary_token_hash = match std::env::var("SUMMARY_API_TOKEN") {
+        Ok(val) if !val.is_empty() => {
+            std::env::remove_var("SUMMARY_API_TOKEN");
+            log::info!("Dashboard summary token (SUMMARY_API_TOKEN) configured");
+            Some(sha256_hex(&val))
+        }
+        _ => None,
+    };
+
+    // ── OpenID Connect sign-in ──
+    let oidc = crate::oidc::OidcConfig::from_env().map(|config| {
+        log::info!("Single sign-on enabled: issuer={}, client_id={}", config.issuer_url, config.client_id);
+        std::env::remove_var("OIDC_CLIENT_SECRET");
+        Arc::new(OidcClient::new(config))
+    });
+
+    // ── SMTP for the scheduled summary emails ──
+    let smtp = crate::email_report::SmtpConfig::from_env().map(|config| {
+        log::info!("Summary emails enabled: smtp={}:{}, from={}", config.host, config.port, config.from);
+        std::env::remove_var("SMTP_PASSWORD");
+        Arc::new(config)
+    });
+
+    // ── Web Push for notifications ──
+    let vapid_public_key = crate::web_push::VapidConfig::from_env().map(|config| {
+        log::info!("Web Push notifications enabled");
+        std::env::remove_var("VAPID_PRIVATE_KEY");
+        let public_key = config.public_key.clone();
+        crate::notifications::set_delivery(crate::web_push::delivery(Arc::new(config)));
+        public_key
+    });
+
     let state = WebAppState {
         databases: Arc::new(std::sync::RwLock::new(initial_pool)),
         data_dir,
         sessions: Arc::new(SessionStore::new()),
         master_password_hash,
+        summary_token_hash,
+        oidc,
+        smtp,
+        vapid_public_key,
     };
 
     // Start the scheduled sync if SYNC_INTERVAL and SYNC_LOGS_PATH are configured
@@ -2455,6 +6068,53 @@ pub async fn start_server(data_dir: PathBuf) -> Result<(), Box<dyn std::error::E
         log::info!("SYNC_LOGS_PATH configured but SYNC_INTERVAL not set. Sync is manual-only (via Sync button in web interface).");
     }
 
+    if state.smtp.is_some() {
+        let scheduler_state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = start_email_report_scheduler(scheduler_state).await {
+                log::error!("Failed to start summary email scheduler: {}", e);
+            }
+        });
+    }
+
+    // Scheduled backups of every profile if BACKUP_PATH and BACKUP_INTERVAL are configured
+    if let (Ok(backup_path), Ok(backup_interval)) = (std::env::var("BACKUP_PATH"), std::env::var("BACKUP_INTERVAL")) {
+        log::info!("Scheduled backup enabled: path={}, interval={}", backup_path, backup_interval);
+        let scheduler_state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = start_backup_scheduler(scheduler_state, PathBuf::from(backup_path), &backup_interval).await {
+                log::error!("Failed to start backup scheduler: {}", e);
+            }
+        });
+    }
+
+    // Checkpoint and compact the open profile databases while they are idle
+    let maintenance_databases = state.databases.clone();
+    tokio::spawn(crate::db_maintenance::run_scheduler(move || {
+        maintenance_databases.read().unwrap().values().cloned().collect()
+    }));
+
+    // Publish the read-only mirrors of the profiles that enabled them
+    let mirror_state = state.clone();
+    tokio::spawn(crate::db_mirror::run_scheduler(move || {
+        database::list_profiles(&mirror_state.data_dir)
+            .into_iter()
+            .filter_map(|profile| {
+                let config_path = database::config_path_for_profile(&mirror_state.data_dir, &profile);
+                let settings = crate::db_mirror::MirrorSettings::load(&config_path);
+                if !settings.enabled {
+                    return None;
+                }
+                let db = mirror_state
+                    .db_for_profile(&profile)
+                    .map_err(|e| log::warn!("Database mirror: {}", e))
+                    .ok()?;
+                let dest = crate::db_mirror::web_mirror_path(&mirror_state.data_dir, &profile);
+                Some((db, dest, settings.interval_minutes))
+            })
+            .collect()
+    }));
+
     let router = build_router(state);
 
     let host = std::env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
@@ -2464,7 +6124,8 @@ pub async fn start_server(data_dir: PathBuf) -> Result<(), Box<dyn std::error::E
     log::info!("Starting Open DroneLog web server on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, router).await?;
+    // Peer addresses for login throttling (see `client_addr`)
+    axum::serve(listener, router.into_make_service_with_connect_info::<std::net::SocketAddr>()).await?;
 
     Ok(())
 }
@@ -2513,6 +6174,143 @@ async fn start_sync_scheduler(state: WebAppState, cron_expr: &str) -> Result<(),
     }
 }
 
+/// Check every day at 07:00 UTC for profiles whose summary email is due
+async fn start_email_report_scheduler(state: WebAppState) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let sched = JobScheduler::new().await?;
+    let job = Job::new_async("0 0 7 * * *", move |_uuid, _lock| {
+        let state = state.clone();
+        Box::pin(async move {
+            run_scheduled_email_reports(&state).await;
+        })
+    })?;
+    sched.add(job).await?;
+    sched.start().await?;
+    log::info!("Summary email scheduler started (daily at 07:00 UTC)");
+
+    // Keep the scheduler running
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+    }
+}
+
+/// Start the cron scheduler for the backups of all profiles
+async fn start_backup_scheduler(
+    state: WebAppState,
+    backup_dir: PathBuf,
+    cron_expr: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let sched = JobScheduler::new().await?;
+    cron_expr.parse::<cron::Schedule>()
+        .map_err(|e| format!("Invalid cron expression '{}': {}", cron_expr, e))?;
+    let keep = std::env::var("BACKUP_KEEP").ok().and_then(|v| v.parse::<usize>().ok()).unwrap_or(7).max(1);
+
+    let job = Job::new_async(cron_expr, move |_uuid, _lock| {
+        let state = state.clone();
+        let backup_dir = backup_dir.clone();
+        Box::pin(async move {
+            run_scheduled_backup(&state, &backup_dir, keep);
+        })
+    })?;
+    sched.add(job).await?;
+    sched.start().await?;
+    log::info!("Backup scheduler started with cron expression: {} (keeping {} per profile)", cron_expr, keep);
+
+    // Keep the scheduler running
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+    }
+}
+
+/// Write a backup of every profile to `{backup_dir}/{profile}/`, keeping the newest `keep`
+fn run_scheduled_backup(state: &WebAppState, backup_dir: &std::path::Path, keep: usize) {
+    const SUFFIX: &str = "_Open_Dronelog.db.backup";
+    let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+
+    for profile in database::list_profiles(&state.data_dir) {
+        let db = match state.db_for_profile(&profile) {
+            Ok(d) => d,
+            Err(e) => {
+                log::warn!("Scheduled backup: {}", e);
+                continue;
+            }
+        };
+        let profile_dir = backup_dir.join(&profile);
+        let dest = profile_dir.join(format!("{}{}", timestamp, SUFFIX));
+        let result = std::fs::create_dir_all(&profile_dir)
+            .map_err(|e| e.to_string())
+            .and_then(|_| db.export_backup(&dest, false).map_err(|e| e.to_string()));
+
+        let error = match result {
+            Ok(()) => {
+                log::info!("Scheduled backup of profile '{}' written to {}", profile, dest.display());
+                db.audit("scheduler", "backup", Some(&dest.display().to_string()), serde_json::json!({}));
+
+                // Prune older backups (file names sort by time)
+                let mut backups: Vec<PathBuf> = std::fs::read_dir(&profile_dir)
+                    .map(|entries| {
+                        entries
+                            .filter_map(|e| e.ok().map(|e| e.path()))
+                            .filter(|p| p.file_name().is_some_and(|n| n.to_string_lossy().ends_with(SUFFIX)))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                backups.sort();
+                let excess = backups.len().saturating_sub(keep);
+                for old in &backups[..excess] {
+                    if let Err(e) = std::fs::remove_file(old) {
+                        log::warn!("Scheduled backup: failed to remove {}: {}", old.display(), e);
+                    }
+                }
+                None
+            }
+            Err(e) => {
+                log::error!("Scheduled backup of profile '{}' failed: {}", profile, e);
+                Some(e)
+            }
+        };
+
+        let config_path = database::config_path_for_profile(&state.data_dir, &profile);
+        crate::notifications::notify_profile(&db, &config_path, &crate::notifications::NotificationEvent::BackupCompleted {
+            destination: dest.display().to_string(),
+            error,
+        });
+    }
+}
+
+/// Send the summary emails due today for all profiles
+async fn run_scheduled_email_reports(state: &WebAppState) {
+    let Some(smtp) = state.smtp.clone() else {
+        return;
+    };
+    let today = chrono::Utc::now().date_naive();
+    for profile in database::list_profiles(&state.data_dir) {
+        let config_path = database::config_path_for_profile(&state.data_dir, &profile);
+        let settings = crate::email_report::EmailReportSettings::load(&config_path);
+        if !settings.enabled || !crate::email_report::is_due(settings.frequency, today) {
+            continue;
+        }
+        let db = match state.db_for_profile(&profile) {
+            Ok(d) => d,
+            Err(e) => {
+                log::warn!("Summary email: {}", e);
+                continue;
+            }
+        };
+        let units = crate::units::UnitSettings::load(&config_path);
+        let result = match crate::email_report::build_report(&db, &profile, settings.frequency, &units, today) {
+            Ok((subject, body)) => smtp.send(&settings.recipients, &subject, body).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => {
+                log::info!("Summary email for profile '{}' sent to {} recipient(s)", profile, settings.recipients.len());
+                db.audit("scheduler", "email_report_sent", Some("email_report"), serde_json::json!({ "recipients": settings.recipients }));
+            }
+            Err(e) => log::error!("Summary email for profile '{}' failed: {}", profile, e),
+        }
+    }
+}
+
 /// Run the folder sync operation for ALL profiles (called by scheduler).
 /// Each profile syncs from its own subfolder: base for "default", base/{profile} for others.
 async fn run_scheduled_sync(state: &WebAppState) -> Result<(usize, usize, usize), String> {
@@ -2574,6 +6372,10 @@ async fn run_scheduled_sync(state: &WebAppState) -> Result<(usize, usize, usize)
                 continue;
             }
         };
+        if db.is_read_only() {
+            log::warn!("Scheduled sync: profile '{}' is open read-only (locked by another process), skipping", profile);
+            continue;
+        }
 
         let parser = LogParser::new(&db);
 
@@ -2588,6 +6390,7 @@ async fn run_scheduled_sync(state: &WebAppState) -> Result<(usize, usize, usize)
             serde_json::json!({})
         };
         let tags_enabled = config.get("smart_tags_enabled").and_then(|v| v.as_bool()).unwrap_or(true);
+        let counts_before = (total_processed, total_skipped, total_errors);
 
         for file_path in &log_files {
             let file_name = file_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
@@ -2613,16 +6416,13 @@ async fn run_scheduled_sync(state: &WebAppState) -> Result<(usize, usize, usize)
             };
 
             // Check for duplicate flight
-            if db.is_duplicate_flight(
-                parse_result.metadata.drone_serial.as_deref(),
-                parse_result.metadata.battery_serial.as_deref(),
-                parse_result.metadata.start_time,
-            ).unwrap_or(None).is_some() {
+            if crate::dedupe::find_duplicate(&db, &parse_result.metadata, messages::default_locale()).unwrap_or(None).is_some() {
                 total_skipped += 1;
                 continue;
             }
 
             // Insert flight
+            let mut stages = crate::import_profile::StageTimer::start();
             let flight_id = match db.insert_flight(&parse_result.metadata) {
                 Ok(id) => id,
                 Err(e) => {
@@ -2690,9 +6490,34 @@ async fn run_scheduled_sync(state: &WebAppState) -> Result<(usize, usize, usize)
                 }
             }
 
+            if let Err(e) = db.save_import_report(&parse_result.import_report(flight_id)) {
+                log::warn!("Scheduled sync [{}]: Failed to save import report for {}: {}", profile, file_name, e);
+            }
+            let insert_ms = stages.lap();
+
+            if let Err(e) = crate::hooks::run_pipeline(&db, flight_id, &config, crate::hooks::HookTrigger::Import) {
+                log::warn!("Scheduled sync [{}]: Post-import hooks failed for {}: {}", profile, file_name, e);
+            }
+            crate::import_profile::record(&db, flight_id, &parse_result, insert_ms, stages.lap());
+            crate::cloud_push::push_in_background(db.clone(), &config, flight_id, file_path);
+            db.finish_import(flight_id);
+            db.audit("sync", "import", Some(&flight_id.to_string()), serde_json::json!({ "file": file_name }));
+
             total_processed += 1;
             log::debug!("Scheduled sync [{}]: Imported {}", profile, file_name);
         }
+
+        let imported = total_processed - counts_before.0;
+        let errors = total_errors - counts_before.2;
+        if imported > 0 || errors > 0 {
+            let source = sync_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| profile.clone());
+            crate::notifications::notify(&db, &config, &crate::notifications::NotificationEvent::ImportCompleted {
+                source,
+                imported,
+                skipped: total_skipped - counts_before.1,
+                errors,
+            });
+        }
     }
 
     Ok((total_processed, total_skipped, total_errors))
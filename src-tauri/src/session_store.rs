@@ -7,9 +7,15 @@
 //! `X-Session` header; the `ProfileDb` extractor validates it before
 //! granting access.
 //!
-//! Sessions expire after 24 hours.  A per-profile lockout engages after
-//! 5 consecutive failed password attempts, blocking further attempts for
-//! 60 seconds.
+//! In multi-user mode (see `users`), `POST /api/users/login` issues a user
+//! session the same way; it is sent in the `X-User-Session` header.
+//!
+//! Sessions expire after 24 hours.  A per-profile (or per-user) lockout
+//! engages after 5 failed password attempts within 15 minutes, blocking
+//! further attempts for 60 seconds; each further lockout within a day
+//! doubles the wait, up to an hour.  Client addresses are locked out the
+//! same way after 20 failures, whichever account or profile they target,
+//! so guessing across many names is throttled too.
 
 use std::collections::HashMap;
 use std::sync::RwLock;
@@ -21,7 +27,16 @@ use rand::Rng;
 
 const DEFAULT_SESSION_TTL_HOURS: u64 = 24;
 const MAX_FAILURES: u32 = 5;
+/// Failures per client address (across all profiles and accounts)
+const MAX_CLIENT_FAILURES: u32 = 20;
+/// Failures older than this no longer count
+const FAILURE_WINDOW: Duration = Duration::from_secs(15 * 60);
 const LOCKOUT_DURATION: Duration = Duration::from_secs(60);
+const MAX_LOCKOUT_DURATION: Duration = Duration::from_secs(3600);
+/// Quiet time after which earlier lockouts are forgotten
+const LOCKOUT_MEMORY: Duration = Duration::from_secs(24 * 3600);
+/// Failure records kept before stale ones are dropped
+const MAX_FAILURE_RECORDS: usize = 10_000;
 
 /// Read `SESSION_TTL_HOURS` from the environment (once) and return the
 /// configured TTL.  Falls back to 24 h when the var is absent or invalid.
@@ -45,9 +60,17 @@ struct Session {
     created: Instant,
 }
 
+struct UserSession {
+    username: String,
+    created: Instant,
+}
+
 struct FailureRecord {
     count: u32,
     last_attempt: Instant,
+    /// Lockouts so far, for the escalating wait
+    lockouts: u32,
+    locked_until: Option<Instant>,
 }
 
 // ────────────────────────────── Store ─────────────────────────────────
@@ -55,7 +78,9 @@ struct FailureRecord {
 pub struct SessionStore {
     /// token → session
     sessions: RwLock<HashMap<String, Session>>,
-    /// profile → failure record
+    /// token → user session
+    user_sessions: RwLock<HashMap<String, UserSession>>,
+    /// profile (or `user:<name>`, `client:<addr>`, `master:`) → failure record
     failures: RwLock<HashMap<String, FailureRecord>>,
 }
 
@@ -63,6 +88,7 @@ impl SessionStore {
     pub fn new() -> Self {
         Self {
             sessions: RwLock::new(HashMap::new()),
+            user_sessions: RwLock::new(HashMap::new()),
             failures: RwLock::new(HashMap::new()),
         }
     }
@@ -111,50 +137,134 @@ impl SessionStore {
     pub fn purge_expired(&self) {
         let mut sessions = self.sessions.write().unwrap();
         sessions.retain(|_, s| s.created.elapsed() < session_ttl());
+        let mut user_sessions = self.user_sessions.write().unwrap();
+        user_sessions.retain(|_, s| s.created.elapsed() < session_ttl());
+    }
+
+    // ── User sessions (multi-user mode) ──
+
+    /// Create a session for user `username` and return the opaque token.
+    pub fn create_user_session(&self, username: &str) -> String {
+        let token = generate_token();
+        let session = UserSession {
+            username: username.to_string(),
+            created: Instant::now(),
+        };
+        self.user_sessions.write().unwrap().insert(token.clone(), session);
+        self.failures.write().unwrap().remove(&user_lockout_key(username));
+        token
+    }
+
+    /// Validate a user token.  Returns `Some(username)` if valid & not expired.
+    pub fn validate_user(&self, token: &str) -> Option<String> {
+        let sessions = self.user_sessions.read().unwrap();
+        sessions.get(token).and_then(|s| {
+            if s.created.elapsed() < session_ttl() {
+                Some(s.username.clone())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Revoke all sessions of a user (password change, deletion).
+    pub fn revoke_user(&self, username: &str) {
+        let mut sessions = self.user_sessions.write().unwrap();
+        sessions.retain(|_, s| s.username != username);
+    }
+
+    /// Revoke a specific user session token (logout).
+    pub fn revoke_user_token(&self, token: &str) {
+        self.user_sessions.write().unwrap().remove(token);
     }
 
     // ── Lockout ──
 
     /// Record a failed password attempt.  Returns `true` if the profile
-    /// is now locked out.
+    /// (or other lockout key) is now locked out.
     pub fn record_failure(&self, profile: &str) -> bool {
+        let max_failures = if profile.starts_with(CLIENT_KEY_PREFIX) { MAX_CLIENT_FAILURES } else { MAX_FAILURES };
+        let now = Instant::now();
         let mut failures = self.failures.write().unwrap();
+        if failures.len() >= MAX_FAILURE_RECORDS {
+            failures.retain(|_, rec| rec.last_attempt.elapsed() < LOCKOUT_MEMORY);
+        }
         let rec = failures.entry(profile.to_string()).or_insert(FailureRecord {
             count: 0,
-            last_attempt: Instant::now(),
+            last_attempt: now,
+            lockouts: 0,
+            locked_until: None,
         });
-        // If the lockout window has passed, reset the counter
-        if rec.last_attempt.elapsed() > LOCKOUT_DURATION {
+        let idle = now.duration_since(rec.last_attempt);
+        if idle > LOCKOUT_MEMORY {
+            rec.lockouts = 0;
+        }
+        // Old failures (or those before the last lockout) no longer count
+        if idle > FAILURE_WINDOW {
             rec.count = 0;
         }
         rec.count += 1;
-        rec.last_attempt = Instant::now();
-        rec.count >= MAX_FAILURES
+        rec.last_attempt = now;
+        if rec.count >= max_failures {
+            rec.count = 0;
+            rec.lockouts += 1;
+            rec.locked_until = Some(now + lockout_duration(rec.lockouts));
+            return true;
+        }
+        false
+    }
+
+    /// Time left of a lockout, None when attempts are allowed.
+    pub fn lockout_remaining(&self, profile: &str) -> Option<Duration> {
+        let failures = self.failures.read().unwrap();
+        let until = failures.get(profile)?.locked_until?;
+        until.checked_duration_since(Instant::now()).filter(|d| !d.is_zero())
     }
 
     /// Check whether a profile is currently locked out.
     pub fn is_locked_out(&self, profile: &str) -> bool {
-        let failures = self.failures.read().unwrap();
-        match failures.get(profile) {
-            Some(rec) if rec.count >= MAX_FAILURES => {
-                rec.last_attempt.elapsed() < LOCKOUT_DURATION
-            }
-            _ => false,
-        }
+        self.lockout_remaining(profile).is_some()
+    }
+
+    /// Forget the failures of a lockout key (after a successful attempt).
+    pub fn clear_failures(&self, profile: &str) {
+        self.failures.write().unwrap().remove(profile);
     }
 }
 
+/// Wait after the n-th lockout: 60 s, doubling up to an hour.
+fn lockout_duration(lockouts: u32) -> Duration {
+    LOCKOUT_DURATION
+        .saturating_mul(1 << lockouts.saturating_sub(1).min(16))
+        .min(MAX_LOCKOUT_DURATION)
+}
+
 // ────────────────────────────── Helpers ───────────────────────────────
 
+const CLIENT_KEY_PREFIX: &str = "client:";
+
+/// Lockout key for the master password (`PROFILE_CREATION_PASS`).
+pub const MASTER_LOCKOUT_KEY: &str = "master:";
+
+/// Lockout key for a user login, kept apart from profile names.
+pub fn user_lockout_key(username: &str) -> String {
+    format!("user:{}", username)
+}
+
+/// Lockout key for a client address, counting failures of every kind.
+pub fn client_lockout_key(addr: &str) -> String {
+    format!("{}{}", CLIENT_KEY_PREFIX, addr)
+}
+
 /// Generate a cryptographically random 256-bit token (base64url-encoded).
-fn generate_token() -> String {
+pub fn generate_token() -> String {
     let mut bytes = [0u8; 32];
     rand::thread_rng().fill(&mut bytes);
     base64_url_encode(&bytes)
 }
 
 /// Minimal base64url encoding (no padding, URL-safe alphabet).
-fn base64_url_encode(data: &[u8]) -> String {
+pub fn base64_url_encode(data: &[u8]) -> String {
     const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
     let mut out = String::with_capacity((data.len() * 4 + 2) / 3);
     for chunk in data.chunks(3) {
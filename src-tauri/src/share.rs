@@ -0,0 +1,293 @@
+//! Publishing a flight's track for public viewing.
+//!
+//! For pilots who post flights publicly: the track is written as GPX and
+//! published to the target configured under the `sharing` key of
+//! `config.json`, and the public URL is returned.
+//!
+//! - `webdav` — `PUT` to `{url}/{file}` (basic auth when a user name is set),
+//!   e.g. Nextcloud or any WebDAV share behind a public web server
+//! - `folder` — written to a local directory that is served as a static site
+//!   (a web server root, a synced Pages repository, ...)
+//! - `upload` — `POST` as multipart field `file` to the upload API of a GPX
+//!   viewer or aggregator (3D replay sites and the like), with the password as
+//!   bearer token when set; the share URL is read from the JSON response
+//!   field `urlField` (default `url`) or the `Location` header
+//!
+//! For `webdav` and `folder` the share URL is `{publicUrl}/{file}`. The file
+//! name is stable per flight, so sharing again replaces the published track.
+//! Home location anonymization applies to shared tracks. Every share is
+//! recorded in the audit log (action `share_flight`).
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::database::{Database, DatabaseError};
+use crate::privacy::PrivacySettings;
+
+const CONFIG_KEY: &str = "sharing";
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(60);
+const DEFAULT_URL_FIELD: &str = "url";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ShareError {
+    #[error("Sharing is not configured")]
+    NotConfigured,
+
+    #[error("Flight has no GPS track to share")]
+    NoTrack,
+
+    #[error("Upload failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Failed to write the track: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("The server rejected the upload ({status}): {body}")]
+    Rejected { status: u16, body: String },
+
+    #[error("The upload response has no share URL")]
+    NoShareUrl,
+}
+
+/// Where tracks are published
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShareTarget {
+    #[default]
+    Webdav,
+    Folder,
+    Upload,
+}
+
+/// Sharing settings as shown to the frontend (the password is never returned)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SharingSettings {
+    pub target: ShareTarget,
+    /// WebDAV collection URL, or the upload API endpoint
+    pub url: String,
+    /// Local directory (`folder`)
+    pub folder: String,
+    /// Base URL the published files are served from (`webdav`, `folder`)
+    pub public_url: String,
+    pub username: String,
+    /// Write-only: empty keeps the stored password
+    #[serde(skip_serializing)]
+    pub password: String,
+    /// Read-only: whether a password is stored
+    pub has_password: bool,
+    /// JSON field of the upload response holding the share URL (`upload`)
+    pub url_field: Option<String>,
+}
+
+impl SharingSettings {
+    /// Read the settings from a parsed config.json
+    pub fn from_config(config: &serde_json::Value) -> Self {
+        let mut settings: Self = config
+            .get(CONFIG_KEY)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        settings.password = config
+            .get(CONFIG_KEY)
+            .and_then(|s| s.get("password"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        settings.has_password = !settings.password.is_empty();
+        settings
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let is_http = |u: &str| u.starts_with("https://") || u.starts_with("http://");
+        match self.target {
+            ShareTarget::Webdav | ShareTarget::Upload if !is_http(self.url.trim()) => {
+                return Err("URL must be an http(s) URL".to_string());
+            }
+            ShareTarget::Folder if self.folder.trim().is_empty() => {
+                return Err("Folder is required".to_string());
+            }
+            _ => {}
+        }
+        if self.target != ShareTarget::Upload && !is_http(self.public_url.trim()) {
+            return Err("Public URL must be an http(s) URL".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Store new settings in config.json; an empty `password` keeps the existing one
+pub fn save_settings(config_path: &Path, mut settings: SharingSettings) -> Result<SharingSettings, String> {
+    let mut config: serde_json::Value = std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    settings.validate()?;
+    if settings.password.trim().is_empty() {
+        settings.password = SharingSettings::from_config(&config).password;
+    }
+    config[CONFIG_KEY] = serde_json::json!({
+        "target": settings.target,
+        "url": settings.url.trim().trim_end_matches('/'),
+        "folder": settings.folder.trim(),
+        "publicUrl": settings.public_url.trim().trim_end_matches('/'),
+        "username": settings.username.trim(),
+        "password": settings.password.trim(),
+        "urlField": settings.url_field.as_deref().map(str::trim).filter(|f| !f.is_empty()),
+    });
+    std::fs::write(config_path, serde_json::to_string_pretty(&config).unwrap())
+        .map_err(|e| format!("Failed to write config: {}", e))?;
+    Ok(SharingSettings::from_config(&config))
+}
+
+/// A published track
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedFlight {
+    pub flight_id: i64,
+    pub target: ShareTarget,
+    pub file_name: String,
+    pub url: String,
+}
+
+/// Published file name of a flight (stable, so sharing again replaces it)
+pub fn share_file_name(flight_id: i64, display_name: &str) -> String {
+    format!("flight-{}-{}.gpx", flight_id, crate::bundle::sanitize_file_stem(display_name))
+}
+
+/// Read the share URL from an upload response: `field` of a JSON body (dots
+/// for nested objects), else the `Location` header
+fn share_url_from_response(body: &str, field: &str, location: Option<&str>) -> Option<String> {
+    let json: Option<serde_json::Value> = serde_json::from_str(body).ok();
+    let from_body = json.as_ref().and_then(|v| {
+        field
+            .split('.')
+            .try_fold(v, |v, key| v.get(key))
+            .and_then(|v| v.as_str())
+            .filter(|u| !u.is_empty())
+            .map(str::to_string)
+    });
+    from_body.or_else(|| location.filter(|u| !u.is_empty()).map(str::to_string))
+}
+
+async fn publish(settings: &SharingSettings, file_name: &str, gpx: Vec<u8>) -> Result<String, ShareError> {
+    let public = |name: &str| format!("{}/{}", settings.public_url.trim_end_matches('/'), name);
+    match settings.target {
+        ShareTarget::Folder => {
+            let folder = PathBuf::from(&settings.folder);
+            std::fs::create_dir_all(&folder)?;
+            std::fs::write(folder.join(file_name), gpx)?;
+            Ok(public(file_name))
+        }
+        ShareTarget::Webdav => {
+            let client = reqwest::Client::builder().timeout(UPLOAD_TIMEOUT).build()?;
+            let mut request = client
+                .put(format!("{}/{}", settings.url.trim_end_matches('/'), file_name))
+                .header(reqwest::header::CONTENT_TYPE, "application/gpx+xml");
+            if !settings.username.is_empty() {
+                request = request.basic_auth(&settings.username, Some(&settings.password));
+            }
+            let response = request.body(gpx).send().await?;
+            let status = response.status();
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(ShareError::Rejected { status: status.as_u16(), body: body.chars().take(300).collect() });
+            }
+            Ok(public(file_name))
+        }
+        ShareTarget::Upload => {
+            let client = reqwest::Client::builder().timeout(UPLOAD_TIMEOUT).build()?;
+            let part = reqwest::multipart::Part::bytes(gpx)
+                .file_name(file_name.to_string())
+                .mime_str("application/gpx+xml")?;
+            let mut request = client.post(&settings.url).multipart(reqwest::multipart::Form::new().part("file", part));
+            if !settings.password.is_empty() {
+                request = request.bearer_auth(&settings.password);
+            }
+            let response = request.send().await?;
+            let status = response.status();
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            let body = response.text().await.unwrap_or_default();
+            if !status.is_success() {
+                return Err(ShareError::Rejected { status: status.as_u16(), body: body.chars().take(300).collect() });
+            }
+            let field = settings.url_field.as_deref().unwrap_or(DEFAULT_URL_FIELD);
+            share_url_from_response(&body, field, location.as_deref()).ok_or(ShareError::NoShareUrl)
+        }
+    }
+}
+
+/// Publish a flight's track and record the outcome in the audit log
+pub async fn share_flight(
+    db: &Database,
+    config: &serde_json::Value,
+    flight_id: i64,
+    actor: &str,
+) -> Result<SharedFlight, ShareError> {
+    let settings = SharingSettings::from_config(config);
+    if settings.validate().is_err() {
+        return Err(ShareError::NotConfigured);
+    }
+    let mut flight = db.get_flight_by_id(flight_id)?;
+    let mut records = db.get_flight_telemetry(flight_id, None, flight.point_count.map(|c| c as i64))?;
+    let privacy = PrivacySettings::from_config(config);
+    if privacy.is_active() {
+        privacy.apply_to_flight(&mut flight);
+        privacy.apply_to_records(&mut records);
+    }
+    if !records.iter().any(|r| r.latitude.is_some() && r.longitude.is_some()) {
+        return Err(ShareError::NoTrack);
+    }
+
+    let file_name = share_file_name(flight_id, &flight.display_name);
+    let gpx = crate::bundle::track_gpx(&flight, &records).into_bytes();
+    let result = publish(&settings, &file_name, gpx).await;
+    match &result {
+        Ok(url) => log::info!("Shared flight {} at {}", flight_id, url),
+        Err(e) => log::warn!("Sharing flight {} failed: {}", flight_id, e),
+    }
+    db.audit(
+        actor,
+        "share_flight",
+        Some(&flight_id.to_string()),
+        serde_json::json!({
+            "target": settings.target,
+            "url": result.as_ref().ok(),
+            "error": result.as_ref().err().map(|e| e.to_string()),
+        }),
+    );
+    Ok(SharedFlight { flight_id, target: settings.target, file_name, url: result? })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_share_url_and_file_name() {
+        assert_eq!(share_file_name(42, "Beach run #2"), "flight-42-Beach_run__2.gpx");
+
+        let body = r#"{"data": {"share": "https://viewer.example/t/abc"}, "url": ""}"#;
+        assert_eq!(
+            share_url_from_response(body, "data.share", None).as_deref(),
+            Some("https://viewer.example/t/abc")
+        );
+        // Empty field falls back to the Location header
+        assert_eq!(
+            share_url_from_response(body, "url", Some("https://viewer.example/t/xyz")).as_deref(),
+            Some("https://viewer.example/t/xyz")
+        );
+        assert_eq!(share_url_from_response("not json", "url", None), None);
+
+        let settings = SharingSettings { target: ShareTarget::Folder, folder: "/srv/www".to_string(), ..Default::default() };
+        assert!(settings.validate().is_err(), "folder target needs a public URL");
+    }
+}
@@ -0,0 +1,262 @@
+//! Parser module for Skydio JSON flight logs.
+//!
+//! Skydio flight logs exported as JSON hold flight metadata (vehicle model and
+//! serial, start time) at the top level and the samples in an array
+//! (`telemetry`, `samples`, `records` or `data`). Exports from different app
+//! and cloud versions use slightly different key names and may nest groups
+//! such as `position` or `battery`; fields are looked up by alias (see
+//! `json_fields`).
+
+use std::io::Read;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::database::Database;
+use crate::formats::{has_extension, FlightLogFormat, ParseFuture};
+use crate::json_fields::{flatten, num, parse_time, text, Flat};
+use crate::models::{FlightMetadata, TelemetryPoint};
+use crate::parser::{LogParser, ParseInfo, ParseResult, ParserError};
+
+/// Bytes read from the start of a JSON file when sniffing for a Skydio log
+const DETECT_BYTES: u64 = 4096;
+/// Keys that may hold the sample array
+const SAMPLE_KEYS: [&str; 5] = ["telemetry", "samples", "records", "data", "flight_data"];
+
+/// Skydio JSON flight logs
+pub struct SkydioFormat;
+
+impl FlightLogFormat for SkydioFormat {
+    fn name(&self) -> &'static str {
+        "Skydio JSON"
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        if !has_extension(path, "json") {
+            return false;
+        }
+        let Ok(file) = std::fs::File::open(path) else {
+            return false;
+        };
+        let mut head = String::new();
+        let _ = file.take(DETECT_BYTES).read_to_string(&mut head);
+        head.to_lowercase().contains("skydio")
+    }
+
+    fn parse<'a>(&'a self, ctx: &'a LogParser<'_>, path: &'a Path, file_hash: &'a str) -> ParseFuture<'a> {
+        Box::pin(async move { SkydioParser::new(ctx.db()).parse(path, file_hash) })
+    }
+}
+
+/// Sample time in ms: relative seconds/ms columns first, then absolute timestamps
+fn sample_time_ms(flat: &Flat<'_>) -> Option<f64> {
+    if let Some(ms) = num(flat, &["time_ms", "offset_ms", "elapsed_ms"]) {
+        return Some(ms);
+    }
+    if let Some(s) = num(flat, &["time_s", "t", "elapsed", "offset", "flight_time"]) {
+        return Some(s * 1000.0);
+    }
+    ["timestamp", "time", "utc_time", "datetime"]
+        .iter()
+        .find_map(|k| flat.get(*k).and_then(|v| parse_time(v)))
+        .map(|dt| dt.timestamp_millis() as f64)
+}
+
+fn decode_samples(samples: &[Value]) -> Vec<TelemetryPoint> {
+    let mut points = Vec::with_capacity(samples.len());
+    let mut first: Option<f64> = None;
+
+    for sample in samples {
+        let flat = flatten(sample);
+        let Some(t) = sample_time_ms(&flat) else {
+            continue;
+        };
+        let t0 = *first.get_or_insert(t);
+
+        let lat = num(&flat, &["latitude", "lat", "position_latitude", "gps_latitude"]);
+        let lon = num(&flat, &["longitude", "lon", "lng", "position_longitude", "gps_longitude"]);
+        let (latitude, longitude) = match (lat, lon) {
+            (Some(lat), Some(lon)) if lat.abs() <= 90.0 && lon.abs() <= 180.0 && !(lat.abs() < 1e-6 && lon.abs() < 1e-6) => {
+                (Some(lat), Some(lon))
+            }
+            _ => (None, None),
+        };
+
+        let velocity = flat.get("velocity").and_then(|v| v.as_array());
+        let component = |i: usize| velocity.and_then(|a| a.get(i)).and_then(|v| v.as_f64());
+        let vn = num(&flat, &["velocity_north", "vel_n", "vn", "velocity_x"]).or_else(|| component(0));
+        let ve = num(&flat, &["velocity_east", "vel_e", "ve", "velocity_y"]).or_else(|| component(1));
+        let vd = num(&flat, &["velocity_down", "vel_d", "vd", "velocity_z"]).or_else(|| component(2));
+
+        let height = num(&flat, &["altitude_agl", "height_above_takeoff", "relative_altitude", "height", "altitude_rel"]);
+        let battery_percent = num(&flat, &["battery_percent", "battery_soc", "battery_percentage", "battery_charge", "soc"])
+            .map(|v| if v <= 1.0 { v * 100.0 } else { v });
+
+        points.push(TelemetryPoint {
+            timestamp_ms: (t - t0).round() as i64,
+            latitude,
+            longitude,
+            altitude: num(&flat, &["altitude_msl", "altitude", "alt", "position_altitude"]).or(height),
+            height,
+            altitude_abs: num(&flat, &["altitude_msl"]),
+            speed: num(&flat, &["speed", "ground_speed", "horizontal_speed"])
+                .or_else(|| vn.zip(ve).map(|(n, e)| (n * n + e * e).sqrt())),
+            velocity_x: vn,
+            velocity_y: ve,
+            velocity_z: vd,
+            roll: num(&flat, &["roll", "attitude_roll"]),
+            pitch: num(&flat, &["pitch", "attitude_pitch"]),
+            yaw: num(&flat, &["yaw", "heading", "attitude_yaw"]),
+            gimbal_pitch: num(&flat, &["gimbal_pitch", "camera_pitch"]),
+            gimbal_roll: num(&flat, &["gimbal_roll"]),
+            gimbal_yaw: num(&flat, &["gimbal_yaw"]),
+            battery_percent: battery_percent.map(|v| v.round() as i32),
+            battery_voltage: num(&flat, &["battery_voltage", "voltage"]),
+            battery_current: num(&flat, &["battery_current", "current"]).map(f64::abs),
+            battery_temp: num(&flat, &["battery_temperature", "battery_temp", "temperature"]),
+            satellites: num(&flat, &["gps_satellites", "num_satellites", "satellites", "gps_num_sats"]).map(|v| v as i32),
+            flight_mode: text(&flat, &["flight_mode", "mode", "flight_state"]),
+            rc_signal: num(&flat, &["link_quality", "signal_strength", "rssi"]).map(|v| v as i32),
+            ..Default::default()
+        });
+    }
+    points
+}
+
+/// Skydio JSON parser
+pub struct SkydioParser<'a> {
+    db: &'a Database,
+}
+
+impl<'a> SkydioParser<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Parse a Skydio JSON log
+    pub fn parse(&self, file_path: &Path, file_hash: &str) -> Result<ParseResult, ParserError> {
+        let parse_start = std::time::Instant::now();
+        log::info!("Parsing Skydio JSON file: {:?}", file_path);
+
+        let content = std::fs::read_to_string(file_path)?;
+        let root: Value =
+            serde_json::from_str(&content).map_err(|e| ParserError::Parse(format!("Invalid JSON: {}", e)))?;
+
+        let samples = match &root {
+            Value::Array(a) => a.as_slice(),
+            _ => SAMPLE_KEYS
+                .iter()
+                .find_map(|k| root.get(*k).and_then(|v| v.as_array()))
+                .map(|a| a.as_slice())
+                .ok_or_else(|| ParserError::Parse("No telemetry array found in Skydio log".to_string()))?,
+        };
+        let points = decode_samples(samples);
+        if !points.iter().any(|p| p.latitude.is_some()) {
+            log::warn!("No positioned samples found in Skydio log");
+            return Err(ParserError::NoTelemetryData);
+        }
+
+        // Metadata may sit at the top level or in a `flight` / `vehicle` group
+        let meta = flatten(&root);
+        let meta_group = |group: &str| root.get(group).map(flatten).unwrap_or_default();
+        let (flight_meta, vehicle_meta) = (meta_group("flight"), meta_group("vehicle"));
+        let lookup = |aliases: &[&str]| {
+            text(&meta, aliases)
+                .or_else(|| text(&flight_meta, aliases))
+                .or_else(|| text(&vehicle_meta, aliases))
+        };
+
+        let first_sample_time = samples.iter().find_map(|s| {
+            let flat = flatten(s);
+            ["timestamp", "utc_time", "datetime"].iter().find_map(|k| flat.get(*k).and_then(|v| parse_time(v)))
+        });
+        let start_time = ["start_time", "flight_start", "takeoff_time", "start"]
+            .iter()
+            .find_map(|k| {
+                root.get(*k)
+                    .or_else(|| root.get("flight").and_then(|f| f.get(*k)))
+                    .and_then(parse_time)
+            })
+            .or(first_sample_time);
+
+        let parser = LogParser::new(self.db);
+        let stats = parser.calculate_stats(&points);
+
+        let file_name = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let display_name = file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(&file_name)
+            .to_string();
+        let end_time = start_time.map(|t| t + chrono::Duration::milliseconds((stats.duration_secs * 1000.0) as i64));
+
+        let metadata = FlightMetadata {
+            id: self.db.generate_flight_id(),
+            file_name,
+            display_name,
+            file_hash: Some(file_hash.to_string()),
+            drone_model: Some(
+                lookup(&["vehicle_model", "model", "vehicle_type", "product_name"]).unwrap_or_else(|| "Skydio".to_string()),
+            ),
+            drone_serial: lookup(&["vehicle_serial", "serial_number", "serial", "vehicle_id"]),
+            aircraft_name: lookup(&["vehicle_name", "name"]),
+            battery_serial: lookup(&["battery_serial"]),
+            cycle_count: None,
+            start_time,
+            end_time,
+            duration_secs: Some(stats.duration_secs),
+            total_distance: Some(stats.total_distance_m),
+            max_altitude: Some(stats.max_altitude_m),
+            max_speed: Some(stats.max_speed_ms),
+            home_lat: stats.home_location.map(|h| h[1]),
+            home_lon: stats.home_location.map(|h| h[0]),
+            point_count: points.len() as i32,
+            photo_count: 0,
+            video_count: 0,
+            rc_serial: lookup(&["controller_serial"]),
+            battery_life: None,
+        };
+
+        log::info!(
+            "Skydio parse complete in {:.1}s: duration={:.1}s, distance={:.0}m, max_alt={:.1}m, points={}",
+            parse_start.elapsed().as_secs_f64(),
+            stats.duration_secs,
+            stats.total_distance_m,
+            stats.max_altitude_m,
+            points.len()
+        );
+
+        let mut tags = LogParser::generate_smart_tags(&metadata, &stats);
+        tags.insert(0, "Skydio".to_string());
+
+        Ok(ParseResult { metadata, points, tags, manual_tags: Vec::new(), notes: None, color: None, messages: Vec::new(), info: ParseInfo::new("Skydio JSON") })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_samples() {
+        let root: Value = serde_json::json!({
+            "vehicle": { "model": "Skydio 2+", "serial": "S2P-001" },
+            "telemetry": [
+                { "timestamp": "2023-06-01T10:00:00Z", "position": { "latitude": 37.5, "longitude": -122.3, "altitude_msl": 12.0 }, "battery": { "percent": 0.95 } },
+                { "timestamp": "2023-06-01T10:00:00.500Z", "position": { "latitude": 37.5001, "longitude": -122.3 }, "velocity": [3.0, 4.0, 0.0], "battery_percent": 94 }
+            ]
+        });
+        let points = decode_samples(root["telemetry"].as_array().unwrap());
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[1].timestamp_ms, 500);
+        assert!((points[0].latitude.unwrap() - 37.5).abs() < 1e-9);
+        assert_eq!(points[0].battery_percent, Some(95));
+        assert!((points[1].speed.unwrap() - 5.0).abs() < 1e-9);
+        assert_eq!(points[1].battery_percent, Some(94));
+    }
+}
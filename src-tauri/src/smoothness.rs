@@ -0,0 +1,176 @@
+//! Smoothness score of a flight, 0 (rough) to 100 (silky).
+//!
+//! Three parts, each scored 0-100 over the airborne part of the log, which is
+//! resampled to half-second steps:
+//!
+//! - jerk — RMS rate of change of the acceleration of the velocity vector
+//!   (abrupt starts, stops and direction changes)
+//! - gimbal — RMS angular rate of the gimbal pitch and yaw (shaky or hurried
+//!   camera moves)
+//! - sticks — share of the time with any stick pushed past 80%
+//!
+//! The score is their weighted mean (40/30/30), over the parts the log has
+//! data for. Computed at import and stored on the flight row, so flights can
+//! be ranked by it.
+
+use crate::flight_phases::airborne;
+use crate::models::TelemetryPoint;
+
+const STEP_MS: i64 = 500;
+/// Longer gaps break the rate calculations
+const MAX_STEP_MS: i64 = 2_000;
+/// RMS jerk (m/s³) that scores 37 (1/e of full marks)
+const JERK_SCALE: f64 = 4.0;
+/// RMS gimbal rate (°/s) that scores 37
+const GIMBAL_RATE_SCALE: f64 = 20.0;
+/// Stick deflection (percent) counted as aggressive
+const AGGRESSIVE_STICK_PCT: f64 = 80.0;
+const JERK_WEIGHT: f64 = 0.4;
+const GIMBAL_WEIGHT: f64 = 0.3;
+const STICK_WEIGHT: f64 = 0.3;
+
+/// Overall score and the measurements behind it (None without the data)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Smoothness {
+    pub score: f64,
+    pub jerk_rms: Option<f64>,
+    pub gimbal_rate_rms: Option<f64>,
+    pub aggressive_stick_fraction: Option<f64>,
+}
+
+fn rms(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| (values.iter().map(|v| v * v).sum::<f64>() / values.len() as f64).sqrt())
+}
+
+fn velocity(p: &TelemetryPoint) -> Option<[f64; 3]> {
+    Some([p.velocity_x?, p.velocity_y?, p.velocity_z.unwrap_or(0.0)])
+}
+
+/// Difference between two headings in degrees, wrapped to -180..180
+fn angle_delta(from: f64, to: f64) -> f64 {
+    (to - from + 540.0).rem_euclid(360.0) - 180.0
+}
+
+fn jerks(steps: &[&TelemetryPoint]) -> Vec<f64> {
+    let mut jerks = Vec::new();
+    for w in steps.windows(3) {
+        let (dt1_ms, dt2_ms) = (w[1].timestamp_ms - w[0].timestamp_ms, w[2].timestamp_ms - w[1].timestamp_ms);
+        if dt1_ms > MAX_STEP_MS || dt2_ms > MAX_STEP_MS {
+            continue;
+        }
+        let (Some(v0), Some(v1), Some(v2)) = (velocity(w[0]), velocity(w[1]), velocity(w[2])) else {
+            continue;
+        };
+        let (dt1, dt2) = (dt1_ms as f64 / 1000.0, dt2_ms as f64 / 1000.0);
+        let change: f64 = (0..3)
+            .map(|i| ((v2[i] - v1[i]) / dt2 - (v1[i] - v0[i]) / dt1).powi(2))
+            .sum::<f64>()
+            .sqrt();
+        jerks.push(change / ((dt1 + dt2) / 2.0));
+    }
+    jerks
+}
+
+fn gimbal_rates(steps: &[&TelemetryPoint]) -> Vec<f64> {
+    let mut rates = Vec::new();
+    for w in steps.windows(2) {
+        let dt_ms = w[1].timestamp_ms - w[0].timestamp_ms;
+        let (Some(p0), Some(p1)) = (w[0].gimbal_pitch, w[1].gimbal_pitch) else {
+            continue;
+        };
+        if dt_ms > MAX_STEP_MS {
+            continue;
+        }
+        let yaw = match (w[0].gimbal_yaw, w[1].gimbal_yaw) {
+            (Some(y0), Some(y1)) => angle_delta(y0, y1),
+            _ => 0.0,
+        };
+        rates.push((p1 - p0).hypot(yaw) / (dt_ms as f64 / 1000.0));
+    }
+    rates
+}
+
+fn aggressive_stick_fraction(steps: &[&TelemetryPoint]) -> Option<f64> {
+    let (mut total_ms, mut aggressive_ms) = (0, 0);
+    for w in steps.windows(2) {
+        let dt_ms = w[1].timestamp_ms - w[0].timestamp_ms;
+        let p = w[0];
+        let sticks: Vec<f64> = [p.rc_aileron, p.rc_elevator, p.rc_throttle, p.rc_rudder].into_iter().flatten().collect();
+        if dt_ms > MAX_STEP_MS || sticks.is_empty() {
+            continue;
+        }
+        total_ms += dt_ms;
+        if sticks.iter().any(|s| s.abs() >= AGGRESSIVE_STICK_PCT) {
+            aggressive_ms += dt_ms;
+        }
+    }
+    (total_ms > 0).then(|| aggressive_ms as f64 / total_ms as f64)
+}
+
+/// Score the airborne part of a flight (None when the log has none of the data)
+pub fn score(points: &[TelemetryPoint]) -> Option<Smoothness> {
+    let mut steps: Vec<&TelemetryPoint> = Vec::new();
+    for p in points.iter().filter(|p| airborne(p)) {
+        if steps.last().map_or(true, |last| p.timestamp_ms - last.timestamp_ms >= STEP_MS) {
+            steps.push(p);
+        }
+    }
+
+    let jerk_rms = rms(&jerks(&steps));
+    let gimbal_rate_rms = rms(&gimbal_rates(&steps));
+    let aggressive_stick_fraction = aggressive_stick_fraction(&steps);
+
+    let parts = [
+        (jerk_rms.map(|j| 100.0 * (-j / JERK_SCALE).exp()), JERK_WEIGHT),
+        (gimbal_rate_rms.map(|r| 100.0 * (-r / GIMBAL_RATE_SCALE).exp()), GIMBAL_WEIGHT),
+        (aggressive_stick_fraction.map(|f| 100.0 * (1.0 - f)), STICK_WEIGHT),
+    ];
+    let weight: f64 = parts.iter().filter(|(s, _)| s.is_some()).map(|(_, w)| w).sum();
+    if weight == 0.0 {
+        return None;
+    }
+    let score = parts.iter().filter_map(|(s, w)| s.map(|s| s * w)).sum::<f64>() / weight;
+    Some(Smoothness {
+        score,
+        jerk_rms,
+        gimbal_rate_rms,
+        aggressive_stick_fraction,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(t_ms: i64, vx: f64, gimbal_yaw: f64, elevator: f64) -> TelemetryPoint {
+        TelemetryPoint {
+            timestamp_ms: t_ms,
+            height: Some(30.0),
+            velocity_x: Some(vx),
+            velocity_y: Some(0.0),
+            gimbal_pitch: Some(-30.0),
+            gimbal_yaw: Some(gimbal_yaw),
+            rc_elevator: Some(elevator),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_steady_beats_jerky() {
+        // Steady 5 m/s with the gimbal panning slowly across north
+        let steady: Vec<_> = (0..60).map(|i| point(i * 500, 5.0, (355.0 + i as f64) % 360.0, 40.0)).collect();
+        let smooth = score(&steady).unwrap();
+        assert!(smooth.jerk_rms.unwrap() < 1e-9);
+        assert!((smooth.gimbal_rate_rms.unwrap() - 2.0).abs() < 1e-9);
+        assert_eq!(smooth.aggressive_stick_fraction, Some(0.0));
+        assert!(smooth.score > 95.0);
+
+        // Full stick, speed flipping between 0 and 8 m/s
+        let jerky: Vec<_> = (0..60)
+            .map(|i| point(i * 500, if i % 2 == 0 { 0.0 } else { 8.0 }, 0.0, 100.0))
+            .collect();
+        let rough = score(&jerky).unwrap();
+        assert_eq!(rough.aggressive_stick_fraction, Some(1.0));
+        assert!(rough.score < 40.0);
+    }
+}
@@ -0,0 +1,127 @@
+//! Speed from GPS positions, for logs whose speed field is junk.
+//!
+//! Some aircraft log a speed of zero throughout, or values unrelated to how
+//! the aircraft moved (wrong units, a stuck field), which makes max and
+//! average speed obviously wrong. After parsing, the logged speed is compared
+//! with the speed between GPS positions over the samples where the aircraft
+//! was clearly moving. When the two don't agree (median ratio outside
+//! `RATIO_RANGE`), the logged speed is replaced by the position speed,
+//! measured across a centred `WINDOW_MS` window to keep GPS jitter out, and
+//! the import report flags the flight (`speedDerived`).
+
+use crate::models::TelemetryPoint;
+use crate::parser::haversine_distance;
+
+/// Span of positions each derived speed is measured over, centred on the sample
+const WINDOW_MS: i64 = 2_000;
+/// Position speed above which a sample counts as moving (m/s)
+const MOVING_SPEED: f64 = 3.0;
+/// Moving samples needed before the logged speed is judged
+const MIN_MOVING_SAMPLES: usize = 20;
+/// Accepted range of the median logged / position speed ratio
+const RATIO_RANGE: (f64, f64) = (0.33, 3.0);
+
+/// Horizontal speed (m/s) of each point from the positions within
+/// `WINDOW_MS` around it (None without a position)
+pub fn position_speeds(points: &[TelemetryPoint]) -> Vec<Option<f64>> {
+    let fixes: Vec<(usize, i64, f64, f64)> = points
+        .iter()
+        .enumerate()
+        .filter_map(|(i, p)| match (p.latitude, p.longitude) {
+            (Some(lat), Some(lon)) if lat.is_finite() && lon.is_finite() && (lat.abs() > 1e-6 || lon.abs() > 1e-6) => {
+                Some((i, p.timestamp_ms, lat, lon))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut speeds = vec![None; points.len()];
+    let (mut lo, mut hi) = (0, 0);
+    for (k, &(index, t, _, _)) in fixes.iter().enumerate() {
+        while fixes[lo].1 < t - WINDOW_MS / 2 {
+            lo += 1;
+        }
+        hi = hi.max(k);
+        while hi + 1 < fixes.len() && fixes[hi + 1].1 <= t + WINDOW_MS / 2 {
+            hi += 1;
+        }
+        let (a, b) = (&fixes[lo], &fixes[hi]);
+        let dt = (b.1 - a.1) as f64 / 1000.0;
+        if dt > 0.0 {
+            speeds[index] = Some(haversine_distance(a.2, a.3, b.2, b.3) / dt);
+        }
+    }
+    speeds
+}
+
+/// Whether the logged speed disagrees with the positions while moving
+/// (a missing speed counts as zero)
+fn logged_speed_is_junk(points: &[TelemetryPoint], derived: &[Option<f64>]) -> bool {
+    let mut ratios: Vec<f64> = points
+        .iter()
+        .zip(derived)
+        .filter_map(|(p, d)| {
+            let d = d.filter(|d| *d > MOVING_SPEED)?;
+            Some(p.speed.filter(|s| s.is_finite()).unwrap_or(0.0).abs() / d)
+        })
+        .collect();
+    if ratios.len() < MIN_MOVING_SAMPLES {
+        return false;
+    }
+    let mid = ratios.len() / 2;
+    let median = *ratios.select_nth_unstable_by(mid, |a, b| a.total_cmp(b)).1;
+    median < RATIO_RANGE.0 || median > RATIO_RANGE.1
+}
+
+/// Replace the speed of every point with the position speed when the logged
+/// one is junk. Returns whether it was replaced.
+pub fn apply(points: &mut [TelemetryPoint]) -> bool {
+    let derived = position_speeds(points);
+    if !logged_speed_is_junk(points, &derived) {
+        return false;
+    }
+    for (point, speed) in points.iter_mut().zip(derived) {
+        point.speed = speed;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Eastward at 10 m/s, 2 Hz, with the given logged speed
+    fn track(logged: Option<f64>) -> Vec<TelemetryPoint> {
+        let metres_per_degree = 111_195.0 * 46.0_f64.to_radians().cos();
+        (0..60)
+            .map(|i| TelemetryPoint {
+                timestamp_ms: i * 500,
+                latitude: Some(46.0),
+                longitude: Some(7.0 + (i as f64 * 5.0) / metres_per_degree),
+                speed: logged,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_junk_speed_is_replaced() {
+        let mut good = track(Some(10.2));
+        assert!(!apply(&mut good));
+        assert_eq!(good[30].speed, Some(10.2));
+
+        for logged in [Some(0.0), None, Some(102.0)] {
+            let mut junk = track(logged);
+            assert!(apply(&mut junk));
+            let speed = junk[30].speed.unwrap();
+            assert!((speed - 10.0).abs() < 0.1, "{}", speed);
+        }
+
+        // Hovering: nothing to judge by
+        let mut hover: Vec<TelemetryPoint> = track(Some(0.0))
+            .into_iter()
+            .map(|p| TelemetryPoint { longitude: Some(7.0), ..p })
+            .collect();
+        assert!(!apply(&mut hover));
+    }
+}
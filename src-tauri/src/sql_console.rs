@@ -0,0 +1,167 @@
+//! Read-only SQL console for power users.
+//!
+//! Runs one ad-hoc `SELECT` against the profile's DuckDB database and returns
+//! the rows as JSON. The statement is parsed by DuckDB itself
+//! (`json_serialize_sql`, which only accepts `SELECT` statements) and its
+//! syntax tree checked: table functions (`read_csv`, `glob`, ...) and
+//! anything read from a quoted path or URL instead of a table are refused, so
+//! queries cannot reach the file system or network. It then runs on a
+//! read-only copy of the database opened with external access and
+//! configuration changes disabled (see `Database::query_read_only`), inside a
+//! transaction that is always rolled back, with a row cap and a time limit
+//! after which DuckDB interrupts it.
+
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::database::{Database, DatabaseError};
+
+pub const DEFAULT_MAX_ROWS: usize = 1_000;
+pub const MAX_ROWS_LIMIT: usize = 10_000;
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// Scalar functions with side effects
+const FORBIDDEN_FUNCTIONS: &[&str] = &["nextval", "setval", "getenv"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum SqlConsoleError {
+    #[error("Query is empty")]
+    Empty,
+
+    #[error("Only a single SELECT statement is allowed: {0}")]
+    NotSelect(String),
+
+    #[error("Not allowed in console queries: {0}")]
+    Forbidden(String),
+
+    #[error("Query cancelled after {} seconds", TIMEOUT.as_secs())]
+    Timeout,
+
+    #[error("{0}")]
+    Database(#[from] DatabaseError),
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    /// One array per row, in column order
+    pub rows: Vec<Vec<serde_json::Value>>,
+    /// More rows matched than were returned
+    pub truncated: bool,
+    pub elapsed_ms: f64,
+}
+
+/// Walk the syntax tree from `json_serialize_sql` and refuse table
+/// functions, non-table sources and side-effecting functions
+fn check_node(node: &serde_json::Value) -> Result<(), SqlConsoleError> {
+    match node {
+        serde_json::Value::Object(map) => {
+            match map.get("type").and_then(|t| t.as_str()) {
+                Some("TABLE_FUNCTION") => {
+                    let name = map
+                        .get("function")
+                        .and_then(|f| f.get("function_name"))
+                        .and_then(|n| n.as_str())
+                        .unwrap_or("table function");
+                    return Err(SqlConsoleError::Forbidden(format!("{}()", name)));
+                }
+                Some("BASE_TABLE") => {
+                    let name = map.get("table_name").and_then(|n| n.as_str()).unwrap_or("");
+                    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                        return Err(SqlConsoleError::Forbidden(format!("reading '{}'", name)));
+                    }
+                }
+                Some("FUNCTION") => {
+                    let name = map.get("function_name").and_then(|n| n.as_str()).unwrap_or("");
+                    if FORBIDDEN_FUNCTIONS.contains(&name.to_ascii_lowercase().as_str()) {
+                        return Err(SqlConsoleError::Forbidden(format!("{}()", name)));
+                    }
+                }
+                _ => {}
+            }
+            map.values().try_for_each(check_node)
+        }
+        serde_json::Value::Array(items) => items.iter().try_for_each(check_node),
+        _ => Ok(()),
+    }
+}
+
+/// Check the `json_serialize_sql` output of a query
+fn check_query(serialized: &str) -> Result<(), SqlConsoleError> {
+    let ast: serde_json::Value = serde_json::from_str(serialized)
+        .map_err(|e| SqlConsoleError::NotSelect(format!("unreadable syntax tree: {}", e)))?;
+    if ast.get("error").and_then(|e| e.as_bool()).unwrap_or(false) {
+        let message = ast.get("error_message").and_then(|m| m.as_str()).unwrap_or("parse error");
+        return Err(SqlConsoleError::NotSelect(message.to_string()));
+    }
+    let statements = ast.get("statements").and_then(|s| s.as_array()).map_or(0, |s| s.len());
+    if statements != 1 {
+        return Err(SqlConsoleError::NotSelect(format!("{} statements", statements)));
+    }
+    check_node(&ast)
+}
+
+/// Run a console query, returning at most `max_rows` rows
+/// (default `DEFAULT_MAX_ROWS`, at most `MAX_ROWS_LIMIT`)
+pub fn run(db: &Database, sql: &str, max_rows: Option<usize>) -> Result<QueryResult, SqlConsoleError> {
+    let sql = sql.trim().trim_end_matches(';').trim_end();
+    if sql.is_empty() {
+        return Err(SqlConsoleError::Empty);
+    }
+    check_query(&db.serialize_sql(sql)?)?;
+
+    let max_rows = max_rows.unwrap_or(DEFAULT_MAX_ROWS).clamp(1, MAX_ROWS_LIMIT);
+    let start = Instant::now();
+    let (columns, json_rows) = db.query_read_only(sql, max_rows + 1, TIMEOUT).map_err(|e| {
+        if start.elapsed() >= TIMEOUT {
+            SqlConsoleError::Timeout
+        } else {
+            SqlConsoleError::Database(e)
+        }
+    })?;
+
+    let truncated = json_rows.len() > max_rows;
+    let rows = json_rows
+        .iter()
+        .take(max_rows)
+        .map(|json| {
+            let object: serde_json::Map<String, serde_json::Value> = serde_json::from_str(json).unwrap_or_default();
+            columns
+                .iter()
+                .map(|c| object.get(c).cloned().unwrap_or(serde_json::Value::Null))
+                .collect()
+        })
+        .collect();
+    Ok(QueryResult {
+        columns,
+        rows,
+        truncated,
+        elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn select_from(table_ref: serde_json::Value) -> String {
+        serde_json::json!({
+            "error": false,
+            "statements": [{ "node": { "type": "SELECT_NODE", "from_table": table_ref, "select_list": [] } }]
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_check_query() {
+        assert!(check_query(&select_from(serde_json::json!({ "type": "BASE_TABLE", "table_name": "flights" }))).is_ok());
+        assert!(check_query(&select_from(serde_json::json!({ "type": "BASE_TABLE", "table_name": "/etc/passwd.csv" }))).is_err());
+        assert!(check_query(&select_from(serde_json::json!({
+            "type": "TABLE_FUNCTION",
+            "function": { "type": "FUNCTION", "function_name": "read_csv" }
+        })))
+        .is_err());
+        assert!(check_query(r#"{"error":true,"error_type":"not implemented","error_message":"Only SELECT statements can be serialized to json!"}"#).is_err());
+    }
+}
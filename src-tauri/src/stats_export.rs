@@ -0,0 +1,243 @@
+//! Statistics export.
+//!
+//! Bundles the overview totals, per-month aggregates and the battery/drone
+//! usage tables into a single [`StatsExport`] that can be serialized as JSON
+//! or rendered one table at a time as CSV for spreadsheets and year-end summaries.
+
+use serde::Serialize;
+
+use crate::database::{Database, DatabaseError};
+use crate::flight_phases::PhaseTimes;
+use crate::models::{BatteryUsage, DroneUsage, MonthlyStats, OverviewStats};
+
+/// Logbook-wide totals (the scalar part of `OverviewStats`)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverviewSummary {
+    pub total_flights: i64,
+    pub total_distance_m: f64,
+    pub total_duration_secs: f64,
+    pub total_points: i64,
+    pub total_photos: i64,
+    pub total_videos: i64,
+    pub max_altitude_m: f64,
+    pub max_distance_from_home_m: f64,
+    /// Airborne time per phase (see `flight_phases`)
+    pub phase_times: PhaseTimes,
+}
+
+impl From<&OverviewStats> for OverviewSummary {
+    fn from(s: &OverviewStats) -> Self {
+        Self {
+            total_flights: s.total_flights,
+            total_distance_m: s.total_distance_m,
+            total_duration_secs: s.total_duration_secs,
+            total_points: s.total_points,
+            total_photos: s.total_photos,
+            total_videos: s.total_videos,
+            max_altitude_m: s.max_altitude_m,
+            max_distance_from_home_m: s.max_distance_from_home_m,
+            phase_times: s.phase_times,
+        }
+    }
+}
+
+/// Everything the export covers
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsExport {
+    pub generated_at: String,
+    pub overview: OverviewSummary,
+    pub monthly: Vec<MonthlyStats>,
+    pub batteries: Vec<BatteryUsage>,
+    pub drones: Vec<DroneUsage>,
+}
+
+/// A table that can be exported as CSV
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsTable {
+    Overview,
+    Monthly,
+    Batteries,
+    Drones,
+}
+
+impl StatsTable {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "overview" => Some(Self::Overview),
+            "monthly" => Some(Self::Monthly),
+            "batteries" => Some(Self::Batteries),
+            "drones" => Some(Self::Drones),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Overview => "overview",
+            Self::Monthly => "monthly",
+            Self::Batteries => "batteries",
+            Self::Drones => "drones",
+        }
+    }
+}
+
+impl StatsExport {
+    /// Collect all export tables from the database
+    pub fn collect(db: &Database) -> Result<Self, DatabaseError> {
+        let overview = db.get_overview_stats()?;
+        Ok(Self {
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            overview: OverviewSummary::from(&overview),
+            monthly: db.get_monthly_stats()?,
+            batteries: overview.batteries_used,
+            drones: overview.drones_used,
+        })
+    }
+
+    /// Render one table as CSV (header row + one line per record)
+    pub fn to_csv(&self, table: StatsTable) -> String {
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        match table {
+            StatsTable::Overview => {
+                let o = &self.overview;
+                rows.push(vec!["metric".into(), "value".into()]);
+                rows.push(vec!["total_flights".into(), o.total_flights.to_string()]);
+                rows.push(vec!["total_distance_m".into(), format!("{:.1}", o.total_distance_m)]);
+                rows.push(vec!["total_duration_secs".into(), format!("{:.0}", o.total_duration_secs)]);
+                rows.push(vec!["total_points".into(), o.total_points.to_string()]);
+                rows.push(vec!["total_photos".into(), o.total_photos.to_string()]);
+                rows.push(vec!["total_videos".into(), o.total_videos.to_string()]);
+                rows.push(vec!["max_altitude_m".into(), format!("{:.1}", o.max_altitude_m)]);
+                rows.push(vec!["max_distance_from_home_m".into(), format!("{:.1}", o.max_distance_from_home_m)]);
+                let p = &o.phase_times;
+                rows.push(vec!["hover_secs".into(), format!("{:.0}", p.hover_secs)]);
+                rows.push(vec!["transit_secs".into(), format!("{:.0}", p.transit_secs)]);
+                rows.push(vec!["ascend_secs".into(), format!("{:.0}", p.ascend_secs)]);
+                rows.push(vec!["descend_secs".into(), format!("{:.0}", p.descend_secs)]);
+            }
+            StatsTable::Monthly => {
+                rows.push(
+                    ["month", "flight_count", "total_duration_secs", "total_distance_m", "max_altitude_m", "total_photos", "total_videos"]
+                        .iter()
+                        .map(|s| s.to_string())
+                        .collect(),
+                );
+                for m in &self.monthly {
+                    rows.push(vec![
+                        m.month.clone(),
+                        m.flight_count.to_string(),
+                        format!("{:.0}", m.total_duration_secs),
+                        format!("{:.1}", m.total_distance_m),
+                        format!("{:.1}", m.max_altitude_m),
+                        m.total_photos.to_string(),
+                        m.total_videos.to_string(),
+                    ]);
+                }
+            }
+            StatsTable::Batteries => {
+                rows.push(
+                    ["battery_serial", "flight_count", "total_duration_secs", "max_cycle_count"]
+                        .iter()
+                        .map(|s| s.to_string())
+                        .collect(),
+                );
+                for b in &self.batteries {
+                    rows.push(vec![
+                        b.battery_serial.clone(),
+                        b.flight_count.to_string(),
+                        format!("{:.0}", b.total_duration_secs),
+                        b.max_cycle_count.map(|c| c.to_string()).unwrap_or_default(),
+                    ]);
+                }
+            }
+            StatsTable::Drones => {
+                rows.push(
+                    ["drone_model", "drone_serial", "aircraft_name", "flight_count"]
+                        .iter()
+                        .map(|s| s.to_string())
+                        .collect(),
+                );
+                for d in &self.drones {
+                    rows.push(vec![
+                        d.drone_model.clone(),
+                        d.drone_serial.clone().unwrap_or_default(),
+                        d.aircraft_name.clone().unwrap_or_default(),
+                        d.flight_count.to_string(),
+                    ]);
+                }
+            }
+        }
+
+        let mut out = String::new();
+        for row in rows {
+            let line: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+            out.push_str(&line.join(","));
+            out.push_str("\r\n");
+        }
+        out
+    }
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> StatsExport {
+        StatsExport {
+            generated_at: "2026-01-01T00:00:00+00:00".to_string(),
+            overview: OverviewSummary {
+                total_flights: 2,
+                total_distance_m: 1500.0,
+                total_duration_secs: 900.0,
+                total_points: 100,
+                total_photos: 3,
+                total_videos: 1,
+                max_altitude_m: 120.0,
+                max_distance_from_home_m: 640.0,
+                phase_times: PhaseTimes::default(),
+            },
+            monthly: vec![MonthlyStats {
+                month: "2025-12".to_string(),
+                flight_count: 2,
+                total_duration_secs: 900.0,
+                total_distance_m: 1500.0,
+                max_altitude_m: 120.0,
+                total_photos: 3,
+                total_videos: 1,
+            }],
+            batteries: vec![],
+            drones: vec![DroneUsage {
+                drone_model: "Mavic 3".to_string(),
+                drone_serial: None,
+                aircraft_name: Some("Work, primary".to_string()),
+                flight_count: 2,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_monthly_csv() {
+        let csv = sample().to_csv(StatsTable::Monthly);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "month,flight_count,total_duration_secs,total_distance_m,max_altitude_m,total_photos,total_videos");
+        assert_eq!(lines[1], "2025-12,2,900,1500.0,120.0,3,1");
+    }
+
+    #[test]
+    fn test_csv_quoting() {
+        let csv = sample().to_csv(StatsTable::Drones);
+        assert!(csv.contains("Mavic 3,,\"Work, primary\",2"));
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -0,0 +1,145 @@
+//! Compact transfer encoding for flight telemetry.
+//!
+//! The web client can ask `/api/flight_data` for `encoding=compact`: each
+//! numeric series is quantized to a fixed-point integer at a per-field scale
+//! (1e-7° for coordinates, centimetres for heights and speeds, ...) and sent as
+//! deltas from the previous non-null value, timestamps become millisecond
+//! deltas and booleans become 0/1. Slowly changing series then serialize as
+//! runs of short integers, which cuts the JSON several-fold. Series that aren't
+//! plain numbers (cell voltages, flight mode) are passed through unchanged.
+//!
+//! Decoding: `value = (running sum of deltas) / scale`, with nulls kept in
+//! place and not advancing the sum.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::models::TelemetryData;
+
+/// Identifies the format so clients can reject an encoding they don't know
+pub const COMPACT_ENCODING: &str = "compact-v1";
+
+/// One quantized, delta-encoded series
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactSeries {
+    /// Divide the accumulated value by this to get the original unit
+    pub scale: f64,
+    /// Values are 0/1 flags standing for booleans
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub boolean: bool,
+    pub values: Vec<Option<i64>>,
+}
+
+/// Column-oriented compact form of [`TelemetryData`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactTelemetry {
+    pub encoding: &'static str,
+    /// Milliseconds since flight start, delta-encoded
+    pub time_ms: Vec<i64>,
+    /// Numeric and boolean series by their `TelemetryData` field name
+    pub series: BTreeMap<String, CompactSeries>,
+    /// Series sent as-is (not plain numbers)
+    pub raw: BTreeMap<String, Value>,
+    pub omitted_fields: Vec<String>,
+}
+
+/// Fixed-point scale per field: the smallest step kept is `1 / scale`
+fn field_scale(field: &str) -> f64 {
+    match field {
+        "latitude" | "longitude" => 1e7,
+        "battery" | "satellites" | "rcSignal" | "rcUplink" | "rcDownlink" => 1.0,
+        "batteryVoltage" => 1000.0,
+        "batteryCurrent" | "batteryFullCapacity" | "batteryRemainedCapacity" => 1.0,
+        "pitch" | "roll" | "yaw" | "gimbalPitch" | "gimbalRoll" | "gimbalYaw" | "batteryTemp" => 10.0,
+        _ => 100.0,
+    }
+}
+
+/// Quantize and delta-encode one series; None when it holds anything but
+/// numbers, booleans and nulls
+fn encode_series(field: &str, values: &[Value]) -> Option<CompactSeries> {
+    let boolean = values.iter().any(Value::is_boolean);
+    let scale = if boolean { 1.0 } else { field_scale(field) };
+    let mut previous = 0i64;
+    let mut encoded = Vec::with_capacity(values.len());
+    for value in values {
+        let quantized = match value {
+            Value::Null => None,
+            Value::Bool(b) if boolean => Some(*b as i64),
+            Value::Number(n) if !boolean => Some((n.as_f64()? * scale).round() as i64),
+            _ => return None,
+        };
+        encoded.push(quantized.map(|q| {
+            let delta = q - previous;
+            previous = q;
+            delta
+        }));
+    }
+    Some(CompactSeries { scale, boolean, values: encoded })
+}
+
+/// Encode telemetry in the compact transfer format
+pub fn encode_compact(data: &TelemetryData) -> CompactTelemetry {
+    let mut previous = 0i64;
+    let time_ms = data
+        .time
+        .iter()
+        .map(|t| {
+            let ms = (t * 1000.0).round() as i64;
+            let delta = ms - previous;
+            previous = ms;
+            delta
+        })
+        .collect();
+
+    let mut series = BTreeMap::new();
+    let mut raw = BTreeMap::new();
+    if let Ok(Value::Object(fields)) = serde_json::to_value(data) {
+        for (field, value) in fields {
+            if field == "time" || field == "omittedFields" {
+                continue;
+            }
+            let Value::Array(values) = value else {
+                continue;
+            };
+            match encode_series(&field, &values) {
+                Some(encoded) => {
+                    series.insert(field, encoded);
+                }
+                None => {
+                    raw.insert(field, Value::Array(values));
+                }
+            }
+        }
+    }
+
+    CompactTelemetry {
+        encoding: COMPACT_ENCODING,
+        time_ms,
+        series,
+        raw,
+        omitted_fields: data.omitted_fields.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_encode_series_quantizes_and_delta_encodes() {
+        let lat = encode_series("latitude", &[json!(47.1234567), Value::Null, json!(47.1234570)]).unwrap();
+        assert_eq!(lat.values, vec![Some(471_234_567), None, Some(3)]);
+
+        let photo = encode_series("isPhoto", &[json!(false), json!(true), json!(true)]).unwrap();
+        assert!(photo.boolean);
+        assert_eq!(photo.values, vec![Some(0), Some(1), Some(0)]);
+
+        assert!(encode_series("flightMode", &[json!("GPS")]).is_none());
+    }
+}
@@ -0,0 +1,86 @@
+//! Helpers shared by the unit tests.
+
+use crate::models::Flight;
+
+/// Builds a [`Flight`] for tests. Only the id, file name and display name are
+/// set up front; everything else starts empty, so a test sets just the fields
+/// the code under test reads.
+pub struct FlightBuilder(Flight);
+
+impl FlightBuilder {
+    pub fn new(id: i64) -> Self {
+        let flight = serde_json::from_value(serde_json::json!({
+            "id": id,
+            "fileName": format!("{}.txt", id),
+            "displayName": format!("Flight {}", id),
+        }))
+        .unwrap();
+        Self(flight)
+    }
+
+    pub fn display_name(mut self, display_name: &str) -> Self {
+        self.0.display_name = display_name.to_string();
+        self
+    }
+
+    pub fn drone_model(mut self, model: &str) -> Self {
+        self.0.drone_model = Some(model.to_string());
+        self
+    }
+
+    pub fn drone_serial(mut self, serial: &str) -> Self {
+        self.0.drone_serial = Some(serial.to_string());
+        self
+    }
+
+    pub fn battery_serial(mut self, serial: &str) -> Self {
+        self.0.battery_serial = Some(serial.to_string());
+        self
+    }
+
+    pub fn cycle_count(mut self, cycles: Option<i32>) -> Self {
+        self.0.cycle_count = cycles;
+        self
+    }
+
+    pub fn pilot(mut self, pilot: Option<&str>) -> Self {
+        self.0.pilot = pilot.map(str::to_string);
+        self
+    }
+
+    /// Start time as RFC 3339
+    pub fn start(mut self, start_time: &str) -> Self {
+        self.0.start_time = Some(start_time.to_string());
+        self
+    }
+
+    pub fn duration(mut self, secs: f64) -> Self {
+        self.0.duration_secs = Some(secs);
+        self
+    }
+
+    pub fn distance(mut self, meters: f64) -> Self {
+        self.0.total_distance = Some(meters);
+        self
+    }
+
+    pub fn max_altitude(mut self, meters: f64) -> Self {
+        self.0.max_altitude = Some(meters);
+        self
+    }
+
+    pub fn home(mut self, lat: f64, lon: f64) -> Self {
+        self.0.home_lat = Some(lat);
+        self.0.home_lon = Some(lon);
+        self
+    }
+
+    pub fn notes(mut self, notes: &str) -> Self {
+        self.0.notes = Some(notes.to_string());
+        self
+    }
+
+    pub fn build(self) -> Flight {
+        self.0
+    }
+}
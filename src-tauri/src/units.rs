@@ -0,0 +1,201 @@
+//! Display units, chosen per quantity.
+//!
+//! Stored under the `units` key of `config.json`. Each quantity has its own
+//! unit, so altitude can be in feet while distance stays in kilometers and
+//! speed is in knots. The reports and exports meant for reading (the flight
+//! bundle summary, the summary email, the invoice CSV and PDF) format their
+//! figures with these settings. Machine-readable exports keep SI units: the
+//! telemetry CSV of bundles, the statistics export (columns named with their
+//! unit) and export templates (each column names its own unit).
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+const CONFIG_KEY: &str = "units";
+const METERS_PER_MILE: f64 = 1609.344;
+const METERS_PER_NAUTICAL_MILE: f64 = 1852.0;
+const METERS_PER_FOOT: f64 = 0.3048;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DistanceUnit {
+    /// Meters below 1 km, kilometers above
+    #[default]
+    Km,
+    Mi,
+    /// Nautical miles
+    Nm,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AltitudeUnit {
+    #[default]
+    M,
+    Ft,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpeedUnit {
+    Ms,
+    #[default]
+    Kmh,
+    Mph,
+    /// Knots
+    Kn,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureUnit {
+    #[default]
+    C,
+    F,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnitSettings {
+    #[serde(default)]
+    pub distance: DistanceUnit,
+    #[serde(default)]
+    pub altitude: AltitudeUnit,
+    #[serde(default)]
+    pub speed: SpeedUnit,
+    #[serde(default)]
+    pub temperature: TemperatureUnit,
+}
+
+impl UnitSettings {
+    pub fn from_config(config: &serde_json::Value) -> Self {
+        config
+            .get(CONFIG_KEY)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Read the settings from a `config.json` (defaults when missing)
+    pub fn load(config_path: &Path) -> Self {
+        let config: serde_json::Value = std::fs::read_to_string(config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::json!({}));
+        Self::from_config(&config)
+    }
+
+    /// Label of the long distance unit, for column headers
+    pub fn distance_label(&self) -> &'static str {
+        match self.distance {
+            DistanceUnit::Km => "km",
+            DistanceUnit::Mi => "mi",
+            DistanceUnit::Nm => "NM",
+        }
+    }
+
+    /// Meters in the long distance unit (km, mi or NM)
+    pub fn distance_value(&self, meters: f64) -> f64 {
+        match self.distance {
+            DistanceUnit::Km => meters / 1000.0,
+            DistanceUnit::Mi => meters / METERS_PER_MILE,
+            DistanceUnit::Nm => meters / METERS_PER_NAUTICAL_MILE,
+        }
+    }
+
+    /// A distance in meters, e.g. "850 m", "12.3 km", "7.6 NM"
+    pub fn format_distance(&self, meters: f64) -> String {
+        if self.distance == DistanceUnit::Km && meters.abs() < 1000.0 {
+            return format!("{:.0} m", meters);
+        }
+        format!("{:.1} {}", self.distance_value(meters), self.distance_label())
+    }
+
+    pub fn altitude_label(&self) -> &'static str {
+        match self.altitude {
+            AltitudeUnit::M => "m",
+            AltitudeUnit::Ft => "ft",
+        }
+    }
+
+    /// Meters in the altitude unit
+    pub fn altitude_value(&self, meters: f64) -> f64 {
+        match self.altitude {
+            AltitudeUnit::M => meters,
+            AltitudeUnit::Ft => meters / METERS_PER_FOOT,
+        }
+    }
+
+    /// An altitude in meters, e.g. "120.5 m" or "395 ft"
+    pub fn format_altitude(&self, meters: f64) -> String {
+        match self.altitude {
+            AltitudeUnit::M => format!("{:.1} m", meters),
+            AltitudeUnit::Ft => format!("{:.0} ft", self.altitude_value(meters)),
+        }
+    }
+
+    pub fn speed_label(&self) -> &'static str {
+        match self.speed {
+            SpeedUnit::Ms => "m/s",
+            SpeedUnit::Kmh => "km/h",
+            SpeedUnit::Mph => "mph",
+            SpeedUnit::Kn => "kn",
+        }
+    }
+
+    /// m/s in the speed unit
+    pub fn speed_value(&self, meters_per_sec: f64) -> f64 {
+        match self.speed {
+            SpeedUnit::Ms => meters_per_sec,
+            SpeedUnit::Kmh => meters_per_sec * 3.6,
+            SpeedUnit::Mph => meters_per_sec * 3600.0 / METERS_PER_MILE,
+            SpeedUnit::Kn => meters_per_sec * 3600.0 / METERS_PER_NAUTICAL_MILE,
+        }
+    }
+
+    /// A speed in m/s, e.g. "15.2 m/s" or "29.5 kn"
+    pub fn format_speed(&self, meters_per_sec: f64) -> String {
+        format!("{:.1} {}", self.speed_value(meters_per_sec), self.speed_label())
+    }
+
+    /// A temperature in °C, as "21.5 C" or "70.7 F" (plain ASCII for the PDFs)
+    pub fn format_temperature(&self, celsius: f64) -> String {
+        match self.temperature {
+            TemperatureUnit::C => format!("{:.1} C", celsius),
+            TemperatureUnit::F => format!("{:.1} F", celsius * 9.0 / 5.0 + 32.0),
+        }
+    }
+}
+
+/// Store the unit settings in config.json
+pub fn save_settings(config_path: &Path, settings: UnitSettings) -> Result<UnitSettings, String> {
+    let mut config: serde_json::Value = std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(serde_json::json!({}));
+    config[CONFIG_KEY] = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+    std::fs::write(config_path, serde_json::to_string_pretty(&config).unwrap())
+        .map_err(|e| format!("Failed to write config: {}", e))?;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_per_field_units() {
+        let units: UnitSettings =
+            serde_json::from_value(serde_json::json!({ "altitude": "ft", "speed": "kn" })).unwrap();
+        assert_eq!(units.distance, DistanceUnit::Km);
+        assert_eq!(units.format_altitude(120.0), "394 ft");
+        assert_eq!(units.format_speed(10.0), "19.4 kn");
+        assert_eq!(units.format_distance(850.0), "850 m");
+        assert_eq!(units.format_distance(12_345.0), "12.3 km");
+        assert_eq!(units.format_temperature(20.0), "20.0 C");
+
+        let aviation = UnitSettings { distance: DistanceUnit::Nm, speed: SpeedUnit::Ms, ..units };
+        assert_eq!(aviation.format_distance(1852.0), "1.0 NM");
+        assert_eq!(aviation.format_speed(10.0), "10.0 m/s");
+    }
+}
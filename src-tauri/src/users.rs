@@ -0,0 +1,329 @@
+//! User accounts and roles for shared web deployments.
+//!
+//! The web server starts out single-user: anyone who can reach it can do
+//! everything (profile passwords aside). Creating the first account switches
+//! it to multi-user mode. Every API request then needs a user session
+//! (`X-User-Session`, issued by `POST /api/users/login`), and each route group
+//! requires a minimum role:
+//!
+//! - `viewer` — read all flights, statistics and exports
+//! - `editor` — also import flights, and edit or delete the flights they imported
+//! - `admin`  — also change any flight, settings, backups, profiles and users
+//!
+//! Accounts are stored in `users.json` in the data directory as argon2id
+//! hashes, like profile passwords; accounts used only through single sign-on
+//! (see `oidc`) have no password. Flights remember the account that imported
+//! them (`flights.owner`); flights imported before multi-user mode have no
+//! owner, so only admins can change them.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::profile_auth;
+
+const MAX_USERNAME_LEN: usize = 64;
+
+/// What an account may do, ordered from least to most privileged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Viewer,
+    Editor,
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Viewer => "viewer",
+            Self::Editor => "editor",
+            Self::Admin => "admin",
+        }
+    }
+}
+
+/// One entry of `users.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UserRecord {
+    /// None for single sign-on only accounts
+    password_hash: Option<String>,
+    role: Role,
+    /// Linked OpenID identity (`<issuer>|<sub>`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    external_id: Option<String>,
+}
+
+/// username → account
+type UserMap = BTreeMap<String, UserRecord>;
+
+/// Account as shown to clients (no password hash)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserInfo {
+    pub username: String,
+    pub role: Role,
+    /// Signs in through the OpenID provider
+    pub sso: bool,
+}
+
+/// The signed-in account making a request (multi-user mode only)
+#[derive(Debug, Clone)]
+pub struct CurrentUser {
+    pub username: String,
+    pub role: Role,
+}
+
+impl CurrentUser {
+    /// Whether this account may change or delete a flight imported by `owner`
+    pub fn can_modify(&self, owner: Option<&str>) -> bool {
+        match self.role {
+            Role::Admin => true,
+            Role::Editor => owner == Some(self.username.as_str()),
+            Role::Viewer => false,
+        }
+    }
+}
+
+fn users_file(data_dir: &Path) -> PathBuf {
+    data_dir.join("users.json")
+}
+
+fn load_users(data_dir: &Path) -> UserMap {
+    fs::read_to_string(users_file(data_dir))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_users(data_dir: &Path, users: &UserMap) -> Result<(), String> {
+    let path = users_file(data_dir);
+    let json = serde_json::to_string_pretty(users)
+        .map_err(|e| format!("Failed to serialize users: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn validate_username(username: &str) -> Result<(), String> {
+    if username.is_empty() || username.len() > MAX_USERNAME_LEN {
+        return Err(format!("Username must be 1 to {} characters", MAX_USERNAME_LEN));
+    }
+    if !username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '@'))
+    {
+        return Err("Username may only contain letters, digits, '.', '_', '-' and '@'".to_string());
+    }
+    Ok(())
+}
+
+/// Reject a change that would leave other accounts without any admin
+fn ensure_admin_remains(users: &UserMap) -> Result<(), String> {
+    if !users.is_empty() && !users.values().any(|u| u.role == Role::Admin) {
+        return Err("At least one admin account is required".to_string());
+    }
+    Ok(())
+}
+
+// ────────────────────────────── public API ──────────────────────────────
+
+/// True once at least one account exists
+pub fn multi_user_enabled(data_dir: &Path) -> bool {
+    !load_users(data_dir).is_empty()
+}
+
+/// All accounts, sorted by username
+pub fn list_users(data_dir: &Path) -> Vec<UserInfo> {
+    load_users(data_dir)
+        .into_iter()
+        .map(|(username, record)| UserInfo {
+            username,
+            role: record.role,
+            sso: record.external_id.is_some(),
+        })
+        .collect()
+}
+
+/// Current role of an account (None once it has been deleted)
+pub fn get_role(data_dir: &Path, username: &str) -> Option<Role> {
+    load_users(data_dir).get(username).map(|u| u.role)
+}
+
+/// Create an account. The first one must be an admin. Without a password
+/// the account can only sign in through the OpenID provider.
+pub fn create_user(data_dir: &Path, username: &str, password: Option<&str>, role: Role) -> Result<UserInfo, String> {
+    let username = username.trim();
+    validate_username(username)?;
+    if password.is_some_and(str::is_empty) {
+        return Err("Password cannot be empty".to_string());
+    }
+    let mut users = load_users(data_dir);
+    if users.contains_key(username) {
+        return Err(format!("User '{}' already exists", username));
+    }
+    if users.is_empty() && role != Role::Admin {
+        return Err("The first account must be an admin".to_string());
+    }
+    users.insert(
+        username.to_string(),
+        UserRecord {
+            password_hash: password.map(profile_auth::hash_password).transpose()?,
+            role,
+            external_id: None,
+        },
+    );
+    save_users(data_dir, &users)?;
+    log::info!("Created {} account '{}'", role.as_str(), username);
+    Ok(UserInfo { username: username.to_string(), role, sso: false })
+}
+
+/// Check a login and return the account's role
+pub fn verify_login(data_dir: &Path, username: &str, password: &str) -> Result<Role, String> {
+    match load_users(data_dir).get(username) {
+        Some(UserRecord { password_hash: Some(hash), role, .. }) if profile_auth::verify_password(password, hash) => {
+            Ok(*role)
+        }
+        _ => {
+            log::warn!("Failed login attempt for user '{}'", username);
+            Err("Incorrect username or password".to_string())
+        }
+    }
+}
+
+/// Change an account's role
+pub fn set_role(data_dir: &Path, username: &str, role: Role) -> Result<(), String> {
+    let mut users = load_users(data_dir);
+    let user = users
+        .get_mut(username)
+        .ok_or_else(|| format!("User '{}' not found", username))?;
+    user.role = role;
+    ensure_admin_remains(&users)?;
+    save_users(data_dir, &users)?;
+    log::info!("User '{}' is now {}", username, role.as_str());
+    Ok(())
+}
+
+/// Replace an account's password
+pub fn set_password(data_dir: &Path, username: &str, new_password: &str) -> Result<(), String> {
+    if new_password.is_empty() {
+        return Err("Password cannot be empty".to_string());
+    }
+    let mut users = load_users(data_dir);
+    let user = users
+        .get_mut(username)
+        .ok_or_else(|| format!("User '{}' not found", username))?;
+    user.password_hash = Some(profile_auth::hash_password(new_password)?);
+    save_users(data_dir, &users)?;
+    log::info!("Password changed for user '{}'", username);
+    Ok(())
+}
+
+/// Map an OpenID identity to a local account: the account linked to
+/// `external_id`, else a new account with `default_role` when given. Only in
+/// multi-user mode.
+///
+/// With `link_by_username` (opt-in, and only for identities whose email the
+/// provider has verified) an unlinked account named `username` is linked now,
+/// but only if it has no password, i.e. an admin created it for single
+/// sign-on. Accounts with a password are never taken over by name.
+pub fn resolve_external(
+    data_dir: &Path,
+    external_id: &str,
+    username: &str,
+    default_role: Option<Role>,
+    link_by_username: bool,
+) -> Result<UserInfo, String> {
+    let mut users = load_users(data_dir);
+    if users.is_empty() {
+        return Err("Create an admin account before signing in with single sign-on".to_string());
+    }
+    if let Some((name, record)) = users.iter().find(|(_, u)| u.external_id.as_deref() == Some(external_id)) {
+        return Ok(UserInfo { username: name.clone(), role: record.role, sso: true });
+    }
+
+    let username = username.trim();
+    let role = match users.get_mut(username) {
+        Some(record) if link_by_username && record.external_id.is_none() && record.password_hash.is_none() => {
+            record.external_id = Some(external_id.to_string());
+            log::info!("Linked account '{}' to single sign-on identity", username);
+            record.role
+        }
+        Some(_) => {
+            log::warn!("Refused single sign-on as '{}': the account is not linked to this identity", username);
+            return Err(format!("Account '{}' is not linked to this identity — ask an admin", username));
+        }
+        None => {
+            let role = default_role
+                .ok_or_else(|| format!("No account for '{}' — ask an admin to create one", username))?;
+            validate_username(username)?;
+            users.insert(
+                username.to_string(),
+                UserRecord {
+                    password_hash: None,
+                    role,
+                    external_id: Some(external_id.to_string()),
+                },
+            );
+            log::info!("Created {} account '{}' on first single sign-on", role.as_str(), username);
+            role
+        }
+    };
+    save_users(data_dir, &users)?;
+    Ok(UserInfo { username: username.to_string(), role, sso: true })
+}
+
+/// Delete an account. Deleting the last one returns to single-user mode.
+pub fn delete_user(data_dir: &Path, username: &str) -> Result<(), String> {
+    let mut users = load_users(data_dir);
+    if users.remove(username).is_none() {
+        return Err(format!("User '{}' not found", username));
+    }
+    ensure_admin_remains(&users)?;
+    save_users(data_dir, &users)?;
+    log::info!("Deleted user '{}'", username);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roles_and_last_admin() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = dir.path();
+
+        assert!(create_user(data_dir, "member", Some("pw"), Role::Editor).is_err());
+        create_user(data_dir, "chair", Some("pw"), Role::Admin).unwrap();
+        create_user(data_dir, "member", Some("pw"), Role::Editor).unwrap();
+        assert!(multi_user_enabled(data_dir));
+        assert_eq!(verify_login(data_dir, "member", "pw"), Ok(Role::Editor));
+        assert!(verify_login(data_dir, "member", "wrong").is_err());
+
+        assert!(set_role(data_dir, "chair", Role::Viewer).is_err());
+        assert!(delete_user(data_dir, "chair").is_err());
+        delete_user(data_dir, "member").unwrap();
+        delete_user(data_dir, "chair").unwrap();
+        assert!(!multi_user_enabled(data_dir));
+
+        // Single sign-on needs an existing admin; new identities get the default role
+        create_user(data_dir, "chair", Some("pw"), Role::Admin).unwrap();
+        assert!(resolve_external(data_dir, "idp|1", "pilot", None, false).is_err());
+        assert_eq!(resolve_external(data_dir, "idp|1", "pilot", Some(Role::Viewer), false).unwrap().role, Role::Viewer);
+        assert_eq!(resolve_external(data_dir, "idp|1", "renamed", None, false).unwrap().username, "pilot");
+
+        // Accounts with a password are never linked by name; invited ones only when opted in
+        assert!(resolve_external(data_dir, "idp|2", "chair", Some(Role::Viewer), true).is_err());
+        create_user(data_dir, "guest", None, Role::Editor).unwrap();
+        assert!(resolve_external(data_dir, "idp|3", "guest", None, false).is_err());
+        assert_eq!(resolve_external(data_dir, "idp|3", "guest", None, true).unwrap().role, Role::Editor);
+        assert!(resolve_external(data_dir, "idp|4", "guest", None, true).is_err());
+        assert!(verify_login(data_dir, "pilot", "").is_err());
+
+        let editor = CurrentUser { username: "member".to_string(), role: Role::Editor };
+        assert!(editor.can_modify(Some("member")));
+        assert!(!editor.can_modify(Some("chair")));
+        assert!(!editor.can_modify(None));
+    }
+}
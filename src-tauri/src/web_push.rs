@@ -0,0 +1,175 @@
+//! Web Push delivery of notifications for web deployments.
+//!
+//! The VAPID key pair comes from the environment: `VAPID_PUBLIC_KEY` and
+//! `VAPID_PRIVATE_KEY` (base64url, as printed by
+//! `npx web-push generate-vapid-keys`), and optionally `VAPID_SUBJECT`, a
+//! `mailto:` or `https:` contact for the push services. Browsers subscribe
+//! with the public key and register the subscription with the profile
+//! (`push_subscriptions` table); each notification is encrypted for and sent
+//! to every subscription of the profile. Subscriptions the push service
+//! reports gone (HTTP 404 / 410) are removed on the next delivery.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use web_push_native::jwt_simple::algorithms::ES256KeyPair;
+use web_push_native::p256::PublicKey;
+use web_push_native::{Auth, WebPushBuilder};
+
+use crate::database::Database;
+use crate::notifications::{Delivery, Notification, PushSubscription};
+
+const DEFAULT_SUBJECT: &str = "mailto:admin@localhost";
+const PUSH_TIMEOUT: Duration = Duration::from_secs(15);
+/// Seconds the push service keeps an undelivered message
+const PUSH_TTL: &str = "86400";
+
+/// Endpoints reported gone, removed from the profiles on the next delivery
+static EXPIRED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+#[derive(Debug, thiserror::Error)]
+pub enum PushError {
+    #[error("Invalid subscription: {0}")]
+    InvalidSubscription(String),
+
+    #[error("Failed to encrypt the message: {0}")]
+    Encrypt(String),
+
+    #[error("Push request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("Subscription expired (HTTP {0})")]
+    Gone(u16),
+
+    #[error("Push service returned HTTP {0}")]
+    Rejected(u16),
+}
+
+/// VAPID keys read from the environment
+pub struct VapidConfig {
+    /// Handed to browsers as `applicationServerKey`
+    pub public_key: String,
+    key_pair: ES256KeyPair,
+    subject: String,
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, base64::DecodeError> {
+    URL_SAFE_NO_PAD.decode(value.trim().trim_end_matches('='))
+}
+
+impl VapidConfig {
+    /// None unless `VAPID_PUBLIC_KEY` and `VAPID_PRIVATE_KEY` are set and valid
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let public_key = var("VAPID_PUBLIC_KEY")?;
+        let private_key = var("VAPID_PRIVATE_KEY")?;
+        let key_pair = match decode(&private_key).map_err(|e| e.to_string()).and_then(|bytes| {
+            ES256KeyPair::from_bytes(&bytes).map_err(|e| e.to_string())
+        }) {
+            Ok(key_pair) => key_pair,
+            Err(e) => {
+                log::error!("Ignoring invalid VAPID_PRIVATE_KEY: {}", e);
+                return None;
+            }
+        };
+        Some(Self {
+            public_key: public_key.trim().to_string(),
+            key_pair,
+            subject: var("VAPID_SUBJECT").unwrap_or_else(|| DEFAULT_SUBJECT.to_string()),
+        })
+    }
+
+    /// Encrypt `payload` for one subscription and send it
+    pub async fn send(&self, client: &reqwest::Client, subscription: &PushSubscription, payload: &[u8]) -> Result<(), PushError> {
+        let invalid = |e: String| PushError::InvalidSubscription(e);
+        let Ok(endpoint) = subscription.endpoint.parse() else {
+            return Err(invalid(format!("endpoint is not a URL: {}", subscription.endpoint)));
+        };
+        let p256dh = decode(&subscription.keys.p256dh).map_err(|e| invalid(e.to_string()))?;
+        let auth = decode(&subscription.keys.auth).map_err(|e| invalid(e.to_string()))?;
+        if auth.len() != 16 {
+            return Err(invalid("auth secret must be 16 bytes".to_string()));
+        }
+        let public_key = PublicKey::from_sec1_bytes(&p256dh).map_err(|e| invalid(e.to_string()))?;
+
+        let request = WebPushBuilder::new(endpoint, public_key, Auth::clone_from_slice(&auth))
+            .with_vapid(&self.key_pair, &self.subject)
+            .build(payload.to_vec())
+            .map_err(|e| PushError::Encrypt(e.to_string()))?;
+
+        let mut builder = client.post(&subscription.endpoint).header("TTL", PUSH_TTL);
+        for (name, value) in request.headers() {
+            if let Ok(value) = value.to_str() {
+                builder = builder.header(name.as_str(), value);
+            }
+        }
+        let response = builder.body(request.into_body()).send().await?;
+        match response.status().as_u16() {
+            status if response.status().is_success() => {
+                log::debug!("Push delivered (HTTP {})", status);
+                Ok(())
+            }
+            status @ (404 | 410) => Err(PushError::Gone(status)),
+            status => Err(PushError::Rejected(status)),
+        }
+    }
+}
+
+fn take_expired(db: &Database) {
+    let expired: Vec<String> = EXPIRED.lock().unwrap().iter().flatten().cloned().collect();
+    if !expired.is_empty() {
+        if let Err(e) = db.remove_push_subscriptions(&expired) {
+            log::warn!("Failed to remove expired push subscriptions: {}", e);
+        }
+    }
+}
+
+/// Forget that an endpoint expired (the browser subscribed again)
+pub fn clear_expired(endpoint: &str) {
+    if let Some(set) = EXPIRED.lock().unwrap().as_mut() {
+        set.remove(endpoint);
+    }
+}
+
+/// Delivery sending each notification to the profile's subscriptions
+pub fn delivery(config: Arc<VapidConfig>) -> Delivery {
+    Box::new(move |db: &Database, notification: &Notification| {
+        take_expired(db);
+        let subscriptions = match db.get_push_subscriptions() {
+            Ok(s) if !s.is_empty() => s,
+            Ok(_) => return,
+            Err(e) => {
+                log::warn!("Failed to read push subscriptions: {}", e);
+                return;
+            }
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            log::warn!("No async runtime to send push notifications from");
+            return;
+        };
+        let payload = serde_json::to_vec(notification).unwrap_or_default();
+        let config = config.clone();
+        runtime.spawn(async move {
+            let client = match reqwest::Client::builder().timeout(PUSH_TIMEOUT).build() {
+                Ok(c) => c,
+                Err(e) => {
+                    log::warn!("Failed to create HTTP client for push: {}", e);
+                    return;
+                }
+            };
+            for subscription in subscriptions {
+                match config.send(&client, &subscription, &payload).await {
+                    Ok(()) => {}
+                    Err(PushError::Gone(_)) => {
+                        log::info!("Push subscription expired, removing: {}", subscription.endpoint);
+                        EXPIRED.lock().unwrap().get_or_insert_with(HashSet::new).insert(subscription.endpoint);
+                    }
+                    Err(e) => log::warn!("Push to {} failed: {}", subscription.endpoint, e),
+                }
+            }
+        });
+    })
+}
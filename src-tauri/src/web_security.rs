@@ -0,0 +1,151 @@
+//! CORS policy and security headers of the web API.
+//!
+//! Cross-origin requests are refused unless their origin is listed in
+//! `CORS_ALLOWED_ORIGINS` (comma-separated, e.g.
+//! `https://logbook.example.com,http://localhost:1420`; `*` allows any origin,
+//! the old behaviour). The bundled frontend is served from the same origin
+//! as the API and needs no entry.
+//!
+//! Every API response gets `X-Content-Type-Options: nosniff`,
+//! `Referrer-Policy`, `X-Frame-Options` (`X_FRAME_OPTIONS`, default
+//! `SAMEORIGIN`) and `Content-Security-Policy` (`CONTENT_SECURITY_POLICY`,
+//! default `default-src 'none'; frame-ancestors 'self'`, as the API serves
+//! data and downloads, never pages). Setting either variable to `off` omits
+//! the header, e.g. when a reverse proxy already adds it.
+
+use std::sync::OnceLock;
+
+use axum::http::{HeaderName, HeaderValue};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+const DEFAULT_CSP: &str = "default-src 'none'; frame-ancestors 'self'";
+const DEFAULT_FRAME_OPTIONS: &str = "SAMEORIGIN";
+
+/// Origins allowed to call the API from a browser
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AllowedOrigins {
+    /// Same-origin requests only
+    None,
+    Any,
+    List(Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+pub struct SecurityConfig {
+    pub allowed_origins: AllowedOrigins,
+    pub content_security_policy: Option<HeaderValue>,
+    pub frame_options: Option<HeaderValue>,
+}
+
+/// Parse a `CORS_ALLOWED_ORIGINS` value. Origins are compared exactly, so
+/// trailing slashes are dropped.
+pub fn parse_origins(value: &str) -> AllowedOrigins {
+    let origins: Vec<String> = value
+        .split(',')
+        .map(|o| o.trim().trim_end_matches('/').to_string())
+        .filter(|o| !o.is_empty())
+        .collect();
+    if origins.iter().any(|o| o == "*") {
+        AllowedOrigins::Any
+    } else if origins.is_empty() {
+        AllowedOrigins::None
+    } else {
+        AllowedOrigins::List(origins)
+    }
+}
+
+/// A header setting: the default when unset, none when `off`
+fn header_setting(name: &str, default: &str) -> Option<HeaderValue> {
+    let value = std::env::var(name).unwrap_or_else(|_| default.to_string());
+    let value = value.trim();
+    if value.is_empty() || value.eq_ignore_ascii_case("off") {
+        return None;
+    }
+    match HeaderValue::from_str(value) {
+        Ok(v) => Some(v),
+        Err(_) => {
+            log::warn!("Ignoring invalid {}, using the default", name);
+            HeaderValue::from_str(default).ok()
+        }
+    }
+}
+
+/// Read the settings from the environment (once)
+pub fn security_config() -> &'static SecurityConfig {
+    static CONFIG: OnceLock<SecurityConfig> = OnceLock::new();
+    CONFIG.get_or_init(|| {
+        let allowed_origins = std::env::var("CORS_ALLOWED_ORIGINS")
+            .map(|v| parse_origins(&v))
+            .unwrap_or(AllowedOrigins::None);
+        match &allowed_origins {
+            AllowedOrigins::None => log::info!("CORS: cross-origin requests are refused"),
+            AllowedOrigins::Any => log::warn!("CORS: cross-origin requests are allowed from any origin"),
+            AllowedOrigins::List(origins) => log::info!("CORS: allowed origins {}", origins.join(", ")),
+        }
+        SecurityConfig {
+            allowed_origins,
+            content_security_policy: header_setting("CONTENT_SECURITY_POLICY", DEFAULT_CSP),
+            frame_options: header_setting("X_FRAME_OPTIONS", DEFAULT_FRAME_OPTIONS),
+        }
+    })
+}
+
+/// The CORS layer for the configured origins
+pub fn cors_layer(config: &SecurityConfig) -> CorsLayer {
+    let cors = CorsLayer::new()
+        .allow_methods(Any)
+        .allow_headers(Any)
+        .expose_headers([HeaderName::from_static("x-api-version")]);
+    match &config.allowed_origins {
+        AllowedOrigins::None => cors,
+        AllowedOrigins::Any => cors.allow_origin(Any),
+        AllowedOrigins::List(origins) => {
+            let origins: Vec<HeaderValue> = origins
+                .iter()
+                .filter_map(|o| match HeaderValue::from_str(o) {
+                    Ok(v) => Some(v),
+                    Err(_) => {
+                        log::warn!("CORS: ignoring invalid origin '{}'", o);
+                        None
+                    }
+                })
+                .collect();
+            cors.allow_origin(AllowOrigin::list(origins))
+        }
+    }
+}
+
+/// Add the security headers to a response (keeping any set by the handler)
+pub async fn add_security_headers(mut response: axum::response::Response) -> axum::response::Response {
+    let config = security_config();
+    let headers = response.headers_mut();
+    headers
+        .entry(axum::http::header::X_CONTENT_TYPE_OPTIONS)
+        .or_insert(HeaderValue::from_static("nosniff"));
+    headers
+        .entry(axum::http::header::REFERRER_POLICY)
+        .or_insert(HeaderValue::from_static("strict-origin-when-cross-origin"));
+    if let Some(value) = &config.frame_options {
+        headers.entry(axum::http::header::X_FRAME_OPTIONS).or_insert(value.clone());
+    }
+    if let Some(value) = &config.content_security_policy {
+        headers.entry(axum::http::header::CONTENT_SECURITY_POLICY).or_insert(value.clone());
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_origins() {
+        assert_eq!(parse_origins(""), AllowedOrigins::None);
+        assert_eq!(parse_origins(" , "), AllowedOrigins::None);
+        assert_eq!(parse_origins("https://a.example, *"), AllowedOrigins::Any);
+        assert_eq!(
+            parse_origins("https://logbook.example.com/, http://localhost:1420"),
+            AllowedOrigins::List(vec!["https://logbook.example.com".to_string(), "http://localhost:1420".to_string()])
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! "Year in review" summary.
+//!
+//! Builds a shareable recap of one calendar year from the flight list: totals,
+//! the longest flight, the most flown aircraft, a month-by-month breakdown and
+//! the flying locations (with the ones first visited that year marked as new)
+//! plus their bounding box, so the frontend can render a recap card and a small
+//! map without fetching any telemetry.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::Datelike;
+use serde::Serialize;
+
+use crate::models::Flight;
+use crate::parser::haversine_distance;
+
+/// Home points closer than this belong to the same location
+const LOCATION_RADIUS_M: f64 = 1000.0;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YearReviewFlight {
+    pub id: i64,
+    pub display_name: String,
+    pub start_time: Option<String>,
+    pub duration_secs: f64,
+    pub total_distance_m: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YearReviewAircraft {
+    pub drone_model: Option<String>,
+    pub drone_serial: Option<String>,
+    pub aircraft_name: Option<String>,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YearReviewMonth {
+    /// 1 = January
+    pub month: u32,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+    pub total_distance_m: f64,
+}
+
+/// A cluster of take-off points flown from during the year
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YearReviewLocation {
+    pub lat: f64,
+    pub lon: f64,
+    pub flight_count: i64,
+    /// No flight was logged here in any earlier year
+    pub is_new: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YearReview {
+    pub year: i32,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+    pub total_hours: f64,
+    pub total_distance_m: f64,
+    pub flight_days: i64,
+    pub longest_flight: Option<YearReviewFlight>,
+    pub favorite_aircraft: Option<YearReviewAircraft>,
+    /// Always 12 entries, January first
+    pub monthly: Vec<YearReviewMonth>,
+    pub locations: Vec<YearReviewLocation>,
+    pub new_location_count: i64,
+    /// `[min_lon, min_lat, max_lon, max_lat]` of the year's locations
+    pub map_bounds: Option<[f64; 4]>,
+}
+
+fn valid_home(f: &Flight) -> Option<(f64, f64)> {
+    match (f.home_lat, f.home_lon) {
+        (Some(lat), Some(lon)) if lat.abs() > 0.000001 || lon.abs() > 0.000001 => Some((lat, lon)),
+        _ => None,
+    }
+}
+
+/// Build the review for `year` from every flight in the logbook (earlier years
+/// are needed to tell which locations are new)
+pub fn build_year_review(year: i32, flights: &[Flight]) -> YearReview {
+    let mut dated: Vec<(chrono::DateTime<chrono::Utc>, &Flight)> = flights
+        .iter()
+        .filter_map(|f| f.start_time_utc().map(|t| (t, f)))
+        .filter(|(t, _)| t.year() <= year)
+        .collect();
+    dated.sort_by_key(|(t, _)| *t);
+
+    // Cluster take-off points in chronological order, remembering the first
+    // year each cluster was flown from
+    struct Cluster {
+        lat: f64,
+        lon: f64,
+        first_year: i32,
+        count_in_year: i64,
+    }
+    let mut clusters: Vec<Cluster> = Vec::new();
+    for (t, f) in &dated {
+        let Some((lat, lon)) = valid_home(f) else { continue };
+        let in_year = t.year() == year;
+        match clusters
+            .iter_mut()
+            .find(|c| haversine_distance(c.lat, c.lon, lat, lon) <= LOCATION_RADIUS_M)
+        {
+            Some(c) => {
+                if in_year {
+                    c.count_in_year += 1;
+                }
+            }
+            None => clusters.push(Cluster {
+                lat,
+                lon,
+                first_year: t.year(),
+                count_in_year: if in_year { 1 } else { 0 },
+            }),
+        }
+    }
+
+    let year_flights: Vec<&Flight> = dated
+        .iter()
+        .filter(|(t, _)| t.year() == year)
+        .map(|(_, f)| *f)
+        .collect();
+
+    let mut monthly: Vec<YearReviewMonth> = (1..=12)
+        .map(|month| YearReviewMonth {
+            month,
+            flight_count: 0,
+            total_duration_secs: 0.0,
+            total_distance_m: 0.0,
+        })
+        .collect();
+    let mut days = HashSet::new();
+    let mut aircraft: HashMap<String, YearReviewAircraft> = HashMap::new();
+    for (t, f) in dated.iter().filter(|(t, _)| t.year() == year) {
+        let m = &mut monthly[t.month0() as usize];
+        m.flight_count += 1;
+        m.total_duration_secs += f.duration_secs.unwrap_or(0.0);
+        m.total_distance_m += f.total_distance.unwrap_or(0.0);
+        days.insert(t.ordinal());
+
+        let key = f
+            .drone_serial
+            .clone()
+            .filter(|s| !s.is_empty())
+            .or_else(|| f.drone_model.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
+        let entry = aircraft.entry(key).or_insert_with(|| YearReviewAircraft {
+            drone_model: f.drone_model.clone(),
+            drone_serial: f.drone_serial.clone().filter(|s| !s.is_empty()),
+            aircraft_name: f.aircraft_name.clone(),
+            flight_count: 0,
+            total_duration_secs: 0.0,
+        });
+        entry.flight_count += 1;
+        entry.total_duration_secs += f.duration_secs.unwrap_or(0.0);
+    }
+
+    let total_duration_secs: f64 = monthly.iter().map(|m| m.total_duration_secs).sum();
+    let total_distance_m: f64 = monthly.iter().map(|m| m.total_distance_m).sum();
+
+    let longest_flight = year_flights
+        .iter()
+        .filter(|f| f.duration_secs.is_some())
+        .max_by(|a, b| {
+            a.duration_secs
+                .unwrap_or(0.0)
+                .partial_cmp(&b.duration_secs.unwrap_or(0.0))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .map(|f| YearReviewFlight {
+            id: f.id,
+            display_name: f.display_name.clone(),
+            start_time: f.start_time.clone(),
+            duration_secs: f.duration_secs.unwrap_or(0.0),
+            total_distance_m: f.total_distance.unwrap_or(0.0),
+        });
+
+    // Most flights wins, total air time breaks ties
+    let favorite_aircraft = aircraft.into_values().max_by(|a, b| {
+        a.flight_count.cmp(&b.flight_count).then(
+            a.total_duration_secs
+                .partial_cmp(&b.total_duration_secs)
+                .unwrap_or(std::cmp::Ordering::Equal),
+        )
+    });
+
+    let locations: Vec<YearReviewLocation> = clusters
+        .iter()
+        .filter(|c| c.count_in_year > 0)
+        .map(|c| YearReviewLocation {
+            lat: c.lat,
+            lon: c.lon,
+            flight_count: c.count_in_year,
+            is_new: c.first_year == year,
+        })
+        .collect();
+
+    let map_bounds = locations.iter().fold(None, |acc: Option<[f64; 4]>, l| {
+        Some(match acc {
+            None => [l.lon, l.lat, l.lon, l.lat],
+            Some(b) => [b[0].min(l.lon), b[1].min(l.lat), b[2].max(l.lon), b[3].max(l.lat)],
+        })
+    });
+
+    YearReview {
+        year,
+        flight_count: year_flights.len() as i64,
+        total_duration_secs,
+        total_hours: total_duration_secs / 3600.0,
+        total_distance_m,
+        flight_days: days.len() as i64,
+        longest_flight,
+        favorite_aircraft,
+        monthly,
+        new_location_count: locations.iter().filter(|l| l.is_new).count() as i64,
+        locations,
+        map_bounds,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FlightBuilder;
+
+    fn flight(id: i64, start: &str, duration: f64, serial: &str, home: (f64, f64)) -> Flight {
+        FlightBuilder::new(id).start(start).duration(duration).drone_serial(serial).home(home.0, home.1).build()
+    }
+
+    #[test]
+    fn test_year_review_totals_and_locations() {
+        let flights = vec![
+            flight(1, "2024-06-01T10:00:00Z", 600.0, "A", (47.0, 8.0)),
+            flight(2, "2025-03-10T10:00:00Z", 1200.0, "A", (47.001, 8.001)), // same spot as 2024
+            flight(3, "2025-03-10T15:00:00Z", 1800.0, "B", (46.0, 7.0)),
+            flight(4, "2025-08-20T09:00:00Z", 900.0, "B", (46.0005, 7.0)),
+            flight(5, "2026-01-02T09:00:00Z", 3000.0, "A", (45.0, 6.0)), // next year
+        ];
+        let review = build_year_review(2025, &flights);
+
+        assert_eq!(review.flight_count, 3);
+        assert_eq!(review.flight_days, 2);
+        assert!((review.total_hours - 1.0833).abs() < 0.001);
+        assert_eq!(review.longest_flight.as_ref().map(|f| f.id), Some(3));
+        assert_eq!(review.favorite_aircraft.as_ref().and_then(|a| a.drone_serial.as_deref()), Some("B"));
+        assert_eq!(review.monthly.len(), 12);
+        assert_eq!(review.monthly[2].flight_count, 2);
+        assert_eq!(review.monthly[7].flight_count, 1);
+
+        assert_eq!(review.locations.len(), 2);
+        assert_eq!(review.new_location_count, 1);
+        let new = review.locations.iter().find(|l| l.is_new).unwrap();
+        assert_eq!(new.flight_count, 2);
+        let bounds = review.map_bounds.unwrap();
+        for (got, want) in bounds.iter().zip([7.0, 46.0, 8.0, 47.0]) {
+            assert!((got - want).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_empty_year() {
+        let review = build_year_review(2020, &[]);
+        assert_eq!(review.flight_count, 0);
+        assert!(review.longest_flight.is_none());
+        assert!(review.map_bounds.is_none());
+        assert_eq!(review.monthly.len(), 12);
+    }
+}
@@ -0,0 +1,62 @@
+//! Corpus regression suite for the log parsers.
+//!
+//! Every file in `tests/corpus/` is a damaged, truncated or hostile input
+//! (see the README there). Each one, and truncations of it, must come back
+//! from `LogParser::parse_log` as an error: a panic escaping the parser, a
+//! hang or an abort fails the suite. Inputs found by the fuzz target
+//! (`fuzz/`) belong here once fixed.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use drone_logbook_lib::database::Database;
+use drone_logbook_lib::parser::LogParser;
+
+/// Longest a single corpus file may take (the DJI decoder's own timeout is longer,
+/// but none of the corpus files should get near it)
+const FILE_TIMEOUT: Duration = Duration::from_secs(60);
+/// Truncation points, as fractions of the file length
+const TRUNCATIONS: [f64; 3] = [0.25, 0.5, 0.75];
+
+fn corpus_files() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("corpus");
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .expect("tests/corpus is missing")
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e != "md"))
+        .collect();
+    files.sort();
+    files
+}
+
+async fn assert_rejected(parser: &LogParser<'_>, path: &Path) {
+    let outcome = tokio::time::timeout(FILE_TIMEOUT, parser.parse_log(path))
+        .await
+        .unwrap_or_else(|_| panic!("{:?}: parser hung", path));
+    if let Ok(result) = outcome {
+        panic!("{:?}: expected an error, got a flight with {} points", path, result.points.len());
+    }
+}
+
+#[tokio::test]
+async fn corpus_inputs_are_rejected_without_panicking() {
+    let data_dir = tempfile::tempdir().unwrap();
+    let db = Database::new(data_dir.path().to_path_buf(), "default").unwrap();
+    let parser = LogParser::new(&db);
+    let scratch = tempfile::tempdir().unwrap();
+
+    let files = corpus_files();
+    assert!(!files.is_empty(), "tests/corpus is empty");
+    for path in &files {
+        assert_rejected(&parser, path).await;
+
+        // Same name (formats are detected by extension), shorter content
+        let bytes = std::fs::read(path).unwrap();
+        for fraction in TRUNCATIONS {
+            let cut = (bytes.len() as f64 * fraction) as usize;
+            let truncated = scratch.path().join(path.file_name().unwrap());
+            std::fs::write(&truncated, &bytes[..cut]).unwrap();
+            assert_rejected(&parser, &truncated).await;
+        }
+    }
+}
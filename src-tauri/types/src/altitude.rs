@@ -0,0 +1,37 @@
+//! Altitude reference of charts, tracks and max-altitude stats.
+
+use serde::{Deserialize, Serialize};
+
+/// What charts, tracks and max-altitude stats are relative to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AltitudeReference {
+    #[default]
+    Takeoff,
+    Msl,
+}
+
+impl AltitudeReference {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim() {
+            "takeoff" => Ok(Self::Takeoff),
+            "msl" => Ok(Self::Msl),
+            other => Err(format!("Unknown altitude reference '{}' (use takeoff or msl)", other)),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Takeoff => "takeoff",
+            Self::Msl => "msl",
+        }
+    }
+
+    /// SQL expression for a flight's max altitude in this reference
+    pub fn max_altitude_sql(self) -> &'static str {
+        match self {
+            Self::Takeoff => "max_altitude",
+            Self::Msl => "COALESCE(max_altitude_msl, max_altitude)",
+        }
+    }
+}
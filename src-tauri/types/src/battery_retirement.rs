@@ -0,0 +1,17 @@
+//! Batteries recommended for retirement.
+
+use serde::{Deserialize, Serialize};
+
+/// A battery past at least one threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetirementRecommendation {
+    pub battery_serial: String,
+    pub display_name: Option<String>,
+    pub cycles: i64,
+    pub capacity_retention_pct: Option<f64>,
+    pub sag_increase_pct: Option<f64>,
+    pub age_days: Option<i64>,
+    /// One sentence per threshold crossed
+    pub reasons: Vec<String>,
+}
@@ -0,0 +1,12 @@
+//! User-defined telemetry series computed at query time.
+
+use serde::{Deserialize, Serialize};
+
+/// One derived series of a flight data response, aligned with its time axis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DerivedSeries {
+    pub name: String,
+    pub unit: Option<String>,
+    pub values: Vec<Option<f64>>,
+}
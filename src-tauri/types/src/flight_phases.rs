@@ -0,0 +1,13 @@
+//! Time spent hovering, in transit, ascending and descending.
+
+use serde::{Deserialize, Serialize};
+
+/// Airborne seconds per phase
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseTimes {
+    pub hover_secs: f64,
+    pub transit_secs: f64,
+    pub ascend_secs: f64,
+    pub descend_secs: f64,
+}
@@ -0,0 +1,29 @@
+//! Recording gaps in a flight's telemetry.
+
+use serde::{Deserialize, Serialize};
+
+/// One recording gap
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryGap {
+    /// Last sample before the gap
+    pub start_ms: i64,
+    /// First sample after the gap
+    pub end_ms: i64,
+    pub duration_ms: i64,
+    /// Straight-line distance between the positions either side, when both are known
+    pub distance_m: Option<f64>,
+}
+
+/// The gaps of a flight and their statistics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GapReport {
+    pub gaps: Vec<TelemetryGap>,
+    pub total_gap_ms: i64,
+    pub longest_gap_ms: i64,
+    /// Share of the recording (first to last sample) not inside a gap, 0-100
+    pub coverage_pct: f64,
+    /// Interval above which this log counts a gap
+    pub threshold_ms: i64,
+}
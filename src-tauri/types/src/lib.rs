@@ -0,0 +1,23 @@
+//! Request and response models of the Open DroneLog API.
+//!
+//! These are the structs the server and the desktop app serialize, so
+//! scripts and companion tools can deserialize API responses without
+//! keeping their own copies in sync. The crate has no dependencies beyond
+//! serde and chrono.
+//!
+//! ```
+//! use drone_logbook_types::models::Flight;
+//!
+//! let body = r#"[{"id": 1, "fileName": "DJIFlightRecord_2026-05-01.txt", "displayName": "Beach", "durationSecs": 912.4}]"#;
+//! let flights: Vec<Flight> = serde_json::from_str(body).unwrap();
+//! assert_eq!(flights[0].duration_secs, Some(912.4));
+//! ```
+//!
+//! Field names are camelCase on the wire, as in `docs/api-guide.md`.
+
+pub mod altitude;
+pub mod battery_retirement;
+pub mod derived_metrics;
+pub mod flight_phases;
+pub mod gaps;
+pub mod models;
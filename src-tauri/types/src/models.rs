@@ -0,0 +1,1124 @@
+//! Data models for the Open DroneLog application.
+//!
+//! These structs are shared between Rust backend and TypeScript frontend
+//! via Tauri's IPC system with serde serialization, and re-exported by the
+//! app as `models`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::altitude::AltitudeReference;
+use crate::derived_metrics::DerivedSeries;
+use crate::flight_phases::PhaseTimes;
+use crate::gaps::GapReport;
+
+/// Flight metadata stored in the flights table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlightMetadata {
+    pub id: i64,
+    pub file_name: String,
+    pub display_name: String,
+    pub file_hash: Option<String>,
+    pub drone_model: Option<String>,
+    pub drone_serial: Option<String>,
+    pub aircraft_name: Option<String>,
+    pub battery_serial: Option<String>,
+    pub cycle_count: Option<i32>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+    pub duration_secs: Option<f64>,
+    pub total_distance: Option<f64>,
+    pub max_altitude: Option<f64>,
+    pub max_speed: Option<f64>,
+    pub home_lat: Option<f64>,
+    pub home_lon: Option<f64>,
+    pub point_count: i32,
+    pub photo_count: i32,
+    pub video_count: i32,
+    pub rc_serial: Option<String>,
+    pub battery_life: Option<i32>,
+}
+
+/// Flight summary for list display
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Flight {
+    pub id: i64,
+    pub file_name: String,
+    pub display_name: String,
+    pub file_hash: Option<String>,
+    pub drone_model: Option<String>,
+    pub drone_serial: Option<String>,
+    pub aircraft_name: Option<String>,
+    pub battery_serial: Option<String>,
+    pub cycle_count: Option<i32>,
+    pub start_time: Option<String>,
+    pub duration_secs: Option<f64>,
+    pub total_distance: Option<f64>,
+    pub max_altitude: Option<f64>,
+    pub max_speed: Option<f64>,
+    pub home_lat: Option<f64>,
+    pub home_lon: Option<f64>,
+    pub point_count: Option<i32>,
+    pub photo_count: Option<i32>,
+    pub video_count: Option<i32>,
+    pub rc_serial: Option<String>,
+    pub battery_life: Option<i32>,
+    #[serde(default)]
+    pub tags: Vec<FlightTag>,
+    pub notes: Option<String>,
+    #[serde(default = "default_flight_color")]
+    pub color: Option<String>,
+    pub pilot: Option<String>,
+    /// Takeoff location name
+    pub location_name: Option<String>,
+    /// Correction applied to the parsed start time (seconds)
+    pub time_offset_secs: Option<f64>,
+    /// Parsed values replaced by metadata edits (`drone_model`, `start_time`, ...)
+    pub original_metadata: Option<serde_json::Value>,
+    /// Entered by hand, without a log file (no telemetry)
+    #[serde(default)]
+    pub is_manual: bool,
+    /// Web account that imported the flight (multi-user mode)
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Takeoffs and landings in the log (None for manual entries and flights
+    /// imported before counting; count as one each)
+    #[serde(default)]
+    pub takeoff_count: Option<i32>,
+    #[serde(default)]
+    pub landing_count: Option<i32>,
+    /// Airborne time split into hover / transit / ascend / descend (None for
+    /// manual entries and flights imported before the split was computed)
+    #[serde(default)]
+    pub phase_times: Option<PhaseTimes>,
+    /// 0 (rough) to 100 (smooth) from jerk, gimbal motion and stick inputs
+    /// (see `smoothness`; None when not scored)
+    #[serde(default)]
+    pub smoothness_score: Option<f64>,
+    /// Altitude data in the log: "barometric", "gps_msl", "barometric+gps_msl"
+    /// or "none" (see `altitude`; None for manual entries)
+    #[serde(default)]
+    pub altitude_source: Option<String>,
+    /// Personal limits this flight exceeded (see `ExceedanceLimits`)
+    #[serde(default)]
+    pub exceedances: Vec<FlightExceedance>,
+}
+
+/// Flights changed since a point in time (`GET /api/flights?modified_since=`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightChanges {
+    /// Flights imported or changed after `modified_since`
+    pub flights: Vec<Flight>,
+    /// Flights deleted after `modified_since`
+    pub deleted_ids: Vec<i64>,
+    /// Server time to pass as `modified_since` on the next request
+    pub synced_at: String,
+}
+
+/// Corrections for `update_flight_metadata` (None = leave unchanged, "" = clear)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightMetadataUpdate {
+    pub drone_model: Option<String>,
+    pub battery_serial: Option<String>,
+    pub pilot: Option<String>,
+    pub location_name: Option<String>,
+    /// Shift of the start time from the parsed value, for logs with a wrong clock
+    pub time_offset_secs: Option<f64>,
+}
+
+impl FlightMetadataUpdate {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.time_offset_secs.is_some_and(|o| !o.is_finite()) {
+            return Err("Time offset must be a number of seconds".to_string());
+        }
+        let fields = [&self.drone_model, &self.battery_serial, &self.pilot, &self.location_name];
+        if fields.iter().all(|f| f.is_none()) && self.time_offset_secs.is_none() {
+            return Err("No metadata fields to update".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn default_flight_color() -> Option<String> {
+    Some("#7dd3fc".to_string())
+}
+
+impl Flight {
+    /// Parse the stored start time (RFC 3339 or DuckDB's VARCHAR cast) as UTC
+    pub fn start_time_utc(&self) -> Option<DateTime<Utc>> {
+        let s = self.start_time.as_deref()?;
+        chrono::DateTime::parse_from_rfc3339(s)
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok()
+            .or_else(|| {
+                chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+                    .or_else(|_| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f"))
+                    .ok()
+                    .map(|ndt| ndt.and_utc())
+            })
+    }
+}
+
+/// A personal limit exceeded by a flight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightExceedance {
+    pub kind: String, // "altitude" or "distance"
+    pub limit_m: f64,
+    pub actual_m: f64,
+}
+
+/// User-configured altitude/range limits (None = not set)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExceedanceLimits {
+    /// Max height above takeoff in meters
+    pub max_altitude_m: Option<f64>,
+    /// Max horizontal distance from home in meters
+    pub max_distance_m: Option<f64>,
+}
+
+/// A tag attached to a flight, with a type indicator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightTag {
+    pub tag: String,
+    pub tag_type: String,  // "auto" or "manual"
+}
+
+/// Raw telemetry point from parser (for bulk insert)
+#[derive(Debug, Clone, Default)]
+pub struct TelemetryPoint {
+    pub timestamp_ms: i64,
+
+    // Position
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub altitude: Option<f64>,
+    pub height: Option<f64>,
+    pub vps_height: Option<f64>,
+    pub altitude_abs: Option<f64>,
+
+    // Velocity
+    pub speed: Option<f64>,
+    pub velocity_x: Option<f64>,
+    pub velocity_y: Option<f64>,
+    pub velocity_z: Option<f64>,
+
+    // Orientation
+    pub pitch: Option<f64>,
+    pub roll: Option<f64>,
+    pub yaw: Option<f64>,
+
+    // Gimbal
+    pub gimbal_pitch: Option<f64>,
+    pub gimbal_roll: Option<f64>,
+    pub gimbal_yaw: Option<f64>,
+
+    // Power
+    pub battery_percent: Option<i32>,
+    pub battery_voltage: Option<f64>,
+    pub battery_current: Option<f64>,
+    pub battery_temp: Option<f64>,
+    pub battery_full_capacity: Option<f64>,
+    pub battery_remained_capacity: Option<f64>,
+    pub cell_voltages: Option<Vec<f64>>,
+
+    // Status
+    pub flight_mode: Option<String>,
+    pub gps_signal: Option<i32>,
+    pub satellites: Option<i32>,
+    pub rc_signal: Option<i32>,
+    pub rc_uplink: Option<i32>,
+    pub rc_downlink: Option<i32>,
+
+    // RC stick inputs (normalized to -100..+100 percentage)
+    pub rc_aileron: Option<f64>,
+    pub rc_elevator: Option<f64>,
+    pub rc_throttle: Option<f64>,
+    pub rc_rudder: Option<f64>,
+
+    // Camera state
+    pub is_photo: Option<bool>,
+    pub is_video: Option<bool>,
+
+    /// Airborne flag reported by the aircraft (motors on and off the ground),
+    /// used to count takeoffs and landings; not stored
+    pub is_flying: Option<bool>,
+}
+
+/// Telemetry record for frontend consumption (optimized for ECharts)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryRecord {
+    pub timestamp_ms: i64,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub altitude: Option<f64>,
+    pub height: Option<f64>,
+    pub vps_height: Option<f64>,
+    pub speed: Option<f64>,
+    pub velocity_x: Option<f64>,
+    pub velocity_y: Option<f64>,
+    pub velocity_z: Option<f64>,
+    pub battery_percent: Option<i32>,
+    pub battery_voltage: Option<f64>,
+    pub battery_temp: Option<f64>,
+    pub battery_current: Option<f64>,
+    pub battery_full_capacity: Option<f64>,
+    pub battery_remained_capacity: Option<f64>,
+    pub cell_voltages: Option<Vec<f64>>,
+    pub pitch: Option<f64>,
+    pub roll: Option<f64>,
+    pub yaw: Option<f64>,
+    pub gimbal_pitch: Option<f64>,
+    pub gimbal_roll: Option<f64>,
+    pub gimbal_yaw: Option<f64>,
+    pub satellites: Option<i32>,
+    pub flight_mode: Option<String>,
+    pub gps_signal: Option<i32>,
+    pub rc_signal: Option<i32>,
+    pub rc_uplink: Option<i32>,
+    pub rc_downlink: Option<i32>,
+    pub rc_aileron: Option<f64>,
+    pub rc_elevator: Option<f64>,
+    pub rc_throttle: Option<f64>,
+    pub rc_rudder: Option<f64>,
+    pub is_photo: Option<bool>,
+    pub is_video: Option<bool>,
+}
+
+/// Flight message (tip or warning from DJI app)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightMessage {
+    pub timestamp_ms: i64,
+    pub message_type: String, // "tip", "warn", or "caution"
+    pub message: String,
+}
+
+/// Response format optimized for ECharts rendering
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightDataResponse {
+    pub flight: Flight,
+    pub telemetry: TelemetryData,
+    pub track: Vec<[f64; 3]>, // [lng, lat, height] for map
+    pub messages: Vec<FlightMessage>,
+    /// User-defined series (see `derived_metrics`), aligned with `telemetry.time`
+    #[serde(default)]
+    pub derived: Vec<DerivedSeries>,
+    /// Recording gaps, each marked in `telemetry` by a sample with every value null
+    #[serde(default)]
+    pub gaps: GapReport,
+    /// Reference of `telemetry.height` and `track` (see `altitude`)
+    #[serde(default)]
+    pub altitude_reference: AltitudeReference,
+}
+
+/// Overview statistics across all flights
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverviewStats {
+    pub total_flights: i64,
+    pub total_distance_m: f64,
+    pub total_duration_secs: f64,
+    pub total_points: i64,
+    pub total_photos: i64,
+    pub total_videos: i64,
+    pub max_altitude_m: f64,
+    pub max_distance_from_home_m: f64,
+    pub batteries_used: Vec<BatteryUsage>,
+    pub drones_used: Vec<DroneUsage>,
+    pub flights_by_date: Vec<FlightDateCount>,
+    pub top_flights: Vec<TopFlight>,
+    pub top_distance_flights: Vec<TopDistanceFlight>,
+    pub battery_health_points: Vec<BatteryHealthPoint>,
+    /// Flights that exceeded the altitude limit
+    pub altitude_exceedance_flights: i64,
+    /// Flights that exceeded the distance limit
+    pub distance_exceedance_flights: i64,
+    /// Airborne time per phase, summed over the flights that have it
+    pub phase_times: PhaseTimes,
+    /// Batteries past a retirement threshold (filled by the command / handler,
+    /// which know the profile's thresholds)
+    #[serde(default)]
+    pub battery_retirement: Vec<crate::battery_retirement::RetirementRecommendation>,
+}
+
+/// Compact logbook summary for dashboard widgets (Homepage, Dashy, ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogbookSummary {
+    pub total_flights: i64,
+    /// Total flight time in hours, rounded to one decimal
+    pub total_hours: f64,
+    /// Total distance in kilometers, rounded to one decimal
+    pub total_distance_km: f64,
+    pub last_flight_date: Option<String>,
+    pub last_flight_name: Option<String>,
+    /// Batteries estimated to be near the end of their cycle life
+    pub battery_warnings: i64,
+    pub battery_warning_serials: Vec<String>,
+}
+
+/// Battery usage summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryUsage {
+    pub battery_serial: String,
+    pub flight_count: i64,
+    /// Total flight duration for this battery in seconds
+    pub total_duration_secs: f64,
+    /// Max battery cycle count observed for this battery (from SmartBatteryStatic)
+    pub max_cycle_count: Option<i32>,
+}
+
+/// Drone usage summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroneUsage {
+    pub drone_model: String,
+    pub drone_serial: Option<String>,
+    pub aircraft_name: Option<String>,
+    pub flight_count: i64,
+}
+
+/// Flight count per date for activity heatmap
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightDateCount {
+    pub date: String,
+    pub count: i64,
+}
+
+/// One day of the activity calendar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityDay {
+    /// `YYYY-MM-DD`
+    pub date: String,
+    pub count: i64,
+    pub duration_secs: f64,
+    pub distance_m: f64,
+}
+
+/// A run of consecutive flying days
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlyingStreak {
+    pub start_date: String,
+    pub end_date: String,
+    pub days: i64,
+}
+
+/// Busiest ISO week (Monday to Sunday)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BusiestWeek {
+    /// Monday of the week, `YYYY-MM-DD`
+    pub week_start: String,
+    pub count: i64,
+    pub duration_secs: f64,
+}
+
+/// Streak and recency statistics over all flights
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityStats {
+    pub flying_days: i64,
+    pub last_flight_date: Option<String>,
+    pub days_since_last_flight: Option<i64>,
+    /// Streak still alive (last flying day is today or yesterday)
+    pub current_streak: Option<FlyingStreak>,
+    pub longest_streak: Option<FlyingStreak>,
+    pub busiest_day: Option<FlightDateCount>,
+    pub busiest_week: Option<BusiestWeek>,
+}
+
+/// Aggregates for one calendar month
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthlyStats {
+    /// "YYYY-MM"
+    pub month: String,
+    pub flight_count: i64,
+    pub total_duration_secs: f64,
+    pub total_distance_m: f64,
+    pub max_altitude_m: f64,
+    pub total_photos: i64,
+    pub total_videos: i64,
+}
+
+/// Top flight summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopFlight {
+    pub id: i64,
+    pub display_name: String,
+    pub duration_secs: f64,
+    pub start_time: Option<String>,
+}
+
+/// Top flight by max distance from home
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopDistanceFlight {
+    pub id: i64,
+    pub display_name: String,
+    pub max_distance_from_home_m: f64,
+    pub start_time: Option<String>,
+}
+
+/// Metric a [`RankedFlight`] list is ordered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TopFlightMetric {
+    /// Longest flights (seconds)
+    Duration,
+    /// Most distance flown (meters)
+    Distance,
+    /// Furthest from home (meters)
+    DistanceFromHome,
+    /// Fastest (m/s)
+    MaxSpeed,
+    /// Highest (meters)
+    MaxAltitude,
+    /// Lowest battery temperature recorded (°C), coldest first
+    ColdestTemperature,
+    /// Most app tips, warnings and cautions
+    MostEvents,
+    /// Highest smoothness score (0-100)
+    Smoothness,
+}
+
+impl TopFlightMetric {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "duration" => Some(Self::Duration),
+            "distance" => Some(Self::Distance),
+            "distance_from_home" => Some(Self::DistanceFromHome),
+            "max_speed" => Some(Self::MaxSpeed),
+            "max_altitude" => Some(Self::MaxAltitude),
+            "coldest_temperature" => Some(Self::ColdestTemperature),
+            "most_events" => Some(Self::MostEvents),
+            "smoothness" => Some(Self::Smoothness),
+            _ => None,
+        }
+    }
+}
+
+/// Telemetry value a [`Histogram`] is binned by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistogramMetric {
+    /// Height above takeoff (meters)
+    Height,
+    /// Ground speed (m/s)
+    Speed,
+}
+
+impl HistogramMetric {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "height" => Some(Self::Height),
+            "speed" => Some(Self::Speed),
+            _ => None,
+        }
+    }
+
+    /// The requested bin width, else the metric's default; None when the
+    /// request is under 0.1 (or not a number)
+    pub fn bin_width(&self, requested: Option<f64>) -> Option<f64> {
+        let default = match self {
+            Self::Height => 10.0,
+            Self::Speed => 1.0,
+        };
+        Some(requested.unwrap_or(default)).filter(|w| *w >= 0.1)
+    }
+}
+
+/// Time spent with the metric in `[min, max)`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramBin {
+    pub min: f64,
+    pub max: f64,
+    pub seconds: f64,
+}
+
+/// Distribution of a telemetry value over time, for one flight or all of them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Histogram {
+    pub metric: HistogramMetric,
+    /// None for the whole logbook
+    pub flight_id: Option<i64>,
+    pub bin_width: f64,
+    pub total_secs: f64,
+    /// Contiguous from the lowest to the highest bin with time in it
+    pub bins: Vec<HistogramBin>,
+}
+
+/// One entry of a top-N flight list; `value` is in the metric's unit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RankedFlight {
+    pub id: i64,
+    pub display_name: String,
+    pub value: f64,
+    pub start_time: Option<String>,
+}
+
+/// Battery health scatter/line point per flight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryHealthPoint {
+    pub flight_id: i64,
+    pub battery_serial: String,
+    pub start_time: Option<String>,
+    pub duration_mins: f64,
+    pub delta_percent: f64,
+    pub rate_per_min: f64,
+}
+
+/// Telemetry data formatted for ECharts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryData {
+    /// Time axis in seconds from flight start
+    pub time: Vec<f64>,
+    /// Latitude series
+    pub latitude: Vec<Option<f64>>,
+    /// Longitude series
+    pub longitude: Vec<Option<f64>>,
+    /// Altitude series (legacy fallback)
+    pub altitude: Vec<Option<f64>>,
+    /// Height series
+    pub height: Vec<Option<f64>>,
+    /// VPS height series
+    pub vps_height: Vec<Option<f64>>,
+    /// Speed series
+    pub speed: Vec<Option<f64>>,
+    /// Velocity X series (north)
+    pub velocity_x: Vec<Option<f64>>,
+    /// Velocity Y series (east)
+    pub velocity_y: Vec<Option<f64>>,
+    /// Velocity Z series (down)
+    pub velocity_z: Vec<Option<f64>>,
+    /// Battery percent series
+    pub battery: Vec<Option<i32>>,
+    /// Battery voltage series
+    pub battery_voltage: Vec<Option<f64>>,
+    /// Battery temperature series
+    pub battery_temp: Vec<Option<f64>>,
+    /// Battery current series
+    pub battery_current: Vec<Option<f64>>,
+    /// Battery full capacity series (mAh)
+    pub battery_full_capacity: Vec<Option<f64>>,
+    /// Battery remaining capacity series (mAh)
+    pub battery_remained_capacity: Vec<Option<f64>>,
+    /// Individual cell voltages series (JSON arrays stored as Vec)
+    pub cell_voltages: Vec<Option<Vec<f64>>>,
+    /// Number of GPS satellites
+    pub satellites: Vec<Option<i32>>,
+    /// RC signal strength
+    pub rc_signal: Vec<Option<i32>>,
+    /// RC uplink signal strength
+    pub rc_uplink: Vec<Option<i32>>,
+    /// RC downlink signal strength
+    pub rc_downlink: Vec<Option<i32>>,
+    /// Pitch angle
+    pub pitch: Vec<Option<f64>>,
+    /// Roll angle
+    pub roll: Vec<Option<f64>>,
+    /// Yaw/Heading
+    pub yaw: Vec<Option<f64>>,
+    /// Gimbal pitch angle
+    pub gimbal_pitch: Vec<Option<f64>>,
+    /// Gimbal roll angle
+    pub gimbal_roll: Vec<Option<f64>>,
+    /// Gimbal yaw/heading
+    pub gimbal_yaw: Vec<Option<f64>>,
+    /// RC aileron stick input (normalized -100..+100)
+    pub rc_aileron: Vec<Option<f64>>,
+    /// RC elevator stick input (normalized -100..+100)
+    pub rc_elevator: Vec<Option<f64>>,
+    /// RC throttle stick input (normalized -100..+100)
+    pub rc_throttle: Vec<Option<f64>>,
+    /// RC rudder stick input (normalized -100..+100)
+    pub rc_rudder: Vec<Option<f64>>,
+    /// Photo capture indicator (true when taking photo)
+    pub is_photo: Vec<Option<bool>>,
+    /// Video recording indicator (true when recording)
+    pub is_video: Vec<Option<bool>>,
+    /// Flight mode (e.g., "GPS", "ATTI", "Sport")
+    pub flight_mode: Vec<Option<String>>,
+    /// Series left empty because every value was null (camelCase field names);
+    /// see [`TelemetryData::prune_empty_series`]
+    #[serde(default)]
+    pub omitted_fields: Vec<String>,
+}
+
+impl TelemetryData {
+    /// Create TelemetryData from a vector of TelemetryRecords
+    ///
+    /// Uses a single pass over the records to build all column vectors
+    /// simultaneously, avoiding 20 separate iterator traversals.
+    pub fn from_records(records: &[TelemetryRecord]) -> Self {
+        let n = records.len();
+        let base_time = records.first().map(|r| r.timestamp_ms).unwrap_or(0);
+
+        let mut time = Vec::with_capacity(n);
+        let mut latitude = Vec::with_capacity(n);
+        let mut longitude = Vec::with_capacity(n);
+        let mut altitude = Vec::with_capacity(n);
+        let mut height = Vec::with_capacity(n);
+        let mut vps_height = Vec::with_capacity(n);
+        let mut speed = Vec::with_capacity(n);
+        let mut velocity_x = Vec::with_capacity(n);
+        let mut velocity_y = Vec::with_capacity(n);
+        let mut velocity_z = Vec::with_capacity(n);
+        let mut battery = Vec::with_capacity(n);
+        let mut battery_voltage = Vec::with_capacity(n);
+        let mut battery_temp = Vec::with_capacity(n);
+        let mut battery_current = Vec::with_capacity(n);
+        let mut battery_full_capacity = Vec::with_capacity(n);
+        let mut battery_remained_capacity = Vec::with_capacity(n);
+        let mut cell_voltages = Vec::with_capacity(n);
+        let mut satellites = Vec::with_capacity(n);
+        let mut rc_signal = Vec::with_capacity(n);
+        let mut rc_uplink = Vec::with_capacity(n);
+        let mut rc_downlink = Vec::with_capacity(n);
+        let mut pitch = Vec::with_capacity(n);
+        let mut roll = Vec::with_capacity(n);
+        let mut yaw = Vec::with_capacity(n);
+        let mut gimbal_pitch = Vec::with_capacity(n);
+        let mut gimbal_roll = Vec::with_capacity(n);
+        let mut gimbal_yaw = Vec::with_capacity(n);
+        let mut rc_aileron = Vec::with_capacity(n);
+        let mut rc_elevator = Vec::with_capacity(n);
+        let mut rc_throttle = Vec::with_capacity(n);
+        let mut rc_rudder = Vec::with_capacity(n);
+        let mut is_photo = Vec::with_capacity(n);
+        let mut is_video = Vec::with_capacity(n);
+        let mut flight_mode = Vec::with_capacity(n);
+
+        for r in records {
+            time.push((r.timestamp_ms - base_time) as f64 / 1000.0);
+            latitude.push(r.latitude);
+            longitude.push(r.longitude);
+            altitude.push(r.altitude);
+            height.push(r.height);
+            vps_height.push(r.vps_height);
+            speed.push(r.speed);
+            velocity_x.push(r.velocity_x);
+            velocity_y.push(r.velocity_y);
+            velocity_z.push(r.velocity_z);
+            battery.push(r.battery_percent);
+            battery_voltage.push(r.battery_voltage);
+            battery_temp.push(r.battery_temp);
+            battery_current.push(r.battery_current);
+            battery_full_capacity.push(r.battery_full_capacity);
+            battery_remained_capacity.push(r.battery_remained_capacity);
+            cell_voltages.push(r.cell_voltages.clone());
+            satellites.push(r.satellites);
+            rc_signal.push(r.rc_signal);
+            rc_uplink.push(r.rc_uplink);
+            rc_downlink.push(r.rc_downlink);
+            pitch.push(r.pitch);
+            roll.push(r.roll);
+            yaw.push(r.yaw);
+            gimbal_pitch.push(r.gimbal_pitch);
+            gimbal_roll.push(r.gimbal_roll);
+            gimbal_yaw.push(r.gimbal_yaw);
+            rc_aileron.push(r.rc_aileron);
+            rc_elevator.push(r.rc_elevator);
+            rc_throttle.push(r.rc_throttle);
+            rc_rudder.push(r.rc_rudder);
+            is_photo.push(r.is_photo);
+            is_video.push(r.is_video);
+            flight_mode.push(r.flight_mode.clone());
+        }
+
+        Self {
+            time,
+            latitude,
+            longitude,
+            altitude,
+            height,
+            vps_height,
+            speed,
+            velocity_x,
+            velocity_y,
+            velocity_z,
+            battery,
+            battery_voltage,
+            battery_temp,
+            battery_current,
+            battery_full_capacity,
+            battery_remained_capacity,
+            cell_voltages,
+            satellites,
+            rc_signal,
+            rc_uplink,
+            rc_downlink,
+            pitch,
+            roll,
+            yaw,
+            gimbal_pitch,
+            gimbal_roll,
+            gimbal_yaw,
+            rc_aileron,
+            rc_elevator,
+            rc_throttle,
+            rc_rudder,
+            is_photo,
+            is_video,
+            flight_mode,
+            omitted_fields: Vec::new(),
+        }
+    }
+
+    /// Empty every series whose values are all null and list it in
+    /// `omitted_fields`, so aircraft that never report e.g. VPS height or RC
+    /// uplink don't ship arrays of nulls. Clients restore omitted series as
+    /// null arrays of the `time` length. Call after [`Self::extract_track`].
+    pub fn prune_empty_series(&mut self) {
+        fn prune<T>(series: &mut Vec<Option<T>>, name: &str, omitted: &mut Vec<String>) {
+            if !series.is_empty() && series.iter().all(Option::is_none) {
+                *series = Vec::new();
+                omitted.push(name.to_string());
+            }
+        }
+
+        let mut omitted = Vec::new();
+        prune(&mut self.latitude, "latitude", &mut omitted);
+        prune(&mut self.longitude, "longitude", &mut omitted);
+        prune(&mut self.altitude, "altitude", &mut omitted);
+        prune(&mut self.height, "height", &mut omitted);
+        prune(&mut self.vps_height, "vpsHeight", &mut omitted);
+        prune(&mut self.speed, "speed", &mut omitted);
+        prune(&mut self.velocity_x, "velocityX", &mut omitted);
+        prune(&mut self.velocity_y, "velocityY", &mut omitted);
+        prune(&mut self.velocity_z, "velocityZ", &mut omitted);
+        prune(&mut self.battery, "battery", &mut omitted);
+        prune(&mut self.battery_voltage, "batteryVoltage", &mut omitted);
+        prune(&mut self.battery_temp, "batteryTemp", &mut omitted);
+        prune(&mut self.battery_current, "batteryCurrent", &mut omitted);
+        prune(&mut self.battery_full_capacity, "batteryFullCapacity", &mut omitted);
+        prune(&mut self.battery_remained_capacity, "batteryRemainedCapacity", &mut omitted);
+        prune(&mut self.cell_voltages, "cellVoltages", &mut omitted);
+        prune(&mut self.satellites, "satellites", &mut omitted);
+        prune(&mut self.rc_signal, "rcSignal", &mut omitted);
+        prune(&mut self.rc_uplink, "rcUplink", &mut omitted);
+        prune(&mut self.rc_downlink, "rcDownlink", &mut omitted);
+        prune(&mut self.pitch, "pitch", &mut omitted);
+        prune(&mut self.roll, "roll", &mut omitted);
+        prune(&mut self.yaw, "yaw", &mut omitted);
+        prune(&mut self.gimbal_pitch, "gimbalPitch", &mut omitted);
+        prune(&mut self.gimbal_roll, "gimbalRoll", &mut omitted);
+        prune(&mut self.gimbal_yaw, "gimbalYaw", &mut omitted);
+        prune(&mut self.rc_aileron, "rcAileron", &mut omitted);
+        prune(&mut self.rc_elevator, "rcElevator", &mut omitted);
+        prune(&mut self.rc_throttle, "rcThrottle", &mut omitted);
+        prune(&mut self.rc_rudder, "rcRudder", &mut omitted);
+        prune(&mut self.is_photo, "isPhoto", &mut omitted);
+        prune(&mut self.is_video, "isVideo", &mut omitted);
+        prune(&mut self.flight_mode, "flightMode", &mut omitted);
+        self.omitted_fields = omitted;
+    }
+
+    /// Extract a GPS track from the telemetry data for map visualization.
+    ///
+    /// Filters out null/zero coordinates and downsamples to `max_points`
+    /// using uniform stride. Returns `[lng, lat, height]` triples.
+    pub fn extract_track(&self, max_points: usize) -> Vec<[f64; 3]> {
+        // Collect valid GPS points
+        let valid: Vec<[f64; 3]> = self.latitude.iter()
+            .zip(self.longitude.iter())
+            .zip(self.height.iter().zip(self.vps_height.iter().zip(self.altitude.iter())))
+            .filter_map(|((lat, lng), (h, (vps, alt)))| {
+                let lat_v = (*lat)?;
+                let lng_v = (*lng)?;
+                // Skip 0,0 points
+                if lat_v.abs() < 0.000001 && lng_v.abs() < 0.000001 {
+                    return None;
+                }
+                let height_v = h.or(*vps).or(*alt).unwrap_or(0.0);
+                Some([lng_v, lat_v, height_v])
+            })
+            .collect();
+
+        if valid.len() <= max_points {
+            return valid;
+        }
+
+        // Downsample with uniform stride
+        let stride = valid.len() / max_points;
+        valid.into_iter()
+            .step_by(stride.max(1))
+            .collect()
+    }
+}
+
+/// Count photo and video capture events from telemetry points.
+/// Photos are counted as false→true transitions in `is_photo`.
+/// Videos are counted as false→true transitions in `is_video`.
+/// Returns (photo_count, video_count).
+pub fn count_media_events(points: &[TelemetryPoint]) -> (i32, i32) {
+    let mut photo_count = 0i32;
+    let mut video_count = 0i32;
+    let mut was_photo = false;
+    let mut was_video = false;
+
+    for p in points {
+        let is_photo = p.is_photo.unwrap_or(false);
+        let is_video = p.is_video.unwrap_or(false);
+
+        if is_photo && !was_photo {
+            photo_count += 1;
+        }
+        if is_video && !was_video {
+            video_count += 1;
+        }
+
+        was_photo = is_photo;
+        was_video = is_video;
+    }
+
+    (photo_count, video_count)
+}
+
+/// Import result returned to frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResult {
+    pub success: bool,
+    pub flight_id: Option<i64>,
+    /// Localized message for display
+    pub message: String,
+    /// Stable message code (e.g. `import.already_imported`) to match on
+    /// instead of the localized text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    pub point_count: usize,
+    pub file_hash: Option<String>,
+    /// Validation summary for a successful import
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report: Option<ImportReport>,
+    /// Set when the file was skipped as a copy of a flight already in the
+    /// logbook; import it again with `onConflict` to replace or keep both
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conflict: Option<ImportConflict>,
+}
+
+/// Flight in the logbook that an imported file duplicates (same aircraft,
+/// start time and duration, although the file bytes differ)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportConflict {
+    pub existing_flight_id: i64,
+    pub existing_name: String,
+    pub existing_start_time: Option<String>,
+    pub existing_duration_secs: Option<f64>,
+    /// Duration of the flight in the file
+    pub duration_secs: Option<f64>,
+    /// What matched, e.g. "same drone, start time, and duration"
+    pub reason: String,
+}
+
+/// Presence of a single telemetry field across an imported flight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldCoverage {
+    pub field: String,
+    pub present_count: usize,
+    pub null_percent: f64,
+}
+
+/// Validation summary stored per flight at import time, so missing chart
+/// data can be told apart from a parsing gap
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub flight_id: i64,
+    pub format: String,
+    pub record_version: Option<i32>,
+    pub decryption: Option<String>,
+    pub quirk: Option<String>,
+    /// Logged speed replaced by speed from GPS positions (see `speed_fallback`)
+    #[serde(default)]
+    pub speed_derived: bool,
+    /// GPS fixes removed as glitches (see `gps_filter`)
+    #[serde(default)]
+    pub gps_outliers: usize,
+    pub point_count: usize,
+    pub field_coverage: Vec<FieldCoverage>,
+    pub created_at: Option<String>,
+}
+
+/// A telemetry column name and whether a point has a value for it
+type FieldCheck = (&'static str, fn(&TelemetryPoint) -> bool);
+
+/// Count non-null values for every optional telemetry column
+pub fn compute_field_coverage(points: &[TelemetryPoint]) -> Vec<FieldCoverage> {
+    let fields: [FieldCheck; 35] = [
+        ("latitude", |p| p.latitude.is_some()),
+        ("longitude", |p| p.longitude.is_some()),
+        ("altitude", |p| p.altitude.is_some()),
+        ("height", |p| p.height.is_some()),
+        ("vps_height", |p| p.vps_height.is_some()),
+        ("altitude_abs", |p| p.altitude_abs.is_some()),
+        ("speed", |p| p.speed.is_some()),
+        ("velocity_x", |p| p.velocity_x.is_some()),
+        ("velocity_y", |p| p.velocity_y.is_some()),
+        ("velocity_z", |p| p.velocity_z.is_some()),
+        ("pitch", |p| p.pitch.is_some()),
+        ("roll", |p| p.roll.is_some()),
+        ("yaw", |p| p.yaw.is_some()),
+        ("gimbal_pitch", |p| p.gimbal_pitch.is_some()),
+        ("gimbal_roll", |p| p.gimbal_roll.is_some()),
+        ("gimbal_yaw", |p| p.gimbal_yaw.is_some()),
+        ("battery_percent", |p| p.battery_percent.is_some()),
+        ("battery_voltage", |p| p.battery_voltage.is_some()),
+        ("battery_current", |p| p.battery_current.is_some()),
+        ("battery_temp", |p| p.battery_temp.is_some()),
+        ("battery_full_capacity", |p| p.battery_full_capacity.is_some()),
+        ("battery_remained_capacity", |p| p.battery_remained_capacity.is_some()),
+        ("cell_voltages", |p| p.cell_voltages.is_some()),
+        ("flight_mode", |p| p.flight_mode.is_some()),
+        ("gps_signal", |p| p.gps_signal.is_some()),
+        ("satellites", |p| p.satellites.is_some()),
+        ("rc_signal", |p| p.rc_signal.is_some()),
+        ("rc_uplink", |p| p.rc_uplink.is_some()),
+        ("rc_downlink", |p| p.rc_downlink.is_some()),
+        ("rc_aileron", |p| p.rc_aileron.is_some()),
+        ("rc_elevator", |p| p.rc_elevator.is_some()),
+        ("rc_throttle", |p| p.rc_throttle.is_some()),
+        ("rc_rudder", |p| p.rc_rudder.is_some()),
+        ("is_photo", |p| p.is_photo.is_some()),
+        ("is_video", |p| p.is_video.is_some()),
+    ];
+
+    let total = points.len();
+    fields
+        .iter()
+        .map(|(name, is_present)| {
+            let present_count = points.iter().filter(|p| is_present(p)).count();
+            let null_percent = if total == 0 {
+                100.0
+            } else {
+                (total - present_count) as f64 * 100.0 / total as f64
+            };
+            FieldCoverage {
+                field: name.to_string(),
+                present_count,
+                null_percent,
+            }
+        })
+        .collect()
+}
+
+/// Statistics for a flight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightStats {
+    pub duration_secs: f64,
+    pub total_distance_m: f64,
+    pub max_altitude_m: f64,
+    pub max_speed_ms: f64,
+    pub avg_speed_ms: f64,
+    pub min_battery: i32,
+    pub home_location: Option<[f64; 2]>,
+    pub max_distance_from_home_m: f64,
+    pub start_battery_percent: Option<i32>,
+    pub end_battery_percent: Option<i32>,
+    pub start_battery_temp: Option<f64>,
+}
+
+/// A document stored alongside the logbook (flight authorization, waiver,
+/// insurance certificate, client contract...). `flight_id` is None for
+/// documents that are not tied to a single flight.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    pub id: String,
+    pub flight_id: Option<i64>,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub description: Option<String>,
+    pub created_at: Option<String>,
+}
+
+/// A reusable pre-flight or post-flight checklist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistTemplate {
+    /// Generated on first save when empty
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub phase: String, // "preflight" or "postflight"
+    pub items: Vec<String>,
+}
+
+/// One line of a completed checklist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistItemResult {
+    pub item: String,
+    pub checked: bool,
+    pub checked_at: Option<String>,
+}
+
+/// A checklist run, attached to a flight or to a session (e.g. a field day)
+/// when the flight has not been imported yet. The template name and phase are
+/// copied so the record survives template edits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistRecord {
+    #[serde(default)]
+    pub id: String,
+    pub template_id: Option<String>,
+    pub template_name: String,
+    pub phase: String,
+    pub flight_id: Option<i64>,
+    pub session: Option<String>,
+    pub items: Vec<ChecklistItemResult>,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Cached list preview of a flight, built at import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightPreview {
+    pub flight_id: i64,
+    /// Simplified `[lon, lat]` polyline
+    pub track: Vec<[f64; 2]>,
+    /// Bucket-averaged height (m) across the flight
+    pub altitude_sparkline: Vec<f64>,
+    /// Bucket-averaged battery percentage across the flight
+    pub battery_sparkline: Vec<f64>,
+    /// Small SVG map thumbnail of the track
+    pub thumbnail_svg: Option<String>,
+}
+
+/// One audit log entry (a data-modifying action)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub id: String,
+    pub timestamp: String,
+    /// "desktop", "sync" or "web (<client address>)"
+    pub actor: String,
+    pub action: String,
+    pub target: Option<String>,
+    pub details: serde_json::Value,
+}
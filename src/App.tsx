@@ -4,7 +4,7 @@ import i18n from '@/i18n';
 import { useFlightStore } from '@/stores/flightStore';
 import { Dashboard } from '@/components/dashboard/Dashboard';
 import { PasswordInput } from '@/components/ui/PasswordInput';
-import { isWebMode, unlockProfile } from '@/lib/api';
+import { isWebMode, setBackendLocale, unlockProfile } from '@/lib/api';
 
 /** Loading overlay shown during database initialization/migration */
 function InitializationOverlay() {
@@ -355,7 +355,7 @@ class AppErrorBoundary extends React.Component<
 
 function App() {
   const { t } = useTranslation();
-  const { loadFlights, error, clearError, donationAcknowledged, themeMode, isFlightsInitialized, needsAuth, loadSupporterStatus } = useFlightStore();
+  const { loadFlights, error, clearError, donationAcknowledged, themeMode, isFlightsInitialized, needsAuth, loadSupporterStatus, appLanguage } = useFlightStore();
   const [bannerDismissed, setBannerDismissed] = useState(() => {
     if (typeof sessionStorage === 'undefined') return false;
     return sessionStorage.getItem('donationBannerDismissed') === 'true';
@@ -366,6 +366,11 @@ function App() {
     loadFlights();
   }, [loadFlights]);
 
+  // Backend messages follow the app language (desktop; web requests send Accept-Language)
+  useEffect(() => {
+    setBackendLocale(appLanguage).catch((e) => console.warn('Failed to set backend locale:', e));
+  }, [appLanguage]);
+
   // Load supporter/donation status from backend on mount
   useEffect(() => {
     loadSupporterStatus();
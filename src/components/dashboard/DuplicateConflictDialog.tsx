@@ -0,0 +1,60 @@
+/**
+ * Asks what to do with an imported file that duplicates a flight already in
+ * the logbook (same drone, start time and duration, different file bytes).
+ */
+
+import { useTranslation } from 'react-i18next';
+import type { ConflictResolution, ImportConflict } from '@/types';
+import { formatDateTime, formatDuration } from '@/lib/utils';
+
+interface DuplicateConflictDialogProps {
+  fileName: string;
+  conflict: ImportConflict;
+  onResolve: (resolution: ConflictResolution) => void;
+}
+
+export function DuplicateConflictDialog({ fileName, conflict, onResolve }: DuplicateConflictDialogProps) {
+  const { t } = useTranslation();
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center p-4">
+      {/* Backdrop */}
+      <div className="absolute inset-0 bg-black/60 backdrop-blur-sm" onClick={() => onResolve('skip')} />
+
+      <div className="relative bg-drone-secondary rounded-xl border border-gray-700 shadow-2xl w-full max-w-md p-4 space-y-3">
+        <h2 className="text-lg font-semibold text-white">{t('importer.conflictTitle')}</h2>
+        <p className="text-sm text-gray-300">
+          {t('importer.conflictBody', { file: fileName, flight: conflict.existingName, reason: conflict.reason })}
+        </p>
+        <dl className="grid grid-cols-2 gap-x-3 gap-y-1 text-xs text-gray-400">
+          <dt>{t('importer.conflictExistingStart')}</dt>
+          <dd className="text-gray-200">{formatDateTime(conflict.existingStartTime)}</dd>
+          <dt>{t('importer.conflictExistingDuration')}</dt>
+          <dd className="text-gray-200">{formatDuration(conflict.existingDurationSecs)}</dd>
+          <dt>{t('importer.conflictNewDuration')}</dt>
+          <dd className="text-gray-200">{formatDuration(conflict.durationSecs)}</dd>
+        </dl>
+        <div className="flex justify-end gap-2 pt-2">
+          <button
+            onClick={() => onResolve('skip')}
+            className="px-3 py-1.5 text-sm rounded-lg text-gray-300 hover:text-white hover:bg-gray-700 transition-colors"
+          >
+            {t('importer.conflictSkip')}
+          </button>
+          <button
+            onClick={() => onResolve('keep_both')}
+            className="px-3 py-1.5 text-sm rounded-lg border border-gray-600 text-gray-200 hover:bg-gray-700 transition-colors"
+          >
+            {t('importer.conflictKeepBoth')}
+          </button>
+          <button
+            onClick={() => onResolve('replace')}
+            className="px-3 py-1.5 text-sm rounded-lg bg-drone-primary text-white hover:bg-drone-primary/80 transition-colors"
+          >
+            {t('importer.conflictReplace')}
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
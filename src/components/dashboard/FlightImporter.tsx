@@ -27,14 +27,17 @@ import {
   removeFromSyncBlacklist,
   clearSyncBlacklist,
   getAllowedLogExtensions,
+  sendNotification,
 } from '@/lib/api';
 import { useFlightStore } from '@/stores/flightStore';
+import type { ConflictResolution, ImportConflict } from '@/types';
 import { ManualEntryModal } from './ManualEntryModal';
+import { DuplicateConflictDialog } from './DuplicateConflictDialog';
 
 // Storage keys for sync folder and autoscan
 const SYNC_FOLDER_KEY = 'syncFolderPath';
 const AUTOSCAN_KEY = 'autoscanEnabled';
-const DEFAULT_ALLOWED_EXTENSIONS = ['txt', 'dat', 'log', 'csv'];
+const DEFAULT_ALLOWED_EXTENSIONS = ['txt', 'dat', 'log', 'csv', 'json', 'gpx', 'pud', 'bbl', 'bfl'];
 
 function normalizeExtension(ext: string): string {
   return ext.trim().replace(/^\./, '').toLowerCase();
@@ -128,6 +131,11 @@ export function FlightImporter() {
   const [backgroundSyncResult, setBackgroundSyncResult] = useState<string | null>(null);
   const [autoscanEnabled, setAutoscanEnabledState] = useState(() => getAutoscanEnabled());
   const [isManualEntryOpen, setIsManualEntryOpen] = useState(false);
+  const [pendingConflict, setPendingConflict] = useState<{
+    fileName: string;
+    conflict: ImportConflict;
+    resolve: (resolution: ConflictResolution) => void;
+  } | null>(null);
   const [allowedExtensions, setAllowedExtensions] = useState<string[]>(DEFAULT_ALLOWED_EXTENSIONS);
   const backgroundSyncTriggeredRef = useRef(false);
   const backgroundSyncAbortRef = useRef(false);
@@ -192,6 +200,21 @@ export function FlightImporter() {
     }
   };
 
+  /**
+   * Import one file; for manual imports, ask what to do when it duplicates a
+   * flight already in the logbook and import it again with the answer
+   */
+  const importWithConflictPrompt = async (item: string | File, name: string, isManualImport: boolean) => {
+    const result = await importLog(item, true);
+    if (!isManualImport || !result.conflict) return result;
+    const conflict = result.conflict;
+    const resolution = await new Promise<ConflictResolution>((resolve) =>
+      setPendingConflict({ fileName: name, conflict, resolve })
+    );
+    setPendingConflict(null);
+    return resolution === 'skip' ? result : importLog(item, true, resolution);
+  };
+
   /** 
    * Process a batch of files efficiently
    * - Personal API keys: no cooldown, optimized batch import
@@ -200,8 +223,11 @@ export function FlightImporter() {
    * - isManualImport: if true, removes files from blacklist (allows re-importing deleted files)
    *                   if false (sync), checks blacklist BEFORE importing and skips blacklisted files
    */
-  const processBatch = async (items: (string | File)[], isManualImport = true) => {
-    if (items.length === 0) return;
+  const processBatch = async (
+    items: (string | File)[],
+    isManualImport = true
+  ): Promise<{ imported: number; skipped: number; errors: number }> => {
+    if (items.length === 0) return { imported: 0, skipped: 0, errors: 0 };
 
     setBatchMessage(null);
     setIsBatchProcessing(true);
@@ -269,11 +295,11 @@ export function FlightImporter() {
         }
 
         // Import without refreshing flight list (skipRefresh = true)
-        const result = await importLog(item, true);
+        const result = await importWithConflictPrompt(item, name, isManualImport);
         if (!result.success) {
-          if (result.message.toLowerCase().includes('already been imported')) {
+          if (result.code === 'import.already_imported') {
             skipped += 1;
-          } else if (result.message.toLowerCase().includes('duplicate flight')) {
+          } else if (result.code === 'import.duplicate') {
             duplicates += 1;
           } else {
             // Parse errors, corrupt files, incompatible formats, timeouts, etc.
@@ -313,6 +339,7 @@ export function FlightImporter() {
       if (blacklisted > 0) parts.push(`${blacklisted} ${t('importer.skippedBlacklisted')}`);
       if (invalidFiles > 0) parts.push(`${invalidFiles} ${t('importer.skippedIncompatible')}`);
       setBatchMessage(`${t('importer.importFinished')} ${parts.join(', ')}.`);
+      return { imported: processed, skipped: skipped + duplicates + blacklisted, errors: invalidFiles };
     } else {
       // Standard path with cooldown (default API key)
       // Refresh flight list after each successful import (during cooldown)
@@ -342,11 +369,11 @@ export function FlightImporter() {
         }
         
         // Use skipRefresh=true to defer refresh until batch completes
-        const result = await importLog(item, true);
+        const result = await importWithConflictPrompt(item, name, isManualImport);
         if (!result.success) {
-          if (result.message.toLowerCase().includes('already been imported')) {
+          if (result.code === 'import.already_imported') {
             skipped += 1;
-          } else if (result.message.toLowerCase().includes('duplicate flight')) {
+          } else if (result.code === 'import.duplicate') {
             duplicates += 1;
           } else {
             // Parse errors, corrupt files, incompatible formats, timeouts, etc.
@@ -394,6 +421,7 @@ export function FlightImporter() {
       if (blacklisted > 0) parts.push(`${blacklisted} ${t('importer.skippedBlacklisted')}`);
       if (invalidFiles > 0) parts.push(`${invalidFiles} ${t('importer.skippedIncompatible')}`);
       setBatchMessage(`${t('importer.importFinished')} ${parts.join(', ')}.`);
+      return { imported: processed, skipped: skipped + duplicates + blacklisted, errors: invalidFiles };
     }
   };
 
@@ -704,7 +732,11 @@ export function FlightImporter() {
           setBackgroundSyncResult(null);
           
           // Process the new files (non-blocking, will show normal import progress)
-          await processBatchRef.current(newFiles, false);
+          const counts = await processBatchRef.current(newFiles, false);
+          if (counts.imported > 0 || counts.errors > 0) {
+            const source = folderPath.split(/[\\/]/).filter(Boolean).pop() ?? folderPath;
+            sendNotification({ kind: 'import_completed', source, ...counts }).catch(() => {});
+          }
         }
       } catch (e) {
         console.error('Background sync check failed:', e);
@@ -1037,6 +1069,14 @@ export function FlightImporter() {
         </>
       )}
 
+      {pendingConflict && (
+        <DuplicateConflictDialog
+          fileName={pendingConflict.fileName}
+          conflict={pendingConflict.conflict}
+          onResolve={pendingConflict.resolve}
+        />
+      )}
+
       {/* Manual Entry Modal */}
       <ManualEntryModal
         isOpen={isManualEntryOpen}
@@ -17,7 +17,7 @@ import { buildCsv, buildJson, buildGpx, buildKml } from '@/lib/exportUtils';
 import { useFlightStore } from '@/stores/flightStore';
 import { formatDuration, formatDateTime, formatDistance, formatAltitude, normalizeSerial, formatDateDisplay } from '@/lib/utils';
 import { type DateRange } from 'react-day-picker';
-import type { FlightDataResponse, Flight, TelemetryData } from '@/types';
+import type { ChecklistRecord, FlightDataResponse, Flight, TelemetryData } from '@/types';
 import { useTranslation } from 'react-i18next';
 import { addToBlacklist } from './FlightImporter';
 import { FlyCardGenerator } from './FlyCardGenerator';
@@ -1529,7 +1529,7 @@ export function FlightList({
         setExportProgress({ done: i, total: filteredFlights.length, currentFile: safeName });
 
         try {
-          const data: FlightDataResponse = await api.getFlightData(flight.id, 999999999);
+          const data: FlightDataResponse = await api.getFlightData(flight.id, 999999999, true);
 
           // Store for summary
           flightsData.push({ flight, data });
@@ -1685,10 +1685,21 @@ export function FlightList({
             }
           }
 
+          // Fetch completed checklists if the section is enabled
+          let checklists: ChecklistRecord[] = [];
+          if (config.fieldConfig.checklists) {
+            try {
+              checklists = await api.getChecklistRecords(flight.id);
+            } catch {
+              // Checklists unavailable; report shows none recorded
+            }
+          }
+
           reportData.push({
             flight,
             data,
             weather,
+            checklists,
             getDroneDisplayName,
             getBatteryDisplayName,
             getDisplaySerial,
@@ -1850,7 +1861,7 @@ export function FlightList({
     if (!flight) return;
 
     try {
-      const data = await api.getFlightData(flightId);
+      const data = await api.getFlightData(flightId, undefined, true);
       if (!data) return;
 
       let content = '';
@@ -118,7 +118,7 @@ export function FlightStats({ data }: FlightStatsProps) {
         .slice(0, 80);
 
       // Fetch full-resolution data for export (display data is downsampled to ~5000 points)
-      const fullData = await getFlightData(flight.id);
+      const fullData = await getFlightData(flight.id, undefined, true);
 
       let content = '';
       switch (format) {
@@ -9,8 +9,8 @@ import { useTranslation } from 'react-i18next';
 import ReactECharts from 'echarts-for-react';
 import { type DateRange } from 'react-day-picker';
 import 'react-day-picker/dist/style.css';
-import type { Flight, OverviewStats } from '@/types';
-import { getBatteryFullCapacityHistory } from '@/lib/api';
+import type { AirframeSeed, Flight, OverviewStats } from '@/types';
+import { getAirframeSeeds, getBatteryFullCapacityHistory, setAirframeSeed } from '@/lib/api';
 import {
   formatDistance,
   formatDuration,
@@ -72,6 +72,27 @@ export function Overview({ stats, flights, unitPrefs, onSelectFlight }: Overview
   const donationAcknowledged = useFlightStore((state) => state.donationAcknowledged);
   const resolvedTheme = useMemo(() => resolveThemeMode(themeMode), [themeMode]);
   const [showSignatureModal, setShowSignatureModal] = useState(false);
+  // Flights and time before this logbook, keyed by upper-cased aircraft serial
+  const [airframeSeeds, setAirframeSeeds] = useState<Record<string, AirframeSeed>>({});
+
+  useEffect(() => {
+    getAirframeSeeds()
+      .then((seeds) => setAirframeSeeds(Object.fromEntries(seeds.map((s) => [s.serial.toUpperCase(), s]))))
+      .catch((e) => console.warn('Failed to load airframe seeds:', e));
+  }, []);
+
+  const saveAirframeSeed = async (seed: AirframeSeed) => {
+    await setAirframeSeed(seed);
+    setAirframeSeeds((prev) => {
+      const next = { ...prev };
+      if (seed.priorDurationSecs > 0 || seed.priorFlights > 0) {
+        next[seed.serial.toUpperCase()] = seed;
+      } else {
+        delete next[seed.serial.toUpperCase()];
+      }
+      return next;
+    });
+  };
 
   // Use sidebar-filtered flights (fall back to all flights if no filter set yet)
   const filteredFlights = useMemo(() => {
@@ -314,6 +335,8 @@ export function Overview({ stats, flights, unitPrefs, onSelectFlight }: Overview
           renameDrone={renameDrone}
           getDisplaySerial={getDisplaySerial}
           hideSerialNumbers={hideSerialNumbers}
+          airframeSeeds={airframeSeeds}
+          saveAirframeSeed={saveAirframeSeed}
         />
       </div>
 
@@ -1148,6 +1171,8 @@ function DroneFlightTimeList({
   renameDrone,
   getDisplaySerial,
   hideSerialNumbers,
+  airframeSeeds,
+  saveAirframeSeed,
 }: {
   drones: { droneModel: string; droneSerial: string | null; aircraftName: string | null; flightCount: number; totalDurationSecs: number; displayLabel: string }[];
   isLight: boolean;
@@ -1155,10 +1180,13 @@ function DroneFlightTimeList({
   renameDrone: (serial: string, displayName: string) => void;
   getDisplaySerial: (serial: string) => string;
   hideSerialNumbers: boolean;
+  airframeSeeds: Record<string, AirframeSeed>;
+  saveAirframeSeed: (seed: AirframeSeed) => Promise<void>;
 }) {
   const { t } = useTranslation();
   const [editingSerial, setEditingSerial] = useState<string | null>(null);
   const [draftName, setDraftName] = useState('');
+  const [draftPriorHours, setDraftPriorHours] = useState('');
   const [renameError, setRenameError] = useState<string | null>(null);
   const [sortMode, setSortMode] = useState<SortMode>('progress');
 
@@ -1200,10 +1228,27 @@ function DroneFlightTimeList({
   const handleStartRename = (serial: string, fallbackName: string) => {
     setEditingSerial(serial);
     setDraftName(getDroneDisplayName(serial, fallbackName));
+    const prior = airframeSeeds[serial.toUpperCase()]?.priorDurationSecs ?? 0;
+    setDraftPriorHours(prior > 0 ? String(Math.round((prior / 3600) * 10) / 10) : '');
     setRenameError(null);
   };
 
   const handleSaveRename = (serial: string) => {
+    const priorHours = draftPriorHours.trim() === '' ? 0 : Number(draftPriorHours);
+    if (!Number.isFinite(priorHours) || priorHours < 0) {
+      setRenameError(t('overview.priorHoursInvalid'));
+      return;
+    }
+    const seed = airframeSeeds[serial.toUpperCase()];
+    const priorSecs = Math.round(priorHours * 3600);
+    if (priorSecs !== Math.round(seed?.priorDurationSecs ?? 0)) {
+      saveAirframeSeed({
+        serial,
+        priorDurationSecs: priorSecs,
+        priorFlights: seed?.priorFlights ?? 0,
+        notes: seed?.notes ?? null,
+      }).catch((e) => console.error('Failed to save airframe seed:', e));
+    }
     const name = draftName.trim();
     if (name.length === 0) {
       setEditingSerial(null);
@@ -1262,6 +1307,9 @@ function DroneFlightTimeList({
           const hours = Math.floor(drone.totalDurationSecs / 3600);
           const minutes = Math.floor((drone.totalDurationSecs % 3600) / 60);
           const durationLabel = hours > 0 ? `${hours}h ${minutes}m` : `${minutes}m`;
+          // Airframe total including the hours flown before this logbook
+          const priorSecs = drone.droneSerial ? (airframeSeeds[drone.droneSerial.toUpperCase()]?.priorDurationSecs ?? 0) : 0;
+          const airframeHours = Math.round(((drone.totalDurationSecs + priorSecs) / 3600) * 10) / 10;
 
           return (
             <div key={drone.droneSerial || drone.droneModel}>
@@ -1281,6 +1329,23 @@ function DroneFlightTimeList({
                     placeholder={t('overview.droneName')}
                     autoFocus
                   />
+                  <input
+                    type="number"
+                    min={0}
+                    step={0.1}
+                    value={draftPriorHours}
+                    onChange={(e) => {
+                      setDraftPriorHours(e.target.value);
+                      setRenameError(null);
+                    }}
+                    onKeyDown={(e) => {
+                      if (e.key === 'Enter') handleSaveRename(drone.droneSerial!);
+                      if (e.key === 'Escape') handleCancelRename();
+                    }}
+                    className="input h-6 text-xs px-2 w-full mt-1"
+                    placeholder={t('overview.priorHours')}
+                    title={t('overview.priorHoursHint')}
+                  />
                   <div className="flex items-center gap-2 mt-0.5">
                     <button
                       onClick={() => handleSaveRename(drone.droneSerial!)}
@@ -1336,8 +1401,14 @@ function DroneFlightTimeList({
                       }}
                     />
                   </div>
-                  <span className="text-[10px] text-gray-400 text-right" style={{ fontVariantNumeric: 'tabular-nums' }}>
-                    {durationLabel} · {t('overview.flightCount', { count: drone.flightCount })}
+                  <span
+                    className="text-[10px] text-gray-400 text-right"
+                    style={{ fontVariantNumeric: 'tabular-nums' }}
+                    title={priorSecs > 0 ? t('overview.airframeHours', { hours: airframeHours }) : undefined}
+                  >
+                    {durationLabel}
+                    {priorSecs > 0 && <span className="text-gray-500"> +{Math.round(priorSecs / 3600)}h</span>}
+                    {' · '}{t('overview.flightCount', { count: drone.flightCount })}
                   </span>
                 </div>
               )}
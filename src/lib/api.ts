@@ -10,13 +10,13 @@
  * - "web"  (set when building for Docker/web deployment)
  */
 
-import type { Flight, FlightDataResponse, FlightTag, ImportResult, OverviewStats } from '@/types';
+import type { ActivityDay, ActivityStats, AirframeSeed, ChecklistRecord, ConflictResolution, CurrentUserStatus, Flight, FlightDataResponse, FlightMetadataUpdate, FlightPreview, FlightTag, Histogram, HistogramMetric, ImportResult, IntegrityReport, LocationOverrides, LogSource, NotificationEvent, OverflightReport, OverviewStats, PlaceStats, PreflightReport, RankedFlight, SqlQueryResult, TelemetryData, TopFlightMetric, UserAccount, UserRole } from '@/types';
 
 const isWeb = import.meta.env.VITE_BACKEND === 'web';
 
 // Base URL for web mode API calls (relative in production, configurable in dev)
-const API_BASE = import.meta.env.VITE_API_URL || '/api';
-const DEFAULT_ALLOWED_LOG_EXTENSIONS = ['txt', 'dat', 'log', 'csv'];
+const API_BASE = import.meta.env.VITE_API_URL || '/api/v1';
+const DEFAULT_ALLOWED_LOG_EXTENSIONS = ['txt', 'dat', 'log', 'csv', 'json', 'gpx', 'pud', 'bbl', 'bfl'];
 
 // ============================================================================
 // Tauri invoke wrapper (lazy-loaded to avoid import errors in web mode)
@@ -86,6 +86,7 @@ export function removeProfileKey(key: string): void {
  * the request to the correct database — enabling independent multi-tab usage.
  * When a session token is available (password-protected profile), it is
  * sent via `X-Session` so the server can authenticate the request.
+ * On multi-user servers the signed-in account's token goes in `X-User-Session`.
  */
 function profileHeaders(): Record<string, string> {
   const headers: Record<string, string> = {};
@@ -94,6 +95,15 @@ function profileHeaders(): Record<string, string> {
   if (session) {
     headers['X-Session'] = session;
   }
+  const userSession = typeof localStorage !== 'undefined' ? localStorage.getItem('userSession') : null;
+  if (userSession) {
+    headers['X-User-Session'] = userSession;
+  }
+  // Backend messages are served in the app language
+  const language = typeof localStorage !== 'undefined' ? localStorage.getItem('appLanguage') : null;
+  if (language) {
+    headers['Accept-Language'] = language;
+  }
   return headers;
 }
 
@@ -106,9 +116,11 @@ async function fetchJson<T>(url: string, options?: RequestInit): Promise<T> {
   if (!response.ok) {
     const body = await response.text();
     let errorMsg: string;
+    let code: string | undefined;
     try {
       const parsed = JSON.parse(body);
       errorMsg = parsed.error || body;
+      code = parsed.code;
     } catch {
       errorMsg = body;
     }
@@ -118,7 +130,8 @@ async function fetchJson<T>(url: string, options?: RequestInit): Promise<T> {
       removeProfileKey('profileSession');
       _onSessionExpired?.();
     }
-    throw new Error(errorMsg);
+    // Catalog messages are localized; `code` identifies them
+    throw Object.assign(new Error(errorMsg), { code });
   }
   return response.json();
 }
@@ -127,6 +140,13 @@ async function fetchJson<T>(url: string, options?: RequestInit): Promise<T> {
 // API Functions
 // ============================================================================
 
+/** Set the language of backend messages (desktop only; web requests send Accept-Language) */
+export async function setBackendLocale(locale: string): Promise<void> {
+  if (isWeb) return;
+  const invoke = await getTauriInvoke();
+  await invoke('set_locale', { locale });
+}
+
 export async function getFlights(): Promise<Flight[]> {
   if (isWeb) {
     return fetchJson<Flight[]>('/flights');
@@ -143,6 +163,95 @@ export async function getOverviewStats(): Promise<OverviewStats> {
   return invoke('get_overview_stats') as Promise<OverviewStats>;
 }
 
+export async function getTopFlights(metric: TopFlightMetric, limit = 10): Promise<RankedFlight[]> {
+  if (isWeb) {
+    const params = new URLSearchParams({ metric, limit: String(limit) });
+    return fetchJson<RankedFlight[]>(`/flights/top?${params}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_top_flights', { metric, limit }) as Promise<RankedFlight[]>;
+}
+
+/** Run a read-only SELECT against the database (single statement, 10 s limit) */
+export async function runSqlQuery(sql: string, maxRows?: number): Promise<SqlQueryResult> {
+  if (isWeb) {
+    return fetchJson<SqlQueryResult>('/sql/query', {
+      method: 'POST',
+      body: JSON.stringify({ sql, max_rows: maxRows ?? null }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('run_sql_query', { sql, maxRows: maxRows ?? null }) as Promise<SqlQueryResult>;
+}
+
+/** Pre-flight briefing for a location and (RFC 3339) time, now when omitted */
+export async function getPreflightBriefing(lat: number, lon: number, time?: string): Promise<PreflightReport> {
+  if (isWeb) {
+    const params = new URLSearchParams({ lat: String(lat), lon: String(lon) });
+    if (time) params.set('time', time);
+    return fetchJson<PreflightReport>(`/preflight?${params}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_preflight_briefing', { lat, lon, time: time ?? null }) as Promise<PreflightReport>;
+}
+
+/** Check the logbook integrity chain and every flight against it */
+export async function verifyIntegrity(): Promise<IntegrityReport> {
+  if (isWeb) {
+    return fetchJson<IntegrityReport>('/integrity/verify');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('verify_integrity') as Promise<IntegrityReport>;
+}
+
+/** Time spent per height or speed band, for one flight or (without `flightId`) all of them */
+export async function getHistogram(metric: HistogramMetric, flightId?: number, binWidth?: number): Promise<Histogram> {
+  if (isWeb) {
+    const params = new URLSearchParams({ metric });
+    if (flightId !== undefined) params.set('flight_id', String(flightId));
+    if (binWidth !== undefined) params.set('bin_width', String(binWidth));
+    return fetchJson<Histogram>(`/histogram?${params}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_histogram', { metric, flightId: flightId ?? null, binWidth: binWidth ?? null }) as Promise<Histogram>;
+}
+
+/** Flights per day over all years, or the inclusive `YYYY-MM-DD` range */
+export async function getActivityCalendar(from?: string, to?: string): Promise<ActivityDay[]> {
+  if (isWeb) {
+    const params = new URLSearchParams();
+    if (from) params.set('from', from);
+    if (to) params.set('to', to);
+    return fetchJson<ActivityDay[]>(`/activity/calendar?${params}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_activity_calendar', { from: from ?? null, to: to ?? null }) as Promise<ActivityDay[]>;
+}
+
+export async function getActivityStats(): Promise<ActivityStats> {
+  if (isWeb) {
+    return fetchJson<ActivityStats>('/activity/stats');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_activity_stats') as Promise<ActivityStats>;
+}
+
+/** Flights, minutes and heights over an area drawn as `[lon, lat]` vertices */
+export async function getOverflightReport(
+  polygon: [number, number][],
+  from?: string,
+  to?: string,
+): Promise<OverflightReport> {
+  if (isWeb) {
+    return fetchJson<OverflightReport>('/reports/overflight', {
+      method: 'POST',
+      body: JSON.stringify({ polygon, from: from ?? null, to: to ?? null }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_overflight_report', { polygon, from: from ?? null, to: to ?? null }) as Promise<OverflightReport>;
+}
+
 export async function getBatteryFullCapacityHistory(
   batterySerial: string,
 ): Promise<[number, string, number][]> {
@@ -154,20 +263,90 @@ export async function getBatteryFullCapacityHistory(
   return invoke('get_battery_full_capacity_history', { batterySerial }) as Promise<[number, string, number][]>;
 }
 
+/** Telemetry in the web server's `encoding=compact` transfer format */
+interface CompactTelemetry {
+  encoding: string;
+  timeMs: number[];
+  series: Record<string, { scale: number; boolean?: boolean; values: (number | null)[] }>;
+  raw: Record<string, unknown[]>;
+  omittedFields: string[];
+}
+
+/** Decode compact telemetry: accumulate the deltas and undo the fixed-point scale */
+function decodeCompactTelemetry(compact: CompactTelemetry): TelemetryData {
+  if (compact.encoding !== 'compact-v1') {
+    throw new Error(`Unsupported telemetry encoding: ${compact.encoding}`);
+  }
+  const telemetry: Record<string, unknown> = { ...compact.raw, omittedFields: compact.omittedFields };
+  let ms = 0;
+  telemetry.time = compact.timeMs.map((delta) => (ms += delta) / 1000);
+  for (const [field, series] of Object.entries(compact.series)) {
+    let acc = 0;
+    telemetry[field] = series.values.map((delta) => {
+      if (delta == null) return null;
+      acc += delta;
+      return series.boolean ? acc !== 0 : acc / series.scale;
+    });
+  }
+  return telemetry as unknown as TelemetryData;
+}
+
+/** Re-expand series the backend omitted because they were all null */
+function restoreOmittedSeries(data: FlightDataResponse): FlightDataResponse {
+  const telemetry = data.telemetry as unknown as Record<string, unknown>;
+  const length = data.telemetry.time.length;
+  for (const field of data.telemetry.omittedFields ?? []) {
+    telemetry[field] = new Array(length).fill(null);
+  }
+  return data;
+}
+
+/** Web server bounds on telemetry points per request (null = unbounded) */
+interface MaxPointsConfig {
+  defaultMaxPoints: number | null;
+  maxPointsLimit: number | null;
+}
+
+let maxPointsConfig: Promise<MaxPointsConfig> | null = null;
+
+/** Clamp a requested point count to the server's limit, so full-resolution
+ * requests degrade to the finest resolution the deployment allows */
+async function clampMaxPoints(maxPoints?: number): Promise<number | undefined> {
+  maxPointsConfig ??= fetchJson<MaxPointsConfig>('/flight_data/limits').catch(() => {
+    maxPointsConfig = null;
+    return { defaultMaxPoints: null, maxPointsLimit: null };
+  });
+  const { maxPointsLimit } = await maxPointsConfig;
+  if (maxPointsLimit == null) return maxPoints;
+  return maxPoints == null ? maxPointsLimit : Math.min(maxPoints, maxPointsLimit);
+}
+
+/**
+ * Get a flight's data. Pass `anonymize` for exports: positions near saved home
+ * zones are then hidden or offset according to the privacy settings.
+ */
 export async function getFlightData(
   flightId: number,
   maxPoints?: number,
+  anonymize = false,
 ): Promise<FlightDataResponse> {
   if (isWeb) {
-    const params = new URLSearchParams({ flight_id: String(flightId) });
-    if (maxPoints != null) params.set('max_points', String(maxPoints));
-    return fetchJson<FlightDataResponse>(`/flight_data?${params}`);
+    const params = new URLSearchParams({ flight_id: String(flightId), encoding: 'compact' });
+    const points = await clampMaxPoints(maxPoints);
+    if (points != null) params.set('max_points', String(points));
+    if (anonymize) params.set('anonymize', 'true');
+    const data = await fetchJson<Omit<FlightDataResponse, 'telemetry'> & { telemetry: CompactTelemetry }>(
+      `/flight_data?${params}`,
+    );
+    return restoreOmittedSeries({ ...data, telemetry: decodeCompactTelemetry(data.telemetry) });
   }
   const invoke = await getTauriInvoke();
-  return invoke('get_flight_data', {
+  const data = (await invoke('get_flight_data', {
     flightId,
     maxPoints: maxPoints ?? null,
-  }) as Promise<FlightDataResponse>;
+    anonymize,
+  })) as FlightDataResponse;
+  return restoreOmittedSeries(data);
 }
 
 /**
@@ -177,6 +356,7 @@ export async function getFlightData(
  */
 export async function importLog(
   fileOrPath: string | File,
+  onConflict?: ConflictResolution,
 ): Promise<ImportResult> {
   if (isWeb) {
     const formData = new FormData();
@@ -184,7 +364,8 @@ export async function importLog(
       throw new Error('File path import is not supported in web mode. Please provide a File object.');
     }
     formData.append('file', fileOrPath, fileOrPath.name);
-    const response = await fetch(`${API_BASE}/import`, {
+    const query = onConflict ? `?on_conflict=${onConflict}` : '';
+    const response = await fetch(`${API_BASE}/import${query}`, {
       method: 'POST',
       body: formData,
       headers: profileHeaders(),
@@ -196,7 +377,7 @@ export async function importLog(
     return response.json();
   }
   const invoke = await getTauriInvoke();
-  return invoke('import_log', { filePath: fileOrPath as string }) as Promise<ImportResult>;
+  return invoke('import_log', { filePath: fileOrPath as string, onConflict: onConflict ?? null }) as Promise<ImportResult>;
 }
 
 /**
@@ -206,8 +387,8 @@ export async function importLog(
 export interface CreateManualFlightParams {
   flightTitle?: string; // Optional custom display name
   aircraftName: string;
-  droneSerial: string;
-  batterySerial: string;
+  droneSerial?: string; // Optional for aircraft without serial records
+  batterySerial?: string;
   startTime: string; // ISO 8601 format
   durationSecs: number;
   totalDistance?: number; // in meters
@@ -226,8 +407,8 @@ export async function createManualFlight(
       body: JSON.stringify({
         flight_title: params.flightTitle ?? null,
         aircraft_name: params.aircraftName,
-        drone_serial: params.droneSerial,
-        battery_serial: params.batterySerial,
+        drone_serial: params.droneSerial ?? null,
+        battery_serial: params.batterySerial ?? null,
         start_time: params.startTime,
         duration_secs: params.durationSecs,
         total_distance: params.totalDistance ?? null,
@@ -242,8 +423,8 @@ export async function createManualFlight(
   return invoke('create_manual_flight', {
     flightTitle: params.flightTitle ?? null,
     aircraftName: params.aircraftName,
-    droneSerial: params.droneSerial,
-    batterySerial: params.batterySerial,
+    droneSerial: params.droneSerial ?? null,
+    batterySerial: params.batterySerial ?? null,
     startTime: params.startTime,
     durationSecs: params.durationSecs,
     totalDistance: params.totalDistance ?? null,
@@ -254,6 +435,37 @@ export async function createManualFlight(
   }) as Promise<ImportResult>;
 }
 
+/**
+ * Attach a GPX track (watch, phone) to a manual flight, giving it a map path
+ * and distance stats. Returns the updated flight.
+ * - Tauri: passes a file path string
+ * - Web: uploads the file via multipart/form-data
+ */
+export async function attachManualTrack(
+  flightId: number,
+  fileOrPath: string | File,
+): Promise<Flight> {
+  if (isWeb) {
+    if (typeof fileOrPath === 'string') {
+      throw new Error('File path upload is not supported in web mode. Please provide a File object.');
+    }
+    const formData = new FormData();
+    formData.append('file', fileOrPath, fileOrPath.name);
+    const response = await fetch(`${API_BASE}/flights/manual_track?flight_id=${flightId}`, {
+      method: 'POST',
+      body: formData,
+      headers: profileHeaders(),
+    });
+    if (!response.ok) {
+      const body = await response.text();
+      throw new Error(body);
+    }
+    return response.json();
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('attach_manual_track', { flightId, filePath: fileOrPath as string }) as Promise<Flight>;
+}
+
 /**
  * Compute file hash without importing.
  * Tauri-only: used to check blacklist before importing.
@@ -266,6 +478,18 @@ export async function computeFileHash(filePath: string): Promise<string> {
   return invoke('compute_file_hash', { filePath }) as Promise<string>;
 }
 
+/**
+ * Find DJI app folders with flight logs on this computer, to offer as import sources.
+ * Tauri-only: the web server can't see the user's drives, so this returns none.
+ */
+export async function detectLogSources(): Promise<LogSource[]> {
+  if (isWeb) {
+    return [];
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('detect_log_sources') as Promise<LogSource[]>;
+}
+
 /**
  * Get all allowed import extensions.
  * - Tauri: built-in + parsers.json mappings from app data directory.
@@ -291,6 +515,33 @@ export async function getAllowedLogExtensions(): Promise<string[]> {
   return extensions;
 }
 
+/** Completed checklists of a flight (all checklists when flightId is omitted) */
+export async function getFlightPreviews(flightIds?: number[]): Promise<FlightPreview[]> {
+  if (isWeb) {
+    const params = flightIds ? `?ids=${flightIds.join(',')}` : '';
+    return fetchJson<FlightPreview[]>(`/flights/previews${params}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_flight_previews', { flightIds: flightIds ?? null }) as Promise<FlightPreview[]>;
+}
+
+export async function seedDemoData(): Promise<number[]> {
+  if (isWeb) {
+    return fetchJson<number[]>('/demo/seed', { method: 'POST' });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('seed_demo_data') as Promise<number[]>;
+}
+
+export async function getChecklistRecords(flightId?: number): Promise<ChecklistRecord[]> {
+  if (isWeb) {
+    const params = flightId != null ? `?flight_id=${flightId}` : '';
+    return fetchJson<ChecklistRecord[]>(`/checklists/records${params}`);
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_checklist_records', { flightId: flightId ?? null }) as Promise<ChecklistRecord[]>;
+}
+
 export async function deleteFlight(flightId: number): Promise<boolean> {
   if (isWeb) {
     return fetchJson<boolean>(`/flights/delete?flight_id=${flightId}`, {
@@ -337,6 +588,20 @@ export async function updateFlightName(
   return invoke('update_flight_name', { flightId, displayName }) as Promise<boolean>;
 }
 
+export async function updateFlightMetadata(
+  flightId: number,
+  update: FlightMetadataUpdate,
+): Promise<Flight> {
+  if (isWeb) {
+    return fetchJson<Flight>('/flights/metadata', {
+      method: 'PUT',
+      body: JSON.stringify({ flight_id: flightId, update }),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('update_flight_metadata', { flightId, update }) as Promise<Flight>;
+}
+
 export async function updateFlightNotes(
   flightId: number,
   notes: string | null,
@@ -456,6 +721,57 @@ export async function setEquipmentName(serial: string, equipmentType: 'battery'
   return invoke('set_equipment_name', { serial, equipmentType, displayName }) as Promise<boolean>;
 }
 
+/** Flights and time each aircraft flew before this logbook */
+export async function getAirframeSeeds(): Promise<AirframeSeed[]> {
+  if (isWeb) {
+    return fetchJson<AirframeSeed[]>('/airframe_seeds');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_airframe_seeds') as Promise<AirframeSeed[]>;
+}
+
+/** Set an aircraft's airframe seed (zero flights and time removes it) */
+export async function setAirframeSeed(seed: AirframeSeed): Promise<boolean> {
+  if (isWeb) {
+    return fetchJson<boolean>('/airframe_seeds', {
+      method: 'POST',
+      body: JSON.stringify(seed),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('set_airframe_seed', { seed }) as Promise<boolean>;
+}
+
+/** Hours, flights and last visit per named site or take-off cluster */
+export async function getPlaceStats(): Promise<PlaceStats[]> {
+  if (isWeb) {
+    return fetchJson<PlaceStats[]>('/analytics/places');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_place_stats') as Promise<PlaceStats[]>;
+}
+
+/** Location tag renames and named sites */
+export async function getLocationOverrides(): Promise<LocationOverrides> {
+  if (isWeb) {
+    return fetchJson<LocationOverrides>('/location_overrides');
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('get_location_overrides') as Promise<LocationOverrides>;
+}
+
+/** Replace the location tag renames and named sites (applied on import and reprocess) */
+export async function setLocationOverrides(overrides: LocationOverrides): Promise<LocationOverrides> {
+  if (isWeb) {
+    return fetchJson<LocationOverrides>('/location_overrides', {
+      method: 'POST',
+      body: JSON.stringify(overrides),
+    });
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('set_location_overrides', { overrides }) as Promise<LocationOverrides>;
+}
+
 // ============================================================================
 // Tag Management
 // ============================================================================
@@ -681,6 +997,38 @@ export async function setKeepUploadSettings(enabled: boolean, folderPath?: strin
   }) as Promise<KeepUploadSettings>;
 }
 
+/** Notify an event the frontend finished, e.g. the folder sync at startup (Tauri desktop only) */
+export async function sendNotification(event: NotificationEvent): Promise<boolean> {
+  if (isWeb) {
+    // The server notifies its own events
+    return false;
+  }
+  const invoke = await getTauriInvoke();
+  return invoke('send_notification', { event }) as Promise<boolean>;
+}
+
+/** Subscribe this browser to Web Push notifications for the profile (web mode only) */
+export async function subscribeToPush(): Promise<boolean> {
+  if (!isWeb || !('serviceWorker' in navigator) || !('PushManager' in window)) {
+    return false;
+  }
+  const { vapidPublicKey } = await fetchJson<{ vapidPublicKey: string | null }>('/notifications/settings');
+  if (!vapidPublicKey || (await Notification.requestPermission()) !== 'granted') {
+    return false;
+  }
+  const registration = await navigator.serviceWorker.register('/push-sw.js');
+  const key = atob(vapidPublicKey.replace(/-/g, '+').replace(/_/g, '/'));
+  const subscription = await registration.pushManager.subscribe({
+    userVisibleOnly: true,
+    applicationServerKey: Uint8Array.from(key, (c) => c.charCodeAt(0)),
+  });
+  await fetchJson('/notifications/subscribe', {
+    method: 'POST',
+    body: JSON.stringify(subscription.toJSON()),
+  });
+  return true;
+}
+
 // ============================================================================
 // File helpers for web mode (replacing Tauri dialog/fs plugins)
 // ============================================================================
@@ -862,10 +1210,14 @@ function getBackupFilename(): string {
   return `${timestamp}_Open_Dronelog.db.backup`;
 }
 
-export async function backupDatabase(): Promise<boolean> {
+/**
+ * Export a backup. With `scrub`, serial numbers are hashed, coordinates shifted
+ * and identifying tables left out, so the file can be shared for debugging.
+ */
+export async function backupDatabase(scrub = false): Promise<boolean> {
   if (isWeb) {
     // Web mode: download via fetch
-    const response = await fetch(`${API_BASE}/backup`, { headers: profileHeaders() });
+    const response = await fetch(`${API_BASE}/backup${scrub ? '?scrub=true' : ''}`, { headers: profileHeaders() });
     if (!response.ok) {
       const body = await response.text();
       throw new Error(body);
@@ -883,7 +1235,7 @@ export async function backupDatabase(): Promise<boolean> {
   });
   if (!destPath) return false; // user cancelled
   const invoke = await getTauriInvoke();
-  await invoke('export_backup', { destPath });
+  await invoke('export_backup', { destPath, scrub });
   return true;
 }
 
@@ -1060,6 +1412,89 @@ export async function hasMasterPassword(): Promise<boolean> {
   return false;
 }
 
+// ── User accounts and roles (web multi-user mode only) ──
+
+export async function getCurrentUser(): Promise<CurrentUserStatus> {
+  if (!isWeb) return { multiUser: false, user: null, sso: false };
+  return fetchJson<CurrentUserStatus>('/users/me');
+}
+
+export async function loginUser(username: string, password: string): Promise<UserAccount> {
+  const result = await fetchJson<{ user: UserAccount; session: string }>('/users/login', {
+    method: 'POST',
+    body: JSON.stringify({ username, password }),
+  });
+  localStorage.setItem('userSession', result.session);
+  return result.user;
+}
+
+/** Start single sign-on: the server redirects to the OpenID provider */
+export function startSsoLogin(): void {
+  window.location.href = `${API_BASE}/auth/oidc/login`;
+}
+
+/**
+ * Pick up the result of a single sign-on redirect (`#user_session=...` or
+ * `#sso_error=...`), store the session and clean the URL.
+ * Returns the error message, if any.
+ */
+export function consumeSsoRedirect(): string | null {
+  if (!isWeb || !window.location.hash) return null;
+  const params = new URLSearchParams(window.location.hash.slice(1));
+  const session = params.get('user_session');
+  const error = params.get('sso_error');
+  if (!session && !error) return null;
+  if (session) localStorage.setItem('userSession', session);
+  window.history.replaceState(null, '', window.location.pathname + window.location.search);
+  return error;
+}
+
+export async function logoutUser(): Promise<void> {
+  try {
+    await fetchJson<boolean>('/users/logout', { method: 'POST' });
+  } finally {
+    localStorage.removeItem('userSession');
+  }
+}
+
+export async function listUsers(): Promise<UserAccount[]> {
+  return fetchJson<UserAccount[]>('/users');
+}
+
+/**
+ * Create an account; the first one must be an admin and enables multi-user mode.
+ * Without a password the account can only sign in with single sign-on.
+ */
+export async function createUser(
+  username: string,
+  password: string | null,
+  role: UserRole,
+  masterPassword?: string
+): Promise<UserAccount> {
+  return fetchJson<UserAccount>('/users', {
+    method: 'POST',
+    body: JSON.stringify({ username, password, role, master_password: masterPassword || null }),
+  });
+}
+
+export async function setUserRole(username: string, role: UserRole): Promise<boolean> {
+  return fetchJson<boolean>('/users/role', {
+    method: 'POST',
+    body: JSON.stringify({ username, role }),
+  });
+}
+
+export async function setUserPassword(username: string, newPassword: string): Promise<boolean> {
+  return fetchJson<boolean>('/users/password', {
+    method: 'POST',
+    body: JSON.stringify({ username, new_password: newPassword }),
+  });
+}
+
+export async function deleteUser(username: string): Promise<boolean> {
+  return fetchJson<boolean>(`/users?username=${encodeURIComponent(username)}`, { method: 'DELETE' });
+}
+
 // ── Auto-logout (Tauri desktop only) ──
 
 export async function getAutoLogout(): Promise<boolean> {
@@ -389,12 +389,33 @@ export function buildJson(data: FlightDataResponse, unitPrefs?: UnitPreferences)
   return JSON.stringify(exportData, null, 2);
 }
 
+/**
+ * GPX <metadata> block carrying the flight record and messages as JSON, so the
+ * file can be re-imported with its name, serials, notes, color and tags
+ */
+function buildGpxMetadata(data: FlightDataResponse, flightName: string): string {
+  const appVersion = typeof __APP_VERSION__ !== 'undefined' ? __APP_VERSION__ : 'unknown';
+  const record = JSON.stringify({
+    format: 'Drone Logbook GPX Export',
+    appVersion,
+    flight: data.flight,
+    messages: data.messages,
+  });
+  return `  <metadata>
+    <name>${flightName}</name>
+    <extensions>
+      <dl:flight xmlns:dl="https://github.com/arpanghosh8453/drone-logbook">${escapeXml(record)}</dl:flight>
+    </extensions>
+  </metadata>`;
+}
+
 /**
  * Build GPX export string from flight data
  */
 export function buildGpx(data: FlightDataResponse): string {
   const { flight, telemetry, track } = data;
   const flightName = escapeXml(flight.displayName || flight.fileName || 'Flight');
+  const metadata = buildGpxMetadata(data, flightName);
 
   // Handle manual entries with no telemetry - create waypoint at home location
   if (!telemetry.time || telemetry.time.length === 0) {
@@ -403,6 +424,7 @@ export function buildGpx(data: FlightDataResponse): string {
       const eleStr = flight.maxAltitude != null ? `<ele>${flight.maxAltitude}</ele>` : '';
       return `<?xml version="1.0" encoding="UTF-8"?>
 <gpx version="1.1" creator="Drone Logbook">
+${metadata}
   <wpt lat="${flight.homeLat}" lon="${flight.homeLon}">
     <name>${flightName}</name>
     ${eleStr}
@@ -413,9 +435,7 @@ export function buildGpx(data: FlightDataResponse): string {
     // No location data at all
     return `<?xml version="1.0" encoding="UTF-8"?>
 <gpx version="1.1" creator="Drone Logbook">
-  <metadata>
-    <name>${flightName}</name>
-  </metadata>
+${metadata}
 </gpx>`;
   }
 
@@ -442,6 +462,7 @@ export function buildGpx(data: FlightDataResponse): string {
 
   return `<?xml version="1.0" encoding="UTF-8"?>
 <gpx version="1.1" creator="Drone Logbook">
+${metadata}
   <trk>
     <name>${flightName}</name>
     <trkseg>
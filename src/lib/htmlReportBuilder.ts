@@ -6,7 +6,7 @@
  * Layout uses grouped cards per flight for A4 print readability.
  */
 
-import type { Flight, FlightDataResponse, TelemetryData } from '@/types';
+import type { ChecklistRecord, Flight, FlightDataResponse, TelemetryData } from '@/types';
 import type { WeatherData } from '@/lib/weather';
 import { type UnitSystem, type UnitPreferences, ensureAmPmUpperCase, formatDateDisplay, formatDateHeader as utilFormatDateHeader } from '@/lib/utils';
 
@@ -59,6 +59,9 @@ export interface HtmlReportFieldConfig {
   // Tags
   manualTags: boolean;
   autoTags: boolean;
+
+  // Checklists
+  checklists: boolean;
 }
 
 export const DEFAULT_FIELD_CONFIG: HtmlReportFieldConfig = {
@@ -93,6 +96,7 @@ export const DEFAULT_FIELD_CONFIG: HtmlReportFieldConfig = {
   videoCount: true,
   manualTags: true,
   autoTags: true,
+  checklists: true,
 };
 
 export interface FieldGroup {
@@ -167,6 +171,12 @@ export const FIELD_GROUPS: FieldGroup[] = [
       { key: 'autoTags', label: 'Auto Tags' },
     ],
   },
+  {
+    name: 'Checklists',
+    fields: [
+      { key: 'checklists', label: 'Checklists' },
+    ],
+  },
 ];
 
 // ============================================================================
@@ -386,6 +396,7 @@ export interface FlightReportData {
   flight: Flight;
   data: FlightDataResponse;
   weather?: WeatherData | null;
+  checklists?: ChecklistRecord[];
   getDroneDisplayName?: (serial: string, fallback: string) => string;
   getBatteryDisplayName?: (serial: string) => string;
   getDisplaySerial?: (serial: string) => string;
@@ -574,6 +585,20 @@ function buildFlightColumns(
     columns.push({ isStacked: stackedGroups.length > 1, groups: stackedGroups });
   }
 
+  // 6. Checklists Column (one line per completed checklist)
+  if (fc.checklists) {
+    const checklistItems: { label: string; value: string }[] = [];
+    for (const rec of fd.checklists || []) {
+      const done = rec.items.filter((i) => i.checked).length;
+      const when = rec.completedAt ? ` · ${fmtTimeFull(rec.completedAt, lang, hour12)}` : '';
+      checklistItems.push({ label: esc(rec.templateName), value: esc(`${done}/${rec.items.length}${when}`) });
+    }
+    if (checklistItems.length === 0) {
+      checklistItems.push({ label: tr('report.checklistsGroup', 'Checklists'), value: tr('report.noChecklists', 'None recorded') });
+    }
+    columns.push({ isStacked: false, groups: [{ group: tr('report.checklistsGroup', 'Checklists'), items: checklistItems }] });
+  }
+
   return columns;
 }
 
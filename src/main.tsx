@@ -1,6 +1,6 @@
 import React from 'react';
 import ReactDOM from 'react-dom/client';
-import { isWebMode } from '@/lib/api';
+import { consumeSsoRedirect, isWebMode } from '@/lib/api';
 import App from './App';
 import './index.css';
 import './i18n';
@@ -14,6 +14,13 @@ if (!isWebMode()) {
     });
 }
 
+// Store the session handed back by a single sign-on redirect before the app
+// makes its first request
+if (isWebMode()) {
+  const ssoError = consumeSsoRedirect();
+  if (ssoError) console.warn('Single sign-on failed:', ssoError);
+}
+
 ReactDOM.createRoot(document.getElementById('root')!).render(
   <React.StrictMode>
     <App />
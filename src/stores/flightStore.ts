@@ -5,7 +5,7 @@
 
 import { create } from 'zustand';
 import * as api from '@/lib/api';
-import type { Flight, FlightDataResponse, FlightMessage, ImportResult, OverviewStats } from '@/types';
+import type { ConflictResolution, Flight, FlightDataResponse, FlightMessage, ImportResult, OverviewStats } from '@/types';
 import { normalizeSerial, LEGACY_DATE_LOCALE_MAP, type UnitPreferences, DEFAULT_UNIT_PREFS } from '@/lib/utils';
 import i18n from '@/i18n';
 
@@ -94,7 +94,7 @@ interface FlightState {
   loadFlights: () => Promise<void>;
   loadOverview: () => Promise<void>;
   selectFlight: (flightId: number) => Promise<void>;
-  importLog: (fileOrPath: string | File, skipRefresh?: boolean) => Promise<ImportResult>;
+  importLog: (fileOrPath: string | File, skipRefresh?: boolean, onConflict?: ConflictResolution) => Promise<ImportResult>;
   importLogBatch: (filesOrPaths: (string | File)[]) => Promise<{ processed: number; skipped: number; lastFlightId: number | null }>;
   loadApiKeyType: () => Promise<void>;
   deleteFlight: (flightId: number) => Promise<void>;
@@ -437,7 +437,10 @@ export const useFlightStore = create<FlightState>((set, get) => ({
       }
     } catch (err) {
       const errMsg = String(err);
-      const isAuthError = /password.protected|Session expired|re-authenticate|UNAUTHORIZED|Profile is locked/i.test(errMsg);
+      const code = (err as { code?: string } | null)?.code;
+      const isAuthError =
+        code?.startsWith('auth.') ||
+        /password.protected|Session expired|re-authenticate|UNAUTHORIZED|Profile is locked/i.test(errMsg);
       set({
         isLoading: false,
         needsAuth: isAuthError,
@@ -512,10 +515,11 @@ export const useFlightStore = create<FlightState>((set, get) => ({
 
   // Import a new log file
   // skipRefresh: when true, doesn't reload flights/select (used by batch import)
-  importLog: async (fileOrPath: string | File, skipRefresh = false) => {
+  // onConflict: how to settle a match with a flight already in the logbook
+  importLog: async (fileOrPath: string | File, skipRefresh = false, onConflict?: ConflictResolution) => {
     set({ isImporting: true, error: null });
     try {
-      const result = await api.importLog(fileOrPath);
+      const result = await api.importLog(fileOrPath, onConflict);
 
       if (result.success && result.flightId && !skipRefresh) {
         // Reload flights and select the new one (only for single imports)
@@ -557,7 +561,7 @@ export const useFlightStore = create<FlightState>((set, get) => ({
         if (result.success && result.flightId) {
           processed += 1;
           lastFlightId = result.flightId;
-        } else if (result.message.toLowerCase().includes('already been imported')) {
+        } else if (result.code === 'import.already_imported') {
           skipped += 1;
         }
       } catch {
@@ -35,6 +35,80 @@ export interface Flight {
   color?: string | null;
   rcSerial?: string | null;
   batteryLife?: number | null;
+  pilot?: string | null;
+  /** Takeoff location name */
+  locationName?: string | null;
+  /** Correction applied to the parsed start time (seconds) */
+  timeOffsetSecs?: number | null;
+  /** Parsed values replaced by metadata edits, keyed by column name */
+  originalMetadata?: Record<string, string | null> | null;
+  /** Entered by hand, without a log file (no telemetry) */
+  isManual?: boolean;
+  /** Web account that imported the flight (multi-user mode) */
+  owner?: string | null;
+  /** Takeoffs and landings detected in the log (null = not counted, one each) */
+  takeoffCount?: number | null;
+  landingCount?: number | null;
+  /** Airborne seconds per phase (null = not computed) */
+  phaseTimes?: PhaseTimes | null;
+  /** 0 (rough) to 100 (smooth) from jerk, gimbal motion and stick inputs (null = not scored) */
+  smoothnessScore?: number | null;
+  /** Altitude data in the log */
+  altitudeSource?: 'barometric' | 'gps_msl' | 'barometric+gps_msl' | 'none' | null;
+}
+
+/** `GET /api/flights?modified_since=` / `get_flight_changes` */
+export interface FlightChanges {
+  flights: Flight[];
+  deletedIds: number[];
+  /** Pass as `modified_since` on the next request */
+  syncedAt: string;
+}
+
+/** A folder with DJI flight logs found by `detect_log_sources` (desktop) */
+export interface LogSource {
+  app: 'dji_fly' | 'dji_go4' | 'dji_assistant';
+  name: string;
+  path: string;
+  fileCount: number;
+  latestModified: string | null;
+  /** Log files in the folder, newest first */
+  files: string[];
+}
+
+/** Airborne seconds split by what the aircraft was doing */
+export interface PhaseTimes {
+  hoverSecs: number;
+  transitSecs: number;
+  ascendSecs: number;
+  descendSecs: number;
+}
+
+/** Web account role (multi-user mode) */
+export type UserRole = 'viewer' | 'editor' | 'admin';
+
+export interface UserAccount {
+  username: string;
+  role: UserRole;
+  /** Signs in through the OpenID provider */
+  sso?: boolean;
+}
+
+/** Whether sign-in is required and who is signed in */
+export interface CurrentUserStatus {
+  multiUser: boolean;
+  user: UserAccount | null;
+  /** Single sign-on is configured */
+  sso: boolean;
+}
+
+/** Corrections for updateFlightMetadata (omitted = unchanged, '' = clear) */
+export interface FlightMetadataUpdate {
+  droneModel?: string;
+  batterySerial?: string;
+  pilot?: string;
+  locationName?: string;
+  timeOffsetSecs?: number;
 }
 
 /** Telemetry data formatted for ECharts */
@@ -81,6 +155,8 @@ export interface TelemetryData {
   batteryFullCapacity?: (number | null)[];
   /** Battery remaining capacity in mAh */
   batteryRemainedCapacity?: (number | null)[];
+  /** Series sent empty because every value was null (restored as null arrays by the API client) */
+  omittedFields?: string[];
 }
 
 /** App tip/warning/caution message from DJI flight log */
@@ -98,6 +174,133 @@ export interface FlightDataResponse {
   track: [number, number, number][];
   /** App tip/warning messages */
   messages?: FlightMessage[];
+  /** User-defined series, aligned with `telemetry.time` */
+  derived?: DerivedSeries[];
+  /** Recording gaps, each marked in `telemetry` by a sample with every value null */
+  gaps?: GapReport;
+  /** Reference of `telemetry.height` and `track` */
+  altitudeReference?: AltitudeReference;
+}
+
+/** Height above takeoff, or above mean sea level */
+export type AltitudeReference = 'takeoff' | 'msl';
+
+/** Flight time rounding in reports and exports */
+export type DurationRounding = 'exact' | 'minute' | 'tenths';
+
+/** Display units of reports and exports, chosen per quantity */
+export interface UnitSettings {
+  distance: 'km' | 'mi' | 'nm';
+  altitude: 'm' | 'ft';
+  speed: 'ms' | 'kmh' | 'mph' | 'kn';
+  temperature: 'c' | 'f';
+}
+
+/** Background event raised as a notification */
+export type NotificationEvent =
+  | { kind: 'import_completed'; source: string; imported: number; skipped: number; errors: number }
+  | { kind: 'backup_completed'; destination: string; error?: string | null }
+  | { kind: 'battery_health'; batterySerial: string; displayName?: string | null; reasons: string[] };
+
+/** Which background events raise a notification */
+export interface NotificationSettings {
+  importCompleted: boolean;
+  backupCompleted: boolean;
+  batteryHealth: boolean;
+}
+
+/** Result of a read-only SQL console query */
+export interface SqlQueryResult {
+  columns: string[];
+  rows: unknown[][];
+  /** More rows matched than were returned */
+  truncated: boolean;
+  elapsedMs: number;
+}
+
+/** A user-defined zone rule for pre-flight briefings */
+export interface PreflightZone {
+  name: string;
+  lat: number;
+  lon: number;
+  radiusM: number;
+  airspaceClass?: string | null;
+  maxHeightM?: number | null;
+  notes?: string | null;
+}
+
+/** Sun, forecast, airspace and zone rules for a planned flight */
+export interface PreflightReport {
+  latitude: number;
+  longitude: number;
+  time: string;
+  sun: {
+    civilDawn: string | null;
+    sunrise: string | null;
+    sunset: string | null;
+    civilDusk: string | null;
+    daylight: boolean;
+  };
+  /** Open-Meteo forecast for the planned hour, when reachable */
+  weather: Record<string, unknown> | null;
+  airspaces: { name: string; class: string; lowerLimit: string | null; upperLimit: string | null; source: 'openaip' | 'zone' }[];
+  airspaceLookup: 'openaip' | 'not_configured' | 'failed';
+  zones: (PreflightZone & { inside: boolean; distanceM: number })[];
+}
+
+/** One link of the logbook integrity chain */
+export interface IntegrityEntry {
+  seq: number;
+  recordedAt: string;
+  flightId: number;
+  event: 'import' | 'update' | 'delete' | 'restore' | 'baseline';
+  fileHash: string | null;
+  recordHash: string | null;
+  prevHash: string;
+  entryHash: string;
+}
+
+/** Result of checking the integrity chain and the flights against it */
+export interface IntegrityReport {
+  valid: boolean;
+  entries: number;
+  flightsChecked: number;
+  chainHead: string;
+  /** Flights imported before the chain existed */
+  unrecordedFlights: number[];
+  issues: { flightId: number | null; seq: number | null; message: string }[];
+}
+
+/** A user-defined telemetry column (DuckDB SQL expression) */
+export interface DerivedMetric {
+  name: string;
+  expression: string;
+  unit?: string | null;
+}
+
+export interface DerivedSeries {
+  name: string;
+  unit: string | null;
+  values: (number | null)[];
+}
+
+/** A recording gap (signal loss, app backgrounded) */
+export interface TelemetryGap {
+  /** Last sample before the gap */
+  startMs: number;
+  /** First sample after the gap */
+  endMs: number;
+  durationMs: number;
+  distanceM: number | null;
+}
+
+export interface GapReport {
+  gaps: TelemetryGap[];
+  totalGapMs: number;
+  longestGapMs: number;
+  /** Share of the recording not inside a gap, 0-100 */
+  coveragePct: number;
+  thresholdMs: number;
 }
 
 export interface BatteryUsage {
@@ -133,6 +336,99 @@ export interface TopDistanceFlight {
   startTime: string | null;
 }
 
+/** One day of the activity calendar */
+export interface ActivityDay {
+  date: string;
+  count: number;
+  durationSecs: number;
+  distanceM: number;
+}
+
+export interface FlyingStreak {
+  startDate: string;
+  endDate: string;
+  days: number;
+}
+
+export interface ActivityStats {
+  flyingDays: number;
+  lastFlightDate: string | null;
+  daysSinceLastFlight: number | null;
+  /** Streak still alive (last flying day is today or yesterday) */
+  currentStreak: FlyingStreak | null;
+  longestStreak: FlyingStreak | null;
+  busiestDay: FlightDateCount | null;
+  busiestWeek: { weekStart: string; count: number; durationSecs: number } | null;
+}
+
+/** Time spent in one height band over the area */
+export interface HeightBand {
+  minM: number;
+  /** null for the top band */
+  maxM: number | null;
+  seconds: number;
+}
+
+export interface AreaFlight {
+  flightId: number;
+  displayName: string;
+  startTime: string | null;
+  secondsInside: number;
+  minHeightM: number | null;
+  maxHeightM: number | null;
+  meanHeightM: number | null;
+}
+
+/** Flights over a drawn area (see getOverflightReport) */
+export interface OverflightReport {
+  from: string | null;
+  to: string | null;
+  flightCount: number;
+  totalMinutes: number;
+  maxHeightM: number | null;
+  heightBands: HeightBand[];
+  /** Newest first */
+  flights: AreaFlight[];
+}
+
+/** Metrics accepted by `getTopFlights` */
+export type TopFlightMetric =
+  | 'duration'
+  | 'distance'
+  | 'distance_from_home'
+  | 'max_speed'
+  | 'max_altitude'
+  | 'coldest_temperature'
+  | 'most_events'
+  | 'smoothness';
+
+/** Top-N list entry; `value` is in the metric's unit (s, m, m/s, °C or a count) */
+export interface RankedFlight {
+  id: number;
+  displayName: string;
+  value: number;
+  startTime: string | null;
+}
+
+/** Telemetry value a histogram is binned by (meters / m/s) */
+export type HistogramMetric = 'height' | 'speed';
+
+/** Seconds spent with the value in `[min, max)` */
+export interface HistogramBin {
+  min: number;
+  max: number;
+  seconds: number;
+}
+
+export interface Histogram {
+  metric: HistogramMetric;
+  /** null for the whole logbook */
+  flightId: number | null;
+  binWidth: number;
+  totalSecs: number;
+  bins: HistogramBin[];
+}
+
 export interface BatteryHealthPoint {
   flightId: number;
   batterySerial: string;
@@ -157,15 +453,119 @@ export interface OverviewStats {
   topFlights: TopFlight[];
   topDistanceFlights: TopDistanceFlight[];
   batteryHealthPoints: BatteryHealthPoint[];
+  /** Airborne time per phase, summed over the flights that have it */
+  phaseTimes?: PhaseTimes;
+  /** Batteries past a retirement threshold */
+  batteryRetirement?: BatteryRetirementRecommendation[];
+}
+
+/** A battery recommended for retirement, with one reason per threshold crossed */
+export interface BatteryRetirementRecommendation {
+  batterySerial: string;
+  displayName: string | null;
+  cycles: number;
+  capacityRetentionPct: number | null;
+  sagIncreasePct: number | null;
+  ageDays: number | null;
+  reasons: string[];
 }
 
 /** Result from import_log command */
 export interface ImportResult {
   success: boolean;
   flightId: number | null;
+  /** Localized message (served in the app language) */
   message: string;
+  /** Stable message code, e.g. 'import.already_imported' */
+  code?: string;
   pointCount: number;
   fileHash: string | null;
+  /** Set when the file was skipped as a copy of a flight already in the logbook */
+  conflict?: ImportConflict;
+}
+
+/** Flight in the logbook that an imported file duplicates */
+export interface ImportConflict {
+  existingFlightId: number;
+  existingName: string;
+  existingStartTime: string | null;
+  existingDurationSecs: number | null;
+  durationSecs: number | null;
+  reason: string;
+}
+
+/** How to settle an import conflict */
+export type ConflictResolution = 'skip' | 'replace' | 'keep_both';
+
+/** Flights and time an aircraft flew before this logbook */
+export interface AirframeSeed {
+  serial: string;
+  priorDurationSecs: number;
+  priorFlights: number;
+  notes?: string | null;
+}
+
+/** Area of a named site; polygon points are [lon, lat] */
+export type SiteShape =
+  | { kind: 'circle'; lat: number; lon: number; radiusM: number }
+  | { kind: 'polygon'; points: [number, number][] };
+
+/** A user-defined place that names flights taking off inside it */
+export interface NamedSite {
+  id: string;
+  name: string;
+  parent?: string | null;
+  shape: SiteShape;
+}
+
+/** Location tag renames (empty drops the tag) and named sites */
+export interface LocationOverrides {
+  renames: Record<string, string>;
+  sites: NamedSite[];
+}
+
+/** Flights from one take-off place (named site or home point cluster) */
+export interface PlaceStats {
+  key: string;
+  name: string | null;
+  isSite: boolean;
+  lat: number;
+  lon: number;
+  flightCount: number;
+  totalDurationSecs: number;
+  lastFlight: string | null;
+  flightIds: number[];
+}
+
+/** One line of a completed checklist */
+export interface ChecklistItemResult {
+  item: string;
+  checked: boolean;
+  checkedAt: string | null;
+}
+
+/** Completed pre/post-flight checklist, attached to a flight or a session */
+export interface ChecklistRecord {
+  id: string;
+  templateId: string | null;
+  templateName: string;
+  phase: 'preflight' | 'postflight';
+  flightId: number | null;
+  session: string | null;
+  items: ChecklistItemResult[];
+  startedAt: string | null;
+  completedAt: string | null;
+  notes: string | null;
+}
+
+/** Cached flight list preview, built at import */
+export interface FlightPreview {
+  flightId: number;
+  /** Simplified [lon, lat] polyline */
+  track: [number, number][];
+  altitudeSparkline: number[];
+  batterySparkline: number[];
+  thumbnailSvg: string | null;
 }
 
 /** Flight statistics */